@@ -0,0 +1,10 @@
+-- Restore the trigger without the schema condition
+DROP TRIGGER IF EXISTS update_item_type_updated_at;
+CREATE TRIGGER update_item_type_updated_at
+AFTER UPDATE ON item_types
+WHEN (OLD.name IS NOT NEW.name OR OLD.review_function IS NOT NEW.review_function)
+BEGIN
+    UPDATE item_types SET updated_at = strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE id = NEW.id;
+END;
+
+ALTER TABLE item_types DROP COLUMN item_data_schema;
@@ -0,0 +1,12 @@
+-- Optional JSON Schema that item_data of this type must satisfy
+ALTER TABLE item_types ADD COLUMN item_data_schema TEXT;
+
+-- Trigger: also bump item_types.updated_at when the schema changes
+DROP TRIGGER IF EXISTS update_item_type_updated_at;
+CREATE TRIGGER update_item_type_updated_at
+AFTER UPDATE ON item_types
+WHEN (OLD.name IS NOT NEW.name OR OLD.review_function IS NOT NEW.review_function OR
+      OLD.item_data_schema IS NOT NEW.item_data_schema)
+BEGIN
+    UPDATE item_types SET updated_at = strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE id = NEW.id;
+END;
@@ -0,0 +1,7 @@
+-- When a buried card returns to the queue (NULL if it isn't buried).
+--
+-- Deliberately left out of update_card_updated_at: burying only hides a
+-- card for a while, so it shouldn't force the card's event chain to
+-- recompute (and unburying every card at once would otherwise invalidate
+-- every cache).
+ALTER TABLE cards ADD COLUMN buried_until TIMESTAMP DEFAULT NULL;
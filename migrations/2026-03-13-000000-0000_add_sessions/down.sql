@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS reviews_session_id_index;
+ALTER TABLE reviews DROP COLUMN session_id;
+DROP TABLE IF EXISTS sessions;
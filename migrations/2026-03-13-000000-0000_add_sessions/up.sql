@@ -0,0 +1,10 @@
+-- Review sessions: a span of studying that reviews can be attributed to
+CREATE TABLE sessions (
+    id TEXT NOT NULL PRIMARY KEY,
+    started_at TIMESTAMP NOT NULL,
+    ended_at TIMESTAMP DEFAULT NULL
+);
+
+-- Reviews optionally belong to the session they were recorded in
+ALTER TABLE reviews ADD COLUMN session_id TEXT DEFAULT NULL REFERENCES sessions(id) ON DELETE SET NULL;
+CREATE INDEX reviews_session_id_index ON reviews(session_id);
@@ -0,0 +1,13 @@
+DROP TRIGGER IF EXISTS update_card_updated_at;
+CREATE TRIGGER update_card_updated_at
+AFTER UPDATE ON cards
+WHEN (OLD.id IS NOT NEW.id OR OLD.item_id IS NOT NEW.item_id OR
+      OLD.card_index IS NOT NEW.card_index OR OLD.next_review IS NOT NEW.next_review OR
+      OLD.last_review IS NOT NEW.last_review OR OLD.scheduler_data IS NOT NEW.scheduler_data OR
+      OLD.priority IS NOT NEW.priority OR OLD.suspended IS NOT NEW.suspended OR
+      OLD.sort_position IS NOT NEW.sort_position)
+BEGIN
+    UPDATE cards SET updated_at = strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE id = NEW.id;
+END;
+
+ALTER TABLE cards DROP COLUMN note;
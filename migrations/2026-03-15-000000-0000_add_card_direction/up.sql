@@ -0,0 +1,18 @@
+-- Which way round a two-sided card asks its question: 'forward' (front to
+-- back) or 'reverse' (back to front). NULL for cards with no direction.
+ALTER TABLE cards ADD COLUMN direction TEXT DEFAULT NULL;
+
+-- Existing Basic cards are all front to back, and Vocabulary items have
+-- always had a term to definition card followed by its reverse.
+UPDATE cards SET direction = 'forward'
+WHERE card_index = 0 AND item_id IN (
+    SELECT items.id FROM items
+    INNER JOIN item_types ON item_types.id = items.item_type
+    WHERE item_types.name IN ('Basic', 'Vocabulary')
+);
+UPDATE cards SET direction = 'reverse'
+WHERE card_index = 1 AND item_id IN (
+    SELECT items.id FROM items
+    INNER JOIN item_types ON item_types.id = items.item_type
+    WHERE item_types.name = 'Vocabulary'
+);
@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS update_card_version;
+ALTER TABLE cards DROP COLUMN version;
@@ -0,0 +1,20 @@
+-- A counter bumped on every change to a card, so an update can be made
+-- conditional on the card not having changed since the client read it.
+ALTER TABLE cards ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
+
+-- Bumped by a trigger rather than by each write, so reviews, suspending,
+-- burying and the rest all invalidate a stale client's copy without having
+-- to remember to. Excluded fields, as for update_card_updated_at: the
+-- card_data cache and its timestamps, and the daily priority_offset
+-- shuffle, none of which a client sets.
+CREATE TRIGGER update_card_version
+AFTER UPDATE ON cards
+WHEN (OLD.id IS NOT NEW.id OR OLD.item_id IS NOT NEW.item_id OR
+      OLD.card_index IS NOT NEW.card_index OR OLD.next_review IS NOT NEW.next_review OR
+      OLD.last_review IS NOT NEW.last_review OR OLD.scheduler_data IS NOT NEW.scheduler_data OR
+      OLD.priority IS NOT NEW.priority OR OLD.suspended IS NOT NEW.suspended OR
+      OLD.sort_position IS NOT NEW.sort_position OR OLD.note IS NOT NEW.note OR
+      OLD.buried_until IS NOT NEW.buried_until OR OLD.direction IS NOT NEW.direction)
+BEGIN
+    UPDATE cards SET version = OLD.version + 1 WHERE id = NEW.id;
+END;
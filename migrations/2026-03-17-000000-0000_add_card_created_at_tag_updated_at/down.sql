@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS update_tag_updated_at;
+ALTER TABLE tags DROP COLUMN updated_at;
+ALTER TABLE cards DROP COLUMN created_at;
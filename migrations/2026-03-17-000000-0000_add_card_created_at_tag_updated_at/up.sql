@@ -0,0 +1,17 @@
+-- When each card was created. Cards are created along with their item, so
+-- existing cards take their item's created_at.
+ALTER TABLE cards ADD COLUMN created_at TIMESTAMP NOT NULL DEFAULT '1970-01-01 00:00:00';
+UPDATE cards SET created_at = (SELECT created_at FROM items WHERE items.id = cards.item_id);
+
+-- When each tag was last renamed or had its visibility changed. Existing
+-- tags haven't changed since they were created.
+ALTER TABLE tags ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT '1970-01-01 00:00:00';
+UPDATE tags SET updated_at = created_at;
+
+-- Trigger: auto-update tags.updated_at when name or visible changes
+CREATE TRIGGER update_tag_updated_at
+AFTER UPDATE ON tags
+WHEN (OLD.name IS NOT NEW.name OR OLD.visible IS NOT NEW.visible)
+BEGIN
+    UPDATE tags SET updated_at = strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE id = NEW.id;
+END;
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS tags_parent_id_index;
+ALTER TABLE tags DROP COLUMN parent_id;
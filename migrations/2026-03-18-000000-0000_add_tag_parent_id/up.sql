@@ -0,0 +1,5 @@
+-- Optional parent of each tag, so tags can form a hierarchy such as
+-- Language > Spanish > Verbs. Deleting a parent leaves its children as
+-- top-level tags.
+ALTER TABLE tags ADD COLUMN parent_id TEXT REFERENCES tags(id) ON DELETE SET NULL;
+CREATE INDEX tags_parent_id_index ON tags(parent_id);
@@ -0,0 +1,3 @@
+-- Marks reviews made while cramming, which are logged but don't reschedule
+-- their card
+ALTER TABLE reviews ADD COLUMN cram BOOLEAN NOT NULL DEFAULT FALSE;
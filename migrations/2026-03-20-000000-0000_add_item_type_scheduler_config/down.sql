@@ -0,0 +1,12 @@
+-- Restore the trigger without the scheduler config condition
+DROP TRIGGER IF EXISTS update_item_type_updated_at;
+CREATE TRIGGER update_item_type_updated_at
+AFTER UPDATE ON item_types
+WHEN (OLD.name IS NOT NEW.name OR OLD.review_function IS NOT NEW.review_function OR
+      OLD.item_data_schema IS NOT NEW.item_data_schema OR
+      OLD.default_priority IS NOT NEW.default_priority)
+BEGIN
+    UPDATE item_types SET updated_at = strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE id = NEW.id;
+END;
+
+ALTER TABLE item_types DROP COLUMN scheduler_config;
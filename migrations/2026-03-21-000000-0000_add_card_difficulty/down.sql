@@ -0,0 +1,26 @@
+DROP TRIGGER IF EXISTS update_card_version;
+CREATE TRIGGER update_card_version
+AFTER UPDATE ON cards
+WHEN (OLD.id IS NOT NEW.id OR OLD.item_id IS NOT NEW.item_id OR
+      OLD.card_index IS NOT NEW.card_index OR OLD.next_review IS NOT NEW.next_review OR
+      OLD.last_review IS NOT NEW.last_review OR OLD.scheduler_data IS NOT NEW.scheduler_data OR
+      OLD.priority IS NOT NEW.priority OR OLD.suspended IS NOT NEW.suspended OR
+      OLD.sort_position IS NOT NEW.sort_position OR OLD.note IS NOT NEW.note OR
+      OLD.buried_until IS NOT NEW.buried_until OR OLD.direction IS NOT NEW.direction)
+BEGIN
+    UPDATE cards SET version = OLD.version + 1 WHERE id = NEW.id;
+END;
+
+DROP TRIGGER IF EXISTS update_card_updated_at;
+CREATE TRIGGER update_card_updated_at
+AFTER UPDATE ON cards
+WHEN (OLD.id IS NOT NEW.id OR OLD.item_id IS NOT NEW.item_id OR
+      OLD.card_index IS NOT NEW.card_index OR OLD.next_review IS NOT NEW.next_review OR
+      OLD.last_review IS NOT NEW.last_review OR OLD.scheduler_data IS NOT NEW.scheduler_data OR
+      OLD.priority IS NOT NEW.priority OR OLD.suspended IS NOT NEW.suspended OR
+      OLD.sort_position IS NOT NEW.sort_position OR OLD.note IS NOT NEW.note)
+BEGIN
+    UPDATE cards SET updated_at = strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE id = NEW.id;
+END;
+
+ALTER TABLE cards DROP COLUMN difficulty;
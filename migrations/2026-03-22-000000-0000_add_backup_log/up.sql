@@ -0,0 +1,10 @@
+-- One row per periodic backup attempt, so a backup that keeps failing shows
+-- up in GET /admin/backups rather than only in the server's logs
+CREATE TABLE backup_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    attempted_at TIMESTAMP NOT NULL,
+    backup_type TEXT NOT NULL,
+    success BOOLEAN NOT NULL,
+    -- Why the backup failed (NULL if it succeeded)
+    error TEXT
+);
@@ -0,0 +1,3 @@
+-- What the user actually answered in a review, kept for looking back over
+-- their mistakes
+ALTER TABLE reviews ADD COLUMN response TEXT;
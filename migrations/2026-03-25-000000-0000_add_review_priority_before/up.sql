@@ -0,0 +1,4 @@
+-- The priority a card had before a review changed it (see adjust_priority),
+-- so undoing the review can put it back. NULL if the review didn't change
+-- the card's priority.
+ALTER TABLE reviews ADD COLUMN priority_before REAL;
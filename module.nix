@@ -39,6 +39,36 @@ in {
 							default = null;
 							description = "The number of backups to keep";
 						};
+						pool_size = lib.mkOption {
+							type = types.nullOr types.ints.positive;
+							default = null;
+							description = "The maximum number of database connections";
+						};
+						connection_timeout_secs = lib.mkOption {
+							type = types.nullOr types.int;
+							default = null;
+							description = "Seconds to wait for a free database connection before failing a request";
+						};
+						host = lib.mkOption {
+							type = types.nullOr types.str;
+							default = null;
+							description = "The IP address or hostname to listen on";
+						};
+						port = lib.mkOption {
+							type = types.nullOr types.port;
+							default = null;
+							description = "The port to listen on";
+						};
+						allowed_origins = lib.mkOption {
+							type = types.nullOr types.str;
+							default = null;
+							description = "Comma-separated list of origins allowed to make cross-origin requests, or \"*\" for any origin";
+						};
+						request_log_level = lib.mkOption {
+							type = types.nullOr (types.enum [ "error" "warn" "info" "debug" "trace" ]);
+							default = null;
+							description = "The level to log each HTTP request at";
+						};
 					};
 				};
 				default = {};
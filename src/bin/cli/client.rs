@@ -1,10 +1,13 @@
 use chrono::{DateTime, Utc};
 use hippocampus::dto::{
-	CreateItemDto, CreateItemTypeDto, CreateReviewDto, CreateTagDto, GetQueryDto,
-	SortPositionAction, SuspendedFilter, UpdateItemDto,
+	CardState, CreateItemDto, CreateItemTypeDto, CreateReviewDto, CreateTagDto, GetQueryDto,
+	ListReviewsQueryDto, SortPositionAction, SuspendedFilter, TagMatch, UpdateItemDto,
 };
-use hippocampus::models::{Card, CardId, Item, ItemId, ItemType, ItemTypeId, Review, Tag, TagId};
-use reqwest::Client;
+use hippocampus::models::{
+	Card, CardId, Item, ItemId, ItemType, ItemTypeId, Rating, Review, Tag, TagId,
+};
+use reqwest::{Client, RequestBuilder};
+use std::time::Duration;
 
 /// Error type for CLI client operations
 #[derive(Debug)]
@@ -77,9 +80,18 @@ fn build_query_params(query: &GetQueryDto) -> Vec<(&'static str, String)> {
 	if let Some(ref id) = query.item_type_id {
 		params.push(("item_type_id", id.0.clone()));
 	}
+	for id in &query.exclude_item_type_ids {
+		params.push(("exclude_item_type_ids", id.0.clone()));
+	}
 	for tag_id in &query.tag_ids {
 		params.push(("tag_ids", tag_id.0.clone()));
 	}
+	if query.tag_match == TagMatch::Any {
+		params.push(("tag_match", "any".to_string()));
+	}
+	if let Some(include) = query.include_descendant_tags {
+		params.push(("include_descendant_tags", include.to_string()));
+	}
 	if let Some(ref dt) = query.next_review_before {
 		params.push(("next_review_before", dt.to_rfc3339()));
 	}
@@ -106,20 +118,90 @@ fn build_query_params(query: &GetQueryDto) -> Vec<(&'static str, String)> {
 	if let Some(ref id) = query.child_item_id {
 		params.push(("child_item_id", id.0.clone()));
 	}
+	match query.state {
+		Some(CardState::New) => params.push(("state", "new".to_string())),
+		Some(CardState::Due) => params.push(("state", "due".to_string())),
+		Some(CardState::Learning) => params.push(("state", "learning".to_string())),
+		Some(CardState::Review) => params.push(("state", "review".to_string())),
+		None => {}
+	}
+	if let Some(cram) = query.cram {
+		params.push(("cram", cram.to_string()));
+	}
 
 	params
 }
 
+/// How long to wait for a response by default before giving up on a request
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many times an idempotent request is retried after failing
+const MAX_RETRIES: u32 = 2;
+
+/// How long to wait before the first retry, doubling for each one after
+const RETRY_BACKOFF: Duration = Duration::from_millis(200);
+
+/// Whether a request that failed this way might succeed if sent again
+fn is_retryable(result: &Result<reqwest::Response, reqwest::Error>) -> bool {
+	match result {
+		Ok(response) => matches!(
+			response.status(),
+			reqwest::StatusCode::BAD_GATEWAY
+				| reqwest::StatusCode::SERVICE_UNAVAILABLE
+				| reqwest::StatusCode::GATEWAY_TIMEOUT
+		),
+		Err(e) => e.is_connect() || e.is_timeout(),
+	}
+}
+
 impl HippocampusClient {
 	/// Creates a new HippocampusClient
 	///
 	/// ### Arguments
 	///
 	/// * `base_url` - The base URL of the Hippocampus server
-	pub fn new(base_url: String) -> Self {
+	/// * `timeout` - How long to wait for each response before giving up
+	pub fn new(base_url: String, timeout: Duration) -> Self {
 		Self {
 			base_url,
-			client: Client::new(),
+			client: Client::builder()
+				.timeout(timeout)
+				.build()
+				.expect("failed to build the HTTP client"),
+		}
+	}
+
+	/// Sends a request that's safe to repeat, retrying with backoff if the
+	/// server can't be reached, times out or is briefly unavailable
+	///
+	/// ### Arguments
+	///
+	/// * `request` - The request, which must not have a streaming body
+	///
+	/// ### Returns
+	///
+	/// The response to the last attempt, which may still be an error status
+	/// for `check` to report
+	///
+	/// ### Errors
+	///
+	/// Returns the last attempt's error if every attempt failed to get a response
+	async fn send_idempotent(
+		&self,
+		request: RequestBuilder,
+	) -> Result<reqwest::Response, ClientError> {
+		let mut attempt = 0;
+		loop {
+			let result = request
+				.try_clone()
+				.expect("idempotent requests have no streaming body")
+				.send()
+				.await;
+			if attempt == MAX_RETRIES || !is_retryable(&result) {
+				return result.map_err(ClientError::Request);
+			}
+			tokio::time::sleep(RETRY_BACKOFF * 2u32.pow(attempt)).await;
+			attempt += 1;
 		}
 	}
 
@@ -129,11 +211,8 @@ impl HippocampusClient {
 	pub async fn list_item_types(&self) -> Result<Vec<ItemType>, ClientError> {
 		let url = format!("{}/item_types", self.base_url);
 		let response = self
-			.client
-			.get(&url)
-			.send()
-			.await
-			.map_err(ClientError::Request)?
+			.send_idempotent(self.client.get(&url))
+			.await?
 			.check()
 			.await?;
 		response.json().await.map_err(ClientError::Request)
@@ -149,6 +228,9 @@ impl HippocampusClient {
 		let dto = CreateItemTypeDto {
 			name,
 			review_function,
+			item_data_schema: None,
+			default_priority: None,
+			scheduler_config: None,
 		};
 		let response = self
 			.client
@@ -171,6 +253,9 @@ impl HippocampusClient {
 		let url = format!("{}/item_types/{}", self.base_url, id);
 		let dto = hippocampus::dto::UpdateItemTypeDto {
 			review_function: Some(review_function),
+			item_data_schema: None,
+			default_priority: None,
+			scheduler_config: None,
 		};
 		let response = self
 			.client
@@ -188,11 +273,8 @@ impl HippocampusClient {
 	pub async fn get_item_type(&self, id: &ItemTypeId) -> Result<ItemType, ClientError> {
 		let url = format!("{}/item_types/{}", self.base_url, id);
 		let response = self
-			.client
-			.get(&url)
-			.send()
-			.await
-			.map_err(ClientError::Request)?
+			.send_idempotent(self.client.get(&url))
+			.await?
 			.check()
 			.await?;
 		response.json().await.map_err(ClientError::Request)
@@ -206,12 +288,8 @@ impl HippocampusClient {
 		let params = build_query_params(query);
 
 		let response = self
-			.client
-			.get(&url)
-			.query(&params)
-			.send()
-			.await
-			.map_err(ClientError::Request)?
+			.send_idempotent(self.client.get(&url).query(&params))
+			.await?
 			.check()
 			.await?;
 		response.json().await.map_err(ClientError::Request)
@@ -227,6 +305,7 @@ impl HippocampusClient {
 	) -> Result<Item, ClientError> {
 		let url = format!("{}/items", self.base_url);
 		let dto = CreateItemDto {
+			id: None,
 			item_type_id,
 			title,
 			item_data,
@@ -247,15 +326,16 @@ impl HippocampusClient {
 	/// Gets a specific item by ID
 	pub async fn get_item(&self, id: &ItemId) -> Result<Option<Item>, ClientError> {
 		let url = format!("{}/items/{}", self.base_url, id);
-		let response = self
-			.client
-			.get(&url)
-			.send()
+		let response = self.send_idempotent(self.client.get(&url)).await?;
+		if response.status() == reqwest::StatusCode::NOT_FOUND {
+			return Ok(None);
+		}
+		let response = response.check().await?;
+		response
+			.json()
 			.await
-			.map_err(ClientError::Request)?
-			.check()
-			.await?;
-		response.json().await.map_err(ClientError::Request)
+			.map(Some)
+			.map_err(ClientError::Request)
 	}
 
 	/// Updates an item
@@ -300,31 +380,49 @@ impl HippocampusClient {
 		let params = build_query_params(query);
 
 		let response = self
-			.client
-			.get(&url)
-			.query(&params)
-			.send()
-			.await
-			.map_err(ClientError::Request)?
+			.send_idempotent(self.client.get(&url).query(&params))
+			.await?
 			.check()
 			.await?;
 		response.json().await.map_err(ClientError::Request)
 	}
 
-	/// Gets a specific card by ID
-	pub async fn get_card(&self, id: &CardId) -> Result<Option<Card>, ClientError> {
-		let url = format!("{}/cards/{}", self.base_url, id);
+	/// Lists the unsuspended cards due for review now
+	pub async fn list_due_cards(&self) -> Result<Vec<Card>, ClientError> {
+		self.list_cards(&GetQueryDto {
+			state: Some(CardState::Due),
+			suspended_filter: SuspendedFilter::Exclude,
+			..Default::default()
+		})
+		.await
+	}
+
+	/// Lists all cards for an item
+	pub async fn list_cards_for_item(&self, item_id: &ItemId) -> Result<Vec<Card>, ClientError> {
+		let url = format!("{}/items/{}/cards", self.base_url, item_id);
 		let response = self
-			.client
-			.get(&url)
-			.send()
-			.await
-			.map_err(ClientError::Request)?
+			.send_idempotent(self.client.get(&url))
+			.await?
 			.check()
 			.await?;
 		response.json().await.map_err(ClientError::Request)
 	}
 
+	/// Gets a specific card by ID
+	pub async fn get_card(&self, id: &CardId) -> Result<Option<Card>, ClientError> {
+		let url = format!("{}/cards/{}", self.base_url, id);
+		let response = self.send_idempotent(self.client.get(&url)).await?;
+		if response.status() == reqwest::StatusCode::NOT_FOUND {
+			return Ok(None);
+		}
+		let response = response.check().await?;
+		response
+			.json()
+			.await
+			.map(Some)
+			.map_err(ClientError::Request)
+	}
+
 	/// Updates a card's priority
 	pub async fn update_card_priority(
 		&self,
@@ -365,11 +463,8 @@ impl HippocampusClient {
 	) -> Result<Vec<(DateTime<Utc>, serde_json::Value)>, ClientError> {
 		let url = format!("{}/cards/{}/next_reviews", self.base_url, card_id);
 		let response = self
-			.client
-			.get(&url)
-			.send()
-			.await
-			.map_err(ClientError::Request)?
+			.send_idempotent(self.client.get(&url))
+			.await?
 			.check()
 			.await?;
 		response.json().await.map_err(ClientError::Request)
@@ -382,11 +477,22 @@ impl HippocampusClient {
 	) -> Result<Vec<Review>, ClientError> {
 		let url = format!("{}/cards/{}/reviews", self.base_url, card_id);
 		let response = self
-			.client
-			.get(&url)
-			.send()
-			.await
-			.map_err(ClientError::Request)?
+			.send_idempotent(self.client.get(&url))
+			.await?
+			.check()
+			.await?;
+		response.json().await.map_err(ClientError::Request)
+	}
+
+	/// Lists reviews across all cards, newest first
+	pub async fn list_reviews(
+		&self,
+		query: &ListReviewsQueryDto,
+	) -> Result<Vec<Review>, ClientError> {
+		let url = format!("{}/reviews", self.base_url);
+		let response = self
+			.send_idempotent(self.client.get(&url).query(query))
+			.await?
 			.check()
 			.await?;
 		response.json().await.map_err(ClientError::Request)
@@ -432,9 +538,18 @@ impl HippocampusClient {
 	// ── Review endpoints ─────────────────────────────────────────────
 
 	/// Creates a new review
-	pub async fn create_review(&self, card_id: CardId, rating: i32) -> Result<Review, ClientError> {
+	pub async fn create_review(
+		&self,
+		card_id: CardId,
+		rating: Rating,
+	) -> Result<Review, ClientError> {
 		let url = format!("{}/reviews", self.base_url);
-		let dto = CreateReviewDto { card_id, rating };
+		let dto = CreateReviewDto {
+			card_id,
+			rating: rating.as_i32(),
+			session_id: None,
+			response: None,
+		};
 		let response = self
 			.client
 			.post(&url)
@@ -453,20 +568,26 @@ impl HippocampusClient {
 	pub async fn list_tags(&self) -> Result<Vec<Tag>, ClientError> {
 		let url = format!("{}/tags", self.base_url);
 		let response = self
-			.client
-			.get(&url)
-			.send()
-			.await
-			.map_err(ClientError::Request)?
+			.send_idempotent(self.client.get(&url))
+			.await?
 			.check()
 			.await?;
 		response.json().await.map_err(ClientError::Request)
 	}
 
-	/// Creates a new tag
-	pub async fn create_tag(&self, name: String, visible: bool) -> Result<Tag, ClientError> {
+	/// Creates a new tag, optionally under a parent tag
+	pub async fn create_tag(
+		&self,
+		name: String,
+		visible: bool,
+		parent_id: Option<TagId>,
+	) -> Result<Tag, ClientError> {
 		let url = format!("{}/tags", self.base_url);
-		let dto = CreateTagDto { name, visible };
+		let dto = CreateTagDto {
+			name,
+			visible,
+			parent_id,
+		};
 		let response = self
 			.client
 			.post(&url)
@@ -483,11 +604,8 @@ impl HippocampusClient {
 	pub async fn list_tags_for_item(&self, item_id: &ItemId) -> Result<Vec<Tag>, ClientError> {
 		let url = format!("{}/items/{}/tags", self.base_url, item_id);
 		let response = self
-			.client
-			.get(&url)
-			.send()
-			.await
-			.map_err(ClientError::Request)?
+			.send_idempotent(self.client.get(&url))
+			.await?
 			.check()
 			.await?;
 		response.json().await.map_err(ClientError::Request)
@@ -527,3 +645,170 @@ impl HippocampusClient {
 		Ok(())
 	}
 }
+
+/// Serves the real application on a fresh in-memory database, returning a
+/// client connected to it
+#[cfg(test)]
+pub async fn test_server_client() -> HippocampusClient {
+	// A named shared-cache database, so every pooled connection sees the same data
+	let database_url = format!(
+		"file:cli_test_{}?mode=memory&cache=shared",
+		uuid::Uuid::new_v4()
+	);
+	let pool = std::sync::Arc::new(hippocampus::db::init_pool(&database_url));
+	hippocampus::run_migrations(&mut pool.get().unwrap());
+	let app = hippocampus::create_app(pool);
+
+	let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let addr = listener.local_addr().unwrap();
+	tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+	HippocampusClient::new(format!("http://{}", addr), DEFAULT_TIMEOUT)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::{
+		Json, Router,
+		extract::RawQuery,
+		http::StatusCode,
+		response::{IntoResponse, Response},
+		routing::{get, post},
+	};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use std::sync::{Arc, Mutex};
+
+	/// A handler that's unavailable for its first `failures` requests, then
+	/// responds with `body`, counting every request in `requests`
+	fn flaky(
+		failures: usize,
+		body: serde_json::Value,
+		requests: Arc<AtomicUsize>,
+	) -> impl Fn() -> std::future::Ready<Response> + Clone {
+		move || {
+			let n = requests.fetch_add(1, Ordering::SeqCst);
+			std::future::ready(if n < failures {
+				StatusCode::SERVICE_UNAVAILABLE.into_response()
+			} else {
+				Json(body.clone()).into_response()
+			})
+		}
+	}
+
+	/// Serves `router` on a free local port, returning its base URL
+	async fn serve(router: Router) -> String {
+		let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap();
+		tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+		format!("http://{}", addr)
+	}
+
+	#[test]
+	fn test_build_query_params_includes_descendant_tags() {
+		let query = GetQueryDto {
+			include_descendant_tags: Some(true),
+			..Default::default()
+		};
+
+		let params = build_query_params(&query);
+
+		assert!(params.contains(&("include_descendant_tags", "true".to_string())));
+	}
+
+	#[test]
+	fn test_build_query_params_includes_cram() {
+		let query = GetQueryDto {
+			cram: Some(true),
+			..Default::default()
+		};
+
+		let params = build_query_params(&query);
+
+		assert!(params.contains(&("cram", "true".to_string())));
+	}
+
+	#[tokio::test]
+	async fn test_list_due_cards_queries_due_state() {
+		let card = Card::new(ItemId::new(), 0, Utc::now(), 0.7);
+		let received_query = Arc::new(Mutex::new(None));
+
+		let router = Router::new().route(
+			"/cards",
+			get({
+				let card = card.clone();
+				let received_query = received_query.clone();
+				move |RawQuery(query): RawQuery| async move {
+					*received_query.lock().unwrap() = query;
+					Json(vec![card])
+				}
+			}),
+		);
+		let client = HippocampusClient::new(serve(router).await, DEFAULT_TIMEOUT);
+
+		let cards = client.list_due_cards().await.unwrap();
+
+		assert_eq!(cards.len(), 1);
+		assert_eq!(cards[0].get_id(), card.get_id());
+		assert_eq!(cards[0].get_priority(), 0.7);
+		let query = received_query.lock().unwrap().clone().unwrap();
+		assert!(query.contains("state=due"), "got: {}", query);
+		assert!(query.contains("suspended_filter=Exclude"), "got: {}", query);
+	}
+
+	#[tokio::test]
+	async fn test_gets_are_retried_while_the_server_is_unavailable() {
+		let requests = Arc::new(AtomicUsize::new(0));
+		let router = Router::new().route(
+			"/item_types",
+			get(flaky(1, serde_json::json!([]), requests.clone())),
+		);
+		let client = HippocampusClient::new(serve(router).await, DEFAULT_TIMEOUT);
+
+		let item_types = client.list_item_types().await.unwrap();
+
+		assert!(item_types.is_empty());
+		assert_eq!(requests.load(Ordering::SeqCst), 2);
+	}
+
+	#[tokio::test]
+	async fn test_gets_give_up_after_the_last_retry() {
+		let requests = Arc::new(AtomicUsize::new(0));
+		let router = Router::new().route(
+			"/item_types",
+			get(flaky(usize::MAX, serde_json::json!([]), requests.clone())),
+		);
+		let client = HippocampusClient::new(serve(router).await, DEFAULT_TIMEOUT);
+
+		let err = client.list_item_types().await.unwrap_err();
+
+		assert!(
+			matches!(err, ClientError::Server { status, .. } if status == reqwest::StatusCode::SERVICE_UNAVAILABLE),
+			"got: {}",
+			err
+		);
+		assert_eq!(requests.load(Ordering::SeqCst), 1 + MAX_RETRIES as usize);
+	}
+
+	#[tokio::test]
+	async fn test_posts_are_not_retried() {
+		let requests = Arc::new(AtomicUsize::new(0));
+		let tag = Tag::new("tag".to_string(), true);
+		let router = Router::new().route(
+			"/tags",
+			post(flaky(
+				1,
+				serde_json::to_value(&tag).unwrap(),
+				requests.clone(),
+			)),
+		);
+		let client = HippocampusClient::new(serve(router).await, DEFAULT_TIMEOUT);
+
+		assert!(
+			client
+				.create_tag("tag".to_string(), true, None)
+				.await
+				.is_err()
+		);
+		assert_eq!(requests.load(Ordering::SeqCst), 1);
+	}
+}
@@ -1,8 +1,9 @@
 use chrono::{DateTime, Utc};
 use clap::Subcommand;
-use hippocampus::dto::{GetQueryDto, SortPositionAction, SuspendedFilter};
+use hippocampus::dto::{CardState, GetQueryDto, SortPositionAction, SuspendedFilter, TagMatch};
 use hippocampus::models::{CardId, ItemId, ItemTypeId, TagId};
 
+use super::todo::fetch_cards_with_items;
 use crate::client::HippocampusClient;
 use crate::output::{self, OutputConfig};
 
@@ -14,9 +15,21 @@ pub enum CardCommands {
 		/// Filter by item type ID
 		#[clap(long)]
 		item_type_id: Option<ItemTypeId>,
+		/// Exclude cards whose item has one of these item type IDs
+		#[clap(long)]
+		exclude_item_type_ids: Vec<ItemTypeId>,
 		/// Filter by tag IDs
 		#[clap(long)]
 		tag_ids: Vec<TagId>,
+		/// Also match cards tagged with descendants of the filter tags
+		#[clap(long)]
+		include_descendant_tags: bool,
+		/// Match cards with any of the filter tags rather than all of them
+		#[clap(long)]
+		any_tag: bool,
+		/// Ignore scheduling for cramming: include cards that aren't due or are buried
+		#[clap(long)]
+		cram: bool,
 		/// Only cards with next review before this datetime (RFC 3339)
 		#[clap(long)]
 		next_review_before: Option<DateTime<Utc>>,
@@ -41,7 +54,12 @@ pub enum CardCommands {
 		/// Filter by child item ID
 		#[clap(long)]
 		child_item_id: Option<ItemId>,
+		/// Card state: new, due, learning, or review
+		#[clap(long)]
+		state: Option<String>,
 	},
+	/// Show the cards due for review now
+	Due,
 	/// Get a specific card by ID
 	Get {
 		/// The card ID
@@ -126,6 +144,17 @@ fn parse_suspended_filter(s: &str) -> SuspendedFilter {
 	}
 }
 
+/// Parses a card state string into the enum, ignoring unknown states
+fn parse_card_state(s: &str) -> Option<CardState> {
+	match s.to_lowercase().as_str() {
+		"new" => Some(CardState::New),
+		"due" => Some(CardState::Due),
+		"learning" => Some(CardState::Learning),
+		"review" => Some(CardState::Review),
+		_ => None,
+	}
+}
+
 /// Executes a card command
 pub async fn execute(
 	client: &HippocampusClient,
@@ -135,7 +164,11 @@ pub async fn execute(
 	match cmd {
 		CardCommands::List {
 			item_type_id,
+			exclude_item_type_ids,
 			tag_ids,
+			include_descendant_tags,
+			any_tag,
+			cram,
 			next_review_before,
 			last_review_after,
 			suspended_filter,
@@ -144,6 +177,7 @@ pub async fn execute(
 			split_priority,
 			parent_item_id,
 			child_item_id,
+			state,
 		} => {
 			let query = GetQueryDto {
 				item_type_id,
@@ -156,14 +190,32 @@ pub async fn execute(
 				split_priority: if split_priority { Some(true) } else { None },
 				parent_item_id,
 				child_item_id,
+				state: state.as_deref().and_then(parse_card_state),
+				include_descendant_tags: if include_descendant_tags {
+					Some(true)
+				} else {
+					None
+				},
+				cram: if cram { Some(true) } else { None },
+				exclude_item_type_ids,
+				tag_match: if any_tag {
+					TagMatch::Any
+				} else {
+					TagMatch::All
+				},
 			};
 			let cards = client.list_cards(&query).await?;
-			output::print_cards(&cards, config);
+			output::print(&cards, config);
+		}
+		CardCommands::Due => {
+			let cards = client.list_due_cards().await?;
+			let cards_with_items = fetch_cards_with_items(client, cards).await?;
+			output::print(&output::DueCards(&cards_with_items), config);
 		}
 		CardCommands::Get { id } => {
 			let card = client.get_card(&id).await?;
 			match card {
-				Some(card) => output::print_card(&card, config),
+				Some(card) => output::print(&card, config),
 				None => {
 					eprintln!("Card not found: {}", id);
 					std::process::exit(1);
@@ -172,28 +224,31 @@ pub async fn execute(
 		}
 		CardCommands::Priority { id, value } => {
 			let card = client.update_card_priority(&id, value).await?;
-			output::print_card(&card, config);
+			output::print(&card, config);
 		}
 		CardCommands::Suspend { id, suspend } => {
 			client.suspend_card(&id, suspend).await?;
 			let action = if suspend { "Suspended" } else { "Unsuspended" };
-			output::print_success(&format!("{} card {}", action, id), config);
+			output::print(
+				&output::Success::new(&format!("{} card {}", action, id)),
+				config,
+			);
 		}
 		CardCommands::NextReviews { id } => {
 			let next_reviews = client.get_next_reviews(&id).await?;
-			output::print_next_reviews(&next_reviews, config);
+			output::print(&output::NextReviews(&next_reviews), config);
 		}
 		CardCommands::ReorderToTop { id } => {
 			let card = client
 				.set_sort_position(&id, &SortPositionAction::Top)
 				.await?;
-			output::print_card_json(&card, config);
+			output::print(&output::CardJson(&card), config);
 		}
 		CardCommands::ReorderToBottom { id } => {
 			let card = client
 				.set_sort_position(&id, &SortPositionAction::Bottom)
 				.await?;
-			output::print_card_json(&card, config);
+			output::print(&output::CardJson(&card), config);
 		}
 		CardCommands::ReorderBefore {
 			id_to_move,
@@ -205,7 +260,7 @@ pub async fn execute(
 					&SortPositionAction::Before { card_id: target_id },
 				)
 				.await?;
-			output::print_card_json(&card, config);
+			output::print(&output::CardJson(&card), config);
 		}
 		CardCommands::ReorderAfter {
 			id_to_move,
@@ -217,7 +272,7 @@ pub async fn execute(
 					&SortPositionAction::After { card_id: target_id },
 				)
 				.await?;
-			output::print_card_json(&card, config);
+			output::print(&output::CardJson(&card), config);
 		}
 		CardCommands::ClearOrdering {
 			item_type_id,
@@ -239,10 +294,43 @@ pub async fn execute(
 				split_priority: None,
 				parent_item_id: None,
 				child_item_id: None,
+				state: None,
+				include_descendant_tags: None,
+				cram: None,
+				exclude_item_type_ids: Vec::new(),
+				tag_match: TagMatch::default(),
 			};
 			client.clear_sort_positions(&query).await?;
-			output::print_success("Cleared card ordering", config);
+			output::print(&output::Success::new("Cleared card ordering"), config);
 		}
 	}
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::output::{CardJson, DueCards, NextReviews, Success, assert_renders_in_every_format};
+	use hippocampus::models::{Card, Item, JsonValue};
+	use serde_json::json;
+
+	#[test]
+	fn test_card_output_renders_in_every_format() {
+		let item = Item::new(
+			ItemTypeId::new(),
+			"An Item".to_string(),
+			JsonValue(json!({})),
+		);
+		let card = Card::new(item.get_id(), 0, Utc::now(), 0.5);
+
+		assert_renders_in_every_format(&card);
+		assert_renders_in_every_format(&vec![card.clone()]);
+		assert_renders_in_every_format(&Vec::<Card>::new());
+		assert_renders_in_every_format(&CardJson(&serde_json::to_value(&card).unwrap()));
+		assert_renders_in_every_format(&NextReviews(&[(Utc::now(), json!({}))]));
+		assert_renders_in_every_format(&NextReviews(&[]));
+		assert_renders_in_every_format(&DueCards(&[(card, Some(item))]));
+		assert_renders_in_every_format(&DueCards(&[]));
+		assert_renders_in_every_format(&Success::new("Cleared card ordering"));
+	}
+}
@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use hippocampus::config::{self as app_config, CONFIG_FILE_NAME};
+
+use crate::output::{self, OutputConfig};
+
+/// Config file commands
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+	/// Write a config file listing every setting at its default
+	Init {
+		/// Overwrite the config file if it already exists
+		#[clap(long)]
+		force: bool,
+		/// Config directory to use instead of the XDG config directory
+		#[clap(long)]
+		config_dir: Option<PathBuf>,
+	},
+	/// Print where the config file lives
+	Path {
+		/// Config directory to use instead of the XDG config directory
+		#[clap(long)]
+		config_dir: Option<PathBuf>,
+	},
+}
+
+/// Resolves the config directory, from the override if given
+///
+/// Debug builds don't read a config file from the XDG config directory (see
+/// `config::get_config_dir_path`), so they need an override.
+fn resolve_config_dir(override_path: Option<PathBuf>) -> Result<PathBuf, String> {
+	if let Some(path) = override_path {
+		return Ok(path);
+	}
+	if cfg!(debug_assertions) {
+		return Err(
+			"Debug builds don't read a config file from the XDG config directory; \
+			 use --config-dir to choose a directory"
+				.to_string(),
+		);
+	}
+	app_config::default_config_dir()
+		.ok_or_else(|| "Could not determine the XDG config directory".to_string())
+}
+
+/// Executes a config command
+pub fn execute(
+	cmd: ConfigCommands,
+	config: &OutputConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+	match cmd {
+		ConfigCommands::Init { force, config_dir } => {
+			let config_dir = resolve_config_dir(config_dir)?;
+			let config_path = app_config::write_default_config(&config_dir, force)?;
+			output::print(
+				&output::Success::new(&format!(
+					"Wrote default config to {}",
+					config_path.display()
+				)),
+				config,
+			);
+		}
+		ConfigCommands::Path { config_dir } => {
+			let config_path = resolve_config_dir(config_dir)?.join(CONFIG_FILE_NAME);
+			if !config.quiet && !config_path.exists() {
+				eprintln!("(no config file there yet; create one with `config init`)");
+			}
+			output::print(&output::ConfigPath::new(&config_path), config);
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::output::{ConfigPath, Success, assert_renders_in_every_format};
+
+	#[test]
+	fn test_config_output_renders_in_every_format() {
+		let config_dir = tempfile::tempdir().unwrap();
+		let config_path = config_dir.path().join(CONFIG_FILE_NAME);
+
+		assert_renders_in_every_format(&ConfigPath::new(&config_path));
+		assert_renders_in_every_format(&Success::new("Wrote default config"));
+	}
+
+	#[test]
+	fn test_resolve_config_dir_prefers_override() {
+		let config_dir = tempfile::tempdir().unwrap();
+		assert_eq!(
+			resolve_config_dir(Some(config_dir.path().to_path_buf())),
+			Ok(config_dir.path().to_path_buf())
+		);
+	}
+}
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use clap::Subcommand;
-use hippocampus::dto::{GetQueryDto, SuspendedFilter};
+use hippocampus::dto::{GetQueryDto, SuspendedFilter, TagMatch};
 use hippocampus::models::{ItemId, ItemTypeId, TagId};
 
 use crate::client::HippocampusClient;
@@ -17,6 +17,12 @@ pub enum ItemCommands {
 		/// Filter by tag IDs
 		#[clap(long)]
 		tag_ids: Vec<TagId>,
+		/// Also match items tagged with descendants of the filter tags
+		#[clap(long)]
+		include_descendant_tags: bool,
+		/// Match items with any of the filter tags rather than all of them
+		#[clap(long)]
+		any_tag: bool,
 		/// Only items with next review before this datetime (RFC 3339)
 		#[clap(long)]
 		next_review_before: Option<DateTime<Utc>>,
@@ -96,6 +102,8 @@ pub async fn execute(
 		ItemCommands::List {
 			item_type_id,
 			tag_ids,
+			include_descendant_tags,
+			any_tag,
 			next_review_before,
 			last_review_after,
 			suspended_filter,
@@ -115,9 +123,22 @@ pub async fn execute(
 				split_priority: None,
 				parent_item_id,
 				child_item_id,
+				state: None,
+				include_descendant_tags: if include_descendant_tags {
+					Some(true)
+				} else {
+					None
+				},
+				cram: None,
+				exclude_item_type_ids: Vec::new(),
+				tag_match: if any_tag {
+					TagMatch::Any
+				} else {
+					TagMatch::All
+				},
 			};
 			let items = client.list_items(&query).await?;
-			output::print_items(&items, config);
+			output::print(&items, config);
 		}
 		ItemCommands::Create {
 			item_type_id,
@@ -129,12 +150,12 @@ pub async fn execute(
 			let item = client
 				.create_item(item_type_id, title, item_data, priority)
 				.await?;
-			output::print_item(&item, config);
+			output::print(&item, config);
 		}
 		ItemCommands::Get { id } => {
 			let item = client.get_item(&id).await?;
 			match item {
-				Some(item) => output::print_item(&item, config),
+				Some(item) => output::print(&item, config),
 				None => {
 					eprintln!("Item not found: {}", id);
 					std::process::exit(1);
@@ -144,12 +165,36 @@ pub async fn execute(
 		ItemCommands::Update { id, title, data } => {
 			let item_data = data.map(|d| serde_json::from_str(&d)).transpose()?;
 			let item = client.update_item(&id, title, item_data).await?;
-			output::print_item(&item, config);
+			output::print(&item, config);
 		}
 		ItemCommands::Delete { id } => {
 			client.delete_item(&id).await?;
-			output::print_success(&format!("Deleted item {}", id), config);
+			output::print(
+				&output::Success::new(&format!("Deleted item {}", id)),
+				config,
+			);
 		}
 	}
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::output::{Success, assert_renders_in_every_format};
+	use hippocampus::models::{Item, JsonValue};
+	use serde_json::json;
+
+	#[test]
+	fn test_item_output_renders_in_every_format() {
+		let item = Item::new(
+			ItemTypeId::new(),
+			"An Item".to_string(),
+			JsonValue(json!({"front": "F", "back": "B"})),
+		);
+		assert_renders_in_every_format(&item);
+		assert_renders_in_every_format(&vec![item]);
+		assert_renders_in_every_format(&Vec::<Item>::new());
+		assert_renders_in_every_format(&Success::new("Deleted item item-1"));
+	}
+}
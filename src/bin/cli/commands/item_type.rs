@@ -42,26 +42,40 @@ pub async fn execute(
 	match cmd {
 		ItemTypeCommands::List => {
 			let item_types = client.list_item_types().await?;
-			output::print_item_types(&item_types, config);
+			output::print(&item_types, config);
 		}
 		ItemTypeCommands::Create {
 			name,
 			review_function,
 		} => {
 			let item_type = client.create_item_type(name, Some(review_function)).await?;
-			output::print_item_type(&item_type, config);
+			output::print(&item_type, config);
 		}
 		ItemTypeCommands::Get { id } => {
 			let item_type = client.get_item_type(&id).await?;
-			output::print_item_type(&item_type, config);
+			output::print(&item_type, config);
 		}
 		ItemTypeCommands::Update {
 			id,
 			review_function,
 		} => {
 			let item_type = client.update_item_type(&id, review_function).await?;
-			output::print_item_type(&item_type, config);
+			output::print(&item_type, config);
 		}
 	}
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use crate::output::assert_renders_in_every_format;
+	use hippocampus::models::ItemType;
+
+	#[test]
+	fn test_item_type_output_renders_in_every_format() {
+		let item_type = ItemType::new("Basic".to_string(), "fsrs".to_string());
+		assert_renders_in_every_format(&item_type);
+		assert_renders_in_every_format(&vec![item_type]);
+		assert_renders_in_every_format(&Vec::<ItemType>::new());
+	}
+}
@@ -1,6 +1,8 @@
 pub mod card;
+pub mod config;
 pub mod item;
 pub mod item_type;
 pub mod review;
+pub mod review_session;
 pub mod tag;
 pub mod todo;
@@ -1,6 +1,15 @@
+use std::future::Future;
+use std::io::Write;
+use std::pin::Pin;
+
+use chrono::{DateTime, TimeDelta, Utc};
 use clap::Subcommand;
-use hippocampus::models::CardId;
+use hippocampus::dto::{CardState, GetQueryDto, ListReviewsQueryDto, SuspendedFilter};
+use hippocampus::models::{CardId, Rating};
+use tokio::io::{AsyncBufReadExt, BufReader, Lines, Stdin};
 
+use super::review_session::{RatingInput, ReviewTally, card_sides, parse_rating_input};
+use super::todo::{resolve_item_type_id, resolve_tag_ids};
 use crate::client::HippocampusClient;
 use crate::output::{self, OutputConfig};
 
@@ -14,17 +23,239 @@ pub enum ReviewCommands {
 		card_id: CardId,
 		/// The rating (1-4)
 		#[clap(long)]
-		rating: i32,
+		rating: Rating,
 	},
 
-	/// List the reviews for a card
+	/// List reviews, newest first
 	List {
-		/// The card ID to review
+		/// Only list the reviews of this card
 		#[clap(long)]
-		card_id: CardId,
+		card_id: Option<CardId>,
+		/// Only list reviews from within this long ago, e.g. 7d, 24h or 1h30m
+		#[clap(long, value_parser = parse_duration)]
+		since: Option<TimeDelta>,
+	},
+
+	/// Review due cards interactively until none are left
+	Start {
+		/// Filter by tag name or ID, can be specified multiple times
+		#[clap(long)]
+		tag: Vec<String>,
+		/// Filter by item type name or ID
+		#[clap(long)]
+		item_type: Option<String>,
 	},
 }
 
+/// Parses a duration like `7d`, `24h` or `1h30m`
+///
+/// A duration is one or more whole numbers, each followed by a unit: `s`
+/// (seconds), `m` (minutes), `h` (hours), `d` (days) or `w` (weeks).
+///
+/// ### Arguments
+///
+/// * `s` - The duration to parse
+///
+/// ### Returns
+///
+/// The duration
+///
+/// ### Errors
+///
+/// Returns a message suitable for the command line if `s` isn't a duration
+fn parse_duration(s: &str) -> Result<TimeDelta, String> {
+	let invalid = || {
+		format!(
+			"invalid duration {:?}: expected a number and a unit (s, m, h, d or w), like 7d or 24h",
+			s
+		)
+	};
+
+	let mut total = TimeDelta::zero();
+	let mut rest = s.trim();
+	if rest.is_empty() {
+		return Err(invalid());
+	}
+	while !rest.is_empty() {
+		let digits = rest
+			.find(|c: char| !c.is_ascii_digit())
+			.ok_or_else(invalid)?;
+		let amount: i64 = rest[..digits].parse().map_err(|_| invalid())?;
+		let unit = rest[digits..].chars().next().ok_or_else(invalid)?;
+		let part = match unit {
+			's' => TimeDelta::try_seconds(amount),
+			'm' => TimeDelta::try_minutes(amount),
+			'h' => TimeDelta::try_hours(amount),
+			'd' => TimeDelta::try_days(amount),
+			'w' => TimeDelta::try_weeks(amount),
+			_ => None,
+		};
+		total = part
+			.and_then(|part| total.checked_add(&part))
+			.ok_or_else(invalid)?;
+		rest = &rest[digits + unit.len_utf8()..];
+	}
+	Ok(total)
+}
+
+/// Works out the earliest review time a `--since` duration allows
+///
+/// ### Arguments
+///
+/// * `now` - The current time
+/// * `since` - How far back to list reviews from
+///
+/// ### Returns
+///
+/// `since` before `now`
+///
+/// ### Errors
+///
+/// Returns a message suitable for the command line if `since` reaches back
+/// further than a date can represent
+fn since_cutoff(now: DateTime<Utc>, since: TimeDelta) -> Result<DateTime<Utc>, String> {
+	now.checked_sub_signed(since)
+		.ok_or_else(|| "invalid --since: the duration reaches too far back".to_string())
+}
+
+/// Ends a review session early when the user presses Ctrl+C
+struct Interrupt {
+	ctrl_c: Pin<Box<dyn Future<Output = std::io::Result<()>> + Send>>,
+}
+
+impl Interrupt {
+	/// Starts listening for Ctrl+C, which from now on no longer kills the
+	/// process outright
+	fn listen() -> Self {
+		Self {
+			ctrl_c: Box::pin(tokio::signal::ctrl_c()),
+		}
+	}
+
+	/// Runs `future` unless Ctrl+C is pressed first
+	///
+	/// ### Returns
+	///
+	/// The future's output, or None if the session was interrupted
+	async fn guard<F: Future>(&mut self, future: F) -> Option<F::Output> {
+		tokio::select! {
+			biased;
+			_ = &mut self.ctrl_c => None,
+			output = future => Some(output),
+		}
+	}
+}
+
+/// Prints a prompt and reads the user's answer
+///
+/// ### Returns
+///
+/// The line typed, or None if the session was interrupted or stdin closed
+async fn prompt(
+	lines: &mut Lines<BufReader<Stdin>>,
+	interrupt: &mut Interrupt,
+	message: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+	print!("{}", message);
+	std::io::stdout().flush()?;
+	match interrupt.guard(lines.next_line()).await {
+		Some(line) => Ok(line?),
+		None => Ok(None),
+	}
+}
+
+/// Reviews due cards one at a time until none are left
+///
+/// Each card's front is shown, then its back once the user presses Enter,
+/// and the user's rating is recorded as a review. Due cards are fetched
+/// again once every card fetched has been reviewed, so cards that come due
+/// during the session (like ones rated Again) are reviewed too. Ctrl+C, `q`
+/// or closing stdin ends the session early.
+///
+/// ### Arguments
+///
+/// * `client` - The client to fetch cards and record reviews with
+/// * `query` - Which due cards to review
+///
+/// ### Returns
+///
+/// The ratings given during the session
+async fn run_review_session(
+	client: &HippocampusClient,
+	query: &GetQueryDto,
+) -> Result<ReviewTally, Box<dyn std::error::Error>> {
+	let mut interrupt = Interrupt::listen();
+	let mut lines = BufReader::new(tokio::io::stdin()).lines();
+	let mut tally = ReviewTally::default();
+
+	loop {
+		let Some(cards) = interrupt.guard(client.list_cards(query)).await else {
+			return Ok(tally);
+		};
+		let cards = cards?;
+		if cards.is_empty() {
+			println!("No cards due.");
+			return Ok(tally);
+		}
+
+		let count = cards.len();
+		for (i, card) in cards.into_iter().enumerate() {
+			let Some(item) = interrupt.guard(client.get_item(&card.get_item_id())).await else {
+				return Ok(tally);
+			};
+			let Some(item) = item? else {
+				eprintln!(
+					"Skipping card {}, whose item {} wasn't found",
+					card.get_id(),
+					card.get_item_id()
+				);
+				continue;
+			};
+			let sides = card_sides(&card, &item);
+
+			println!("\n[{}/{}] {}", i + 1, count, item.get_title());
+			println!("{}", sides.front);
+			if prompt(
+				&mut lines,
+				&mut interrupt,
+				"Press Enter to show the answer...",
+			)
+			.await?
+			.is_none()
+			{
+				return Ok(tally);
+			}
+			println!("{}", sides.back);
+
+			let rating = loop {
+				let Some(line) = prompt(
+					&mut lines,
+					&mut interrupt,
+					"Rating (1 Again, 2 Hard, 3 Good, 4 Easy, q to quit): ",
+				)
+				.await?
+				else {
+					return Ok(tally);
+				};
+				match parse_rating_input(&line) {
+					Ok(RatingInput::Rate(rating)) => break rating,
+					Ok(RatingInput::Quit) => return Ok(tally),
+					Err(e) => eprintln!("{}", e),
+				}
+			};
+
+			let Some(review) = interrupt
+				.guard(client.create_review(card.get_id(), rating))
+				.await
+			else {
+				return Ok(tally);
+			};
+			review?;
+			tally.record(rating);
+		}
+	}
+}
+
 /// Executes a review command
 pub async fn execute(
 	client: &HippocampusClient,
@@ -34,12 +265,117 @@ pub async fn execute(
 	match cmd {
 		ReviewCommands::Create { card_id, rating } => {
 			let review = client.create_review(card_id, rating).await?;
-			output::print_review(&review, config);
+			output::print(&review, config);
 		}
-		ReviewCommands::List { card_id } => {
+		ReviewCommands::List {
+			card_id: Some(card_id),
+			since: None,
+		} => {
 			let reviews = client.list_reviews_for_card(&card_id).await?;
-			output::print_reviews(&reviews, config);
+			output::print(&reviews, config);
+		}
+		ReviewCommands::List { card_id, since } => {
+			let from = since
+				.map(|since| since_cutoff(Utc::now(), since))
+				.transpose()?;
+			let query = ListReviewsQueryDto {
+				from,
+				card_id,
+				..Default::default()
+			};
+			let reviews = client.list_reviews(&query).await?;
+			output::print(&reviews, config);
+		}
+		ReviewCommands::Start { tag, item_type } => {
+			let item_type_id = match item_type {
+				Some(name_or_id) => Some(resolve_item_type_id(client, &name_or_id).await?),
+				None => None,
+			};
+			let query = GetQueryDto {
+				item_type_id,
+				tag_ids: resolve_tag_ids(client, &tag).await?,
+				suspended_filter: SuspendedFilter::Exclude,
+				state: Some(CardState::Due),
+				..Default::default()
+			};
+			let tally = run_review_session(client, &query).await?;
+			println!();
+			output::print(&tally, config);
 		}
 	}
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::output::assert_renders_in_every_format;
+	use hippocampus::models::Review;
+
+	#[test]
+	fn test_review_output_renders_in_every_format() {
+		let review = Review::new(CardId("card-1".to_string()), Rating::Good);
+		assert_renders_in_every_format(&review);
+		assert_renders_in_every_format(&vec![review]);
+		assert_renders_in_every_format(&Vec::<Review>::new());
+
+		let mut tally = ReviewTally::default();
+		tally.record(Rating::Again);
+		assert_renders_in_every_format(&tally);
+		let json: serde_json::Value = serde_json::from_str(&output::render(
+			&tally,
+			&OutputConfig {
+				format: output::OutputFormat::Json,
+				quiet: false,
+			},
+		))
+		.unwrap();
+		assert_eq!(json["reviewed"], 1);
+		assert_eq!(json["again"], 1);
+	}
+
+	#[test]
+	fn test_parse_duration_days_and_hours() {
+		assert_eq!(parse_duration("7d"), Ok(TimeDelta::days(7)));
+		assert_eq!(parse_duration("24h"), Ok(TimeDelta::hours(24)));
+		assert_eq!(parse_duration("2w"), Ok(TimeDelta::weeks(2)));
+		assert_eq!(parse_duration("90s"), Ok(TimeDelta::seconds(90)));
+		assert_eq!(
+			parse_duration("1h30m"),
+			Ok(TimeDelta::hours(1) + TimeDelta::minutes(30))
+		);
+		assert_eq!(parse_duration(" 1d "), Ok(TimeDelta::days(1)));
+	}
+
+	#[test]
+	fn test_parse_duration_rejects_invalid_input() {
+		for input in [
+			"",
+			"7",
+			"d",
+			"7x",
+			"-7d",
+			"7 d",
+			"1.5h",
+			"99999999999999999999d",
+		] {
+			let err = parse_duration(input).unwrap_err();
+			assert!(err.contains("invalid duration"), "got: {}", err);
+		}
+	}
+
+	#[test]
+	fn test_since_cutoff() {
+		let now = Utc::now();
+		assert_eq!(
+			since_cutoff(now, TimeDelta::days(7)),
+			Ok(now - TimeDelta::days(7))
+		);
+
+		// A duration that parses but goes back past the earliest date errors
+		// rather than panicking
+		let since = parse_duration("100000000000d").unwrap();
+		let err = since_cutoff(now, since).unwrap_err();
+		assert!(err.contains("--since"), "got: {}", err);
+	}
+}
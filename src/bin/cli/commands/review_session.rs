@@ -0,0 +1,280 @@
+//! The parts of `review start` that don't touch the terminal or the server:
+//! working out what a card shows, reading ratings, and tallying a session.
+
+use hippocampus::models::{Card, CardDirection, Item, Rating};
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use serde_json::Value;
+
+use crate::output::Render;
+
+/// What's shown before and after revealing a card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardSides {
+	/// The question, shown first
+	pub front: String,
+	/// The answer, shown once the user asks for it
+	pub back: String,
+}
+
+/// Works out the question and answer a card shows
+///
+/// Cloze items show their `text` with the card's deletion (or, for
+/// `"all_in_one"` items, every deletion) blanked out. Items with `front` and
+/// `back` (or `term` and `definition`) fields show them, swapped for reverse
+/// cards. Anything else shows the item's title, then its data.
+///
+/// ### Arguments
+///
+/// * `card` - The card being reviewed
+/// * `item` - The card's item
+///
+/// ### Returns
+///
+/// The card's front and back
+pub fn card_sides(card: &Card, item: &Item) -> CardSides {
+	let data = item.get_data().0;
+
+	if let Some(clozes) = data.get("clozes").and_then(Value::as_array) {
+		let text = data
+			.get("text")
+			.and_then(Value::as_str)
+			.map(str::to_string)
+			.unwrap_or_else(|| item.get_title());
+		let deletions: Vec<String> = clozes.iter().map(value_text).collect();
+		let hidden: Vec<&String> =
+			if data.get("cloze_mode").and_then(Value::as_str) == Some("all_in_one") {
+				deletions.iter().collect()
+			} else {
+				deletions
+					.get(card.get_card_index() as usize)
+					.into_iter()
+					.collect()
+			};
+		let front = hidden.into_iter().fold(text.clone(), |front, deletion| {
+			front.replacen(deletion.as_str(), "[...]", 1)
+		});
+		return CardSides { front, back: text };
+	}
+
+	for (front_field, back_field) in [("front", "back"), ("term", "definition")] {
+		if let (Some(front), Some(back)) = (data.get(front_field), data.get(back_field)) {
+			let (front, back) = (value_text(front), value_text(back));
+			return match card.get_direction() {
+				Some(CardDirection::Reverse) => CardSides {
+					front: back,
+					back: front,
+				},
+				_ => CardSides { front, back },
+			};
+		}
+	}
+
+	CardSides {
+		front: item.get_title(),
+		back: serde_json::to_string_pretty(&data).unwrap_or_default(),
+	}
+}
+
+/// Renders a JSON value for display, without quotes around strings
+fn value_text(value: &Value) -> String {
+	match value {
+		Value::String(s) => s.clone(),
+		other => other.to_string(),
+	}
+}
+
+/// What the user answered when asked to rate a card
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RatingInput {
+	/// Record a review with this rating
+	Rate(Rating),
+	/// End the session without rating the card
+	Quit,
+}
+
+/// Parses the user's answer to the rating prompt
+///
+/// ### Arguments
+///
+/// * `input` - The line the user typed
+///
+/// ### Returns
+///
+/// The rating or request to quit
+///
+/// ### Errors
+///
+/// Returns a message to show the user if the input is neither a rating from
+/// 1 to 4 nor `q`/`quit`
+pub fn parse_rating_input(input: &str) -> Result<RatingInput, String> {
+	let input = input.trim();
+	if input.eq_ignore_ascii_case("q") || input.eq_ignore_ascii_case("quit") {
+		return Ok(RatingInput::Quit);
+	}
+	input.parse().map(RatingInput::Rate)
+}
+
+/// The ratings given during a review session
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReviewTally {
+	/// Reviews recorded, indexed by rating value - 1
+	counts: [usize; Rating::ALL.len()],
+}
+
+impl ReviewTally {
+	/// Counts a recorded review
+	pub fn record(&mut self, rating: Rating) {
+		self.counts[rating.as_i32() as usize - 1] += 1;
+	}
+
+	/// Returns the number of reviews recorded
+	pub fn total(&self) -> usize {
+		self.counts.iter().sum()
+	}
+
+	/// Summarises the session, e.g.
+	/// `Reviewed 3 cards (Again 1, Hard 0, Good 2, Easy 0)`
+	pub fn summary(&self) -> String {
+		let total = self.total();
+		let counts: Vec<String> = Rating::ALL
+			.iter()
+			.zip(self.counts)
+			.map(|(rating, count)| format!("{:?} {}", rating, count))
+			.collect();
+		format!(
+			"Reviewed {} card{} ({})",
+			total,
+			if total == 1 { "" } else { "s" },
+			counts.join(", ")
+		)
+	}
+}
+
+/// Serializes as `{"reviewed": 3, "again": 1, "hard": 0, "good": 2, "easy": 0}`
+impl Serialize for ReviewTally {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut state = serializer.serialize_struct("ReviewTally", 1 + Rating::ALL.len())?;
+		state.serialize_field("reviewed", &self.total())?;
+		state.serialize_field("again", &self.counts[0])?;
+		state.serialize_field("hard", &self.counts[1])?;
+		state.serialize_field("good", &self.counts[2])?;
+		state.serialize_field("easy", &self.counts[3])?;
+		state.end()
+	}
+}
+
+impl Render for ReviewTally {
+	fn human(&self) -> String {
+		self.summary()
+	}
+
+	fn quiet(&self) -> String {
+		self.total().to_string()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::Utc;
+	use hippocampus::models::{ItemTypeId, JsonValue};
+	use serde_json::json;
+
+	fn item_and_card(data: Value, card_index: i32) -> (Item, Card) {
+		let item = Item::new(ItemTypeId::new(), "The Title".to_string(), JsonValue(data));
+		let card = Card::new(item.get_id(), card_index, Utc::now(), 0.5);
+		(item, card)
+	}
+
+	#[test]
+	fn test_card_sides_basic_forward_and_reverse() {
+		let (item, mut card) = item_and_card(json!({"front": "Hello", "back": "World"}), 0);
+		card.set_direction(Some(CardDirection::Forward));
+		assert_eq!(
+			card_sides(&card, &item),
+			CardSides {
+				front: "Hello".to_string(),
+				back: "World".to_string()
+			}
+		);
+
+		card.set_direction(Some(CardDirection::Reverse));
+		assert_eq!(
+			card_sides(&card, &item),
+			CardSides {
+				front: "World".to_string(),
+				back: "Hello".to_string()
+			}
+		);
+	}
+
+	#[test]
+	fn test_card_sides_vocabulary() {
+		let (item, card) = item_and_card(json!({"term": "chat", "definition": "cat"}), 0);
+		let sides = card_sides(&card, &item);
+		assert_eq!(sides.front, "chat");
+		assert_eq!(sides.back, "cat");
+	}
+
+	#[test]
+	fn test_card_sides_cloze_hides_the_cards_deletion() {
+		let data = json!({"text": "Paris is in France", "clozes": ["Paris", "France"]});
+		let (item, card) = item_and_card(data.clone(), 1);
+		let sides = card_sides(&card, &item);
+		assert_eq!(sides.front, "Paris is in [...]");
+		assert_eq!(sides.back, "Paris is in France");
+
+		let mut all_in_one = data;
+		all_in_one["cloze_mode"] = json!("all_in_one");
+		let (item, card) = item_and_card(all_in_one, 0);
+		assert_eq!(card_sides(&card, &item).front, "[...] is in [...]");
+	}
+
+	#[test]
+	fn test_card_sides_falls_back_to_title_and_data() {
+		let (item, card) = item_and_card(json!({"notes": "anything"}), 0);
+		let sides = card_sides(&card, &item);
+		assert_eq!(sides.front, "The Title");
+		assert!(sides.back.contains("anything"), "got: {}", sides.back);
+	}
+
+	#[test]
+	fn test_parse_rating_input() {
+		assert_eq!(
+			parse_rating_input("3\n"),
+			Ok(RatingInput::Rate(Rating::Good))
+		);
+		assert_eq!(
+			parse_rating_input(" 1 "),
+			Ok(RatingInput::Rate(Rating::Again))
+		);
+		assert_eq!(parse_rating_input("q"), Ok(RatingInput::Quit));
+		assert_eq!(parse_rating_input("QUIT"), Ok(RatingInput::Quit));
+		assert!(parse_rating_input("5").is_err());
+		assert!(parse_rating_input("").is_err());
+	}
+
+	#[test]
+	fn test_review_tally_summary() {
+		let mut tally = ReviewTally::default();
+		assert_eq!(
+			tally.summary(),
+			"Reviewed 0 cards (Again 0, Hard 0, Good 0, Easy 0)"
+		);
+
+		tally.record(Rating::Good);
+		assert_eq!(
+			tally.summary(),
+			"Reviewed 1 card (Again 0, Hard 0, Good 1, Easy 0)"
+		);
+
+		tally.record(Rating::Again);
+		tally.record(Rating::Good);
+		assert_eq!(tally.total(), 3);
+		assert_eq!(
+			tally.summary(),
+			"Reviewed 3 cards (Again 1, Hard 0, Good 2, Easy 0)"
+		);
+	}
+}
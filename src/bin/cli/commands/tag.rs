@@ -1,8 +1,14 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
+
 use clap::Subcommand;
 use hippocampus::models::{ItemId, TagId};
+use serde::Serialize;
 
 use crate::client::HippocampusClient;
-use crate::output::{self, OutputConfig};
+use crate::output::{self, OutputConfig, Render};
 
 /// Tag management commands
 #[derive(Subcommand, Debug)]
@@ -17,6 +23,9 @@ pub enum TagCommands {
 		/// Whether the tag is visible
 		#[clap(long)]
 		visible: bool,
+		/// The ID of the tag to nest this one under
+		#[clap(long)]
+		parent_id: Option<TagId>,
 	},
 	/// Add a tag to an item
 	Add {
@@ -37,6 +46,110 @@ pub enum TagCommands {
 		/// The item ID
 		item_id: ItemId,
 	},
+	/// Add a tag to many items, read one item ID per line
+	Apply {
+		/// The tag ID
+		tag_id: TagId,
+		/// Read the item IDs from stdin
+		#[clap(long, conflicts_with = "file", required_unless_present = "file")]
+		stdin: bool,
+		/// Read the item IDs from a file
+		#[clap(long)]
+		file: Option<PathBuf>,
+	},
+}
+
+/// Reads item IDs, one per line
+///
+/// Surrounding whitespace and blank lines are ignored, as are repeats of an
+/// ID already read.
+///
+/// ### Arguments
+///
+/// * `reader` - Where to read the IDs from
+///
+/// ### Returns
+///
+/// The item IDs, in the order first read
+///
+/// ### Errors
+///
+/// Returns an error if the reader can't be read
+fn read_item_ids(reader: impl BufRead) -> io::Result<Vec<ItemId>> {
+	let mut seen = HashSet::new();
+	let mut item_ids = Vec::new();
+	for line in reader.lines() {
+		let line = line?;
+		let id = line.trim();
+		if !id.is_empty() && seen.insert(id.to_string()) {
+			item_ids.push(ItemId(id.to_string()));
+		}
+	}
+	Ok(item_ids)
+}
+
+/// An item a tag couldn't be added to, and why
+#[derive(Serialize, Debug)]
+struct ApplyTagFailure {
+	item_id: ItemId,
+	error: String,
+}
+
+/// The outcome of adding a tag to many items
+#[derive(Serialize, Debug)]
+struct ApplyTagReport {
+	tag_id: TagId,
+	applied: Vec<ItemId>,
+	failed: Vec<ApplyTagFailure>,
+}
+
+impl Render for ApplyTagReport {
+	fn human(&self) -> String {
+		let total = self.applied.len() + self.failed.len();
+		let mut out = vec![format!(
+			"Tagged {} of {} item{} with {}",
+			self.applied.len(),
+			total,
+			if total == 1 { "" } else { "s" },
+			self.tag_id
+		)];
+		if !self.failed.is_empty() {
+			out.push("Failed:".to_string());
+			out.extend(
+				self.failed
+					.iter()
+					.map(|failure| format!("  {}: {}", failure.item_id, failure.error)),
+			);
+		}
+		out.join("\n")
+	}
+
+	fn quiet(&self) -> String {
+		self.applied.len().to_string()
+	}
+}
+
+/// Adds a tag to every item, carrying on past the ones that fail
+async fn apply_tag(
+	client: &HippocampusClient,
+	tag_id: TagId,
+	item_ids: Vec<ItemId>,
+) -> ApplyTagReport {
+	let mut report = ApplyTagReport {
+		tag_id,
+		applied: Vec::new(),
+		failed: Vec::new(),
+	};
+	for item_id in item_ids {
+		match client.add_tag_to_item(&item_id, &report.tag_id).await {
+			Ok(()) => report.applied.push(item_id),
+			Err(e) => report.failed.push(ApplyTagFailure {
+				item_id,
+				error: e.to_string(),
+			}),
+		}
+	}
+	report
 }
 
 /// Executes a tag command
@@ -48,27 +161,111 @@ pub async fn execute(
 	match cmd {
 		TagCommands::List => {
 			let tags = client.list_tags().await?;
-			output::print_tags(&tags, config);
+			output::print(&tags, config);
 		}
-		TagCommands::Create { name, visible } => {
-			let tag = client.create_tag(name, visible).await?;
-			output::print_tag(&tag, config);
+		TagCommands::Create {
+			name,
+			visible,
+			parent_id,
+		} => {
+			let tag = client.create_tag(name, visible, parent_id).await?;
+			output::print(&tag, config);
 		}
 		TagCommands::Add { item_id, tag_id } => {
 			client.add_tag_to_item(&item_id, &tag_id).await?;
-			output::print_success(&format!("Added tag {} to item {}", tag_id, item_id), config);
+			output::print(
+				&output::Success::new(&format!("Added tag {} to item {}", tag_id, item_id)),
+				config,
+			);
 		}
 		TagCommands::Remove { item_id, tag_id } => {
 			client.remove_tag_from_item(&item_id, &tag_id).await?;
-			output::print_success(
-				&format!("Removed tag {} from item {}", tag_id, item_id),
+			output::print(
+				&output::Success::new(&format!("Removed tag {} from item {}", tag_id, item_id)),
 				config,
 			);
 		}
 		TagCommands::ListForItem { item_id } => {
 			let tags = client.list_tags_for_item(&item_id).await?;
-			output::print_tags(&tags, config);
+			output::print(&tags, config);
+		}
+		TagCommands::Apply {
+			tag_id,
+			stdin: _,
+			file,
+		} => {
+			let item_ids = match file {
+				Some(path) => read_item_ids(BufReader::new(
+					File::open(&path)
+						.map_err(|e| format!("Failed to open {}: {}", path.display(), e))?,
+				))?,
+				None => read_item_ids(io::stdin().lock())?,
+			};
+			let report = apply_tag(client, tag_id, item_ids).await;
+			output::print(&report, config);
+			if !report.failed.is_empty() {
+				let failed = report.failed.len();
+				return Err(format!(
+					"{} item{} couldn't be tagged",
+					failed,
+					if failed == 1 { "" } else { "s" }
+				)
+				.into());
+			}
 		}
 	}
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::output::{Success, assert_renders_in_every_format};
+	use hippocampus::models::Tag;
+
+	#[test]
+	fn test_read_item_ids_skips_blanks_and_repeats() {
+		let input = "item-1\n  item-2  \n\n\titem-1\nitem-3";
+
+		let item_ids = read_item_ids(io::Cursor::new(input)).unwrap();
+
+		let ids: Vec<&str> = item_ids.iter().map(|id| id.0.as_str()).collect();
+		assert_eq!(ids, vec!["item-1", "item-2", "item-3"]);
+		assert!(read_item_ids(io::Cursor::new("")).unwrap().is_empty());
+	}
+
+	#[test]
+	fn test_apply_tag_report_lists_failures() {
+		let report = ApplyTagReport {
+			tag_id: TagId("tag-1".to_string()),
+			applied: vec![ItemId("item-1".to_string())],
+			failed: vec![ApplyTagFailure {
+				item_id: ItemId("item-2".to_string()),
+				error: "Server error (404): Item not found".to_string(),
+			}],
+		};
+
+		let human = report.human();
+		assert!(
+			human.starts_with("Tagged 1 of 2 items with tag-1"),
+			"got: {}",
+			human
+		);
+		assert!(
+			human.contains("item-2: Server error (404)"),
+			"got: {}",
+			human
+		);
+		assert_eq!(report.quiet(), "1");
+		assert_renders_in_every_format(&report);
+	}
+
+	#[test]
+	fn test_tag_output_renders_in_every_format() {
+		let tag = Tag::new("Spanish".to_string(), true);
+		assert_renders_in_every_format(&tag);
+		assert_renders_in_every_format(&vec![tag]);
+		assert_renders_in_every_format(&Vec::<Tag>::new());
+		assert_renders_in_every_format(&Success::new("Added tag to item"));
+	}
+}
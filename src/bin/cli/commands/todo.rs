@@ -1,13 +1,16 @@
 use chrono::{TimeZone, Utc};
 use clap::Subcommand;
 use hippocampus::dto::{GetQueryDto, SuspendedFilter};
-use hippocampus::models::{Card, CardId, Item, ItemTypeId, TagId};
+use hippocampus::models::{Card, CardId, Item, ItemId, ItemTypeId, Rating, TagId};
 
 use crate::client::HippocampusClient;
 use crate::output::{self, OutputConfig};
 
 const TODO_ITEM_TYPE: &str = "Todo";
 
+/// The tag `todo complete --tag-done` attaches, and `todo reopen` removes
+const DONE_TAG: &str = "done";
+
 /// High-level todo workflow commands
 #[derive(Subcommand, Debug)]
 pub enum TodoCommands {
@@ -30,47 +33,66 @@ pub enum TodoCommands {
 	},
 	/// List recently completed todos (suspended today)
 	Completed,
-	/// Mark a todo as complete (suspend the card)
+	/// Mark a todo as complete
+	///
+	/// Completing a todo suspends its card, so it stops coming up as due and
+	/// shows in `todo completed` instead. Completing a recurring todo (one
+	/// whose item type uses the "recurring" review function) records a review
+	/// instead, which schedules its next occurrence.
 	Complete {
-		/// The card ID to complete
-		card_id: CardId,
+		/// The todo's item ID (a card ID also works)
+		id: String,
+		/// Also tag the item "done", creating the tag if needed
+		#[clap(long)]
+		tag_done: bool,
 	},
-	/// Mark a todo as incomplete (unsuspend the card)
-	Uncomplete {
-		/// The card ID to uncomplete
-		card_id: CardId,
+	/// Reopen a completed todo
+	///
+	/// Reopening unsuspends the todo's card and removes any "done" tag.
+	#[command(alias = "uncomplete")]
+	Reopen {
+		/// The todo's item ID (a card ID also works)
+		id: String,
 	},
 	/// Record a review for a card
 	Review {
 		/// The card ID to review
 		card_id: CardId,
 		/// The rating (1-4)
-		rating: i32,
+		rating: Rating,
 	},
 }
 
 /// Resolves the todo item type name to its ID
 async fn resolve_todo_item_type_id(
 	client: &HippocampusClient,
+) -> Result<ItemTypeId, Box<dyn std::error::Error>> {
+	resolve_item_type_id(client, TODO_ITEM_TYPE).await
+}
+
+/// Resolves an item type name or ID to its ID
+pub(super) async fn resolve_item_type_id(
+	client: &HippocampusClient,
+	name_or_id: &str,
 ) -> Result<ItemTypeId, Box<dyn std::error::Error>> {
 	let item_types = client.list_item_types().await?;
 	// Try name match first (case-insensitive)
 	for item_type in &item_types {
-		if item_type.get_name().eq_ignore_ascii_case(TODO_ITEM_TYPE) {
+		if item_type.get_name().eq_ignore_ascii_case(name_or_id) {
 			return Ok(item_type.get_id());
 		}
 	}
 	// Fall back to ID match
 	for item_type in &item_types {
-		if item_type.get_id().0 == TODO_ITEM_TYPE {
+		if item_type.get_id().0 == name_or_id {
 			return Ok(item_type.get_id());
 		}
 	}
-	Err(format!("Item type not found: {}", TODO_ITEM_TYPE).into())
+	Err(format!("Item type not found: {}", name_or_id).into())
 }
 
 /// Resolves a list of tag names or IDs to their IDs
-async fn resolve_tag_ids(
+pub(super) async fn resolve_tag_ids(
 	client: &HippocampusClient,
 	names_or_ids: &[String],
 ) -> Result<Vec<TagId>, Box<dyn std::error::Error>> {
@@ -91,6 +113,68 @@ async fn resolve_tag_ids(
 	Ok(ids)
 }
 
+/// Resolves a todo's item ID, or one of its card IDs, to the item and its cards
+///
+/// ### Arguments
+///
+/// * `client` - The client to look the todo up with
+/// * `id` - An item ID, or a card ID for scripts written before `complete`
+///   took item IDs
+///
+/// ### Returns
+///
+/// The todo's item ID and the IDs of all its cards
+///
+/// ### Errors
+///
+/// Returns an error if `id` is neither an item nor a card, or if the server
+/// can't be reached
+async fn resolve_todo_cards(
+	client: &HippocampusClient,
+	id: &str,
+) -> Result<(ItemId, Vec<CardId>), Box<dyn std::error::Error>> {
+	let item_id = ItemId(id.to_string());
+	if client.get_item(&item_id).await?.is_some() {
+		let cards = client.list_cards_for_item(&item_id).await?;
+		return Ok((item_id, cards.iter().map(Card::get_id).collect()));
+	}
+	match client.get_card(&CardId(id.to_string())).await? {
+		Some(card) => Ok((card.get_item_id(), vec![card.get_id()])),
+		None => Err(format!("Todo not found: {}", id).into()),
+	}
+}
+
+/// Returns whether an item's type uses the "recurring" review function
+async fn is_recurring(
+	client: &HippocampusClient,
+	item_id: &ItemId,
+) -> Result<bool, Box<dyn std::error::Error>> {
+	let item = client
+		.get_item(item_id)
+		.await?
+		.ok_or_else(|| format!("Todo not found: {}", item_id))?;
+	let item_type = client.get_item_type(&item.get_item_type()).await?;
+	Ok(item_type.get_review_function() == "recurring")
+}
+
+/// Finds the tag with the given name, creating it if there isn't one
+async fn find_or_create_tag(
+	client: &HippocampusClient,
+	name: &str,
+) -> Result<TagId, Box<dyn std::error::Error>> {
+	let tags = client.list_tags().await?;
+	if let Some(tag) = tags
+		.iter()
+		.find(|t| t.get_name().eq_ignore_ascii_case(name))
+	{
+		return Ok(tag.get_id());
+	}
+	Ok(client
+		.create_tag(name.to_string(), true, None)
+		.await?
+		.get_id())
+}
+
 /// Returns the start of tomorrow (midnight UTC)
 fn tomorrow_midnight() -> chrono::DateTime<Utc> {
 	let today = Utc::now().date_naive();
@@ -105,7 +189,7 @@ fn today_midnight() -> chrono::DateTime<Utc> {
 }
 
 /// Fetches items for a list of cards, returning paired results
-async fn fetch_cards_with_items(
+pub(super) async fn fetch_cards_with_items(
 	client: &HippocampusClient,
 	cards: Vec<Card>,
 ) -> Result<Vec<(Card, Option<Item>)>, Box<dyn std::error::Error>> {
@@ -156,14 +240,14 @@ pub async fn execute(
 				}
 			}
 
-			output::print_item(&item, config);
+			output::print(&item, config);
 		}
 
 		TodoCommands::Due { tag } => {
 			let query = build_due_query(client, &tag).await?;
 			let cards = client.list_cards(&query).await?;
 			let cards_with_items = fetch_cards_with_items(client, cards).await?;
-			output::print_todo_cards(&cards_with_items, config);
+			output::print(&output::TodoCards(&cards_with_items), config);
 		}
 
 		TodoCommands::Completed => {
@@ -176,23 +260,194 @@ pub async fn execute(
 			};
 			let cards = client.list_cards(&query).await?;
 			let cards_with_items = fetch_cards_with_items(client, cards).await?;
-			output::print_todo_cards(&cards_with_items, config);
+			output::print(&output::TodoCards(&cards_with_items), config);
 		}
 
-		TodoCommands::Complete { card_id } => {
-			client.suspend_card(&card_id, true).await?;
-			output::print_success(&format!("Completed todo {}", card_id), config);
+		TodoCommands::Complete { id, tag_done } => {
+			let (item_id, card_ids) = resolve_todo_cards(client, &id).await?;
+			let recurring = is_recurring(client, &item_id).await?;
+			for card_id in card_ids {
+				if recurring {
+					client.create_review(card_id, Rating::Good).await?;
+				} else {
+					client.suspend_card(&card_id, true).await?;
+				}
+			}
+			if tag_done {
+				let tag_id = find_or_create_tag(client, DONE_TAG).await?;
+				client.add_tag_to_item(&item_id, &tag_id).await?;
+			}
+			output::print(
+				&output::Success::new(&format!("Completed todo {}", id)),
+				config,
+			);
 		}
 
-		TodoCommands::Uncomplete { card_id } => {
-			client.suspend_card(&card_id, false).await?;
-			output::print_success(&format!("Uncompleted todo {}", card_id), config);
+		TodoCommands::Reopen { id } => {
+			let (item_id, card_ids) = resolve_todo_cards(client, &id).await?;
+			for card_id in &card_ids {
+				client.suspend_card(card_id, false).await?;
+			}
+			let tags = client.list_tags_for_item(&item_id).await?;
+			if let Some(tag) = tags
+				.iter()
+				.find(|t| t.get_name().eq_ignore_ascii_case(DONE_TAG))
+			{
+				client.remove_tag_from_item(&item_id, &tag.get_id()).await?;
+			}
+			output::print(
+				&output::Success::new(&format!("Reopened todo {}", id)),
+				config,
+			);
 		}
 
 		TodoCommands::Review { card_id, rating } => {
 			let review = client.create_review(card_id, rating).await?;
-			output::print_review(&review, config);
+			output::print(&review, config);
 		}
 	}
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::client::test_server_client;
+	use crate::output::{OutputFormat, Success, TodoCards, assert_renders_in_every_format};
+	use hippocampus::models::JsonValue;
+	use serde_json::json;
+
+	const QUIET: OutputConfig = OutputConfig {
+		format: OutputFormat::Human,
+		quiet: true,
+	};
+
+	/// Returns whether every card of the item is suspended, and its tag names
+	async fn todo_state(client: &HippocampusClient, item_id: &ItemId) -> (bool, Vec<String>) {
+		let cards = client.list_cards_for_item(item_id).await.unwrap();
+		assert!(!cards.is_empty());
+		let tags = client.list_tags_for_item(item_id).await.unwrap();
+		(
+			cards.iter().all(|c| c.get_suspended().is_some()),
+			tags.iter().map(|t| t.get_name()).collect(),
+		)
+	}
+
+	#[tokio::test]
+	async fn test_complete_and_reopen_round_trip() {
+		let client = test_server_client().await;
+		let item_type = client
+			.create_item_type(TODO_ITEM_TYPE.to_string(), None)
+			.await
+			.unwrap();
+		let item = client
+			.create_item(
+				item_type.get_id(),
+				"Water the plants".to_string(),
+				json!({}),
+				0.5,
+			)
+			.await
+			.unwrap();
+		let item_id = item.get_id();
+		assert_eq!(todo_state(&client, &item_id).await, (false, vec![]));
+
+		let complete = TodoCommands::Complete {
+			id: item_id.0.clone(),
+			tag_done: true,
+		};
+		execute(&client, complete, &QUIET).await.unwrap();
+		assert_eq!(
+			todo_state(&client, &item_id).await,
+			(true, vec![DONE_TAG.to_string()])
+		);
+
+		let reopen = TodoCommands::Reopen {
+			id: item_id.0.clone(),
+		};
+		execute(&client, reopen, &QUIET).await.unwrap();
+		assert_eq!(todo_state(&client, &item_id).await, (false, vec![]));
+	}
+
+	#[tokio::test]
+	async fn test_complete_reschedules_a_recurring_todo() {
+		let client = test_server_client().await;
+		let item_type = client
+			.create_item_type("RecurringTodo".to_string(), Some("recurring".to_string()))
+			.await
+			.unwrap();
+		let item = client
+			.create_item(
+				item_type.get_id(),
+				"Take out the bins".to_string(),
+				json!({"every": "1w"}),
+				0.5,
+			)
+			.await
+			.unwrap();
+
+		let complete = TodoCommands::Complete {
+			id: item.get_id().0.clone(),
+			tag_done: false,
+		};
+		let before = Utc::now();
+		execute(&client, complete, &QUIET).await.unwrap();
+
+		let card = &client.list_cards_for_item(&item.get_id()).await.unwrap()[0];
+		assert!(card.get_suspended().is_none());
+		assert!(card.get_next_review() >= before + chrono::Duration::days(7));
+		assert!(card.get_next_review() <= Utc::now() + chrono::Duration::days(7));
+	}
+
+	#[tokio::test]
+	async fn test_complete_accepts_a_card_id() {
+		let client = test_server_client().await;
+		let item_type = client
+			.create_item_type(TODO_ITEM_TYPE.to_string(), None)
+			.await
+			.unwrap();
+		let item = client
+			.create_item(
+				item_type.get_id(),
+				"Call the bank".to_string(),
+				json!({}),
+				0.5,
+			)
+			.await
+			.unwrap();
+		let card_id = client.list_cards_for_item(&item.get_id()).await.unwrap()[0].get_id();
+
+		let complete = TodoCommands::Complete {
+			id: card_id.0.clone(),
+			tag_done: false,
+		};
+		execute(&client, complete, &QUIET).await.unwrap();
+		assert_eq!(todo_state(&client, &item.get_id()).await, (true, vec![]));
+	}
+
+	#[tokio::test]
+	async fn test_complete_unknown_todo_fails() {
+		let client = test_server_client().await;
+		let complete = TodoCommands::Complete {
+			id: "no-such-todo".to_string(),
+			tag_done: false,
+		};
+		let err = execute(&client, complete, &QUIET).await.unwrap_err();
+		assert_eq!(err.to_string(), "Todo not found: no-such-todo");
+	}
+
+	#[test]
+	fn test_todo_output_renders_in_every_format() {
+		let item = Item::new(
+			ItemTypeId::new(),
+			"Water the plants".to_string(),
+			JsonValue(json!({})),
+		);
+		let card = Card::new(item.get_id(), 0, Utc::now(), 0.5);
+		let orphan = Card::new(ItemId::new(), 0, Utc::now(), 0.5);
+
+		assert_renders_in_every_format(&TodoCards(&[(card, Some(item)), (orphan, None)]));
+		assert_renders_in_every_format(&TodoCards(&[]));
+		assert_renders_in_every_format(&Success::new("Completed todo card-1"));
+	}
+}
@@ -2,11 +2,13 @@ mod client;
 mod commands;
 mod output;
 
-use clap::{Parser, Subcommand};
-use client::HippocampusClient;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use client::{ClientError, HippocampusClient};
 use hippocampus::config;
 use output::{OutputConfig, OutputFormat};
 use std::process;
+use std::time::Duration;
 
 /// CLI for the Hippocampus spaced repetition system
 #[derive(Parser, Debug)]
@@ -24,6 +26,10 @@ struct Cli {
 	#[clap(short, long, global = true)]
 	quiet: bool,
 
+	/// Seconds to wait for the server to respond to each request
+	#[clap(long, env = "HIPPOCAMPUS_TIMEOUT", default_value_t = client::DEFAULT_TIMEOUT.as_secs(), global = true)]
+	timeout: u64,
+
 	#[command(subcommand)]
 	command: Commands,
 }
@@ -48,6 +54,15 @@ enum Commands {
 	/// High-level todo workflow commands
 	#[command(subcommand)]
 	Todo(commands::todo::TodoCommands),
+	/// Manage the config file
+	#[command(subcommand)]
+	Config(commands::config::ConfigCommands),
+	/// Print a shell completion script to stdout
+	Completions {
+		/// The shell to generate completions for
+		#[clap(value_enum)]
+		shell: Shell,
+	},
 }
 
 /// Resolves the server URL from CLI args, config file, or defaults
@@ -75,9 +90,18 @@ fn resolve_server_url(cli_url: Option<String>) -> String {
 }
 
 /// Formats an error for human-readable stderr output
-fn format_error(err: &dyn std::error::Error) -> String {
+fn format_error(err: &(dyn std::error::Error + 'static)) -> String {
 	let err_string = err.to_string();
 
+	if let Some(ClientError::Request(e)) = err.downcast_ref::<ClientError>()
+		&& e.is_timeout()
+	{
+		return format!(
+			"The server didn't respond in time (raise --timeout to wait longer)\n  {}",
+			err_string
+		);
+	}
+
 	// ClientError::Request wraps reqwest errors — check for connection issues
 	if err_string.contains("error sending request")
 		|| err_string.contains("connection refused")
@@ -98,8 +122,17 @@ fn format_error(err: &dyn std::error::Error) -> String {
 #[tokio::main]
 async fn main() {
 	let cli = Cli::parse();
+
+	// Completions are generated locally, without a server to talk to
+	if let Commands::Completions { shell } = cli.command {
+		let mut command = Cli::command();
+		let name = command.get_name().to_string();
+		clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+		return;
+	}
+
 	let server_url = resolve_server_url(cli.server_url);
-	let client = HippocampusClient::new(server_url);
+	let client = HippocampusClient::new(server_url, Duration::from_secs(cli.timeout));
 	let output_config = OutputConfig {
 		format: cli.format,
 		quiet: cli.quiet,
@@ -112,6 +145,8 @@ async fn main() {
 		Commands::Review(cmd) => commands::review::execute(&client, cmd, &output_config).await,
 		Commands::Tag(cmd) => commands::tag::execute(&client, cmd, &output_config).await,
 		Commands::Todo(cmd) => commands::todo::execute(&client, cmd, &output_config).await,
+		Commands::Config(cmd) => commands::config::execute(cmd, &output_config),
+		Commands::Completions { .. } => unreachable!("completions are handled above"),
 	};
 
 	if let Err(e) = result {
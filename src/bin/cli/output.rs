@@ -1,6 +1,9 @@
 use chrono::{DateTime, Utc};
 use clap::ValueEnum;
 use hippocampus::models::{Card, Item, ItemType, Review, Tag};
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use std::path::Path;
 
 /// Output format for CLI commands
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -22,545 +25,690 @@ pub struct OutputConfig {
 	pub quiet: bool,
 }
 
-/// Prints a list of item types in the specified format
-pub fn print_item_types(item_types: &[ItemType], config: &OutputConfig) {
-	match config.format {
-		OutputFormat::Human => {
-			if item_types.is_empty() {
-				if !config.quiet {
-					println!("No item types found.");
-				}
-				return;
-			}
-			if config.quiet {
-				for it in item_types {
-					println!("{}", it.get_id());
-				}
-				return;
-			}
-			let max_id = item_types
-				.iter()
-				.map(|t| t.get_id().0.len())
-				.max()
-				.unwrap_or(2);
-			println!("{:<width$}  NAME", "ID", width = max_id);
-			for it in item_types {
-				println!("{:<width$}  {}", it.get_id(), it.get_name(), width = max_id);
-			}
-		}
-		OutputFormat::Json => {
-			println!("{}", serde_json::to_string_pretty(item_types).unwrap());
-		}
-		OutputFormat::Waybar => {
-			println!("{}", serde_json::to_string(item_types).unwrap());
-		}
+/// Something a command prints, in every output format
+///
+/// JSON output is the value's `Serialize` form, so implementors only say how
+/// they look to a person, and in quiet mode.
+pub trait Render: Serialize {
+	/// Renders the value for a person to read
+	fn human(&self) -> String;
+
+	/// Renders just the value's IDs or count, one per line, for scripts
+	fn quiet(&self) -> String;
+
+	/// Renders the value for a Waybar custom module
+	///
+	/// Defaults to compact JSON.
+	fn waybar(&self) -> String {
+		serde_json::to_string(self).unwrap()
 	}
 }
 
-/// Prints a single item type in the specified format
-pub fn print_item_type(item_type: &ItemType, config: &OutputConfig) {
+/// Renders a value in the configured output format
+///
+/// ### Arguments
+///
+/// * `data` - The value to render
+/// * `config` - The output format and whether to be quiet
+///
+/// ### Returns
+///
+/// The rendered output, which is empty if there's nothing to print (such as
+/// an empty list in quiet mode)
+pub fn render<T: Render + ?Sized>(data: &T, config: &OutputConfig) -> String {
 	match config.format {
-		OutputFormat::Human => {
-			if config.quiet {
-				println!("{}", item_type.get_id());
-				return;
-			}
-			println!("ID:      {}", item_type.get_id());
-			println!("Name:    {}", item_type.get_name());
-			println!("Created: {}", item_type.get_created_at());
-		}
-		OutputFormat::Json => {
-			println!("{}", serde_json::to_string_pretty(item_type).unwrap());
-		}
-		OutputFormat::Waybar => {
-			println!("{}", serde_json::to_string(item_type).unwrap());
-		}
+		OutputFormat::Human if config.quiet => data.quiet(),
+		OutputFormat::Human => data.human(),
+		// JSON output is the same whether or not the CLI is quiet
+		OutputFormat::Json => serde_json::to_string_pretty(data).unwrap(),
+		OutputFormat::Waybar => data.waybar(),
 	}
 }
 
-/// Prints a list of items in the specified format
-pub fn print_items(items: &[Item], config: &OutputConfig) {
-	match config.format {
-		OutputFormat::Human => {
-			if items.is_empty() {
-				if !config.quiet {
-					println!("No items found.");
-				}
-				return;
-			}
-			if config.quiet {
-				for item in items {
-					println!("{}", item.get_id());
-				}
-				return;
-			}
-			let max_id = items.iter().map(|i| i.get_id().0.len()).max().unwrap_or(2);
-			let max_title = items.iter().map(|i| i.get_title().len()).max().unwrap_or(5);
-			println!(
+/// Prints a value to stdout in the configured output format
+///
+/// ### Arguments
+///
+/// * `data` - The value to print
+/// * `config` - The output format and whether to be quiet
+pub fn print<T: Render + ?Sized>(data: &T, config: &OutputConfig) {
+	let output = render(data, config);
+	if !output.is_empty() {
+		println!("{}", output);
+	}
+}
+
+impl<T> Render for Vec<T>
+where
+	T: Serialize,
+	[T]: Render,
+{
+	fn human(&self) -> String {
+		self.as_slice().human()
+	}
+
+	fn quiet(&self) -> String {
+		self.as_slice().quiet()
+	}
+
+	fn waybar(&self) -> String {
+		self.as_slice().waybar()
+	}
+}
+
+/// Joins one line per value
+fn lines<T>(values: &[T], line: impl Fn(&T) -> String) -> String {
+	values.iter().map(line).collect::<Vec<_>>().join("\n")
+}
+
+/// Returns the width of the widest value in a column, or of its header
+fn column_width<T>(values: &[T], header: &str, width: impl Fn(&T) -> usize) -> usize {
+	values
+		.iter()
+		.map(width)
+		.max()
+		.unwrap_or(0)
+		.max(header.len())
+}
+
+/// Gets the title of a card's item, or a placeholder if it's missing
+fn item_title(item: &Option<Item>) -> String {
+	item.as_ref()
+		.map(|i| i.get_title())
+		.unwrap_or_else(|| "???".to_string())
+}
+
+impl Render for [ItemType] {
+	fn human(&self) -> String {
+		if self.is_empty() {
+			return "No item types found.".to_string();
+		}
+		let max_id = column_width(self, "ID", |t| t.get_id().0.len());
+		let mut out = vec![format!("{:<width$}  NAME", "ID", width = max_id)];
+		out.extend(
+			self.iter()
+				.map(|it| format!("{:<width$}  {}", it.get_id(), it.get_name(), width = max_id)),
+		);
+		out.join("\n")
+	}
+
+	fn quiet(&self) -> String {
+		lines(self, |it| it.get_id().to_string())
+	}
+}
+
+impl Render for ItemType {
+	fn human(&self) -> String {
+		[
+			format!("ID:      {}", self.get_id()),
+			format!("Name:    {}", self.get_name()),
+			format!("Created: {}", self.get_created_at()),
+		]
+		.join("\n")
+	}
+
+	fn quiet(&self) -> String {
+		self.get_id().to_string()
+	}
+}
+
+impl Render for [Item] {
+	fn human(&self) -> String {
+		if self.is_empty() {
+			return "No items found.".to_string();
+		}
+		let max_id = column_width(self, "ID", |i| i.get_id().0.len());
+		let max_title = column_width(self, "TITLE", |i| i.get_title().len());
+		let mut out = vec![format!(
+			"{:<id_w$}  {:<title_w$}",
+			"ID",
+			"TITLE",
+			id_w = max_id,
+			title_w = max_title,
+		)];
+		out.extend(self.iter().map(|item| {
+			format!(
 				"{:<id_w$}  {:<title_w$}",
-				"ID",
-				"TITLE",
+				item.get_id(),
+				item.get_title(),
 				id_w = max_id,
 				title_w = max_title,
-			);
-			for item in items {
-				println!(
-					"{:<id_w$}  {:<title_w$}",
-					item.get_id(),
-					item.get_title(),
-					id_w = max_id,
-					title_w = max_title,
-				);
-			}
-		}
-		OutputFormat::Json => {
-			println!("{}", serde_json::to_string_pretty(items).unwrap());
-		}
-		OutputFormat::Waybar => {
-			println!("{}", serde_json::to_string(items).unwrap());
-		}
+			)
+		}));
+		out.join("\n")
+	}
+
+	fn quiet(&self) -> String {
+		lines(self, |item| item.get_id().to_string())
 	}
 }
 
-/// Prints a single item in the specified format
-pub fn print_item(item: &Item, config: &OutputConfig) {
-	match config.format {
-		OutputFormat::Human => {
-			if config.quiet {
-				println!("{}", item.get_id());
-				return;
-			}
-			println!("ID:        {}", item.get_id());
-			println!("Type:      {}", item.get_item_type());
-			println!("Title:     {}", item.get_title());
-			println!("Data:      {}", item.get_data().0);
-			println!("Created:   {}", item.get_created_at());
-			println!("Updated:   {}", item.get_updated_at());
-		}
-		OutputFormat::Json => {
-			println!("{}", serde_json::to_string_pretty(item).unwrap());
-		}
-		OutputFormat::Waybar => {
-			println!("{}", serde_json::to_string(item).unwrap());
-		}
+impl Render for Item {
+	fn human(&self) -> String {
+		[
+			format!("ID:        {}", self.get_id()),
+			format!("Type:      {}", self.get_item_type()),
+			format!("Title:     {}", self.get_title()),
+			format!("Data:      {}", self.get_data().0),
+			format!("Created:   {}", self.get_created_at()),
+			format!("Updated:   {}", self.get_updated_at()),
+		]
+		.join("\n")
+	}
+
+	fn quiet(&self) -> String {
+		self.get_id().to_string()
 	}
 }
 
-/// Prints a list of cards in the specified format
-pub fn print_cards(cards: &[Card], config: &OutputConfig) {
-	match config.format {
-		OutputFormat::Human => {
-			if cards.is_empty() {
-				if !config.quiet {
-					println!("No cards found.");
-				}
-				return;
-			}
-			if config.quiet {
-				for card in cards {
-					println!("{}", card.get_id());
-				}
-				return;
-			}
-			let max_id = cards.iter().map(|c| c.get_id().0.len()).max().unwrap_or(2);
-			let max_item = cards
-				.iter()
-				.map(|c| c.get_item_id().0.len())
-				.max()
-				.unwrap_or(4);
-			println!(
-				"{:<id_w$}  {:<item_w$}  {:>8}  {:<16}  {:>8}  STATUS",
-				"ID",
-				"ITEM",
-				"PRIORITY",
-				"NEXT REVIEW",
-				"SORT",
+impl Render for [Card] {
+	fn human(&self) -> String {
+		if self.is_empty() {
+			return "No cards found.".to_string();
+		}
+		let max_id = column_width(self, "ID", |c| c.get_id().0.len());
+		let max_item = column_width(self, "ITEM", |c| c.get_item_id().0.len());
+		let mut out = vec![format!(
+			"{:<id_w$}  {:<item_w$}  {:>8}  {:<16}  {:>8}  STATUS",
+			"ID",
+			"ITEM",
+			"PRIORITY",
+			"NEXT REVIEW",
+			"SORT",
+			id_w = max_id,
+			item_w = max_item,
+		)];
+		out.extend(self.iter().map(|card| {
+			let status = match card.get_suspended() {
+				Some(dt) => format!("suspended {}", dt.format("%Y-%m-%d %H:%M")),
+				None => "active".to_string(),
+			};
+			let sort_pos = format!("{:.2}", card.get_sort_position());
+			format!(
+				"{:<id_w$}  {:<item_w$}  {:>8.2}  {:<16}  {:>8}  {}",
+				card.get_id(),
+				card.get_item_id(),
+				card.get_priority(),
+				card.get_next_review().format("%Y-%m-%d %H:%M"),
+				sort_pos,
+				status,
 				id_w = max_id,
 				item_w = max_item,
-			);
-			for card in cards {
-				let status = match card.get_suspended() {
-					Some(dt) => format!("suspended {}", dt.format("%Y-%m-%d %H:%M")),
-					None => "active".to_string(),
-				};
-				let sort_pos = format!("{:.2}", card.get_sort_position());
-				println!(
-					"{:<id_w$}  {:<item_w$}  {:>8.2}  {:<16}  {:>8}  {}",
-					card.get_id(),
-					card.get_item_id(),
-					card.get_priority(),
-					card.get_next_review().format("%Y-%m-%d %H:%M"),
-					sort_pos,
-					status,
-					id_w = max_id,
-					item_w = max_item,
-				);
-			}
-		}
-		OutputFormat::Json => {
-			println!("{}", serde_json::to_string_pretty(cards).unwrap());
-		}
-		OutputFormat::Waybar => {
-			println!("{}", serde_json::to_string(cards).unwrap());
-		}
+			)
+		}));
+		out.join("\n")
+	}
+
+	fn quiet(&self) -> String {
+		lines(self, |card| card.get_id().to_string())
 	}
 }
 
-/// Prints a single card in the specified format
-pub fn print_card(card: &Card, config: &OutputConfig) {
-	match config.format {
-		OutputFormat::Human => {
-			if config.quiet {
-				println!("{}", card.get_id());
-				return;
-			}
-			println!("ID:          {}", card.get_id());
-			println!("Item ID:     {}", card.get_item_id());
-			println!("Card Index:  {}", card.get_card_index());
-			println!("Priority:    {:.2}", card.get_priority());
-			println!("Next Review: {}", card.get_next_review());
-			match card.get_last_review() {
-				Some(dt) => println!("Last Review: {}", dt),
-				None => println!("Last Review: never"),
-			}
-			println!("Sort Pos:    {:.2}", card.get_sort_position());
-			match card.get_suspended() {
-				Some(dt) => println!("Suspended:   {}", dt),
-				None => println!("Suspended:   no"),
-			}
-		}
-		OutputFormat::Json => {
-			println!("{}", serde_json::to_string_pretty(card).unwrap());
-		}
-		OutputFormat::Waybar => {
-			println!("{}", serde_json::to_string(card).unwrap());
-		}
+impl Render for Card {
+	fn human(&self) -> String {
+		let last_review = match self.get_last_review() {
+			Some(dt) => dt.to_string(),
+			None => "never".to_string(),
+		};
+		let suspended = match self.get_suspended() {
+			Some(dt) => dt.to_string(),
+			None => "no".to_string(),
+		};
+		[
+			format!("ID:          {}", self.get_id()),
+			format!("Item ID:     {}", self.get_item_id()),
+			format!("Card Index:  {}", self.get_card_index()),
+			format!("Priority:    {:.2}", self.get_priority()),
+			format!("Next Review: {}", self.get_next_review()),
+			format!("Due In:      {}", self.due_in(Utc::now())),
+			format!("Last Review: {}", last_review),
+			format!("Sort Pos:    {:.2}", self.get_sort_position()),
+			format!("Suspended:   {}", suspended),
+		]
+		.join("\n")
+	}
+
+	fn quiet(&self) -> String {
+		self.get_id().to_string()
 	}
 }
 
-/// Prints a card from a raw JSON value (used when the server returns transformed card JSON)
-pub fn print_card_json(card: &serde_json::Value, config: &OutputConfig) {
-	match config.format {
-		OutputFormat::Human => {
-			if config.quiet {
-				if let Some(id) = card.get("id").and_then(|v| v.as_str()) {
-					println!("{}", id);
-				}
-				return;
-			}
-			println!("{}", serde_json::to_string_pretty(card).unwrap());
-		}
-		OutputFormat::Json => {
-			println!("{}", serde_json::to_string_pretty(card).unwrap());
-		}
-		OutputFormat::Waybar => {
-			println!("{}", serde_json::to_string(card).unwrap());
+/// A card as the server returned it, when its JSON has been transformed
+/// (e.g. with the priority offset hidden) and might not be a [`Card`]
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct CardJson<'a>(pub &'a serde_json::Value);
+
+impl Render for CardJson<'_> {
+	fn human(&self) -> String {
+		match serde_json::from_value::<Card>(self.0.clone()) {
+			Ok(card) => card.human(),
+			Err(_) => serde_json::to_string_pretty(self.0).unwrap(),
 		}
 	}
+
+	fn quiet(&self) -> String {
+		self.0
+			.get("id")
+			.and_then(|v| v.as_str())
+			.unwrap_or_default()
+			.to_string()
+	}
 }
 
-/// Prints a list of reviews in the specified format
-pub fn print_reviews(reviews: &[Review], config: &OutputConfig) {
-	match config.format {
-		OutputFormat::Human => {
-			if reviews.is_empty() {
-				if !config.quiet {
-					println!("No reviews found.");
-				}
-				return;
-			}
-			if config.quiet {
-				for review in reviews {
-					println!("{}", review.get_id());
-				}
-				return;
-			}
-			let max_id = reviews
-				.iter()
-				.map(|r| r.get_id().0.len())
-				.max()
-				.unwrap_or(2);
-			let max_card = reviews
-				.iter()
-				.map(|r| r.get_card_id().0.len())
-				.max()
-				.unwrap_or(4);
-			println!(
-				"{:<id_w$}  {:<card_w$}  {:>6}  TIMESTAMP",
-				"ID",
-				"CARD",
-				"RATING",
+impl Render for [Review] {
+	fn human(&self) -> String {
+		if self.is_empty() {
+			return "No reviews found.".to_string();
+		}
+		let max_id = column_width(self, "ID", |r| r.get_id().0.len());
+		let max_card = column_width(self, "CARD", |r| r.get_card_id().0.len());
+		let mut out = vec![format!(
+			"{:<id_w$}  {:<card_w$}  {:>6}  TIMESTAMP",
+			"ID",
+			"CARD",
+			"RATING",
+			id_w = max_id,
+			card_w = max_card,
+		)];
+		out.extend(self.iter().map(|review| {
+			format!(
+				"{:<id_w$}  {:<card_w$}  {:>6}  {}",
+				review.get_id(),
+				review.get_card_id(),
+				review.get_rating(),
+				review.get_review_timestamp().format("%Y-%m-%d %H:%M"),
 				id_w = max_id,
 				card_w = max_card,
-			);
-			for review in reviews {
-				println!(
-					"{:<id_w$}  {:<card_w$}  {:>6}  {}",
-					review.get_id(),
-					review.get_card_id(),
-					review.get_rating(),
-					review.get_review_timestamp().format("%Y-%m-%d %H:%M"),
-					id_w = max_id,
-					card_w = max_card,
-				);
-			}
-		}
-		OutputFormat::Json => {
-			println!("{}", serde_json::to_string_pretty(reviews).unwrap());
-		}
-		OutputFormat::Waybar => {
-			println!("{}", serde_json::to_string(reviews).unwrap());
-		}
+			)
+		}));
+		out.join("\n")
+	}
+
+	fn quiet(&self) -> String {
+		lines(self, |review| review.get_id().to_string())
 	}
 }
 
-/// Prints a single review in the specified format
-pub fn print_review(review: &Review, config: &OutputConfig) {
-	match config.format {
-		OutputFormat::Human => {
-			if config.quiet {
-				println!("{}", review.get_id());
-				return;
-			}
-			println!("ID:        {}", review.get_id());
-			println!("Card ID:   {}", review.get_card_id());
-			println!("Rating:    {}", review.get_rating());
-			println!("Timestamp: {}", review.get_review_timestamp());
-		}
-		OutputFormat::Json => {
-			println!("{}", serde_json::to_string_pretty(review).unwrap());
-		}
-		OutputFormat::Waybar => {
-			println!("{}", serde_json::to_string(review).unwrap());
-		}
+impl Render for Review {
+	fn human(&self) -> String {
+		[
+			format!("ID:        {}", self.get_id()),
+			format!("Card ID:   {}", self.get_card_id()),
+			format!("Rating:    {}", self.get_rating()),
+			format!("Timestamp: {}", self.get_review_timestamp()),
+		]
+		.join("\n")
+	}
+
+	fn quiet(&self) -> String {
+		self.get_id().to_string()
 	}
 }
 
-/// Prints a list of tags in the specified format
-pub fn print_tags(tags: &[Tag], config: &OutputConfig) {
-	match config.format {
-		OutputFormat::Human => {
-			if tags.is_empty() {
-				if !config.quiet {
-					println!("No tags found.");
-				}
-				return;
-			}
-			if config.quiet {
-				for tag in tags {
-					println!("{}", tag.get_id());
-				}
-				return;
-			}
-			let max_id = tags.iter().map(|t| t.get_id().0.len()).max().unwrap_or(2);
-			let max_name = tags.iter().map(|t| t.get_name().len()).max().unwrap_or(4);
-			println!(
-				"{:<id_w$}  {:<name_w$}  VISIBLE",
-				"ID",
-				"NAME",
+impl Render for [Tag] {
+	fn human(&self) -> String {
+		if self.is_empty() {
+			return "No tags found.".to_string();
+		}
+		let max_id = column_width(self, "ID", |t| t.get_id().0.len());
+		let max_name = column_width(self, "NAME", |t| t.get_name().len());
+		let mut out = vec![format!(
+			"{:<id_w$}  {:<name_w$}  VISIBLE",
+			"ID",
+			"NAME",
+			id_w = max_id,
+			name_w = max_name,
+		)];
+		out.extend(self.iter().map(|tag| {
+			let vis = if tag.get_visible() { "yes" } else { "no" };
+			format!(
+				"{:<id_w$}  {:<name_w$}  {}",
+				tag.get_id(),
+				tag.get_name(),
+				vis,
 				id_w = max_id,
 				name_w = max_name,
-			);
-			for tag in tags {
-				let vis = if tag.get_visible() { "yes" } else { "no" };
-				println!(
-					"{:<id_w$}  {:<name_w$}  {}",
-					tag.get_id(),
-					tag.get_name(),
-					vis,
-					id_w = max_id,
-					name_w = max_name,
-				);
-			}
-		}
-		OutputFormat::Json => {
-			println!("{}", serde_json::to_string_pretty(tags).unwrap());
-		}
-		OutputFormat::Waybar => {
-			println!("{}", serde_json::to_string(tags).unwrap());
-		}
+			)
+		}));
+		out.join("\n")
+	}
+
+	fn quiet(&self) -> String {
+		lines(self, |tag| tag.get_id().to_string())
 	}
 }
 
-/// Prints a single tag in the specified format
-pub fn print_tag(tag: &Tag, config: &OutputConfig) {
-	match config.format {
-		OutputFormat::Human => {
-			if config.quiet {
-				println!("{}", tag.get_id());
-				return;
-			}
-			println!("ID:      {}", tag.get_id());
-			println!("Name:    {}", tag.get_name());
-			println!("Visible: {}", tag.get_visible());
-			println!("Created: {}", tag.get_created_at());
-		}
-		OutputFormat::Json => {
-			println!("{}", serde_json::to_string_pretty(tag).unwrap());
-		}
-		OutputFormat::Waybar => {
-			println!("{}", serde_json::to_string(tag).unwrap());
-		}
+impl Render for Tag {
+	fn human(&self) -> String {
+		[
+			format!("ID:      {}", self.get_id()),
+			format!("Name:    {}", self.get_name()),
+			format!("Visible: {}", self.get_visible()),
+			format!("Created: {}", self.get_created_at()),
+		]
+		.join("\n")
+	}
+
+	fn quiet(&self) -> String {
+		self.get_id().to_string()
 	}
 }
 
-/// Prints next review possibilities in the specified format
-pub fn print_next_reviews(
-	next_reviews: &[(DateTime<Utc>, serde_json::Value)],
-	config: &OutputConfig,
-) {
-	match config.format {
-		OutputFormat::Human => {
-			if next_reviews.is_empty() {
-				if !config.quiet {
-					println!("No next reviews available.");
-				}
-				return;
-			}
-			for (i, (dt, data)) in next_reviews.iter().enumerate() {
-				println!(
+/// The next review times a card would get for each rating, worst first
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct NextReviews<'a>(pub &'a [(DateTime<Utc>, serde_json::Value)]);
+
+impl Render for NextReviews<'_> {
+	fn human(&self) -> String {
+		if self.0.is_empty() {
+			return "No next reviews available.".to_string();
+		}
+		lines(
+			&self.0.iter().enumerate().collect::<Vec<_>>(),
+			|(i, (dt, data))| {
+				format!(
 					"Rating {}: {} ({})",
 					i + 1,
 					dt.format("%Y-%m-%d %H:%M"),
 					data
-				);
-			}
-		}
-		OutputFormat::Json => {
-			println!("{}", serde_json::to_string_pretty(next_reviews).unwrap());
-		}
-		OutputFormat::Waybar => {
-			println!("{}", serde_json::to_string(next_reviews).unwrap());
-		}
+				)
+			},
+		)
+	}
+
+	fn quiet(&self) -> String {
+		lines(self.0, |(dt, _)| dt.to_rfc3339())
 	}
 }
 
-/// Prints a simple success message (for operations that don't return data)
-pub fn print_success(message: &str, config: &OutputConfig) {
-	match config.format {
-		OutputFormat::Human => {
-			if !config.quiet {
-				println!("{}", message);
-			}
-		}
-		OutputFormat::Json => {
-			println!(
-				"{}",
-				serde_json::to_string_pretty(
-					&serde_json::json!({"status": "ok", "message": message})
-				)
-				.unwrap()
-			);
-		}
-		OutputFormat::Waybar => {
-			println!(
-				"{}",
-				serde_json::to_string(&serde_json::json!({"status": "ok", "message": message}))
-					.unwrap()
-			);
+/// The result of an operation that doesn't return data
+#[derive(Serialize)]
+pub struct Success<'a> {
+	status: &'static str,
+	message: &'a str,
+}
+
+impl<'a> Success<'a> {
+	/// Creates a success result with a message describing what was done
+	pub fn new(message: &'a str) -> Self {
+		Self {
+			status: "ok",
+			message,
 		}
 	}
 }
 
-/// Prints cards with their associated item titles for todo commands
-pub fn print_todo_cards(cards_with_items: &[(Card, Option<Item>)], config: &OutputConfig) {
-	match config.format {
-		OutputFormat::Human => {
-			if cards_with_items.is_empty() {
-				if !config.quiet {
-					println!("No todos found.");
-				}
-				return;
-			}
-			if config.quiet {
-				for (card, _) in cards_with_items {
-					println!("{}", card.get_id());
-				}
-				return;
-			}
-			let titles: Vec<String> = cards_with_items
-				.iter()
-				.map(|(_, item)| {
-					item.as_ref()
-						.map(|i| i.get_title())
-						.unwrap_or_else(|| "???".to_string())
-				})
-				.collect();
-			let max_id = cards_with_items
-				.iter()
-				.map(|(c, _)| c.get_id().0.len())
-				.max()
-				.unwrap_or(2);
-			let max_title = titles.iter().map(|t| t.len()).max().unwrap_or(5);
-			println!(
-				"{:<id_w$}  {:<title_w$}  {:<16}  {:>8}  SORT",
-				"ID",
-				"TITLE",
-				"NEXT REVIEW",
-				"PRIORITY",
+impl Render for Success<'_> {
+	fn human(&self) -> String {
+		self.message.to_string()
+	}
+
+	/// There's no ID or count to print, so quiet mode prints nothing
+	fn quiet(&self) -> String {
+		String::new()
+	}
+}
+
+/// A card alongside its item, which is None if the item wasn't found
+#[derive(Serialize)]
+struct CardWithItem<'a> {
+	card: &'a Card,
+	item: Option<&'a Item>,
+}
+
+/// Serializes cards with their items as `[{"card": ..., "item": ...}]`
+fn serialize_cards_with_items<S: Serializer>(
+	cards_with_items: &[(Card, Option<Item>)],
+	serializer: S,
+) -> Result<S::Ok, S::Error> {
+	serializer.collect_seq(cards_with_items.iter().map(|(card, item)| CardWithItem {
+		card,
+		item: item.as_ref(),
+	}))
+}
+
+/// Cards with their associated item titles, for todo commands
+pub struct TodoCards<'a>(pub &'a [(Card, Option<Item>)]);
+
+impl Serialize for TodoCards<'_> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serialize_cards_with_items(self.0, serializer)
+	}
+}
+
+impl Render for TodoCards<'_> {
+	fn human(&self) -> String {
+		if self.0.is_empty() {
+			return "No todos found.".to_string();
+		}
+		let titles: Vec<String> = self.0.iter().map(|(_, item)| item_title(item)).collect();
+		let max_id = column_width(self.0, "ID", |(c, _)| c.get_id().0.len());
+		let max_title = column_width(&titles, "TITLE", |t| t.len());
+		let mut out = vec![format!(
+			"{:<id_w$}  {:<title_w$}  {:<16}  {:>8}  SORT",
+			"ID",
+			"TITLE",
+			"NEXT REVIEW",
+			"PRIORITY",
+			id_w = max_id,
+			title_w = max_title,
+		)];
+		out.extend(self.0.iter().zip(titles.iter()).map(|((card, _), title)| {
+			let sort_pos = format!("{:.2}", card.get_sort_position());
+			format!(
+				"{:<id_w$}  {:<title_w$}  {:<16}  {:>8.2}  {}",
+				card.get_id(),
+				title,
+				card.get_next_review().format("%Y-%m-%d %H:%M"),
+				card.get_priority(),
+				sort_pos,
 				id_w = max_id,
 				title_w = max_title,
-			);
-			for ((card, _), title) in cards_with_items.iter().zip(titles.iter()) {
-				let sort_pos = format!("{:.2}", card.get_sort_position());
-				println!(
-					"{:<id_w$}  {:<title_w$}  {:<16}  {:>8.2}  {}",
-					card.get_id(),
-					title,
-					card.get_next_review().format("%Y-%m-%d %H:%M"),
-					card.get_priority(),
-					sort_pos,
-					id_w = max_id,
-					title_w = max_title,
-				);
-			}
-		}
-		OutputFormat::Json => {
-			let data: Vec<serde_json::Value> = cards_with_items
-				.iter()
-				.map(|(card, item)| {
-					serde_json::json!({
-						"card": card,
-						"item": item,
-					})
-				})
-				.collect();
-			println!("{}", serde_json::to_string_pretty(&data).unwrap());
-		}
-		OutputFormat::Waybar => {
-			print_waybar_todo_summary(cards_with_items);
-		}
+			)
+		}));
+		out.join("\n")
 	}
-}
 
-/// Prints waybar-compatible JSON for todo summaries
-///
-/// Output: `{"text": "3", "tooltip": "3 todos due\n- Title 1\n- Title 2", "class": "has-items"}`
-/// Empty: `{"text": "", "tooltip": "No todos due", "class": "empty"}`
-fn print_waybar_todo_summary(cards_with_items: &[(Card, Option<Item>)]) {
-	let count = cards_with_items.len();
-	let (text, tooltip, class) = if count > 0 {
-		let titles: Vec<String> = cards_with_items
-			.iter()
-			.map(|(_, item)| {
-				item.as_ref()
-					.map(|i| i.get_title())
-					.unwrap_or_else(|| "???".to_string())
-			})
-			.collect();
-		let tooltip_lines: Vec<String> = titles.iter().map(|t| format!("- {}", t)).collect();
-		let tooltip = format!("{} todos due\n{}", count, tooltip_lines.join("\n"));
-		(count.to_string(), tooltip, "has-items")
-	} else {
-		(String::new(), "No todos due".to_string(), "empty")
-	};
-	println!(
-		"{}",
+	fn quiet(&self) -> String {
+		lines(self.0, |(card, _)| card.get_id().to_string())
+	}
+
+	/// Waybar gets a summary of the todos due
+	///
+	/// Output: `{"text": "3", "tooltip": "3 todos due\n- Title 1\n- Title 2", "class": "has-items"}`
+	/// Empty: `{"text": "", "tooltip": "No todos due", "class": "empty"}`
+	fn waybar(&self) -> String {
+		let count = self.0.len();
+		let (text, tooltip, class) = if count > 0 {
+			let tooltip_lines = lines(self.0, |(_, item)| format!("- {}", item_title(item)));
+			let tooltip = format!("{} todos due\n{}", count, tooltip_lines);
+			(count.to_string(), tooltip, "has-items")
+		} else {
+			(String::new(), "No todos due".to_string(), "empty")
+		};
 		serde_json::to_string(&serde_json::json!({
 			"text": text,
 			"tooltip": tooltip,
 			"class": class,
 		}))
 		.unwrap()
+	}
+}
+
+/// Where the config file lives, and whether it exists yet
+#[derive(Serialize)]
+pub struct ConfigPath<'a> {
+	path: &'a Path,
+	exists: bool,
+}
+
+impl<'a> ConfigPath<'a> {
+	/// Checks whether the config file at `path` exists
+	pub fn new(path: &'a Path) -> Self {
+		Self {
+			path,
+			exists: path.exists(),
+		}
+	}
+}
+
+impl Render for ConfigPath<'_> {
+	fn human(&self) -> String {
+		self.path.display().to_string()
+	}
+
+	fn quiet(&self) -> String {
+		self.human()
+	}
+}
+
+/// The cards due for review now, with their items' titles
+///
+/// JSON output is `{"count": ..., "cards": [{"card": ..., "item": ...}]}`.
+pub struct DueCards<'a>(pub &'a [(Card, Option<Item>)]);
+
+impl Serialize for DueCards<'_> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut state = serializer.serialize_struct("DueCards", 2)?;
+		state.serialize_field("count", &self.0.len())?;
+		state.serialize_field("cards", &TodoCards(self.0))?;
+		state.end()
+	}
+}
+
+impl Render for DueCards<'_> {
+	fn human(&self) -> String {
+		let count = self.0.len();
+		let mut out = vec![format!(
+			"{} card{} due",
+			count,
+			if count == 1 { "" } else { "s" }
+		)];
+		if count == 0 {
+			return out.join("\n");
+		}
+		let titles: Vec<String> = self.0.iter().map(|(_, item)| item_title(item)).collect();
+		let max_id = column_width(self.0, "ID", |(c, _)| c.get_id().0.len());
+		let max_title = column_width(&titles, "TITLE", |t| t.len());
+		out.push(String::new());
+		out.push(format!(
+			"{:<id_w$}  {:<title_w$}  {:>8}  NEXT REVIEW",
+			"ID",
+			"TITLE",
+			"PRIORITY",
+			id_w = max_id,
+			title_w = max_title,
+		));
+		out.extend(self.0.iter().zip(titles.iter()).map(|((card, _), title)| {
+			format!(
+				"{:<id_w$}  {:<title_w$}  {:>8.2}  {}",
+				card.get_id(),
+				title,
+				card.get_priority(),
+				card.get_next_review().format("%Y-%m-%d %H:%M"),
+				id_w = max_id,
+				title_w = max_title,
+			)
+		}));
+		out.join("\n")
+	}
+
+	fn quiet(&self) -> String {
+		self.0.len().to_string()
+	}
+}
+
+/// Asserts a value renders as valid JSON in the JSON and Waybar formats, and
+/// as non-empty text for a person
+#[cfg(test)]
+pub fn assert_renders_in_every_format<T: Render + ?Sized>(data: &T) {
+	for format in [OutputFormat::Json, OutputFormat::Waybar] {
+		for quiet in [false, true] {
+			let output = render(data, &OutputConfig { format, quiet });
+			if let Err(e) = serde_json::from_str::<serde_json::Value>(&output) {
+				panic!("{:?} output isn't valid JSON ({}):\n{}", format, e, output);
+			}
+		}
+	}
+
+	let human = render(
+		data,
+		&OutputConfig {
+			format: OutputFormat::Human,
+			quiet: false,
+		},
 	);
+	assert!(!human.trim().is_empty(), "human output is empty");
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hippocampus::models::{ItemTypeId, JsonValue};
+
+	fn config(format: OutputFormat, quiet: bool) -> OutputConfig {
+		OutputConfig { format, quiet }
+	}
+
+	#[test]
+	fn test_quiet_lists_print_one_id_per_line() {
+		let tags = vec![
+			Tag::new("one".to_string(), true),
+			Tag::new("two".to_string(), false),
+		];
+
+		let output = render(&tags, &config(OutputFormat::Human, true));
+
+		let expected = format!("{}\n{}", tags[0].get_id(), tags[1].get_id());
+		assert_eq!(output, expected);
+		assert_eq!(
+			render(&Vec::<Tag>::new(), &config(OutputFormat::Human, true)),
+			""
+		);
+	}
+
+	#[test]
+	fn test_json_output_ignores_quiet() {
+		let tag = Tag::new("one".to_string(), true);
+		assert_eq!(
+			render(&tag, &config(OutputFormat::Json, true)),
+			render(&tag, &config(OutputFormat::Json, false))
+		);
+	}
+
+	#[test]
+	fn test_due_cards_render_count() {
+		let item = Item::new(
+			ItemTypeId::new(),
+			"Due Item".to_string(),
+			JsonValue(serde_json::json!({})),
+		);
+		let card = Card::new(item.get_id(), 0, Utc::now(), 0.5);
+		let due = [(card, Some(item))];
+
+		assert_eq!(
+			render(&DueCards(&due), &config(OutputFormat::Human, true)),
+			"1"
+		);
+		let json: serde_json::Value =
+			serde_json::from_str(&render(&DueCards(&due), &config(OutputFormat::Json, false)))
+				.unwrap();
+		assert_eq!(json["count"], 1);
+		assert_eq!(json["cards"][0]["item"]["title"], "Due Item");
+	}
+
+	#[test]
+	fn test_card_json_renders_cards_for_people() {
+		let card = Card::new(hippocampus::models::ItemId::new(), 0, Utc::now(), 0.5);
+		let value = serde_json::to_value(&card).unwrap();
+
+		let human = render(&CardJson(&value), &config(OutputFormat::Human, false));
+		assert!(human.starts_with("ID:"), "got: {}", human);
+		assert_eq!(
+			render(&CardJson(&value), &config(OutputFormat::Human, true)),
+			card.get_id().to_string()
+		);
+	}
 }
@@ -8,9 +8,8 @@ use clap::Parser;
 /// The application provides a RESTful API for managing items and reviews
 /// in a spaced repetition system, which helps users memorize information
 /// more effectively by scheduling reviews at optimal intervals.
-use diesel::Connection;
 use hippocampus::{config::CliArgs, *};
-use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+use std::{path::PathBuf, sync::Arc};
 use tracing::{error, info};
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_subscriber::{self, Registry, filter::LevelFilter, fmt, prelude::*};
@@ -23,10 +22,15 @@ use tracing_subscriber::{self, Registry, filter::LevelFilter, fmt, prelude::*};
 /// 3. Sets up the database connection pool
 /// 4. Creates the web application
 /// 5. Starts the web server
+/// 6. On SIGINT/SIGTERM, drains in-flight requests and takes a final backup
 #[tokio::main]
 async fn main() {
 	let args = CliArgs::parse();
 	let debug = args.debug;
+	let watch_config = args.watch_config;
+	let no_migrate = args.no_migrate;
+	// Kept so reloads of the config file apply the same overrides
+	let arg_overrides = config::config_from_args(args.clone());
 
 	// Load configuration from all sources (validates path overrides in debug builds)
 	let config = config::get_config(args).unwrap_or_else(|e| {
@@ -36,7 +40,9 @@ async fn main() {
 
 	// Initialize logging for better debugging and monitoring
 	println!("Initializing logging");
-	let _guard = init_tracing(debug, config.state_dir.clone());
+	let log_rotation = config.log_rotation().expect("log_rotation was validated");
+	let (tracing_guard, log_dir) =
+		init_tracing(debug, config.state_dir.clone(), log_rotation.clone());
 
 	info!("Starting Hippocampus SRS Server");
 
@@ -60,13 +66,12 @@ async fn main() {
 		}
 	}
 
-	// Start periodic backup task
-	info!("Starting periodic backup task");
-	start_periodic_backup(
-		config.database_url.clone(),
-		config.backup_interval(),
-		config.backup_count,
-	);
+	// Keep the logs directory from growing forever. Without rotation there
+	// is only ever the one log file, so nothing to clean up.
+	if log_rotation != Rotation::NEVER {
+		info!("Starting periodic log cleanup task");
+		start_periodic_log_cleanup(log_dir, config.log_retention);
+	}
 
 	// Create the database file if it doesn't exist (skip for in-memory databases)
 	if config.database_url != ":memory:" && !std::path::Path::new(&config.database_url).exists() {
@@ -82,18 +87,41 @@ async fn main() {
 		std::fs::File::create(&config.database_url).expect("Failed to create database file");
 	}
 
-	// Run embedded migrations
-	info!("Running database migrations");
-	{
-		let mut conn = diesel::SqliteConnection::establish(&config.database_url)
-			.expect("Failed to connect to database for migrations");
-		run_migrations(&mut conn);
+	// Bring the schema up to date, unless it's managed externally
+	if no_migrate {
+		info!("Not running database migrations (--no-migrate)");
+	} else {
+		info!("Running database migrations");
+		match migrate_database(&config.database_url) {
+			Ok(applied) => info!("Applied {} database migrations", applied),
+			Err(e) => {
+				error!("{}", e);
+				panic!("{}", e);
+			}
+		}
 	}
 
 	// Initialize the database connection pool
 	// This pool will be shared across all request handlers
 	info!("Initializing database connection pool");
-	let pool = Arc::new(db::init_pool(&config.database_url));
+	let pool_options = db::PoolOptions::from(&config);
+	info!(
+		"Pool size {}, connection timeout {:?}",
+		pool_options.max_size, pool_options.connection_timeout
+	);
+	let pool = Arc::new(db::init_pool_with_options(
+		&config.database_url,
+		pool_options,
+	));
+
+	// Start periodic backup task, now the backup log's table exists
+	info!("Starting periodic backup task");
+	start_periodic_backup(
+		pool.clone(),
+		config.database_url.clone(),
+		config.backup_interval(),
+		config.backup_count,
+	);
 
 	// Migrate scheduler data from SM-2 to FSRS if needed
 	info!("Checking for scheduler data migration");
@@ -101,16 +129,57 @@ async fn main() {
 		.await
 		.expect("Failed to migrate scheduler data");
 
-	// Build our application with routes
-	// This sets up all the API endpoints
-	let app = create_app(pool);
-
-	// Define the address to listen on (localhost:3000), or (localhost:3001) if we are running in debug mode
-	let addr = if cfg!(debug_assertions) {
-		SocketAddr::from(([127, 0, 0, 1], 3001))
+	// Optionally keep item types in memory, now nothing else will change them
+	repo::set_item_type_cache_enabled(config.item_type_cache);
+
+	// Make the settings that can be reloaded while running visible to the
+	// repo: the default priority to card creation, the timezone to
+	// everything that counts days, the interval fuzz, rating scale and
+	// minimum interval to review scheduling, and whether priority offsets
+	// are enabled to the daily shuffle
+	info!(
+		"Default card priority {}, timezone {}, review interval fuzz {}, {}-button rating scale, minimum interval {}, priority offsets {}",
+		config.default_priority,
+		config.timezone().unwrap_or(config::DEFAULT_TIMEZONE),
+		config.interval_fuzz,
+		config.rating_scale,
+		config.minimum_interval.as_deref().unwrap_or("none"),
+		if config.priority_offsets_enabled {
+			"enabled"
+		} else {
+			"disabled"
+		}
+	);
+	config_watcher::store_reloadable_settings(&pool, &config)
+		.await
+		.expect("Failed to store reloadable settings");
+
+	// Optionally keep those settings in step with the config file. The
+	// watcher stops when dropped, so it is held until the server stops.
+	let _config_watcher = if watch_config {
+		match config_watcher::watch_config_file(pool.clone(), arg_overrides, config.clone()) {
+			Ok(watcher) => Some(watcher),
+			Err(e) => {
+				error!("Not watching the config file: {}", e);
+				None
+			}
+		}
 	} else {
-		SocketAddr::from(([127, 0, 0, 1], 3000))
+		None
 	};
+
+	// Build our application with routes
+	// This sets up all the API endpoints
+	let app_options = AppOptions::from(&config);
+	info!("Allowed CORS origins: {:?}", app_options.allowed_origins);
+	info!("Logging requests at level {}", app_options.request_log_level);
+	let app = create_app_with_options(pool, app_options);
+
+	// Resolve the address to listen on (validated when the config was loaded)
+	let addr = config.listen_addr().unwrap_or_else(|e| {
+		error!("{}", e);
+		std::process::exit(1);
+	});
 	info!("Listening on {}", addr);
 
 	// Start the server and wait for connections
@@ -120,15 +189,35 @@ async fn main() {
 	match tokio::net::TcpListener::bind(addr).await {
 		Ok(listener) => {
 			// Start serving requests
-			// This will run until the program is terminated
-			if let Err(e) = axum::serve(listener, app).await {
-				error!("Server error: {}", e);
+			// This will run until we receive SIGINT or SIGTERM, and then
+			// return once all in-flight requests have completed
+			match serve_until_shutdown(listener, app, os_shutdown_signal()).await {
+				Ok(signal) => info!("Server stopped after {:?}", signal),
+				Err(e) => error!("Server error: {}", e),
+			}
+
+			// Take the final backup only after draining, so it includes
+			// every write the server acknowledged
+			info!("Taking final database backup");
+			match backup_database(
+				&config.database_url,
+				BackupType::Startup,
+				config.backup_count,
+			) {
+				Ok(_) => info!("Final database backup completed successfully"),
+				Err(e) => error!("Final database backup failed: {}", e),
 			}
 		}
 		Err(e) => {
 			error!("Failed to bind to address {}: {}", addr, e);
 		}
 	}
+
+	info!("Hippocampus SRS Server shut down");
+
+	// Dropped explicitly so it's clear the log writer must outlive
+	// everything above, including the shutdown logs
+	drop(tracing_guard);
 }
 
 /// Initialize tracing with both console and file outputs
@@ -143,7 +232,14 @@ async fn main() {
 ///
 /// A special debug layer can be enabled by setting the HIPPOCAMPUS_DEBUG
 /// environment variable, which will output DEBUG-level logs to the console.
-fn init_tracing(debug: bool, state_dir: Option<PathBuf>) -> impl Drop {
+///
+/// The log file is rotated every `rotation` period. Returns the log writer's
+/// guard, which must be kept alive, and the directory the logs are written to.
+fn init_tracing(
+	debug: bool,
+	state_dir: Option<PathBuf>,
+	rotation: Rotation,
+) -> (impl Drop, PathBuf) {
 	let log_dir_path = state_dir
 		.map(|path| path.join("logs"))
 		.unwrap_or_else(|| PathBuf::from("logs"));
@@ -154,7 +250,7 @@ fn init_tracing(debug: bool, state_dir: Option<PathBuf>) -> impl Drop {
 	}
 
 	// Setup a file appender for all log levels
-	let file_appender = RollingFileAppender::new(Rotation::DAILY, log_dir_path, "hippocampus.log");
+	let file_appender = RollingFileAppender::new(rotation, log_dir_path.clone(), LOG_FILE_NAME);
 
 	// Non-blocking writer for better performance
 	let (file_writer, guard) = tracing_appender::non_blocking(file_appender);
@@ -190,7 +286,7 @@ fn init_tracing(debug: bool, state_dir: Option<PathBuf>) -> impl Drop {
 	println!("Tracing subscriber initialized");
 
 	// Return the guard so it stays alive for the program's duration
-	guard
+	(guard, log_dir_path)
 }
 
 #[cfg(test)]
@@ -1,18 +1,69 @@
+use crate::time_utils::parse_step_duration;
+use chrono_tz::Tz;
 use clap::Parser;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use toml;
-use tracing::{info, warn};
+use tracing::{Level, info, warn};
+use tracing_appender::rolling::Rotation;
 
 /// Default database filename
 pub const DEFAULT_DATABASE_FILENAME: &str = "srs_server.db";
+/// Name of the config file inside the config directory
+pub const CONFIG_FILE_NAME: &str = "config.toml";
 /// Default backup interval in minutes
 pub const DEFAULT_BACKUP_INTERVAL_MINUTES: u64 = 20;
 /// Default number of backups to keep
 pub const DEFAULT_BACKUP_COUNT: u32 = 10;
+/// Default maximum number of pooled database connections
+///
+/// Kept small because SQLite serializes writers: extra connections mostly
+/// just wait on each other, but a few let reads proceed alongside a write.
+pub const DEFAULT_POOL_SIZE: u32 = 4;
+/// Default time in seconds to wait for a pooled connection before failing
+pub const DEFAULT_CONNECTION_TIMEOUT_SECS: u64 = 5;
+/// Default host the server listens on
+pub const DEFAULT_HOST: &str = "127.0.0.1";
+/// Default port the server listens on (3001 in debug builds, so a
+/// development server can run alongside an installed one)
+pub const DEFAULT_PORT: u16 = if cfg!(debug_assertions) { 3001 } else { 3000 };
+/// Default level for per-request log spans
+pub const DEFAULT_REQUEST_LOG_LEVEL: Level = Level::INFO;
+/// Default priority given to newly created cards
+pub const DEFAULT_PRIORITY: f32 = 0.5;
+/// Default timezone that decides when one day ends and the next begins
+pub const DEFAULT_TIMEZONE: Tz = Tz::UTC;
+/// Default fraction review intervals are randomly stretched or shrunk by
+/// (none, so intervals are exactly what the scheduler computes)
+pub const DEFAULT_INTERVAL_FUZZ: f64 = 0.0;
+/// Default amount a card's priority rises by when it's rated Again (none)
+pub const DEFAULT_FAIL_PRIORITY_BOOST: f32 = 0.0;
+/// Default amount a card's priority falls by when it's rated Easy (none)
+pub const DEFAULT_EASY_PRIORITY_DECAY: f32 = 0.0;
+/// Default tag applied to leeches when `leech_action` is `tag`
+pub const DEFAULT_LEECH_TAG: &str = "leech";
+/// Default number of buttons reviews are rated with (Again, Hard, Good, Easy)
+pub const DEFAULT_RATING_SCALE: u8 = 4;
+/// Default period after which the log file is rotated
+pub const DEFAULT_LOG_ROTATION: Rotation = Rotation::DAILY;
+/// Default number of rotated log files to keep (two weeks of daily logs)
+pub const DEFAULT_LOG_RETENTION: u32 = 14;
+/// Default size in bytes a response must reach before it's compressed
+pub const DEFAULT_COMPRESSION_MIN_SIZE: u16 = 1024;
+/// Default seconds between checks for newly due cards on `GET /events/due`
+pub const DEFAULT_DUE_EVENTS_INTERVAL_SECS: u64 = 30;
+/// Default largest request body accepted, in bytes (16 MiB)
+pub const DEFAULT_MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+/// Default largest request body accepted by `POST /reviews/import`, in bytes
+/// (256 MiB)
+pub const DEFAULT_MAX_IMPORT_BODY_SIZE: usize = 256 * 1024 * 1024;
+/// The longest `minimum_interval` and `graduating_interval` can be, in days:
+/// 100 years
+pub const MAX_CONFIGURED_INTERVAL_DAYS: u32 = 36_500;
 
 /// Configuration for the Hippocampus application
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +74,87 @@ pub struct Config {
 	pub backup_interval_minutes: u64,
 	/// Number of periodic backups to keep
 	pub backup_count: u32,
+	/// Maximum number of pooled database connections
+	pub pool_size: u32,
+	/// Seconds to wait for a pooled connection before failing the request
+	pub connection_timeout_secs: u64,
+	/// Host (IP address or resolvable name) the server listens on
+	pub host: String,
+	/// Port the server listens on
+	pub port: u16,
+	/// Origins allowed to make cross-origin requests, as a comma-separated
+	/// list or `*`. `None` means same-origin only (no CORS headers are sent).
+	pub allowed_origins: Option<String>,
+	/// Level to log each HTTP request at (`error`, `warn`, `info`, `debug` or
+	/// `trace`). `None` means [`DEFAULT_REQUEST_LOG_LEVEL`].
+	pub request_log_level: Option<String>,
+	/// Priority given to newly created cards whose item type doesn't set its
+	/// own default, between 0 and 1
+	pub default_priority: f32,
+	/// IANA name of the timezone that decides when one day ends and the next
+	/// begins (e.g. `Australia/Melbourne`). `None` means [`DEFAULT_TIMEZONE`].
+	pub timezone: Option<String>,
+	/// Fraction review intervals are randomly stretched or shrunk by, so
+	/// cards reviewed together don't all come due on the same day, between
+	/// 0 (inclusive) and 1 (exclusive)
+	pub interval_fuzz: f64,
+	/// Number of buttons reviews are rated with: 4 (1-4, Again to Easy) or 2
+	/// (1 for fail, 2 for pass)
+	pub rating_scale: u8,
+	/// Shortest interval (e.g. `1d`) a graduated card is scheduled for, so
+	/// review cards don't come back the same day. `None` means no floor.
+	pub minimum_interval: Option<String>,
+	/// Waits (e.g. `1m`, `10m`) a new card goes through, one per passing
+	/// review, before it graduates to its review function's intervals. Empty
+	/// means new cards graduate straight away.
+	pub learning_steps: Vec<String>,
+	/// Waits (e.g. `10m`) a lapsed card goes through, one per passing review,
+	/// before it returns to its reduced interval. Empty means lapsed cards go
+	/// straight back to their reduced interval.
+	pub relearning_steps: Vec<String>,
+	/// Days until a new card is next due after its first passing review, or
+	/// after it passes its last learning step, in place of the interval its
+	/// review function would give it. `None` leaves that interval alone.
+	pub graduating_interval: Option<u32>,
+	/// Whether cards' priorities are shuffled by a small random offset each
+	/// day. When off, every offset is 0 and cards order by base priority.
+	pub priority_offsets_enabled: bool,
+	/// Amount, between 0 and 1, a card's priority rises by each time it's
+	/// rated Again, so cards that keep being failed come up sooner. Capped
+	/// at a priority of 1; 0 leaves priorities alone.
+	pub fail_priority_boost: f32,
+	/// Amount, between 0 and 1, a card's priority falls by each time it's
+	/// rated Easy. Stops at a priority of 0; 0 leaves priorities alone.
+	pub easy_priority_decay: f32,
+	/// What happens to a card when it becomes a leech (`none`, `suspend` or
+	/// `tag`). `None` means nothing does.
+	pub leech_action: Option<String>,
+	/// Tag applied to a leech's item when `leech_action` is `tag`. `None`
+	/// means [`DEFAULT_LEECH_TAG`].
+	pub leech_tag: Option<String>,
+	/// Whether to gzip responses for clients that accept it
+	pub compression: bool,
+	/// Size in bytes a response must reach before it's compressed
+	pub compression_min_size: u16,
+	/// Whether to count requests and reviews and serve them from
+	/// `GET /metrics` for Prometheus to scrape
+	pub metrics: bool,
+	/// Whether to serve the database maintenance routes under `/admin`
+	pub admin: bool,
+	/// Whether to keep item types in memory instead of reading them from
+	/// the database on every lookup
+	pub item_type_cache: bool,
+	/// How often to start a new log file (`hourly`, `daily` or `never`).
+	/// `None` means [`DEFAULT_LOG_ROTATION`].
+	pub log_rotation: Option<String>,
+	/// Number of rotated log files to keep; older ones are deleted
+	pub log_retention: u32,
+	/// Seconds between checks for newly due cards on `GET /events/due`
+	pub due_events_interval_secs: u64,
+	/// Largest request body accepted, in bytes; larger ones get a 413
+	pub max_body_size: usize,
+	/// Largest request body accepted by `POST /reviews/import`, in bytes
+	pub max_import_body_size: usize,
 	/// Resolved config directory path, if any
 	///
 	/// It would be an error to read `config_dir` from a config file,
@@ -59,9 +191,96 @@ pub struct ConfigBuilder {
 	/// Optional backup count
 	#[serde(default)]
 	pub backup_count: Option<u32>,
+	/// Optional maximum number of pooled database connections
+	#[serde(default)]
+	pub pool_size: Option<u32>,
+	/// Optional connection checkout timeout (in seconds)
+	#[serde(default)]
+	pub connection_timeout_secs: Option<u64>,
+	/// Optional host for the server to listen on
+	#[serde(default)]
+	pub host: Option<String>,
+	/// Optional port for the server to listen on
+	#[serde(default)]
+	pub port: Option<u16>,
 	/// Optional server URL for the CLI to connect to
 	#[serde(default)]
 	pub server_url: Option<String>,
+	/// Optional comma-separated list of allowed CORS origins (or `*`)
+	#[serde(default)]
+	pub allowed_origins: Option<String>,
+	/// Optional level to log each HTTP request at
+	#[serde(default)]
+	pub request_log_level: Option<String>,
+	/// Optional priority for newly created cards
+	#[serde(default)]
+	pub default_priority: Option<f32>,
+	/// Optional IANA timezone name
+	#[serde(default)]
+	pub timezone: Option<String>,
+	/// Optional fraction to fuzz review intervals by
+	#[serde(default)]
+	pub interval_fuzz: Option<f64>,
+	/// Optional number of buttons reviews are rated with
+	#[serde(default)]
+	pub rating_scale: Option<u8>,
+	/// Optional shortest interval for graduated cards
+	#[serde(default)]
+	pub minimum_interval: Option<String>,
+	/// Optional learning steps for new cards
+	#[serde(default)]
+	pub learning_steps: Option<Vec<String>>,
+	/// Optional relearning steps for lapsed cards
+	#[serde(default)]
+	pub relearning_steps: Option<Vec<String>>,
+	/// Optional first interval, in days, for graduating cards
+	#[serde(default)]
+	pub graduating_interval: Option<u32>,
+	/// Optionally shuffle priorities by a daily random offset
+	#[serde(default)]
+	pub priority_offsets_enabled: Option<bool>,
+	/// Optional priority boost for cards rated Again
+	#[serde(default)]
+	pub fail_priority_boost: Option<f32>,
+	/// Optional priority decay for cards rated Easy
+	#[serde(default)]
+	pub easy_priority_decay: Option<f32>,
+	/// Optional action taken on cards that become leeches
+	#[serde(default)]
+	pub leech_action: Option<String>,
+	/// Optional tag applied to leeches
+	#[serde(default)]
+	pub leech_tag: Option<String>,
+	/// Optionally gzip responses
+	#[serde(default)]
+	pub compression: Option<bool>,
+	/// Optional size (in bytes) above which responses are compressed
+	#[serde(default)]
+	pub compression_min_size: Option<u16>,
+	/// Optionally serve Prometheus metrics
+	#[serde(default)]
+	pub metrics: Option<bool>,
+	/// Optionally serve the admin routes
+	#[serde(default)]
+	pub admin: Option<bool>,
+	/// Optionally cache item types in memory
+	#[serde(default)]
+	pub item_type_cache: Option<bool>,
+	/// Optional period to rotate the log file after
+	#[serde(default)]
+	pub log_rotation: Option<String>,
+	/// Optional number of rotated log files to keep
+	#[serde(default)]
+	pub log_retention: Option<u32>,
+	/// Optional seconds between checks for newly due cards
+	#[serde(default)]
+	pub due_events_interval_secs: Option<u64>,
+	/// Optional largest request body accepted, in bytes
+	#[serde(default)]
+	pub max_body_size: Option<usize>,
+	/// Optional largest request body accepted by the review import, in bytes
+	#[serde(default)]
+	pub max_import_body_size: Option<usize>,
 	/// Optional config directory path
 	///
 	/// It would be an error to read `config_dir` from a config file,
@@ -83,7 +302,7 @@ pub struct ConfigBuilder {
 }
 
 /// Command line arguments for the application
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[clap(name = "hippocampus", about = "A Spaced Repetition System")]
 pub struct CliArgs {
 	/// Database URL
@@ -98,10 +317,136 @@ pub struct CliArgs {
 	#[clap(long, env = "BACKUP_COUNT")]
 	pub backup_count: Option<u32>,
 
+	/// Maximum number of pooled database connections
+	#[clap(long, env = "HIPPOCAMPUS_POOL_SIZE")]
+	pub pool_size: Option<u32>,
+
+	/// Seconds to wait for a pooled database connection before failing
+	#[clap(long, env = "HIPPOCAMPUS_CONNECTION_TIMEOUT_SECS")]
+	pub connection_timeout_secs: Option<u64>,
+
+	/// Host to listen on (IP address or resolvable name)
+	#[clap(long, env = "HIPPOCAMPUS_HOST")]
+	pub host: Option<String>,
+
+	/// Port to listen on
+	#[clap(long, env = "HIPPOCAMPUS_PORT")]
+	pub port: Option<u16>,
+
+	/// Origins allowed to make cross-origin requests (comma-separated, or `*`)
+	#[clap(long, env = "HIPPOCAMPUS_ALLOWED_ORIGINS")]
+	pub allowed_origins: Option<String>,
+
+	/// Level to log each HTTP request at (error, warn, info, debug or trace)
+	#[clap(long, env = "HIPPOCAMPUS_REQUEST_LOG_LEVEL")]
+	pub request_log_level: Option<String>,
+
+	/// Priority given to newly created cards, between 0 and 1
+	#[clap(long, env = "HIPPOCAMPUS_DEFAULT_PRIORITY")]
+	pub default_priority: Option<f32>,
+
+	/// IANA name of the timezone days are counted in (e.g. Australia/Melbourne)
+	#[clap(long, env = "HIPPOCAMPUS_TIMEZONE")]
+	pub timezone: Option<String>,
+
+	/// Fraction to randomly stretch or shrink review intervals by (e.g. 0.05)
+	#[clap(long, env = "HIPPOCAMPUS_INTERVAL_FUZZ")]
+	pub interval_fuzz: Option<f64>,
+
+	/// Number of buttons reviews are rated with: 4 (Again to Easy) or 2 (fail/pass)
+	#[clap(long, env = "HIPPOCAMPUS_RATING_SCALE")]
+	pub rating_scale: Option<u8>,
+
+	/// Shortest interval graduated cards are scheduled for (e.g. 1d)
+	#[clap(long, env = "HIPPOCAMPUS_MINIMUM_INTERVAL")]
+	pub minimum_interval: Option<String>,
+
+	/// Learning steps new cards go through before graduating (e.g. 1m,10m)
+	#[clap(long, env = "HIPPOCAMPUS_LEARNING_STEPS", value_delimiter = ',')]
+	pub learning_steps: Option<Vec<String>>,
+
+	/// Relearning steps lapsed cards go through before returning to review (e.g. 10m)
+	#[clap(long, env = "HIPPOCAMPUS_RELEARNING_STEPS", value_delimiter = ',')]
+	pub relearning_steps: Option<Vec<String>>,
+
+	/// Days until a new card is next due once it graduates (e.g. 1)
+	#[clap(long, env = "HIPPOCAMPUS_GRADUATING_INTERVAL")]
+	pub graduating_interval: Option<u32>,
+
+	/// Shuffle card priorities by a small random offset each day (true or false)
+	#[clap(long, env = "HIPPOCAMPUS_PRIORITY_OFFSETS_ENABLED")]
+	pub priority_offsets_enabled: Option<bool>,
+
+	/// Amount a card's priority rises by each time it's rated Again (e.g. 0.1)
+	#[clap(long, env = "HIPPOCAMPUS_FAIL_PRIORITY_BOOST")]
+	pub fail_priority_boost: Option<f32>,
+
+	/// Amount a card's priority falls by each time it's rated Easy (e.g. 0.02)
+	#[clap(long, env = "HIPPOCAMPUS_EASY_PRIORITY_DECAY")]
+	pub easy_priority_decay: Option<f32>,
+
+	/// What happens to a card when it becomes a leech (none, suspend or tag)
+	#[clap(long, env = "HIPPOCAMPUS_LEECH_ACTION")]
+	pub leech_action: Option<String>,
+
+	/// Tag applied to leeches when the leech action is tag (e.g. leech)
+	#[clap(long, env = "HIPPOCAMPUS_LEECH_TAG")]
+	pub leech_tag: Option<String>,
+
 	/// Debug mode
 	#[clap(long, env = "HIPPOCAMPUS_DEBUG", default_value_t = false)]
 	pub debug: bool,
 
+	/// Reload settings that are safe to change while running whenever the
+	/// config file changes
+	#[clap(long, env = "HIPPOCAMPUS_WATCH_CONFIG", default_value_t = false)]
+	pub watch_config: bool,
+
+	/// Don't run pending database migrations at startup, for databases whose
+	/// schema is managed externally
+	#[clap(long, env = "HIPPOCAMPUS_NO_MIGRATE", default_value_t = false)]
+	pub no_migrate: bool,
+
+	/// Gzip responses for clients that accept it (true or false)
+	#[clap(long, env = "HIPPOCAMPUS_COMPRESSION")]
+	pub compression: Option<bool>,
+
+	/// Size in bytes a response must reach before it's compressed
+	#[clap(long, env = "HIPPOCAMPUS_COMPRESSION_MIN_SIZE")]
+	pub compression_min_size: Option<u16>,
+
+	/// Serve Prometheus metrics from /metrics
+	#[clap(long, env = "HIPPOCAMPUS_METRICS", default_value_t = false)]
+	pub metrics: bool,
+
+	/// Serve the database maintenance routes under /admin
+	#[clap(long, env = "HIPPOCAMPUS_ADMIN", default_value_t = false)]
+	pub admin: bool,
+
+	/// Cache item types in memory instead of reading them on every lookup
+	#[clap(long, env = "HIPPOCAMPUS_ITEM_TYPE_CACHE", default_value_t = false)]
+	pub item_type_cache: bool,
+
+	/// How often to start a new log file (hourly, daily or never)
+	#[clap(long, env = "HIPPOCAMPUS_LOG_ROTATION")]
+	pub log_rotation: Option<String>,
+
+	/// Number of rotated log files to keep
+	#[clap(long, env = "HIPPOCAMPUS_LOG_RETENTION")]
+	pub log_retention: Option<u32>,
+
+	/// Seconds between checks for newly due cards on /events/due
+	#[clap(long, env = "HIPPOCAMPUS_DUE_EVENTS_INTERVAL_SECS")]
+	pub due_events_interval_secs: Option<u64>,
+
+	/// Largest request body accepted, in bytes
+	#[clap(long, env = "HIPPOCAMPUS_MAX_BODY_SIZE")]
+	pub max_body_size: Option<usize>,
+
+	/// Largest request body accepted by /reviews/import, in bytes
+	#[clap(long, env = "HIPPOCAMPUS_MAX_IMPORT_BODY_SIZE")]
+	pub max_import_body_size: Option<usize>,
+
 	/// Override path to the config directory
 	#[clap(long, env = "HIPPOCAMPUS_CONFIG_DIR")]
 	pub config_dir: Option<PathBuf>,
@@ -120,11 +465,251 @@ pub struct CliArgs {
 	pub debug_allow_path_override: bool,
 }
 
+/// Which origins the server accepts cross-origin requests from
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum AllowedOrigins {
+	/// No CORS headers are sent, so browsers only allow same-origin requests
+	#[default]
+	SameOrigin,
+	/// Any origin may make cross-origin requests (`*`)
+	Any,
+	/// Only the listed origins may make cross-origin requests
+	List(Vec<String>),
+}
+
+/// What happens to a card when it becomes a leech, see
+/// [`crate::repo::DEFAULT_LEECH_THRESHOLD`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum LeechAction {
+	/// Nothing; leeches are only listed by `GET /cards/leeches`
+	#[default]
+	None,
+	/// The card is suspended
+	Suspend,
+	/// The card's item is tagged with the given tag, which is created if no
+	/// tag has that name
+	Tag(String),
+}
+
+impl AllowedOrigins {
+	/// Parses an `allowed_origins` setting
+	///
+	/// The setting is either `*` (any origin) or a comma-separated list of
+	/// origins such as `http://localhost:5173,https://example.com`. Whitespace
+	/// around entries and empty entries are ignored, so a setting that contains
+	/// no origins at all falls back to [`AllowedOrigins::SameOrigin`].
+	///
+	/// ### Arguments
+	///
+	/// * `raw` - The raw setting, or `None` if it was not provided
+	///
+	/// ### Returns
+	///
+	/// The parsed set of allowed origins
+	pub fn parse(raw: Option<&str>) -> Self {
+		let Some(raw) = raw else {
+			return AllowedOrigins::SameOrigin;
+		};
+
+		if raw.trim() == "*" {
+			return AllowedOrigins::Any;
+		}
+
+		let origins: Vec<String> = raw
+			.split(',')
+			.map(str::trim)
+			.filter(|origin| !origin.is_empty())
+			.map(|origin| origin.trim_end_matches('/').to_string())
+			.collect();
+
+		if origins.is_empty() {
+			AllowedOrigins::SameOrigin
+		} else {
+			AllowedOrigins::List(origins)
+		}
+	}
+}
+
 impl Config {
 	/// Returns the backup interval as a Duration
 	pub fn backup_interval(&self) -> Duration {
 		Duration::from_secs(self.backup_interval_minutes * 60)
 	}
+
+	/// Resolves the address the server should listen on
+	///
+	/// ### Returns
+	///
+	/// The first socket address `host:port` resolves to, or an error message
+	/// if the host is neither an IP address nor a resolvable name
+	pub fn listen_addr(&self) -> Result<SocketAddr, String> {
+		(self.host.as_str(), self.port)
+			.to_socket_addrs()
+			.map_err(|e| format!("Invalid host {:?}: {}", self.host, e))?
+			.next()
+			.ok_or_else(|| format!("Host {:?} did not resolve to any address", self.host))
+	}
+
+	/// Returns the parsed set of origins allowed to make cross-origin requests
+	pub fn allowed_origins(&self) -> AllowedOrigins {
+		AllowedOrigins::parse(self.allowed_origins.as_deref())
+	}
+
+	/// Returns the parsed level to log each HTTP request at
+	///
+	/// ### Returns
+	///
+	/// The configured level, [`DEFAULT_REQUEST_LOG_LEVEL`] if none was set,
+	/// or an error message if the setting isn't a valid level
+	pub fn request_log_level(&self) -> Result<Level, String> {
+		match &self.request_log_level {
+			None => Ok(DEFAULT_REQUEST_LOG_LEVEL),
+			Some(raw) => raw.trim().parse::<Level>().map_err(|_| {
+				format!(
+					"Invalid request_log_level {:?}: expected one of error, warn, info, debug or trace",
+					raw
+				)
+			}),
+		}
+	}
+
+	/// Returns the parsed period to rotate the log file after
+	///
+	/// ### Returns
+	///
+	/// The configured rotation, [`DEFAULT_LOG_ROTATION`] if none was set, or
+	/// an error message if the setting isn't `hourly`, `daily` or `never`
+	pub fn log_rotation(&self) -> Result<Rotation, String> {
+		match self.log_rotation.as_deref().map(str::trim) {
+			None => Ok(DEFAULT_LOG_ROTATION),
+			Some(raw) if raw.eq_ignore_ascii_case("hourly") => Ok(Rotation::HOURLY),
+			Some(raw) if raw.eq_ignore_ascii_case("daily") => Ok(Rotation::DAILY),
+			Some(raw) if raw.eq_ignore_ascii_case("never") => Ok(Rotation::NEVER),
+			Some(raw) => Err(format!(
+				"Invalid log_rotation {:?}: expected one of hourly, daily or never",
+				raw
+			)),
+		}
+	}
+
+	/// Returns the parsed action taken on cards that become leeches
+	///
+	/// ### Returns
+	///
+	/// The configured action, [`LeechAction::None`] if none was set, or an
+	/// error message if the setting isn't `none`, `suspend` or `tag`, or the
+	/// leech tag is blank
+	pub fn leech_action(&self) -> Result<LeechAction, String> {
+		match self.leech_action.as_deref().map(str::trim) {
+			None => Ok(LeechAction::None),
+			Some(raw) if raw.eq_ignore_ascii_case("none") => Ok(LeechAction::None),
+			Some(raw) if raw.eq_ignore_ascii_case("suspend") => Ok(LeechAction::Suspend),
+			Some(raw) if raw.eq_ignore_ascii_case("tag") => {
+				let tag = self
+					.leech_tag
+					.as_deref()
+					.unwrap_or(DEFAULT_LEECH_TAG)
+					.trim();
+				if tag.is_empty() {
+					return Err("leech_tag must not be empty".to_string());
+				}
+				Ok(LeechAction::Tag(tag.to_string()))
+			}
+			Some(raw) => Err(format!(
+				"Invalid leech_action {:?}: expected one of none, suspend or tag",
+				raw
+			)),
+		}
+	}
+
+	/// Returns the parsed floor for graduated cards' intervals
+	///
+	/// ### Returns
+	///
+	/// The configured minimum interval, zero if none was set, or an error
+	/// message if the setting isn't a duration such as `12h` or `1d`, or is
+	/// longer than [`MAX_CONFIGURED_INTERVAL_DAYS`]
+	pub fn minimum_interval(&self) -> Result<chrono::Duration, String> {
+		let Some(raw) = &self.minimum_interval else {
+			return Ok(chrono::Duration::zero());
+		};
+		let interval = parse_step_duration(raw.trim()).ok_or_else(|| {
+			format!(
+				"Invalid minimum_interval {:?}: expected a duration such as \"12h\" or \"1d\"",
+				raw
+			)
+		})?;
+		if interval > chrono::Duration::days(MAX_CONFIGURED_INTERVAL_DAYS.into()) {
+			return Err(format!(
+				"minimum_interval can be at most {} days, got {:?}",
+				MAX_CONFIGURED_INTERVAL_DAYS, raw
+			));
+		}
+		Ok(interval)
+	}
+
+	/// Returns the parsed learning steps for new cards
+	///
+	/// ### Returns
+	///
+	/// The configured steps, in order, or an error message naming the first
+	/// one that isn't a duration such as `30s`, `10m`, `1h` or `1d`
+	pub fn learning_steps(&self) -> Result<Vec<chrono::Duration>, String> {
+		parse_steps("learning", &self.learning_steps)
+	}
+
+	/// Returns the parsed relearning steps for lapsed cards
+	///
+	/// ### Returns
+	///
+	/// The configured steps, in order, or an error message naming the first
+	/// one that isn't a duration such as `30s`, `10m`, `1h` or `1d`
+	pub fn relearning_steps(&self) -> Result<Vec<chrono::Duration>, String> {
+		parse_steps("relearning", &self.relearning_steps)
+	}
+
+	/// Returns the parsed timezone that decides when one day ends and the
+	/// next begins
+	///
+	/// ### Returns
+	///
+	/// The configured timezone, [`DEFAULT_TIMEZONE`] if none was set, or an
+	/// error message if the setting isn't an IANA timezone name
+	pub fn timezone(&self) -> Result<Tz, String> {
+		match &self.timezone {
+			None => Ok(DEFAULT_TIMEZONE),
+			Some(raw) => raw.trim().parse::<Tz>().map_err(|_| {
+				format!(
+					"Invalid timezone {:?}: expected an IANA timezone name such as \"Europe/London\"",
+					raw
+				)
+			}),
+		}
+	}
+}
+
+/// Parses configured learning or relearning steps
+///
+/// ### Arguments
+///
+/// * `kind` - Which steps these are, for the error message
+/// * `steps` - The steps as configured
+///
+/// ### Returns
+///
+/// The steps, in order, or an error message naming the first invalid one
+fn parse_steps(kind: &str, steps: &[String]) -> Result<Vec<chrono::Duration>, String> {
+	steps
+		.iter()
+		.map(|raw| {
+			parse_step_duration(raw.trim()).ok_or_else(|| {
+				format!(
+					"Invalid {} step {:?}: expected a duration such as \"10m\", \"1h\" or \"1d\"",
+					kind, raw
+				)
+			})
+		})
+		.collect()
 }
 
 impl ConfigBuilder {
@@ -136,7 +721,42 @@ impl ConfigBuilder {
 				.backup_interval_minutes
 				.or(self.backup_interval_minutes),
 			backup_count: other.backup_count.or(self.backup_count),
+			pool_size: other.pool_size.or(self.pool_size),
+			connection_timeout_secs: other
+				.connection_timeout_secs
+				.or(self.connection_timeout_secs),
+			host: other.host.or(self.host),
+			port: other.port.or(self.port),
 			server_url: other.server_url.or(self.server_url),
+			allowed_origins: other.allowed_origins.or(self.allowed_origins),
+			request_log_level: other.request_log_level.or(self.request_log_level),
+			default_priority: other.default_priority.or(self.default_priority),
+			timezone: other.timezone.or(self.timezone),
+			interval_fuzz: other.interval_fuzz.or(self.interval_fuzz),
+			rating_scale: other.rating_scale.or(self.rating_scale),
+			minimum_interval: other.minimum_interval.or(self.minimum_interval),
+			learning_steps: other.learning_steps.or(self.learning_steps),
+			relearning_steps: other.relearning_steps.or(self.relearning_steps),
+			graduating_interval: other.graduating_interval.or(self.graduating_interval),
+			priority_offsets_enabled: other
+				.priority_offsets_enabled
+				.or(self.priority_offsets_enabled),
+			fail_priority_boost: other.fail_priority_boost.or(self.fail_priority_boost),
+			easy_priority_decay: other.easy_priority_decay.or(self.easy_priority_decay),
+			leech_action: other.leech_action.or(self.leech_action),
+			leech_tag: other.leech_tag.or(self.leech_tag),
+			compression: other.compression.or(self.compression),
+			compression_min_size: other.compression_min_size.or(self.compression_min_size),
+			metrics: other.metrics.or(self.metrics),
+			admin: other.admin.or(self.admin),
+			item_type_cache: other.item_type_cache.or(self.item_type_cache),
+			log_rotation: other.log_rotation.or(self.log_rotation),
+			log_retention: other.log_retention.or(self.log_retention),
+			due_events_interval_secs: other
+				.due_events_interval_secs
+				.or(self.due_events_interval_secs),
+			max_body_size: other.max_body_size.or(self.max_body_size),
+			max_import_body_size: other.max_import_body_size.or(self.max_import_body_size),
 			config_dir: other.config_dir.or(self.config_dir),
 			data_dir: other.data_dir.or(self.data_dir),
 			state_dir: other.state_dir.or(self.state_dir),
@@ -173,6 +793,47 @@ impl ConfigBuilder {
 				.backup_interval_minutes
 				.unwrap_or(DEFAULT_BACKUP_INTERVAL_MINUTES),
 			backup_count: self.backup_count.unwrap_or(DEFAULT_BACKUP_COUNT),
+			pool_size: self.pool_size.unwrap_or(DEFAULT_POOL_SIZE),
+			connection_timeout_secs: self
+				.connection_timeout_secs
+				.unwrap_or(DEFAULT_CONNECTION_TIMEOUT_SECS),
+			host: self.host.unwrap_or_else(|| DEFAULT_HOST.to_string()),
+			port: self.port.unwrap_or(DEFAULT_PORT),
+			allowed_origins: self.allowed_origins,
+			request_log_level: self.request_log_level,
+			default_priority: self.default_priority.unwrap_or(DEFAULT_PRIORITY),
+			timezone: self.timezone,
+			interval_fuzz: self.interval_fuzz.unwrap_or(DEFAULT_INTERVAL_FUZZ),
+			rating_scale: self.rating_scale.unwrap_or(DEFAULT_RATING_SCALE),
+			minimum_interval: self.minimum_interval,
+			learning_steps: self.learning_steps.unwrap_or_default(),
+			relearning_steps: self.relearning_steps.unwrap_or_default(),
+			graduating_interval: self.graduating_interval,
+			priority_offsets_enabled: self.priority_offsets_enabled.unwrap_or(true),
+			fail_priority_boost: self
+				.fail_priority_boost
+				.unwrap_or(DEFAULT_FAIL_PRIORITY_BOOST),
+			easy_priority_decay: self
+				.easy_priority_decay
+				.unwrap_or(DEFAULT_EASY_PRIORITY_DECAY),
+			leech_action: self.leech_action,
+			leech_tag: self.leech_tag,
+			compression: self.compression.unwrap_or(true),
+			compression_min_size: self
+				.compression_min_size
+				.unwrap_or(DEFAULT_COMPRESSION_MIN_SIZE),
+			metrics: self.metrics.unwrap_or(false),
+			admin: self.admin.unwrap_or(false),
+			item_type_cache: self.item_type_cache.unwrap_or(false),
+			log_rotation: self.log_rotation,
+			log_retention: self.log_retention.unwrap_or(DEFAULT_LOG_RETENTION),
+			due_events_interval_secs: self
+				.due_events_interval_secs
+				.unwrap_or(DEFAULT_DUE_EVENTS_INTERVAL_SECS),
+			max_body_size: self.max_body_size.unwrap_or(DEFAULT_MAX_BODY_SIZE),
+			max_import_body_size: self
+				.max_import_body_size
+				.unwrap_or(DEFAULT_MAX_IMPORT_BODY_SIZE),
 			config_dir: self.config_dir,
 			data_dir,
 			state_dir,
@@ -218,7 +879,37 @@ pub fn config_from_args(args: CliArgs) -> ConfigBuilder {
 		database_url: args.database_url,
 		backup_interval_minutes: args.backup_interval_minutes,
 		backup_count: args.backup_count,
+		pool_size: args.pool_size,
+		connection_timeout_secs: args.connection_timeout_secs,
+		host: args.host,
+		port: args.port,
 		server_url: None,
+		allowed_origins: args.allowed_origins,
+		request_log_level: args.request_log_level,
+		default_priority: args.default_priority,
+		timezone: args.timezone,
+		interval_fuzz: args.interval_fuzz,
+		rating_scale: args.rating_scale,
+		minimum_interval: args.minimum_interval,
+		learning_steps: args.learning_steps,
+		relearning_steps: args.relearning_steps,
+		graduating_interval: args.graduating_interval,
+		priority_offsets_enabled: args.priority_offsets_enabled,
+		fail_priority_boost: args.fail_priority_boost,
+		easy_priority_decay: args.easy_priority_decay,
+		leech_action: args.leech_action,
+		leech_tag: args.leech_tag,
+		// An unset flag leaves the config file's setting alone
+		compression: args.compression,
+		compression_min_size: args.compression_min_size,
+		metrics: args.metrics.then_some(true),
+		admin: args.admin.then_some(true),
+		item_type_cache: args.item_type_cache.then_some(true),
+		log_rotation: args.log_rotation,
+		log_retention: args.log_retention,
+		due_events_interval_secs: args.due_events_interval_secs,
+		max_body_size: args.max_body_size,
+		max_import_body_size: args.max_import_body_size,
 		config_dir: args.config_dir,
 		data_dir: args.data_dir,
 		state_dir: args.state_dir,
@@ -254,17 +945,10 @@ pub fn get_config_dir_path(override_path: Option<PathBuf>) -> Option<PathBuf> {
 		return Some(path);
 	}
 
-	let mut config_path = match ProjectDirs::from("com", "hippocampus", "hippocampus") {
-		Some(proj_dirs) => {
-			let config_dir = proj_dirs.config_dir();
-			let path = PathBuf::from(config_dir);
-			Some(path)
-		}
-		None => {
-			warn!("Could not determine XDG config directory, skipping config file");
-			None
-		}
-	};
+	let mut config_path = default_config_dir();
+	if config_path.is_none() {
+		warn!("Could not determine XDG config directory, skipping config file");
+	}
 
 	config_path = config_path.and_then(|path| {
 		if !path.exists() {
@@ -278,6 +962,206 @@ pub fn get_config_dir_path(override_path: Option<PathBuf>) -> Option<PathBuf> {
 	config_path
 }
 
+/// Gets the XDG config directory for the application, whether or not it
+/// exists yet
+///
+/// Unlike [`get_config_dir_path`], this doesn't check the directory exists or
+/// skip it in debug builds, so it says where a config file *would* be read
+/// from, e.g. for writing a new one.
+///
+/// ### Returns
+///
+/// The config directory, or None if the home directory can't be determined
+pub fn default_config_dir() -> Option<PathBuf> {
+	ProjectDirs::from("com", "hippocampus", "hippocampus")
+		.map(|proj_dirs| proj_dirs.config_dir().to_path_buf())
+}
+
+/// Renders a config file listing every setting at its default
+///
+/// Every setting is commented out, so the file behaves exactly like having no
+/// config file until a line is uncommented and edited.
+///
+/// ### Returns
+///
+/// The config file's TOML
+pub fn default_config_toml() -> String {
+	format!(
+		r#"# Hippocampus configuration
+#
+# Every setting is shown at its default value. Uncomment a line to change it.
+# Environment variables and command line flags take precedence over this file.
+
+# URL the CLI connects to the server at
+# server_url = "http://localhost:{port}"
+
+# SQLite database to use (defaults to {database} in the data directory)
+# database_url = "{database}"
+
+# Minutes between periodic backups of the database
+# backup_interval_minutes = {backup_interval_minutes}
+
+# Number of periodic backups to keep
+# backup_count = {backup_count}
+
+# Maximum number of database connections
+# pool_size = {pool_size}
+
+# Seconds a request waits for a free database connection before failing
+# connection_timeout_secs = {connection_timeout_secs}
+
+# IP address or hostname the server listens on
+# host = "{host}"
+
+# Port the server listens on
+# port = {port}
+
+# Origins allowed to make cross-origin requests, comma-separated, or "*"
+# allowed_origins = "http://localhost:5173"
+
+# Level each HTTP request is logged at: error, warn, info, debug or trace
+# request_log_level = "{request_log_level}"
+
+# Priority (between 0 and 1) given to new cards
+# default_priority = {default_priority:?}
+
+# IANA timezone whose midnight starts a new day
+# timezone = "{timezone}"
+
+# Fraction (at least 0, below 1) review intervals are randomly stretched or
+# shrunk by
+# interval_fuzz = {interval_fuzz:?}
+
+# Number of buttons reviews are rated with: 4 (1-4, Again to Easy) or 2 (1 for
+# fail, 2 for pass)
+# rating_scale = {rating_scale}
+
+# Shortest interval a graduated card is scheduled for, after fuzzing, so review
+# cards don't come back the same day, e.g. "1d". Learning steps can be shorter.
+# minimum_interval = "1d"
+
+# Waits a new card goes through, one per passing review, before graduating to
+# the spaced repetition intervals, e.g. ["1m", "10m"]
+# learning_steps = []
+
+# Waits a lapsed card goes through, one per passing review, before returning to
+# its reduced interval, e.g. ["10m"]
+# relearning_steps = []
+
+# Days until a new card is next due once it graduates: after its first passing
+# review, or after its last learning step. Unset, the card's review function
+# picks the interval.
+# graduating_interval = 1
+
+# Shuffle card priorities by a small random offset (up to 0.05 either way)
+# each day, so cards of equal priority don't always come in the same order.
+# Turn this off for strict priority ordering.
+# priority_offsets_enabled = true
+
+# Amount (between 0 and 1) a card's priority rises by each time it's rated
+# Again, so cards that keep being failed come up sooner, and falls by each
+# time it's rated Easy
+# fail_priority_boost = {fail_priority_boost:?}
+# easy_priority_decay = {easy_priority_decay:?}
+
+# What happens to a card once it's been rated Again {leech_threshold} times, making it a
+# leech: none, suspend (so it stops coming up) or tag (its item gets leech_tag)
+# leech_action = "none"
+# leech_tag = "{leech_tag}"
+
+# Gzip responses for clients that send Accept-Encoding: gzip, once they're at
+# least compression_min_size bytes
+# compression = true
+# compression_min_size = {compression_min_size}
+
+# Serve Prometheus metrics from GET /metrics
+# metrics = false
+
+# Serve the database maintenance routes, such as POST /admin/vacuum. There's
+# no authentication yet, so only turn this on if the server isn't exposed.
+# admin = false
+
+# Keep item types in memory instead of reading them from the database for
+# every item created. Only turn this on if nothing else writes to the database.
+# item_type_cache = false
+
+# How often to start a new log file: hourly, daily or never
+# log_rotation = "daily"
+
+# Number of rotated log files to keep
+# log_retention = {log_retention}
+
+# Seconds between checks for newly due cards, for clients listening on
+# GET /events/due
+# due_events_interval_secs = {due_events_interval_secs}
+
+# Largest request body accepted, in bytes; larger requests get a 413. The
+# review import takes its own, higher, limit.
+# max_body_size = {max_body_size}
+# max_import_body_size = {max_import_body_size}
+
+# Directories for the database and backups, and for logs
+# data_dir = "/path/to/data"
+# state_dir = "/path/to/state"
+"#,
+		port = DEFAULT_PORT,
+		database = DEFAULT_DATABASE_FILENAME,
+		backup_interval_minutes = DEFAULT_BACKUP_INTERVAL_MINUTES,
+		backup_count = DEFAULT_BACKUP_COUNT,
+		pool_size = DEFAULT_POOL_SIZE,
+		connection_timeout_secs = DEFAULT_CONNECTION_TIMEOUT_SECS,
+		host = DEFAULT_HOST,
+		request_log_level = DEFAULT_REQUEST_LOG_LEVEL.as_str().to_lowercase(),
+		default_priority = DEFAULT_PRIORITY,
+		timezone = DEFAULT_TIMEZONE.name(),
+		interval_fuzz = DEFAULT_INTERVAL_FUZZ,
+		rating_scale = DEFAULT_RATING_SCALE,
+		fail_priority_boost = DEFAULT_FAIL_PRIORITY_BOOST,
+		easy_priority_decay = DEFAULT_EASY_PRIORITY_DECAY,
+		leech_threshold = crate::repo::DEFAULT_LEECH_THRESHOLD,
+		leech_tag = DEFAULT_LEECH_TAG,
+		compression_min_size = DEFAULT_COMPRESSION_MIN_SIZE,
+		log_retention = DEFAULT_LOG_RETENTION,
+		due_events_interval_secs = DEFAULT_DUE_EVENTS_INTERVAL_SECS,
+		max_body_size = DEFAULT_MAX_BODY_SIZE,
+		max_import_body_size = DEFAULT_MAX_IMPORT_BODY_SIZE,
+	)
+}
+
+/// Writes [`default_config_toml`] to the config file in `config_dir`,
+/// creating the directory if needed
+///
+/// ### Arguments
+///
+/// * `config_dir` - The directory to write `config.toml` in
+/// * `force` - Whether to overwrite an existing config file
+///
+/// ### Returns
+///
+/// The path of the config file written
+///
+/// ### Errors
+///
+/// Returns an error message if the config file already exists and `force`
+/// isn't set, or the directory or file can't be written
+pub fn write_default_config(config_dir: &Path, force: bool) -> Result<PathBuf, String> {
+	let config_path = config_dir.join(CONFIG_FILE_NAME);
+	if config_path.exists() && !force {
+		return Err(format!(
+			"Config file already exists at {:?}; use --force to overwrite it",
+			config_path
+		));
+	}
+
+	fs::create_dir_all(config_dir)
+		.map_err(|e| format!("Failed to create config directory {:?}: {}", config_dir, e))?;
+	fs::write(&config_path, default_config_toml())
+		.map_err(|e| format!("Failed to write config file {:?}: {}", config_path, e))?;
+	info!("Wrote default config to {:?}", config_path);
+
+	Ok(config_path)
+}
+
 /// Gets the data directory path
 ///
 /// This function returns the path to the data directory for the application
@@ -407,20 +1291,121 @@ pub fn get_config(args: CliArgs) -> Result<Config, String> {
 	// Resolve config dir early — we need it to locate the config file
 	let config_dir_path = get_config_dir_path(args.config_dir.clone());
 
+	let config = load_config(config_from_args(args), config_dir_path)?;
+
+	info!(
+		"Final configuration: database_url={}, backup_interval={}min, backup_count={}, host={}, port={}",
+		config.database_url,
+		config.backup_interval_minutes,
+		config.backup_count,
+		config.host,
+		config.port
+	);
+
+	Ok(config)
+}
+
+/// Loads the config file from `config_dir`, applies `args` over it and
+/// validates the result
+///
+/// [`get_config`] uses this at startup, and the config watcher uses it to
+/// reload the file with the same precedence, so a setting given on the
+/// command line can't be overridden by editing the file.
+///
+/// ### Arguments
+///
+/// * `args` - The settings from command line arguments and environment variables
+/// * `config_dir` - The resolved config directory, if any
+///
+/// ### Returns
+///
+/// The validated configuration, or an error message if the file can't be
+/// read or a setting is invalid
+pub fn load_config(args: ConfigBuilder, config_dir: Option<PathBuf>) -> Result<Config, String> {
 	// Merge file config with args config (args take precedence),
 	// then override config_dir with the resolved path.
-	let mut builder = config_from_file(config_dir_path.as_ref().map(|p| p.join("config.toml")))?
-		.merge(config_from_args(args));
-	builder.config_dir = config_dir_path;
+	let mut builder =
+		config_from_file(config_dir.as_ref().map(|p| p.join(CONFIG_FILE_NAME)))?.merge(args);
+	builder.config_dir = config_dir;
 
 	let config = builder.build();
+	validate_config(&config)?;
+	Ok(config)
+}
 
-	info!(
-		"Final configuration: database_url={}, backup_interval={}min, backup_count={}",
-		config.database_url, config.backup_interval_minutes, config.backup_count
-	);
+/// Checks every setting that can be invalid
+///
+/// ### Returns
+///
+/// Ok(()) if the config is valid, or an error message naming the first
+/// invalid setting
+pub fn validate_config(config: &Config) -> Result<(), String> {
+	// Reject an invalid request log level up front rather than silently
+	// falling back to the default once the server is running
+	config.request_log_level()?;
+	config.timezone()?;
+	config.listen_addr()?;
+	config.log_rotation()?;
+	config.minimum_interval()?;
+	config.learning_steps()?;
+	config.relearning_steps()?;
+	config.leech_action()?;
+	if config.pool_size == 0 {
+		return Err("pool_size must be at least 1".to_string());
+	}
+	if config.log_retention == 0 {
+		return Err("log_retention must be at least 1".to_string());
+	}
+	match config.graduating_interval {
+		Some(days) if !(1..=MAX_CONFIGURED_INTERVAL_DAYS).contains(&days) => {
+			return Err(format!(
+				"graduating_interval must be between 1 and {}, got {}",
+				MAX_CONFIGURED_INTERVAL_DAYS, days
+			));
+		}
+		_ => {}
+	}
+	if config.due_events_interval_secs == 0 {
+		return Err("due_events_interval_secs must be at least 1".to_string());
+	}
+	if config.max_body_size == 0 {
+		return Err("max_body_size must be at least 1".to_string());
+	}
+	if config.max_import_body_size == 0 {
+		return Err("max_import_body_size must be at least 1".to_string());
+	}
+	if !(0.0..=1.0).contains(&config.default_priority) {
+		return Err(format!(
+			"default_priority must be between 0 and 1, got {}",
+			config.default_priority
+		));
+	}
+	if !(0.0..1.0).contains(&config.interval_fuzz) {
+		return Err(format!(
+			"interval_fuzz must be at least 0 and less than 1, got {}",
+			config.interval_fuzz
+		));
+	}
+	if !(0.0..=1.0).contains(&config.fail_priority_boost) {
+		return Err(format!(
+			"fail_priority_boost must be between 0 and 1, got {}",
+			config.fail_priority_boost
+		));
+	}
+	if !(0.0..=1.0).contains(&config.easy_priority_decay) {
+		return Err(format!(
+			"easy_priority_decay must be between 0 and 1, got {}",
+			config.easy_priority_decay
+		));
+	}
+	if ![2, 4].contains(&config.rating_scale) {
+		return Err(format!(
+			"rating_scale must be 2 or 4, got {}",
+			config.rating_scale
+		));
+	}
 
-	Ok(config)
+	Ok(())
 }
 
 #[cfg(test)]
@@ -16,6 +16,35 @@ fn arb_config_builder() -> impl Strategy<Value = ConfigBuilder> {
 				backup_interval_minutes,
 				backup_count,
 				server_url,
+				pool_size: None,
+				connection_timeout_secs: None,
+				host: None,
+				port: None,
+				allowed_origins: None,
+				request_log_level: None,
+				default_priority: None,
+				timezone: None,
+				interval_fuzz: None,
+				rating_scale: None,
+				minimum_interval: None,
+				learning_steps: None,
+				relearning_steps: None,
+				graduating_interval: None,
+				priority_offsets_enabled: None,
+				fail_priority_boost: None,
+				easy_priority_decay: None,
+				leech_action: None,
+				leech_tag: None,
+				compression: None,
+				compression_min_size: None,
+				metrics: None,
+				admin: None,
+				item_type_cache: None,
+				log_rotation: None,
+				log_retention: None,
+				due_events_interval_secs: None,
+				max_body_size: None,
+				max_import_body_size: None,
 				config_dir: None,
 				data_dir: None,
 				state_dir: None,
@@ -37,6 +66,35 @@ fn arb_full_config_builder() -> impl Strategy<Value = ConfigBuilder> {
 				backup_interval_minutes: Some(backup_interval_minutes),
 				backup_count: Some(backup_count),
 				server_url: Some(server_url),
+				pool_size: None,
+				connection_timeout_secs: None,
+				host: None,
+				port: None,
+				allowed_origins: None,
+				request_log_level: None,
+				default_priority: None,
+				timezone: None,
+				interval_fuzz: None,
+				rating_scale: None,
+				minimum_interval: None,
+				learning_steps: None,
+				relearning_steps: None,
+				graduating_interval: None,
+				priority_offsets_enabled: None,
+				fail_priority_boost: None,
+				easy_priority_decay: None,
+				leech_action: None,
+				leech_tag: None,
+				compression: None,
+				compression_min_size: None,
+				metrics: None,
+				admin: None,
+				item_type_cache: None,
+				log_rotation: None,
+				log_retention: None,
+				due_events_interval_secs: None,
+				max_body_size: None,
+				max_import_body_size: None,
 				config_dir: None,
 				data_dir: None,
 				state_dir: None,
@@ -143,6 +201,35 @@ proptest! {
 			database_url: String::new(),
 			backup_interval_minutes: minutes,
 			backup_count: 0,
+			pool_size: DEFAULT_POOL_SIZE,
+			connection_timeout_secs: DEFAULT_CONNECTION_TIMEOUT_SECS,
+			host: DEFAULT_HOST.to_string(),
+			port: DEFAULT_PORT,
+			allowed_origins: None,
+			request_log_level: None,
+			default_priority: DEFAULT_PRIORITY,
+			timezone: None,
+			interval_fuzz: DEFAULT_INTERVAL_FUZZ,
+			rating_scale: DEFAULT_RATING_SCALE,
+			minimum_interval: None,
+			learning_steps: Vec::new(),
+			relearning_steps: Vec::new(),
+			graduating_interval: None,
+			priority_offsets_enabled: true,
+			fail_priority_boost: 0.0,
+			easy_priority_decay: 0.0,
+			leech_action: None,
+			leech_tag: None,
+			compression: true,
+			compression_min_size: DEFAULT_COMPRESSION_MIN_SIZE,
+			metrics: false,
+			admin: false,
+			item_type_cache: false,
+			log_rotation: None,
+			log_retention: DEFAULT_LOG_RETENTION,
+			due_events_interval_secs: DEFAULT_DUE_EVENTS_INTERVAL_SECS,
+			max_body_size: DEFAULT_MAX_BODY_SIZE,
+			max_import_body_size: DEFAULT_MAX_IMPORT_BODY_SIZE,
 			config_dir: None,
 			data_dir: None,
 			state_dir: None,
@@ -173,7 +260,38 @@ proptest! {
 			database_url: database_url.clone(),
 			backup_interval_minutes,
 			backup_count,
+			pool_size: None,
+			connection_timeout_secs: None,
+			host: None,
+			port: None,
+			allowed_origins: None,
+			request_log_level: None,
+			default_priority: None,
+			timezone: None,
+			interval_fuzz: None,
+			rating_scale: None,
+			minimum_interval: None,
+			learning_steps: None,
+			relearning_steps: None,
+			graduating_interval: None,
+			priority_offsets_enabled: None,
+			fail_priority_boost: None,
+			easy_priority_decay: None,
+			leech_action: None,
+			leech_tag: None,
+			compression: None,
+			compression_min_size: None,
 			debug,
+			watch_config: false,
+			no_migrate: false,
+			metrics: false,
+			admin: false,
+			item_type_cache: false,
+			log_rotation: None,
+			log_retention: None,
+			due_events_interval_secs: None,
+			max_body_size: None,
+			max_import_body_size: None,
 			config_dir: None,
 			data_dir: None,
 			state_dir: None,
@@ -208,7 +326,38 @@ proptest! {
 			database_url: database_url.clone(),
 			backup_interval_minutes,
 			backup_count,
+			pool_size: None,
+			connection_timeout_secs: None,
+			host: None,
+			port: None,
+			allowed_origins: None,
+			request_log_level: None,
+			default_priority: None,
+			timezone: None,
+			interval_fuzz: None,
+			rating_scale: None,
+			minimum_interval: None,
+			learning_steps: None,
+			relearning_steps: None,
+			graduating_interval: None,
+			priority_offsets_enabled: None,
+			fail_priority_boost: None,
+			easy_priority_decay: None,
+			leech_action: None,
+			leech_tag: None,
+			compression: None,
+			compression_min_size: None,
 			debug,
+			watch_config: false,
+			no_migrate: false,
+			metrics: false,
+			admin: false,
+			item_type_cache: false,
+			log_rotation: None,
+			log_retention: None,
+			due_events_interval_secs: None,
+			max_body_size: None,
+			max_import_body_size: None,
 			config_dir: None,
 			data_dir: None,
 			state_dir: None,
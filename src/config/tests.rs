@@ -127,6 +127,35 @@ fn test_backup_interval_conversion() {
 		database_url: "test.db".to_string(),
 		backup_interval_minutes: 30,
 		backup_count: 5,
+		pool_size: DEFAULT_POOL_SIZE,
+		connection_timeout_secs: DEFAULT_CONNECTION_TIMEOUT_SECS,
+		host: DEFAULT_HOST.to_string(),
+		port: DEFAULT_PORT,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: DEFAULT_PRIORITY,
+		timezone: None,
+		interval_fuzz: DEFAULT_INTERVAL_FUZZ,
+		rating_scale: DEFAULT_RATING_SCALE,
+		minimum_interval: None,
+		learning_steps: Vec::new(),
+		relearning_steps: Vec::new(),
+		graduating_interval: None,
+		priority_offsets_enabled: true,
+		fail_priority_boost: 0.0,
+		easy_priority_decay: 0.0,
+		leech_action: None,
+		leech_tag: None,
+		compression: true,
+		compression_min_size: DEFAULT_COMPRESSION_MIN_SIZE,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: DEFAULT_LOG_RETENTION,
+		due_events_interval_secs: DEFAULT_DUE_EVENTS_INTERVAL_SECS,
+		max_body_size: DEFAULT_MAX_BODY_SIZE,
+		max_import_body_size: DEFAULT_MAX_IMPORT_BODY_SIZE,
 		config_dir: None,
 		data_dir: None,
 		state_dir: None,
@@ -137,6 +166,834 @@ fn test_backup_interval_conversion() {
 	assert_eq!(duration, Duration::from_secs(30 * 60));
 }
 
+// ============================================================================
+// AllowedOrigins::parse tests
+// ============================================================================
+
+#[test]
+fn test_allowed_origins_unset_is_same_origin() {
+	assert_eq!(AllowedOrigins::parse(None), AllowedOrigins::SameOrigin);
+}
+
+#[test]
+fn test_allowed_origins_wildcard() {
+	assert_eq!(AllowedOrigins::parse(Some("*")), AllowedOrigins::Any);
+	assert_eq!(AllowedOrigins::parse(Some("  * ")), AllowedOrigins::Any);
+}
+
+#[test]
+fn test_allowed_origins_comma_separated_list() {
+	let origins = AllowedOrigins::parse(Some(
+		"http://localhost:5173, https://example.com/ ,,http://127.0.0.1:8080",
+	));
+
+	assert_eq!(
+		origins,
+		AllowedOrigins::List(vec![
+			"http://localhost:5173".to_string(),
+			"https://example.com".to_string(),
+			"http://127.0.0.1:8080".to_string(),
+		])
+	);
+}
+
+#[test]
+fn test_allowed_origins_empty_list_is_same_origin() {
+	assert_eq!(AllowedOrigins::parse(Some("")), AllowedOrigins::SameOrigin);
+	assert_eq!(AllowedOrigins::parse(Some(" , ,")), AllowedOrigins::SameOrigin);
+}
+
+#[test]
+fn test_allowed_origins_from_config_file() {
+	let temp_dir = tempdir().unwrap();
+	let config_path = create_test_config_file(
+		&temp_dir,
+		"allowed_origins = \"http://localhost:5173\"\n",
+	);
+
+	let config = config_from_file(Some(config_path)).unwrap().build();
+
+	assert_eq!(
+		config.allowed_origins(),
+		AllowedOrigins::List(vec!["http://localhost:5173".to_string()])
+	);
+}
+
+// ============================================================================
+// Host and port tests
+// ============================================================================
+
+#[test]
+fn test_listen_addr_defaults() {
+	let config = ConfigBuilder::default().build();
+
+	assert_eq!(config.host, DEFAULT_HOST);
+	assert_eq!(config.port, DEFAULT_PORT);
+	assert_eq!(
+		config.listen_addr(),
+		Ok(SocketAddr::from(([127, 0, 0, 1], DEFAULT_PORT)))
+	);
+}
+
+#[test]
+fn test_port_flag_overrides_default() {
+	let args = CliArgs::try_parse_from(["hippocampus", "--port", "4321", "--host", "0.0.0.0"])
+		.unwrap();
+
+	let config = ConfigBuilder::default().merge(config_from_args(args)).build();
+
+	assert_eq!(config.port, 4321);
+	assert_eq!(
+		config.listen_addr(),
+		Ok(SocketAddr::from(([0, 0, 0, 0], 4321)))
+	);
+}
+
+#[test]
+fn test_port_flag_overrides_config_file() {
+	let temp_dir = tempdir().unwrap();
+	let config_path = create_test_config_file(&temp_dir, "host = \"localhost\"\nport = 8080\n");
+	let args = CliArgs::try_parse_from(["hippocampus", "--port", "4321"]).unwrap();
+
+	let config = config_from_file(Some(config_path))
+		.unwrap()
+		.merge(config_from_args(args))
+		.build();
+
+	assert_eq!(config.host, "localhost");
+	assert_eq!(config.port, 4321);
+	assert_eq!(config.listen_addr().unwrap().port(), 4321);
+}
+
+#[test]
+fn test_listen_addr_rejects_invalid_host() {
+	let config = ConfigBuilder {
+		host: Some("not a host!".to_string()),
+		..Default::default()
+	}
+	.build();
+
+	assert!(config.listen_addr().unwrap_err().contains("not a host!"));
+}
+
+// ============================================================================
+// Connection pool tests
+// ============================================================================
+
+#[test]
+fn test_pool_options_from_config() {
+	let args = CliArgs::try_parse_from([
+		"hippocampus",
+		"--pool-size",
+		"2",
+		"--connection-timeout-secs",
+		"1",
+	])
+	.unwrap();
+
+	let config = ConfigBuilder::default().merge(config_from_args(args)).build();
+	let options = crate::db::PoolOptions::from(&config);
+
+	assert_eq!(options.max_size, 2);
+	assert_eq!(options.connection_timeout, Duration::from_secs(1));
+}
+
+#[test]
+fn test_pool_options_defaults() {
+	let config = ConfigBuilder::default().build();
+
+	assert_eq!(
+		crate::db::PoolOptions::from(&config),
+		crate::db::PoolOptions::default()
+	);
+}
+
+// ============================================================================
+// Config::request_log_level tests
+// ============================================================================
+
+/// Builds a `Config` with the given raw `request_log_level` setting
+fn config_with_request_log_level(raw: Option<&str>) -> Config {
+	ConfigBuilder {
+		request_log_level: raw.map(str::to_string),
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
+		..Default::default()
+	}
+	.build()
+}
+
+#[test]
+fn test_request_log_level_defaults_to_info() {
+	let config = config_with_request_log_level(None);
+
+	assert_eq!(config.request_log_level(), Ok(DEFAULT_REQUEST_LOG_LEVEL));
+	assert_eq!(DEFAULT_REQUEST_LOG_LEVEL, Level::INFO);
+}
+
+#[test]
+fn test_request_log_level_parses_case_insensitively() {
+	assert_eq!(
+		config_with_request_log_level(Some("debug")).request_log_level(),
+		Ok(Level::DEBUG)
+	);
+	assert_eq!(
+		config_with_request_log_level(Some(" WARN ")).request_log_level(),
+		Ok(Level::WARN)
+	);
+}
+
+#[test]
+fn test_request_log_level_rejects_invalid_level() {
+	let result = config_with_request_log_level(Some("loud")).request_log_level();
+
+	assert!(result.unwrap_err().contains("loud"));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn test_get_config_rejects_invalid_request_log_level() {
+	let config_dir = tempdir().unwrap();
+	create_test_config_file(&config_dir, "request_log_level = \"verbose\"\n");
+
+	let args = CliArgs {
+		database_url: None,
+		backup_interval_minutes: None,
+		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
+		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
+		config_dir: Some(config_dir.path().to_path_buf()),
+		data_dir: None,
+		state_dir: None,
+		debug_allow_path_override: true,
+	};
+
+	let result = get_config(args);
+
+	assert!(result.unwrap_err().contains("request_log_level"));
+}
+
+// ============================================================================
+// Config::timezone tests
+// ============================================================================
+
+/// Builds a `Config` with the given raw `timezone` setting
+fn config_with_timezone(raw: Option<&str>) -> Config {
+	ConfigBuilder {
+		timezone: raw.map(str::to_string),
+		..Default::default()
+	}
+	.build()
+}
+
+#[test]
+fn test_timezone_defaults_to_utc() {
+	assert_eq!(config_with_timezone(None).timezone(), Ok(DEFAULT_TIMEZONE));
+	assert_eq!(DEFAULT_TIMEZONE, chrono_tz::Tz::UTC);
+}
+
+#[test]
+fn test_timezone_parses_iana_name() {
+	assert_eq!(
+		config_with_timezone(Some("Australia/Melbourne")).timezone(),
+		Ok(chrono_tz::Tz::Australia__Melbourne)
+	);
+	assert_eq!(
+		config_with_timezone(Some(" Europe/London ")).timezone(),
+		Ok(chrono_tz::Tz::Europe__London)
+	);
+}
+
+#[test]
+fn test_timezone_rejects_unknown_name() {
+	let result = config_with_timezone(Some("Mars/Olympus_Mons")).timezone();
+
+	assert!(result.unwrap_err().contains("Mars/Olympus_Mons"));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn test_get_config_rejects_invalid_timezone() {
+	let config_dir = tempdir().unwrap();
+	create_test_config_file(&config_dir, "timezone = \"Nowhere/Special\"\n");
+
+	let args = CliArgs {
+		database_url: None,
+		backup_interval_minutes: None,
+		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
+		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
+		config_dir: Some(config_dir.path().to_path_buf()),
+		data_dir: None,
+		state_dir: None,
+		debug_allow_path_override: true,
+	};
+
+	let result = get_config(args);
+
+	assert!(result.unwrap_err().contains("timezone"));
+}
+
+#[test]
+fn test_get_config_rejects_zero_pool_size() {
+	let config_dir = tempdir().unwrap();
+	create_test_config_file(&config_dir, "pool_size = 0\n");
+
+	let args = CliArgs {
+		database_url: None,
+		backup_interval_minutes: None,
+		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
+		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
+		config_dir: Some(config_dir.path().to_path_buf()),
+		data_dir: None,
+		state_dir: None,
+		debug_allow_path_override: true,
+	};
+
+	let result = get_config(args);
+
+	assert!(result.unwrap_err().contains("pool_size"));
+}
+
+#[test]
+fn test_get_config_rejects_out_of_range_default_priority() {
+	let config_dir = tempdir().unwrap();
+	create_test_config_file(&config_dir, "default_priority = 1.5\n");
+
+	let args = CliArgs {
+		database_url: None,
+		backup_interval_minutes: None,
+		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
+		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
+		config_dir: Some(config_dir.path().to_path_buf()),
+		data_dir: None,
+		state_dir: None,
+		debug_allow_path_override: true,
+	};
+
+	let result = get_config(args);
+
+	assert!(result.unwrap_err().contains("default_priority"));
+}
+
+#[test]
+fn test_get_config_rejects_out_of_range_interval_fuzz() {
+	for raw in ["1.0", "-0.1"] {
+		let config_dir = tempdir().unwrap();
+		create_test_config_file(&config_dir, &format!("interval_fuzz = {}\n", raw));
+
+		let args = CliArgs {
+			database_url: None,
+			backup_interval_minutes: None,
+			backup_count: None,
+			pool_size: None,
+			connection_timeout_secs: None,
+			host: None,
+			port: None,
+			allowed_origins: None,
+			request_log_level: None,
+			default_priority: None,
+			timezone: None,
+			interval_fuzz: None,
+			rating_scale: None,
+			minimum_interval: None,
+			learning_steps: None,
+			relearning_steps: None,
+			graduating_interval: None,
+			priority_offsets_enabled: None,
+			fail_priority_boost: None,
+			easy_priority_decay: None,
+			leech_action: None,
+			leech_tag: None,
+			compression: None,
+			compression_min_size: None,
+			debug: false,
+			watch_config: false,
+			no_migrate: false,
+			metrics: false,
+			admin: false,
+			item_type_cache: false,
+			log_rotation: None,
+			log_retention: None,
+			due_events_interval_secs: None,
+			max_body_size: None,
+			max_import_body_size: None,
+			config_dir: Some(config_dir.path().to_path_buf()),
+			data_dir: None,
+			state_dir: None,
+			debug_allow_path_override: true,
+		};
+
+		let result = get_config(args);
+
+		assert!(result.unwrap_err().contains("interval_fuzz"));
+	}
+}
+
+#[test]
+fn test_get_config_rejects_unsupported_rating_scale() {
+	for raw in ["3", "0"] {
+		let config_dir = tempdir().unwrap();
+		create_test_config_file(&config_dir, &format!("rating_scale = {}\n", raw));
+
+		let args = CliArgs {
+			database_url: None,
+			backup_interval_minutes: None,
+			backup_count: None,
+			pool_size: None,
+			connection_timeout_secs: None,
+			host: None,
+			port: None,
+			allowed_origins: None,
+			request_log_level: None,
+			default_priority: None,
+			timezone: None,
+			interval_fuzz: None,
+			rating_scale: None,
+			minimum_interval: None,
+			learning_steps: None,
+			relearning_steps: None,
+			graduating_interval: None,
+			priority_offsets_enabled: None,
+			fail_priority_boost: None,
+			easy_priority_decay: None,
+			leech_action: None,
+			leech_tag: None,
+			compression: None,
+			compression_min_size: None,
+			debug: false,
+			watch_config: false,
+			no_migrate: false,
+			metrics: false,
+			admin: false,
+			item_type_cache: false,
+			log_rotation: None,
+			log_retention: None,
+			due_events_interval_secs: None,
+			max_body_size: None,
+			max_import_body_size: None,
+			config_dir: Some(config_dir.path().to_path_buf()),
+			data_dir: None,
+			state_dir: None,
+			debug_allow_path_override: true,
+		};
+
+		let result = get_config(args);
+
+		assert!(result.unwrap_err().contains("rating_scale"));
+	}
+}
+
+#[test]
+fn test_get_config_rejects_invalid_log_settings() {
+	for (contents, setting) in [
+		("log_rotation = \"weekly\"\n", "log_rotation"),
+		("log_retention = 0\n", "log_retention"),
+	] {
+		let config_dir = tempdir().unwrap();
+		create_test_config_file(&config_dir, contents);
+
+		let args = ConfigBuilder::default();
+		let result = load_config(args, Some(config_dir.path().to_path_buf()));
+
+		assert!(result.unwrap_err().contains(setting));
+	}
+}
+
+#[test]
+fn test_get_config_rejects_zero_due_events_interval() {
+	let config_dir = tempdir().unwrap();
+	create_test_config_file(&config_dir, "due_events_interval_secs = 0\n");
+
+	let args = ConfigBuilder::default();
+	let result = load_config(args, Some(config_dir.path().to_path_buf()));
+
+	assert!(result.unwrap_err().contains("due_events_interval_secs"));
+}
+
+#[test]
+fn test_get_config_rejects_zero_body_size_limits() {
+	for setting in ["max_body_size", "max_import_body_size"] {
+		let config_dir = tempdir().unwrap();
+		create_test_config_file(&config_dir, &format!("{} = 0\n", setting));
+
+		let args = ConfigBuilder::default();
+		let result = load_config(args, Some(config_dir.path().to_path_buf()));
+
+		assert!(result.unwrap_err().contains(setting));
+	}
+}
+
+#[test]
+fn test_learning_steps_parsing() {
+	let config_dir = tempdir().unwrap();
+	create_test_config_file(&config_dir, "learning_steps = [\"1m\", \"10m\", \"1d\"]\n");
+
+	let config = load_config(
+		ConfigBuilder::default(),
+		Some(config_dir.path().to_path_buf()),
+	)
+	.unwrap();
+
+	assert_eq!(
+		config.learning_steps(),
+		Ok(vec![
+			chrono::Duration::minutes(1),
+			chrono::Duration::minutes(10),
+			chrono::Duration::days(1),
+		])
+	);
+	assert_eq!(
+		ConfigBuilder::default().build().learning_steps(),
+		Ok(vec![])
+	);
+}
+
+#[test]
+fn test_get_config_rejects_invalid_learning_steps() {
+	let config_dir = tempdir().unwrap();
+	create_test_config_file(&config_dir, "learning_steps = [\"1m\", \"soon\"]\n");
+
+	let result = load_config(
+		ConfigBuilder::default(),
+		Some(config_dir.path().to_path_buf()),
+	);
+
+	let err = result.unwrap_err();
+	assert!(err.contains("learning step"), "got: {}", err);
+	assert!(err.contains("soon"), "got: {}", err);
+}
+
+#[test]
+fn test_compression_settings() {
+	let config = ConfigBuilder::default().build();
+	assert!(config.compression);
+	assert_eq!(config.compression_min_size, DEFAULT_COMPRESSION_MIN_SIZE);
+
+	let config_dir = tempdir().unwrap();
+	create_test_config_file(
+		&config_dir,
+		"compression = false\ncompression_min_size = 4096\n",
+	);
+	let config = load_config(
+		ConfigBuilder::default(),
+		Some(config_dir.path().to_path_buf()),
+	)
+	.unwrap();
+	assert!(!config.compression);
+	assert_eq!(config.compression_min_size, 4096);
+	assert_eq!(crate::AppOptions::from(&config).compression_min_size, None);
+}
+
+#[test]
+fn test_item_type_cache_setting() {
+	assert!(!ConfigBuilder::default().build().item_type_cache);
+
+	let config_dir = tempdir().unwrap();
+	create_test_config_file(&config_dir, "item_type_cache = true\n");
+	let config = load_config(
+		ConfigBuilder::default(),
+		Some(config_dir.path().to_path_buf()),
+	)
+	.unwrap();
+	assert!(config.item_type_cache);
+}
+
+#[test]
+fn test_relearning_steps_parsing() {
+	let config_dir = tempdir().unwrap();
+	create_test_config_file(&config_dir, "relearning_steps = [\"10m\"]\n");
+
+	let config = load_config(
+		ConfigBuilder::default(),
+		Some(config_dir.path().to_path_buf()),
+	)
+	.unwrap();
+	assert_eq!(
+		config.relearning_steps(),
+		Ok(vec![chrono::Duration::minutes(10)])
+	);
+
+	create_test_config_file(&config_dir, "relearning_steps = [\"later\"]\n");
+	let err = load_config(
+		ConfigBuilder::default(),
+		Some(config_dir.path().to_path_buf()),
+	)
+	.unwrap_err();
+	assert!(err.contains("relearning step"), "got: {}", err);
+	assert!(err.contains("later"), "got: {}", err);
+}
+
+#[test]
+fn test_minimum_interval_parsing() {
+	assert_eq!(
+		ConfigBuilder::default().build().minimum_interval(),
+		Ok(chrono::Duration::zero())
+	);
+
+	let config_dir = tempdir().unwrap();
+	create_test_config_file(&config_dir, "minimum_interval = \"1d\"\n");
+	let config = load_config(
+		ConfigBuilder::default(),
+		Some(config_dir.path().to_path_buf()),
+	)
+	.unwrap();
+	assert_eq!(config.minimum_interval(), Ok(chrono::Duration::days(1)));
+
+	create_test_config_file(&config_dir, "minimum_interval = \"tomorrow\"\n");
+	let err = load_config(
+		ConfigBuilder::default(),
+		Some(config_dir.path().to_path_buf()),
+	)
+	.unwrap_err();
+	assert!(err.contains("minimum_interval"), "got: {}", err);
+	assert!(err.contains("tomorrow"), "got: {}", err);
+
+	create_test_config_file(&config_dir, "minimum_interval = \"36501d\"\n");
+	let err = load_config(
+		ConfigBuilder::default(),
+		Some(config_dir.path().to_path_buf()),
+	)
+	.unwrap_err();
+	assert!(err.contains("at most 36500 days"), "got: {}", err);
+}
+
+#[test]
+fn test_graduating_interval_parsing() {
+	assert_eq!(ConfigBuilder::default().build().graduating_interval, None);
+
+	let config_dir = tempdir().unwrap();
+	create_test_config_file(&config_dir, "graduating_interval = 3\n");
+	let config = load_config(
+		ConfigBuilder::default(),
+		Some(config_dir.path().to_path_buf()),
+	)
+	.unwrap();
+	assert_eq!(config.graduating_interval, Some(3));
+
+	for invalid in ["0", "36501", "4294967295"] {
+		create_test_config_file(&config_dir, &format!("graduating_interval = {}\n", invalid));
+		let err = load_config(
+			ConfigBuilder::default(),
+			Some(config_dir.path().to_path_buf()),
+		)
+		.unwrap_err();
+		assert!(err.contains("graduating_interval"), "got: {}", err);
+	}
+}
+
+#[test]
+fn test_priority_adjustment_parsing() {
+	let config = ConfigBuilder::default().build();
+	assert_eq!(config.fail_priority_boost, DEFAULT_FAIL_PRIORITY_BOOST);
+	assert_eq!(config.easy_priority_decay, DEFAULT_EASY_PRIORITY_DECAY);
+
+	let config_dir = tempdir().unwrap();
+	create_test_config_file(
+		&config_dir,
+		"fail_priority_boost = 0.1\neasy_priority_decay = 0.02\n",
+	);
+	let config = load_config(
+		ConfigBuilder::default(),
+		Some(config_dir.path().to_path_buf()),
+	)
+	.unwrap();
+	assert_eq!(config.fail_priority_boost, 0.1);
+	assert_eq!(config.easy_priority_decay, 0.02);
+
+	for (contents, setting) in [
+		("fail_priority_boost = 1.5\n", "fail_priority_boost"),
+		("easy_priority_decay = -0.1\n", "easy_priority_decay"),
+	] {
+		create_test_config_file(&config_dir, contents);
+		let err = load_config(
+			ConfigBuilder::default(),
+			Some(config_dir.path().to_path_buf()),
+		)
+		.unwrap_err();
+		assert!(err.contains(setting), "got: {}", err);
+	}
+}
+
+#[test]
+fn test_leech_action_parsing() {
+	let mut config = ConfigBuilder::default().build();
+	assert_eq!(config.leech_action(), Ok(LeechAction::None));
+
+	for (raw, action) in [
+		("none", LeechAction::None),
+		("Suspend", LeechAction::Suspend),
+		(" tag ", LeechAction::Tag(DEFAULT_LEECH_TAG.to_string())),
+	] {
+		config.leech_action = Some(raw.to_string());
+		assert_eq!(config.leech_action(), Ok(action));
+	}
+
+	config.leech_tag = Some("struggling".to_string());
+	assert_eq!(
+		config.leech_action(),
+		Ok(LeechAction::Tag("struggling".to_string()))
+	);
+	config.leech_tag = Some("  ".to_string());
+	assert!(config.leech_action().unwrap_err().contains("leech_tag"));
+
+	config.leech_action = Some("delete".to_string());
+	assert!(config.leech_action().unwrap_err().contains("leech_action"));
+}
+
+#[test]
+fn test_log_rotation_parsing() {
+	let mut config = ConfigBuilder::default().build();
+	assert_eq!(config.log_rotation(), Ok(DEFAULT_LOG_ROTATION));
+
+	for (raw, rotation) in [
+		("hourly", Rotation::HOURLY),
+		("Daily", Rotation::DAILY),
+		(" never ", Rotation::NEVER),
+	] {
+		config.log_rotation = Some(raw.to_string());
+		assert_eq!(config.log_rotation(), Ok(rotation));
+	}
+
+	config.log_rotation = Some("minutely".to_string());
+	assert!(config.log_rotation().is_err());
+}
+
 // ============================================================================
 // ConfigBuilder::build tests
 // ============================================================================
@@ -151,6 +1008,15 @@ fn test_build_defaults() {
 		DEFAULT_BACKUP_INTERVAL_MINUTES
 	);
 	assert_eq!(config.backup_count, DEFAULT_BACKUP_COUNT);
+	assert_eq!(config.default_priority, DEFAULT_PRIORITY);
+	assert_eq!(config.interval_fuzz, DEFAULT_INTERVAL_FUZZ);
+	assert_eq!(config.log_retention, DEFAULT_LOG_RETENTION);
+	assert_eq!(
+		config.due_events_interval_secs,
+		DEFAULT_DUE_EVENTS_INTERVAL_SECS
+	);
+	assert_eq!(config.max_body_size, DEFAULT_MAX_BODY_SIZE);
+	assert_eq!(config.max_import_body_size, DEFAULT_MAX_IMPORT_BODY_SIZE);
 	assert_eq!(config.config_dir, None);
 	// In debug builds, data_dir and state_dir resolve to None (no override given)
 	if cfg!(debug_assertions) {
@@ -258,7 +1124,38 @@ fn test_config_from_args_with_all_values() {
 		database_url: Some("args.db".to_string()),
 		backup_interval_minutes: Some(45),
 		backup_count: Some(15),
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
 		debug: true,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
 		config_dir: None,
 		data_dir: None,
 		state_dir: None,
@@ -279,7 +1176,38 @@ fn test_config_from_args_with_partial_values() {
 		database_url: Some("args.db".to_string()),
 		backup_interval_minutes: None,
 		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
 		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
 		config_dir: None,
 		data_dir: None,
 		state_dir: None,
@@ -300,7 +1228,38 @@ fn test_config_from_args_with_no_values() {
 		database_url: None,
 		backup_interval_minutes: None,
 		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
 		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
 		config_dir: None,
 		data_dir: None,
 		state_dir: None,
@@ -325,7 +1284,38 @@ fn test_config_from_args_forwards_dir_fields() {
 		database_url: None,
 		backup_interval_minutes: None,
 		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
 		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
 		config_dir: Some(config_path.clone()),
 		data_dir: Some(data_path.clone()),
 		state_dir: Some(state_path.clone()),
@@ -576,7 +1566,38 @@ fn test_get_config_with_config_dir_override() {
 		database_url: None,
 		backup_interval_minutes: None,
 		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
 		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
 		config_dir: Some(config_dir.path().to_path_buf()),
 		data_dir: None,
 		state_dir: None,
@@ -599,7 +1620,38 @@ fn test_get_config_with_data_dir_override() {
 		database_url: None,
 		backup_interval_minutes: None,
 		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
 		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
 		config_dir: None,
 		data_dir: Some(data_dir.path().to_path_buf()),
 		state_dir: None,
@@ -626,7 +1678,38 @@ fn test_get_config_with_state_dir_override() {
 		database_url: None,
 		backup_interval_minutes: None,
 		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
 		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
 		config_dir: None,
 		data_dir: None,
 		state_dir: Some(state_dir.path().to_path_buf()),
@@ -651,7 +1734,38 @@ fn test_get_config_args_override_config_dir_file() {
 		database_url: Some("from_args.db".to_string()),
 		backup_interval_minutes: None,
 		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
 		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
 		config_dir: Some(config_dir.path().to_path_buf()),
 		data_dir: None,
 		state_dir: None,
@@ -677,7 +1791,38 @@ fn test_get_config_rejects_overrides_without_flag() {
 		database_url: None,
 		backup_interval_minutes: None,
 		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
 		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
 		config_dir: None,
 		data_dir: Some(data_dir.path().to_path_buf()),
 		state_dir: None,
@@ -710,7 +1855,38 @@ fn test_get_config_precedence() {
 		database_url: Some("args.db".to_string()),
 		backup_interval_minutes: None,
 		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
 		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
 		config_dir: None,
 		data_dir: None,
 		state_dir: None,
@@ -742,7 +1918,38 @@ fn test_full_config_with_all_sources() {
 		database_url: Some("args.db".to_string()),
 		backup_interval_minutes: None,
 		backup_count: Some(25),
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
 		debug: true,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
 		config_dir: None,
 		data_dir: None,
 		state_dir: None,
@@ -769,7 +1976,38 @@ fn test_full_config_with_no_overrides() {
 		database_url: None,
 		backup_interval_minutes: None,
 		backup_count: None,
+		pool_size: None,
+		connection_timeout_secs: None,
+		host: None,
+		port: None,
+		allowed_origins: None,
+		request_log_level: None,
+		default_priority: None,
+		timezone: None,
+		interval_fuzz: None,
+		rating_scale: None,
+		minimum_interval: None,
+		learning_steps: None,
+		relearning_steps: None,
+		graduating_interval: None,
+		priority_offsets_enabled: None,
+		fail_priority_boost: None,
+		easy_priority_decay: None,
+		leech_action: None,
+		leech_tag: None,
+		compression: None,
+		compression_min_size: None,
 		debug: false,
+		watch_config: false,
+		no_migrate: false,
+		metrics: false,
+		admin: false,
+		item_type_cache: false,
+		log_rotation: None,
+		log_retention: None,
+		due_events_interval_secs: None,
+		max_body_size: None,
+		max_import_body_size: None,
 		config_dir: None,
 		data_dir: None,
 		state_dir: None,
@@ -788,3 +2026,74 @@ fn test_full_config_with_no_overrides() {
 	);
 	assert_eq!(final_config.backup_count, DEFAULT_BACKUP_COUNT);
 }
+
+// ============================================================================
+// write_default_config tests
+// ============================================================================
+
+#[test]
+fn test_write_default_config_lists_every_default() {
+	let parent = tempdir().unwrap();
+	let config_dir = parent.path().join("hippocampus");
+
+	let config_path = write_default_config(&config_dir, false).unwrap();
+	assert_eq!(config_path, config_dir.join(CONFIG_FILE_NAME));
+
+	// Everything is commented out, so the file changes nothing as written
+	let as_written = config_from_file(Some(config_path.clone())).unwrap();
+	assert!(as_written.database_url.is_none());
+	assert!(as_written.server_url.is_none());
+
+	// Uncommented, every line must be a known key at its default value
+	let content = fs::read_to_string(&config_path).unwrap();
+	let uncommented: String = content
+		.lines()
+		.filter_map(|line| line.strip_prefix("# "))
+		.filter(|line| line.contains(" = "))
+		.map(|line| format!("{}\n", line))
+		.collect();
+	let builder: ConfigBuilder = toml::from_str(&uncommented).unwrap();
+	assert_eq!(
+		builder.server_url,
+		Some(format!("http://localhost:{}", DEFAULT_PORT))
+	);
+	assert_eq!(
+		builder.database_url.as_deref(),
+		Some(DEFAULT_DATABASE_FILENAME)
+	);
+	assert_eq!(
+		builder.backup_interval_minutes,
+		Some(DEFAULT_BACKUP_INTERVAL_MINUTES)
+	);
+	assert_eq!(builder.backup_count, Some(DEFAULT_BACKUP_COUNT));
+	assert_eq!(builder.timezone.as_deref(), Some("UTC"));
+	assert_eq!(builder.default_priority, Some(DEFAULT_PRIORITY));
+	assert_eq!(builder.log_retention, Some(DEFAULT_LOG_RETENTION));
+	assert_eq!(
+		builder.due_events_interval_secs,
+		Some(DEFAULT_DUE_EVENTS_INTERVAL_SECS)
+	);
+	assert!(
+		builder.data_dir.is_some() && builder.state_dir.is_some(),
+		"every setting should be listed"
+	);
+}
+
+#[test]
+fn test_write_default_config_refuses_to_overwrite_without_force() {
+	let config_dir = tempdir().unwrap();
+	let config_path = create_test_config_file(&config_dir, "backup_count = 3\n");
+
+	let err = write_default_config(config_dir.path(), false).unwrap_err();
+	assert!(err.contains("--force"), "got: {}", err);
+	assert_eq!(
+		fs::read_to_string(&config_path).unwrap(),
+		"backup_count = 3\n"
+	);
+
+	write_default_config(config_dir.path(), true).unwrap();
+	assert_eq!(
+		fs::read_to_string(&config_path).unwrap(),
+		default_config_toml()
+	);
+}
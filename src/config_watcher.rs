@@ -0,0 +1,420 @@
+//! Reloads settings from the config file while the server is running.
+//!
+//! Only some settings can change without a restart: the ones the repo reads
+//! from the `metadata` table (see [`repo::Settings`]), listed in
+//! [`RELOADABLE_SETTINGS`]. Storing the reloaded values there, all in one
+//! transaction, is all it takes for every handler to pick them up on its next
+//! request. Every other setting (`database_url`, `port`, the pool and backup
+//! settings, ...) is only read at startup, so a change to one is logged and
+//! otherwise ignored; a new setting is treated that way until it's listed.
+
+use crate::config::{CONFIG_FILE_NAME, Config, ConfigBuilder, DEFAULT_TIMEZONE, load_config};
+use crate::db::DbPool;
+use crate::repo;
+use anyhow::{Result, anyhow};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::{Map, Value};
+use std::ffi::OsStr;
+use std::sync::{Arc, mpsc};
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tracing::{error, info, warn};
+
+/// How long to wait for more changes after the config file changes, so that
+/// an editor's several writes while saving cause a single reload
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// The settings that take effect without a restart, by their name in the
+/// config file
+const RELOADABLE_SETTINGS: [&str; 13] = [
+	"default_priority",
+	"timezone",
+	"interval_fuzz",
+	"rating_scale",
+	"minimum_interval",
+	"learning_steps",
+	"relearning_steps",
+	"graduating_interval",
+	"priority_offsets_enabled",
+	"fail_priority_boost",
+	"easy_priority_decay",
+	"leech_action",
+	"leech_tag",
+];
+
+/// Stores the settings that can change while the server is running, where
+/// the handlers read them from
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `config` - The config to take the settings from
+///
+/// ### Returns
+///
+/// A Result indicating success or failure
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - The config's timezone, (re)learning steps or leech action are invalid
+/// - Unable to get a connection from the pool
+/// - The database writes fail
+pub async fn store_reloadable_settings(pool: &DbPool, config: &Config) -> Result<()> {
+	let settings = repo::Settings {
+		default_priority: config.default_priority,
+		timezone: config.timezone().map_err(|e| anyhow!(e))?,
+		interval_fuzz: config.interval_fuzz,
+		rating_scale: config.rating_scale,
+		minimum_interval: config.minimum_interval().map_err(|e| anyhow!(e))?,
+		learning_steps: repo::LearningSteps {
+			learning: config.learning_steps().map_err(|e| anyhow!(e))?,
+			relearning: config.relearning_steps().map_err(|e| anyhow!(e))?,
+			graduating_interval: config
+				.graduating_interval
+				.map(|days| chrono::Duration::days(days.into())),
+		},
+		priority_offsets_enabled: config.priority_offsets_enabled,
+		priority_adjustments: repo::PriorityAdjustments {
+			fail_boost: config.fail_priority_boost,
+			easy_decay: config.easy_priority_decay,
+		},
+		leech_action: config.leech_action().map_err(|e| anyhow!(e))?,
+	};
+	repo::set_settings(pool, &settings).await
+}
+
+/// Applies a reloaded config over the one in effect
+///
+/// The reloadable settings are taken from `new` and stored; every other
+/// setting that differs is logged as needing a restart, and keeps its
+/// `current` value.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `current` - The config in effect
+/// * `new` - The reloaded config
+///
+/// ### Returns
+///
+/// The config now in effect
+///
+/// ### Errors
+///
+/// Returns an error if the reloadable settings can't be stored
+pub async fn apply_config_changes(pool: &DbPool, current: &Config, new: &Config) -> Result<Config> {
+	let current_values = config_values(current)?;
+	let mut effective_values = current_values.clone();
+	for (setting, value) in config_values(new)? {
+		if current_values.get(&setting) == Some(&value) {
+			continue;
+		}
+		if RELOADABLE_SETTINGS.contains(&setting.as_str()) {
+			effective_values.insert(setting, value);
+		} else {
+			warn!(
+				"Ignoring the change to {}, which only takes effect after a restart",
+				setting
+			);
+		}
+	}
+
+	let mut effective: Config = serde_json::from_value(Value::Object(effective_values))?;
+	effective.config_dir = current.config_dir.clone();
+	store_reloadable_settings(pool, &effective).await?;
+
+	info!(
+		"Reloaded config: default_priority={}, timezone={}, interval_fuzz={}, rating_scale={}, minimum_interval={:?}, learning_steps={:?}, relearning_steps={:?}, graduating_interval={:?}, priority_offsets_enabled={}, fail_priority_boost={}, easy_priority_decay={}, leech_action={:?}",
+		effective.default_priority,
+		effective.timezone().unwrap_or(DEFAULT_TIMEZONE),
+		effective.interval_fuzz,
+		effective.rating_scale,
+		effective.minimum_interval,
+		effective.learning_steps,
+		effective.relearning_steps,
+		effective.graduating_interval,
+		effective.priority_offsets_enabled,
+		effective.fail_priority_boost,
+		effective.easy_priority_decay,
+		effective.leech_action().unwrap_or_default()
+	);
+
+	Ok(effective)
+}
+
+/// Watches the config file, applying its reloadable settings whenever it
+/// changes
+///
+/// The config directory is watched rather than the file itself, so the watch
+/// survives editors that save by replacing the file. A reload that fails
+/// (the file doesn't parse, or a setting is invalid) is logged and leaves the
+/// settings in effect unchanged. Must be called from within a Tokio runtime.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `args` - The settings from command line arguments and environment
+///   variables, which keep taking precedence over the file
+/// * `config` - The config the server started with
+///
+/// ### Returns
+///
+/// The watcher, which stops watching when dropped
+///
+/// ### Errors
+///
+/// Returns an error if the config has no config directory, or it can't be
+/// watched
+pub fn watch_config_file(
+	pool: Arc<DbPool>,
+	args: ConfigBuilder,
+	config: Config,
+) -> Result<RecommendedWatcher> {
+	let config_dir = config
+		.config_dir
+		.clone()
+		.ok_or_else(|| anyhow!("There is no config directory to watch"))?;
+
+	let (changed_tx, changed_rx) = mpsc::channel();
+	let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+		match event {
+			Ok(event) if is_config_file_change(&event) => {
+				// The receiver only goes away if a reload panicked
+				let _ = changed_tx.send(());
+			}
+			Ok(_) => {}
+			Err(e) => warn!("Error watching the config file: {}", e),
+		}
+	})?;
+	watcher.watch(&config_dir, RecursiveMode::NonRecursive)?;
+	info!(
+		"Watching {:?} for config changes",
+		config_dir.join(CONFIG_FILE_NAME)
+	);
+
+	// Reloads run on their own thread, which ends once the watcher (and with
+	// it the sending half of the channel) is dropped
+	let runtime = Handle::current();
+	std::thread::spawn(move || {
+		let mut current = config;
+		while changed_rx.recv().is_ok() {
+			// Let the rest of a save land, then reload once for all of it
+			std::thread::sleep(RELOAD_DEBOUNCE);
+			while changed_rx.try_recv().is_ok() {}
+
+			info!("Config file changed, reloading");
+			let new = match load_config(args.clone(), current.config_dir.clone()) {
+				Ok(new) => new,
+				Err(e) => {
+					error!("Not reloading the config file: {}", e);
+					continue;
+				}
+			};
+			match runtime.block_on(apply_config_changes(&pool, &current, &new)) {
+				Ok(effective) => current = effective,
+				Err(e) => error!("Failed to apply the reloaded config: {}", e),
+			}
+		}
+	});
+
+	Ok(watcher)
+}
+
+/// Every setting in a config, by its name in the config file
+fn config_values(config: &Config) -> Result<Map<String, Value>> {
+	match serde_json::to_value(config)? {
+		Value::Object(values) => Ok(values),
+		other => Err(anyhow!(
+			"Config serialized to {} rather than an object",
+			other
+		)),
+	}
+}
+
+/// Whether a file system event in the config directory changed the config file
+fn is_config_file_change(event: &Event) -> bool {
+	matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+		&& event
+			.paths
+			.iter()
+			.any(|path| path.file_name() == Some(OsStr::new(CONFIG_FILE_NAME)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::LeechAction;
+	use crate::dto::CreateItemDto;
+	use crate::handlers::{Json, create_item_handler};
+	use crate::models::ItemTypeId;
+	use crate::repo::tests::setup_test_db;
+	use axum::extract::State;
+	use serde_json::json;
+	use tempfile::tempdir;
+
+	/// Creates an item through the handler and returns its first card's priority
+	async fn new_card_priority(pool: &Arc<DbPool>, item_type_id: &ItemTypeId, title: &str) -> f32 {
+		let payload = CreateItemDto {
+			id: None,
+			item_type_id: item_type_id.clone(),
+			title: title.to_string(),
+			item_data: json!({"front": "F", "back": "B"}),
+			priority: 0.5,
+		};
+		let item = create_item_handler(State(pool.clone()), Json(payload))
+			.await
+			.unwrap();
+		repo::get_cards_for_item(pool, &item.0.item.get_id()).unwrap()[0].get_priority()
+	}
+
+	#[tokio::test]
+	async fn test_handlers_pick_up_applied_config_changes() {
+		let pool = setup_test_db();
+		let item_type = repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		let current = ConfigBuilder::default().build();
+		store_reloadable_settings(&pool, &current).await.unwrap();
+		assert_eq!(
+			new_card_priority(&pool, &item_type.get_id(), "Before").await,
+			0.5
+		);
+
+		let new = Config {
+			default_priority: 0.8,
+			timezone: Some("Australia/Melbourne".to_string()),
+			..current.clone()
+		};
+		let effective = apply_config_changes(&pool, &current, &new).await.unwrap();
+
+		assert_eq!(effective.default_priority, 0.8);
+		assert_eq!(
+			new_card_priority(&pool, &item_type.get_id(), "After").await,
+			0.8
+		);
+		assert_eq!(
+			repo::get_settings(&pool).unwrap().timezone,
+			"Australia/Melbourne".parse::<chrono_tz::Tz>().unwrap()
+		);
+	}
+
+	#[tokio::test]
+	async fn test_restart_only_changes_are_ignored() {
+		let pool = setup_test_db();
+		let current = ConfigBuilder::default().build();
+
+		let new = Config {
+			database_url: "elsewhere.db".to_string(),
+			port: current.port + 1,
+			interval_fuzz: 0.1,
+			rating_scale: 2,
+			minimum_interval: Some("1d".to_string()),
+			graduating_interval: Some(3),
+			priority_offsets_enabled: false,
+			fail_priority_boost: 0.2,
+			easy_priority_decay: 0.05,
+			leech_action: Some("tag".to_string()),
+			leech_tag: Some("hard".to_string()),
+			..current.clone()
+		};
+		let effective = apply_config_changes(&pool, &current, &new).await.unwrap();
+
+		assert_eq!(effective.database_url, current.database_url);
+		assert_eq!(effective.port, current.port);
+		assert_eq!(effective.interval_fuzz, 0.1);
+		assert_eq!(effective.rating_scale, 2);
+		assert_eq!(effective.graduating_interval, Some(3));
+		assert!(!effective.priority_offsets_enabled);
+
+		let settings = repo::get_settings(&pool).unwrap();
+		assert_eq!(settings.interval_fuzz, 0.1);
+		assert_eq!(settings.rating_scale, 2);
+		assert_eq!(settings.minimum_interval, chrono::Duration::days(1));
+		assert_eq!(
+			settings.learning_steps.graduating_interval,
+			Some(chrono::Duration::days(3))
+		);
+		assert!(!settings.priority_offsets_enabled);
+		assert_eq!(
+			settings.priority_adjustments,
+			repo::PriorityAdjustments {
+				fail_boost: 0.2,
+				easy_decay: 0.05,
+			}
+		);
+		assert_eq!(settings.leech_action, LeechAction::Tag("hard".to_string()));
+	}
+
+	#[tokio::test]
+	async fn test_unlisted_settings_are_restart_only() {
+		let pool = setup_test_db();
+		let current = ConfigBuilder::default().build();
+
+		// Settings without a hand-written check are still ignored
+		let new = Config {
+			compression: !current.compression,
+			compression_min_size: current.compression_min_size + 1,
+			default_priority: 0.7,
+			..current.clone()
+		};
+		let effective = apply_config_changes(&pool, &current, &new).await.unwrap();
+
+		assert_eq!(effective.compression, current.compression);
+		assert_eq!(effective.compression_min_size, current.compression_min_size);
+		assert_eq!(effective.default_priority, 0.7);
+		assert_eq!(repo::get_settings(&pool).unwrap().default_priority, 0.7);
+	}
+
+	#[test]
+	fn test_load_config_keeps_args_precedence() {
+		let config_dir = tempdir().unwrap();
+		let config_path = config_dir.path().join(CONFIG_FILE_NAME);
+		std::fs::write(
+			&config_path,
+			"default_priority = 0.3\ntimezone = \"Europe/London\"\n",
+		)
+		.unwrap();
+		let args = ConfigBuilder {
+			default_priority: Some(0.6),
+			..Default::default()
+		};
+
+		let config = load_config(args.clone(), Some(config_dir.path().to_path_buf())).unwrap();
+		assert_eq!(config.default_priority, 0.6);
+		assert_eq!(config.timezone.as_deref(), Some("Europe/London"));
+
+		// An invalid file is rejected rather than half applied
+		std::fs::write(&config_path, "interval_fuzz = 2.0\n").unwrap();
+		let err = load_config(args, Some(config_dir.path().to_path_buf())).unwrap_err();
+		assert!(err.contains("interval_fuzz"), "got: {}", err);
+	}
+
+	#[tokio::test]
+	async fn test_watcher_reloads_on_file_change() {
+		let pool = setup_test_db();
+		let config_dir = tempdir().unwrap();
+		let config_path = config_dir.path().join(CONFIG_FILE_NAME);
+		std::fs::write(&config_path, "default_priority = 0.5\n").unwrap();
+
+		let config = load_config(
+			ConfigBuilder::default(),
+			Some(config_dir.path().to_path_buf()),
+		)
+		.unwrap();
+		store_reloadable_settings(&pool, &config).await.unwrap();
+		let _watcher = watch_config_file(pool.clone(), ConfigBuilder::default(), config).unwrap();
+
+		std::fs::write(&config_path, "default_priority = 0.9\n").unwrap();
+
+		let mut reloaded = false;
+		for _ in 0..100 {
+			tokio::time::sleep(Duration::from_millis(50)).await;
+			if repo::get_settings(&pool).unwrap().default_priority == 0.9 {
+				reloaded = true;
+				break;
+			}
+		}
+		assert!(reloaded, "the watcher never reloaded the config file");
+	}
+}
@@ -1,7 +1,8 @@
 use diesel::Connection;
 use diesel::RunQueryDsl;
+use diesel::connection::SimpleConnection;
 use diesel::query_dsl::load_dsl::ExecuteDsl;
-use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::r2d2::{ConnectionManager, CustomizeConnection, Pool};
 use diesel::result::{DatabaseErrorKind, Error as DieselError};
 /// Database connection module
 ///
@@ -12,6 +13,8 @@ use diesel::sqlite::SqliteConnection;
 use std::time::Duration;
 use tokio::time::sleep;
 
+use crate::config;
+
 /// Type alias for a connection pool of SQLite connections
 ///
 /// This type is used throughout the application to represent a pool of database
@@ -19,7 +22,64 @@ use tokio::time::sleep;
 /// and helps manage database resources.
 pub type DbPool = Pool<ConnectionManager<SqliteConnection>>;
 
-/// Initializes a new database connection pool
+/// How long (in milliseconds) SQLite waits on a locked database before
+/// returning `SQLITE_BUSY`
+pub(crate) const BUSY_TIMEOUT_MS: u64 = 5000;
+
+/// Configures every new pooled connection before it is handed out
+///
+/// Runs on each connection the pool opens, so every checkout has:
+/// - `busy_timeout`, so SQLite itself waits on a held lock instead of
+///   failing immediately with "database is locked". This is set first, as
+///   switching to WAL needs a lock that connections opened together at
+///   startup contend for.
+/// - `journal_mode=WAL`, letting readers proceed alongside a writer
+///   (in-memory databases ignore this and stay in `memory` mode)
+/// - `foreign_keys=ON`, which SQLite leaves off per connection by default
+#[derive(Debug, Clone, Copy)]
+pub struct SqlitePragmas;
+
+impl CustomizeConnection<SqliteConnection, diesel::r2d2::Error> for SqlitePragmas {
+	fn on_acquire(&self, conn: &mut SqliteConnection) -> Result<(), diesel::r2d2::Error> {
+		conn.batch_execute(&format!(
+			"PRAGMA busy_timeout = {}; PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;",
+			BUSY_TIMEOUT_MS
+		))
+		.map_err(diesel::r2d2::Error::QueryError)
+	}
+}
+
+/// Options controlling the size and behaviour of the connection pool
+///
+/// [`PoolOptions::default`] uses [`config::DEFAULT_POOL_SIZE`] and
+/// [`config::DEFAULT_CONNECTION_TIMEOUT_SECS`], which is what [`init_pool`] uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolOptions {
+	/// Maximum number of connections the pool will open
+	pub max_size: u32,
+	/// How long to wait for a free connection before giving up
+	pub connection_timeout: Duration,
+}
+
+impl Default for PoolOptions {
+	fn default() -> Self {
+		PoolOptions {
+			max_size: config::DEFAULT_POOL_SIZE,
+			connection_timeout: Duration::from_secs(config::DEFAULT_CONNECTION_TIMEOUT_SECS),
+		}
+	}
+}
+
+impl From<&config::Config> for PoolOptions {
+	fn from(config: &config::Config) -> Self {
+		PoolOptions {
+			max_size: config.pool_size,
+			connection_timeout: Duration::from_secs(config.connection_timeout_secs),
+		}
+	}
+}
+
+/// Initializes a new database connection pool with default options
 ///
 /// ### Arguments
 ///
@@ -37,28 +97,155 @@ pub type DbPool = Pool<ConnectionManager<SqliteConnection>>;
 ///
 /// ```
 /// use hippocampus::db;
-/// use std::fs;
 ///
-/// let pool = db::init_pool("database.db");
-///
-/// // Clean up the test database
-/// fs::remove_file("database.db").ok();
+/// let pool = db::init_pool(":memory:");
 /// ```
 pub fn init_pool(database_url: &str) -> DbPool {
+	init_pool_with_options(database_url, PoolOptions::default())
+}
+
+/// Initializes a new database connection pool
+///
+/// ### Arguments
+///
+/// * `database_url` - A string slice containing the database connection URL
+/// * `options` - The pool size and connection timeout to use
+///
+/// ### Returns
+///
+/// A new connection pool configured with the provided database URL and options
+///
+/// ### Panics
+///
+/// This function will panic if the connection pool cannot be created
+/// (including if `options.max_size` is zero)
+pub fn init_pool_with_options(database_url: &str, options: PoolOptions) -> DbPool {
 	// Create a new connection manager for SQLite
 	let manager = ConnectionManager::<SqliteConnection>::new(database_url);
 
-	// Build a connection pool with default configuration
+	// Build a connection pool. Checkouts fail after `connection_timeout`
+	// rather than hanging when every connection is in use, and every new
+	// connection gets the pragmas from `SqlitePragmas`.
 	// This will panic if the pool cannot be created
 	Pool::builder()
+		.max_size(options.max_size)
+		.connection_timeout(options.connection_timeout)
+		.connection_customizer(Box::new(SqlitePragmas))
 		.build(manager)
 		.expect("Failed to create DB pool.")
 }
 
-// Constants for retry configuration
+// Constants for the default retry policy
 const INITIAL_DELAY_MS: u64 = 100;
+const MAX_DELAY_MS: u64 = 2000;
 const MAX_RETRIES: u32 = 5;
 
+/// Controls how transient database errors are retried
+///
+/// The delay before retry `n` (counting from 1) is
+/// `min(initial_delay * 2^(n-1), max_delay)`. With `jitter` enabled, a
+/// random delay between half and all of that value is used instead, so
+/// requests that hit the same lock at the same moment don't all wake up and
+/// collide again together.
+///
+/// Only errors that [`is_retryable_error`] accepts (lock contention) are
+/// retried; everything else, such as constraint violations, is returned
+/// immediately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+	/// Maximum number of retries after the first attempt
+	pub max_retries: u32,
+	/// Delay before the first retry
+	pub initial_delay: Duration,
+	/// Upper bound on the delay between any two attempts
+	pub max_delay: Duration,
+	/// Whether to randomize each delay
+	pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		RetryPolicy {
+			max_retries: MAX_RETRIES,
+			initial_delay: Duration::from_millis(INITIAL_DELAY_MS),
+			max_delay: Duration::from_millis(MAX_DELAY_MS),
+			jitter: true,
+		}
+	}
+}
+
+impl RetryPolicy {
+	/// Returns the un-jittered backoff before the given retry
+	///
+	/// ### Arguments
+	///
+	/// * `retry` - The retry number, starting at 1
+	///
+	/// ### Returns
+	///
+	/// The exponential backoff for that retry, capped at `max_delay`
+	pub fn backoff(&self, retry: u32) -> Duration {
+		let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+		self.initial_delay
+			.saturating_mul(factor)
+			.min(self.max_delay)
+	}
+
+	/// Returns the delay to actually sleep before the given retry
+	///
+	/// ### Arguments
+	///
+	/// * `retry` - The retry number, starting at 1
+	///
+	/// ### Returns
+	///
+	/// The backoff for that retry, randomized into `[backoff / 2, backoff]`
+	/// when `jitter` is enabled
+	pub fn delay(&self, retry: u32) -> Duration {
+		let backoff = self.backoff(retry);
+		if !self.jitter {
+			return backoff;
+		}
+		let half = backoff / 2;
+		half + half.mul_f64(rand::random::<f64>())
+	}
+}
+
+/// Runs `op` until it succeeds, fails with a non-retryable error, or the
+/// policy's retry budget is spent, sleeping between attempts.
+async fn retry_with_policy<T, F>(policy: &RetryPolicy, mut op: F) -> Result<T, DieselError>
+where
+	F: FnMut() -> Result<T, DieselError>,
+{
+	let mut retries = 0;
+
+	loop {
+		match op() {
+			Ok(val) => {
+				if retries > 0 {
+					tracing::debug!(retries, "Database operation succeeded after retrying");
+				}
+				return Ok(val);
+			}
+			Err(e) => {
+				if retries >= policy.max_retries || !is_retryable_error(&e) {
+					return Err(e);
+				}
+				retries += 1;
+				let delay = policy.delay(retries);
+				tracing::warn!(
+					error = %e,
+					retry = retries,
+					max_retries = policy.max_retries,
+					delay_ms = delay.as_millis() as u64,
+					"Database operation failed with a transient error, retrying"
+				);
+				sleep(delay).await;
+			}
+		}
+	}
+}
+
 /// Checks if a Diesel error is likely temporary and worth retrying.
 fn is_retryable_error(err: &DieselError) -> bool {
 	match err {
@@ -87,7 +274,7 @@ fn is_retryable_error(err: &DieselError) -> bool {
 pub trait ExecuteWithRetry:
 	RunQueryDsl<SqliteConnection> + ExecuteDsl<SqliteConnection> + Clone + Send + Sync + 'static
 {
-	/// Executes the query, retrying with exponential backoff if a transient error occurs.
+	/// Executes the query, retrying with the default [`RetryPolicy`] if a transient error occurs.
 	///
 	/// ### Arguments
 	///
@@ -104,32 +291,29 @@ pub trait ExecuteWithRetry:
 	/// depending on the Diesel operation.
 	#[allow(async_fn_in_trait)]
 	async fn execute_with_retry(&self, conn: &mut SqliteConnection) -> Result<usize, DieselError> {
-		let mut attempts = 0;
-		let mut delay = Duration::from_millis(INITIAL_DELAY_MS);
-
-		loop {
-			// Clone the query builder for this attempt, as `execute` consumes it.
-			let query_clone = self.clone();
-
-			// Execute the query
-			let result = query_clone.execute(conn);
-
-			match result {
-				Ok(rows_affected) => return Ok(rows_affected),
-				Err(e) => {
-					if attempts >= MAX_RETRIES || !is_retryable_error(&e) {
-						// If max retries reached or error is not retryable, return the error
-						return Err(e);
-					}
-					// Log the retry attempt (optional, but good practice)
-					// tracing::warn!(error = ?e, attempt = attempts + 1, delay_ms = delay.as_millis(), "Query failed, retrying after delay...");
-
-					attempts += 1;
-					sleep(delay).await;
-					delay *= 2; // Double the delay for the next attempt
-				}
-			}
-		}
+		self.execute_with_retry_policy(conn, &RetryPolicy::default())
+			.await
+	}
+
+	/// Executes the query, retrying according to `policy` if a transient error occurs.
+	///
+	/// ### Arguments
+	///
+	/// * `conn` - A mutable reference to the SQLite connection.
+	/// * `policy` - How many times to retry and how long to wait between attempts.
+	///
+	/// ### Returns
+	///
+	/// A `Result` containing the number of affected rows on success, or a `DieselError`
+	/// if the operation fails after exhausting retries or encounters a non-retryable error.
+	#[allow(async_fn_in_trait)]
+	async fn execute_with_retry_policy(
+		&self,
+		conn: &mut SqliteConnection,
+		policy: &RetryPolicy,
+	) -> Result<usize, DieselError> {
+		// Clone the query builder for each attempt, as `execute` consumes it.
+		retry_with_policy(policy, || self.clone().execute(conn)).await
 	}
 }
 
@@ -151,7 +335,7 @@ impl<T> ExecuteWithRetry for T where
 /// [`deferred_transaction_with_retry`]: that holds only SHARED on the
 /// no-write path so concurrent readers don't serialize on the write lock.
 ///
-/// Uses the same exponential backoff strategy as `ExecuteWithRetry`.
+/// Uses the default [`RetryPolicy`], the same as `ExecuteWithRetry`.
 /// The closure receives a mutable reference to the connection and must return
 /// a `Result<T, diesel::result::Error>`.
 ///
@@ -170,22 +354,10 @@ pub async fn transaction_with_retry<T, F>(
 where
 	F: FnMut(&mut SqliteConnection) -> Result<T, DieselError>,
 {
-	let mut attempts = 0;
-	let mut delay = Duration::from_millis(INITIAL_DELAY_MS);
-
-	loop {
-		match conn.immediate_transaction(&mut f) {
-			Ok(val) => return Ok(val),
-			Err(e) => {
-				if attempts >= MAX_RETRIES || !is_retryable_error(&e) {
-					return Err(e);
-				}
-				attempts += 1;
-				sleep(delay).await;
-				delay *= 2;
-			}
-		}
-	}
+	retry_with_policy(&RetryPolicy::default(), || {
+		conn.immediate_transaction(&mut f)
+	})
+	.await
 }
 
 /// Runs a closure inside a DEFERRED `transaction` with retry on transient
@@ -225,22 +397,7 @@ pub async fn deferred_transaction_with_retry<T, F>(
 where
 	F: FnMut(&mut SqliteConnection) -> Result<T, DieselError>,
 {
-	let mut attempts = 0;
-	let mut delay = Duration::from_millis(INITIAL_DELAY_MS);
-
-	loop {
-		match conn.transaction(&mut f) {
-			Ok(val) => return Ok(val),
-			Err(e) => {
-				if attempts >= MAX_RETRIES || !is_retryable_error(&e) {
-					return Err(e);
-				}
-				attempts += 1;
-				sleep(delay).await;
-				delay *= 2;
-			}
-		}
-	}
+	retry_with_policy(&RetryPolicy::default(), || conn.transaction(&mut f)).await
 }
 
 #[cfg(test)]
@@ -277,6 +434,108 @@ mod tests {
 		assert!(result.is_ok(), "Should be able to execute a simple query");
 	}
 
+	/// Tests that the pool honours the configured options
+	///
+	/// This test verifies that:
+	/// 1. The pool reports the configured maximum size
+	/// 2. The pool uses the configured connection timeout
+	/// 3. Checking out more connections than the maximum fails after the timeout
+	#[test]
+	fn test_init_pool_with_options() {
+		let options = PoolOptions {
+			max_size: 2,
+			connection_timeout: Duration::from_millis(100),
+		};
+		let pool = init_pool_with_options(":memory:", options);
+
+		assert_eq!(pool.max_size(), 2);
+		assert_eq!(pool.connection_timeout(), Duration::from_millis(100));
+
+		let _first = pool.get().unwrap();
+		let _second = pool.get().unwrap();
+		assert!(
+			pool.get().is_err(),
+			"Checkout should fail once the pool is exhausted"
+		);
+	}
+
+	#[derive(QueryableByName)]
+	struct JournalMode {
+		#[diesel(sql_type = diesel::sql_types::Text)]
+		journal_mode: String,
+	}
+
+	#[derive(QueryableByName)]
+	struct PragmaValue {
+		#[diesel(sql_type = diesel::sql_types::BigInt)]
+		value: i64,
+	}
+
+	/// Tests that pooled connections are configured by `SqlitePragmas`
+	///
+	/// This test verifies that a fresh pooled connection to a file database:
+	/// 1. Reports WAL journal mode
+	/// 2. Has the busy timeout set
+	/// 3. Has foreign key enforcement enabled
+	#[test]
+	fn test_pooled_connection_pragmas() {
+		// WAL needs a real file; in-memory databases always report "memory"
+		let dir = tempfile::tempdir().unwrap();
+		let database_url = dir.path().join("pragmas.db");
+		let pool = init_pool(database_url.to_str().unwrap());
+		let mut conn = pool.get().unwrap();
+
+		let mode: JournalMode = diesel::sql_query("PRAGMA journal_mode")
+			.get_result(&mut *conn)
+			.unwrap();
+		assert_eq!(mode.journal_mode, "wal");
+
+		let timeout: PragmaValue =
+			diesel::sql_query("SELECT timeout AS value FROM pragma_busy_timeout")
+				.get_result(&mut *conn)
+				.unwrap();
+		assert_eq!(timeout.value, BUSY_TIMEOUT_MS as i64);
+
+		let foreign_keys: PragmaValue =
+			diesel::sql_query("SELECT foreign_keys AS value FROM pragma_foreign_keys")
+				.get_result(&mut *conn)
+				.unwrap();
+		assert_eq!(foreign_keys.value, 1);
+	}
+
+	/// Tests that connections configured together on a new database don't
+	/// lock each other out
+	///
+	/// This test verifies that:
+	/// 1. `SqlitePragmas` succeeds on every one of several connections to a
+	///    fresh file database configured at once, as the pool does at startup,
+	///    rather than failing some with "database is locked"
+	#[test]
+	fn test_pragmas_on_connections_opened_together() {
+		for _ in 0..5 {
+			let dir = tempfile::tempdir().unwrap();
+			let database_url = dir.path().join("startup.db");
+			let database_url = database_url.to_str().unwrap();
+			let barrier = std::sync::Barrier::new(8);
+
+			std::thread::scope(|scope| {
+				let handles = (0..8)
+					.map(|_| {
+						scope.spawn(|| {
+							let mut conn = SqliteConnection::establish(database_url).unwrap();
+							barrier.wait();
+							SqlitePragmas.on_acquire(&mut conn)
+						})
+					})
+					.collect::<Vec<_>>();
+				for handle in handles {
+					let result = handle.join().unwrap();
+					assert!(result.is_ok(), "{:?}", result.err());
+				}
+			});
+		}
+	}
+
 	#[test]
 	fn test_is_retryable_error_serialization_failure() {
 		let err = DieselError::DatabaseError(
@@ -327,4 +586,125 @@ mod tests {
 		);
 		assert!(!is_retryable_error(&err));
 	}
+
+	#[test]
+	fn test_retry_policy_backoff_doubles_up_to_max_delay() {
+		let policy = RetryPolicy {
+			max_retries: 10,
+			initial_delay: Duration::from_millis(100),
+			max_delay: Duration::from_millis(1000),
+			jitter: false,
+		};
+
+		assert_eq!(policy.backoff(1), Duration::from_millis(100));
+		assert_eq!(policy.backoff(2), Duration::from_millis(200));
+		assert_eq!(policy.backoff(3), Duration::from_millis(400));
+		assert_eq!(policy.backoff(4), Duration::from_millis(800));
+		assert_eq!(policy.backoff(5), Duration::from_millis(1000));
+		assert_eq!(policy.backoff(100), Duration::from_millis(1000));
+		assert_eq!(policy.delay(3), policy.backoff(3));
+	}
+
+	proptest::proptest! {
+		#[test]
+		fn prop_retry_policy_jittered_delay_within_bounds(retry in 1u32..64) {
+			let policy = RetryPolicy::default();
+			let backoff = policy.backoff(retry);
+			let delay = policy.delay(retry);
+
+			proptest::prop_assert!(delay >= backoff / 2);
+			proptest::prop_assert!(delay <= backoff);
+		}
+	}
+
+	/// Opens two plain connections (no busy timeout) to a fresh file database
+	/// containing a single table with a unique column
+	fn two_connections(dir: &tempfile::TempDir) -> (SqliteConnection, SqliteConnection) {
+		let database_url = dir.path().join("retry.db");
+		let database_url = database_url.to_str().unwrap();
+		let mut first = SqliteConnection::establish(database_url).unwrap();
+		first
+			.batch_execute("CREATE TABLE entries (value INTEGER NOT NULL UNIQUE)")
+			.unwrap();
+		let second = SqliteConnection::establish(database_url).unwrap();
+		(first, second)
+	}
+
+	/// Tests that a write blocked by another connection's lock succeeds once
+	/// the lock is released, within the retry budget
+	#[tokio::test]
+	async fn test_execute_with_retry_recovers_from_transient_lock() {
+		let dir = tempfile::tempdir().unwrap();
+		let (mut holder, mut writer) = two_connections(&dir);
+		let policy = RetryPolicy {
+			max_retries: 10,
+			initial_delay: Duration::from_millis(20),
+			max_delay: Duration::from_millis(50),
+			jitter: true,
+		};
+
+		// Take the write lock so the first attempt fails with "database is locked"
+		holder.batch_execute("BEGIN IMMEDIATE").unwrap();
+		let insert = diesel::sql_query("INSERT INTO entries (value) VALUES (1)");
+		assert!(is_retryable_error(
+			&insert.clone().execute(&mut writer).unwrap_err()
+		));
+
+		let (result, ()) = tokio::join!(
+			insert.execute_with_retry_policy(&mut writer, &policy),
+			async {
+				sleep(Duration::from_millis(100)).await;
+				holder.batch_execute("COMMIT").unwrap();
+			}
+		);
+
+		assert_eq!(result.unwrap(), 1);
+	}
+
+	/// Tests that the error is returned once the retry budget is spent
+	#[tokio::test]
+	async fn test_execute_with_retry_gives_up_after_max_retries() {
+		let dir = tempfile::tempdir().unwrap();
+		let (mut holder, mut writer) = two_connections(&dir);
+		let policy = RetryPolicy {
+			max_retries: 2,
+			initial_delay: Duration::from_millis(1),
+			max_delay: Duration::from_millis(1),
+			jitter: false,
+		};
+
+		holder.batch_execute("BEGIN IMMEDIATE").unwrap();
+		let result = diesel::sql_query("INSERT INTO entries (value) VALUES (1)")
+			.execute_with_retry_policy(&mut writer, &policy)
+			.await;
+
+		assert!(is_retryable_error(&result.unwrap_err()));
+	}
+
+	/// Tests that constraint violations fail fast instead of being retried
+	#[tokio::test]
+	async fn test_execute_with_retry_does_not_retry_constraint_violation() {
+		let dir = tempfile::tempdir().unwrap();
+		let (_, mut writer) = two_connections(&dir);
+		let policy = RetryPolicy {
+			max_retries: 5,
+			initial_delay: Duration::from_secs(10),
+			max_delay: Duration::from_secs(10),
+			jitter: false,
+		};
+		let insert = diesel::sql_query("INSERT INTO entries (value) VALUES (1)");
+		insert.clone().execute(&mut writer).unwrap();
+
+		let started = std::time::Instant::now();
+		let result = insert.execute_with_retry_policy(&mut writer, &policy).await;
+
+		assert!(matches!(
+			result,
+			Err(DieselError::DatabaseError(
+				DatabaseErrorKind::UniqueViolation,
+				_
+			))
+		));
+		assert!(started.elapsed() < policy.initial_delay);
+	}
 }
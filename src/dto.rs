@@ -1,14 +1,23 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-use crate::models::{CardEventFnName, CardId, Item, ItemId, ItemTypeId, OrderIndex, TagId};
+use crate::models::{
+	Card, CardEventFnName, CardId, Item, ItemId, ItemType, ItemTypeId, OrderIndex, Rating, Session,
+	SessionId, Tag, TagId,
+};
 
 /// Data transfer object for creating a new item
 ///
 /// This struct is used to deserialize JSON requests for creating items.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CreateItemDto {
+	/// A client-chosen ID (a UUID, optionally prefixed with `item-`), making
+	/// the create idempotent: retrying with the same ID returns the existing
+	/// item instead of creating a duplicate
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub id: Option<ItemId>,
+
 	/// The item type ID
 	pub item_type_id: ItemTypeId,
 
@@ -23,6 +32,29 @@ pub struct CreateItemDto {
 	pub priority: f32,
 }
 
+/// Data transfer object for previewing the cards an item would get
+///
+/// This struct is used to deserialize the JSON body of
+/// `POST /items/preview_cards`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PreviewCardsDto {
+	/// The item type the item would have
+	pub item_type_id: ItemTypeId,
+
+	/// The data the item would have
+	pub item_data: serde_json::Value,
+}
+
+/// Data transfer object for changing an item's type
+///
+/// This struct is used to deserialize the JSON body of
+/// `PUT /items/{item_id}/type`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChangeItemTypeDto {
+	/// The item type to move the item to
+	pub item_type_id: ItemTypeId,
+}
+
 /// Data transfer object for updating an item
 ///
 /// This struct is used to deserialize JSON requests for updating items.
@@ -48,8 +80,43 @@ pub struct CreateReviewDto {
 	/// The ID of the card being reviewed
 	pub card_id: CardId,
 
-	/// The rating given during the review (typically 1-3)
+	/// The rating given during the review, 1-4; the handler turns it into a
+	/// [`Rating`], so an invalid one can be reported as such
 	pub rating: i32,
+
+	/// The running session to record the review in, if any
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub session_id: Option<SessionId>,
+
+	/// What was actually answered, kept with the review for looking back over
+	/// mistakes
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub response: Option<String>,
+}
+
+/// One past review in a log imported with `POST /reviews/import`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImportReviewDto {
+	/// The ID of the card that was reviewed
+	pub card_id: CardId,
+
+	/// The rating given, 1-4; the handler turns it into a [`Rating`], so an
+	/// invalid one can be reported as such
+	pub rating: i32,
+
+	/// When the review happened
+	pub timestamp: DateTime<Utc>,
+}
+
+/// Query parameters for recording a review
+///
+/// This struct is used to deserialize query parameters for `POST /reviews`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct CreateReviewQueryDto {
+	/// Whether this is a cram review, which is logged without rescheduling
+	/// the card
+	pub cram: bool,
 }
 
 /// Data transfer object for creating a new item type
@@ -62,6 +129,18 @@ pub struct CreateItemTypeDto {
 	/// The review function to use for scheduling (defaults to "fsrs" if not provided)
 	#[serde(default)]
 	pub review_function: Option<String>,
+	/// A JSON Schema that item data of this type must satisfy (no validation if not provided)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub item_data_schema: Option<serde_json::Value>,
+	/// The priority given to new cards of this type, between 0 and 1 (the
+	/// configured default if not provided)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub default_priority: Option<f32>,
+	/// Tuning parameters for the scheduler of this type, see
+	/// [`SchedulerConfig`](crate::models::SchedulerConfig) (the defaults if
+	/// not provided)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub scheduler_config: Option<serde_json::Value>,
 }
 
 /// Data transfer object for updating an item type
@@ -71,6 +150,160 @@ pub struct CreateItemTypeDto {
 pub struct UpdateItemTypeDto {
 	/// The new review function for the item type
 	pub review_function: Option<String>,
+	/// The new item data schema: absent leaves it unchanged, `null` removes it
+	#[serde(
+		default,
+		deserialize_with = "deserialize_present",
+		skip_serializing_if = "Option::is_none"
+	)]
+	pub item_data_schema: Option<Option<serde_json::Value>>,
+	/// The new default priority for new cards: absent leaves it unchanged,
+	/// `null` falls back to the configured default
+	#[serde(
+		default,
+		deserialize_with = "deserialize_present",
+		skip_serializing_if = "Option::is_none"
+	)]
+	pub default_priority: Option<Option<f32>>,
+	/// The new scheduler tuning parameters: absent leaves them unchanged,
+	/// `null` goes back to the defaults
+	#[serde(
+		default,
+		deserialize_with = "deserialize_present",
+		skip_serializing_if = "Option::is_none"
+	)]
+	pub scheduler_config: Option<Option<serde_json::Value>>,
+}
+
+/// Deserializes a field that is present in the input as `Some`, so that an
+/// explicit `null` becomes `Some(None)` while an absent field (via
+/// `#[serde(default)]`) stays `None`
+fn deserialize_present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+	T: Deserialize<'de>,
+	D: serde::Deserializer<'de>,
+{
+	T::deserialize(deserializer).map(Some)
+}
+
+/// Query parameters for listing item types
+///
+/// This struct is used to deserialize query parameters for `GET /item_types`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ListItemTypesQueryDto {
+	/// Whether to include the number of items and cards of each type
+	pub include_counts: bool,
+}
+
+/// Query parameters for deleting an item type
+///
+/// This struct is used to deserialize query parameters for
+/// `DELETE /item_types/{id}`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct DeleteItemTypeQueryDto {
+	/// Whether to delete the item type's items (and their cards and reviews)
+	/// too, rather than refusing while it has any
+	pub cascade: bool,
+}
+
+/// An item type as returned by `GET /item_types`
+///
+/// Serializes to the plain item type, plus `item_count` and `card_count`
+/// when counts were requested.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ItemTypeWithCounts {
+	/// The item type itself
+	#[serde(flatten)]
+	pub item_type: ItemType,
+
+	/// The number of items of this type, if counts were requested
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub item_count: Option<i64>,
+
+	/// The number of cards belonging to items of this type, if counts were requested
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub card_count: Option<i64>,
+}
+
+/// Related records `GET /items` can include in each item, see
+/// [`ListItemsQueryDto`]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemExpansion {
+	/// The name of the item's item type
+	ItemType,
+}
+
+/// Query parameters for listing items, alongside the [`GetQueryDto`] filters
+///
+/// This struct is used to deserialize query parameters for `GET /items`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ListItemsQueryDto {
+	/// What to include in each item besides its own fields, if anything
+	pub expand: Option<ItemExpansion>,
+}
+
+/// An item as returned by `GET /items`
+///
+/// Serializes to the plain item, plus `item_type_name` when
+/// `expand=item_type` was requested.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ItemWithItemType {
+	/// The item itself
+	#[serde(flatten)]
+	pub item: Item,
+
+	/// The name of the item's item type, if it was requested
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub item_type_name: Option<String>,
+}
+
+/// An item as returned by `POST /items`
+///
+/// Serializes to the plain item, plus the `warnings` about its data found by
+/// `validate_item_data_warnings`, which didn't stop it being created.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreatedItem {
+	/// The item itself
+	#[serde(flatten)]
+	pub item: Item,
+
+	/// Things about the item's data that are probably a mistake, if any
+	#[serde(default)]
+	pub warnings: Vec<String>,
+}
+
+/// An item together with its cards and tags, as returned by
+/// `GET /items/{item_id}/full`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ItemWithCardsAndTags {
+	/// The item itself
+	pub item: Item,
+
+	/// The item's cards, in card index order
+	pub cards: Vec<Card>,
+
+	/// The tags on the item
+	pub tags: Vec<Tag>,
+}
+
+/// A review session together with the stats derived from its reviews
+///
+/// Serializes to the plain session, plus `card_count` and `duration_secs`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SessionWithStats {
+	/// The session itself
+	#[serde(flatten)]
+	pub session: Session,
+
+	/// The number of distinct cards reviewed in the session
+	pub card_count: i64,
+
+	/// How long the session lasted in seconds, or None if it is still running
+	pub duration_secs: Option<i64>,
 }
 
 /// Data transfer object for creating a new card
@@ -95,6 +328,34 @@ pub struct CreateTagDto {
 
 	/// The visibility of the tag
 	pub visible: bool,
+
+	/// The tag to create this one under, if any
+	#[serde(default)]
+	pub parent_id: Option<TagId>,
+}
+
+/// Query parameters for listing an item's tags
+///
+/// This struct is used to deserialize query parameters for
+/// `GET /items/{item_id}/tags`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ListItemTagsQueryDto {
+	/// Whether to include the ancestors of the item's tags too
+	pub include_ancestors: bool,
+}
+
+/// How much a tag is used, as returned by `GET /tags/{tag_id}/stats`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TagStats {
+	/// The number of items with the tag
+	pub item_count: i64,
+
+	/// The number of cards belonging to items with the tag
+	pub card_count: i64,
+
+	/// How many of those cards are unsuspended and due now
+	pub due_count: i64,
 }
 
 /// Data transfer object for creating a new item relation
@@ -154,6 +415,370 @@ pub struct ItemParentGraphNode {
 	pub parents: Vec<ItemParentGraphNode>,
 }
 
+/// A preview of what reviewing a card with a given rating would do
+///
+/// Returned by `GET /cards/{card_id}/preview`, one per rating.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReviewPreview {
+	/// The rating this preview is for
+	pub rating: Rating,
+
+	/// When the card would next be due if reviewed now with this rating
+	pub next_review: DateTime<Utc>,
+
+	/// How long from now until `next_review`, in seconds
+	pub interval_seconds: i64,
+}
+
+/// Query parameters for listing reviews across all cards
+///
+/// This struct is used to deserialize query parameters for `GET /reviews`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct ListReviewsQueryDto {
+	/// Only include reviews at or after this time
+	pub from: Option<DateTime<Utc>>,
+
+	/// Only include reviews before this time
+	pub to: Option<DateTime<Utc>>,
+
+	/// Only include reviews with this rating (1-4)
+	pub rating: Option<i32>,
+
+	/// Only include reviews of this card
+	pub card_id: Option<CardId>,
+
+	/// The maximum number of reviews to return (defaults to 100, at most 1000)
+	pub limit: Option<u32>,
+
+	/// The number of matching reviews to skip, for paging through results
+	pub offset: u32,
+}
+
+/// Query parameters for paging through a list of reviews
+///
+/// This struct is used to deserialize query parameters for
+/// `GET /items/{item_id}/reviews`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct ReviewsPageQueryDto {
+	/// The maximum number of reviews to return (defaults to 100, at most 1000)
+	pub limit: Option<u32>,
+
+	/// The number of reviews to skip, for paging through results
+	pub offset: u32,
+}
+
+/// Data transfer object for updating a card's priority
+///
+/// This enum is used to deserialize the JSON body of
+/// `PATCH /cards/{card_id}/priority`: either a bare priority, or an object
+/// that can also give the card version the client read, so the update is
+/// rejected if the card has changed since.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+pub enum UpdateCardPriorityDto {
+	/// Just the new priority, applied whatever the card's version
+	Priority(f32),
+	/// The new priority, applied only if the card is still at `version`
+	WithVersion {
+		/// The new priority, between 0 and 1
+		priority: f32,
+		/// The card version the client read (not checked if not provided)
+		#[serde(default)]
+		version: Option<i32>,
+	},
+}
+
+impl UpdateCardPriorityDto {
+	/// Returns the new priority
+	pub fn priority(&self) -> f32 {
+		match self {
+			UpdateCardPriorityDto::Priority(priority)
+			| UpdateCardPriorityDto::WithVersion { priority, .. } => *priority,
+		}
+	}
+
+	/// Returns the card version the update expects, if any
+	pub fn version(&self) -> Option<i32> {
+		match self {
+			UpdateCardPriorityDto::Priority(_) => None,
+			UpdateCardPriorityDto::WithVersion { version, .. } => *version,
+		}
+	}
+}
+
+impl From<f32> for UpdateCardPriorityDto {
+	fn from(priority: f32) -> Self {
+		UpdateCardPriorityDto::Priority(priority)
+	}
+}
+
+/// Data transfer object for setting a card's note
+///
+/// This struct is used to deserialize the JSON body of
+/// `PUT /cards/{card_id}/note`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetCardNoteDto {
+	/// The new note, or null to clear it
+	pub note: Option<String>,
+}
+
+/// Data transfer object for setting a card's difficulty
+///
+/// This struct is used to deserialize the JSON body of
+/// `PUT /cards/{card_id}/difficulty`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetCardDifficultyDto {
+	/// How hard the card is, between 0 and 1, or null to unset it
+	pub difficulty: Option<f32>,
+}
+
+/// Data transfer object for burying a card
+///
+/// This struct is used to deserialize the optional JSON body of
+/// `POST /cards/{card_id}/bury`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BuryCardDto {
+	/// When the card returns to the queue (the start of the next local day
+	/// if not provided)
+	#[serde(default)]
+	pub until: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for listing leech cards
+///
+/// This struct is used to deserialize query parameters for `GET /cards/leeches`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListLeechesQueryDto {
+	/// The number of failed (rating 1) reviews that makes a card a leech
+	#[serde(default = "default_leech_threshold")]
+	pub threshold: u32,
+
+	/// Whether to suspend the leeches that are found
+	#[serde(default)]
+	pub suspend: bool,
+}
+
+impl Default for ListLeechesQueryDto {
+	fn default() -> Self {
+		Self {
+			threshold: default_leech_threshold(),
+			suspend: false,
+		}
+	}
+}
+
+/// The default leech threshold
+fn default_leech_threshold() -> u32 {
+	crate::repo::DEFAULT_LEECH_THRESHOLD
+}
+
+/// Data transfer object for fetching several cards at once
+///
+/// This struct is used to deserialize JSON requests for `POST /cards/batch_get`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BatchGetCardsDto {
+	/// The IDs of the cards to fetch, at most `MAX_BATCH_GET_CARDS`
+	pub card_ids: Vec<CardId>,
+}
+
+/// The data of a `due` event sent from `GET /events/due`
+///
+/// One is sent when the stream opens, then whenever the number of due cards
+/// changes or cards become due.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DueCardsEvent {
+	/// The number of unsuspended, unburied cards due now
+	pub due_count: usize,
+	/// The IDs of the cards that became due since the previous event, in the
+	/// order they came due; empty in the first event
+	pub newly_due_card_ids: Vec<CardId>,
+}
+
+/// Data transfer object for swapping two cards' places in the queue
+///
+/// This struct is used to deserialize JSON requests for `POST /cards/swap`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SwapCardsDto {
+	/// The ID of one card to swap
+	pub card_a: CardId,
+	/// The ID of the other card to swap
+	pub card_b: CardId,
+}
+
+/// Query parameters for regenerating an item's cards
+///
+/// This struct is used to deserialize query parameters for
+/// `POST /items/{item_id}/regenerate_cards`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct RegenerateCardsQueryDto {
+	/// Whether to delete cards the item no longer calls for, along with their
+	/// reviews
+	pub remove_extra: bool,
+}
+
+/// Query parameters for listing the hardest cards
+///
+/// This struct is used to deserialize query parameters for `GET /cards/hardest`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HardestCardsQueryDto {
+	/// The most cards to return (defaults to 10, at most 100)
+	#[serde(default = "default_hardest_cards_limit")]
+	pub limit: u32,
+
+	/// The fewest reviews a card needs to be ranked (defaults to 5)
+	#[serde(default = "default_hardest_cards_min_reviews")]
+	pub min_reviews: u32,
+}
+
+impl Default for HardestCardsQueryDto {
+	fn default() -> Self {
+		Self {
+			limit: default_hardest_cards_limit(),
+			min_reviews: default_hardest_cards_min_reviews(),
+		}
+	}
+}
+
+/// The default number of hardest cards to return
+fn default_hardest_cards_limit() -> u32 {
+	crate::repo::DEFAULT_HARDEST_CARDS_LIMIT
+}
+
+/// The default number of reviews a card needs to be ranked among the hardest
+fn default_hardest_cards_min_reviews() -> u32 {
+	crate::repo::DEFAULT_HARDEST_CARDS_MIN_REVIEWS
+}
+
+/// Query parameters for listing cards related to a card
+///
+/// This struct is used to deserialize query parameters for
+/// `GET /cards/{card_id}/related`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RelatedCardsQueryDto {
+	/// The most cards to return (defaults to 10, at most 100)
+	#[serde(default = "default_related_cards_limit")]
+	pub limit: u32,
+}
+
+impl Default for RelatedCardsQueryDto {
+	fn default() -> Self {
+		Self {
+			limit: default_related_cards_limit(),
+		}
+	}
+}
+
+/// The default number of related cards to return
+fn default_related_cards_limit() -> u32 {
+	crate::repo::DEFAULT_RELATED_CARDS_LIMIT
+}
+
+/// Query parameters for forecasting when cards come due
+///
+/// This struct is used to deserialize query parameters for `GET /cards/forecast`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ForecastQueryDto {
+	/// How many local days to forecast, starting with today (defaults to 7,
+	/// at most 365)
+	#[serde(default = "default_forecast_days")]
+	pub days: u32,
+}
+
+impl Default for ForecastQueryDto {
+	fn default() -> Self {
+		Self {
+			days: default_forecast_days(),
+		}
+	}
+}
+
+/// The default number of days to forecast
+fn default_forecast_days() -> u32 {
+	crate::repo::DEFAULT_FORECAST_DAYS
+}
+
+/// The number of cards coming due in one bucket of a forecast
+///
+/// Returned by `GET /cards/forecast`, overdue bucket first, then one per day.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ForecastBucket {
+	/// The local day the cards come due on, or null for the overdue bucket:
+	/// cards that are already due
+	pub date: Option<NaiveDate>,
+
+	/// The number of unsuspended cards coming due
+	pub due_count: i64,
+}
+
+/// How the unsuspended cards are spread across the stages of learning
+///
+/// Returned by `GET /stats/overview`. A card's interval is the time between
+/// its last and next review; every unsuspended card falls in exactly one of
+/// the four buckets.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct StatsOverview {
+	/// Cards that have never been reviewed
+	pub new_count: i64,
+
+	/// Reviewed cards with an interval under a day
+	pub learning_count: i64,
+
+	/// Reviewed cards with an interval of at least a day but under
+	/// [`MATURE_INTERVAL_DAYS`](crate::repo::MATURE_INTERVAL_DAYS)
+	pub young_count: i64,
+
+	/// Reviewed cards with an interval of at least
+	/// [`MATURE_INTERVAL_DAYS`](crate::repo::MATURE_INTERVAL_DAYS)
+	pub mature_count: i64,
+
+	/// The average FSRS `difficulty` across cards that have one, or None if
+	/// none do
+	pub average_difficulty: Option<f64>,
+}
+
+/// What vacuuming the database did to its size
+///
+/// Returned by `POST /admin/vacuum`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VacuumResult {
+	/// The size of the database before vacuuming, in bytes
+	pub size_before_bytes: i64,
+
+	/// The size of the database after vacuuming, in bytes
+	pub size_after_bytes: i64,
+
+	/// How many bytes vacuuming freed (never negative)
+	pub freed_bytes: i64,
+}
+
+/// What recounting every card's lapses changed
+///
+/// Returned by `POST /admin/backfill-lapses`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillLapsesResult {
+	/// How many cards' lapse counts were corrected
+	pub cards_updated: usize,
+}
+
+/// The database's migration status
+///
+/// Returned by `GET /admin/migrations`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+	/// The versions of the migrations that have been applied, oldest first
+	pub applied: Vec<String>,
+
+	/// The versions of the embedded migrations that haven't been applied yet,
+	/// oldest first
+	pub pending: Vec<String>,
+
+	/// Whether any embedded migrations haven't been applied yet
+	pub has_pending: bool,
+}
+
 /// Data transfer object for creating a new card fetched event
 ///
 /// This struct is used to deserialize JSON requests for creating card fetched events.
@@ -198,18 +823,60 @@ pub enum SuspendedFilter {
 	Only,
 }
 
+/// How `GetQueryDto::tag_ids` combine
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+	/// Items must have every requested tag
+	#[default]
+	All,
+	/// Items must have at least one of the requested tags
+	Any,
+}
+
+/// Where a card is in the review cycle, for filtering with `GetQueryDto::state`
+///
+/// Learning and review cards are told apart by the gap between their last
+/// and next reviews, in the days it was scheduled for: under a day is still
+/// learning, a day or more is review.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum CardState {
+	/// Never reviewed (`last_review` is null)
+	New,
+	/// `next_review` is at or before now, whether or not it has been reviewed
+	Due,
+	/// Reviewed, and scheduled less than a day after its last review
+	Learning,
+	/// Reviewed, and scheduled a day or more after its last review
+	Review,
+}
+
 /// Data transfer object for getting all items or cards matching a query
 ///
 /// This struct is used to deserialize JSON requests for getting all items or cards matching a query.
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
 #[serde(default)]
 pub struct GetQueryDto {
 	/// The ID of the item type to filter by
 	pub item_type_id: Option<ItemTypeId>,
 
+	/// The IDs of item types whose items are excluded
+	///
+	/// Composes with `item_type_id`, so naming the same type in both
+	/// matches nothing.
+	pub exclude_item_type_ids: Vec<ItemTypeId>,
+
 	/// The IDs of the tags to filter by
 	pub tag_ids: Vec<TagId>,
 
+	/// Whether items need all of `tag_ids` (the default) or any of them
+	pub tag_match: TagMatch,
+
+	/// When true, each tag in `tag_ids` also matches items with any tag
+	/// beneath it in the tag hierarchy
+	pub include_descendant_tags: Option<bool>,
+
 	/// The maximum next review date to filter by
 	pub next_review_before: Option<DateTime<Utc>>,
 
@@ -234,12 +901,27 @@ pub struct GetQueryDto {
 
 	/// Filter to parents of this child item ID
 	pub child_item_id: Option<ItemId>,
+
+	/// Only include cards in this state
+	///
+	/// Like every other filter, this is ANDed with the date filters, so
+	/// `state=new` with `last_review_after` matches nothing, and `state=due`
+	/// with `next_review_before` narrows to whichever cutoff is earlier.
+	pub state: Option<CardState>,
+
+	/// When true, ignore scheduling for cramming: `next_review_before` and
+	/// burying are ignored, `GET /cards/next` doesn't wait for cards to be
+	/// due, and suspended cards are always excluded
+	pub cram: Option<bool>,
 }
 
 /// Builder for GetQueryDto
 pub struct GetQueryDtoBuilder {
 	item_type_id: Option<ItemTypeId>,
+	exclude_item_type_ids: Vec<ItemTypeId>,
 	tag_ids: Vec<TagId>,
+	tag_match: TagMatch,
+	include_descendant_tags: Option<bool>,
 	next_review_before: Option<DateTime<Utc>>,
 	last_review_after: Option<DateTime<Utc>>,
 	suspended_filter: SuspendedFilter,
@@ -248,6 +930,8 @@ pub struct GetQueryDtoBuilder {
 	split_priority: Option<bool>,
 	parent_item_id: Option<ItemId>,
 	child_item_id: Option<ItemId>,
+	state: Option<CardState>,
+	cram: Option<bool>,
 }
 
 impl GetQueryDtoBuilder {
@@ -255,7 +939,10 @@ impl GetQueryDtoBuilder {
 	pub fn new() -> Self {
 		Self {
 			item_type_id: None,
+			exclude_item_type_ids: Vec::new(),
 			tag_ids: Vec::new(),
+			tag_match: TagMatch::default(),
+			include_descendant_tags: None,
 			next_review_before: None,
 			last_review_after: None,
 			suspended_filter: SuspendedFilter::default(),
@@ -264,6 +951,8 @@ impl GetQueryDtoBuilder {
 			split_priority: None,
 			parent_item_id: None,
 			child_item_id: None,
+			state: None,
+			cram: None,
 		}
 	}
 
@@ -273,6 +962,12 @@ impl GetQueryDtoBuilder {
 		self
 	}
 
+	/// Adds an item type ID to exclude
+	pub fn exclude_item_type_id(mut self, item_type_id: ItemTypeId) -> Self {
+		self.exclude_item_type_ids.push(item_type_id);
+		self
+	}
+
 	/// Sets the tag IDs to filter by
 	pub fn tag_ids(mut self, tag_ids: Vec<TagId>) -> Self {
 		self.tag_ids = tag_ids;
@@ -285,6 +980,12 @@ impl GetQueryDtoBuilder {
 		self
 	}
 
+	/// Sets whether items need all or any of the tags
+	pub fn tag_match(mut self, tag_match: TagMatch) -> Self {
+		self.tag_match = tag_match;
+		self
+	}
+
 	/// Sets the maximum next review date to filter by
 	pub fn next_review_before(mut self, next_review_before: DateTime<Utc>) -> Self {
 		self.next_review_before = Some(next_review_before);
@@ -315,6 +1016,12 @@ impl GetQueryDtoBuilder {
 		self
 	}
 
+	/// Sets whether tags also match items with tags beneath them
+	pub fn include_descendant_tags(mut self, include_descendant_tags: bool) -> Self {
+		self.include_descendant_tags = Some(include_descendant_tags);
+		self
+	}
+
 	/// Sets the split_priority flag
 	pub fn split_priority(mut self, split_priority: bool) -> Self {
 		self.split_priority = Some(split_priority);
@@ -333,11 +1040,26 @@ impl GetQueryDtoBuilder {
 		self
 	}
 
+	/// Sets the card state to filter by
+	pub fn state(mut self, state: CardState) -> Self {
+		self.state = Some(state);
+		self
+	}
+
+	/// Sets whether to ignore scheduling for cramming
+	pub fn cram(mut self, cram: bool) -> Self {
+		self.cram = Some(cram);
+		self
+	}
+
 	/// Builds the GetQueryDto
 	pub fn build(self) -> GetQueryDto {
 		GetQueryDto {
 			item_type_id: self.item_type_id,
+			exclude_item_type_ids: self.exclude_item_type_ids,
 			tag_ids: self.tag_ids,
+			tag_match: self.tag_match,
+			include_descendant_tags: self.include_descendant_tags,
 			next_review_before: self.next_review_before,
 			last_review_after: self.last_review_after,
 			suspended_filter: self.suspended_filter,
@@ -346,6 +1068,8 @@ impl GetQueryDtoBuilder {
 			split_priority: self.split_priority,
 			parent_item_id: self.parent_item_id,
 			child_item_id: self.child_item_id,
+			state: self.state,
+			cram: self.cram,
 		}
 	}
 }
@@ -360,6 +1084,17 @@ impl fmt::Display for GetQueryDto {
 			write!(f, "item_type_id: None, ")?;
 		}
 
+		if !self.exclude_item_type_ids.is_empty() {
+			write!(f, "exclude_item_type_ids: [")?;
+			for (i, item_type_id) in self.exclude_item_type_ids.iter().enumerate() {
+				if i > 0 {
+					write!(f, ", ")?;
+				}
+				write!(f, "{}", item_type_id)?;
+			}
+			write!(f, "], ")?;
+		}
+
 		write!(f, "tag_ids: [")?;
 		for (i, tag_id) in self.tag_ids.iter().enumerate() {
 			if i > 0 {
@@ -369,6 +1104,14 @@ impl fmt::Display for GetQueryDto {
 		}
 		write!(f, "], ")?;
 
+		if self.tag_match == TagMatch::Any {
+			write!(f, "tag_match: any, ")?;
+		}
+
+		if self.include_descendant_tags == Some(true) {
+			write!(f, "include_descendant_tags: true, ")?;
+		}
+
 		if let Some(review_date) = self.next_review_before {
 			write!(f, "next_review_before: {} ", review_date)?;
 		} else {
@@ -389,6 +1132,14 @@ impl fmt::Display for GetQueryDto {
 			write!(f, "child_item_id: {}, ", child_id)?;
 		}
 
+		if let Some(state) = self.state {
+			write!(f, "state: {:?}, ", state)?;
+		}
+
+		if self.cram == Some(true) {
+			write!(f, "cram: true, ")?;
+		}
+
 		write!(f, "}}")
 	}
 }
@@ -136,6 +136,7 @@ proptest! {
 		priority in arb_priority(),
 	) {
 		let dto = CreateItemDto {
+			id: None,
 			item_type_id: item_type_id.clone(),
 			title: title.clone(),
 			item_data: item_data.clone(),
@@ -154,17 +155,19 @@ proptest! {
 	#[test]
 	fn prop_d1_5_create_review_dto_serde_roundtrip(
 		card_id in arb_card_id(),
-		rating in any::<i32>(),
+		rating in arb_rating(),
 	) {
 		let dto = CreateReviewDto {
 			card_id: card_id.clone(),
-			rating,
+			rating: rating.as_i32(),
+			session_id: None,
+			response: None,
 		};
 		let json_str = serde_json::to_string(&dto).unwrap();
 		let deserialized: CreateReviewDto = serde_json::from_str(&json_str).unwrap();
 
 		prop_assert_eq!(&deserialized.card_id, &card_id);
-		prop_assert_eq!(deserialized.rating, rating);
+		prop_assert_eq!(deserialized.rating, rating.as_i32());
 	}
 
 	/// D1.6: SuspendedFilter all variants roundtrip
@@ -70,6 +70,7 @@ fn test_get_query_dto_display_full() {
 	let dto = GetQueryDto {
 		item_type_id: Some(ItemTypeId("type-1".to_string())),
 		tag_ids: vec![TagId("tag-a".to_string()), TagId("tag-b".to_string())],
+		include_descendant_tags: Some(true),
 		next_review_before: Some(Utc.with_ymd_and_hms(2025, 6, 15, 12, 0, 0).unwrap()),
 		last_review_after: Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()),
 		suspended_filter: SuspendedFilter::Include,
@@ -78,14 +79,46 @@ fn test_get_query_dto_display_full() {
 		split_priority: Some(true),
 		parent_item_id: None,
 		child_item_id: None,
+		state: Some(CardState::Learning),
+		cram: Some(true),
+		exclude_item_type_ids: Vec::new(),
+		tag_match: TagMatch::Any,
 	};
 
 	let display = format!("{}", dto);
 	assert!(display.contains("item_type_id: type-1"));
 	assert!(display.contains("tag-a"));
 	assert!(display.contains("tag-b"));
+	assert!(display.contains("include_descendant_tags: true"));
 	assert!(display.contains("next_review_before: 2025-06-15"));
 	assert!(display.contains("last_review_after: 2025-01-01"));
+	assert!(display.contains("state: Learning"));
+	assert!(display.contains("cram: true"));
+	assert!(display.contains("tag_match: any"));
+}
+
+#[test]
+fn test_tag_match_deserializes_lowercase_and_defaults_to_all() {
+	let dto: GetQueryDto = serde_json::from_value(json!({"tag_match": "any"})).unwrap();
+	assert_eq!(dto.tag_match, TagMatch::Any);
+
+	let dto: GetQueryDto = serde_json::from_value(json!({})).unwrap();
+	assert_eq!(dto.tag_match, TagMatch::All);
+}
+
+#[test]
+fn test_card_state_deserializes_lowercase() {
+	for (raw, state) in [
+		("new", CardState::New),
+		("due", CardState::Due),
+		("learning", CardState::Learning),
+		("review", CardState::Review),
+	] {
+		let deserialized: CardState = serde_json::from_value(json!(raw)).unwrap();
+		assert_eq!(deserialized, state);
+		assert_eq!(serde_json::to_value(state).unwrap(), raw);
+	}
+	assert!(serde_json::from_value::<CardState>(json!("New")).is_err());
 }
 
 #[test]
@@ -104,6 +137,7 @@ fn test_suspended_filter_serde_roundtrip() {
 #[test]
 fn test_create_item_dto_serde_roundtrip() {
 	let dto = CreateItemDto {
+		id: None,
 		item_type_id: ItemTypeId("type-1".to_string()),
 		title: "Test Item".to_string(),
 		item_data: json!({"key": "value"}),
@@ -117,6 +151,17 @@ fn test_create_item_dto_serde_roundtrip() {
 	assert!((deserialized.priority - 0.7).abs() < f32::EPSILON);
 }
 
+#[test]
+fn test_create_item_dto_optional_id() {
+	let json_str = r#"{"item_type_id":"t1","title":"Test","item_data":null}"#;
+	let dto: CreateItemDto = serde_json::from_str(json_str).unwrap();
+	assert_eq!(dto.id, None);
+
+	let json_str = r#"{"id":"item-1","item_type_id":"t1","title":"Test","item_data":null}"#;
+	let dto: CreateItemDto = serde_json::from_str(json_str).unwrap();
+	assert_eq!(dto.id, Some(ItemId("item-1".to_string())));
+}
+
 #[test]
 fn test_create_item_dto_default_priority() {
 	// When priority is not specified in JSON, it should default to 0.5
@@ -124,3 +169,43 @@ fn test_create_item_dto_default_priority() {
 	let dto: CreateItemDto = serde_json::from_str(json_str).unwrap();
 	assert!((dto.priority - 0.5).abs() < f32::EPSILON);
 }
+
+#[test]
+fn test_update_item_type_dto_item_data_schema_absent_vs_null() {
+	// An absent schema leaves the current one alone
+	let dto: UpdateItemTypeDto = serde_json::from_str(r#"{"review_function":"fsrs"}"#).unwrap();
+	assert_eq!(dto.item_data_schema, None);
+
+	// An explicit null removes it
+	let dto: UpdateItemTypeDto = serde_json::from_str(r#"{"item_data_schema":null}"#).unwrap();
+	assert_eq!(dto.item_data_schema, Some(None));
+
+	// A value replaces it
+	let dto: UpdateItemTypeDto =
+		serde_json::from_str(r#"{"item_data_schema":{"type":"object"}}"#).unwrap();
+	assert_eq!(dto.item_data_schema, Some(Some(json!({"type": "object"}))));
+
+	// Serializing an unset schema omits it rather than sending null
+	let dto = UpdateItemTypeDto {
+		review_function: None,
+		item_data_schema: None,
+		default_priority: None,
+		scheduler_config: None,
+	};
+	assert_eq!(
+		serde_json::to_value(&dto).unwrap(),
+		json!({"review_function": null})
+	);
+}
+
+#[test]
+fn test_update_item_type_dto_default_priority_null_vs_absent() {
+	let dto: UpdateItemTypeDto = serde_json::from_str(r#"{}"#).unwrap();
+	assert_eq!(dto.default_priority, None);
+
+	let dto: UpdateItemTypeDto = serde_json::from_str(r#"{"default_priority":null}"#).unwrap();
+	assert_eq!(dto.default_priority, Some(None));
+
+	let dto: UpdateItemTypeDto = serde_json::from_str(r#"{"default_priority":0.8}"#).unwrap();
+	assert_eq!(dto.default_priority, Some(Some(0.8)));
+}
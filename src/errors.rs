@@ -1,27 +1,124 @@
 use axum::{
 	Json,
+	extract::rejection::JsonRejection,
 	http::StatusCode,
 	response::{IntoResponse, Response},
 };
+use serde::Serialize;
+use std::fmt;
 use thiserror::Error;
 use tracing::{debug, error, info, warn};
 
 use crate::card_event_registry::CardEventChainError;
-use crate::models::CardEventFnName;
+use crate::models::{CardEventFnName, ItemTypeId};
 use crate::repo::CardFetchError;
 
+/// The kind of resource an `ApiError::NotFound` refers to
+///
+/// Lets the error body say *what* was missing, both in its message and in
+/// its machine-readable `code`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+	Card,
+	Item,
+	ItemType,
+	ItemRelation,
+	Tag,
+	/// A tag's association with an item
+	ItemTag,
+	Review,
+	Session,
+	CardFetchedEvent,
+}
+
+impl fmt::Display for Resource {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Resource::Card => "Card",
+			Resource::Item => "Item",
+			Resource::ItemType => "Item type",
+			Resource::ItemRelation => "Item relation",
+			Resource::Tag => "Tag",
+			Resource::ItemTag => "Tag on item",
+			Resource::Review => "Review",
+			Resource::Session => "Session",
+			Resource::CardFetchedEvent => "Card fetched event",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+/// Machine-readable error codes, sent as the `code` field of every error body
+///
+/// Unlike the status code these distinguish every kind of error, so clients
+/// can branch on (or localize) them without parsing the message.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+	InternalError,
+	CardNotFound,
+	ItemNotFound,
+	ItemTypeNotFound,
+	ItemRelationNotFound,
+	TagNotFound,
+	ItemTagNotFound,
+	ReviewNotFound,
+	SessionNotFound,
+	CardFetchedEventNotFound,
+	ItemTypeUnknown,
+	InvalidId,
+	InvalidRating,
+	InvalidPriority,
+	InvalidDifficulty,
+	InvalidReviewFunction,
+	InvalidItemData,
+	InvalidItemDataSchema,
+	InvalidSchedulerConfig,
+	BatchTooLarge,
+	InvalidBatchItem,
+	InvalidReviewImport,
+	InvalidJson,
+	PayloadTooLarge,
+	MethodNotAllowed,
+	CycleDetected,
+	Conflict,
+	UnknownCardEventFn,
+	CardEventChainFailed,
+}
+
 #[derive(Error, Debug)]
 pub enum ApiError {
 	#[error("Database error: {0}")]
 	Database(#[from] anyhow::Error),
-	#[error("Item/Card/etc. not found")]
-	NotFound,
+	#[error("{0} not found")]
+	NotFound(Resource),
+	/// A client-supplied ID isn't well-formed
+	#[error("Invalid id: {0}")]
+	InvalidId(String),
+	/// A request body referenced an item type that doesn't exist
+	#[error("Unknown item type: {0}")]
+	UnknownItemType(ItemTypeId),
 	#[error("Invalid rating: {0}")]
 	InvalidRating(String),
 	#[error("Invalid priority: {0}")]
 	InvalidPriority(String),
+	#[error("Invalid difficulty: {0}")]
+	InvalidDifficulty(String),
 	#[error("Invalid review function: {0}")]
 	InvalidReviewFunction(String),
+	#[error("Invalid item data: {0}")]
+	InvalidItemData(String),
+	#[error("Invalid item data schema: {0}")]
+	InvalidItemDataSchema(String),
+	#[error("Invalid scheduler config: {0}")]
+	InvalidSchedulerConfig(String),
+	#[error("Batch too large: {0}")]
+	BatchTooLarge(String),
+	/// One item of a batch create was rejected, so none were created
+	#[error("Invalid batch item: {0}")]
+	InvalidBatchItem(String),
+	#[error("Invalid review import: {0}")]
+	InvalidReviewImport(String),
 	#[error("Method not allowed")]
 	MethodNotAllowed,
 	#[error("Cycle detected: adding this relation would create a cycle")]
@@ -37,6 +134,11 @@ pub enum ApiError {
 	/// than bad client input.
 	#[error("Card event chain failed: {0}")]
 	CardEventChainFailed(CardEventChainError),
+	/// The request body couldn't be extracted as JSON. Malformed JSON and
+	/// JSON that doesn't fit the expected shape both become a 422; other
+	/// rejections (e.g. a missing `Content-Type`) keep axum's own status.
+	#[error("Invalid JSON body: {0}")]
+	InvalidJson(#[from] JsonRejection),
 }
 
 impl IntoResponse for ApiError {
@@ -50,12 +152,22 @@ impl IntoResponse for ApiError {
 					"Internal server error".to_string(),
 				)
 			}
-			ApiError::NotFound => {
+			ApiError::NotFound(resource) => {
 				// Not Found errors are just informational
-				debug!(error.kind = "not_found", "Resource not found");
+				debug!(error.kind = "not_found", resource = %resource, "Resource not found");
+				(StatusCode::NOT_FOUND, format!("{} not found", resource))
+			}
+			ApiError::InvalidId(msg) => {
+				// Client errors are logged at warn level
+				warn!(error.kind = "invalid_id", message = %msg, "Invalid id: {}", msg);
+				(StatusCode::BAD_REQUEST, msg.clone())
+			}
+			ApiError::UnknownItemType(id) => {
+				// Client errors are logged at warn level
+				warn!(error.kind = "item_type_unknown", item_type_id = %id, "Unknown item type: {}", id);
 				(
-					StatusCode::NOT_FOUND,
-					"Item/Card/etc. not found".to_string(),
+					StatusCode::BAD_REQUEST,
+					format!("Unknown item type: {}", id),
 				)
 			}
 			ApiError::InvalidRating(msg) => {
@@ -68,11 +180,46 @@ impl IntoResponse for ApiError {
 				warn!(error.kind = "invalid_priority", message = %msg, "Invalid priority: {}", msg);
 				(StatusCode::BAD_REQUEST, msg.clone())
 			}
+			ApiError::InvalidDifficulty(msg) => {
+				// Client errors are logged at warn level
+				warn!(error.kind = "invalid_difficulty", message = %msg, "Invalid difficulty: {}", msg);
+				(StatusCode::BAD_REQUEST, msg.clone())
+			}
 			ApiError::InvalidReviewFunction(msg) => {
 				// Client errors are logged at warn level
 				warn!(error.kind = "invalid_review_function", message = %msg, "Invalid review function: {}", msg);
 				(StatusCode::BAD_REQUEST, msg.clone())
 			}
+			ApiError::InvalidItemData(msg) => {
+				// Client errors are logged at warn level
+				warn!(error.kind = "invalid_item_data", message = %msg, "Invalid item data: {}", msg);
+				(StatusCode::BAD_REQUEST, msg.clone())
+			}
+			ApiError::InvalidItemDataSchema(msg) => {
+				// Client errors are logged at warn level
+				warn!(error.kind = "invalid_item_data_schema", message = %msg, "Invalid item data schema: {}", msg);
+				(StatusCode::BAD_REQUEST, msg.clone())
+			}
+			ApiError::InvalidSchedulerConfig(msg) => {
+				// Client errors are logged at warn level
+				warn!(error.kind = "invalid_scheduler_config", message = %msg, "Invalid scheduler config: {}", msg);
+				(StatusCode::BAD_REQUEST, msg.clone())
+			}
+			ApiError::BatchTooLarge(msg) => {
+				// Client errors are logged at warn level
+				warn!(error.kind = "batch_too_large", message = %msg, "Batch too large: {}", msg);
+				(StatusCode::BAD_REQUEST, msg.clone())
+			}
+			ApiError::InvalidBatchItem(msg) => {
+				// Client errors are logged at warn level
+				warn!(error.kind = "invalid_batch_item", message = %msg, "Invalid batch item: {}", msg);
+				(StatusCode::BAD_REQUEST, msg.clone())
+			}
+			ApiError::InvalidReviewImport(msg) => {
+				// Client errors are logged at warn level
+				warn!(error.kind = "invalid_review_import", message = %msg, "Invalid review import: {}", msg);
+				(StatusCode::BAD_REQUEST, msg.clone())
+			}
 			ApiError::MethodNotAllowed => {
 				// Client errors are logged at warn level
 				warn!(error.kind = "method_not_allowed", "Method not allowed");
@@ -112,6 +259,17 @@ impl IntoResponse for ApiError {
 					format!("Card event chain failed: {}", chain_err),
 				)
 			}
+			ApiError::InvalidJson(rejection) => {
+				let message = rejection.body_text();
+				warn!(error.kind = "invalid_json", message = %message, "Invalid JSON body: {}", message);
+				let status = match rejection {
+					JsonRejection::JsonDataError(_) | JsonRejection::JsonSyntaxError(_) => {
+						StatusCode::UNPROCESSABLE_ENTITY
+					}
+					_ => rejection.status(),
+				};
+				(status, message)
+			}
 		};
 
 		// Log all error responses in a consistent format
@@ -122,13 +280,61 @@ impl IntoResponse for ApiError {
 		);
 
 		let body = Json(serde_json::json!({
-			"error": message
+			"error": message,
+			"code": self.code(),
 		}));
 
 		(status, body).into_response()
 	}
 }
 
+impl ApiError {
+	/// Gets the machine-readable code for this error
+	///
+	/// ### Returns
+	///
+	/// The `ErrorCode` sent as the `code` field of the error body
+	pub fn code(&self) -> ErrorCode {
+		match self {
+			ApiError::Database(_) => ErrorCode::InternalError,
+			ApiError::NotFound(resource) => match resource {
+				Resource::Card => ErrorCode::CardNotFound,
+				Resource::Item => ErrorCode::ItemNotFound,
+				Resource::ItemType => ErrorCode::ItemTypeNotFound,
+				Resource::ItemRelation => ErrorCode::ItemRelationNotFound,
+				Resource::Tag => ErrorCode::TagNotFound,
+				Resource::ItemTag => ErrorCode::ItemTagNotFound,
+				Resource::Review => ErrorCode::ReviewNotFound,
+				Resource::Session => ErrorCode::SessionNotFound,
+				Resource::CardFetchedEvent => ErrorCode::CardFetchedEventNotFound,
+			},
+			ApiError::UnknownItemType(_) => ErrorCode::ItemTypeUnknown,
+			ApiError::InvalidId(_) => ErrorCode::InvalidId,
+			ApiError::InvalidRating(_) => ErrorCode::InvalidRating,
+			ApiError::InvalidPriority(_) => ErrorCode::InvalidPriority,
+			ApiError::InvalidDifficulty(_) => ErrorCode::InvalidDifficulty,
+			ApiError::InvalidReviewFunction(_) => ErrorCode::InvalidReviewFunction,
+			ApiError::InvalidItemData(_) => ErrorCode::InvalidItemData,
+			ApiError::InvalidItemDataSchema(_) => ErrorCode::InvalidItemDataSchema,
+			ApiError::InvalidSchedulerConfig(_) => ErrorCode::InvalidSchedulerConfig,
+			ApiError::BatchTooLarge(_) => ErrorCode::BatchTooLarge,
+			ApiError::InvalidBatchItem(_) => ErrorCode::InvalidBatchItem,
+			ApiError::InvalidReviewImport(_) => ErrorCode::InvalidReviewImport,
+			ApiError::MethodNotAllowed => ErrorCode::MethodNotAllowed,
+			ApiError::CycleDetected => ErrorCode::CycleDetected,
+			ApiError::Conflict(_) => ErrorCode::Conflict,
+			ApiError::UnknownCardEventFn(_) => ErrorCode::UnknownCardEventFn,
+			ApiError::CardEventChainFailed(_) => ErrorCode::CardEventChainFailed,
+			ApiError::InvalidJson(rejection)
+				if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE =>
+			{
+				ErrorCode::PayloadTooLarge
+			}
+			ApiError::InvalidJson(_) => ErrorCode::InvalidJson,
+		}
+	}
+}
+
 /// `?`-friendly conversion for handlers that call `repo::get_card` /
 /// `repo::list_cards` / `repo::list_cards_by_item`. Keeps the typed
 /// distinction between registry/data drift (which needs operator
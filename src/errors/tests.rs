@@ -17,14 +17,49 @@ async fn test_database_error_response() {
 	let (status, body) = error_response(error).await;
 	assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
 	assert_eq!(body["error"], "Internal server error");
+	assert_eq!(body["code"], "internal_error");
 }
 
 #[tokio::test]
 async fn test_not_found_response() {
-	let error = ApiError::NotFound;
+	let error = ApiError::NotFound(Resource::Card);
 	let (status, body) = error_response(error).await;
 	assert_eq!(status, StatusCode::NOT_FOUND);
-	assert_eq!(body["error"], "Item/Card/etc. not found");
+	assert_eq!(body["error"], "Card not found");
+	assert_eq!(body["code"], "card_not_found");
+}
+
+#[tokio::test]
+async fn test_not_found_codes_per_resource() {
+	for (resource, code) in [
+		(Resource::Item, "item_not_found"),
+		(Resource::ItemType, "item_type_not_found"),
+		(Resource::Review, "review_not_found"),
+		(Resource::Session, "session_not_found"),
+	] {
+		let (status, body) = error_response(ApiError::NotFound(resource)).await;
+		assert_eq!(status, StatusCode::NOT_FOUND);
+		assert_eq!(body["code"], code);
+	}
+}
+
+#[tokio::test]
+async fn test_invalid_id_response() {
+	let msg = "Item id must be a UUID".to_string();
+	let error = ApiError::InvalidId(msg.clone());
+	let (status, body) = error_response(error).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+	assert_eq!(body["error"], msg);
+	assert_eq!(body["code"], "invalid_id");
+}
+
+#[tokio::test]
+async fn test_unknown_item_type_response() {
+	let error = ApiError::UnknownItemType(ItemTypeId("item-type-missing".to_string()));
+	let (status, body) = error_response(error).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+	assert_eq!(body["error"], "Unknown item type: item-type-missing");
+	assert_eq!(body["code"], "item_type_unknown");
 }
 
 #[tokio::test]
@@ -43,6 +78,17 @@ async fn test_invalid_priority_response() {
 	let (status, body) = error_response(error).await;
 	assert_eq!(status, StatusCode::BAD_REQUEST);
 	assert_eq!(body["error"], msg);
+	assert_eq!(body["code"], "invalid_priority");
+}
+
+#[tokio::test]
+async fn test_invalid_difficulty_response() {
+	let msg = "Difficulty must be between 0 and 1".to_string();
+	let error = ApiError::InvalidDifficulty(msg.clone());
+	let (status, body) = error_response(error).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+	assert_eq!(body["error"], msg);
+	assert_eq!(body["code"], "invalid_difficulty");
 }
 
 #[tokio::test]
@@ -54,6 +100,64 @@ async fn test_invalid_review_function_response() {
 	assert_eq!(body["error"], msg);
 }
 
+#[tokio::test]
+async fn test_invalid_item_data_response() {
+	let msg = "Cloze items require a `clozes` array".to_string();
+	let error = ApiError::InvalidItemData(msg.clone());
+	let (status, body) = error_response(error).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+	assert_eq!(body["error"], msg);
+}
+
+#[tokio::test]
+async fn test_invalid_item_data_schema_response() {
+	let msg = "7 is not valid under any of the schemas listed in the 'anyOf' keyword".to_string();
+	let error = ApiError::InvalidItemDataSchema(msg.clone());
+	let (status, body) = error_response(error).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+	assert_eq!(body["error"], msg);
+}
+
+#[tokio::test]
+async fn test_invalid_scheduler_config_response() {
+	let msg = "jitter must be at least 0 and below 1, got 2".to_string();
+	let error = ApiError::InvalidSchedulerConfig(msg.clone());
+	let (status, body) = error_response(error).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+	assert_eq!(body["error"], msg);
+	assert_eq!(body["code"], "invalid_scheduler_config");
+}
+
+#[tokio::test]
+async fn test_batch_too_large_response() {
+	let msg = "At most 500 cards can be fetched at once, got 501".to_string();
+	let error = ApiError::BatchTooLarge(msg.clone());
+	let (status, body) = error_response(error).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+	assert_eq!(body["error"], msg);
+	assert_eq!(body["code"], "batch_too_large");
+}
+
+#[tokio::test]
+async fn test_invalid_batch_item_response() {
+	let msg = "Item 3: Unknown item type: item_type-missing".to_string();
+	let error = ApiError::InvalidBatchItem(msg.clone());
+	let (status, body) = error_response(error).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+	assert_eq!(body["error"], msg);
+	assert_eq!(body["code"], "invalid_batch_item");
+}
+
+#[tokio::test]
+async fn test_invalid_review_import_response() {
+	let msg = "Reviews must be sorted by timestamp".to_string();
+	let error = ApiError::InvalidReviewImport(msg.clone());
+	let (status, body) = error_response(error).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+	assert_eq!(body["error"], msg);
+	assert_eq!(body["code"], "invalid_review_import");
+}
+
 #[tokio::test]
 async fn test_method_not_allowed_response() {
 	let error = ApiError::MethodNotAllowed;
@@ -0,0 +1,200 @@
+//! Weak ETags for GET responses, so polling clients can skip downloading
+//! data that hasn't changed.
+//!
+//! [`etag`] hashes each successful response body into a weak `ETag`, and
+//! answers a request whose `If-None-Match` already names that tag with an
+//! empty `304 Not Modified`. Only stored data goes into the tag: a card's
+//! computed fields (such as `retrievability`) change with the clock alone,
+//! so they are left out, or a reviewed card would never be unchanged. The
+//! handler still runs to produce the body being hashed.
+
+use axum::{
+	body::{Body, to_bytes},
+	extract::Request,
+	http::{HeaderMap, HeaderValue, Method, StatusCode, header},
+	middleware::Next,
+	response::{IntoResponse, Response},
+};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use tracing::{debug, warn};
+
+use crate::models::Card;
+
+/// Computes the weak ETag for a response body
+///
+/// Card fields computed as of now ([`Card::COMPUTED_FIELDS`]) are left out
+/// of JSON bodies, whether the body is one card or a list of them, so the
+/// tag only changes when stored data does.
+///
+/// ### Arguments
+///
+/// * `body` - The response body
+///
+/// ### Returns
+///
+/// The ETag, e.g. `W/"1f2e3d4c5b6a7988"`
+pub fn weak_etag(body: &[u8]) -> String {
+	let mut hasher = DefaultHasher::new();
+	match serde_json::from_slice::<serde_json::Value>(body) {
+		Ok(mut json) => {
+			strip_computed_fields(&mut json);
+			json.to_string().hash(&mut hasher);
+		}
+		Err(_) => body.hash(&mut hasher),
+	}
+	format!("W/\"{:016x}\"", hasher.finish())
+}
+
+/// Removes the computed card fields from a response's top-level object, or
+/// from each object in a top-level list
+///
+/// Nested values, such as an item's `item_data`, are left alone, since a
+/// key there with the same name is stored data.
+fn strip_computed_fields(json: &mut serde_json::Value) {
+	let strip = |value: &mut serde_json::Value| {
+		if let Some(obj) = value.as_object_mut() {
+			for field in Card::COMPUTED_FIELDS {
+				obj.remove(field);
+			}
+		}
+	};
+	match json {
+		serde_json::Value::Array(values) => values.iter_mut().for_each(strip),
+		value => strip(value),
+	}
+}
+
+/// Checks whether a request's `If-None-Match` header matches an ETag
+///
+/// Tags are compared weakly, as RFC 9110 requires for `If-None-Match`, so
+/// `"abc"` and `W/"abc"` match each other. `*` matches any tag.
+///
+/// ### Arguments
+///
+/// * `headers` - The request's headers
+/// * `etag` - The ETag of the current response
+///
+/// ### Returns
+///
+/// Whether the client already has the current response
+fn if_none_match_matches(headers: &HeaderMap, etag: &str) -> bool {
+	let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+	let etag = opaque(etag);
+	headers
+		.get_all(header::IF_NONE_MATCH)
+		.iter()
+		.filter_map(|value| value.to_str().ok())
+		.flat_map(|value| value.split(','))
+		.any(|tag| tag.trim() == "*" || opaque(tag) == etag)
+}
+
+/// Middleware adding a weak `ETag` to successful GET responses, and
+/// answering `304 Not Modified` when the request's `If-None-Match` matches it
+///
+/// Other methods and unsuccessful responses pass through untouched.
+///
+/// ### Arguments
+///
+/// * `request` - The incoming request
+/// * `next` - The rest of the middleware stack and the handler
+///
+/// ### Returns
+///
+/// The response from `next` with an `ETag` header, or an empty 304
+pub async fn etag(request: Request, next: Next) -> Response {
+	if request.method() != Method::GET {
+		return next.run(request).await;
+	}
+
+	let request_headers = request.headers().clone();
+	let response = next.run(request).await;
+	if response.status() != StatusCode::OK {
+		return response;
+	}
+
+	let (mut parts, body) = response.into_parts();
+	let bytes = match to_bytes(body, usize::MAX).await {
+		Ok(bytes) => bytes,
+		Err(e) => {
+			warn!("Failed to read response body for ETag: {}", e);
+			return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+		}
+	};
+
+	let etag = weak_etag(&bytes);
+	let etag_header = HeaderValue::from_str(&etag).expect("ETags are valid header values");
+
+	if if_none_match_matches(&request_headers, &etag) {
+		debug!("Client already has {}, responding 304", etag);
+		return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag_header)]).into_response();
+	}
+
+	parts.headers.insert(header::ETAG, etag_header);
+	Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_weak_etag_depends_only_on_the_body() {
+		assert_eq!(weak_etag(b"[1,2,3]"), weak_etag(b"[1,2,3]"));
+		assert_ne!(weak_etag(b"[1,2,3]"), weak_etag(b"[1,2]"));
+		assert!(weak_etag(b"").starts_with("W/\""));
+	}
+
+	#[test]
+	fn test_weak_etag_ignores_computed_card_fields() {
+		let card = |retrievability: f64, due_in: &str| {
+			serde_json::json!({
+				"id": "card-1",
+				"next_review": "2026-03-10T12:00:00Z",
+				"retrievability": retrievability,
+				"due_in": due_in,
+			})
+		};
+		let etag_of = |json: serde_json::Value| weak_etag(json.to_string().as_bytes());
+
+		assert_eq!(
+			etag_of(card(0.9, "3 days")),
+			etag_of(card(0.8, "2 days"))
+		);
+		assert_eq!(
+			etag_of(serde_json::json!([card(0.9, "3 days")])),
+			etag_of(serde_json::json!([card(0.8, "2 days")]))
+		);
+
+		// Stored fields still count, including ones with the same name
+		// nested in stored data
+		let mut moved = card(0.9, "3 days");
+		moved["next_review"] = "2026-03-11T12:00:00Z".into();
+		assert_ne!(etag_of(card(0.9, "3 days")), etag_of(moved));
+		assert_ne!(
+			etag_of(serde_json::json!({"item_data": {"due_in": "1d"}})),
+			etag_of(serde_json::json!({"item_data": {"due_in": "2d"}}))
+		);
+	}
+
+	#[test]
+	fn test_if_none_match_matches() {
+		let etag = weak_etag(b"body");
+		let headers_with = |value: &str| {
+			let mut headers = HeaderMap::new();
+			headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(value).unwrap());
+			headers
+		};
+
+		assert!(if_none_match_matches(&headers_with(&etag), &etag));
+		assert!(if_none_match_matches(&headers_with("*"), &etag));
+		// Strong and weak forms of the same tag match
+		let strong = etag.trim_start_matches("W/").to_string();
+		assert!(if_none_match_matches(&headers_with(&strong), &etag));
+		// Any tag in a list matches
+		let list = format!("W/\"other\", {}", etag);
+		assert!(if_none_match_matches(&headers_with(&list), &etag));
+
+		assert!(!if_none_match_matches(&headers_with("W/\"other\""), &etag));
+		assert!(!if_none_match_matches(&HeaderMap::new(), &etag));
+	}
+}
@@ -0,0 +1,119 @@
+use axum::extract::State;
+use std::sync::Arc;
+use tracing::{debug, info, instrument};
+
+use super::Json;
+use crate::db::DbPool;
+use crate::dto::{BackfillLapsesResult, MigrationStatus, VacuumResult};
+use crate::errors::ApiError;
+use crate::models::BackupLogEntry;
+use crate::repo;
+
+/// Handler for vacuuming the database
+///
+/// This function handles POST requests to `/admin/vacuum`, which is only
+/// routed when the `admin` setting is on. Vacuuming rewrites the whole
+/// database file, so other requests may block briefly while it runs.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+///
+/// ### Returns
+///
+/// The database's size before and after, and the space freed, as JSON
+#[instrument(skip(pool))]
+pub async fn vacuum_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+) -> Result<Json<VacuumResult>, ApiError> {
+	info!("Vacuuming database");
+
+	let result = repo::vacuum_database(&pool)
+		.await
+		.map_err(ApiError::Database)?;
+
+	info!("Vacuuming freed {} bytes", result.freed_bytes);
+
+	Ok(Json(result))
+}
+
+/// Handler for recounting every card's lapses from its review history
+///
+/// This function handles POST requests to `/admin/backfill-lapses`, which is
+/// only routed when the `admin` setting is on. It's for databases with
+/// reviews from before lapses were counted, and replays every reviewed
+/// card's history, so it can take a while on a large collection.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+///
+/// ### Returns
+///
+/// How many cards' lapse counts were corrected, as JSON
+#[instrument(skip(pool))]
+pub async fn backfill_lapses_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+) -> Result<Json<BackfillLapsesResult>, ApiError> {
+	info!("Backfilling lapses");
+
+	let cards_updated = repo::backfill_lapses(&pool)
+		.await
+		.map_err(ApiError::Database)?;
+
+	info!("Backfilled lapses for {} cards", cards_updated);
+
+	Ok(Json(BackfillLapsesResult { cards_updated }))
+}
+
+/// Handler for getting the database's migration status
+///
+/// This function handles GET requests to `/admin/migrations`, which is only
+/// routed when the `admin` setting is on. It's read-only, for diagnosing
+/// deployments.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+///
+/// ### Returns
+///
+/// The applied and pending migration versions as JSON
+#[instrument(skip(pool))]
+pub async fn migration_status_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+) -> Result<Json<MigrationStatus>, ApiError> {
+	debug!("Getting migration status");
+
+	let status = repo::get_migration_status(&pool).map_err(ApiError::Database)?;
+
+	Ok(Json(status))
+}
+
+/// Handler for listing recent periodic backup attempts
+///
+/// This function handles GET requests to `/admin/backups`, which is only
+/// routed when the `admin` setting is on. It's read-only, for checking that
+/// backups are still being taken.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+///
+/// ### Returns
+///
+/// The recorded backup attempts, newest first, as JSON
+#[instrument(skip(pool))]
+pub async fn list_backups_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+) -> Result<Json<Vec<BackupLogEntry>>, ApiError> {
+	debug!("Listing backup attempts");
+
+	let entries = repo::list_backup_attempts(&pool).map_err(ApiError::Database)?;
+
+	Ok(Json(entries))
+}
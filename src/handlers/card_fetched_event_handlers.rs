@@ -1,13 +1,11 @@
-use axum::{
-	Json,
-	extract::{Path, State},
-};
+use axum::extract::{Path, State};
 use std::sync::Arc;
 use tracing::{debug, info, instrument};
 
+use super::Json;
 use crate::db::DbPool;
 use crate::dto::CreateCardFetchedEventDto;
-use crate::errors::ApiError;
+use crate::errors::{ApiError, Resource};
 use crate::models::{CardEventFnName, CardFetchedEvent, ItemTypeId};
 use crate::repo;
 use crate::repo::{
@@ -48,7 +46,7 @@ pub async fn create_card_fetched_event_handler(
 	.await
 	.map_err(|e| match e {
 		CreateCardFetchedEventError::Duplicate => ApiError::Conflict(e.to_string()),
-		CreateCardFetchedEventError::ItemTypeNotFound => ApiError::NotFound,
+		CreateCardFetchedEventError::ItemTypeNotFound => ApiError::NotFound(Resource::ItemType),
 		CreateCardFetchedEventError::UnknownFunction(name) => ApiError::UnknownCardEventFn(name),
 		CreateCardFetchedEventError::Other(err) => ApiError::Database(err),
 	})?;
@@ -83,7 +81,7 @@ pub async fn list_card_fetched_events_handler(
 	// registered" (200 with `[]`) without a TOCTOU window between two
 	// separate queries.
 	let events = repo::list_events_for_item_type(&pool, &item_type_id).map_err(|e| match e {
-		ListEventsForItemTypeError::ItemTypeNotFound => ApiError::NotFound,
+		ListEventsForItemTypeError::ItemTypeNotFound => ApiError::NotFound(Resource::ItemType),
 		ListEventsForItemTypeError::Other(err) => ApiError::Database(err),
 	})?;
 
@@ -118,8 +116,8 @@ pub async fn delete_card_fetched_event_handler(
 	repo::delete_card_fetched_event(&pool, &item_type_id, &function_name)
 		.await
 		.map_err(|e| match e {
-			DeleteCardFetchedEventError::ItemTypeNotFound => ApiError::NotFound,
-			DeleteCardFetchedEventError::NotFound => ApiError::NotFound,
+			DeleteCardFetchedEventError::ItemTypeNotFound => ApiError::NotFound(Resource::ItemType),
+			DeleteCardFetchedEventError::NotFound => ApiError::NotFound(Resource::CardFetchedEvent),
 			DeleteCardFetchedEventError::Other(err) => ApiError::Database(err),
 		})?;
 
@@ -157,7 +155,7 @@ mod tests {
 		let res = create_card_fetched_event_handler(
 			axum::extract::State(pool.clone()),
 			axum::extract::Path(it.clone()),
-			axum::Json(CreateCardFetchedEventDto {
+			Json(CreateCardFetchedEventDto {
 				order_index: OrderIndex(0),
 				function_name: CardEventFnName("test_set_title".to_owned()),
 			}),
@@ -178,14 +176,18 @@ mod tests {
 		let err = create_card_fetched_event_handler(
 			axum::extract::State(pool.clone()),
 			axum::extract::Path(ItemTypeId("nope".to_owned())),
-			axum::Json(CreateCardFetchedEventDto {
+			Json(CreateCardFetchedEventDto {
 				order_index: OrderIndex(0),
 				function_name: CardEventFnName("test_set_title".to_owned()),
 			}),
 		)
 		.await
 		.expect_err("should fail");
-		assert!(matches!(err, ApiError::NotFound), "got {:?}", err);
+		assert!(
+			matches!(err, ApiError::NotFound(Resource::ItemType)),
+			"got {:?}",
+			err
+		);
 	}
 
 	#[tokio::test]
@@ -195,7 +197,7 @@ mod tests {
 		let err = create_card_fetched_event_handler(
 			axum::extract::State(pool.clone()),
 			axum::extract::Path(it),
-			axum::Json(CreateCardFetchedEventDto {
+			Json(CreateCardFetchedEventDto {
 				order_index: OrderIndex(0),
 				function_name: CardEventFnName("not_registered".to_owned()),
 			}),
@@ -216,7 +218,7 @@ mod tests {
 		let _ = create_card_fetched_event_handler(
 			axum::extract::State(pool.clone()),
 			axum::extract::Path(it.clone()),
-			axum::Json(CreateCardFetchedEventDto {
+			Json(CreateCardFetchedEventDto {
 				order_index: payload.order_index,
 				function_name: payload.function_name.clone(),
 			}),
@@ -227,7 +229,7 @@ mod tests {
 		let err = create_card_fetched_event_handler(
 			axum::extract::State(pool.clone()),
 			axum::extract::Path(it),
-			axum::Json(payload),
+			Json(payload),
 		)
 		.await
 		.expect_err("should fail");
@@ -243,7 +245,11 @@ mod tests {
 		)
 		.await
 		.expect_err("should 404 when the item type does not exist");
-		assert!(matches!(err, ApiError::NotFound), "got {:?}", err);
+		assert!(
+			matches!(err, ApiError::NotFound(Resource::ItemType)),
+			"got {:?}",
+			err
+		);
 	}
 
 	#[tokio::test]
@@ -256,7 +262,11 @@ mod tests {
 		)
 		.await
 		.expect_err("should fail");
-		assert!(matches!(err, ApiError::NotFound), "got {:?}", err);
+		assert!(
+			matches!(err, ApiError::NotFound(Resource::CardFetchedEvent)),
+			"got {:?}",
+			err
+		);
 	}
 
 	/// Wire-format test: `order_index` must deserialize as a non-negative
@@ -1,17 +1,30 @@
+use anyhow::anyhow;
 use axum::{
-	Json,
+	body::{Body, Bytes},
 	extract::{Path, State},
+	http::{StatusCode, header},
+	response::{IntoResponse, Response},
 };
 use axum_extra::extract::Query;
+use futures_util::stream;
 use std::sync::Arc;
-use tracing::{debug, info, instrument};
+use tracing::{debug, error, info, instrument};
 
-use crate::errors::ApiError;
+use super::Json;
+use crate::errors::{ApiError, Resource};
 use crate::models::Card;
 use crate::repo;
-use crate::{db::DbPool, models::ItemId};
 use crate::{
-	dto::{CreateCardDto, GetQueryDto, SortPositionAction},
+	db::DbPool,
+	models::{ItemId, ItemTypeId},
+};
+use crate::{
+	dto::{
+		BatchGetCardsDto, BuryCardDto, CreateCardDto, ForecastBucket, ForecastQueryDto,
+		GetQueryDto, HardestCardsQueryDto, ListLeechesQueryDto, RegenerateCardsQueryDto,
+		RelatedCardsQueryDto, SetCardDifficultyDto, SetCardNoteDto, SortPositionAction,
+		StatsOverview, SwapCardsDto, UpdateCardPriorityDto,
+	},
 	models::CardId,
 };
 
@@ -42,7 +55,7 @@ pub async fn create_card_handler(
 	// First check if the item exists
 	let item = repo::get_item(&pool, &item_id)
 		.map_err(ApiError::Database)?
-		.ok_or(ApiError::NotFound)?;
+		.ok_or(ApiError::NotFound(Resource::Item))?;
 
 	// Call the repository function to create the card
 	let card = repo::create_card(&pool, &item.get_id(), payload.card_index, payload.priority)
@@ -66,7 +79,7 @@ pub async fn create_card_handler(
 ///
 /// ### Returns
 ///
-/// The requested card as JSON, or null if not found
+/// The requested card as JSON, or 404 if it doesn't exist
 #[instrument(skip(pool), fields(card_id = %card_id))]
 pub async fn get_card_handler(
 	// Extract the database pool from the application state
@@ -91,7 +104,7 @@ pub async fn get_card_handler(
 		Some(card) => {
 			debug!("Card found with id: {}", card.get_id());
 			let json = if query.split_priority.unwrap_or(false) {
-				serde_json::to_value(&card).expect("Card serialization should never fail")
+				card.to_json()
 			} else {
 				card.to_json_hide_priority_offset()
 			};
@@ -99,11 +112,72 @@ pub async fn get_card_handler(
 		}
 		None => {
 			debug!("Card not found");
-			Ok(Json(serde_json::Value::Null))
+			Err(ApiError::NotFound(Resource::Card))
 		}
 	}
 }
 
+/// Handler for retrieving a card together with its item and item type
+///
+/// This function handles GET requests to `/cards/{card_id}/context`, saving
+/// clients the separate requests for the card's item and item type.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `card_id` - The ID of the card to retrieve, extracted from the URL path
+///
+/// ### Returns
+///
+/// The card, its item and its item type as `{card, item, item_type}`, or 404
+/// if the card doesn't exist
+///
+/// ### Errors
+///
+/// Returns a 500 if the card's item or item type is missing, since that
+/// means the database is inconsistent
+#[instrument(skip(pool), fields(card_id = %card_id))]
+pub async fn get_card_context_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the card ID from the URL path
+	Path(card_id): Path<CardId>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+	debug!("Getting card with its item and item type");
+
+	let card = repo::get_card(&pool, &card_id)
+		.await?
+		.ok_or(ApiError::NotFound(Resource::Card))?;
+
+	// The foreign keys should make these impossible, so a missing item or
+	// item type is data corruption: a 500 with the details logged, not a 404
+	let item = repo::get_item(&pool, &card.get_item_id())
+		.map_err(ApiError::Database)?
+		.ok_or_else(|| {
+			ApiError::Database(anyhow!(
+				"Card {} references missing item {}",
+				card.get_id(),
+				card.get_item_id()
+			))
+		})?;
+	let item_type = repo::get_item_type(&pool, &item.get_item_type())
+		.map_err(ApiError::Database)?
+		.ok_or_else(|| {
+			ApiError::Database(anyhow!(
+				"Item {} references missing item type {}",
+				item.get_id(),
+				item.get_item_type()
+			))
+		})?;
+
+	// The card is returned with its priority offset folded in, as elsewhere
+	Ok(Json(serde_json::json!({
+		"card": card.to_json_hide_priority_offset(),
+		"item": item,
+		"item_type": item_type,
+	})))
+}
+
 /// Handler for listing all cards with optional filtering
 ///
 /// This function handles GET requests to `/cards`.
@@ -138,7 +212,7 @@ pub async fn list_cards_handler(
 		.iter()
 		.map(|card| {
 			if split {
-				serde_json::to_value(card).expect("Card serialization should never fail")
+				card.to_json()
 			} else {
 				card.to_json_hide_priority_offset()
 			}
@@ -149,6 +223,363 @@ pub async fn list_cards_handler(
 	Ok(Json(json_cards))
 }
 
+/// Number of cards `GET /cards/stream` reads from the database at a time
+pub const CARD_STREAM_BATCH_SIZE: u32 = 500;
+
+/// Handler for streaming cards as newline-delimited JSON
+///
+/// This function handles GET requests to `/cards/stream`. It takes the same
+/// filters as `/cards`, but writes one card per line as it reads them in
+/// batches of [`CARD_STREAM_BATCH_SIZE`], so neither side has to hold every
+/// card at once.
+///
+/// The first batch is read before responding, so bad filters and database
+/// errors still get an error status. An error on a later batch can only end
+/// the stream early, and is logged.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `query` - Query parameters for filtering the results
+///
+/// ### Returns
+///
+/// An `application/x-ndjson` response with a line for each matching card
+#[instrument(skip(pool, query))]
+pub async fn stream_cards_handler(
+	// Extract the database connection pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract and parse query parameters
+	Query(query): Query<GetQueryDto>,
+) -> Result<Response, ApiError> {
+	debug!("Streaming cards with filters: {:?}", query);
+
+	let first_batch = repo::list_cards_page(&pool, &query, CARD_STREAM_BATCH_SIZE, 0).await?;
+	let split = query.split_priority.unwrap_or(false);
+
+	// Each step writes out one batch, first reading the next one (if this
+	// one was full) to carry over to the following step with its offset
+	let batches = stream::unfold(Some((Ok(first_batch), 0)), move |state| {
+		let pool = pool.clone();
+		let query = query.clone();
+		async move {
+			let (batch, offset) = state?;
+			let cards = match batch {
+				Ok(cards) if cards.is_empty() => return None,
+				Ok(cards) => cards,
+				Err(e) => {
+					error!("Stopping card stream at offset {}: {}", offset, e);
+					return Some((Err(anyhow::Error::from(e)), None));
+				}
+			};
+
+			let mut lines = Vec::new();
+			for card in &cards {
+				let json = if split {
+					card.to_json()
+				} else {
+					card.to_json_hide_priority_offset()
+				};
+				if let Err(e) = serde_json::to_writer(&mut lines, &json) {
+					return Some((Err(anyhow::Error::from(e)), None));
+				}
+				lines.push(b'\n');
+			}
+
+			let next = if cards.len() < CARD_STREAM_BATCH_SIZE as usize {
+				None
+			} else {
+				let next_offset = offset + CARD_STREAM_BATCH_SIZE;
+				let next_batch =
+					repo::list_cards_page(&pool, &query, CARD_STREAM_BATCH_SIZE, next_offset).await;
+				Some((next_batch, next_offset))
+			};
+			Some((Ok(Bytes::from(lines)), next))
+		}
+	});
+
+	Ok((
+		[(header::CONTENT_TYPE, "application/x-ndjson")],
+		Body::from_stream(batches),
+	)
+		.into_response())
+}
+
+/// Handler for getting the next card to review
+///
+/// This function handles GET requests to `/cards/next`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `query` - Query parameters for filtering the results, as for `/cards`
+///
+/// ### Returns
+///
+/// The highest-ordered due card matching the filters as JSON, or
+/// 204 No Content if no card is due
+#[instrument(skip(pool, query))]
+pub async fn next_card_handler(
+	// Extract the database connection pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract and parse query parameters
+	Query(query): Query<GetQueryDto>,
+) -> Result<Response, ApiError> {
+	debug!("Getting next card with filters: {:?}", query);
+
+	let Some(card) = repo::next_card(&pool, &query).await? else {
+		debug!("No cards due");
+		return Ok(StatusCode::NO_CONTENT.into_response());
+	};
+
+	debug!("Next card is {}", card.get_id());
+	let json = if query.split_priority.unwrap_or(false) {
+		card.to_json()
+	} else {
+		card.to_json_hide_priority_offset()
+	};
+	Ok(Json(json).into_response())
+}
+
+/// Handler for getting a random card to review
+///
+/// This function handles GET requests to `/cards/random`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `query` - Query parameters for filtering the results, as for `/cards`
+///
+/// ### Returns
+///
+/// A due card matching the filters, picked at random, as JSON, or
+/// 204 No Content if no card is due
+#[instrument(skip(pool, query))]
+pub async fn random_card_handler(
+	// Extract the database connection pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract and parse query parameters
+	Query(query): Query<GetQueryDto>,
+) -> Result<Response, ApiError> {
+	debug!("Getting random card with filters: {:?}", query);
+
+	let Some(card) = repo::random_card(&pool, &query).await? else {
+		debug!("No cards due");
+		return Ok(StatusCode::NO_CONTENT.into_response());
+	};
+
+	debug!("Random card is {}", card.get_id());
+	let json = if query.split_priority.unwrap_or(false) {
+		card.to_json()
+	} else {
+		card.to_json_hide_priority_offset()
+	};
+	Ok(Json(json).into_response())
+}
+
+/// Handler for listing leech cards
+///
+/// This function handles GET requests to `/cards/leeches`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `query` - The leech `threshold` (defaults to 8) and whether to `suspend` the leeches
+///
+/// ### Returns
+///
+/// The cards with at least `threshold` failed reviews as JSON, most-failed first
+#[instrument(skip(pool))]
+pub async fn list_leeches_handler(
+	// Extract the database connection pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract and parse query parameters
+	Query(query): Query<ListLeechesQueryDto>,
+) -> Result<Json<Vec<serde_json::Value>>, ApiError> {
+	debug!(
+		"Listing leeches with threshold {} (suspend: {})",
+		query.threshold, query.suspend
+	);
+
+	let leeches = repo::list_leeches(&pool, query.threshold, query.suspend).await?;
+
+	info!("Retrieved {} leeches", leeches.len());
+
+	Ok(Json(
+		leeches
+			.iter()
+			.map(|card| card.to_json_hide_priority_offset())
+			.collect(),
+	))
+}
+
+/// Handler for listing the hardest cards
+///
+/// This function handles GET requests to `/cards/hardest`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `query` - The most cards to return (`limit`, defaults to 10, at most 100)
+///   and the fewest reviews a card needs to be ranked (`min_reviews`,
+///   defaults to 5)
+///
+/// ### Returns
+///
+/// The cards with the highest share of reviews rated 1 as JSON, hardest first
+#[instrument(skip(pool))]
+pub async fn list_hardest_cards_handler(
+	// Extract the database connection pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract and parse query parameters
+	Query(query): Query<HardestCardsQueryDto>,
+) -> Result<Json<Vec<serde_json::Value>>, ApiError> {
+	debug!(
+		"Listing up to {} hardest cards with at least {} reviews",
+		query.limit, query.min_reviews
+	);
+
+	let hardest = repo::list_hardest_cards(&pool, query.limit, query.min_reviews).await?;
+
+	info!("Retrieved {} hardest cards", hardest.len());
+
+	Ok(Json(
+		hardest
+			.iter()
+			.map(|card| card.to_json_hide_priority_offset())
+			.collect(),
+	))
+}
+
+/// Handler for listing the cards related to a card
+///
+/// This function handles GET requests to `/cards/{card_id}/related`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `card_id` - The ID of the card to find related cards for
+/// * `query` - The most cards to return (`limit`, defaults to 10, at most 100)
+///
+/// ### Returns
+///
+/// The cards of the items sharing the most tags with the card's item as JSON,
+/// most shared tags first, or 404 if the card doesn't exist
+#[instrument(skip(pool), fields(card_id = %card_id))]
+pub async fn list_related_cards_handler(
+	// Extract the database connection pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the card ID from the URL path
+	Path(card_id): Path<CardId>,
+	// Extract and parse query parameters
+	Query(query): Query<RelatedCardsQueryDto>,
+) -> Result<Json<Vec<serde_json::Value>>, ApiError> {
+	debug!("Listing up to {} related cards", query.limit);
+
+	let related = repo::list_related_cards(&pool, &card_id, query.limit)
+		.await?
+		.ok_or(ApiError::NotFound(Resource::Card))?;
+
+	info!("Retrieved {} related cards", related.len());
+
+	Ok(Json(
+		related
+			.iter()
+			.map(|card| card.to_json_hide_priority_offset())
+			.collect(),
+	))
+}
+
+/// Handler for forecasting when cards come due
+///
+/// This function handles GET requests to `/cards/forecast`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `query` - How many `days` to forecast (defaults to 7, at most 365)
+///
+/// ### Returns
+///
+/// The number of cards already overdue, then the number coming due on each
+/// local day from today, as JSON
+#[instrument(skip(pool))]
+pub async fn forecast_handler(
+	// Extract the database connection pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract and parse query parameters
+	Query(query): Query<ForecastQueryDto>,
+) -> Result<Json<Vec<ForecastBucket>>, ApiError> {
+	debug!("Forecasting due cards over {} days", query.days);
+
+	let buckets = repo::forecast_due_cards(&pool, query.days).map_err(ApiError::Database)?;
+
+	Ok(Json(buckets))
+}
+
+/// Handler for getting an overview of the deck's health
+///
+/// This function handles GET requests to `/stats/overview`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+///
+/// ### Returns
+///
+/// The number of new, learning, young and mature cards, and their average
+/// ease and difficulty, as JSON
+#[instrument(skip(pool))]
+pub async fn get_stats_overview_handler(
+	// Extract the database connection pool from the application state
+	State(pool): State<Arc<DbPool>>,
+) -> Result<Json<StatsOverview>, ApiError> {
+	debug!("Getting stats overview");
+
+	let overview = repo::get_stats_overview(&pool).map_err(ApiError::Database)?;
+
+	Ok(Json(overview))
+}
+
+/// Handler for regenerating an item's cards from its item type's rules
+///
+/// This function handles POST requests to `/items/{item_id}/regenerate_cards`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `item_id` - The ID of the item to regenerate cards for
+/// * `query` - Whether to `remove_extra` cards the item no longer calls for
+///
+/// ### Returns
+///
+/// All of the item's cards afterwards, in index order, as JSON, or 404 if
+/// the item doesn't exist
+#[instrument(skip(pool), fields(item_id = %item_id))]
+pub async fn regenerate_cards_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the item ID from the URL path
+	Path(item_id): Path<ItemId>,
+	// Extract query parameters
+	Query(query): Query<RegenerateCardsQueryDto>,
+) -> Result<Json<Vec<serde_json::Value>>, ApiError> {
+	debug!("Regenerating cards for item");
+
+	let cards = repo::regenerate_cards_for_item(&pool, &item_id, query.remove_extra)
+		.await?
+		.ok_or(ApiError::NotFound(Resource::Item))?;
+
+	info!("Item {} now has {} cards", item_id, cards.len());
+
+	Ok(Json(
+		cards
+			.iter()
+			.map(|card| card.to_json_hide_priority_offset())
+			.collect(),
+	))
+}
+
 /// Handler for listing cards for a specific item
 ///
 /// This function handles GET requests to `/items/{item_id}/cards`.
@@ -175,7 +606,7 @@ pub async fn list_cards_by_item_handler(
 	// First check if the item exists
 	let _item = repo::get_item(&pool, &item_id)
 		.map_err(ApiError::Database)?
-		.ok_or(ApiError::NotFound)?;
+		.ok_or(ApiError::NotFound(Resource::Item))?;
 
 	// Call the cache-aware repo wrapper, which ensures all stale `card_data`
 	// caches are recomputed before returning. `?` uses the typed
@@ -189,7 +620,64 @@ pub async fn list_cards_by_item_handler(
 		.iter()
 		.map(|card| {
 			if split {
-				serde_json::to_value(card).expect("Card serialization should never fail")
+				card.to_json()
+			} else {
+				card.to_json_hide_priority_offset()
+			}
+		})
+		.collect();
+
+	// Return the list of cards as JSON
+	Ok(Json(json_cards))
+}
+
+/// Handler for listing cards of a specific item type
+///
+/// This function handles GET requests to `/item_types/{item_type_id}/cards`.
+/// The usual card query parameters further filter the result; any
+/// `item_type_id` among them is overridden by the path.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `item_type_id` - The ID of the item type to get cards for
+/// * `query` - Query parameters for further filtering the results
+///
+/// ### Returns
+///
+/// A list of cards of items with the specified item type as JSON, or 404 if
+/// the item type doesn't exist
+#[instrument(skip(pool, query), fields(item_type_id = %item_type_id))]
+pub async fn list_cards_by_item_type_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the item type ID from the URL path
+	Path(item_type_id): Path<ItemTypeId>,
+	// Extract query parameters
+	Query(mut query): Query<GetQueryDto>,
+) -> Result<Json<Vec<serde_json::Value>>, ApiError> {
+	debug!("Listing cards for item type");
+
+	// Distinguish an unknown item type from one without cards
+	repo::get_item_type(&pool, &item_type_id)
+		.map_err(ApiError::Database)?
+		.ok_or(ApiError::NotFound(Resource::ItemType))?;
+
+	query.item_type_id = Some(item_type_id.clone());
+	let cards = repo::list_cards(&pool, &query).await?;
+
+	info!(
+		"Retrieved {} cards for item type {}",
+		cards.len(),
+		item_type_id
+	);
+
+	let split = query.split_priority.unwrap_or(false);
+	let json_cards: Vec<serde_json::Value> = cards
+		.iter()
+		.map(|card| {
+			if split {
+				card.to_json()
 			} else {
 				card.to_json_hide_priority_offset()
 			}
@@ -212,7 +700,7 @@ pub async fn list_cards_by_item_handler(
 ///
 /// ### Returns
 ///
-/// The updated card as JSON
+/// An empty successful response, or 404 if the card doesn't exist
 #[instrument(skip(pool), fields(card_id = %id))]
 pub async fn suspend_card_handler(
 	// Extract the database pool from the application state
@@ -228,6 +716,10 @@ pub async fn suspend_card_handler(
 		debug!("Resuming card");
 	}
 
+	repo::get_card_raw(&pool, &id)
+		.map_err(ApiError::Database)?
+		.ok_or(ApiError::NotFound(Resource::Card))?;
+
 	repo::set_card_suspended(&pool, &id, payload)
 		.await
 		.map_err(ApiError::Database)?;
@@ -241,6 +733,257 @@ pub async fn suspend_card_handler(
 	Ok(())
 }
 
+/// Handler for burying a card until later
+///
+/// This function handles POST requests to `/cards/{card_id}/bury`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `id` - The ID of the card to bury
+/// * `payload` - Optional JSON body giving when the card returns; without
+///   one it returns at the start of the next local day
+///
+/// ### Returns
+///
+/// The buried card as JSON, or 404 if the card doesn't exist
+#[instrument(skip(pool, payload), fields(card_id = %id))]
+pub async fn bury_card_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the card ID from the URL path
+	Path(id): Path<CardId>,
+	// Extract and deserialize the optional JSON request body
+	payload: Option<Json<BuryCardDto>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+	debug!("Burying card");
+
+	// Map a missing card to 404 rather than the repo's "Card not found" 500
+	repo::get_card_raw(&pool, &id)
+		.map_err(ApiError::Database)?
+		.ok_or(ApiError::NotFound(Resource::Card))?;
+
+	let until = payload.and_then(|Json(payload)| payload.until);
+	let until = repo::bury_card(&pool, &id, until)
+		.await
+		.map_err(ApiError::Database)?;
+
+	info!("Successfully buried card until {}", until);
+
+	let card = repo::get_card(&pool, &id)
+		.await?
+		.ok_or(ApiError::NotFound(Resource::Card))?;
+	Ok(Json(card.to_json_hide_priority_offset()))
+}
+
+/// Handler for setting or clearing a card's note
+///
+/// This function handles PUT requests to `/cards/{card_id}/note`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `id` - The ID of the card to annotate
+/// * `payload` - The new note, where a null note clears it
+///
+/// ### Returns
+///
+/// The updated card as JSON, or 404 if the card doesn't exist
+#[instrument(skip(pool, payload), fields(card_id = %id))]
+pub async fn set_card_note_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the card ID from the URL path
+	Path(id): Path<CardId>,
+	// Extract and deserialize the JSON request body
+	Json(payload): Json<SetCardNoteDto>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+	debug!("Setting card note");
+
+	// Map a missing card to 404 rather than the repo's "Card not found" 500
+	repo::get_card_raw(&pool, &id)
+		.map_err(ApiError::Database)?
+		.ok_or(ApiError::NotFound(Resource::Card))?;
+
+	repo::set_card_note(&pool, &id, payload.note)
+		.await
+		.map_err(ApiError::Database)?;
+
+	info!("Successfully set card note");
+
+	let card = repo::get_card(&pool, &id)
+		.await?
+		.ok_or(ApiError::NotFound(Resource::Card))?;
+	Ok(Json(card.to_json_hide_priority_offset()))
+}
+
+/// Handler for fetching several cards at once
+///
+/// This function handles POST requests to `/cards/batch_get`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `query` - Query parameters; only `split_priority` is used
+/// * `payload` - The request payload containing the IDs of the cards to fetch
+///
+/// ### Returns
+///
+/// One entry per requested ID as JSON, in request order: the card, or null
+/// if there's no card with that ID. 400 if more than `MAX_BATCH_GET_CARDS`
+/// IDs are requested.
+#[instrument(skip(pool, payload), fields(count = payload.card_ids.len()))]
+pub async fn batch_get_cards_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract query parameters
+	Query(query): Query<GetQueryDto>,
+	// Extract and deserialize the JSON request body
+	Json(payload): Json<BatchGetCardsDto>,
+) -> Result<Json<Vec<Option<serde_json::Value>>>, ApiError> {
+	debug!("Fetching a batch of cards");
+
+	if payload.card_ids.len() > repo::MAX_BATCH_GET_CARDS {
+		return Err(ApiError::BatchTooLarge(format!(
+			"At most {} cards can be fetched at once, got {}",
+			repo::MAX_BATCH_GET_CARDS,
+			payload.card_ids.len()
+		)));
+	}
+
+	let cards = repo::get_cards_by_ids(&pool, &payload.card_ids).await?;
+	let split_priority = query.split_priority.unwrap_or(false);
+	let cards: Vec<Option<serde_json::Value>> = cards
+		.into_iter()
+		.map(|card| {
+			card.map(|card| {
+				if split_priority {
+					card.to_json()
+				} else {
+					card.to_json_hide_priority_offset()
+				}
+			})
+		})
+		.collect();
+
+	info!(
+		"Fetched {} of {} cards",
+		cards.iter().flatten().count(),
+		cards.len()
+	);
+
+	Ok(Json(cards))
+}
+
+/// Handler for suspending or unsuspending every card matching a filter
+///
+/// This function handles POST requests to `/cards/suspend`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `query` - Query filters selecting the cards; `suspended_filter` is ignored
+/// * `payload` - The request payload containing the new suspension state
+///
+/// ### Returns
+///
+/// The number of cards whose suspension state changed as JSON
+#[instrument(skip(pool))]
+pub async fn suspend_cards_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the query parameters
+	Query(query): Query<GetQueryDto>,
+	// Extract and deserialize the JSON request body
+	Json(payload): Json<bool>,
+) -> Result<Json<usize>, ApiError> {
+	debug!("Setting suspension of matching cards to {}", payload);
+
+	let updated = repo::set_cards_suspended(&pool, &query, payload)
+		.await
+		.map_err(ApiError::Database)?;
+
+	info!("Successfully set suspension of {} cards", updated);
+
+	Ok(Json(updated))
+}
+
+/// Handler for setting or unsetting how hard the user finds a card
+///
+/// This function handles PUT requests to `/cards/{card_id}/difficulty`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `id` - The ID of the card
+/// * `payload` - The new difficulty, where a null difficulty unsets it
+///
+/// ### Returns
+///
+/// The updated card as JSON, 400 if the difficulty isn't between 0 and 1, or
+/// 404 if the card doesn't exist
+#[instrument(skip(pool), fields(card_id = %id, difficulty = ?payload.difficulty))]
+pub async fn set_card_difficulty_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the card ID from the URL path
+	Path(id): Path<CardId>,
+	// Extract and deserialize the JSON request body
+	Json(payload): Json<SetCardDifficultyDto>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+	debug!("Setting card difficulty");
+
+	if let Some(difficulty) = payload.difficulty
+		&& !(0.0..=1.0).contains(&difficulty)
+	{
+		return Err(ApiError::InvalidDifficulty(format!(
+			"Difficulty must be between 0 and 1, got {}",
+			difficulty
+		)));
+	}
+
+	// Map a missing card to 404 rather than the repo's "Card not found" 500
+	repo::get_card_raw(&pool, &id)
+		.map_err(ApiError::Database)?
+		.ok_or(ApiError::NotFound(Resource::Card))?;
+
+	repo::set_card_difficulty(&pool, &id, payload.difficulty)
+		.await
+		.map_err(ApiError::Database)?;
+
+	info!("Successfully set card difficulty");
+
+	let card = repo::get_card(&pool, &id)
+		.await?
+		.ok_or(ApiError::NotFound(Resource::Card))?;
+	Ok(Json(card.to_json_hide_priority_offset()))
+}
+
+/// Handler for unburying every buried card
+///
+/// This function handles POST requests to `/cards/unbury_all`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+///
+/// ### Returns
+///
+/// The number of cards unburied as JSON
+#[instrument(skip(pool))]
+pub async fn unbury_all_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+) -> Result<Json<usize>, ApiError> {
+	debug!("Unburying all cards");
+
+	let unburied = repo::unbury_all(&pool).await.map_err(ApiError::Database)?;
+
+	info!("Successfully unburied {} cards", unburied);
+
+	Ok(Json(unburied))
+}
+
 /// Handler for updating a card's priority
 ///
 /// This function handles PATCH requests to `/cards/{id}/priority`.
@@ -249,22 +992,26 @@ pub async fn suspend_card_handler(
 ///
 /// * `pool` - The database connection pool
 /// * `id` - The ID of the card to update
-/// * `payload` - The request payload containing the new priority
+/// * `payload` - The request payload containing the new priority, and
+///   optionally the card version it was chosen against
 ///
 /// ### Returns
 ///
-/// The updated card as JSON
-#[instrument(skip(pool), fields(card_id = %id, priority = %priority))]
+/// The updated card as JSON, or 409 if a version was given and the card has
+/// changed since
+#[instrument(skip(pool), fields(card_id = %id, priority = %payload.priority(), version = ?payload.version()))]
 pub async fn update_card_priority_handler(
 	// Extract the database pool from the application state
 	State(pool): State<Arc<DbPool>>,
 	// Extract the card ID from the URL path
 	Path(id): Path<CardId>,
 	// Extract and deserialize the JSON request body
-	Json(priority): Json<f32>,
+	Json(payload): Json<UpdateCardPriorityDto>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
 	info!("Updating card priority");
 
+	let priority = payload.priority();
+
 	// Check if the priority is valid
 	if priority < 0.0 || priority > 1.0 {
 		return Err(ApiError::InvalidPriority(format!(
@@ -281,12 +1028,15 @@ pub async fn update_card_priority_handler(
 	// transaction.
 	let _card = repo::get_card_raw(&pool, &id)
 		.map_err(ApiError::Database)?
-		.ok_or(ApiError::NotFound)?;
+		.ok_or(ApiError::NotFound(Resource::Card))?;
 
 	// Call the repository function to update the card's priority (also resets priority_offset to 0)
-	let card = repo::update_card_priority(&pool, &id, priority)
+	let card = repo::update_card_priority(&pool, &id, priority, payload.version())
 		.await
-		.map_err(ApiError::Database)?;
+		.map_err(|e| match e.downcast::<repo::CardVersionConflictError>() {
+			Ok(conflict) => ApiError::Conflict(conflict.to_string()),
+			Err(e) => ApiError::Database(e),
+		})?;
 
 	info!("Successfully updated card priority to {}", priority);
 
@@ -306,7 +1056,8 @@ pub async fn update_card_priority_handler(
 ///
 /// ### Returns
 ///
-/// The updated card as JSON
+/// The updated card as JSON, or 404 if the card (or the card it is placed
+/// relative to) doesn't exist
 #[instrument(skip(pool), fields(card_id = %card_id))]
 pub async fn set_sort_position_handler(
 	State(pool): State<Arc<DbPool>>,
@@ -315,6 +1066,18 @@ pub async fn set_sort_position_handler(
 ) -> Result<Json<serde_json::Value>, ApiError> {
 	info!("Setting card sort position");
 
+	let target_id = match &payload {
+		SortPositionAction::Before { card_id } | SortPositionAction::After { card_id } => {
+			Some(card_id)
+		}
+		SortPositionAction::Top | SortPositionAction::Bottom => None,
+	};
+	for id in std::iter::once(&card_id).chain(target_id) {
+		repo::get_card_raw(&pool, id)
+			.map_err(ApiError::Database)?
+			.ok_or(ApiError::NotFound(Resource::Card))?;
+	}
+
 	let card = match payload {
 		SortPositionAction::Top => repo::move_card_to_top(&pool, &card_id)
 			.await
@@ -338,6 +1101,46 @@ pub async fn set_sort_position_handler(
 	Ok(Json(card.to_json_hide_priority_offset()))
 }
 
+/// Handler for swapping two cards' sort positions
+///
+/// This function handles POST requests to `/cards/swap`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `payload` - The IDs of the two cards to swap
+///
+/// ### Returns
+///
+/// The two updated cards as a JSON array, in the order given, or 404 if
+/// either card doesn't exist
+#[instrument(skip(pool), fields(card_a = %payload.card_a, card_b = %payload.card_b))]
+pub async fn swap_cards_handler(
+	State(pool): State<Arc<DbPool>>,
+	Json(payload): Json<SwapCardsDto>,
+) -> Result<Json<Vec<serde_json::Value>>, ApiError> {
+	info!("Swapping card sort positions");
+
+	for id in [&payload.card_a, &payload.card_b] {
+		repo::get_card_raw(&pool, id)
+			.map_err(ApiError::Database)?
+			.ok_or(ApiError::NotFound(Resource::Card))?;
+	}
+
+	let (card_a, card_b) = repo::swap_card_sort_positions(&pool, &payload.card_a, &payload.card_b)
+		.await
+		.map_err(ApiError::Database)?;
+
+	info!(
+		"Successfully swapped cards {} and {}",
+		payload.card_a, payload.card_b
+	);
+	Ok(Json(vec![
+		card_a.to_json_hide_priority_offset(),
+		card_b.to_json_hide_priority_offset(),
+	]))
+}
+
 /// Handler for clearing a single card's sort position
 ///
 /// This function handles DELETE requests to `/cards/{card_id}/sort_position`.
@@ -349,7 +1152,7 @@ pub async fn set_sort_position_handler(
 ///
 /// ### Returns
 ///
-/// An empty successful response
+/// An empty successful response, or 404 if the card doesn't exist
 #[instrument(skip(pool), fields(card_id = %card_id))]
 pub async fn clear_card_sort_position_handler(
 	State(pool): State<Arc<DbPool>>,
@@ -357,6 +1160,10 @@ pub async fn clear_card_sort_position_handler(
 ) -> Result<(), ApiError> {
 	info!("Clearing card sort position");
 
+	repo::get_card_raw(&pool, &card_id)
+		.map_err(ApiError::Database)?
+		.ok_or(ApiError::NotFound(Resource::Card))?;
+
 	repo::clear_card_sort_position(&pool, &card_id)
 		.await
 		.map_err(ApiError::Database)?;
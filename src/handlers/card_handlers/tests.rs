@@ -1,4 +1,5 @@
 use super::*;
+use crate::models::Rating;
 use crate::repo;
 use crate::test_utils::*;
 use serde_json::json;
@@ -59,7 +60,90 @@ async fn test_create_card_handler_not_found() {
 
 	// Check that we got a NotFound error
 	assert!(result.is_err());
-	assert!(matches!(result.unwrap_err(), ApiError::NotFound));
+	assert!(matches!(
+		result.unwrap_err(),
+		ApiError::NotFound(Resource::Item)
+	));
+}
+
+#[tokio::test]
+async fn test_list_cards_by_item_type_handler() {
+	let pool = setup_test_db();
+
+	// Two item types with one item each, two cards per item
+	let item_type1 = repo::create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item_type2 = repo::create_item_type(&pool, "Test Type 2".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item1 = repo::create_item(
+		&pool,
+		&item_type1.get_id(),
+		"Item 1".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	repo::create_item(
+		&pool,
+		&item_type2.get_id(),
+		"Item 2".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let cards1 = repo::get_cards_for_item(&pool, &item1.get_id()).unwrap();
+
+	// Only the first type's cards are returned
+	let result = list_cards_by_item_type_handler(
+		State(pool.clone()),
+		Path(item_type1.get_id()),
+		Query(GetQueryDto::default()),
+	)
+	.await
+	.unwrap();
+	let cards = result.0;
+	assert_eq!(cards.len(), 2);
+	for card in &cards1 {
+		assert!(cards.iter().any(|c| c["id"] == card.get_id().0));
+	}
+
+	// The usual filters still apply on top of the item type
+	repo::set_card_suspended(&pool, &cards1[0].get_id(), true)
+		.await
+		.unwrap();
+	let query = GetQueryDto {
+		suspended_filter: crate::SuspendedFilter::Only,
+		..Default::default()
+	};
+	let result = list_cards_by_item_type_handler(
+		State(pool.clone()),
+		Path(item_type1.get_id()),
+		Query(query),
+	)
+	.await
+	.unwrap();
+	let cards = result.0;
+	assert_eq!(cards.len(), 1);
+	assert_eq!(cards[0]["id"], cards1[0].get_id().0);
+}
+
+#[tokio::test]
+async fn test_list_cards_by_item_type_handler_not_found() {
+	let pool = setup_test_db();
+
+	let result = list_cards_by_item_type_handler(
+		State(pool.clone()),
+		Path(ItemTypeId("nonexistent".to_string())),
+		Query(GetQueryDto::default()),
+	)
+	.await;
+
+	assert!(matches!(
+		result,
+		Err(ApiError::NotFound(Resource::ItemType))
+	));
 }
 
 #[tokio::test]
@@ -112,11 +196,112 @@ async fn test_get_card_handler_not_found() {
 		Path(CardId("nonexistent".to_string())),
 		Query(GetQueryDto::default()),
 	)
+	.await;
+
+	// Check that we got a 404 rather than a null card
+	assert!(matches!(
+		result.unwrap_err(),
+		ApiError::NotFound(Resource::Card)
+	));
+}
+
+#[tokio::test]
+async fn test_batch_get_cards_handler() {
+	let pool = setup_test_db();
+
+	let item_type = repo::create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let card = repo::create_card(&pool, &item.get_id(), 3, 0.5)
+		.await
+		.unwrap();
+
+	let payload = BatchGetCardsDto {
+		card_ids: vec![CardId("nonexistent".to_string()), card.get_id()],
+	};
+	let result = batch_get_cards_handler(
+		State(pool.clone()),
+		Query(GetQueryDto::default()),
+		Json(payload),
+	)
+	.await
+	.unwrap();
+
+	let cards = &result.0;
+	assert_eq!(cards.len(), 2);
+	assert!(cards[0].is_none());
+	assert_eq!(cards[1].as_ref().unwrap()["id"], card.get_id().0);
+}
+
+#[tokio::test]
+async fn test_batch_get_cards_handler_too_large() {
+	let pool = setup_test_db();
+
+	let payload = BatchGetCardsDto {
+		card_ids: (0..=repo::MAX_BATCH_GET_CARDS)
+			.map(|_| CardId::new())
+			.collect(),
+	};
+	let result = batch_get_cards_handler(
+		State(pool.clone()),
+		Query(GetQueryDto::default()),
+		Json(payload),
+	)
+	.await;
+
+	assert!(matches!(result.unwrap_err(), ApiError::BatchTooLarge(_)));
+}
+
+#[tokio::test]
+async fn test_get_card_context_handler() {
+	let pool = setup_test_db();
+
+	let item_type = repo::create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
 	.await
 	.unwrap();
+	let card = repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone();
 
-	// Check that we got None
-	assert!(result.0.is_null());
+	let result = get_card_context_handler(State(pool.clone()), Path(card.get_id()))
+		.await
+		.unwrap();
+
+	let context = &result.0;
+	assert_eq!(context["card"]["id"], card.get_id().0);
+	assert_eq!(context["item"], serde_json::to_value(&item).unwrap());
+	assert_eq!(
+		context["item_type"],
+		serde_json::to_value(&item_type).unwrap()
+	);
+}
+
+#[tokio::test]
+async fn test_get_card_context_handler_not_found() {
+	let pool = setup_test_db();
+
+	let result =
+		get_card_context_handler(State(pool.clone()), Path(CardId("nonexistent".to_string())))
+			.await;
+
+	assert!(matches!(
+		result.unwrap_err(),
+		ApiError::NotFound(Resource::Card)
+	));
 }
 
 #[tokio::test]
@@ -226,7 +411,10 @@ async fn test_list_cards_by_item_handler_not_found() {
 
 	// Check that we got a NotFound error
 	assert!(result.is_err());
-	assert!(matches!(result.unwrap_err(), ApiError::NotFound));
+	assert!(matches!(
+		result.unwrap_err(),
+		ApiError::NotFound(Resource::Item)
+	));
 }
 
 #[tokio::test]
@@ -256,10 +444,13 @@ async fn test_update_card_priority_handler_success() {
 	let new_priority = 0.8;
 	let payload = new_priority;
 
-	let result =
-		update_card_priority_handler(State(pool.clone()), Path(card.get_id()), Json(payload))
-			.await
-			.unwrap();
+	let result = update_card_priority_handler(
+		State(pool.clone()),
+		Path(card.get_id()),
+		Json(payload.into()),
+	)
+	.await
+	.unwrap();
 
 	// Check the result
 	let updated_card = &result.0;
@@ -296,10 +487,13 @@ async fn test_update_card_priority_handler_boundary_values() {
 	let min_priority = 0.0;
 	let payload = min_priority;
 
-	let result =
-		update_card_priority_handler(State(pool.clone()), Path(card.get_id()), Json(payload))
-			.await
-			.unwrap();
+	let result = update_card_priority_handler(
+		State(pool.clone()),
+		Path(card.get_id()),
+		Json(payload.into()),
+	)
+	.await
+	.unwrap();
 
 	let updated_card = &result.0;
 	assert!((updated_card["priority"].as_f64().unwrap() as f32 - min_priority).abs() < 0.0001);
@@ -308,10 +502,13 @@ async fn test_update_card_priority_handler_boundary_values() {
 	let max_priority = 1.0;
 	let payload = max_priority;
 
-	let result =
-		update_card_priority_handler(State(pool.clone()), Path(card.get_id()), Json(payload))
-			.await
-			.unwrap();
+	let result = update_card_priority_handler(
+		State(pool.clone()),
+		Path(card.get_id()),
+		Json(payload.into()),
+	)
+	.await
+	.unwrap();
 
 	let updated_card = &result.0;
 	assert!((updated_card["priority"].as_f64().unwrap() as f32 - max_priority).abs() < 0.0001);
@@ -342,8 +539,12 @@ async fn test_update_card_priority_handler_invalid_priority_too_low() {
 	let below_min_priority = -0.1;
 	let payload = below_min_priority;
 
-	let result =
-		update_card_priority_handler(State(pool.clone()), Path(card.get_id()), Json(payload)).await;
+	let result = update_card_priority_handler(
+		State(pool.clone()),
+		Path(card.get_id()),
+		Json(payload.into()),
+	)
+	.await;
 
 	// Should return an error
 	assert!(result.is_err());
@@ -375,8 +576,12 @@ async fn test_update_card_priority_handler_invalid_priority_too_high() {
 	let above_max_priority = 1.1;
 	let payload = above_max_priority;
 
-	let result =
-		update_card_priority_handler(State(pool.clone()), Path(card.get_id()), Json(payload)).await;
+	let result = update_card_priority_handler(
+		State(pool.clone()),
+		Path(card.get_id()),
+		Json(payload.into()),
+	)
+	.await;
 
 	// Should return an error
 	assert!(result.is_err());
@@ -394,11 +599,412 @@ async fn test_update_card_priority_handler_nonexistent_card() {
 	let result = update_card_priority_handler(
 		State(pool.clone()),
 		Path(nonexistent_card_id),
-		Json(payload),
+		Json(payload.into()),
 	)
 	.await;
 
 	// Should return an error
 	assert!(result.is_err());
-	assert!(matches!(result.unwrap_err(), ApiError::NotFound));
+	assert!(matches!(
+		result.unwrap_err(),
+		ApiError::NotFound(Resource::Card)
+	));
+}
+
+#[tokio::test]
+async fn test_update_card_priority_handler_stale_version() {
+	let pool = setup_test_db();
+
+	let item_type = repo::create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let card = repo::create_card(&pool, &item.get_id(), 2, 0.5)
+		.await
+		.unwrap();
+
+	// Another client changes the card after this one read version 0
+	repo::update_card_priority(&pool, &card.get_id(), 0.7, None)
+		.await
+		.unwrap();
+
+	let payload: UpdateCardPriorityDto =
+		serde_json::from_value(json!({"priority": 0.9, "version": 0})).unwrap();
+	let result =
+		update_card_priority_handler(State(pool.clone()), Path(card.get_id()), Json(payload)).await;
+	assert!(matches!(result, Err(ApiError::Conflict(_))));
+
+	// Sending the version the card is now at succeeds
+	let payload: UpdateCardPriorityDto =
+		serde_json::from_value(json!({"priority": 0.9, "version": 1})).unwrap();
+	let updated =
+		update_card_priority_handler(State(pool.clone()), Path(card.get_id()), Json(payload))
+			.await
+			.unwrap();
+	assert_eq!(updated.0["version"], 2);
+}
+
+#[tokio::test]
+async fn test_list_leeches_handler() {
+	let pool = setup_test_db();
+
+	let item_type = repo::create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let card = repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone();
+	for _ in 0..2 {
+		repo::record_review(&pool, &card.get_id(), Rating::Again)
+			.await
+			.unwrap();
+	}
+
+	// The default threshold is not reached yet
+	let result = list_leeches_handler(State(pool.clone()), Query(ListLeechesQueryDto::default()))
+		.await
+		.unwrap();
+	assert!(result.0.is_empty());
+
+	let query = ListLeechesQueryDto {
+		threshold: 2,
+		suspend: true,
+	};
+	let result = list_leeches_handler(State(pool.clone()), Query(query))
+		.await
+		.unwrap();
+	let leeches = result.0;
+	assert_eq!(leeches.len(), 1);
+	assert_eq!(leeches[0]["id"], card.get_id().0);
+	assert!(!leeches[0]["suspended"].is_null());
+}
+
+#[tokio::test]
+async fn test_list_hardest_cards_handler() {
+	let pool = setup_test_db();
+
+	let item_type = repo::create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let cards = repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+	repo::record_review(&pool, &cards[0].get_id(), Rating::Good)
+		.await
+		.unwrap();
+	repo::record_review(&pool, &cards[1].get_id(), Rating::Again)
+		.await
+		.unwrap();
+
+	// One review each is too few to rank by default
+	let result =
+		list_hardest_cards_handler(State(pool.clone()), Query(HardestCardsQueryDto::default()))
+			.await
+			.unwrap();
+	assert!(result.0.is_empty());
+
+	let query = HardestCardsQueryDto {
+		limit: 10,
+		min_reviews: 1,
+	};
+	let result = list_hardest_cards_handler(State(pool.clone()), Query(query))
+		.await
+		.unwrap();
+	let hardest = result.0;
+	assert_eq!(hardest.len(), 2);
+	assert_eq!(hardest[0]["id"], cards[1].get_id().0);
+	assert_eq!(hardest[1]["id"], cards[0].get_id().0);
+}
+
+#[tokio::test]
+async fn test_regenerate_cards_handler() {
+	let pool = setup_test_db();
+
+	let item_type = repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+
+	// Making the item reversible calls for a reverse card
+	repo::update_item(
+		&pool,
+		&item.get_id(),
+		None,
+		Some(json!({"front": "Hello", "back": "World", "reversible": true})),
+	)
+	.await
+	.unwrap();
+	let result = regenerate_cards_handler(
+		State(pool.clone()),
+		Path(item.get_id()),
+		Query(RegenerateCardsQueryDto::default()),
+	)
+	.await
+	.unwrap();
+	let cards = result.0;
+	assert_eq!(cards.len(), 2);
+	assert_eq!(cards[1]["card_index"], 1);
+	assert_eq!(cards[1]["direction"], "reverse");
+
+	let result = regenerate_cards_handler(
+		State(pool.clone()),
+		Path(ItemId("nonexistent".to_string())),
+		Query(RegenerateCardsQueryDto { remove_extra: true }),
+	)
+	.await;
+	assert!(matches!(result, Err(ApiError::NotFound(Resource::Item))));
+}
+
+#[tokio::test]
+async fn test_list_related_cards_handler() {
+	let pool = setup_test_db();
+
+	let item_type = repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let tag = repo::create_tag(&pool, "Shared".to_string(), true)
+		.await
+		.unwrap();
+	let mut cards = Vec::new();
+	for title in ["First", "Second"] {
+		let item = repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			json!({"front": "Hello", "back": "World"}),
+		)
+		.await
+		.unwrap();
+		repo::add_tag_to_item(&pool, &tag.get_id(), &item.get_id())
+			.await
+			.unwrap();
+		cards.push(repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone());
+	}
+
+	let result = list_related_cards_handler(
+		State(pool.clone()),
+		Path(cards[0].get_id()),
+		Query(RelatedCardsQueryDto::default()),
+	)
+	.await
+	.unwrap();
+	let related = result.0;
+	assert_eq!(related.len(), 1);
+	assert_eq!(related[0]["id"], cards[1].get_id().0);
+
+	let result = list_related_cards_handler(
+		State(pool.clone()),
+		Path(CardId("nonexistent".to_string())),
+		Query(RelatedCardsQueryDto::default()),
+	)
+	.await;
+	assert!(matches!(
+		result.unwrap_err(),
+		ApiError::NotFound(Resource::Card)
+	));
+}
+
+#[tokio::test]
+async fn test_bury_card_handler() {
+	let pool = setup_test_db();
+
+	let item_type = repo::create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let card = repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone();
+
+	// Without a body the card is buried until tomorrow
+	let result = bury_card_handler(State(pool.clone()), Path(card.get_id()), None)
+		.await
+		.unwrap();
+	assert_eq!(result.0["id"], card.get_id().0);
+	assert!(!result.0["buried_until"].is_null());
+
+	// An explicit time is used as-is
+	let until = chrono::Utc::now() + chrono::Duration::days(3);
+	let payload = BuryCardDto { until: Some(until) };
+	bury_card_handler(
+		State(pool.clone()),
+		Path(card.get_id()),
+		Some(Json(payload)),
+	)
+	.await
+	.unwrap();
+	let stored = repo::get_card_raw(&pool, &card.get_id()).unwrap().unwrap();
+	assert_eq!(stored.get_buried_until(), Some(until));
+}
+
+#[tokio::test]
+async fn test_bury_card_handler_not_found() {
+	let pool = setup_test_db();
+
+	let result = bury_card_handler(
+		State(pool.clone()),
+		Path(CardId("nonexistent".to_string())),
+		None,
+	)
+	.await;
+
+	assert!(matches!(result, Err(ApiError::NotFound(Resource::Card))));
+}
+
+#[tokio::test]
+async fn test_unbury_all_handler() {
+	let pool = setup_test_db();
+
+	let item_type = repo::create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	for card in repo::get_cards_for_item(&pool, &item.get_id()).unwrap() {
+		repo::bury_card(&pool, &card.get_id(), None).await.unwrap();
+	}
+
+	let result = unbury_all_handler(State(pool.clone())).await.unwrap();
+
+	assert_eq!(result.0, 2);
+	let cards = list_cards_handler(State(pool.clone()), Query(GetQueryDto::default()))
+		.await
+		.unwrap();
+	assert_eq!(cards.0.len(), 2);
+}
+
+#[tokio::test]
+async fn test_set_card_note_handler() {
+	let pool = setup_test_db();
+
+	let item_type = repo::create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let card = repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone();
+
+	let payload = SetCardNoteDto {
+		note: Some("Hello sounds like hollow".to_string()),
+	};
+	let result = set_card_note_handler(State(pool.clone()), Path(card.get_id()), Json(payload))
+		.await
+		.unwrap();
+	assert_eq!(result.0["id"], card.get_id().0);
+	assert_eq!(result.0["note"], "Hello sounds like hollow");
+
+	let payload = SetCardNoteDto { note: None };
+	let result = set_card_note_handler(State(pool.clone()), Path(card.get_id()), Json(payload))
+		.await
+		.unwrap();
+	assert!(result.0["note"].is_null());
+}
+
+#[tokio::test]
+async fn test_set_card_difficulty_handler() {
+	let pool = setup_test_db();
+
+	let item_type = repo::create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let card = repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone();
+
+	let payload = SetCardDifficultyDto {
+		difficulty: Some(0.25),
+	};
+	let result =
+		set_card_difficulty_handler(State(pool.clone()), Path(card.get_id()), Json(payload))
+			.await
+			.unwrap();
+	assert_eq!(result.0["id"], card.get_id().0);
+	assert_eq!(result.0["difficulty"], 0.25);
+
+	let payload = SetCardDifficultyDto {
+		difficulty: Some(2.0),
+	};
+	let result =
+		set_card_difficulty_handler(State(pool.clone()), Path(card.get_id()), Json(payload)).await;
+	assert!(matches!(result, Err(ApiError::InvalidDifficulty(_))));
+
+	let payload = SetCardDifficultyDto { difficulty: None };
+	let result =
+		set_card_difficulty_handler(State(pool.clone()), Path(card.get_id()), Json(payload))
+			.await
+			.unwrap();
+	assert!(result.0["difficulty"].is_null());
+
+	let result = set_card_difficulty_handler(
+		State(pool.clone()),
+		Path(CardId("nonexistent".to_string())),
+		Json(SetCardDifficultyDto {
+			difficulty: Some(0.5),
+		}),
+	)
+	.await;
+	assert!(matches!(result, Err(ApiError::NotFound(Resource::Card))));
+}
+
+#[tokio::test]
+async fn test_set_card_note_handler_not_found() {
+	let pool = setup_test_db();
+
+	let result = set_card_note_handler(
+		State(pool.clone()),
+		Path(CardId("nonexistent".to_string())),
+		Json(SetCardNoteDto { note: None }),
+	)
+	.await;
+
+	assert!(matches!(result, Err(ApiError::NotFound(Resource::Card))));
 }
@@ -0,0 +1,96 @@
+use axum::{
+	extract::State,
+	response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::{Stream, stream};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{self, MissedTickBehavior};
+use tracing::{debug, error, instrument};
+
+use crate::db::DbPool;
+use crate::dto::DueCardsEvent;
+use crate::models::CardId;
+use crate::repo;
+
+/// Handler for subscribing to due card notifications
+///
+/// This function handles GET requests to `/events/due`. It sends
+/// server-sent events named `due`, each with a [`DueCardsEvent`] as its
+/// JSON data: one straight away, then one whenever a check finds the number
+/// of due cards changed or cards newly due. Nothing is changed, so clients
+/// can stay subscribed for as long as they like. Comments are sent between
+/// events to keep the connection open.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `poll_interval` - How long to wait between checks for due cards
+///
+/// ### Returns
+///
+/// The stream of events
+#[instrument(skip(pool))]
+pub async fn due_events_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	poll_interval: Duration,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+	debug!("Subscribing to due card events");
+
+	let mut ticker = time::interval(poll_interval);
+	// A check that overruns shouldn't be followed by a burst of catch-up checks
+	ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+	// The state is the IDs due at the previous check, or None before the first
+	let events = stream::unfold(
+		(pool, ticker, None::<HashSet<CardId>>),
+		|(pool, mut ticker, mut previously_due)| async move {
+			loop {
+				ticker.tick().await;
+
+				let due_card_ids = match repo::list_due_card_ids(&pool) {
+					Ok(due_card_ids) => due_card_ids,
+					Err(e) => {
+						// Try again at the next check rather than ending the stream
+						error!("Failed to check for due cards: {}", e);
+						continue;
+					}
+				};
+
+				let (send, newly_due_card_ids) = match &previously_due {
+					None => (true, vec![]),
+					Some(previous) => {
+						let newly_due: Vec<CardId> = due_card_ids
+							.iter()
+							.filter(|id| !previous.contains(id))
+							.cloned()
+							.collect();
+						(
+							!newly_due.is_empty() || previous.len() != due_card_ids.len(),
+							newly_due,
+						)
+					}
+				};
+				let due_count = due_card_ids.len();
+				previously_due = Some(due_card_ids.into_iter().collect());
+
+				if send {
+					debug!(
+						"{} cards due, {} newly",
+						due_count,
+						newly_due_card_ids.len()
+					);
+					let event = Event::default().event("due").json_data(DueCardsEvent {
+						due_count,
+						newly_due_card_ids,
+					});
+					return Some((event, (pool, ticker, previously_due)));
+				}
+			}
+		},
+	);
+
+	Sse::new(events).keep_alive(KeepAlive::default())
+}
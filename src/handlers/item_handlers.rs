@@ -1,19 +1,54 @@
-use axum::{
-	Json,
-	extract::{Path, State},
-};
+use axum::extract::{Path, State};
 use axum_extra::extract::Query;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, info, instrument};
+use uuid::Uuid;
 
+use super::Json;
 use crate::models::Item;
 use crate::repo;
 use crate::{UpdateItemDto, db::DbPool};
 use crate::{
-	dto::{CreateItemDto, GetQueryDto},
+	dto::{
+		ChangeItemTypeDto, CreateItemDto, CreatedItem, GetQueryDto, ItemExpansion,
+		ItemWithItemType, ListItemsQueryDto, PreviewCardsDto,
+	},
 	models::ItemId,
 };
-use crate::{errors::ApiError, models::ItemTypeId};
+use crate::{
+	errors::{ApiError, Resource},
+	models::ItemTypeId,
+};
+
+/// Maps an error from creating or updating an item to an API error
+///
+/// Item data validation failures become 400s; everything else is a database error.
+fn item_write_error(e: anyhow::Error) -> ApiError {
+	match e.downcast::<repo::InvalidItemDataError>() {
+		Ok(invalid) => ApiError::InvalidItemData(invalid.0),
+		Err(e) => ApiError::Database(e),
+	}
+}
+
+/// Normalizes a client-supplied item ID to the server's `item-<uuid>` form
+///
+/// Accepts either a bare UUID or one already carrying the `item-` prefix, so
+/// a client can retry with whichever it has.
+///
+/// ### Arguments
+///
+/// * `id` - The ID from the request body
+///
+/// ### Returns
+///
+/// The normalized ID, or 400 if it isn't a well-formed UUID
+fn parse_client_item_id(id: ItemId) -> Result<ItemId, ApiError> {
+	let raw = id.0.strip_prefix("item-").unwrap_or(&id.0);
+	let uuid = Uuid::parse_str(raw)
+		.map_err(|_| ApiError::InvalidId(format!("Item id must be a UUID, got {:?}", id.0)))?;
+	Ok(ItemId(format!("item-{}", uuid.hyphenated())))
+}
 
 /// Handler for creating a new item
 ///
@@ -22,35 +57,170 @@ use crate::{errors::ApiError, models::ItemTypeId};
 /// ### Arguments
 ///
 /// * `pool` - The database connection pool
-/// * `payload` - The request payload containing the item title
+/// * `payload` - The request payload containing the item title and, for an
+///   idempotent create, the item's ID
 ///
 /// ### Returns
 ///
-/// The newly created item as JSON
+/// The newly created item as JSON (or the existing one, if an item with the
+/// given ID already exists), with `warnings` about data that's allowed but
+/// probably a mistake, such as a Basic item's empty `front`; or 400 if the ID
+/// isn't a UUID, the item type doesn't exist or the item data doesn't have
+/// the shape the item type requires
 #[instrument(skip(pool, payload), fields(item_type_id = %payload.item_type_id, title = %payload.title))]
 pub async fn create_item_handler(
 	// Extract the database pool from the application state
 	State(pool): State<Arc<DbPool>>,
 	// Extract and deserialize the JSON request body
 	Json(payload): Json<CreateItemDto>,
-) -> Result<Json<Item>, ApiError> {
+) -> Result<Json<CreatedItem>, ApiError> {
 	info!("Creating new item");
 
+	let item_id = payload.id.map(parse_client_item_id).transpose()?;
+
 	// Call the repository function to create the item
-	let item = repo::create_item(
+	let item_type_id = payload.item_type_id;
+	let (item, item_type) = repo::create_item_with_id(
 		&pool,
-		&payload.item_type_id,
+		item_id,
+		&item_type_id,
 		payload.title,
 		payload.item_data,
 	)
 	.await
-	.map_err(ApiError::Database)?;
+	.map_err(|e| {
+		if e.to_string().contains("Item type not found") {
+			ApiError::UnknownItemType(item_type_id.clone())
+		} else {
+			item_write_error(e)
+		}
+	})?;
 	// TODO: make unique constraint errors map to an ApiError duplicate
 
 	info!("Successfully created item with id: {}", item.get_id());
 
+	// Point out anything in the data that's allowed but looks like a mistake
+	let warnings = repo::validate_item_data_warnings(&item_type.get_name(), &item.get_data().0);
+	if !warnings.is_empty() {
+		debug!("Item data warnings: {:?}", warnings);
+	}
+
 	// Return the created item as JSON
-	Ok(Json(item))
+	Ok(Json(CreatedItem { item, warnings }))
+}
+
+/// Handler for creating several items at once
+///
+/// This function handles POST requests to `/items/batch`. The items and
+/// their cards are created in a single transaction, so if any item is
+/// rejected none of them are created.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `payload` - The request payload: an array of items, each shaped like
+///   the body of `POST /items`
+///
+/// ### Returns
+///
+/// The created items as JSON, in request order, each with its `warnings` as
+/// for `POST /items`; an item whose ID already exists is returned as stored.
+/// 400 if more than `MAX_BATCH_CREATE_ITEMS` items are sent, or if any item
+/// is invalid (including reusing an existing item's ID for a different
+/// item), naming the index of the first bad item.
+#[instrument(skip(pool, payload), fields(count = payload.len()))]
+pub async fn create_items_batch_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract and deserialize the JSON request body
+	Json(payload): Json<Vec<CreateItemDto>>,
+) -> Result<Json<Vec<CreatedItem>>, ApiError> {
+	info!("Creating a batch of items");
+
+	if payload.len() > repo::MAX_BATCH_CREATE_ITEMS {
+		return Err(ApiError::BatchTooLarge(format!(
+			"At most {} items can be created at once, got {}",
+			repo::MAX_BATCH_CREATE_ITEMS,
+			payload.len()
+		)));
+	}
+
+	let new_items = payload
+		.into_iter()
+		.enumerate()
+		.map(|(index, dto)| {
+			let item_id = dto
+				.id
+				.map(parse_client_item_id)
+				.transpose()
+				.map_err(|e| ApiError::InvalidBatchItem(format!("Item {}: {}", index, e)))?;
+			Ok((item_id, dto.item_type_id, dto.title, dto.item_data))
+		})
+		.collect::<Result<Vec<_>, ApiError>>()?;
+
+	let items = repo::create_items_batch(&pool, new_items)
+		.await
+		.map_err(|e| match e.downcast::<repo::InvalidBatchItemError>() {
+			Ok(invalid) => ApiError::InvalidBatchItem(invalid.to_string()),
+			Err(e) => ApiError::Database(e),
+		})?;
+
+	info!("Successfully created {} items", items.len());
+
+	// Point out anything in each item's data that's allowed but looks like a mistake
+	let mut item_type_names = HashMap::new();
+	let mut created = Vec::with_capacity(items.len());
+	for item in items {
+		let item_type_id = item.get_item_type();
+		if !item_type_names.contains_key(&item_type_id) {
+			let item_type = repo::get_item_type(&pool, &item_type_id)
+				.map_err(ApiError::Database)?
+				.ok_or_else(|| ApiError::UnknownItemType(item_type_id.clone()))?;
+			item_type_names.insert(item_type_id.clone(), item_type.get_name());
+		}
+		let warnings =
+			repo::validate_item_data_warnings(&item_type_names[&item_type_id], &item.get_data().0);
+		created.push(CreatedItem { item, warnings });
+	}
+
+	// Return the created items as JSON
+	Ok(Json(created))
+}
+
+/// Handler for previewing how many cards an item would get
+///
+/// This function handles POST requests to `/items/preview_cards`. Nothing is
+/// written to the database.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `payload` - The request payload containing the item type ID and item data
+///
+/// ### Returns
+///
+/// The number of cards creating the item would produce as JSON, or 400 if
+/// the item type doesn't exist or the item data doesn't have the shape the
+/// item type requires
+#[instrument(skip(pool, payload), fields(item_type_id = %payload.item_type_id))]
+pub async fn preview_cards_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract and deserialize the JSON request body
+	Json(payload): Json<PreviewCardsDto>,
+) -> Result<Json<usize>, ApiError> {
+	debug!("Previewing cards for item");
+
+	let item_type = repo::get_item_type(&pool, &payload.item_type_id)
+		.map_err(ApiError::Database)?
+		.ok_or_else(|| ApiError::UnknownItemType(payload.item_type_id.clone()))?;
+
+	let card_count =
+		repo::preview_cards_for_item(&item_type, &payload.item_data).map_err(item_write_error)?;
+
+	info!("Item would get {} cards", card_count);
+
+	Ok(Json(card_count))
 }
 
 /// Handler for retrieving a specific item
@@ -64,26 +234,144 @@ pub async fn create_item_handler(
 ///
 /// ### Returns
 ///
-/// The requested item as JSON, or null if not found
+/// The requested item as JSON, or 404 if it doesn't exist
 #[instrument(skip(pool), fields(item_id = %item_id))]
 pub async fn get_item_handler(
 	// Extract the database pool from the application state
 	State(pool): State<Arc<DbPool>>,
 	// Extract the item ID from the URL path
 	Path(item_id): Path<ItemId>,
-) -> Result<Json<Option<Item>>, ApiError> {
+) -> Result<Json<Item>, ApiError> {
 	debug!("Retrieving item");
 
 	// Call the repository function to get the item
-	let item = repo::get_item(&pool, &item_id).map_err(ApiError::Database)?;
+	let item = repo::get_item(&pool, &item_id)
+		.map_err(ApiError::Database)?
+		.ok_or_else(|| {
+			debug!("Item not found");
+			ApiError::NotFound(Resource::Item)
+		})?;
 
-	if let Some(ref item) = item {
-		debug!("Item found with id: {}", item.get_id());
-	} else {
-		debug!("Item not found");
-	}
+	debug!("Item found with id: {}", item.get_id());
+
+	// Return the item as JSON
+	Ok(Json(item))
+}
+
+/// Handler for retrieving an item together with its cards and tags
+///
+/// This function handles GET requests to `/items/{item_id}/full`, saving
+/// clients the separate requests for the item, its cards and its tags.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `item_id` - The ID of the item to retrieve, extracted from the URL path
+///
+/// ### Returns
+///
+/// The item, its cards and its tags as `{item, cards, tags}`, or 404 if the
+/// item doesn't exist
+#[instrument(skip(pool), fields(item_id = %item_id))]
+pub async fn get_item_full_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the item ID from the URL path
+	Path(item_id): Path<ItemId>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+	debug!("Retrieving item with its cards and tags");
+
+	let full = repo::get_item_with_cards_and_tags(&pool, &item_id)
+		.await?
+		.ok_or(ApiError::NotFound(Resource::Item))?;
+
+	info!(
+		"Retrieved item with {} cards and {} tags",
+		full.cards.len(),
+		full.tags.len()
+	);
+
+	// Cards are returned with their priority offset folded in, as elsewhere
+	Ok(Json(serde_json::json!({
+		"item": full.item,
+		"cards": full
+			.cards
+			.iter()
+			.map(|card| card.to_json_hide_priority_offset())
+			.collect::<Vec<_>>(),
+		"tags": full.tags,
+	})))
+}
+
+/// Handler for duplicating an item
+///
+/// This function handles POST requests to `/items/{item_id}/duplicate`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `item_id` - The ID of the item to copy, extracted from the URL path
+///
+/// ### Returns
+///
+/// The new item as JSON, with fresh cards and the source's tags, or 404 if
+/// the source item doesn't exist
+#[instrument(skip(pool), fields(item_id = %item_id))]
+pub async fn duplicate_item_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the item ID from the URL path
+	Path(item_id): Path<ItemId>,
+) -> Result<Json<Item>, ApiError> {
+	debug!("Duplicating item");
+
+	let item = repo::duplicate_item(&pool, &item_id)
+		.await
+		.map_err(ApiError::Database)?
+		.ok_or(ApiError::NotFound(Resource::Item))?;
+
+	info!("Duplicated item {} as {}", item_id, item.get_id());
+
+	Ok(Json(item))
+}
+
+/// Handler for moving an item to a different item type
+///
+/// This function handles PUT requests to `/items/{item_id}/type`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `item_id` - The ID of the item to change, extracted from the URL path
+/// * `payload` - The request payload containing the new item type's ID
+///
+/// ### Returns
+///
+/// The updated item as JSON, with its cards reconciled to the new type; 400
+/// if the item type doesn't exist or the item's data doesn't have the shape
+/// it requires; or 404 if the item doesn't exist
+#[instrument(skip(pool, payload), fields(item_id = %item_id, item_type_id = %payload.item_type_id))]
+pub async fn change_item_type_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the item ID from the URL path
+	Path(item_id): Path<ItemId>,
+	// Extract and deserialize the JSON request body
+	Json(payload): Json<ChangeItemTypeDto>,
+) -> Result<Json<Item>, ApiError> {
+	debug!("Changing item type");
+
+	let item_type_id = payload.item_type_id;
+	let item = repo::change_item_type(&pool, &item_id, &item_type_id)
+		.await
+		.map_err(|e| match e.downcast::<repo::ItemTypeNotFoundError>() {
+			Ok(missing) => ApiError::UnknownItemType(missing.0),
+			Err(e) => item_write_error(e),
+		})?
+		.ok_or(ApiError::NotFound(Resource::Item))?;
+
+	info!("Changed item {} to type {}", item_id, item_type_id);
 
-	// Return the item (or None) as JSON
 	Ok(Json(item))
 }
 
@@ -99,7 +387,8 @@ pub async fn get_item_handler(
 ///
 /// ### Returns
 ///
-/// The updated item as JSON
+/// The updated item as JSON, or 400 if the new item data doesn't satisfy
+/// the item type
 #[instrument(skip(pool, payload), fields(item_id = %item_id))]
 pub async fn update_item_handler(
 	// Extract the database pool from the application state
@@ -114,14 +403,14 @@ pub async fn update_item_handler(
 	// First check if the item exists
 	let item = repo::get_item(&pool, &item_id)
 		.map_err(ApiError::Database)?
-		.ok_or(ApiError::NotFound)?;
+		.ok_or(ApiError::NotFound(Resource::Item))?;
 
 	debug!("Found item to update: {}", item.get_id());
 
 	// Call the repository function to update the item
 	let updated_item = repo::update_item(&pool, &item_id, payload.title, payload.item_data)
 		.await
-		.map_err(ApiError::Database)?;
+		.map_err(item_write_error)?;
 
 	info!(
 		"Successfully updated item with id: {}",
@@ -156,7 +445,7 @@ pub async fn delete_item_handler(
 	// First check if the item exists
 	let item = repo::get_item(&pool, &item_id)
 		.map_err(ApiError::Database)?
-		.ok_or(ApiError::NotFound)?;
+		.ok_or(ApiError::NotFound(Resource::Item))?;
 
 	debug!("Found item to delete: {}", item.get_id());
 
@@ -179,6 +468,8 @@ pub async fn delete_item_handler(
 ///
 /// * `pool` - The database connection pool
 /// * `query` - Optional query filters
+/// * `options` - Query parameters; `expand=item_type` adds `item_type_name`
+///   to each item
 ///
 /// ### Returns
 ///
@@ -187,10 +478,29 @@ pub async fn delete_item_handler(
 pub async fn list_items_handler(
 	State(pool): State<Arc<DbPool>>,
 	Query(query): Query<GetQueryDto>,
-) -> Result<Json<Vec<Item>>, ApiError> {
+	Query(options): Query<ListItemsQueryDto>,
+) -> Result<Json<Vec<ItemWithItemType>>, ApiError> {
 	debug!("Listing items with filters: {:?}", query);
 
-	let items = repo::list_items_with_filters(&pool, &query).map_err(ApiError::Database)?;
+	// Look up item type names only if asked, in the same query as the items
+	let items: Vec<ItemWithItemType> = match options.expand {
+		Some(ItemExpansion::ItemType) => repo::list_items_with_item_type_names(&pool, &query)
+			.map_err(ApiError::Database)?
+			.into_iter()
+			.map(|(item, item_type_name)| ItemWithItemType {
+				item,
+				item_type_name: Some(item_type_name),
+			})
+			.collect(),
+		None => repo::list_items_with_filters(&pool, &query)
+			.map_err(ApiError::Database)?
+			.into_iter()
+			.map(|item| ItemWithItemType {
+				item,
+				item_type_name: None,
+			})
+			.collect(),
+	};
 
 	info!("Retrieved {} items", items.len());
 
@@ -221,7 +531,7 @@ pub async fn list_items_by_item_type_handler(
 	// Verify that the item type exists
 	let item_type = repo::get_item_type(&pool, &item_type_id)
 		.map_err(ApiError::Database)?
-		.ok_or(ApiError::NotFound)?;
+		.ok_or(ApiError::NotFound(Resource::ItemType))?;
 
 	// Call the repository function to list items by type
 	let items = repo::get_items_by_type(&pool, &item_type.get_id()).map_err(ApiError::Database)?;
@@ -239,6 +549,7 @@ pub async fn list_items_by_item_type_handler(
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::models::Rating;
 	use crate::repo;
 	use crate::test_utils::*;
 	use axum::extract::Path;
@@ -256,6 +567,7 @@ mod tests {
 
 		// Create a test payload
 		let payload = CreateItemDto {
+			id: None,
 			item_type_id: item_type.get_id(),
 			title: "Test Item".to_string(),
 			item_data: json!({
@@ -271,9 +583,161 @@ mod tests {
 			.unwrap();
 
 		// Check the result
-		let item = result.0;
+		let item = result.0.item;
 		assert_eq!(item.get_title(), "Test Item");
 		assert_eq!(item.get_item_type(), item_type.get_id());
+		assert!(result.0.warnings.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_preview_cards_handler() {
+		let pool = setup_test_db();
+
+		let item_type = repo::create_item_type(&pool, "Cloze".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+
+		let payload = PreviewCardsDto {
+			item_type_id: item_type.get_id(),
+			item_data: json!({"text": "Paris is in France", "clozes": ["Paris", "France"]}),
+		};
+		let result = preview_cards_handler(State(pool.clone()), Json(payload))
+			.await
+			.unwrap();
+		assert_eq!(result.0, 2);
+		assert!(repo::list_items(&pool).unwrap().is_empty());
+
+		// Invalid data is a 400, as it would be on create
+		let payload = PreviewCardsDto {
+			item_type_id: item_type.get_id(),
+			item_data: json!({"text": "Paris is in France"}),
+		};
+		let result = preview_cards_handler(State(pool.clone()), Json(payload)).await;
+		assert!(matches!(result.unwrap_err(), ApiError::InvalidItemData(_)));
+
+		// So is an unknown item type
+		let payload = PreviewCardsDto {
+			item_type_id: ItemTypeId("nonexistent".to_string()),
+			item_data: json!({}),
+		};
+		let result = preview_cards_handler(State(pool.clone()), Json(payload)).await;
+		assert!(matches!(result.unwrap_err(), ApiError::UnknownItemType(_)));
+	}
+
+	#[tokio::test]
+	async fn test_create_item_handler_warns_about_empty_front() {
+		let pool = setup_test_db();
+
+		let item_type = repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		let payload = CreateItemDto {
+			id: None,
+			item_type_id: item_type.get_id(),
+			title: "Blank".to_string(),
+			item_data: json!({"front": "", "back": "World"}),
+			priority: 0.5,
+		};
+
+		let result = create_item_handler(State(pool.clone()), Json(payload))
+			.await
+			.unwrap()
+			.0;
+
+		// The item is created all the same
+		assert_eq!(result.warnings, vec!["`front` is empty"]);
+		assert_eq!(
+			repo::get_item(&pool, &result.item.get_id()).unwrap(),
+			Some(result.item)
+		);
+	}
+
+	#[tokio::test]
+	async fn test_create_item_handler_with_id() {
+		let pool = setup_test_db();
+
+		let item_type = repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		let uuid = Uuid::new_v4();
+
+		let payload = |id: &str| CreateItemDto {
+			id: Some(ItemId(id.to_string())),
+			item_type_id: item_type.get_id(),
+			title: "Test Item".to_string(),
+			item_data: json!({"front": "Hello", "back": "World"}),
+			priority: 0.5,
+		};
+
+		// A bare UUID is given the usual prefix
+		let first = create_item_handler(State(pool.clone()), Json(payload(&uuid.to_string())))
+			.await
+			.unwrap()
+			.0
+			.item;
+		assert_eq!(first.get_id(), ItemId(format!("item-{}", uuid)));
+
+		// Retrying with the prefixed form returns the same item
+		let second = create_item_handler(State(pool.clone()), Json(payload(&first.get_id().0)))
+			.await
+			.unwrap()
+			.0
+			.item;
+		assert_eq!(second, first);
+		assert_eq!(repo::list_items(&pool).unwrap().len(), 1);
+	}
+
+	#[tokio::test]
+	async fn test_create_item_handler_invalid_id() {
+		let pool = setup_test_db();
+
+		let item_type = repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+
+		for bad in ["not-a-uuid", "item-", "item-1234"] {
+			let payload = CreateItemDto {
+				id: Some(ItemId(bad.to_string())),
+				item_type_id: item_type.get_id(),
+				title: "Test Item".to_string(),
+				item_data: json!({"front": "Hello", "back": "World"}),
+				priority: 0.5,
+			};
+
+			let result = create_item_handler(State(pool.clone()), Json(payload)).await;
+			assert!(
+				matches!(result.unwrap_err(), ApiError::InvalidId(_)),
+				"{}",
+				bad
+			);
+		}
+		assert!(repo::list_items(&pool).unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_create_item_handler_invalid_item_data() {
+		let pool = setup_test_db();
+
+		// Create a Basic item type, which requires front and back strings
+		let item_type = repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+
+		// Create a payload without a back
+		let payload = CreateItemDto {
+			id: None,
+			item_type_id: item_type.get_id(),
+			title: "Test Item".to_string(),
+			item_data: json!({"front": "Hello"}),
+			priority: 0.5,
+		};
+
+		// Call the handler
+		let result = create_item_handler(State(pool.clone()), Json(payload)).await;
+
+		// Check that we got an InvalidItemData error and nothing was stored
+		assert!(matches!(result.unwrap_err(), ApiError::InvalidItemData(_)));
+		assert!(repo::list_items(&pool).unwrap().is_empty());
 	}
 
 	#[tokio::test]
@@ -306,15 +770,20 @@ mod tests {
 		.unwrap();
 
 		// Call the handler
-		let result = list_items_handler(State(pool.clone()), Query(GetQueryDto::default()))
-			.await
-			.unwrap();
+		let result = list_items_handler(
+			State(pool.clone()),
+			Query(GetQueryDto::default()),
+			Query(ListItemsQueryDto::default()),
+		)
+		.await
+		.unwrap();
 
 		// Check the result
 		let items = result.0;
 		assert_eq!(items.len(), 2);
-		assert!(items.iter().any(|i| i.get_id() == item1.get_id()));
-		assert!(items.iter().any(|i| i.get_id() == item2.get_id()));
+		assert!(items.iter().any(|i| i.item.get_id() == item1.get_id()));
+		assert!(items.iter().any(|i| i.item.get_id() == item2.get_id()));
+		assert!(items.iter().all(|i| i.item_type_name.is_none()));
 	}
 
 	#[tokio::test]
@@ -351,13 +820,17 @@ mod tests {
 			item_type_id: Some(type1.get_id()),
 			..Default::default()
 		};
-		let result = list_items_handler(State(pool.clone()), Query(query))
-			.await
-			.unwrap();
+		let result = list_items_handler(
+			State(pool.clone()),
+			Query(query),
+			Query(ListItemsQueryDto::default()),
+		)
+		.await
+		.unwrap();
 
 		let items = result.0;
 		assert_eq!(items.len(), 1);
-		assert_eq!(items[0].get_id(), item1.get_id());
+		assert_eq!(items[0].item.get_id(), item1.get_id());
 	}
 
 	#[tokio::test]
@@ -399,13 +872,72 @@ mod tests {
 			suspended_filter: SuspendedFilter::Only,
 			..Default::default()
 		};
-		let result = list_items_handler(State(pool.clone()), Query(query))
+		let result = list_items_handler(
+			State(pool.clone()),
+			Query(query),
+			Query(ListItemsQueryDto::default()),
+		)
+		.await
+		.unwrap();
+
+		let items = result.0;
+		assert_eq!(items.len(), 1);
+		assert_eq!(items[0].item.get_id(), item1.get_id());
+	}
+
+	#[tokio::test]
+	async fn test_list_items_handler_expand_item_type() {
+		let pool = setup_test_db();
+
+		let type1 = repo::create_item_type(&pool, "Test Type A".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		let type2 = repo::create_item_type(&pool, "Test Type B".to_string(), "fsrs".to_string())
 			.await
 			.unwrap();
+		let item1 = repo::create_item(
+			&pool,
+			&type1.get_id(),
+			"Item 1".to_string(),
+			json!({"front": "F1", "back": "B1"}),
+		)
+		.await
+		.unwrap();
+		let item2 = repo::create_item(
+			&pool,
+			&type2.get_id(),
+			"Item 2".to_string(),
+			json!({"front": "F2", "back": "B2"}),
+		)
+		.await
+		.unwrap();
+
+		let result = list_items_handler(
+			State(pool.clone()),
+			Query(GetQueryDto::default()),
+			Query(ListItemsQueryDto {
+				expand: Some(ItemExpansion::ItemType),
+			}),
+		)
+		.await
+		.unwrap();
 
 		let items = result.0;
-		assert_eq!(items.len(), 1);
-		assert_eq!(items[0].get_id(), item1.get_id());
+		assert_eq!(items.len(), 2);
+		for (item, type_name) in [(&item1, "Test Type A"), (&item2, "Test Type B")] {
+			let listed = items
+				.iter()
+				.find(|i| i.item.get_id() == item.get_id())
+				.unwrap();
+			assert_eq!(&listed.item, item);
+			assert_eq!(listed.item_type_name.as_deref(), Some(type_name));
+		}
+
+		// The item type name sits alongside the item's own fields
+		let json = serde_json::to_value(&items[0]).unwrap();
+		assert_eq!(json["item_type"], items[0].item.get_item_type().0);
+		assert_eq!(json["title"], items[0].item.get_title());
+		assert!(json["item_type_name"].is_string());
 	}
 
 	#[tokio::test]
@@ -434,11 +966,65 @@ mod tests {
 			.unwrap();
 
 		// Check the result
-		let retrieved_item = result.0.unwrap();
+		let retrieved_item = result.0;
 		assert_eq!(retrieved_item.get_id(), item.get_id());
 		assert_eq!(retrieved_item.get_title(), "Test Item");
 	}
 
+	#[tokio::test]
+	async fn test_get_item_handler_not_found() {
+		let pool = setup_test_db();
+
+		let result =
+			get_item_handler(State(pool.clone()), Path(ItemId("nonexistent".to_string()))).await;
+
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Item)
+		));
+	}
+
+	#[tokio::test]
+	async fn test_get_item_full_handler() {
+		let pool = setup_test_db();
+
+		let item_type =
+			repo::create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+				.await
+				.unwrap();
+		let item = repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			"Test Item".to_string(),
+			json!({"front": "Hello", "back": "World"}),
+		)
+		.await
+		.unwrap();
+		let tag = repo::create_tag(&pool, "Important".to_string(), true)
+			.await
+			.unwrap();
+		repo::add_tag_to_item(&pool, &tag.get_id(), &item.get_id())
+			.await
+			.unwrap();
+
+		let result = get_item_full_handler(State(pool.clone()), Path(item.get_id()))
+			.await
+			.unwrap();
+
+		assert_eq!(result["item"]["id"], json!(item.get_id()));
+		assert_eq!(result["cards"].as_array().unwrap().len(), 2);
+		assert!(result["cards"][0].get("priority_offset").is_none());
+		assert_eq!(result["tags"][0]["id"], json!(tag.get_id()));
+
+		let result =
+			get_item_full_handler(State(pool.clone()), Path(ItemId("nonexistent".to_string())))
+				.await;
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Item)
+		));
+	}
+
 	#[tokio::test]
 	async fn test_list_items_by_item_type_handler() {
 		let pool = setup_test_db();
@@ -505,7 +1091,10 @@ mod tests {
 
 		// Check that we got a NotFound error
 		assert!(result.is_err());
-		assert!(matches!(result.unwrap_err(), ApiError::NotFound));
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::ItemType)
+		));
 	}
 
 	#[tokio::test]
@@ -552,7 +1141,10 @@ mod tests {
 
 		// Check that we got a NotFound error
 		assert!(result.is_err());
-		assert!(matches!(result.unwrap_err(), ApiError::NotFound));
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Item)
+		));
 	}
 
 	#[tokio::test]
@@ -624,8 +1216,12 @@ mod tests {
 
 		// Create reviews for the first card
 		let card = &cards[0];
-		let review1 = repo::record_review(&pool, &card.get_id(), 2).await.unwrap();
-		let review2 = repo::record_review(&pool, &card.get_id(), 3).await.unwrap();
+		let review1 = repo::record_review(&pool, &card.get_id(), Rating::Hard)
+			.await
+			.unwrap();
+		let review2 = repo::record_review(&pool, &card.get_id(), Rating::Good)
+			.await
+			.unwrap();
 
 		// Verify that reviews exist
 		let reviews_before = repo::get_reviews_for_card(&pool, &card.get_id()).unwrap();
@@ -1,14 +1,12 @@
-use axum::{
-	Json,
-	extract::{Path, Query, State},
-};
+use axum::extract::{Path, Query, State};
 use std::sync::Arc;
 use tracing::{debug, info, instrument};
 
+use super::Json;
 use crate::dto::{
 	CreateItemRelationDto, ItemChildGraphNode, ItemParentGraphNode, ListItemRelationsQueryDto,
 };
-use crate::errors::ApiError;
+use crate::errors::{ApiError, Resource};
 use crate::models::ItemRelation;
 use crate::repo;
 use crate::{db::DbPool, models::ItemId};
@@ -39,10 +37,10 @@ pub async fn create_item_relation_handler(
 	// Validate both items exist
 	repo::get_item(&pool, &parent_id)
 		.map_err(ApiError::Database)?
-		.ok_or(ApiError::NotFound)?;
+		.ok_or(ApiError::NotFound(Resource::Item))?;
 	repo::get_item(&pool, &child_id)
 		.map_err(ApiError::Database)?
-		.ok_or(ApiError::NotFound)?;
+		.ok_or(ApiError::NotFound(Resource::Item))?;
 
 	// Create the relation (cycle detection happens inside)
 	match repo::create_item_relation(&pool, &parent_id, &child_id, &payload.relation_type).await {
@@ -88,7 +86,7 @@ pub async fn delete_item_relation_handler(
 		}
 		Err(e) => {
 			if e.to_string().contains("not found") {
-				Err(ApiError::NotFound)
+				Err(ApiError::NotFound(Resource::ItemRelation))
 			} else {
 				Err(ApiError::Database(e))
 			}
@@ -149,7 +147,7 @@ pub async fn get_children_graph_handler(
 
 	let root_item = repo::get_item(&pool, &item_id)
 		.map_err(ApiError::Database)?
-		.ok_or(ApiError::NotFound)?;
+		.ok_or(ApiError::NotFound(Resource::Item))?;
 
 	let graph = repo::get_children_graph(&pool, &root_item).map_err(ApiError::Database)?;
 
@@ -179,7 +177,7 @@ pub async fn get_parent_graph_handler(
 
 	let root_item = repo::get_item(&pool, &item_id)
 		.map_err(ApiError::Database)?
-		.ok_or(ApiError::NotFound)?;
+		.ok_or(ApiError::NotFound(Resource::Item))?;
 
 	let graph = repo::get_parent_graph(&pool, &root_item).map_err(ApiError::Database)?;
 
@@ -266,7 +264,10 @@ mod tests {
 		.await;
 
 		assert!(result.is_err());
-		assert!(matches!(result.unwrap_err(), ApiError::NotFound));
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Item)
+		));
 	}
 
 	#[tokio::test]
@@ -331,7 +332,10 @@ mod tests {
 		.await;
 
 		assert!(result.is_err());
-		assert!(matches!(result.unwrap_err(), ApiError::NotFound));
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::ItemRelation)
+		));
 	}
 
 	#[tokio::test]
@@ -543,7 +547,10 @@ mod tests {
 		.await;
 
 		assert!(result.is_err());
-		assert!(matches!(result.unwrap_err(), ApiError::NotFound));
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Item)
+		));
 	}
 
 	#[tokio::test]
@@ -631,7 +638,10 @@ mod tests {
 				.await;
 
 		assert!(result.is_err());
-		assert!(matches!(result.unwrap_err(), ApiError::NotFound));
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Item)
+		));
 	}
 
 	#[tokio::test]
@@ -1,13 +1,15 @@
-use axum::{
-	Json,
-	extract::{Path, State},
-};
+use axum::extract::{Path, State};
+use axum_extra::extract::Query;
 use std::sync::Arc;
 use tracing::{debug, info, instrument};
 
-use crate::dto::{CreateItemTypeDto, UpdateItemTypeDto};
-use crate::errors::ApiError;
-use crate::models::ItemType;
+use super::Json;
+use crate::dto::{
+	CreateItemTypeDto, DeleteItemTypeQueryDto, ItemTypeWithCounts, ListItemTypesQueryDto,
+	UpdateItemTypeDto,
+};
+use crate::errors::{ApiError, Resource};
+use crate::models::{ItemType, SchedulerConfig};
 use crate::repo;
 use crate::repo::VALID_REVIEW_FUNCTIONS;
 use crate::{db::DbPool, models::ItemTypeId};
@@ -23,7 +25,9 @@ use crate::{db::DbPool, models::ItemTypeId};
 ///
 /// ### Returns
 ///
-/// The newly created item type as JSON
+/// The newly created item type as JSON, or 400 if the item data schema is
+/// not a valid JSON Schema, the default priority is outside [0, 1] or the
+/// scheduler config is invalid
 #[instrument(skip(pool), fields(name = %payload.name))]
 pub async fn create_item_type_handler(
 	// Extract the database pool from the application state
@@ -46,10 +50,42 @@ pub async fn create_item_type_handler(
 		)));
 	}
 
+	if let Some(default_priority) = payload.default_priority {
+		validate_default_priority(default_priority)?;
+	}
+	if let Some(ref scheduler_config) = payload.scheduler_config {
+		validate_scheduler_config(scheduler_config)?;
+	}
+
 	// Call the repository function to create the item type
-	let item_type = repo::create_item_type(&pool, payload.name, review_function)
+	let mut item_type = repo::create_item_type_with_schema(
+		&pool,
+		payload.name,
+		review_function,
+		payload.item_data_schema,
+	)
+	.await
+	.map_err(item_type_write_error)?;
+
+	if payload.default_priority.is_some() {
+		item_type = repo::update_item_type_default_priority(
+			&pool,
+			&item_type.get_id(),
+			payload.default_priority,
+		)
+		.await
+		.map_err(item_type_write_error)?;
+	}
+
+	if payload.scheduler_config.is_some() {
+		item_type = repo::update_item_type_scheduler_config(
+			&pool,
+			&item_type.get_id(),
+			payload.scheduler_config,
+		)
 		.await
-		.map_err(ApiError::Database)?;
+		.map_err(item_type_write_error)?;
+	}
 
 	info!(
 		"Successfully created item type with id: {}",
@@ -92,7 +128,7 @@ pub async fn get_item_type_handler(
 		}
 		None => {
 			debug!("Item type not found");
-			Err(ApiError::NotFound)
+			Err(ApiError::NotFound(Resource::ItemType))
 		}
 	}
 }
@@ -104,6 +140,8 @@ pub async fn get_item_type_handler(
 /// ### Arguments
 ///
 /// * `pool` - The database connection pool
+/// * `query` - Query parameters; `include_counts=true` adds `item_count` and
+///   `card_count` to each item type
 ///
 /// ### Returns
 ///
@@ -112,11 +150,25 @@ pub async fn get_item_type_handler(
 pub async fn list_item_types_handler(
 	// Extract the database pool from the application state
 	State(pool): State<Arc<DbPool>>,
-) -> Result<Json<Vec<ItemType>>, ApiError> {
+	// Extract the query parameters
+	Query(query): Query<ListItemTypesQueryDto>,
+) -> Result<Json<Vec<ItemTypeWithCounts>>, ApiError> {
 	debug!("Listing all item types");
 
-	// Call the repository function to list all item types
-	let item_types = repo::list_item_types(&pool).map_err(ApiError::Database)?;
+	// Call the repository function to list all item types, counting only if asked
+	let item_types = if query.include_counts {
+		repo::list_item_types_with_counts(&pool).map_err(ApiError::Database)?
+	} else {
+		repo::list_item_types(&pool)
+			.map_err(ApiError::Database)?
+			.into_iter()
+			.map(|item_type| ItemTypeWithCounts {
+				item_type,
+				item_count: None,
+				card_count: None,
+			})
+			.collect()
+	};
 
 	info!("Retrieved {} item types", item_types.len());
 
@@ -124,7 +176,8 @@ pub async fn list_item_types_handler(
 	Ok(Json(item_types))
 }
 
-/// Handler for updating an item type's review function
+/// Handler for updating an item type's review function, item data schema,
+/// default priority and scheduler config
 ///
 /// This function handles PATCH requests to `/item-types/{id}`.
 ///
@@ -132,7 +185,8 @@ pub async fn list_item_types_handler(
 ///
 /// * `pool` - The database connection pool
 /// * `item_type_id` - The ID of the item type to update
-/// * `payload` - The request payload containing the new review function
+/// * `payload` - The request payload containing the new review function, schema,
+///   default priority and/or scheduler config
 ///
 /// ### Returns
 ///
@@ -145,34 +199,138 @@ pub async fn update_item_type_handler(
 ) -> Result<Json<ItemType>, ApiError> {
 	info!("Updating item type");
 
-	if let Some(ref review_function) = payload.review_function {
-		// Validate review_function
-		if !VALID_REVIEW_FUNCTIONS.contains(&review_function.as_str()) {
-			return Err(ApiError::InvalidReviewFunction(format!(
-				"Invalid review function: '{}'. Valid values: {:?}",
-				review_function, VALID_REVIEW_FUNCTIONS
-			)));
-		}
+	// Validate review_function before changing anything
+	if let Some(ref review_function) = payload.review_function
+		&& !VALID_REVIEW_FUNCTIONS.contains(&review_function.as_str())
+	{
+		return Err(ApiError::InvalidReviewFunction(format!(
+			"Invalid review function: '{}'. Valid values: {:?}",
+			review_function, VALID_REVIEW_FUNCTIONS
+		)));
+	}
+	if let Some(Some(default_priority)) = payload.default_priority {
+		validate_default_priority(default_priority)?;
+	}
+	if let Some(Some(ref scheduler_config)) = payload.scheduler_config {
+		validate_scheduler_config(scheduler_config)?;
+	}
 
-		let item_type =
-			repo::update_item_type_review_function(&pool, &item_type_id, review_function.clone())
+	let mut updated = None;
+
+	if let Some(review_function) = payload.review_function {
+		updated = Some(
+			repo::update_item_type_review_function(&pool, &item_type_id, review_function)
 				.await
-				.map_err(|e| {
-					if e.to_string().contains("not found") {
-						ApiError::NotFound
-					} else {
-						ApiError::Database(e)
-					}
-				})?;
-
-		Ok(Json(item_type))
-	} else {
-		// No fields to update, just return the current item type
-		let item_type = repo::get_item_type(&pool, &item_type_id)
-			.map_err(ApiError::Database)?
-			.ok_or(ApiError::NotFound)?;
+				.map_err(item_type_write_error)?,
+		);
+	}
+
+	if let Some(item_data_schema) = payload.item_data_schema {
+		updated = Some(
+			repo::update_item_type_item_data_schema(&pool, &item_type_id, item_data_schema)
+				.await
+				.map_err(item_type_write_error)?,
+		);
+	}
+
+	if let Some(default_priority) = payload.default_priority {
+		updated = Some(
+			repo::update_item_type_default_priority(&pool, &item_type_id, default_priority)
+				.await
+				.map_err(item_type_write_error)?,
+		);
+	}
+
+	if let Some(scheduler_config) = payload.scheduler_config {
+		updated = Some(
+			repo::update_item_type_scheduler_config(&pool, &item_type_id, scheduler_config)
+				.await
+				.map_err(item_type_write_error)?,
+		);
+	}
+
+	match updated {
+		Some(item_type) => Ok(Json(item_type)),
+		None => {
+			// No fields to update, just return the current item type
+			let item_type = repo::get_item_type(&pool, &item_type_id)
+				.map_err(ApiError::Database)?
+				.ok_or(ApiError::NotFound(Resource::ItemType))?;
+
+			Ok(Json(item_type))
+		}
+	}
+}
+
+/// Handler for deleting an item type
+///
+/// This function handles DELETE requests to `/item_types/{id}`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `item_type_id` - The ID of the item type to delete
+/// * `query` - Query parameters; `cascade=true` deletes the item type's items
+///   (and their cards and reviews) too
+///
+/// ### Returns
+///
+/// A Result indicating success, 404 if the item type doesn't exist, or 409 if
+/// it still has items and `cascade` isn't set
+#[instrument(skip(pool), fields(item_type_id = %item_type_id))]
+pub async fn delete_item_type_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the item type ID from the URL path
+	Path(item_type_id): Path<ItemTypeId>,
+	// Extract the query parameters
+	Query(query): Query<DeleteItemTypeQueryDto>,
+) -> Result<Json<()>, ApiError> {
+	info!("Deleting item type with id: {}", item_type_id);
+
+	let deleted_items = repo::delete_item_type(&pool, &item_type_id, query.cascade)
+		.await
+		.map_err(|e| match e.downcast::<repo::ItemTypeInUseError>() {
+			Ok(in_use) => ApiError::Conflict(in_use.to_string()),
+			Err(e) => item_type_write_error(e),
+		})?;
+
+	info!(
+		"Successfully deleted item type {} and {} items",
+		item_type_id, deleted_items
+	);
+
+	Ok(Json(()))
+}
+
+/// Checks that an item type's default priority is between 0 and 1
+fn validate_default_priority(default_priority: f32) -> Result<(), ApiError> {
+	if !(0.0..=1.0).contains(&default_priority) {
+		return Err(ApiError::InvalidPriority(format!(
+			"Default priority must be between 0 and 1, got {}",
+			default_priority
+		)));
+	}
+	Ok(())
+}
+
+/// Checks that an item type's scheduler config only sets known parameters,
+/// to valid values
+fn validate_scheduler_config(scheduler_config: &serde_json::Value) -> Result<(), ApiError> {
+	SchedulerConfig::from_json(scheduler_config)
+		.map(|_| ())
+		.map_err(ApiError::InvalidSchedulerConfig)
+}
 
-		Ok(Json(item_type))
+/// Maps an error from creating or updating an item type to an API error
+///
+/// Invalid schemas become 400s and missing item types 404s; everything else
+/// is a database error.
+fn item_type_write_error(e: anyhow::Error) -> ApiError {
+	match e.downcast::<repo::InvalidItemDataSchemaError>() {
+		Ok(invalid) => ApiError::InvalidItemDataSchema(invalid.0),
+		Err(e) if e.to_string().contains("not found") => ApiError::NotFound(Resource::ItemType),
+		Err(e) => ApiError::Database(e),
 	}
 }
 
@@ -189,6 +347,9 @@ mod tests {
 		let payload = CreateItemTypeDto {
 			name: "Type 1".to_string(),
 			review_function: None,
+			item_data_schema: None,
+			default_priority: None,
+			scheduler_config: None,
 		};
 
 		// Call the handler
@@ -209,6 +370,9 @@ mod tests {
 		let payload = CreateItemTypeDto {
 			name: "Todo".to_string(),
 			review_function: Some("incremental_queue".to_string()),
+			item_data_schema: None,
+			default_priority: None,
+			scheduler_config: None,
 		};
 
 		let result = create_item_type_handler(State(pool.clone()), Json(payload))
@@ -227,6 +391,9 @@ mod tests {
 		let payload = CreateItemTypeDto {
 			name: "Type 1".to_string(),
 			review_function: Some("invalid".to_string()),
+			item_data_schema: None,
+			default_priority: None,
+			scheduler_config: None,
 		};
 
 		let result = create_item_type_handler(State(pool.clone()), Json(payload)).await;
@@ -234,6 +401,114 @@ mod tests {
 		assert!(matches!(result, Err(ApiError::InvalidReviewFunction(_))));
 	}
 
+	#[tokio::test]
+	async fn test_create_item_type_handler_invalid_item_data_schema() {
+		let pool = setup_test_db();
+
+		let payload = CreateItemTypeDto {
+			name: "Type 1".to_string(),
+			review_function: None,
+			item_data_schema: Some(serde_json::json!({"type": 7})),
+			default_priority: None,
+			scheduler_config: None,
+		};
+
+		let result = create_item_type_handler(State(pool.clone()), Json(payload)).await;
+
+		assert!(matches!(result, Err(ApiError::InvalidItemDataSchema(_))));
+	}
+
+	#[tokio::test]
+	async fn test_create_item_type_handler_with_default_priority() {
+		let pool = setup_test_db();
+
+		let payload = CreateItemTypeDto {
+			name: "Urgent".to_string(),
+			review_function: None,
+			item_data_schema: None,
+			default_priority: Some(0.8),
+			scheduler_config: None,
+		};
+
+		let result = create_item_type_handler(State(pool.clone()), Json(payload))
+			.await
+			.unwrap();
+		assert_eq!(result.0.get_default_priority(), Some(0.8));
+
+		let stored = repo::get_item_type(&pool, &result.0.get_id())
+			.unwrap()
+			.unwrap();
+		assert_eq!(stored.get_default_priority(), Some(0.8));
+	}
+
+	#[tokio::test]
+	async fn test_create_item_type_handler_invalid_default_priority() {
+		let pool = setup_test_db();
+
+		let payload = CreateItemTypeDto {
+			name: "Urgent".to_string(),
+			review_function: None,
+			item_data_schema: None,
+			default_priority: Some(1.5),
+			scheduler_config: None,
+		};
+
+		let result = create_item_type_handler(State(pool.clone()), Json(payload)).await;
+
+		assert!(matches!(result, Err(ApiError::InvalidPriority(_))));
+		assert!(repo::list_item_types(&pool).unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_create_item_type_handler_with_scheduler_config() {
+		let pool = setup_test_db();
+		let config = serde_json::json!({"easy_factor": 2.5, "jitter": 0.0});
+
+		let payload = CreateItemTypeDto {
+			name: "Reading".to_string(),
+			review_function: Some("incremental_queue".to_string()),
+			item_data_schema: None,
+			default_priority: None,
+			scheduler_config: Some(config.clone()),
+		};
+
+		let result = create_item_type_handler(State(pool.clone()), Json(payload))
+			.await
+			.unwrap();
+		assert_eq!(result.0.get_scheduler_config().unwrap().0, config);
+
+		// Null goes back to the defaults
+		let payload = UpdateItemTypeDto {
+			review_function: None,
+			item_data_schema: None,
+			default_priority: None,
+			scheduler_config: Some(None),
+		};
+		let result =
+			update_item_type_handler(State(pool.clone()), Path(result.0.get_id()), Json(payload))
+				.await
+				.unwrap();
+		assert_eq!(result.0.get_scheduler_config(), None);
+	}
+
+	#[tokio::test]
+	async fn test_create_item_type_handler_invalid_scheduler_config() {
+		let pool = setup_test_db();
+
+		let payload = CreateItemTypeDto {
+			name: "Reading".to_string(),
+			review_function: None,
+			item_data_schema: None,
+			default_priority: None,
+			scheduler_config: Some(serde_json::json!({"desired_retention": 1.5})),
+		};
+
+		let result = create_item_type_handler(State(pool.clone()), Json(payload)).await;
+
+		assert!(matches!(result, Err(ApiError::InvalidSchedulerConfig(_))));
+		assert!(repo::list_item_types(&pool).unwrap().is_empty());
+	}
+
 	#[tokio::test]
 	async fn test_get_item_type_handler() {
 		let pool = setup_test_db();
@@ -267,7 +542,7 @@ mod tests {
 		.unwrap_err();
 
 		// Check that we got a NotFound error
-		assert!(matches!(result, ApiError::NotFound));
+		assert!(matches!(result, ApiError::NotFound(Resource::ItemType)));
 	}
 
 	#[tokio::test]
@@ -283,7 +558,10 @@ mod tests {
 			.unwrap();
 
 		// Call the handler
-		let result = list_item_types_handler(State(pool.clone())).await.unwrap();
+		let result =
+			list_item_types_handler(State(pool.clone()), Query(ListItemTypesQueryDto::default()))
+				.await
+				.unwrap();
 
 		// Check the result
 		let item_types = result.0;
@@ -291,13 +569,61 @@ mod tests {
 		assert!(
 			item_types
 				.iter()
-				.any(|it| it.get_id() == item_type1.get_id())
+				.any(|it| it.item_type.get_id() == item_type1.get_id())
 		);
 		assert!(
 			item_types
 				.iter()
-				.any(|it| it.get_id() == item_type2.get_id())
+				.any(|it| it.item_type.get_id() == item_type2.get_id())
 		);
+
+		// Counts weren't requested
+		assert!(item_types.iter().all(|it| it.item_count.is_none()));
+		assert!(item_types.iter().all(|it| it.card_count.is_none()));
+	}
+
+	#[tokio::test]
+	async fn test_list_item_types_handler_include_counts() {
+		let pool = setup_test_db();
+
+		// A type with one two-card item, and an empty type
+		let item_type =
+			repo::create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+				.await
+				.unwrap();
+		repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			"Item".to_string(),
+			serde_json::json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		repo::create_item_type(&pool, "Test Type 2".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+
+		// Call the handler
+		let result = list_item_types_handler(
+			State(pool.clone()),
+			Query(ListItemTypesQueryDto {
+				include_counts: true,
+			}),
+		)
+		.await
+		.unwrap();
+
+		// Check the counts
+		let item_types = result.0;
+		let counts = |name: &str| {
+			let it = item_types
+				.iter()
+				.find(|it| it.item_type.get_name() == name)
+				.unwrap();
+			(it.item_count, it.card_count)
+		};
+		assert_eq!(counts("Test Type 1"), (Some(1), Some(2)));
+		assert_eq!(counts("Test Type 2"), (Some(0), Some(0)));
 	}
 
 	#[tokio::test]
@@ -310,6 +636,9 @@ mod tests {
 
 		let payload = UpdateItemTypeDto {
 			review_function: Some("incremental_queue".to_string()),
+			item_data_schema: None,
+			default_priority: None,
+			scheduler_config: None,
 		};
 
 		let result =
@@ -330,6 +659,9 @@ mod tests {
 
 		let payload = UpdateItemTypeDto {
 			review_function: Some("invalid".to_string()),
+			item_data_schema: None,
+			default_priority: None,
+			scheduler_config: None,
 		};
 
 		let result =
@@ -339,12 +671,52 @@ mod tests {
 		assert!(matches!(result, Err(ApiError::InvalidReviewFunction(_))));
 	}
 
+	#[tokio::test]
+	async fn test_update_item_type_handler_item_data_schema() {
+		let pool = setup_test_db();
+
+		let item_type = repo::create_item_type(&pool, "Todo".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		let schema = serde_json::json!({"type": "object", "required": ["title"]});
+
+		// Set the schema without touching the review function
+		let payload = UpdateItemTypeDto {
+			review_function: None,
+			item_data_schema: Some(Some(schema.clone())),
+			default_priority: None,
+			scheduler_config: None,
+		};
+		let result =
+			update_item_type_handler(State(pool.clone()), Path(item_type.get_id()), Json(payload))
+				.await
+				.unwrap();
+		assert_eq!(result.0.get_item_data_schema().unwrap().0, schema);
+		assert_eq!(result.0.get_review_function(), "fsrs");
+
+		// Clear it with an explicit null
+		let payload = UpdateItemTypeDto {
+			review_function: None,
+			item_data_schema: Some(None),
+			default_priority: None,
+			scheduler_config: None,
+		};
+		let result =
+			update_item_type_handler(State(pool.clone()), Path(item_type.get_id()), Json(payload))
+				.await
+				.unwrap();
+		assert_eq!(result.0.get_item_data_schema(), None);
+	}
+
 	#[tokio::test]
 	async fn test_update_item_type_handler_not_found() {
 		let pool = setup_test_db();
 
 		let payload = UpdateItemTypeDto {
 			review_function: Some("fsrs".to_string()),
+			item_data_schema: None,
+			default_priority: None,
+			scheduler_config: None,
 		};
 
 		let result = update_item_type_handler(
@@ -354,6 +726,75 @@ mod tests {
 		)
 		.await;
 
-		assert!(matches!(result, Err(ApiError::NotFound)));
+		assert!(matches!(
+			result,
+			Err(ApiError::NotFound(Resource::ItemType))
+		));
+	}
+
+	#[tokio::test]
+	async fn test_delete_item_type_handler_conflict_then_cascade() {
+		let pool = setup_test_db();
+		let item_type = repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			"Item".to_string(),
+			serde_json::json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+
+		// Refused while the item type still has items
+		let result = delete_item_type_handler(
+			State(pool.clone()),
+			Path(item_type.get_id()),
+			Query(DeleteItemTypeQueryDto::default()),
+		)
+		.await;
+		assert!(
+			matches!(result, Err(ApiError::Conflict(_))),
+			"got {:?}",
+			result
+		);
+		assert!(
+			repo::get_item_type(&pool, &item_type.get_id())
+				.unwrap()
+				.is_some()
+		);
+
+		// Cascading deletes it along with its items
+		delete_item_type_handler(
+			State(pool.clone()),
+			Path(item_type.get_id()),
+			Query(DeleteItemTypeQueryDto { cascade: true }),
+		)
+		.await
+		.unwrap();
+		assert!(
+			repo::get_item_type(&pool, &item_type.get_id())
+				.unwrap()
+				.is_none()
+		);
+		assert!(repo::list_items(&pool).unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_delete_item_type_handler_not_found() {
+		let pool = setup_test_db();
+
+		let result = delete_item_type_handler(
+			State(pool.clone()),
+			Path(ItemTypeId("nonexistent".to_string())),
+			Query(DeleteItemTypeQueryDto::default()),
+		)
+		.await;
+
+		assert!(matches!(
+			result,
+			Err(ApiError::NotFound(Resource::ItemType))
+		));
 	}
 }
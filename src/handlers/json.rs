@@ -0,0 +1,65 @@
+use axum::{
+	extract::{FromRequest, OptionalFromRequest, Request, rejection::JsonRejection},
+	response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::ops::{Deref, DerefMut};
+
+use crate::errors::ApiError;
+
+/// JSON extractor and response used by all handlers
+///
+/// A drop-in replacement for `axum::Json` whose rejection is an `ApiError`,
+/// so malformed or mis-shaped request bodies get a 422 with the usual
+/// `{"error": ...}` body naming the offending field, rather than axum's
+/// plain-text rejection.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+impl<T> Deref for Json<T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for Json<T> {
+	fn deref_mut(&mut self) -> &mut T {
+		&mut self.0
+	}
+}
+
+impl<T, S> FromRequest<S> for Json<T>
+where
+	axum::Json<T>: FromRequest<S, Rejection = JsonRejection>,
+	S: Send + Sync,
+{
+	type Rejection = ApiError;
+
+	async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+		let axum::Json(value) = axum::Json::<T>::from_request(req, state).await?;
+		Ok(Json(value))
+	}
+}
+
+/// Lets handlers take an optional body: a request without a JSON
+/// `Content-Type` extracts as `None`, but a malformed body is still rejected.
+impl<T, S> OptionalFromRequest<S> for Json<T>
+where
+	axum::Json<T>: OptionalFromRequest<S, Rejection = JsonRejection>,
+	S: Send + Sync,
+{
+	type Rejection = ApiError;
+
+	async fn from_request(req: Request, state: &S) -> Result<Option<Self>, Self::Rejection> {
+		let value = <axum::Json<T> as OptionalFromRequest<S>>::from_request(req, state).await?;
+		Ok(value.map(|axum::Json(value)| Json(value)))
+	}
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+	fn into_response(self) -> Response {
+		axum::Json(self.0).into_response()
+	}
+}
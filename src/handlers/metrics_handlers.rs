@@ -0,0 +1,37 @@
+use axum::{
+	extract::State,
+	http::header,
+	response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use tracing::{debug, instrument};
+
+use crate::db::DbPool;
+use crate::metrics;
+
+/// Handler for scraping the server's metrics
+///
+/// This function handles GET requests to `/metrics`, which is only routed
+/// when the `metrics` setting is on.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+///
+/// ### Returns
+///
+/// The request, review and connection pool metrics in the Prometheus text
+/// exposition format
+#[instrument(skip(pool))]
+pub async fn metrics_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+) -> Response {
+	debug!("Rendering metrics");
+
+	(
+		[(header::CONTENT_TYPE, metrics::CONTENT_TYPE)],
+		metrics::metrics().render(&pool),
+	)
+		.into_response()
+}
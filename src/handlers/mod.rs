@@ -1,5 +1,7 @@
+mod admin_handlers;
 mod card_fetched_event_handlers;
 mod card_handlers;
+mod due_event_handlers;
 /// Web API Handlers
 ///
 /// This module contains the handlers for the RESTful API endpoints.
@@ -9,14 +11,22 @@ mod card_handlers;
 mod item_handlers;
 mod item_relation_handlers;
 mod item_type_handlers;
+mod json;
+mod metrics_handlers;
 mod review_handlers;
+mod session_handlers;
 mod tag_handlers;
 
 // Re-export all handlers
+pub use admin_handlers::*;
 pub use card_fetched_event_handlers::*;
 pub use card_handlers::*;
+pub use due_event_handlers::*;
 pub use item_handlers::*;
 pub use item_relation_handlers::*;
 pub use item_type_handlers::*;
+pub use json::Json;
+pub use metrics_handlers::*;
 pub use review_handlers::*;
+pub use session_handlers::*;
 pub use tag_handlers::*;
@@ -1,16 +1,21 @@
-use axum::{
-	Json,
-	extract::{Path, State},
-};
+use axum::extract::{Path, State};
+use axum_extra::extract::Query;
 use chrono::Utc;
 use std::sync::Arc;
 use tracing::{debug, info, instrument, warn};
 
-use crate::dto::CreateReviewDto;
-use crate::errors::ApiError;
-use crate::models::Review;
+use super::Json;
+use crate::dto::{
+	CreateReviewDto, CreateReviewQueryDto, ImportReviewDto, ListReviewsQueryDto, ReviewPreview,
+	ReviewsPageQueryDto,
+};
+use crate::errors::{ApiError, Resource};
+use crate::models::{InvalidRatingError, OutOfScaleRatingError, Rating, Review};
 use crate::repo;
-use crate::{db::DbPool, models::CardId};
+use crate::{
+	db::DbPool,
+	models::{CardId, ItemId, ReviewId},
+};
 
 /// Handler for recording a review for a card
 ///
@@ -19,31 +24,54 @@ use crate::{db::DbPool, models::CardId};
 /// ### Arguments
 ///
 /// * `pool` - The database connection pool
-/// * `payload` - The request payload containing the card ID and rating
+/// * `query` - Whether this is a cram review, which doesn't reschedule the card
+/// * `payload` - The request payload containing the card ID, rating and
+///   optional session ID and response
 ///
 /// ### Returns
 ///
-/// The newly created review as JSON
-#[instrument(skip(pool), fields(card_id = %payload.card_id, rating = %payload.rating))]
+/// The newly created review as JSON, 400 if the rating isn't 1-4 or is above
+/// the configured rating scale, 404 if the card or session doesn't exist, or
+/// 409 if the session has already ended
+#[instrument(skip_all)]
 pub async fn create_review_handler(
 	// Extract the database pool from the application state
 	State(pool): State<Arc<DbPool>>,
+	// Extract whether this is a cram review from the query string
+	Query(query): Query<CreateReviewQueryDto>,
 	// Extract and deserialize the JSON request body
 	Json(payload): Json<CreateReviewDto>,
 ) -> Result<Json<Review>, ApiError> {
-	info!("Creating new review for card");
-
-	// Validate the rating range
-	if payload.rating < 1 || payload.rating > 4 {
-		warn!("Invalid rating: {}", payload.rating);
-		return Err(ApiError::InvalidRating(format!(
-			"Rating must be between 1 and 4, got {}",
-			payload.rating
-		)));
-	}
-
-	// Call the repository function to record the review
-	match repo::record_review(&pool, &payload.card_id, payload.rating).await {
+	info!(card_id = %payload.card_id, rating = %payload.rating, cram = query.cram, "Creating new review for card");
+
+	let rating = Rating::try_from(payload.rating).map_err(|e| {
+		warn!("{}", e);
+		ApiError::InvalidRating(e.to_string())
+	})?;
+
+	// Call the repository function to record the review; a cram review
+	// leaves the card's schedule alone
+	let session_id = payload.session_id.as_ref();
+	let result = if query.cram {
+		repo::record_cram_review_in_session(
+			&pool,
+			&payload.card_id,
+			rating,
+			session_id,
+			payload.response,
+		)
+		.await
+	} else {
+		repo::record_review_in_session(
+			&pool,
+			&payload.card_id,
+			rating,
+			session_id,
+			payload.response,
+		)
+		.await
+	};
+	match result {
 		Ok(review) => {
 			info!("Successfully created review with id: {}", review.get_id());
 			Ok(Json(review))
@@ -52,14 +80,165 @@ pub async fn create_review_handler(
 			// Check if the error is due to card not found
 			if e.to_string().contains("Card not found") {
 				debug!("Card not found");
-				Err(ApiError::NotFound)
+				Err(ApiError::NotFound(Resource::Card))
+			} else if let Some(e) = e.downcast_ref::<OutOfScaleRatingError>() {
+				Err(ApiError::InvalidRating(e.to_string()))
 			} else {
-				Err(ApiError::Database(e))
+				Err(super::session_error(e))
 			}
 		}
 	}
 }
 
+/// Handler for importing a log of past reviews
+///
+/// This function handles POST requests to `/reviews/import`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `payload` - The reviews to import, oldest first
+///
+/// ### Returns
+///
+/// The cards reviewed, rescheduled from their full history, as JSON; 400 if
+/// the reviews aren't sorted by timestamp, one is timestamped in the future,
+/// or a rating isn't 1-4 or is above the configured rating scale; 404 if a card doesn't exist, in which case
+/// nothing is imported
+#[instrument(skip_all)]
+pub async fn import_reviews_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract and deserialize the JSON request body
+	Json(payload): Json<Vec<ImportReviewDto>>,
+) -> Result<Json<Vec<serde_json::Value>>, ApiError> {
+	info!(count = payload.len(), "Importing reviews");
+
+	if payload
+		.windows(2)
+		.any(|pair| pair[1].timestamp < pair[0].timestamp)
+	{
+		return Err(ApiError::InvalidReviewImport(
+			"Reviews must be sorted by timestamp, oldest first".to_string(),
+		));
+	}
+	// Sorted, so only the last review can be the furthest in the future
+	if payload
+		.last()
+		.is_some_and(|review| review.timestamp > Utc::now())
+	{
+		return Err(ApiError::InvalidReviewImport(
+			"Reviews can't be timestamped in the future".to_string(),
+		));
+	}
+
+	let log = payload
+		.into_iter()
+		.map(|review| {
+			Ok((
+				review.card_id,
+				Rating::try_from(review.rating)?,
+				review.timestamp,
+			))
+		})
+		.collect::<Result<Vec<_>, InvalidRatingError>>()
+		.map_err(|e| {
+			warn!("{}", e);
+			ApiError::InvalidRating(e.to_string())
+		})?;
+	let cards = repo::import_reviews(&pool, &log).await.map_err(|e| {
+		if let Some(e) = e.downcast_ref::<repo::ImportedCardNotFoundError>() {
+			debug!("{}", e);
+			ApiError::NotFound(Resource::Card)
+		} else if let Some(e) = e.downcast_ref::<OutOfScaleRatingError>() {
+			ApiError::InvalidRating(e.to_string())
+		} else {
+			ApiError::Database(e)
+		}
+	})?;
+
+	info!("Successfully imported {} reviews", log.len());
+	Ok(Json(
+		cards
+			.iter()
+			.map(|card| card.to_json_hide_priority_offset())
+			.collect(),
+	))
+}
+
+/// Handler for listing reviews across all cards
+///
+/// This function handles GET requests to `/reviews`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `query` - Filters (`from`, `to`, `rating`, `card_id`) and paging (`limit`, `offset`)
+///
+/// ### Returns
+///
+/// The matching reviews as JSON, newest first
+#[instrument(skip(pool, query))]
+pub async fn list_reviews_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the query parameters
+	Query(query): Query<ListReviewsQueryDto>,
+) -> Result<Json<Vec<Review>>, ApiError> {
+	debug!("Listing reviews");
+
+	// Validate the rating filter range
+	if let Some(rating) = query.rating
+		&& !(1..=4).contains(&rating)
+	{
+		warn!("Invalid rating filter: {}", rating);
+		return Err(ApiError::InvalidRating(format!(
+			"Rating must be between 1 and 4, got {}",
+			rating
+		)));
+	}
+
+	let reviews = repo::list_reviews_with_filters(&pool, &query).map_err(ApiError::Database)?;
+
+	info!("Retrieved {} reviews", reviews.len());
+
+	Ok(Json(reviews))
+}
+
+/// Handler for undoing a review
+///
+/// This function handles POST requests to `/reviews/{review_id}/undo`.
+/// The review is deleted and the card is rescheduled as if it had never
+/// happened.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `review_id` - The ID of the review to undo
+///
+/// ### Returns
+///
+/// The rescheduled card as JSON
+#[instrument(skip(pool), fields(review_id = %review_id))]
+pub async fn undo_review_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the review ID from the URL path
+	Path(review_id): Path<ReviewId>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+	info!("Undoing review");
+
+	let card = repo::undo_review(&pool, &review_id)
+		.await
+		.map_err(ApiError::Database)?
+		.ok_or(ApiError::NotFound(Resource::Review))?;
+
+	info!("Successfully undid review for card {}", card.get_id());
+
+	// Return the rescheduled card as JSON with hidden priority offset
+	Ok(Json(card.to_json_hide_priority_offset()))
+}
+
 /// Handler for getting all possible next reviews for a card
 ///
 /// This function handles GET requests to `/cards/{card_id}/next_reviews`.
@@ -71,7 +250,8 @@ pub async fn create_review_handler(
 ///
 /// ### Returns
 ///
-/// A list of next reviews for the specified card as JSON
+/// A list of next reviews for the specified card as JSON, or 404 if the card
+/// doesn't exist
 #[instrument(skip(pool), fields(card_id = %card_id))]
 pub async fn get_all_next_reviews_for_card_handler(
 	// Extract the database pool from the application state
@@ -81,6 +261,10 @@ pub async fn get_all_next_reviews_for_card_handler(
 ) -> Result<Json<Vec<(chrono::DateTime<Utc>, serde_json::Value)>>, ApiError> {
 	debug!("Getting all possible next reviews for card {}", card_id);
 
+	repo::get_card_raw(&pool, &card_id)
+		.map_err(ApiError::Database)?
+		.ok_or(ApiError::NotFound(Resource::Card))?;
+
 	// Get the next reviews for the card
 	let next_reviews = repo::get_all_next_reviews_for_card(&pool, &card_id)
 		.await
@@ -97,6 +281,35 @@ pub async fn get_all_next_reviews_for_card_handler(
 	Ok(Json(next_reviews))
 }
 
+/// Handler for previewing the next review date for each rating
+///
+/// This function handles GET requests to `/cards/{card_id}/preview`.
+/// Nothing is recorded.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `card_id` - The ID of the card to preview
+///
+/// ### Returns
+///
+/// The next review date and interval for each rating 1-4 as JSON
+#[instrument(skip(pool), fields(card_id = %card_id))]
+pub async fn preview_reviews_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the card ID from the URL path
+	Path(card_id): Path<CardId>,
+) -> Result<Json<Vec<ReviewPreview>>, ApiError> {
+	debug!("Previewing reviews for card");
+
+	let previews = repo::preview_reviews(&pool, &card_id)
+		.map_err(ApiError::Database)?
+		.ok_or(ApiError::NotFound(Resource::Card))?;
+
+	Ok(Json(previews))
+}
+
 /// Handler for listing all reviews for a card
 ///
 /// This function handles GET requests to `/cards/{card_id}/reviews`.
@@ -130,7 +343,7 @@ pub async fn list_reviews_by_card_handler(
 	// same time.
 	let card = repo::get_card_raw(&pool, &card_id)
 		.map_err(ApiError::Database)?
-		.ok_or(ApiError::NotFound)?;
+		.ok_or(ApiError::NotFound(Resource::Card))?;
 
 	debug!("Card found with id: {}", card.get_id());
 
@@ -143,6 +356,42 @@ pub async fn list_reviews_by_card_handler(
 	Ok(Json(reviews))
 }
 
+/// Handler for listing the reviews of every card of an item
+///
+/// This function handles GET requests to `/items/{item_id}/reviews`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `item_id` - The ID of the item to get reviews for
+/// * `page` - Paging (`limit`, `offset`)
+///
+/// ### Returns
+///
+/// The item's reviews as JSON, newest first, or 404 if the item doesn't exist
+#[instrument(skip(pool, page), fields(item_id = %item_id))]
+pub async fn list_reviews_by_item_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the item ID from the URL path
+	Path(item_id): Path<ItemId>,
+	// Extract the query parameters
+	Query(page): Query<ReviewsPageQueryDto>,
+) -> Result<Json<Vec<Review>>, ApiError> {
+	debug!("Listing reviews for item");
+
+	repo::get_item(&pool, &item_id)
+		.map_err(ApiError::Database)?
+		.ok_or(ApiError::NotFound(Resource::Item))?;
+
+	let reviews =
+		repo::list_reviews_for_item(&pool, &item_id, &page).map_err(ApiError::Database)?;
+
+	info!("Retrieved {} reviews for item {}", reviews.len(), item_id);
+
+	Ok(Json(reviews))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -174,18 +423,192 @@ mod tests {
 		// Create a payload for the review
 		let payload = CreateReviewDto {
 			card_id: card.get_id(),
-			rating: 2,
+			rating: Rating::Hard.as_i32(),
+			session_id: None,
+			response: None,
 		};
 
 		// Call the handler
-		let result = create_review_handler(State(pool.clone()), Json(payload))
-			.await
-			.unwrap();
+		let result = create_review_handler(
+			State(pool.clone()),
+			Query(CreateReviewQueryDto::default()),
+			Json(payload),
+		)
+		.await
+		.unwrap();
 
 		// Check the result
 		let review = result.0;
 		assert_eq!(review.get_card_id(), card.get_id());
-		assert_eq!(review.get_rating(), 2);
+		assert_eq!(review.get_rating(), Rating::Hard);
+	}
+
+	#[tokio::test]
+	async fn test_import_reviews_handler() {
+		let pool = setup_test_db();
+
+		let item_type = repo::create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		let item = repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			"Test Item".to_string(),
+			json!({"front": "Hello", "back": "World"}),
+		)
+		.await
+		.unwrap();
+		let card = repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone();
+		let now = Utc::now();
+		let review = |rating: Rating, days_ago| ImportReviewDto {
+			card_id: card.get_id(),
+			rating: rating.as_i32(),
+			timestamp: now - chrono::Duration::days(days_ago),
+		};
+
+		// Reviews out of order are refused
+		let result = import_reviews_handler(
+			State(pool.clone()),
+			Json(vec![review(Rating::Good, 1), review(Rating::Good, 3)]),
+		)
+		.await;
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::InvalidReviewImport(_)
+		));
+
+		// As are reviews from the future
+		let result = import_reviews_handler(
+			State(pool.clone()),
+			Json(vec![review(Rating::Good, 3), review(Rating::Good, -1)]),
+		)
+		.await;
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::InvalidReviewImport(_)
+		));
+
+		// In order, they're replayed onto the card
+		let result = import_reviews_handler(
+			State(pool.clone()),
+			Json(vec![review(Rating::Good, 3), review(Rating::Good, 1)]),
+		)
+		.await
+		.unwrap();
+		assert_eq!(result.0.len(), 1);
+		assert_eq!(result.0[0]["id"], card.get_id().0);
+		let stored = repo::get_card_raw(&pool, &card.get_id()).unwrap().unwrap();
+		assert_eq!(
+			stored.get_last_review(),
+			Some(now - chrono::Duration::days(1))
+		);
+
+		// A rating outside 1-4 is refused
+		let mut invalid = review(Rating::Good, 0);
+		invalid.rating = 5;
+		let result = import_reviews_handler(State(pool.clone()), Json(vec![invalid])).await;
+		match result {
+			Err(ApiError::InvalidRating(msg)) => {
+				assert_eq!(msg, "Rating must be between 1 and 4, got 5");
+			}
+			other => panic!("expected InvalidRating, got {:?}", other.map(|r| r.0)),
+		}
+
+		// A card that doesn't exist is a 404
+		let result = import_reviews_handler(
+			State(pool.clone()),
+			Json(vec![ImportReviewDto {
+				card_id: CardId("nonexistent".to_string()),
+				rating: Rating::Good.as_i32(),
+				timestamp: now,
+			}]),
+		)
+		.await;
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Card)
+		));
+	}
+
+	#[tokio::test]
+	async fn test_create_review_handler_cram() {
+		let pool = setup_test_db();
+
+		let item_type = repo::create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		let item = repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			"Test Item".to_string(),
+			json!({"front": "Hello", "back": "World"}),
+		)
+		.await
+		.unwrap();
+		let card = repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone();
+
+		let payload = CreateReviewDto {
+			card_id: card.get_id(),
+			rating: Rating::Good.as_i32(),
+			session_id: None,
+			response: None,
+		};
+		let result = create_review_handler(
+			State(pool.clone()),
+			Query(CreateReviewQueryDto { cram: true }),
+			Json(payload),
+		)
+		.await
+		.unwrap();
+
+		// The review is marked as cram, and the card isn't rescheduled
+		assert!(result.0.get_cram());
+		let stored = repo::get_card_raw(&pool, &card.get_id()).unwrap().unwrap();
+		assert_eq!(stored.get_next_review(), card.get_next_review());
+		assert_eq!(stored.get_last_review(), None);
+	}
+
+	#[tokio::test]
+	async fn test_create_review_handler_out_of_scale_rating() {
+		let pool = setup_test_db();
+		repo::update_settings(&pool, |s| s.rating_scale = 2)
+			.await
+			.unwrap();
+
+		let item_type = repo::create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		let item = repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			"Test Item".to_string(),
+			json!({"front": "Hello", "back": "World"}),
+		)
+		.await
+		.unwrap();
+		let card = repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone();
+
+		let payload = CreateReviewDto {
+			card_id: card.get_id(),
+			rating: Rating::Easy.as_i32(),
+			session_id: None,
+			response: None,
+		};
+		let result = create_review_handler(
+			State(pool.clone()),
+			Query(CreateReviewQueryDto::default()),
+			Json(payload),
+		)
+		.await;
+		match result {
+			Err(ApiError::InvalidRating(msg)) => {
+				assert_eq!(
+					msg,
+					"Rating must be between 1 and 2 on the 2-button rating scale, got 4"
+				);
+			}
+			other => panic!("expected InvalidRating, got {:?}", other.map(|r| r.0)),
+		}
 	}
 
 	#[tokio::test]
@@ -210,18 +633,40 @@ mod tests {
 		let cards = repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
 		let card = &cards[0];
 
-		// Create a payload with an invalid rating
-		let payload = CreateReviewDto {
-			card_id: card.get_id(),
-			rating: 0, // Invalid rating
-		};
-
-		// Call the handler
-		let result = create_review_handler(State(pool.clone()), Json(payload)).await;
+		for rating in [0, 5] {
+			let payload = CreateReviewDto {
+				card_id: card.get_id(),
+				rating,
+				session_id: None,
+				response: None,
+			};
+
+			// Call the handler
+			let result = create_review_handler(
+				State(pool.clone()),
+				Query(CreateReviewQueryDto::default()),
+				Json(payload),
+			)
+			.await;
+
+			// Check that we got an InvalidRating error
+			match result {
+				Err(ApiError::InvalidRating(msg)) => {
+					assert_eq!(
+						msg,
+						format!("Rating must be between 1 and 4, got {}", rating)
+					);
+				}
+				other => panic!("expected InvalidRating, got {:?}", other.map(|r| r.0)),
+			}
+		}
 
-		// Check that we got an InvalidRating error
-		assert!(result.is_err());
-		assert!(matches!(result.unwrap_err(), ApiError::InvalidRating(_)));
+		// No review was recorded
+		assert!(
+			repo::get_reviews_for_card(&pool, &card.get_id())
+				.unwrap()
+				.is_empty()
+		);
 	}
 
 	#[tokio::test]
@@ -231,15 +676,144 @@ mod tests {
 		// Create a payload with a non-existent card ID
 		let payload = CreateReviewDto {
 			card_id: CardId("nonexistent".to_string()),
-			rating: 2,
+			rating: Rating::Hard.as_i32(),
+			session_id: None,
+			response: None,
 		};
 
 		// Call the handler
-		let result = create_review_handler(State(pool.clone()), Json(payload)).await;
+		let result = create_review_handler(
+			State(pool.clone()),
+			Query(CreateReviewQueryDto::default()),
+			Json(payload),
+		)
+		.await;
 
 		// Check that we got a NotFound error
 		assert!(result.is_err());
-		assert!(matches!(result.unwrap_err(), ApiError::NotFound));
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Card)
+		));
+	}
+
+	#[tokio::test]
+	async fn test_undo_review_handler() {
+		let pool = setup_test_db();
+
+		// Set up test data
+		let item_type = repo::create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		let item = repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			"Test Item".to_string(),
+			json!({"front": "Hello", "back": "World"}),
+		)
+		.await
+		.unwrap();
+		let card = repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone();
+		let review = repo::record_review(&pool, &card.get_id(), Rating::Good)
+			.await
+			.unwrap();
+
+		// Call the handler
+		let result = undo_review_handler(State(pool.clone()), Path(review.get_id()))
+			.await
+			.unwrap();
+
+		// The card is back to never having been reviewed
+		let undone = result.0;
+		assert_eq!(undone["id"], card.get_id().0);
+		assert!(undone["last_review"].is_null());
+		assert!(
+			repo::get_reviews_for_card(&pool, &card.get_id())
+				.unwrap()
+				.is_empty()
+		);
+	}
+
+	#[tokio::test]
+	async fn test_undo_review_handler_not_found() {
+		let pool = setup_test_db();
+
+		// Call the handler with a non-existent review ID
+		let result = undo_review_handler(
+			State(pool.clone()),
+			Path(ReviewId("nonexistent".to_string())),
+		)
+		.await;
+
+		// Check that we got a NotFound error
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Review)
+		));
+	}
+
+	#[tokio::test]
+	async fn test_list_reviews_handler_invalid_rating() {
+		let pool = setup_test_db();
+
+		// Call the handler with an out-of-range rating filter
+		let query = ListReviewsQueryDto {
+			rating: Some(5),
+			..Default::default()
+		};
+		let result = list_reviews_handler(State(pool.clone()), Query(query)).await;
+
+		// Check that we got an InvalidRating error
+		assert!(matches!(result.unwrap_err(), ApiError::InvalidRating(_)));
+	}
+
+	#[tokio::test]
+	async fn test_preview_reviews_handler() {
+		let pool = setup_test_db();
+
+		// Set up test data
+		let item_type = repo::create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		let item = repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			"Test Item".to_string(),
+			json!({"front": "Hello", "back": "World"}),
+		)
+		.await
+		.unwrap();
+		let card = repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone();
+
+		// Call the handler
+		let result = preview_reviews_handler(State(pool.clone()), Path(card.get_id()))
+			.await
+			.unwrap();
+
+		// One preview per rating, and nothing recorded
+		let previews = result.0;
+		assert_eq!(previews.len(), 4);
+		assert!(
+			repo::get_reviews_for_card(&pool, &card.get_id())
+				.unwrap()
+				.is_empty()
+		);
+	}
+
+	#[tokio::test]
+	async fn test_preview_reviews_handler_not_found() {
+		let pool = setup_test_db();
+
+		// Call the handler with a non-existent card ID
+		let result =
+			preview_reviews_handler(State(pool.clone()), Path(CardId("nonexistent".to_string())))
+				.await;
+
+		// Check that we got a NotFound error
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Card)
+		));
 	}
 
 	#[tokio::test]
@@ -264,12 +838,16 @@ mod tests {
 		let card = &cards[0];
 
 		// Create some reviews
-		let review1 = repo::record_review(&pool, &card.get_id(), 2).await.unwrap();
+		let review1 = repo::record_review(&pool, &card.get_id(), Rating::Hard)
+			.await
+			.unwrap();
 
 		// We need to wait a moment to ensure the timestamps are different
 		std::thread::sleep(std::time::Duration::from_millis(10));
 
-		let review2 = repo::record_review(&pool, &card.get_id(), 3).await.unwrap();
+		let review2 = repo::record_review(&pool, &card.get_id(), Rating::Good)
+			.await
+			.unwrap();
 
 		// Call the handler
 		let result = list_reviews_by_card_handler(State(pool.clone()), Path(card.get_id()))
@@ -283,6 +861,52 @@ mod tests {
 		assert!(reviews.iter().any(|r| r.get_id() == review2.get_id()));
 	}
 
+	#[tokio::test]
+	async fn test_list_reviews_by_item_handler() {
+		let pool = setup_test_db();
+
+		// "Test Type" items get two cards each
+		let item_type = repo::create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		let item = repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			"Test Item".to_string(),
+			json!({"front": "Hello", "back": "World"}),
+		)
+		.await
+		.unwrap();
+		let cards = repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+
+		let review1 = repo::record_review(&pool, &cards[0].get_id(), Rating::Good)
+			.await
+			.unwrap();
+		let review2 = repo::record_review(&pool, &cards[1].get_id(), Rating::Easy)
+			.await
+			.unwrap();
+
+		let reviews = list_reviews_by_item_handler(
+			State(pool.clone()),
+			Path(item.get_id()),
+			Query(ReviewsPageQueryDto::default()),
+		)
+		.await
+		.unwrap()
+		.0;
+		assert_eq!(reviews.len(), 2);
+		assert!(reviews.iter().any(|r| r.get_id() == review1.get_id()));
+		assert!(reviews.iter().any(|r| r.get_id() == review2.get_id()));
+
+		let result = list_reviews_by_item_handler(
+			State(pool.clone()),
+			Path(ItemId("nonexistent".to_string())),
+			Query(ReviewsPageQueryDto::default()),
+		)
+		.await;
+		assert!(matches!(result, Err(ApiError::NotFound(Resource::Item))));
+	}
+
 	#[tokio::test]
 	async fn test_list_reviews_by_card_handler_not_found() {
 		let pool = setup_test_db();
@@ -296,6 +920,9 @@ mod tests {
 
 		// Check that we got a NotFound error
 		assert!(result.is_err());
-		assert!(matches!(result.unwrap_err(), ApiError::NotFound));
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Card)
+		));
 	}
 }
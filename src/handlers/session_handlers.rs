@@ -0,0 +1,178 @@
+use axum::extract::{Path, State};
+use std::sync::Arc;
+use tracing::{debug, info, instrument};
+
+use super::Json;
+use crate::db::DbPool;
+use crate::dto::SessionWithStats;
+use crate::errors::{ApiError, Resource};
+use crate::models::SessionId;
+use crate::repo;
+
+/// Maps an error from a session-aware repo function to an API error
+///
+/// A missing session becomes a 404 and an already ended one a 409; everything
+/// else is a database error.
+pub(crate) fn session_error(e: anyhow::Error) -> ApiError {
+	if e.to_string().contains("Session not found") {
+		debug!("Session not found");
+		return ApiError::NotFound(Resource::Session);
+	}
+	match e.downcast::<repo::SessionEndedError>() {
+		Ok(ended) => ApiError::Conflict(ended.to_string()),
+		Err(e) => ApiError::Database(e),
+	}
+}
+
+/// Handler for starting a review session
+///
+/// This function handles POST requests to `/sessions`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+///
+/// ### Returns
+///
+/// The newly started session as JSON
+#[instrument(skip(pool))]
+pub async fn start_session_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+) -> Result<Json<SessionWithStats>, ApiError> {
+	info!("Starting new session");
+
+	let session = repo::start_session(&pool)
+		.await
+		.map_err(ApiError::Database)?;
+	let session = repo::get_session_stats(&pool, session).map_err(ApiError::Database)?;
+
+	info!(
+		"Successfully started session with id: {}",
+		session.session.get_id()
+	);
+
+	Ok(Json(session))
+}
+
+/// Handler for ending a review session
+///
+/// This function handles POST requests to `/sessions/{session_id}/end`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `session_id` - The ID of the session to end
+///
+/// ### Returns
+///
+/// The ended session as JSON, with its duration and the number of distinct
+/// cards reviewed in it, 404 if the session doesn't exist, or 409 if it has
+/// already ended
+#[instrument(skip(pool), fields(session_id = %session_id))]
+pub async fn end_session_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the session ID from the URL path
+	Path(session_id): Path<SessionId>,
+) -> Result<Json<SessionWithStats>, ApiError> {
+	info!("Ending session");
+
+	let session = repo::end_session(&pool, &session_id)
+		.await
+		.map_err(session_error)?;
+	let session = repo::get_session_stats(&pool, session).map_err(ApiError::Database)?;
+
+	info!(
+		"Successfully ended session after reviewing {} cards",
+		session.card_count
+	);
+
+	Ok(Json(session))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::Rating;
+	use crate::test_utils::*;
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn test_start_session_handler() {
+		let pool = setup_test_db();
+
+		let result = start_session_handler(State(pool.clone())).await.unwrap();
+
+		assert_eq!(result.card_count, 0);
+		assert_eq!(result.duration_secs, None);
+		assert_eq!(result.session.get_ended_at(), None);
+
+		let stored = repo::get_session(&pool, &result.session.get_id())
+			.unwrap()
+			.unwrap();
+		assert_eq!(stored, result.session);
+	}
+
+	#[tokio::test]
+	async fn test_end_session_handler() {
+		let pool = setup_test_db();
+
+		let item_type = repo::create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		let item = repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			"Test Item".to_string(),
+			json!({"front": "Hello", "back": "World"}),
+		)
+		.await
+		.unwrap();
+		let cards = repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+
+		let session = repo::start_session(&pool).await.unwrap();
+		for card in &cards {
+			repo::record_review_in_session(
+				&pool,
+				&card.get_id(),
+				Rating::Good,
+				Some(&session.get_id()),
+				None,
+			)
+			.await
+			.unwrap();
+		}
+
+		let result = end_session_handler(State(pool.clone()), Path(session.get_id()))
+			.await
+			.unwrap();
+
+		assert_eq!(result.card_count, cards.len() as i64);
+		assert!(result.session.get_ended_at().is_some());
+		assert!(result.duration_secs.unwrap() >= 0);
+	}
+
+	#[tokio::test]
+	async fn test_end_session_handler_not_found() {
+		let pool = setup_test_db();
+
+		let result = end_session_handler(State(pool.clone()), Path(SessionId::new())).await;
+
+		assert!(matches!(result, Err(ApiError::NotFound(Resource::Session))));
+	}
+
+	#[tokio::test]
+	async fn test_end_session_handler_already_ended() {
+		let pool = setup_test_db();
+
+		let session = repo::start_session(&pool).await.unwrap();
+		end_session_handler(State(pool.clone()), Path(session.get_id()))
+			.await
+			.unwrap();
+
+		let result = end_session_handler(State(pool.clone()), Path(session.get_id())).await;
+
+		assert!(matches!(result, Err(ApiError::Conflict(_))));
+	}
+}
@@ -1,15 +1,19 @@
-use axum::{
-	Json,
-	extract::{Path, State},
-};
+use axum::extract::{Path, Query, State};
 use std::sync::Arc;
 use tracing::{debug, info, instrument};
 
+use super::Json;
 use crate::models::Tag;
 use crate::repo;
 use crate::{db::DbPool, models::ItemId};
-use crate::{dto::CreateTagDto, models::CardId};
-use crate::{errors::ApiError, models::TagId};
+use crate::{
+	dto::{CreateTagDto, ListItemTagsQueryDto, TagStats},
+	models::CardId,
+};
+use crate::{
+	errors::{ApiError, Resource},
+	models::TagId,
+};
 
 /// Handler for creating a new tag
 ///
@@ -18,11 +22,12 @@ use crate::{errors::ApiError, models::TagId};
 /// ### Arguments
 ///
 /// * `pool` - The database connection pool
-/// * `payload` - The request payload containing the tag name and visibility
+/// * `payload` - The request payload containing the tag name, visibility and
+///   optional parent tag
 ///
 /// ### Returns
 ///
-/// The newly created tag as JSON
+/// The newly created tag as JSON, or 404 if the parent tag doesn't exist
 #[instrument(skip(pool), fields(name = %payload.name, visible = %payload.visible))]
 pub async fn create_tag_handler(
 	// Extract the database pool from the application state
@@ -33,9 +38,15 @@ pub async fn create_tag_handler(
 	info!("Creating new tag");
 
 	// Call the repository function to create the tag
-	let tag = repo::create_tag(&pool, payload.name, payload.visible)
+	let tag = repo::create_tag_with_parent(&pool, payload.name, payload.visible, payload.parent_id)
 		.await
-		.map_err(ApiError::Database)?;
+		.map_err(|e| {
+			if e.to_string().contains("Parent tag not found") {
+				ApiError::NotFound(Resource::Tag)
+			} else {
+				ApiError::Database(e)
+			}
+		})?;
 
 	info!("Successfully created tag with id: {}", tag.get_id());
 
@@ -101,7 +112,14 @@ pub async fn add_tag_to_item_handler(
 			// Check if the error is due to item or tag not found
 			if e.to_string().contains("FOREIGN KEY constraint failed") {
 				debug!("Failed to add tag: item or tag not found");
-				Err(ApiError::NotFound)
+				// Work out which of the two is missing for the error code
+				let item = repo::get_item(&pool, &item_id).map_err(ApiError::Database)?;
+				let resource = if item.is_none() {
+					Resource::Item
+				} else {
+					Resource::Tag
+				};
+				Err(ApiError::NotFound(resource))
 			} else {
 				Err(ApiError::Database(e))
 			}
@@ -140,7 +158,7 @@ pub async fn remove_tag_from_item_handler(
 			// Check if the error is due to item or tag not found
 			if e.to_string().contains("not found") {
 				debug!("Failed to remove tag: item or tag not found");
-				Err(ApiError::NotFound)
+				Err(ApiError::NotFound(Resource::ItemTag))
 			} else {
 				Err(ApiError::Database(e))
 			}
@@ -148,6 +166,114 @@ pub async fn remove_tag_from_item_handler(
 	}
 }
 
+/// Handler for merging one tag into another
+///
+/// This function handles POST requests to `/tags/{source_id}/merge/{target_id}`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `params` - The source and target tag IDs extracted from the URL path
+///
+/// ### Returns
+///
+/// The surviving target tag as JSON, 404 if either tag doesn't exist, or 409
+/// if the source and target are the same tag
+#[instrument(skip(pool), fields(source_id = %source_id, target_id = %target_id))]
+pub async fn merge_tags_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the source and target tag IDs from the URL path
+	Path((source_id, target_id)): Path<(TagId, TagId)>,
+) -> Result<Json<Tag>, ApiError> {
+	info!("Merging tags");
+
+	if source_id == target_id {
+		return Err(ApiError::Conflict(
+			"Cannot merge a tag into itself".to_string(),
+		));
+	}
+
+	match repo::merge_tags(&pool, &source_id, &target_id).await {
+		Ok(tag) => {
+			info!("Successfully merged tag {} into {}", source_id, target_id);
+			Ok(Json(tag))
+		}
+		Err(e) => {
+			if e.to_string().contains("Tag not found") {
+				debug!("Failed to merge tags: tag not found");
+				Err(ApiError::NotFound(Resource::Tag))
+			} else {
+				Err(ApiError::Database(e))
+			}
+		}
+	}
+}
+
+/// Handler for getting a tag's item, card and due card counts
+///
+/// This function handles GET requests to `/tags/{tag_id}/stats`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `tag_id` - The ID of the tag to count for
+///
+/// ### Returns
+///
+/// The tag's counts as JSON, or 404 if the tag doesn't exist
+#[instrument(skip(pool), fields(tag_id = %tag_id))]
+pub async fn get_tag_stats_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the tag ID from the URL path
+	Path(tag_id): Path<TagId>,
+) -> Result<Json<TagStats>, ApiError> {
+	debug!("Getting tag stats");
+
+	let stats = repo::get_tag_stats(&pool, &tag_id)
+		.map_err(ApiError::Database)?
+		.ok_or(ApiError::NotFound(Resource::Tag))?;
+
+	info!(
+		"Tag {} has {} items, {} cards and {} due",
+		tag_id, stats.item_count, stats.card_count, stats.due_count
+	);
+
+	Ok(Json(stats))
+}
+
+/// Handler for getting a tag together with every tag beneath it
+///
+/// This function handles GET requests to `/tags/{tag_id}/subtree`.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `tag_id` - The ID of the tag at the top of the subtree
+///
+/// ### Returns
+///
+/// The tag followed by its descendants, each level before the next, as JSON,
+/// or 404 if the tag doesn't exist
+#[instrument(skip(pool), fields(tag_id = %tag_id))]
+pub async fn get_tag_subtree_handler(
+	// Extract the database pool from the application state
+	State(pool): State<Arc<DbPool>>,
+	// Extract the tag ID from the URL path
+	Path(tag_id): Path<TagId>,
+) -> Result<Json<Vec<Tag>>, ApiError> {
+	debug!("Getting tag subtree");
+
+	let subtree = repo::get_tag_subtree(&pool, &tag_id)
+		.map_err(ApiError::Database)?
+		.ok_or(ApiError::NotFound(Resource::Tag))?;
+
+	info!("Retrieved {} tags under {}", subtree.len(), tag_id);
+
+	Ok(Json(subtree))
+}
+
 /// Handler for listing all tags for a card
 ///
 /// This function handles GET requests to `/cards/{card_id}/tags`.
@@ -179,7 +305,7 @@ pub async fn list_tags_for_card_handler(
 			// Check if the error is due to card not found
 			if e.to_string().contains("Card not found") {
 				debug!("Card not found");
-				Err(ApiError::NotFound)
+				Err(ApiError::NotFound(Resource::Card))
 			} else {
 				Err(ApiError::Database(e))
 			}
@@ -195,6 +321,7 @@ pub async fn list_tags_for_card_handler(
 ///
 /// * `pool` - The database connection pool
 /// * `item_id` - The ID of the item to get tags for
+/// * `query` - Whether to include the ancestors of the item's tags
 ///
 /// ### Returns
 ///
@@ -205,16 +332,23 @@ pub async fn list_tags_for_item_handler(
 	State(pool): State<Arc<DbPool>>,
 	// Extract the item ID from the URL path
 	Path(item_id): Path<ItemId>,
+	// Extract whether to include ancestor tags from the query string
+	Query(query): Query<ListItemTagsQueryDto>,
 ) -> Result<Json<Vec<Tag>>, ApiError> {
 	debug!("Listing tags for item");
 
 	// First check if the item exists
 	repo::get_item(&pool, &item_id)
 		.map_err(ApiError::Database)?
-		.ok_or(ApiError::NotFound)?;
+		.ok_or(ApiError::NotFound(Resource::Item))?;
 
 	// Call the repository function to list tags for the item
-	let tags = repo::list_tags_for_item(&pool, &item_id).map_err(ApiError::Database)?;
+	let tags = if query.include_ancestors {
+		repo::list_tags_for_item_with_ancestors(&pool, &item_id)
+	} else {
+		repo::list_tags_for_item(&pool, &item_id)
+	}
+	.map_err(ApiError::Database)?;
 
 	info!("Retrieved {} tags for item {}", tags.len(), item_id);
 
@@ -238,6 +372,7 @@ mod tests {
 		let payload = CreateTagDto {
 			name: "Important".to_string(),
 			visible: true,
+			parent_id: None,
 		};
 
 		// Call the handler
@@ -251,6 +386,37 @@ mod tests {
 		assert_eq!(tag.get_visible(), true);
 	}
 
+	#[tokio::test]
+	async fn test_create_tag_handler_with_parent() {
+		let pool = setup_test_db();
+		let parent = repo::create_tag(&pool, "Language".to_string(), true)
+			.await
+			.unwrap();
+
+		let payload = CreateTagDto {
+			name: "Spanish".to_string(),
+			visible: true,
+			parent_id: Some(parent.get_id()),
+		};
+		let tag = create_tag_handler(State(pool.clone()), Json(payload))
+			.await
+			.unwrap()
+			.0;
+		assert_eq!(tag.get_parent_id(), Some(parent.get_id()));
+
+		// A parent that doesn't exist is a 404
+		let payload = CreateTagDto {
+			name: "French".to_string(),
+			visible: true,
+			parent_id: Some(TagId("nonexistent".to_string())),
+		};
+		let result = create_tag_handler(State(pool.clone()), Json(payload)).await;
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Tag)
+		));
+	}
+
 	#[tokio::test]
 	async fn test_list_tags_handler() {
 		let pool = setup_test_db();
@@ -328,7 +494,7 @@ mod tests {
 
 		let err = result.unwrap_err();
 		assert!(
-			matches!(err, ApiError::NotFound),
+			matches!(err, ApiError::NotFound(Resource::Item)),
 			"Expected NotFound error, got {:?}",
 			err
 		);
@@ -399,7 +565,7 @@ mod tests {
 
 		let err = result.unwrap_err();
 		assert!(
-			matches!(err, ApiError::NotFound),
+			matches!(err, ApiError::NotFound(Resource::ItemTag)),
 			"Expected NotFound error, got {:?}",
 			err
 		);
@@ -439,9 +605,13 @@ mod tests {
 			.unwrap();
 
 		// Call the handler
-		let result = list_tags_for_item_handler(State(pool.clone()), Path(item.get_id()))
-			.await
-			.unwrap();
+		let result = list_tags_for_item_handler(
+			State(pool.clone()),
+			Path(item.get_id()),
+			Query(ListItemTagsQueryDto::default()),
+		)
+		.await
+		.unwrap();
 
 		// Check the result
 		let tags = result.0;
@@ -450,6 +620,65 @@ mod tests {
 		assert!(tags.iter().any(|t| t.get_id() == tag2.get_id()));
 	}
 
+	#[tokio::test]
+	async fn test_list_tags_for_item_handler_include_ancestors() {
+		let pool = setup_test_db();
+
+		let item_type =
+			repo::create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+				.await
+				.unwrap();
+		let item = repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			"Test Item".to_string(),
+			json!({"front": "Hello", "back": "World"}),
+		)
+		.await
+		.unwrap();
+
+		let language = repo::create_tag(&pool, "Language".to_string(), true)
+			.await
+			.unwrap();
+		let spanish = repo::create_tag_with_parent(
+			&pool,
+			"Spanish".to_string(),
+			true,
+			Some(language.get_id()),
+		)
+		.await
+		.unwrap();
+		repo::add_tag_to_item(&pool, &spanish.get_id(), &item.get_id())
+			.await
+			.unwrap();
+
+		// Without the flag only the tag on the item is listed
+		let tags = list_tags_for_item_handler(
+			State(pool.clone()),
+			Path(item.get_id()),
+			Query(ListItemTagsQueryDto::default()),
+		)
+		.await
+		.unwrap()
+		.0;
+		assert_eq!(tags.len(), 1);
+
+		// With it, its parent comes too
+		let tags = list_tags_for_item_handler(
+			State(pool.clone()),
+			Path(item.get_id()),
+			Query(ListItemTagsQueryDto {
+				include_ancestors: true,
+			}),
+		)
+		.await
+		.unwrap()
+		.0;
+		assert_eq!(tags.len(), 2);
+		assert!(tags.iter().any(|t| t.get_id() == language.get_id()));
+		assert!(tags.iter().any(|t| t.get_id() == spanish.get_id()));
+	}
+
 	#[tokio::test]
 	async fn test_list_tags_for_item_handler_not_found() {
 		let pool = setup_test_db();
@@ -458,6 +687,7 @@ mod tests {
 		let result = list_tags_for_item_handler(
 			State(pool.clone()),
 			Path(ItemId("nonexistent".to_string())),
+			Query(ListItemTagsQueryDto::default()),
 		)
 		.await;
 
@@ -466,7 +696,7 @@ mod tests {
 
 		let err = result.unwrap_err();
 		assert!(
-			matches!(err, ApiError::NotFound),
+			matches!(err, ApiError::NotFound(Resource::Item)),
 			"Expected NotFound error, got {:?}",
 			err
 		);
@@ -535,6 +765,93 @@ mod tests {
 
 		// Check that we got a NotFound error
 		assert!(result.is_err());
-		assert!(matches!(result.unwrap_err(), ApiError::NotFound));
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Card)
+		));
+	}
+
+	#[tokio::test]
+	async fn test_get_tag_stats_handler() {
+		let pool = setup_test_db();
+
+		let item_type = repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+		let item = repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			"Test Item".to_string(),
+			json!({"front": "Hello", "back": "World"}),
+		)
+		.await
+		.unwrap();
+		let tag = repo::create_tag(&pool, "Important".to_string(), true)
+			.await
+			.unwrap();
+		repo::add_tag_to_item(&pool, &tag.get_id(), &item.get_id())
+			.await
+			.unwrap();
+
+		let result = get_tag_stats_handler(State(pool.clone()), Path(tag.get_id()))
+			.await
+			.unwrap();
+
+		assert_eq!(
+			result.0,
+			TagStats {
+				item_count: 1,
+				card_count: 1,
+				due_count: 1,
+			}
+		);
+	}
+
+	#[tokio::test]
+	async fn test_get_tag_stats_handler_not_found() {
+		let pool = setup_test_db();
+
+		let result =
+			get_tag_stats_handler(State(pool.clone()), Path(TagId("nonexistent".to_string())))
+				.await;
+
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Tag)
+		));
+	}
+
+	#[tokio::test]
+	async fn test_get_tag_subtree_handler() {
+		let pool = setup_test_db();
+		let language = repo::create_tag(&pool, "Language".to_string(), true)
+			.await
+			.unwrap();
+		let spanish = repo::create_tag_with_parent(
+			&pool,
+			"Spanish".to_string(),
+			true,
+			Some(language.get_id()),
+		)
+		.await
+		.unwrap();
+		repo::create_tag(&pool, "Unrelated".to_string(), true)
+			.await
+			.unwrap();
+
+		let subtree = get_tag_subtree_handler(State(pool.clone()), Path(language.get_id()))
+			.await
+			.unwrap()
+			.0;
+		let ids: Vec<TagId> = subtree.iter().map(|t| t.get_id()).collect();
+		assert_eq!(ids, vec![language.get_id(), spanish.get_id()]);
+
+		let result =
+			get_tag_subtree_handler(State(pool.clone()), Path(TagId("nonexistent".to_string())))
+				.await;
+		assert!(matches!(
+			result.unwrap_err(),
+			ApiError::NotFound(Resource::Tag)
+		));
 	}
 }
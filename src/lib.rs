@@ -22,39 +22,74 @@
 /// The library exposes a RESTful API using Axum with the following endpoints:
 ///
 /// Routes for item types:
-/// - GET /item_types: List all item types (handlers::list_item_types_handler)
+/// - GET /item_types: List all item types, optionally with item and card counts (handlers::list_item_types_handler)
 /// - POST /item_types: Create a new item type (handlers::create_item_type_handler)
 /// - GET /item_types/{id}: Get a specific item type (handlers::get_item_type_handler)
-/// - PATCH /item_types/{id}: Update the review function for an item type (handlers::update_item_type_handler)
+/// - PATCH /item_types/{id}: Update the review function, item data schema or default priority for an item type (handlers::update_item_type_handler)
+/// - DELETE /item_types/{id}: Delete an item type, refusing while it has items unless `cascade=true` (handlers::delete_item_type_handler)
 /// - GET /item_types/{id}/items: List all items of a specific type (handlers::list_items_by_item_type_handler)
+/// - GET /item_types/{id}/cards: List all cards of a specific type, with the usual card filters (handlers::list_cards_by_item_type_handler)
 ///
 /// Routes for items:
-/// - GET /items: List all items (handlers::list_items_handler)
-/// - POST /items: Create a new item (handlers::create_item_handler)
+/// - GET /items: List all items, optionally with their item type names (handlers::list_items_handler)
+/// - POST /items: Create a new item, idempotently if given an id (handlers::create_item_handler)
+/// - POST /items/batch: Create several items, with their cards, in one transaction (handlers::create_items_batch_handler)
+/// - POST /items/preview_cards: Count the cards an item would get, without creating it (handlers::preview_cards_handler)
 /// - GET /items/{id}: Get a specific item (handlers::get_item_handler)
 /// - PATCH /items/{id}: Update an item (handlers::update_item_handler)
 /// - DELETE /items/{id}: Delete an item (handlers::delete_item_handler)
 /// - GET /items/{id}/cards: List all cards for an item (handlers::list_cards_by_item_handler)
 /// - POST /items/{id}/cards: Create a new card for an item (handlers::create_card_handler)
+/// - POST /items/{item_id}/regenerate_cards: Create the cards an item's type and data call for but it lacks, optionally removing extras (handlers::regenerate_cards_handler)
+/// - POST /items/{item_id}/duplicate: Copy an item, with fresh cards and the same tags (handlers::duplicate_item_handler)
+/// - PUT /items/{item_id}/type: Move an item to a different item type, reconciling its cards (handlers::change_item_type_handler)
+/// - GET /items/{item_id}/full: Get an item together with its cards and tags (handlers::get_item_full_handler)
+/// - GET /items/{item_id}/reviews: List the reviews of all an item's cards, newest first (handlers::list_reviews_by_item_handler)
 /// - GET /items/{item_id}/tags: List all tags for an item (handlers::list_tags_for_item_handler)
 /// - POST /items/{item_id}/tags/{tag_id}: Add a tag to an item (handlers::add_tag_to_item_handler)
 /// - DELETE /items/{item_id}/tags/{tag_id}: Remove a tag from an item (handlers::remove_tag_from_item_handler)
 ///
 /// Routes for cards:
 /// - GET /cards: List all cards (handlers::list_cards_handler)
+/// - GET /cards/next: Get the next due card to review, or 204 if none (handlers::next_card_handler)
+/// - GET /cards/random: Get a random due card to review, or 204 if none (handlers::random_card_handler)
+/// - GET /cards/stream: Stream the cards matching the same filters as GET /cards as newline-delimited JSON (handlers::stream_cards_handler)
+/// - GET /cards/leeches: List cards with many failed reviews, optionally suspending them (handlers::list_leeches_handler)
+/// - GET /cards/hardest: List the cards with the highest share of failed reviews (handlers::list_hardest_cards_handler)
+/// - GET /cards/forecast: Count the cards overdue and coming due on each of the next days (handlers::forecast_handler)
+/// - POST /cards/unbury_all: Return every buried card to the queue, giving the number unburied (handlers::unbury_all_handler)
+/// - POST /cards/suspend: Suspend or unsuspend every card matching the query filters, giving the number changed (handlers::suspend_cards_handler)
+/// - POST /cards/batch_get: Get several cards by ID in one request, with null for unknown IDs (handlers::batch_get_cards_handler)
+/// - POST /cards/swap: Swap two cards' places in the queue by exchanging their sort positions (handlers::swap_cards_handler)
 /// - GET /cards/{id}: Get a specific card (handlers::get_card_handler)
+/// - GET /cards/{card_id}/context: Get a card together with its item and item type (handlers::get_card_context_handler)
 /// - GET /cards/{card_id}/reviews: List all reviews for a card (handlers::list_reviews_by_card_handler)
 /// - PATCH /cards/{card_id}/priority: Update the priority of a card (handlers::update_card_priority_handler)
 /// - GET /cards/{card_id}/tags: List all tags for a card (handlers::list_tags_for_card_handler)
+/// - GET /cards/{card_id}/related: List the cards of the items sharing the most tags with a card's item (handlers::list_related_cards_handler)
 /// - PATCH /cards/{card_id}/suspend: Suspend a card (handlers::suspend_card_handler)
+/// - PUT /cards/{card_id}/note: Set or clear a card's free-text note (handlers::set_card_note_handler)
+/// - PUT /cards/{card_id}/difficulty: Set or unset how hard the user finds a card (handlers::set_card_difficulty_handler)
+/// - POST /cards/{card_id}/bury: Hide a card from lists and the queue until a given time, by default the start of the next local day (handlers::bury_card_handler)
 /// - GET /cards/{card_id}/next_reviews: Get all possible next reviews for a card (handlers::get_all_next_reviews_for_card_handler)
+/// - GET /cards/{card_id}/preview: Preview the next review date for each rating (handlers::preview_reviews_handler)
 ///
 /// Routes for reviews:
-/// - POST /reviews: Create a new review (handlers::create_review_handler)
+/// - GET /reviews: List reviews, filtered by date range, rating and card, newest first (handlers::list_reviews_handler)
+/// - POST /reviews: Create a new review, optionally within a session (handlers::create_review_handler)
+/// - POST /reviews/{review_id}/undo: Undo a review and reschedule its card (handlers::undo_review_handler)
+/// - POST /reviews/import: Import a log of past reviews, rescheduling each card from its full history (handlers::import_reviews_handler)
+///
+/// Routes for sessions:
+/// - POST /sessions: Start a review session (handlers::start_session_handler)
+/// - POST /sessions/{session_id}/end: End a review session, returning its duration and card count (handlers::end_session_handler)
 ///
 /// Routes for tags:
 /// - GET /tags: List all tags (handlers::list_tags_handler)
 /// - POST /tags: Create a new tag (handlers::create_tag_handler)
+/// - POST /tags/{source_id}/merge/{target_id}: Merge one tag into another, returning the surviving tag (handlers::merge_tags_handler)
+/// - GET /tags/{tag_id}/stats: Count the items and cards with a tag, and how many are due (handlers::get_tag_stats_handler)
+/// - GET /tags/{tag_id}/subtree: Get a tag and every tag beneath it (handlers::get_tag_subtree_handler)
 ///
 /// Routes for item relations:
 /// - GET /item_relations: List item relations with optional filters (handlers::list_item_relations_handler)
@@ -62,6 +97,29 @@
 /// - DELETE /item_relations/{parent_id}/{child_id}: Delete an item relation (handlers::delete_item_relation_handler)
 /// - GET /items/{item_id}/children_graph: Get the children graph of an item (handlers::get_children_graph_handler)
 /// - GET /items/{item_id}/parent_graph: Get the parent graph of an item (handlers::get_parent_graph_handler)
+///
+/// Routes for stats:
+/// - GET /stats/overview: Count the new, learning, young and mature cards, with their average difficulty (handlers::get_stats_overview_handler)
+///
+/// Routes for events:
+/// - GET /events/due: Server-sent events with the due count and newly due card IDs, checked every `AppOptions::due_events_interval` (handlers::due_events_handler)
+///
+/// Request bodies larger than `AppOptions::max_body_size` (or, for POST
+/// /reviews/import, `AppOptions::max_import_body_size`) get a 413 Payload Too
+/// Large.
+///
+/// GET /items, GET /items/{id}, GET /cards and GET /cards/{id} send a weak
+/// `ETag`, and answer a request whose `If-None-Match` matches it with an empty
+/// 304 Not Modified (etag::etag).
+///
+/// Routes for monitoring (only when `AppOptions::metrics` is set):
+/// - GET /metrics: Request, review and connection pool metrics in the Prometheus text format (handlers::metrics_handler)
+///
+/// Routes for maintenance (only when `AppOptions::admin` is set):
+/// - POST /admin/vacuum: Run `VACUUM` and `ANALYZE`, giving the space freed (handlers::vacuum_handler)
+/// - GET /admin/migrations: List the applied migrations and any pending ones (handlers::migration_status_handler)
+/// - GET /admin/backups: List recent periodic backup attempts and whether they succeeded (handlers::list_backups_handler)
+/// - POST /admin/backfill-lapses: Recount every card's lapses from its review history (handlers::backfill_lapses_handler)
 
 /// Database connection module
 pub mod db;
@@ -86,23 +144,101 @@ pub mod dto;
 
 pub mod config;
 
+/// Reloading settings from the config file while the server runs
+pub mod config_watcher;
+
 /// Card event registry for card fetched event functions
 pub mod card_event_registry;
 
 /// Time helpers (precision-matched to SQLite's `strftime('...%f', 'now')`)
 pub mod time_utils;
 
+/// Per-request tracing for the HTTP layer
+pub mod request_tracing;
+
+/// Prometheus metrics served from `GET /metrics`
+pub mod metrics;
+
+/// Weak ETags and `304 Not Modified` for GET responses
+pub mod etag;
+
 use axum::{
 	Router,
-	routing::{delete, get, patch, post},
+	extract::DefaultBodyLimit,
+	http::{HeaderValue, Method},
+	middleware,
+	routing::{delete, get, patch, post, put},
 };
 use std::{sync::Arc, time::Duration};
+use tower_http::compression::CompressionLayer;
+use tower_http::compression::predicate::{NotForContentType, Predicate, SizeAbove};
 use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tracing::{Level, warn};
 
 pub use dto::*;
 pub use errors::ApiError;
 
-/// Creates the application router with all routes
+use config::AllowedOrigins;
+
+/// Options controlling how the application router is built
+///
+/// These are the HTTP-level settings from [`config::Config`] that
+/// [`create_app_with_options`] needs. [`AppOptions::default`] matches what
+/// [`create_app`] uses, which is what the tests run against.
+#[derive(Debug, Clone)]
+pub struct AppOptions {
+	/// Origins allowed to make cross-origin requests
+	pub allowed_origins: AllowedOrigins,
+	/// Level at which each HTTP request's span and completion event are logged
+	pub request_log_level: Level,
+	/// Whether to count requests and serve them from `GET /metrics`
+	pub metrics: bool,
+	/// Whether to serve the database maintenance routes under `/admin`
+	pub admin: bool,
+	/// Size in bytes a response must reach to be gzipped (for clients that
+	/// accept it), or None to never compress responses
+	pub compression_min_size: Option<u16>,
+	/// How often `GET /events/due` checks for newly due cards
+	pub due_events_interval: Duration,
+	/// Largest request body accepted, in bytes
+	pub max_body_size: usize,
+	/// Largest request body `POST /reviews/import` accepts, in bytes
+	pub max_import_body_size: usize,
+}
+
+impl Default for AppOptions {
+	fn default() -> Self {
+		AppOptions {
+			allowed_origins: AllowedOrigins::default(),
+			request_log_level: config::DEFAULT_REQUEST_LOG_LEVEL,
+			metrics: false,
+			admin: false,
+			compression_min_size: Some(config::DEFAULT_COMPRESSION_MIN_SIZE),
+			due_events_interval: Duration::from_secs(config::DEFAULT_DUE_EVENTS_INTERVAL_SECS),
+			max_body_size: config::DEFAULT_MAX_BODY_SIZE,
+			max_import_body_size: config::DEFAULT_MAX_IMPORT_BODY_SIZE,
+		}
+	}
+}
+
+impl From<&config::Config> for AppOptions {
+	fn from(config: &config::Config) -> Self {
+		AppOptions {
+			allowed_origins: config.allowed_origins(),
+			request_log_level: config
+				.request_log_level()
+				.unwrap_or(config::DEFAULT_REQUEST_LOG_LEVEL),
+			metrics: config.metrics,
+			admin: config.admin,
+			compression_min_size: config.compression.then_some(config.compression_min_size),
+			due_events_interval: Duration::from_secs(config.due_events_interval_secs),
+			max_body_size: config.max_body_size,
+			max_import_body_size: config.max_import_body_size,
+		}
+	}
+}
+
+/// Creates the application router with all routes and default options
 ///
 /// This function sets up the Axum router with all the API endpoints.
 ///
@@ -114,23 +250,95 @@ pub use errors::ApiError;
 ///
 /// An Axum Router configured with all routes and the database pool as state
 pub fn create_app(pool: Arc<db::DbPool>) -> Router {
-	// Configure CORS to allow requests from any localhost origin
-	let cors = CorsLayer::new()
-		// Allow all methods
-		.allow_methods(Any)
-		// Allow any localhost origin with any port
-		.allow_origin(AllowOrigin::predicate(|origin, _request_parts| {
-			let origin = origin.as_bytes();
-			// Allow if origin starts with http://localhost: or http://127.0.0.1:
-			origin.starts_with(b"http://localhost:") || origin.starts_with(b"http://127.0.0.1:")
-		}))
-		// Allow all headers
-		.allow_headers(Any)
-		// Disallow credentials for now
-		// TODO: authentication
-		.allow_credentials(false);
-
-	Router::new()
+	create_app_with_options(pool, AppOptions::default())
+}
+
+/// Builds the CORS layer for the given set of allowed origins
+///
+/// Returns `None` for [`AllowedOrigins::SameOrigin`], in which case no CORS
+/// headers are sent and browsers will refuse cross-origin requests. Otherwise
+/// the layer answers preflight `OPTIONS` requests for every method the API
+/// uses. Origins that aren't valid header values are skipped with a warning.
+///
+/// ### Arguments
+///
+/// * `allowed_origins` - The origins allowed to make cross-origin requests
+///
+/// ### Returns
+///
+/// The CORS layer to apply, if any
+fn cors_layer(allowed_origins: &AllowedOrigins) -> Option<CorsLayer> {
+	let allow_origin = match allowed_origins {
+		AllowedOrigins::SameOrigin => return None,
+		AllowedOrigins::Any => AllowOrigin::any(),
+		AllowedOrigins::List(origins) => AllowOrigin::list(origins.iter().filter_map(|origin| {
+			HeaderValue::from_str(origin)
+				.inspect_err(|e| warn!("Ignoring invalid CORS origin {:?}: {}", origin, e))
+				.ok()
+		})),
+	};
+
+	Some(
+		CorsLayer::new()
+			.allow_methods([
+				Method::GET,
+				Method::POST,
+				Method::PUT,
+				Method::PATCH,
+				Method::DELETE,
+				Method::OPTIONS,
+			])
+			.allow_origin(allow_origin)
+			// Allow all headers
+			.allow_headers(Any)
+			// Disallow credentials for now
+			// TODO: authentication
+			.allow_credentials(false),
+	)
+}
+
+/// Builds the layer that gzips responses for clients sending
+/// `Accept-Encoding: gzip`
+///
+/// Responses smaller than `min_size` aren't worth compressing, and images
+/// and streamed responses (gRPC and server-sent events) are left alone, as
+/// tower-http's default predicate does. So is the newline-delimited JSON of
+/// `GET /cards/stream`, which gzip would hold back until a whole block had
+/// built up rather than sending each card as it's read.
+///
+/// ### Arguments
+///
+/// * `min_size` - Size in bytes a response must reach to be compressed
+///
+/// ### Returns
+///
+/// The compression layer to apply
+fn compression_layer(min_size: u16) -> CompressionLayer<impl Predicate> {
+	CompressionLayer::new().compress_when(
+		SizeAbove::new(min_size)
+			.and(NotForContentType::GRPC)
+			.and(NotForContentType::IMAGES)
+			.and(NotForContentType::SSE)
+			.and(NotForContentType::const_new("application/x-ndjson")),
+	)
+}
+
+/// Creates the application router with all routes
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool to be shared with all handlers
+/// * `options` - HTTP-level options such as the allowed CORS origins, the
+///   request log level, whether metrics and the admin routes are served,
+///   when responses are compressed, how often due card events are sent and
+///   how large request bodies may be
+///
+/// ### Returns
+///
+/// An Axum Router configured with all routes and the database pool as state
+pub fn create_app_with_options(pool: Arc<db::DbPool>, options: AppOptions) -> Router {
+	let due_events_interval = options.due_events_interval;
+	let router = Router::new()
 		// Routes for item types
 		.route(
 			"/item_types",
@@ -138,12 +346,18 @@ pub fn create_app(pool: Arc<db::DbPool>) -> Router {
 		)
 		.route(
 			"/item_types/{item_type_id}",
-			get(handlers::get_item_type_handler).patch(handlers::update_item_type_handler),
+			get(handlers::get_item_type_handler)
+				.patch(handlers::update_item_type_handler)
+				.delete(handlers::delete_item_type_handler),
 		)
 		.route(
 			"/item_types/{item_type_id}/items",
 			get(handlers::list_items_by_item_type_handler),
 		)
+		.route(
+			"/item_types/{item_type_id}/cards",
+			get(handlers::list_cards_by_item_type_handler),
+		)
 		.route(
 			"/item_types/{item_type_id}/card_fetched_events",
 			post(handlers::create_card_fetched_event_handler)
@@ -156,11 +370,19 @@ pub fn create_app(pool: Arc<db::DbPool>) -> Router {
 		// Routes for items
 		.route(
 			"/items",
-			post(handlers::create_item_handler).get(handlers::list_items_handler),
+			get(handlers::list_items_handler)
+				.layer(middleware::from_fn(etag::etag))
+				.post(handlers::create_item_handler),
+		)
+		.route("/items/batch", post(handlers::create_items_batch_handler))
+		.route(
+			"/items/preview_cards",
+			post(handlers::preview_cards_handler),
 		)
 		.route(
 			"/items/{item_id}",
 			get(handlers::get_item_handler)
+				.layer(middleware::from_fn(etag::etag))
 				.delete(handlers::delete_item_handler)
 				.patch(handlers::update_item_handler),
 		)
@@ -168,21 +390,61 @@ pub fn create_app(pool: Arc<db::DbPool>) -> Router {
 			"/items/{item_id}/cards",
 			post(handlers::create_card_handler).get(handlers::list_cards_by_item_handler),
 		)
+		.route(
+			"/items/{item_id}/regenerate_cards",
+			post(handlers::regenerate_cards_handler),
+		)
+		.route(
+			"/items/{item_id}/duplicate",
+			post(handlers::duplicate_item_handler),
+		)
+		.route(
+			"/items/{item_id}/type",
+			put(handlers::change_item_type_handler),
+		)
 		.route(
 			"/items/{item_id}/tags",
 			get(handlers::list_tags_for_item_handler),
 		)
+		.route(
+			"/items/{item_id}/full",
+			get(handlers::get_item_full_handler),
+		)
+		.route(
+			"/items/{item_id}/reviews",
+			get(handlers::list_reviews_by_item_handler),
+		)
 		.route(
 			"/items/{item_id}/tags/{tag_id}",
 			post(handlers::add_tag_to_item_handler).delete(handlers::remove_tag_from_item_handler),
 		)
 		// Routes for cards
-		.route("/cards", get(handlers::list_cards_handler))
+		.route(
+			"/cards",
+			get(handlers::list_cards_handler).layer(middleware::from_fn(etag::etag)),
+		)
+		.route("/cards/next", get(handlers::next_card_handler))
+		.route("/cards/random", get(handlers::random_card_handler))
+		.route("/cards/stream", get(handlers::stream_cards_handler))
+		.route("/cards/leeches", get(handlers::list_leeches_handler))
+		.route("/cards/hardest", get(handlers::list_hardest_cards_handler))
+		.route("/cards/forecast", get(handlers::forecast_handler))
+		.route("/cards/unbury_all", post(handlers::unbury_all_handler))
+		.route("/cards/suspend", post(handlers::suspend_cards_handler))
+		.route("/cards/batch_get", post(handlers::batch_get_cards_handler))
+		.route("/cards/swap", post(handlers::swap_cards_handler))
 		.route(
 			"/cards/sort_positions",
 			delete(handlers::clear_sort_positions_handler),
 		)
-		.route("/cards/{card_id}", get(handlers::get_card_handler))
+		.route(
+			"/cards/{card_id}",
+			get(handlers::get_card_handler).layer(middleware::from_fn(etag::etag)),
+		)
+		.route(
+			"/cards/{card_id}/context",
+			get(handlers::get_card_context_handler),
+		)
 		.route(
 			"/cards/{card_id}/reviews",
 			get(handlers::list_reviews_by_card_handler),
@@ -200,21 +462,66 @@ pub fn create_app(pool: Arc<db::DbPool>) -> Router {
 			"/cards/{card_id}/tags",
 			get(handlers::list_tags_for_card_handler),
 		)
+		.route(
+			"/cards/{card_id}/related",
+			get(handlers::list_related_cards_handler),
+		)
 		.route(
 			"/cards/{card_id}/suspend",
 			patch(handlers::suspend_card_handler),
 		)
+		.route(
+			"/cards/{card_id}/note",
+			put(handlers::set_card_note_handler),
+		)
+		.route(
+			"/cards/{card_id}/difficulty",
+			put(handlers::set_card_difficulty_handler),
+		)
+		.route("/cards/{card_id}/bury", post(handlers::bury_card_handler))
 		.route(
 			"/cards/{card_id}/next_reviews",
 			get(handlers::get_all_next_reviews_for_card_handler),
 		)
+		.route(
+			"/cards/{card_id}/preview",
+			get(handlers::preview_reviews_handler),
+		)
 		// Routes for reviews
-		.route("/reviews", post(handlers::create_review_handler))
+		.route(
+			"/reviews",
+			post(handlers::create_review_handler).get(handlers::list_reviews_handler),
+		)
+		// Imports can be far larger than any other request
+		.route(
+			"/reviews/import",
+			post(handlers::import_reviews_handler)
+				.layer(DefaultBodyLimit::max(options.max_import_body_size)),
+		)
+		.route(
+			"/reviews/{review_id}/undo",
+			post(handlers::undo_review_handler),
+		)
+		// Routes for sessions
+		.route("/sessions", post(handlers::start_session_handler))
+		.route(
+			"/sessions/{session_id}/end",
+			post(handlers::end_session_handler),
+		)
 		// Routes for tags
 		.route(
 			"/tags",
 			post(handlers::create_tag_handler).get(handlers::list_tags_handler),
 		)
+		.route(
+			"/tags/{source_id}/merge/{target_id}",
+			post(handlers::merge_tags_handler),
+		)
+		.route("/tags/{tag_id}/stats", get(handlers::get_tag_stats_handler))
+		.route(
+			"/tags/{tag_id}/subtree",
+			get(handlers::get_tag_subtree_handler),
+		)
 		// Routes for item relations
 		.route(
 			"/item_relations",
@@ -233,15 +540,70 @@ pub fn create_app(pool: Arc<db::DbPool>) -> Router {
 			"/items/{item_id}/parent_graph",
 			get(handlers::get_parent_graph_handler),
 		)
-		// Apply CORS middleware to all routes
-		.layer(cors)
-		// Add the database pool to the application state
-		.with_state(pool)
+		// Routes for stats
+		.route("/stats/overview", get(handlers::get_stats_overview_handler))
+		// Routes for events
+		.route(
+			"/events/due",
+			get(move |state| handlers::due_events_handler(state, due_events_interval)),
+		);
+
+	// Serve the maintenance routes only if asked to, as there's no auth yet
+	let router = if options.admin {
+		router
+			.route("/admin/vacuum", post(handlers::vacuum_handler))
+			.route("/admin/migrations", get(handlers::migration_status_handler))
+			.route("/admin/backups", get(handlers::list_backups_handler))
+			.route(
+				"/admin/backfill-lapses",
+				post(handlers::backfill_lapses_handler),
+			)
+	} else {
+		router
+	};
+
+	// Count every request, and serve the counts, if metrics are on
+	let router = if options.metrics {
+		router
+			.route("/metrics", get(handlers::metrics_handler))
+			.layer(middleware::from_fn(metrics::track_requests))
+	} else {
+		router
+	};
+
+	// Refuse oversized request bodies with a 413, rather than buffering them.
+	// Route layers run inside this one, so the import's own limit wins.
+	let router = router.layer(DefaultBodyLimit::max(options.max_body_size));
+
+	// Gzip large responses, unless compression is off
+	let router = match options.compression_min_size {
+		Some(min_size) => router.layer(compression_layer(min_size)),
+		None => router,
+	};
+
+	// Apply CORS middleware to all routes, unless we're same-origin only
+	let router = match cors_layer(&options.allowed_origins) {
+		Some(cors) => router.layer(cors),
+		None => router,
+	};
+
+	// Wrap every request in a span. This is the outermost layer, so the
+	// handlers' `#[instrument]` spans (and CORS preflights) nest beneath it.
+	let router = router.layer(request_tracing::trace_layer(options.request_log_level));
+
+	// Add the database pool to the application state
+	router.with_state(pool)
 }
 
+/// The database migrations, embedded from the `migrations` directory at
+/// compile time
+pub(crate) const MIGRATIONS: diesel_migrations::EmbeddedMigrations =
+	diesel_migrations::embed_migrations!("migrations");
+
 /// Runs the embedded migrations
 ///
-/// This function applies all database migrations to set up the schema. Note that this is currently only used in tests.
+/// This function applies all database migrations to set up the schema. Tests
+/// use it to set up their databases; the server uses [`migrate_database`].
 ///
 /// ### Arguments
 ///
@@ -251,16 +613,40 @@ pub fn create_app(pool: Arc<db::DbPool>) -> Router {
 ///
 /// This function will panic if the migrations fail to run
 pub fn run_migrations(conn: &mut diesel::SqliteConnection) {
-	use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
-
-	// Define the embedded migrations
-	const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+	use diesel_migrations::MigrationHarness;
 
 	// Run all pending migrations
 	conn.run_pending_migrations(MIGRATIONS)
 		.expect("Failed to run migrations");
 }
 
+/// Runs any pending embedded migrations against a database
+///
+/// The server calls this at startup, after the startup backup, so a fresh
+/// database gets its tables and an existing one is brought up to date.
+///
+/// ### Arguments
+///
+/// * `database_url` - The path to the database file, or `:memory:`
+///
+/// ### Returns
+///
+/// A `Result` with the number of migrations applied, or an error message on
+/// failure
+pub fn migrate_database(database_url: &str) -> Result<usize, String> {
+	use diesel::Connection;
+	use diesel_migrations::MigrationHarness;
+
+	let mut conn = diesel::SqliteConnection::establish(database_url)
+		.map_err(|e| format!("Failed to connect to database for migrations: {}", e))?;
+
+	let applied = conn
+		.run_pending_migrations(MIGRATIONS)
+		.map_err(|e| format!("Failed to run migrations: {}", e))?;
+
+	Ok(applied.len())
+}
+
 /// Enum representing the type of backup
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum BackupType {
@@ -268,10 +654,96 @@ pub enum BackupType {
 	Periodic,
 }
 
+impl BackupType {
+	/// Gets the name of the backup type, as used in backup file names and the
+	/// backup log
+	///
+	/// ### Returns
+	///
+	/// `"startup"` or `"periodic"`
+	pub fn as_str(self) -> &'static str {
+		match self {
+			BackupType::Startup => "startup",
+			BackupType::Periodic => "periodic",
+		}
+	}
+}
+
+/// Extension of the sidecar file holding a backup's checksum, appended to
+/// the backup's own file name
+pub const BACKUP_CHECKSUM_EXTENSION: &str = "sha256";
+
+/// Gets the path of the sidecar file holding a backup's checksum
+///
+/// ### Arguments
+///
+/// * `backup_path` - Path to the backup file
+///
+/// ### Returns
+///
+/// The backup's path with `.sha256` appended
+pub fn backup_checksum_path(backup_path: &std::path::Path) -> std::path::PathBuf {
+	let mut path = backup_path.as_os_str().to_owned();
+	path.push(".");
+	path.push(BACKUP_CHECKSUM_EXTENSION);
+	std::path::PathBuf::from(path)
+}
+
+/// Computes the SHA-256 of some bytes, as lowercase hex
+fn sha256_hex(bytes: &[u8]) -> String {
+	ring::digest::digest(&ring::digest::SHA256, bytes)
+		.as_ref()
+		.iter()
+		.map(|byte| format!("{:02x}", byte))
+		.collect()
+}
+
+/// Checks a backup against the checksum stored alongside it
+///
+/// The checksum is read from the backup's `.sha256` sidecar, which holds it
+/// in the same format as `sha256sum`, so a backup can also be checked by
+/// hand with `sha256sum -c`.
+///
+/// ### Arguments
+///
+/// * `backup_path` - Path to the backup file
+///
+/// ### Returns
+///
+/// Ok if the backup's contents match its checksum, or an error message if
+/// they don't, or if the backup or its checksum can't be read
+pub fn verify_backup(backup_path: &std::path::Path) -> Result<(), String> {
+	use std::fs;
+
+	let checksum_path = backup_checksum_path(backup_path);
+	let stored = fs::read_to_string(&checksum_path)
+		.map_err(|e| format!("Failed to read backup checksum {:?}: {}", checksum_path, e))?;
+	let expected = stored
+		.split_whitespace()
+		.next()
+		.ok_or_else(|| format!("Backup checksum {:?} is empty", checksum_path))?;
+
+	let contents =
+		fs::read(backup_path).map_err(|e| format!("Failed to read backup {:?}: {}", backup_path, e))?;
+	let actual = sha256_hex(&contents);
+
+	if !actual.eq_ignore_ascii_case(expected) {
+		return Err(format!(
+			"Backup {:?} doesn't match its checksum: expected {}, got {}",
+			backup_path, expected, actual
+		));
+	}
+
+	Ok(())
+}
+
 /// Backs up the SQLite database file
 ///
 /// This function creates a backup of the database if it's a local file (not an in-memory database).
-/// The backup is stored in a folder called `backups` at the same level as the database file.
+/// The backup is a snapshot taken with `VACUUM INTO`, so it includes commits not yet checkpointed out of the WAL.
+/// The backup is stored in a folder called `backups` at the same level as the database file,
+/// along with a `.sha256` sidecar holding its checksum. The backup is checked against the
+/// checksum once written, see [`verify_backup`].
 ///
 /// ### Arguments
 ///
@@ -296,8 +768,9 @@ pub fn backup_database(
 		return Ok(false);
 	}
 
+	use diesel::connection::SimpleConnection;
+	use diesel::{Connection, RunQueryDsl, SqliteConnection};
 	use std::fs::{self, File};
-	use std::io::{Read, Write};
 	use std::path::{Path, PathBuf};
 	use std::time::{SystemTime, UNIX_EPOCH};
 	use tracing::{debug, info};
@@ -338,32 +811,46 @@ pub fn backup_database(
 	let backup_path = backup_dir.join(format!(
 		"{}.{}.{}.backup",
 		db_filename,
-		match backup_type {
-			BackupType::Startup => "startup",
-			BackupType::Periodic => "periodic",
-		},
+		backup_type.as_str(),
 		timestamp
 	));
 
 	debug!("Creating backup at {:?}", backup_path);
 
-	// TODO: this really should be thread locked so no other operations are happening on the database during backup
-	// but I'm not sure how to do that
-	// so instead we're accepting that some of the 20-minute backups will be corrupted and this is better than nothing
-
-	// Copy the database file to the backup location
-	let mut src =
-		File::open(database_path).map_err(|e| format!("Failed to open database: {}", e))?;
-	let mut dst =
-		File::create(&backup_path).map_err(|e| format!("Failed to create backup file: {}", e))?;
-
-	let mut buffer = Vec::new();
-	src.read_to_end(&mut buffer)
-		.map_err(|e| format!("Failed to read database: {}", e))?;
-	dst.write_all(&buffer)
+	// `VACUUM INTO` writes a consistent snapshot of the database, including
+	// commits still in the WAL file that a copy of the main file would miss.
+	// It won't overwrite an existing file, so clear out a backup taken
+	// earlier in the same second.
+	if backup_path.exists() {
+		fs::remove_file(&backup_path)
+			.map_err(|e| format!("Failed to create backup file: {}", e))?;
+	}
+	let mut conn = SqliteConnection::establish(database_path)
+		.map_err(|e| format!("Failed to open database: {}", e))?;
+	conn.batch_execute(&format!("PRAGMA busy_timeout = {};", db::BUSY_TIMEOUT_MS))
+		.map_err(|e| format!("Failed to open database: {}", e))?;
+	diesel::sql_query("VACUUM INTO ?")
+		.bind::<diesel::sql_types::Text, _>(backup_path.to_string_lossy())
+		.execute(&mut conn)
+		.map_err(|e| format!("Failed to create backup file: {}", e))?;
+	File::open(&backup_path)
+		.and_then(|file| file.sync_all())
 		.map_err(|e| format!("Failed to write backup: {}", e))?;
+	let buffer = fs::read(&backup_path).map_err(|e| format!("Failed to read backup: {}", e))?;
+
+	// Record what the backup should contain, then check that it does
+	let backup_name = backup_path
+		.file_name()
+		.and_then(|n| n.to_str())
+		.unwrap_or_default();
+	fs::write(
+		backup_checksum_path(&backup_path),
+		format!("{}  {}\n", sha256_hex(&buffer), backup_name),
+	)
+	.map_err(|e| format!("Failed to write backup checksum: {}", e))?;
+	verify_backup(&backup_path)?;
 
-	info!("Database backup created at {:?}", backup_path);
+	info!("Database backup created and verified at {:?}", backup_path);
 
 	// If this is a periodic backup, check if we need to clean up old backups
 	if backup_type == BackupType::Periodic {
@@ -393,7 +880,8 @@ fn cleanup_periodic_backups(
 	use std::time::SystemTime;
 	use tracing::{debug, error};
 
-	// Find all periodic backups for this database
+	// Find all periodic backups for this database, leaving out their
+	// checksums, which go with them
 	let pattern = format!("{}.periodic.", db_filename);
 
 	let mut backups = match fs::read_dir(backup_dir) {
@@ -401,7 +889,7 @@ fn cleanup_periodic_backups(
 			.filter_map(Result::ok)
 			.filter(|entry| {
 				if let Some(name) = entry.file_name().to_str() {
-					name.contains(&pattern)
+					name.contains(&pattern) && name.ends_with(".backup")
 				} else {
 					false
 				}
@@ -433,6 +921,11 @@ fn cleanup_periodic_backups(
 			if let Err(e) = fs::remove_file(old_backup.path()) {
 				error!("Failed to remove old backup {:?}: {}", old_backup.path(), e);
 				// Continue with other deletions even if one fails
+				continue;
+			}
+			let checksum_path = backup_checksum_path(&old_backup.path());
+			if let Err(e) = fs::remove_file(&checksum_path) {
+				error!("Failed to remove old backup checksum {:?}: {}", checksum_path, e);
 			}
 		}
 	}
@@ -440,27 +933,83 @@ fn cleanup_periodic_backups(
 	Ok(())
 }
 
+/// Takes one periodic backup of the database, recording the attempt in the
+/// backup log
+///
+/// A failed backup is logged and recorded, but not otherwise acted on, so
+/// `GET /admin/backups` is where one that keeps failing shows up. A backup
+/// that wasn't needed (such as of a PostgreSQL database) isn't recorded.
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool, for the backup log
+/// * `database_path` - The path to the database file
+/// * `backup_count` - The maximum number of periodic backups to keep
+///
+/// ### Returns
+///
+/// The result of the backup, as from [`backup_database`]
+pub async fn run_periodic_backup(
+	pool: &db::DbPool,
+	database_path: &str,
+	backup_count: u32,
+) -> Result<bool, String> {
+	use tracing::{error, info};
+
+	info!("Performing periodic database backup");
+
+	let result = backup_database(database_path, BackupType::Periodic, backup_count);
+	let error = match &result {
+		Ok(true) => {
+			info!("Periodic backup completed successfully");
+			None
+		}
+		Ok(false) => {
+			info!("Periodic backup not needed.");
+			return result;
+		}
+		Err(e) => {
+			error!("Periodic backup failed: {}", e);
+			Some(e.clone())
+		}
+	};
+
+	if let Err(e) = repo::record_backup_attempt(pool, BackupType::Periodic, error).await {
+		error!("Failed to record periodic backup in the backup log: {}", e);
+	}
+
+	result
+}
+
 /// Starts a background task to periodically back up the database
 ///
-/// This function spawns a background task that backs up the database every 20 minutes.
+/// This function spawns a background task that backs up the database every
+/// `backup_duration`, recording each attempt in the backup log.
 ///
 /// ### Arguments
 ///
+/// * `pool` - The database connection pool, for the backup log
 /// * `database_path` - The path to the database file
 /// * `backup_duration` - The duration between backups
 /// * `backup_count` - The maximum number of backups to keep
 ///
 /// ### Notes
 ///
-/// This should only be called once at application startup.
-pub fn start_periodic_backup(database_path: String, backup_duration: Duration, backup_count: u32) {
+/// This should only be called once at application startup, after the
+/// database has been migrated.
+pub fn start_periodic_backup(
+	pool: Arc<db::DbPool>,
+	database_path: String,
+	backup_duration: Duration,
+	backup_count: u32,
+) {
 	// Skip for in-memory databases
 	if database_path == ":memory:" {
 		return;
 	}
 
 	use tokio::time;
-	use tracing::{error, info};
+	use tracing::info;
 
 	// Clone the database path for the async task
 	let db_path = database_path.clone();
@@ -478,24 +1027,200 @@ pub fn start_periodic_backup(database_path: String, backup_duration: Duration, b
 			// Wait for the next interval tick
 			interval.tick().await;
 
-			// Perform the backup
-			info!("Performing periodic database backup");
+			// Perform the backup; failures are logged and recorded
+			let _ = run_periodic_backup(&pool, &db_path, backup_count).await;
+		}
+	});
+}
 
-			match backup_database(&db_path, BackupType::Periodic, backup_count) {
-				Ok(true) => {
-					info!("Periodic backup completed successfully");
-				}
-				Ok(false) => {
-					info!("Periodic backup not needed.");
-				}
-				Err(e) => {
-					error!("Periodic backup failed: {}", e);
-				}
+/// Base name of the server's log files; rotated files get a date suffix,
+/// e.g. `hippocampus.log.2026-03-16`
+pub const LOG_FILE_NAME: &str = "hippocampus.log";
+
+/// How often the log directory is checked for rotated files to delete
+const LOG_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Deletes old rotated log files, keeping only the most recent ones
+///
+/// Rotated files are named after the period they cover
+/// (`hippocampus.log.2026-03-16`, or `hippocampus.log.2026-03-16-09` when
+/// rotating hourly), so sorting by name sorts them oldest to newest, without
+/// relying on modification times.
+///
+/// ### Arguments
+///
+/// * `log_dir` - Path to the log directory
+/// * `log_file_name` - Base filename of the log files
+/// * `log_retention` - The maximum number of rotated log files to keep
+///
+/// ### Returns
+///
+/// A `Result` containing the number of log files deleted, or an error
+/// message if the directory can't be read
+pub fn cleanup_old_logs(
+	log_dir: &std::path::Path,
+	log_file_name: &str,
+	log_retention: u32,
+) -> Result<usize, String> {
+	use std::fs;
+	use tracing::{debug, error};
+
+	let prefix = format!("{}.", log_file_name);
+
+	let mut logs = match fs::read_dir(log_dir) {
+		Ok(entries) => entries
+			.filter_map(Result::ok)
+			.filter(|entry| {
+				entry
+					.file_name()
+					.to_str()
+					.is_some_and(|name| name.starts_with(&prefix))
+			})
+			.map(|entry| entry.path())
+			.collect::<Vec<_>>(),
+		Err(e) => {
+			error!("Failed to read log directory: {}", e);
+			return Err(format!("Failed to read log directory: {}", e));
+		}
+	};
+
+	// Newest first
+	logs.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+
+	let mut deleted = 0;
+	for old_log in logs.iter().skip(log_retention as usize) {
+		debug!("Removing old log file: {:?}", old_log);
+		match fs::remove_file(old_log) {
+			Ok(()) => deleted += 1,
+			// Continue with other deletions even if one fails
+			Err(e) => error!("Failed to remove old log file {:?}: {}", old_log, e),
+		}
+	}
+
+	Ok(deleted)
+}
+
+/// Starts a background task to periodically delete old rotated log files
+///
+/// The directory is checked at startup and then every hour, which keeps up
+/// with both hourly and daily rotation.
+///
+/// ### Arguments
+///
+/// * `log_dir` - Path to the log directory
+/// * `log_retention` - The maximum number of rotated log files to keep
+///
+/// ### Notes
+///
+/// This should only be called once at application startup.
+pub fn start_periodic_log_cleanup(log_dir: std::path::PathBuf, log_retention: u32) {
+	use tokio::time;
+	use tracing::{error, info};
+
+	tokio::spawn(async move {
+		let mut interval = time::interval(LOG_CLEANUP_INTERVAL);
+
+		info!(
+			"Starting periodic log cleanup task (keeping {} log files)",
+			log_retention
+		);
+
+		loop {
+			interval.tick().await;
+
+			match cleanup_old_logs(&log_dir, LOG_FILE_NAME, log_retention) {
+				Ok(0) => {}
+				Ok(deleted) => info!("Deleted {} old log files", deleted),
+				Err(e) => error!("Log cleanup failed: {}", e),
 			}
 		}
 	});
 }
 
+/// The OS signal that asked the server to shut down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownSignal {
+	/// SIGINT, e.g. Ctrl+C in the terminal
+	Interrupt,
+	/// SIGTERM, e.g. `systemctl stop`
+	Terminate,
+}
+
+/// Waits until the process receives SIGINT or SIGTERM
+///
+/// On non-Unix platforms only Ctrl+C is listened for.
+///
+/// ### Returns
+///
+/// The signal that was received
+///
+/// ### Panics
+///
+/// This function will panic if the signal handlers can't be installed
+pub async fn os_shutdown_signal() -> ShutdownSignal {
+	let interrupt = async {
+		tokio::signal::ctrl_c()
+			.await
+			.expect("Failed to install Ctrl+C handler");
+	};
+
+	#[cfg(unix)]
+	let terminate = async {
+		tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+			.expect("Failed to install SIGTERM handler")
+			.recv()
+			.await;
+	};
+
+	#[cfg(not(unix))]
+	let terminate = std::future::pending::<()>();
+
+	tokio::select! {
+		_ = interrupt => ShutdownSignal::Interrupt,
+		_ = terminate => ShutdownSignal::Terminate,
+	}
+}
+
+/// Serves the application until `shutdown` resolves, then drains in-flight requests
+///
+/// The shutdown source is a plain future so tests can drive it directly;
+/// the server passes [`os_shutdown_signal`]. Once it resolves, the listener
+/// stops accepting connections and this function returns after every
+/// in-flight request has completed.
+///
+/// ### Arguments
+///
+/// * `listener` - The TCP listener to accept connections on
+/// * `app` - The application router to serve
+/// * `shutdown` - A future that resolves when the server should shut down
+///
+/// ### Returns
+///
+/// The signal that triggered the shutdown, or an error if the server failed
+/// (in which case the signal may never have fired)
+pub async fn serve_until_shutdown<F>(
+	listener: tokio::net::TcpListener,
+	app: Router,
+	shutdown: F,
+) -> std::io::Result<Option<ShutdownSignal>>
+where
+	F: Future<Output = ShutdownSignal> + Send + 'static,
+{
+	use tracing::info;
+
+	let (signal_tx, signal_rx) = tokio::sync::oneshot::channel();
+
+	axum::serve(listener, app)
+		.with_graceful_shutdown(async move {
+			let signal = shutdown.await;
+			info!("Received {:?}, draining in-flight requests", signal);
+			let _ = signal_tx.send(signal);
+		})
+		.await?;
+
+	Ok(signal_rx.await.ok())
+}
+
 #[cfg(test)]
 pub(crate) mod test_utils;
 
@@ -974,6 +1699,38 @@ mod tests {
 		assert!(result.is_ok());
 	}
 
+	/// Tests the migrate_database function against a fresh database file
+	///
+	/// This test verifies that:
+	/// 1. Every embedded migration is applied to a new database
+	/// 2. The database has its tables afterwards
+	/// 3. Running it again applies nothing
+	#[test]
+	fn test_migrate_database_fresh_file() {
+		let test_db_dir = tempfile::tempdir().expect("Failed to create temp directory");
+		let database_path = test_db_dir.path().join("fresh.db");
+		let database_url = database_path.to_str().unwrap();
+
+		let applied = migrate_database(database_url).unwrap();
+		assert!(applied > 0);
+
+		#[derive(diesel::QueryableByName)]
+		struct TableCount {
+			#[diesel(sql_type = diesel::sql_types::BigInt)]
+			count: i64,
+		}
+
+		let mut conn = SqliteConnection::establish(database_url).unwrap();
+		let tables = diesel::sql_query(
+			"SELECT COUNT(*) AS count FROM sqlite_master WHERE type='table' AND name IN ('items', 'cards', 'reviews')",
+		)
+		.get_result::<TableCount>(&mut conn)
+		.unwrap();
+		assert_eq!(tables.count, 3);
+
+		assert_eq!(migrate_database(database_url).unwrap(), 0);
+	}
+
 	/// Tests the create item type handler
 	///
 	/// This test verifies that:
@@ -1055,6 +1812,56 @@ mod tests {
 		assert_eq!(response_item_type["name"], name);
 	}
 
+	/// Tests that the server shuts down when the shutdown signal fires
+	///
+	/// This test verifies that:
+	/// 1. The server answers requests while the shutdown future is pending
+	/// 2. Resolving the shutdown future makes `serve_until_shutdown` return
+	/// 3. The signal that fired is reported back to the caller
+	/// 4. The listener no longer accepts connections afterwards
+	#[tokio::test]
+	async fn test_serve_until_shutdown_stops_on_signal() {
+		let pool = setup_test_db();
+		let app = create_app(pool);
+
+		let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap();
+
+		let (signal_tx, signal_rx) = tokio::sync::oneshot::channel();
+		let server = tokio::spawn(serve_until_shutdown(listener, app, async move {
+			signal_rx.await.unwrap()
+		}));
+
+		let url = format!("http://{}/item_types", addr);
+		let response = reqwest::get(&url).await.unwrap();
+		assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+		signal_tx.send(ShutdownSignal::Terminate).unwrap();
+
+		let result = tokio::time::timeout(Duration::from_secs(5), server)
+			.await
+			.expect("Server did not shut down after the signal fired")
+			.unwrap()
+			.unwrap();
+		assert_eq!(result, Some(ShutdownSignal::Terminate));
+
+		assert!(
+			tokio::net::TcpStream::connect(addr).await.is_err(),
+			"Server should stop accepting connections after shutdown"
+		);
+	}
+
+	/// Creates a small SQLite database at `path`, for backing up
+	fn create_test_database_file(path: &std::path::Path) {
+		use diesel::connection::SimpleConnection;
+		use diesel::{Connection, SqliteConnection};
+
+		let mut conn = SqliteConnection::establish(path.to_str().unwrap())
+			.expect("Failed to create test database");
+		conn.batch_execute("CREATE TABLE notes (body TEXT); INSERT INTO notes VALUES ('test');")
+			.expect("Failed to write to test database");
+	}
+
 	/// Tests the database backup functionality
 	///
 	/// This test verifies that:
@@ -1063,22 +1870,14 @@ mod tests {
 	/// 3. The backup directory is created if it doesn't exist
 	#[test]
 	fn test_database_backup() {
-		use std::fs::{self, File};
-		use std::io::Write;
+		use std::fs;
 		use std::thread::sleep;
 		use std::time::Duration;
 
 		// Create a temporary test database file
 		let test_db_dir = tempfile::tempdir().expect("Failed to create temp directory");
 		let test_db_path = test_db_dir.path().join("test_backup.db");
-
-		// Create a dummy database file with some content
-		{
-			let mut file =
-				File::create(&test_db_path).expect("Failed to create test database file");
-			file.write_all(b"Test database content")
-				.expect("Failed to write to test database");
-		}
+		create_test_database_file(&test_db_path);
 
 		// Test backup creation
 		let db_path_str = test_db_path.to_str().unwrap();
@@ -1122,7 +1921,7 @@ mod tests {
 				entry
 					.file_name()
 					.to_str()
-					.map(|name| name.contains("periodic"))
+					.map(|name| name.contains("periodic") && name.ends_with(".backup"))
 					.unwrap_or(false)
 			})
 			.collect();
@@ -1140,5 +1939,157 @@ mod tests {
 			5,
 			"Should keep exactly 5 periodic backups"
 		);
+
+		// Each backup kept has its checksum, and removed backups take theirs with them
+		let checksums = fs::read_dir(&backup_dir)
+			.expect("Failed to read backup directory")
+			.filter_map(Result::ok)
+			.filter(|entry| {
+				entry
+					.file_name()
+					.to_str()
+					.map(|name| name.contains("periodic") && name.ends_with(".sha256"))
+					.unwrap_or(false)
+			})
+			.count();
+		assert_eq!(checksums, 5, "Should keep exactly 5 periodic checksums");
+		for backup in &periodic_backups {
+			assert_eq!(super::verify_backup(&backup.path()), Ok(()));
+		}
+	}
+
+	/// Tests that backups are checked against their checksums
+	///
+	/// This test verifies that:
+	/// 1. A fresh backup has a checksum sidecar and verifies
+	/// 2. A backup changed after it was written fails verification
+	/// 3. A backup without its checksum fails verification
+	#[test]
+	fn test_verify_backup() {
+		use std::fs;
+		use std::io::Write;
+
+		let test_db_dir = tempfile::tempdir().expect("Failed to create temp directory");
+		let test_db_path = test_db_dir.path().join("test.db");
+		create_test_database_file(&test_db_path);
+
+		let result = super::backup_database(test_db_path.to_str().unwrap(), BackupType::Startup, 5);
+		assert_eq!(result, Ok(true));
+
+		let backup_path = fs::read_dir(test_db_dir.path().join("backups"))
+			.expect("Failed to read backup directory")
+			.filter_map(Result::ok)
+			.map(|entry| entry.path())
+			.find(|path| path.extension().is_some_and(|ext| ext == "backup"))
+			.expect("A backup should exist");
+		let checksum_path = super::backup_checksum_path(&backup_path);
+		assert!(checksum_path.exists(), "The backup's checksum should exist");
+		assert_eq!(super::verify_backup(&backup_path), Ok(()));
+
+		// Tamper with the backup
+		fs::OpenOptions::new()
+			.append(true)
+			.open(&backup_path)
+			.and_then(|mut file| file.write_all(b"tampered"))
+			.expect("Failed to tamper with backup");
+		let err = super::verify_backup(&backup_path).unwrap_err();
+		assert!(err.contains("doesn't match its checksum"), "{}", err);
+
+		// Lose the checksum
+		fs::remove_file(&checksum_path).expect("Failed to remove checksum");
+		let err = super::verify_backup(&backup_path).unwrap_err();
+		assert!(err.contains("Failed to read backup checksum"), "{}", err);
+	}
+
+	/// Tests that backups include commits still in the WAL file
+	///
+	/// This test verifies that:
+	/// 1. Rows committed through a pooled (WAL mode) connection that hasn't
+	///    checkpointed are in the backup
+	/// 2. The backup verifies against its checksum
+	#[test]
+	fn test_database_backup_includes_wal_commits() {
+		use diesel::connection::SimpleConnection;
+		use diesel::{Connection, RunQueryDsl, SqliteConnection};
+
+		#[derive(diesel::QueryableByName)]
+		struct Count {
+			#[diesel(sql_type = diesel::sql_types::BigInt)]
+			count: i64,
+		}
+
+		let test_db_dir = tempfile::tempdir().expect("Failed to create temp directory");
+		let test_db_path = test_db_dir.path().join("wal.db");
+		let pool = crate::db::init_pool(test_db_path.to_str().unwrap());
+		let mut conn = pool.get().unwrap();
+		conn.batch_execute(
+			"CREATE TABLE notes (body TEXT); INSERT INTO notes VALUES ('one'), ('two');",
+		)
+		.unwrap();
+		assert!(
+			test_db_dir.path().join("wal.db-wal").exists(),
+			"The commits should still be in the WAL file"
+		);
+
+		let result = super::backup_database(test_db_path.to_str().unwrap(), BackupType::Startup, 5);
+		assert_eq!(result, Ok(true));
+
+		let backup_path = std::fs::read_dir(test_db_dir.path().join("backups"))
+			.expect("Failed to read backup directory")
+			.filter_map(Result::ok)
+			.map(|entry| entry.path())
+			.find(|path| path.extension().is_some_and(|ext| ext == "backup"))
+			.expect("A backup should exist");
+		assert_eq!(super::verify_backup(&backup_path), Ok(()));
+
+		let mut backup = SqliteConnection::establish(backup_path.to_str().unwrap()).unwrap();
+		let count: Count = diesel::sql_query("SELECT COUNT(*) AS count FROM notes")
+			.get_result(&mut backup)
+			.unwrap();
+		assert_eq!(count.count, 2);
+	}
+
+	/// Tests that old rotated log files are cleaned up
+	///
+	/// This test verifies that:
+	/// 1. Only the newest `log_retention` rotated log files are kept
+	/// 2. The unrotated log file and unrelated files are left alone
+	/// 3. A second cleanup has nothing left to delete
+	#[test]
+	fn test_cleanup_old_logs() {
+		use std::fs;
+
+		let log_dir = tempfile::tempdir().expect("Failed to create temp directory");
+		let rotated = [
+			"hippocampus.log.2026-03-10",
+			"hippocampus.log.2026-03-12",
+			"hippocampus.log.2026-03-11",
+			"hippocampus.log.2026-03-14",
+			"hippocampus.log.2026-03-13",
+		];
+		for name in rotated.iter().chain(&["hippocampus.log", "notes.txt"]) {
+			fs::write(log_dir.path().join(name), "log line\n").unwrap();
+		}
+
+		let deleted = super::cleanup_old_logs(log_dir.path(), super::LOG_FILE_NAME, 2).unwrap();
+		assert_eq!(deleted, 3);
+
+		let mut remaining: Vec<String> = fs::read_dir(log_dir.path())
+			.unwrap()
+			.map(|entry| entry.unwrap().file_name().into_string().unwrap())
+			.collect();
+		remaining.sort();
+		assert_eq!(
+			remaining,
+			vec![
+				"hippocampus.log",
+				"hippocampus.log.2026-03-13",
+				"hippocampus.log.2026-03-14",
+				"notes.txt",
+			]
+		);
+
+		let deleted = super::cleanup_old_logs(log_dir.path(), super::LOG_FILE_NAME, 2).unwrap();
+		assert_eq!(deleted, 0);
 	}
 }
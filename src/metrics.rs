@@ -0,0 +1,183 @@
+//! Prometheus metrics for scraping by a monitoring server.
+//!
+//! The counters live in a single process-wide [`Metrics`], so the repo layer
+//! can count reviews without the pool having to carry anything extra. HTTP
+//! requests are counted by [`track_requests`], which is only installed (along
+//! with `GET /metrics`) when the `metrics` setting is on.
+
+use crate::db::DbPool;
+use crate::models::Rating;
+use axum::{
+	extract::Request,
+	http::{Method, StatusCode},
+	middleware::Next,
+	response::Response,
+};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+/// The content type of the Prometheus text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Counters for everything `GET /metrics` reports, other than the gauges
+/// read at scrape time
+#[derive(Debug, Default)]
+pub struct Metrics {
+	/// Requests handled, by method and response status
+	http_requests: Mutex<BTreeMap<(String, u16), u64>>,
+	/// Reviews recorded, indexed by rating value - 1
+	reviews_recorded: [AtomicU64; Rating::ALL.len()],
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::default);
+
+/// Returns the process-wide metrics
+pub fn metrics() -> &'static Metrics {
+	&METRICS
+}
+
+impl Metrics {
+	/// Counts a handled HTTP request
+	///
+	/// ### Arguments
+	///
+	/// * `method` - The request's method
+	/// * `status` - The status of the response sent for it
+	pub fn record_http_request(&self, method: &Method, status: StatusCode) {
+		let mut http_requests = self
+			.http_requests
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner());
+		*http_requests
+			.entry((method.to_string(), status.as_u16()))
+			.or_default() += 1;
+	}
+
+	/// Counts a recorded review
+	///
+	/// ### Arguments
+	///
+	/// * `rating` - The rating the review was given
+	pub fn record_review(&self, rating: Rating) {
+		self.reviews_recorded[rating.as_i32() as usize - 1].fetch_add(1, Ordering::Relaxed);
+	}
+
+	/// Gets the number of reviews recorded with a rating
+	///
+	/// ### Arguments
+	///
+	/// * `rating` - The rating to count reviews for
+	///
+	/// ### Returns
+	///
+	/// The number of reviews recorded with `rating` since the process started
+	pub fn reviews_recorded(&self, rating: Rating) -> u64 {
+		self.reviews_recorded[rating.as_i32() as usize - 1].load(Ordering::Relaxed)
+	}
+
+	/// Renders every metric in the Prometheus text exposition format
+	///
+	/// ### Arguments
+	///
+	/// * `pool` - The database connection pool, whose connections in use are
+	///   reported as a gauge
+	///
+	/// ### Returns
+	///
+	/// The metrics, ready to serve with [`CONTENT_TYPE`]
+	pub fn render(&self, pool: &DbPool) -> String {
+		let mut out = String::new();
+
+		// Writing to a String can't fail
+		let _ = writeln!(
+			out,
+			"# HELP http_requests_total HTTP requests handled, by method and status"
+		);
+		let _ = writeln!(out, "# TYPE http_requests_total counter");
+		let http_requests = self
+			.http_requests
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner());
+		for ((method, status), count) in http_requests.iter() {
+			let _ = writeln!(
+				out,
+				"http_requests_total{{method=\"{}\",status=\"{}\"}} {}",
+				method, status, count
+			);
+		}
+		drop(http_requests);
+
+		let _ = writeln!(
+			out,
+			"# HELP reviews_recorded_total Reviews recorded, by rating"
+		);
+		let _ = writeln!(out, "# TYPE reviews_recorded_total counter");
+		for rating in Rating::ALL {
+			let _ = writeln!(
+				out,
+				"reviews_recorded_total{{rating=\"{}\"}} {}",
+				rating,
+				self.reviews_recorded(rating)
+			);
+		}
+
+		let state = pool.state();
+		let _ = writeln!(
+			out,
+			"# HELP db_pool_connections_in_use Database connections currently checked out of the pool"
+		);
+		let _ = writeln!(out, "# TYPE db_pool_connections_in_use gauge");
+		let _ = writeln!(
+			out,
+			"db_pool_connections_in_use {}",
+			state.connections - state.idle_connections
+		);
+
+		out
+	}
+}
+
+/// Middleware counting every request the router handles in
+/// `http_requests_total`
+///
+/// ### Arguments
+///
+/// * `request` - The incoming request
+/// * `next` - The rest of the middleware stack and the handler
+///
+/// ### Returns
+///
+/// The response from `next`, unchanged
+pub async fn track_requests(request: Request, next: Next) -> Response {
+	let method = request.method().clone();
+	let response = next.run(request).await;
+	metrics().record_http_request(&method, response.status());
+	response
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::repo::tests::setup_test_db;
+
+	#[test]
+	fn test_render_includes_every_metric() {
+		let pool = setup_test_db();
+		let metrics = Metrics::default();
+		metrics.record_http_request(&Method::GET, StatusCode::OK);
+		metrics.record_http_request(&Method::GET, StatusCode::OK);
+		metrics.record_http_request(&Method::POST, StatusCode::NOT_FOUND);
+		metrics.record_review(Rating::Good);
+
+		let rendered = metrics.render(&pool);
+
+		assert!(rendered.contains("http_requests_total{method=\"GET\",status=\"200\"} 2\n"));
+		assert!(rendered.contains("http_requests_total{method=\"POST\",status=\"404\"} 1\n"));
+		assert!(rendered.contains("reviews_recorded_total{rating=\"3\"} 1\n"));
+		assert!(rendered.contains("reviews_recorded_total{rating=\"1\"} 0\n"));
+		assert!(rendered.contains("\ndb_pool_connections_in_use "));
+		assert!(rendered.contains("# TYPE db_pool_connections_in_use gauge\n"));
+	}
+}
@@ -0,0 +1,72 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Represents one attempt at a periodic database backup, as kept in the
+/// backup log
+#[derive(Queryable, Selectable, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::backup_log)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct BackupLogEntry {
+	/// Unique identifier for the entry, increasing with each attempt
+	id: i32,
+
+	/// When the backup was attempted
+	attempted_at: NaiveDateTime,
+
+	/// Which kind of backup was attempted (`"startup"` or `"periodic"`)
+	backup_type: String,
+
+	/// Whether the backup was written
+	success: bool,
+
+	/// Why the backup failed (or null if it succeeded)
+	error: Option<String>,
+}
+
+impl BackupLogEntry {
+	/// Gets the entry's ID
+	///
+	/// ### Returns
+	///
+	/// The unique identifier of the entry
+	pub fn get_id(&self) -> i32 {
+		self.id
+	}
+
+	/// Gets when the backup was attempted
+	///
+	/// ### Returns
+	///
+	/// The timestamp of the attempt
+	pub fn get_attempted_at(&self) -> DateTime<Utc> {
+		DateTime::from_naive_utc_and_offset(self.attempted_at, Utc)
+	}
+
+	/// Gets which kind of backup was attempted
+	///
+	/// ### Returns
+	///
+	/// The backup type's name, `"startup"` or `"periodic"`
+	pub fn get_backup_type(&self) -> &str {
+		&self.backup_type
+	}
+
+	/// Gets whether the backup was written
+	///
+	/// ### Returns
+	///
+	/// True if the backup succeeded
+	pub fn get_success(&self) -> bool {
+		self.success
+	}
+
+	/// Gets why the backup failed
+	///
+	/// ### Returns
+	///
+	/// The error message, or None if the backup succeeded
+	pub fn get_error(&self) -> Option<&str> {
+		self.error.as_deref()
+	}
+}
@@ -2,8 +2,8 @@ use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::models::{CardId, ItemId};
-use crate::time_utils::now_ms;
+use crate::models::{CardDirection, CardId, ItemId};
+use crate::time_utils::{format_due_in, now_ms};
 
 use super::JsonValue;
 
@@ -51,6 +51,36 @@ pub struct Card {
 
 	/// When the card_data cache was last recomputed
 	cache_updated_at: Option<NaiveDateTime>,
+
+	/// When this buried card returns to the queue (or null if it isn't buried)
+	buried_until: Option<NaiveDateTime>,
+
+	/// A free-text note attached to the card, such as a mnemonic
+	#[serde(default)]
+	note: Option<String>,
+
+	/// Which way round the card asks its question (or null if it has no
+	/// direction)
+	#[serde(default)]
+	direction: Option<CardDirection>,
+
+	/// Bumped on every change to the card, so an update can check that the
+	/// card hasn't changed since it was read
+	#[serde(default)]
+	version: i32,
+
+	/// When this card was created
+	#[serde(default)]
+	created_at: NaiveDateTime,
+
+	/// How hard the user finds the card, between 0 and 1 (or null if it
+	/// hasn't been set)
+	#[serde(default)]
+	difficulty: Option<f32>,
+
+	/// How many times the card has been rated Again while mature
+	#[serde(default)]
+	lapses: i32,
 }
 
 impl Card {
@@ -86,6 +116,13 @@ impl Card {
 			card_data: None,
 			updated_at: now_ms(),
 			cache_updated_at: None,
+			buried_until: None,
+			note: None,
+			direction: None,
+			version: 0,
+			created_at: now_ms(),
+			difficulty: None,
+			lapses: 0,
 		}
 	}
 
@@ -127,6 +164,13 @@ impl Card {
 			card_data: None,
 			updated_at: now_ms(),
 			cache_updated_at: None,
+			buried_until: None,
+			note: None,
+			direction: None,
+			version: 0,
+			created_at: now_ms(),
+			difficulty: None,
+			lapses: 0,
 		}
 	}
 
@@ -294,6 +338,122 @@ impl Card {
 		self.suspended = suspended.map(|dt| dt.naive_utc());
 	}
 
+	/// Gets when the card returns to the queue if it is buried
+	///
+	/// ### Returns
+	///
+	/// The time the card stops being buried, or None if it was never buried
+	pub fn get_buried_until(&self) -> Option<DateTime<Utc>> {
+		self.buried_until
+			.map(|dt| DateTime::from_naive_utc_and_offset(dt, Utc))
+	}
+
+	/// Gets the card's raw buried-until timestamp
+	///
+	/// ### Returns
+	///
+	/// The raw NaiveDateTime the card stops being buried, or None if it was never buried
+	pub fn get_buried_until_raw(&self) -> Option<NaiveDateTime> {
+		self.buried_until
+	}
+
+	/// Sets when the card returns to the queue
+	///
+	/// ### Arguments
+	///
+	/// * `buried_until` - The time the card stops being buried, or None to unbury it
+	pub fn set_buried_until(&mut self, buried_until: Option<DateTime<Utc>>) {
+		self.buried_until = buried_until.map(|dt| dt.naive_utc());
+	}
+
+	/// Gets the card's note
+	///
+	/// ### Returns
+	///
+	/// The note attached to the card, or None if it has none
+	pub fn get_note(&self) -> Option<&str> {
+		self.note.as_deref()
+	}
+
+	/// Sets the card's note
+	///
+	/// ### Arguments
+	///
+	/// * `note` - The new note, or None to clear it
+	pub fn set_note(&mut self, note: Option<String>) {
+		self.note = note;
+	}
+
+	/// Gets the card's direction
+	///
+	/// ### Returns
+	///
+	/// Which way round the card asks its question, or None if it has no
+	/// direction
+	pub fn get_direction(&self) -> Option<CardDirection> {
+		self.direction
+	}
+
+	/// Sets the card's direction
+	///
+	/// ### Arguments
+	///
+	/// * `direction` - The new direction, or None for no direction
+	pub fn set_direction(&mut self, direction: Option<CardDirection>) {
+		self.direction = direction;
+	}
+
+	/// Gets how hard the user finds the card
+	///
+	/// ### Returns
+	///
+	/// The card's difficulty, between 0 and 1, or None if it hasn't been set
+	pub fn get_difficulty(&self) -> Option<f32> {
+		self.difficulty
+	}
+
+	/// Sets how hard the user finds the card
+	///
+	/// ### Arguments
+	///
+	/// * `difficulty` - The new difficulty, between 0 and 1, or None to unset
+	///   it
+	pub fn set_difficulty(&mut self, difficulty: Option<f32>) {
+		self.difficulty = difficulty;
+	}
+
+	/// Gets the number of times the card has lapsed while mature
+	///
+	/// A mature lapse is rating a mature card (see
+	/// [`MATURE_INTERVAL_DAYS`](crate::repo::MATURE_INTERVAL_DAYS)) Again.
+	/// Unlike [`Card::get_lapses`], failing a card that was learned but not
+	/// yet mature doesn't count. Stored as the card's `lapses` column.
+	///
+	/// ### Returns
+	///
+	/// The number of mature lapses, 0 if the card has never lapsed
+	pub fn get_mature_lapses(&self) -> i32 {
+		self.lapses
+	}
+
+	/// Sets the number of times the card has lapsed while mature
+	///
+	/// ### Arguments
+	///
+	/// * `lapses` - The new number of mature lapses
+	pub fn set_mature_lapses(&mut self, lapses: i32) {
+		self.lapses = lapses;
+	}
+
+	/// Gets the card's version
+	///
+	/// ### Returns
+	///
+	/// The number of times the card has been changed since it was created
+	pub fn get_version(&self) -> i32 {
+		self.version
+	}
+
 	/// Gets the card's sort position
 	///
 	/// ### Returns
@@ -348,6 +508,15 @@ impl Card {
 		self.card_data = card_data;
 	}
 
+	/// Gets the card's creation timestamp
+	///
+	/// ### Returns
+	///
+	/// The timestamp when this card was created
+	pub fn get_created_at(&self) -> DateTime<Utc> {
+		DateTime::from_naive_utc_and_offset(self.created_at, Utc)
+	}
+
 	/// Gets the card's updated_at timestamp
 	///
 	/// ### Returns
@@ -385,9 +554,97 @@ impl Card {
 		self.cache_updated_at
 	}
 
+	/// Gets the probability of recalling the card at the given time
+	///
+	/// Follows the forgetting curve `exp(-elapsed / stability)`, where
+	/// `elapsed` is the days since the last review and `stability` comes
+	/// from the FSRS scheduler data. Times before the last review count as
+	/// no time elapsed.
+	///
+	/// ### Arguments
+	///
+	/// * `now` - The time to compute the retrievability at
+	///
+	/// ### Returns
+	///
+	/// The retrievability between 0 and 1, or None if the card has never been
+	/// reviewed or its scheduler data has no positive stability
+	pub fn retrievability(&self, now: DateTime<Utc>) -> Option<f64> {
+		let last_review = self.get_last_review()?;
+		let stability = self
+			.scheduler_data
+			.as_ref()?
+			.0
+			.get("stability")?
+			.as_f64()
+			.filter(|s| *s > 0.0)?;
+
+		let elapsed_days = (now - last_review).num_milliseconds().max(0) as f64 / 86_400_000.0;
+		Some((-elapsed_days / stability).exp())
+	}
+
+	/// Gets the number of times the card has lapsed
+	///
+	/// A lapse is failing a review of a card that had already been learned,
+	/// counted by the FSRS scheduler as `lapses` in its scheduler data.
+	///
+	/// ### Returns
+	///
+	/// The number of lapses, 0 if none have been recorded
+	pub fn get_lapses(&self) -> u32 {
+		self.scheduler_data
+			.as_ref()
+			.and_then(|data| data.0.get("lapses")?.as_u64())
+			.unwrap_or(0) as u32
+	}
+
+	/// The read-only fields [`Card::to_json`] adds that are computed as of
+	/// now rather than stored, so they change over time on their own
+	pub const COMPUTED_FIELDS: [&'static str; 2] = ["retrievability", "due_in"];
+
+	/// Serializes the card to JSON, along with its read-only computed fields
+	///
+	/// The returned JSON has every stored field, plus `retrievability` as of
+	/// now (null for cards that can't have one) and `due_in`, when the card
+	/// is next due in words (see [`Card::due_in`]).
+	///
+	/// ### Returns
+	///
+	/// A serde_json::Value representing the card
+	pub fn to_json(&self) -> serde_json::Value {
+		let mut json = serde_json::to_value(self).expect("Card serialization should never fail");
+		if let Some(obj) = json.as_object_mut() {
+			obj.insert(
+				"retrievability".to_string(),
+				serde_json::Value::from(self.retrievability(Utc::now())),
+			);
+			obj.insert(
+				"due_in".to_string(),
+				serde_json::Value::from(self.due_in(Utc::now())),
+			);
+		}
+		json
+	}
+
+	/// Describes when the card is next due, for people, e.g. "3 days"
+	///
+	/// ### Arguments
+	///
+	/// * `now` - The time to describe it relative to
+	///
+	/// ### Returns
+	///
+	/// "new" for a card that's never been reviewed, "overdue" for one whose
+	/// next review has passed, and otherwise the time left as formatted by
+	/// [`format_due_in`]
+	pub fn due_in(&self, now: DateTime<Utc>) -> String {
+		let due = self.get_last_review().map(|_| self.get_next_review());
+		format_due_in(due, now)
+	}
+
 	/// Serializes the card to JSON with the priority offset folded into the priority field
 	///
-	/// The returned JSON has:
+	/// The returned JSON is that of `to_json`, except:
 	/// - `priority` = base priority + priority_offset, clamped to [0.0, 1.0]
 	/// - `priority_offset` field removed
 	///
@@ -396,7 +653,7 @@ impl Card {
 	/// A serde_json::Value representing the card with effective priority
 	pub fn to_json_hide_priority_offset(&self) -> serde_json::Value {
 		let effective_priority = (self.priority + self.priority_offset).clamp(0.0, 1.0);
-		let mut json = serde_json::to_value(self).expect("Card serialization should never fail");
+		let mut json = self.to_json();
 		if let Some(obj) = json.as_object_mut() {
 			obj.insert(
 				"priority".to_string(),
@@ -524,4 +781,96 @@ mod tests {
 
 		assert_eq!(card.get_scheduler_data(), scheduler_data);
 	}
+
+	fn reviewed_card(last_review: DateTime<Utc>, stability: f64) -> Card {
+		let mut card = Card::new(ItemId("item1".to_string()), 0, Utc::now(), 0.5);
+		card.set_last_review(Some(last_review));
+		card.set_scheduler_data(Some(JsonValue(json!({
+			"stability": stability,
+			"difficulty": 5.0,
+		}))));
+		card
+	}
+
+	#[test]
+	fn test_retrievability_decreases_with_elapsed_time() {
+		let last_review = Utc::now();
+		let card = reviewed_card(last_review, 10.0);
+
+		let mut previous = card.retrievability(last_review).unwrap();
+		assert!(
+			(previous - 1.0).abs() < 1e-9,
+			"expected ~1.0, got {}",
+			previous
+		);
+
+		for days in [1, 5, 10, 30, 100] {
+			let r = card
+				.retrievability(last_review + chrono::Duration::days(days))
+				.unwrap();
+			assert!(r < previous, "retrievability should drop by day {}", days);
+			assert!(r > 0.0);
+			previous = r;
+		}
+
+		let at_stability = card
+			.retrievability(last_review + chrono::Duration::days(10))
+			.unwrap();
+		assert!((at_stability - (-1.0f64).exp()).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_retrievability_none_when_never_reviewed() {
+		let card = Card::new(ItemId("item1".to_string()), 0, Utc::now(), 0.5);
+		assert_eq!(card.retrievability(Utc::now()), None);
+		assert!(card.to_json()["retrievability"].is_null());
+	}
+
+	#[test]
+	fn test_retrievability_none_without_stability() {
+		let mut card = Card::new(ItemId("item1".to_string()), 0, Utc::now(), 0.5);
+		card.set_last_review(Some(Utc::now()));
+		card.set_scheduler_data(Some(JsonValue(json!({ "interval": 1 }))));
+		assert_eq!(card.retrievability(Utc::now()), None);
+	}
+
+	#[test]
+	fn test_retrievability_before_last_review_is_one() {
+		let last_review = Utc::now();
+		let card = reviewed_card(last_review, 3.0);
+		let r = card
+			.retrievability(last_review - chrono::Duration::days(1))
+			.unwrap();
+		assert_eq!(r, 1.0);
+	}
+
+	#[test]
+	fn test_to_json_includes_retrievability() {
+		let card = reviewed_card(Utc::now() - chrono::Duration::days(2), 4.0);
+		let r = card.to_json()["retrievability"].as_f64().unwrap();
+		assert!((r - (-0.5f64).exp()).abs() < 1e-3, "got {}", r);
+		let r = card.to_json_hide_priority_offset()["retrievability"]
+			.as_f64()
+			.unwrap();
+		assert!((r - (-0.5f64).exp()).abs() < 1e-3, "got {}", r);
+	}
+
+	#[test]
+	fn test_due_in() {
+		let now = Utc::now();
+
+		// Never reviewed, so new however it's scheduled
+		let card = Card::new(ItemId("item1".to_string()), 0, now, 0.5);
+		assert_eq!(card.due_in(now), "new");
+		assert_eq!(card.to_json()["due_in"], "new");
+
+		let mut card = reviewed_card(now - chrono::Duration::days(3), 4.0);
+		card.set_next_review(now - chrono::Duration::hours(1));
+		assert_eq!(card.due_in(now), "overdue");
+		card.set_next_review(now + chrono::Duration::hours(5));
+		assert_eq!(card.due_in(now), "5 hours");
+		card.set_next_review(now + chrono::Duration::days(3) - chrono::Duration::hours(1));
+		assert_eq!(card.due_in(now), "3 days");
+		assert!(card.to_json_hide_priority_offset()["due_in"].is_string());
+	}
 }
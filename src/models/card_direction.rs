@@ -0,0 +1,80 @@
+use std::fmt;
+
+use diesel::{
+	deserialize::{FromSql, FromSqlRow},
+	expression::AsExpression,
+	serialize::{self, IsNull, Output, ToSql},
+	sql_types::Text,
+	sqlite::{Sqlite, SqliteValue},
+};
+use serde::{Deserialize, Serialize};
+
+/// Which way round a two-sided card asks its question
+///
+/// Basic items get a forward card, plus a reverse one when they are
+/// `reversible`; Vocabulary items always get both.
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, AsExpression, FromSqlRow,
+)]
+#[serde(rename_all = "lowercase")]
+#[diesel(sql_type = Text)]
+pub enum CardDirection {
+	/// Shows the front (or term) and asks for the back (or definition)
+	Forward,
+	/// Shows the back (or definition) and asks for the front (or term)
+	Reverse,
+}
+
+impl CardDirection {
+	/// Returns the direction's name, as stored and sent over the wire
+	pub fn as_str(self) -> &'static str {
+		match self {
+			CardDirection::Forward => "forward",
+			CardDirection::Reverse => "reverse",
+		}
+	}
+}
+
+impl FromSql<Text, Sqlite> for CardDirection {
+	fn from_sql(value: SqliteValue<'_, '_, '_>) -> diesel::deserialize::Result<Self> {
+		let raw = <String as FromSql<Text, Sqlite>>::from_sql(value)?;
+		match raw.as_str() {
+			"forward" => Ok(CardDirection::Forward),
+			"reverse" => Ok(CardDirection::Reverse),
+			_ => Err(format!("Unknown card direction: {}", raw).into()),
+		}
+	}
+}
+
+impl ToSql<Text, Sqlite> for CardDirection {
+	fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+		out.set_value(self.as_str());
+		Ok(IsNull::No)
+	}
+}
+
+impl fmt::Display for CardDirection {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_card_direction_serializes_lowercase() {
+		for (direction, raw) in [
+			(CardDirection::Forward, "forward"),
+			(CardDirection::Reverse, "reverse"),
+		] {
+			assert_eq!(serde_json::to_value(direction).unwrap(), raw);
+			assert_eq!(
+				serde_json::from_value::<CardDirection>(raw.into()).unwrap(),
+				direction
+			);
+			assert_eq!(direction.to_string(), raw);
+		}
+	}
+}
@@ -265,6 +265,63 @@ impl fmt::Display for ItemTypeId {
 	}
 }
 
+/// Identifies a review session
+#[derive(
+	Debug,
+	Clone,
+	PartialEq,
+	Eq,
+	Hash,
+	PartialOrd,
+	Ord,
+	Serialize,
+	Deserialize,
+	AsExpression,
+	FromSqlRow,
+)]
+#[diesel(sql_type = Text)]
+pub struct SessionId(pub String);
+
+impl SessionId {
+	pub fn new() -> Self {
+		Self(format!("session-{}", Uuid::new_v4()))
+	}
+}
+
+impl Default for SessionId {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl FromSql<Text, Sqlite> for SessionId {
+	fn from_sql(value: SqliteValue<'_, '_, '_>) -> diesel::deserialize::Result<Self> {
+		let text = <String as FromSql<Text, Sqlite>>::from_sql(value)?;
+		Ok(SessionId(text))
+	}
+}
+
+impl ToSql<Text, Sqlite> for SessionId {
+	fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+		out.set_value(self.0.clone());
+		Ok(IsNull::No)
+	}
+}
+
+impl FromStr for SessionId {
+	type Err = Infallible;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(SessionId(s.to_owned()))
+	}
+}
+
+impl fmt::Display for SessionId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
 /// Identifies a card event function by name
 ///
 /// Newtype around the registry key. Stored as TEXT in `card_fetched_events.function_name`.
@@ -2,13 +2,11 @@ use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::models::ItemTypeId;
+use crate::models::{ItemTypeId, JsonValue};
 use crate::time_utils::now_ms;
 
 /// Represents an item type in the system
-#[derive(
-	Queryable, Selectable, Insertable, Debug, Clone, PartialEq, Eq, Serialize, Deserialize,
-)]
+#[derive(Queryable, Selectable, Insertable, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::item_types)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 pub struct ItemType {
@@ -26,6 +24,17 @@ pub struct ItemType {
 
 	/// When this item type was last updated
 	updated_at: NaiveDateTime,
+
+	/// Optional JSON Schema that the data of items of this type must satisfy
+	item_data_schema: Option<JsonValue>,
+
+	/// Optional priority given to new cards of this type, overriding the
+	/// configured default
+	default_priority: Option<f32>,
+
+	/// Optional tuning parameters for the scheduler, see
+	/// [`SchedulerConfig`](crate::models::SchedulerConfig)
+	scheduler_config: Option<JsonValue>,
 }
 
 impl ItemType {
@@ -34,7 +43,7 @@ impl ItemType {
 	/// ### Arguments
 	///
 	/// * `name` - The name of the item type
-	/// * `review_function` - The review function used for scheduling (e.g. "fsrs", "incremental_queue")
+	/// * `review_function` - The review function used for scheduling (e.g. "fsrs", "incremental_queue", "todo", "recurring")
 	pub fn new(name: String, review_function: String) -> Self {
 		let now = now_ms();
 		Self {
@@ -43,6 +52,9 @@ impl ItemType {
 			created_at: now,
 			review_function,
 			updated_at: now,
+			item_data_schema: None,
+			default_priority: None,
+			scheduler_config: None,
 		}
 	}
 
@@ -70,6 +82,9 @@ impl ItemType {
 			created_at: created_at.naive_utc(),
 			review_function,
 			updated_at: created_at.naive_utc(),
+			item_data_schema: None,
+			default_priority: None,
+			scheduler_config: None,
 		}
 	}
 
@@ -153,6 +168,60 @@ impl ItemType {
 	pub fn set_review_function(&mut self, review_function: String) {
 		self.review_function = review_function;
 	}
+
+	/// Gets the JSON Schema that item data of this type must satisfy
+	///
+	/// ### Returns
+	///
+	/// The schema, or None if item data of this type isn't schema-checked
+	pub fn get_item_data_schema(&self) -> Option<JsonValue> {
+		self.item_data_schema.clone()
+	}
+
+	/// Sets the JSON Schema that item data of this type must satisfy
+	///
+	/// ### Arguments
+	///
+	/// * `item_data_schema` - The new schema, or None to disable schema validation
+	pub fn set_item_data_schema(&mut self, item_data_schema: Option<JsonValue>) {
+		self.item_data_schema = item_data_schema;
+	}
+
+	/// Gets the priority given to new cards of this type
+	///
+	/// ### Returns
+	///
+	/// The priority, or None if new cards use the configured default
+	pub fn get_default_priority(&self) -> Option<f32> {
+		self.default_priority
+	}
+
+	/// Sets the priority given to new cards of this type
+	///
+	/// ### Arguments
+	///
+	/// * `default_priority` - The new priority, or None to use the configured default
+	pub fn set_default_priority(&mut self, default_priority: Option<f32>) {
+		self.default_priority = default_priority;
+	}
+
+	/// Gets the tuning parameters set for the scheduler of this type
+	///
+	/// ### Returns
+	///
+	/// The parameters as set, or None if the scheduler uses its defaults
+	pub fn get_scheduler_config(&self) -> Option<JsonValue> {
+		self.scheduler_config.clone()
+	}
+
+	/// Sets the tuning parameters for the scheduler of this type
+	///
+	/// ### Arguments
+	///
+	/// * `scheduler_config` - The new parameters, or None to use the defaults
+	pub fn set_scheduler_config(&mut self, scheduler_config: Option<JsonValue>) {
+		self.scheduler_config = scheduler_config;
+	}
 }
 
 #[cfg(test)]
@@ -169,6 +238,8 @@ mod tests {
 
 		assert_eq!(item_type.get_name(), name);
 		assert_eq!(item_type.get_review_function(), "fsrs");
+		assert_eq!(item_type.get_item_data_schema(), None);
+		assert_eq!(item_type.get_default_priority(), None);
 
 		// Ensure created_at is within the last second
 		let now = Utc::now();
@@ -13,23 +13,38 @@ pub use ids::*;
 mod item_type;
 pub use item_type::ItemType;
 
+mod scheduler_config;
+pub use scheduler_config::{MAX_INCREMENTAL_QUEUE_INTERVAL_DAYS, MAX_MULTIPLIER, SchedulerConfig};
+
 mod item;
 pub use item::Item;
 
 mod card;
 pub use card::Card;
 
+mod card_direction;
+pub use card_direction::CardDirection;
+
 mod tag;
 pub use tag::Tag;
 
 mod item_tag;
 pub use item_tag::ItemTag;
 
+mod rating;
+pub use rating::{InvalidRatingError, OutOfScaleRatingError, Rating};
+
 mod review;
 pub use review::Review;
 
+mod session;
+pub use session::Session;
+
 mod item_relation;
 pub use item_relation::ItemRelation;
 
 mod card_fetched_event;
 pub use card_fetched_event::CardFetchedEvent;
+
+mod backup_log_entry;
+pub use backup_log_entry::BackupLogEntry;
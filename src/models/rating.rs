@@ -0,0 +1,208 @@
+use std::{fmt, str::FromStr};
+
+use diesel::{
+	deserialize::{FromSql, FromSqlRow},
+	expression::AsExpression,
+	serialize::{self, IsNull, Output, ToSql},
+	sql_types::Integer,
+	sqlite::{Sqlite, SqliteValue},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// How well a card was recalled during a review.
+///
+/// On the wire and in the database a rating is its integer value, 1-4, so
+/// clients keep sending plain numbers; anything outside that range is
+/// rejected when it is converted, rather than deep inside scheduling.
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	PartialOrd,
+	Ord,
+	Hash,
+	Serialize,
+	Deserialize,
+	AsExpression,
+	FromSqlRow,
+)]
+#[serde(try_from = "i32", into = "i32")]
+#[diesel(sql_type = Integer)]
+pub enum Rating {
+	/// The card was forgotten
+	Again = 1,
+	/// The card was recalled with serious difficulty
+	Hard = 2,
+	/// The card was recalled after some hesitation
+	Good = 3,
+	/// The card was recalled perfectly
+	Easy = 4,
+}
+
+/// Error returned when an integer isn't a valid rating
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("Rating must be between 1 and 4, got {0}")]
+pub struct InvalidRatingError(pub i32);
+
+/// Error returned when a rating is outside the configured rating scale
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("Rating must be between 1 and {scale} on the {scale}-button rating scale, got {rating}")]
+pub struct OutOfScaleRatingError {
+	/// The rating that was given
+	pub rating: Rating,
+	/// The configured rating scale
+	pub scale: u8,
+}
+
+impl Rating {
+	/// Every rating, from worst to best
+	pub const ALL: [Rating; 4] = [Rating::Again, Rating::Hard, Rating::Good, Rating::Easy];
+
+	/// Returns the rating's integer value, 1-4
+	pub fn as_i32(self) -> i32 {
+		self as i32
+	}
+
+	/// Interprets a rating given on a 2- or 4-button rating scale
+	///
+	/// On the 4-button scale every rating means itself. On the 2-button
+	/// (pass/fail) scale only 1 (fail) and 2 (pass) can be given, and a pass
+	/// is scheduled as [`Rating::Good`].
+	///
+	/// ### Arguments
+	///
+	/// * `scale` - The number of buttons on the rating scale, 2 or 4
+	///
+	/// ### Returns
+	///
+	/// The rating to schedule the review with
+	///
+	/// ### Errors
+	///
+	/// Returns an `OutOfScaleRatingError` for a rating above the scale
+	pub fn on_scale(self, scale: u8) -> Result<Rating, OutOfScaleRatingError> {
+		match (scale, self) {
+			(2, Rating::Again) => Ok(Rating::Again),
+			(2, Rating::Hard) => Ok(Rating::Good),
+			(2, rating) => Err(OutOfScaleRatingError { rating, scale }),
+			(_, rating) => Ok(rating),
+		}
+	}
+}
+
+impl TryFrom<i32> for Rating {
+	type Error = InvalidRatingError;
+
+	fn try_from(value: i32) -> Result<Self, Self::Error> {
+		match value {
+			1 => Ok(Rating::Again),
+			2 => Ok(Rating::Hard),
+			3 => Ok(Rating::Good),
+			4 => Ok(Rating::Easy),
+			_ => Err(InvalidRatingError(value)),
+		}
+	}
+}
+
+impl From<Rating> for i32 {
+	fn from(rating: Rating) -> Self {
+		rating.as_i32()
+	}
+}
+
+impl FromSql<Integer, Sqlite> for Rating {
+	fn from_sql(value: SqliteValue<'_, '_, '_>) -> diesel::deserialize::Result<Self> {
+		let raw = <i32 as FromSql<Integer, Sqlite>>::from_sql(value)?;
+		Ok(Rating::try_from(raw)?)
+	}
+}
+
+impl ToSql<Integer, Sqlite> for Rating {
+	fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+		out.set_value(self.as_i32());
+		Ok(IsNull::No)
+	}
+}
+
+impl FromStr for Rating {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let value: i32 = s
+			.trim()
+			.parse()
+			.map_err(|_| format!("Rating must be an integer between 1 and 4, got {:?}", s))?;
+		Rating::try_from(value).map_err(|e| e.to_string())
+	}
+}
+
+impl fmt::Display for Rating {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.as_i32())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_rating_deserializes_from_integers() {
+		for (raw, rating) in [
+			(1, Rating::Again),
+			(2, Rating::Hard),
+			(3, Rating::Good),
+			(4, Rating::Easy),
+		] {
+			assert_eq!(
+				serde_json::from_value::<Rating>(raw.into()).unwrap(),
+				rating
+			);
+			assert_eq!(serde_json::to_value(rating).unwrap(), raw);
+		}
+	}
+
+	#[test]
+	fn test_rating_rejects_out_of_range_integers() {
+		for raw in [0, 5, -1] {
+			let err = serde_json::from_value::<Rating>(raw.into()).unwrap_err();
+			assert!(
+				err.to_string().contains("Rating must be between 1 and 4"),
+				"got: {}",
+				err
+			);
+			assert_eq!(Rating::try_from(raw), Err(InvalidRatingError(raw)));
+		}
+	}
+
+	#[test]
+	fn test_rating_parses_from_strings() {
+		assert_eq!("3".parse::<Rating>(), Ok(Rating::Good));
+		assert!("5".parse::<Rating>().is_err());
+		assert!("good".parse::<Rating>().is_err());
+	}
+
+	#[test]
+	fn test_rating_on_scale() {
+		for rating in Rating::ALL {
+			assert_eq!(rating.on_scale(4), Ok(rating));
+		}
+
+		assert_eq!(Rating::Again.on_scale(2), Ok(Rating::Again));
+		assert_eq!(Rating::Hard.on_scale(2), Ok(Rating::Good));
+		for rating in [Rating::Good, Rating::Easy] {
+			let err = rating.on_scale(2).unwrap_err();
+			assert_eq!(err, OutOfScaleRatingError { rating, scale: 2 });
+			assert!(err.to_string().contains("between 1 and 2"), "got: {}", err);
+		}
+	}
+
+	#[test]
+	fn test_rating_all_is_in_order() {
+		let values: Vec<i32> = Rating::ALL.iter().map(|r| r.as_i32()).collect();
+		assert_eq!(values, vec![1, 2, 3, 4]);
+	}
+}
@@ -2,11 +2,9 @@ use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::models::{CardId, ReviewId};
+use crate::models::{CardId, Rating, ReviewId, SessionId};
 
-#[derive(
-	Queryable, Selectable, Insertable, Debug, Clone, PartialEq, Eq, Serialize, Deserialize,
-)]
+#[derive(Queryable, Selectable, Insertable, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::reviews)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 pub struct Review {
@@ -17,10 +15,26 @@ pub struct Review {
 	card_id: CardId,
 
 	/// The rating given during this review
-	rating: i32,
+	rating: Rating,
 
 	/// When this review occurred
 	review_timestamp: NaiveDateTime,
+
+	/// The session this review was recorded in, if any
+	session_id: Option<SessionId>,
+
+	/// Whether this review was made while cramming, so didn't reschedule
+	/// its card
+	#[serde(default)]
+	cram: bool,
+
+	/// What was actually answered in this review, if it was recorded
+	response: Option<String>,
+
+	/// The priority the card had before this review changed it, if it did,
+	/// so undoing the review can put it back
+	#[serde(skip)]
+	priority_before: Option<f32>,
 }
 
 impl Review {
@@ -34,21 +48,16 @@ impl Review {
 	/// ### Returns
 	///
 	/// A new `Review` instance with the specified card ID and rating
-	///
-	/// ### Panics
-	///
-	/// This function will panic if the rating is not in the range 1-4.
-	pub fn new(card_id: CardId, rating: i32) -> Self {
-		// Validate the rating
-		if rating < 1 || rating > 4 {
-			panic!("Rating must be between 1 and 4, got {}", rating);
-		}
-
+	pub fn new(card_id: CardId, rating: Rating) -> Self {
 		Self {
 			id: ReviewId::new(),
 			card_id,
 			rating,
 			review_timestamp: Utc::now().naive_utc(),
+			session_id: None,
+			cram: false,
+			response: None,
+			priority_before: None,
 		}
 	}
 
@@ -67,7 +76,7 @@ impl Review {
 	pub fn new_with_fields(
 		id: ReviewId,
 		card_id: CardId,
-		rating: i32,
+		rating: Rating,
 		review_timestamp: DateTime<Utc>,
 	) -> Self {
 		Self {
@@ -75,6 +84,10 @@ impl Review {
 			card_id,
 			rating,
 			review_timestamp: review_timestamp.naive_utc(),
+			session_id: None,
+			cram: false,
+			response: None,
+			priority_before: None,
 		}
 	}
 
@@ -137,7 +150,7 @@ impl Review {
 	/// ### Returns
 	///
 	/// The rating given during this review
-	pub fn get_rating(&self) -> i32 {
+	pub fn get_rating(&self) -> Rating {
 		self.rating
 	}
 
@@ -146,9 +159,82 @@ impl Review {
 	/// ### Arguments
 	///
 	/// * `rating` - The new rating for the review
-	pub fn set_rating(&mut self, rating: i32) {
+	pub fn set_rating(&mut self, rating: Rating) {
 		self.rating = rating;
 	}
+
+	/// Gets the session this review was recorded in
+	///
+	/// ### Returns
+	///
+	/// The ID of the review's session, or None if it wasn't part of one
+	pub fn get_session_id(&self) -> Option<SessionId> {
+		self.session_id.clone()
+	}
+
+	/// Sets the session this review was recorded in
+	///
+	/// ### Arguments
+	///
+	/// * `session_id` - The ID of the review's session, or None
+	pub fn set_session_id(&mut self, session_id: Option<SessionId>) {
+		self.session_id = session_id;
+	}
+
+	/// Gets whether this review was made while cramming
+	///
+	/// ### Returns
+	///
+	/// True if the review was logged without rescheduling its card
+	pub fn get_cram(&self) -> bool {
+		self.cram
+	}
+
+	/// Sets whether this review was made while cramming
+	///
+	/// ### Arguments
+	///
+	/// * `cram` - Whether the review was logged without rescheduling its card
+	pub fn set_cram(&mut self, cram: bool) {
+		self.cram = cram;
+	}
+
+	/// Gets what was answered in this review
+	///
+	/// ### Returns
+	///
+	/// The recorded answer, or None if none was recorded
+	pub fn get_response(&self) -> Option<String> {
+		self.response.clone()
+	}
+
+	/// Sets what was answered in this review
+	///
+	/// ### Arguments
+	///
+	/// * `response` - The answer given, or None
+	pub fn set_response(&mut self, response: Option<String>) {
+		self.response = response;
+	}
+
+	/// Gets the priority the card had before this review changed it
+	///
+	/// ### Returns
+	///
+	/// The card's earlier priority, or None if the review didn't change it
+	pub fn get_priority_before(&self) -> Option<f32> {
+		self.priority_before
+	}
+
+	/// Sets the priority the card had before this review changed it
+	///
+	/// ### Arguments
+	///
+	/// * `priority_before` - The card's earlier priority, or None if the
+	///   review didn't change it
+	pub fn set_priority_before(&mut self, priority_before: Option<f32>) {
+		self.priority_before = priority_before;
+	}
 }
 
 #[cfg(test)]
@@ -161,12 +247,14 @@ mod tests {
 	#[test]
 	fn test_review_new() {
 		let card_id = CardId::new();
-		let rating = 2;
+		let rating = Rating::Hard;
 
 		let review = Review::new(card_id.clone(), rating);
 
 		assert_eq!(review.get_card_id(), card_id);
 		assert_eq!(review.get_rating(), rating);
+		assert!(!review.get_cram());
+		assert_eq!(review.get_response(), None);
 
 		// Ensure review_timestamp is within the last second
 		let now = Utc::now();
@@ -175,14 +263,4 @@ mod tests {
 
 		assert!(diff.num_seconds() < 1);
 	}
-
-	#[test]
-	#[should_panic(expected = "Rating must be between 1 and 4")]
-	fn test_review_invalid_rating() {
-		let card_id = CardId::new();
-		let invalid_rating = 0;
-
-		// This should panic
-		let _ = Review::new(card_id, invalid_rating);
-	}
 }
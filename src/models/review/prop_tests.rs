@@ -1,4 +1,5 @@
 use super::*;
+use crate::models::InvalidRatingError;
 use crate::test_utils::{
 	arb_card_id, arb_datetime_utc, arb_invalid_rating, arb_rating, arb_review_id,
 };
@@ -66,7 +67,7 @@ proptest! {
 	fn prop_r1_5_new_with_fields_roundtrip(
 		id in arb_review_id(),
 		card_id in arb_card_id(),
-		rating in any::<i32>(),
+		rating in arb_rating(),
 		ts in arb_datetime_utc(),
 	) {
 		let review = Review::new_with_fields(
@@ -86,30 +87,20 @@ proptest! {
 }
 
 // ============================================================================
-// R1p: Constructor Panic Boundaries
+// R1p: Rating Boundaries
 // ============================================================================
 
-#[test]
-#[should_panic(expected = "Rating must be between 1 and 4")]
-fn prop_r1p_1_new_panics_for_rating_0() {
-	let _ = Review::new(CardId("card-id".to_string()), 0);
-}
-
-#[test]
-#[should_panic(expected = "Rating must be between 1 and 4")]
-fn prop_r1p_2_new_panics_for_rating_5() {
-	let _ = Review::new(CardId("card-id".to_string()), 5);
-}
-
 proptest! {
-	/// R1p.3: Review::new panics for any rating outside [1, 4]
+	/// R1p.1: Every integer outside [1, 4] is rejected before a Review can be built
 	#[test]
-	fn prop_r1p_3_new_panics_for_invalid_rating(rating in arb_invalid_rating()) {
-		let result = std::panic::catch_unwind(|| {
-			Review::new(CardId("card-id".to_string()), rating)
-		});
-		prop_assert!(result.is_err(),
-			"Review::new should panic for rating {}", rating);
+	fn prop_r1p_1_invalid_rating_rejected(rating in arb_invalid_rating()) {
+		prop_assert_eq!(Rating::try_from(rating), Err(InvalidRatingError(rating)));
+	}
+
+	/// R1p.2: Every integer in [1, 4] converts to a rating and back
+	#[test]
+	fn prop_r1p_2_valid_rating_roundtrip(rating in arb_rating()) {
+		prop_assert_eq!(Rating::try_from(rating.as_i32()), Ok(rating));
 	}
 }
 
@@ -122,18 +113,18 @@ proptest! {
 	#[test]
 	fn prop_r1r_1_new_does_not_panic_any_card_id(card_id in arb_card_id()) {
 		let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-			Review::new(card_id.clone(), 2)
+			Review::new(card_id.clone(), Rating::Hard)
 		}));
 		prop_assert!(result.is_ok(),
 			"Review::new should not panic for card_id: {:?}", card_id);
 	}
 
-	/// R1r.2: Review::new_with_fields does not panic for arbitrary strings and any i32 rating
+	/// R1r.2: Review::new_with_fields does not panic for arbitrary strings and any rating
 	#[test]
 	fn prop_r1r_2_new_with_fields_does_not_panic(
 		id in arb_review_id(),
 		card_id in arb_card_id(),
-		rating in any::<i32>(),
+		rating in arb_rating(),
 		ts in arb_datetime_utc(),
 	) {
 		let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
@@ -153,15 +144,15 @@ proptest! {
 	/// R2.1: set_card_id / get_card_id roundtrip
 	#[test]
 	fn prop_r2_1_card_id_roundtrip(card_id in arb_card_id()) {
-		let mut review = Review::new(CardId("initial".to_string()), 2);
+		let mut review = Review::new(CardId("initial".to_string()), Rating::Hard);
 		review.set_card_id(card_id.clone());
 		prop_assert_eq!(review.get_card_id(), card_id);
 	}
 
 	/// R2.2: set_rating / get_rating roundtrip
 	#[test]
-	fn prop_r2_2_rating_roundtrip(rating in any::<i32>()) {
-		let mut review = Review::new(CardId("card-id".to_string()), 2);
+	fn prop_r2_2_rating_roundtrip(rating in arb_rating()) {
+		let mut review = Review::new(CardId("card-id".to_string()), Rating::Hard);
 		review.set_rating(rating);
 		prop_assert_eq!(review.get_rating(), rating);
 	}
@@ -169,7 +160,7 @@ proptest! {
 	/// R2.3: set_review_timestamp / get_review_timestamp roundtrip
 	#[test]
 	fn prop_r2_3_timestamp_roundtrip(ts in arb_datetime_utc()) {
-		let mut review = Review::new(CardId("card-id".to_string()), 2);
+		let mut review = Review::new(CardId("card-id".to_string()), Rating::Hard);
 		review.set_review_timestamp(ts);
 		let diff = (review.get_review_timestamp() - ts).num_seconds().abs();
 		prop_assert!(diff == 0, "Timestamps should match, diff: {} seconds", diff);
@@ -205,13 +196,12 @@ proptest! {
 // ============================================================================
 
 proptest! {
-	/// R3r.1: Serialization does not panic for any i32 rating stored via set_rating
+	/// R3r.1: Ratings serialize as their integer value
 	#[test]
-	fn prop_r3r_1_serialize_does_not_panic_any_rating(rating in any::<i32>()) {
-		let mut review = Review::new(CardId("card-id".to_string()), 2);
+	fn prop_r3r_1_rating_serializes_as_integer(rating in arb_rating()) {
+		let mut review = Review::new(CardId("card-id".to_string()), Rating::Hard);
 		review.set_rating(rating);
-		let result = serde_json::to_string(&review);
-		prop_assert!(result.is_ok(),
-			"Serialization should not fail for rating {}", rating);
+		let json = serde_json::to_value(&review).unwrap();
+		prop_assert_eq!(json["rating"].as_i64(), Some(rating.as_i32() as i64));
 	}
 }
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+/// Tuning parameters for the schedulers, set per item type with its
+/// `scheduler_config`
+///
+/// A parameter an item type's config leaves out takes its default, which is
+/// what the schedulers use for item types without a config. Each review
+/// function reads only its own parameters.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchedulerConfig {
+	/// The probability of recall `"fsrs"` schedules reviews for
+	pub desired_retention: f64,
+
+	/// How much `"incremental_queue"` grows the interval of a top-priority
+	/// card on a Good rating
+	pub growth_at_max_priority: f64,
+
+	/// How much `"incremental_queue"` grows the interval of a bottom-priority
+	/// card on a Good rating
+	pub growth_at_min_priority: f64,
+
+	/// How much `"incremental_queue"` scales the Good interval on a Hard rating
+	pub hard_factor: f64,
+
+	/// How much `"incremental_queue"` scales the Good interval on an Easy rating
+	pub easy_factor: f64,
+
+	/// The fraction `"incremental_queue"` randomly moves intervals by either
+	/// way, so reviews don't cluster
+	pub jitter: f64,
+
+	/// How much `"incremental_queue"` slows the interval growth of cards the
+	/// user finds hard, between 0 (difficulty is ignored) and 1 (a card of
+	/// difficulty 1 doesn't grow at all)
+	pub difficulty_weight: f64,
+}
+
+/// The largest any of `"incremental_queue"`'s multipliers can be
+pub const MAX_MULTIPLIER: f64 = 100.0;
+
+/// The longest interval `"incremental_queue"` schedules, in days: 100 years
+pub const MAX_INCREMENTAL_QUEUE_INTERVAL_DAYS: f64 = 36_500.0;
+
+impl Default for SchedulerConfig {
+	fn default() -> Self {
+		Self {
+			desired_retention: 0.9,
+			growth_at_max_priority: 1.2,
+			growth_at_min_priority: 3.0,
+			hard_factor: 0.6,
+			easy_factor: 1.8,
+			jitter: 0.15,
+			difficulty_weight: 0.0,
+		}
+	}
+}
+
+impl SchedulerConfig {
+	/// Parses and validates an item type's scheduler config
+	///
+	/// ### Arguments
+	///
+	/// * `value` - The config, a JSON object of parameters
+	///
+	/// ### Returns
+	///
+	/// The config, with defaults for any parameters left out
+	///
+	/// ### Errors
+	///
+	/// Returns a message if the config isn't an object of known parameters,
+	/// `desired_retention` isn't strictly between 0 and 1, a multiplier isn't
+	/// positive and at most [`MAX_MULTIPLIER`], `jitter` isn't at least 0 and below 1, or `difficulty_weight`
+	/// isn't between 0 and 1
+	pub fn from_json(value: &serde_json::Value) -> Result<Self, String> {
+		let config: Self = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+
+		if !(config.desired_retention > 0.0 && config.desired_retention < 1.0) {
+			return Err(format!(
+				"desired_retention must be between 0 and 1, got {}",
+				config.desired_retention
+			));
+		}
+		for (name, multiplier) in [
+			("growth_at_max_priority", config.growth_at_max_priority),
+			("growth_at_min_priority", config.growth_at_min_priority),
+			("hard_factor", config.hard_factor),
+			("easy_factor", config.easy_factor),
+		] {
+			if !(multiplier > 0.0 && multiplier <= MAX_MULTIPLIER) {
+				return Err(format!(
+					"{} must be positive and at most {}, got {}",
+					name, MAX_MULTIPLIER, multiplier
+				));
+			}
+		}
+		if !(0.0..1.0).contains(&config.jitter) {
+			return Err(format!(
+				"jitter must be at least 0 and below 1, got {}",
+				config.jitter
+			));
+		}
+		if !(0.0..=1.0).contains(&config.difficulty_weight) {
+			return Err(format!(
+				"difficulty_weight must be between 0 and 1, got {}",
+				config.difficulty_weight
+			));
+		}
+
+		Ok(config)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_from_json_fills_in_defaults() {
+		let config = SchedulerConfig::from_json(&json!({"hard_factor": 0.5})).unwrap();
+
+		assert_eq!(config.hard_factor, 0.5);
+		assert_eq!(
+			config,
+			SchedulerConfig {
+				hard_factor: 0.5,
+				..SchedulerConfig::default()
+			}
+		);
+		assert_eq!(
+			SchedulerConfig::from_json(&json!({})).unwrap(),
+			SchedulerConfig::default()
+		);
+	}
+
+	#[test]
+	fn test_from_json_rejects_invalid_configs() {
+		for invalid in [
+			json!({"unknown_factor": 2.0}),
+			json!({"easy_factor": "big"}),
+			json!([1.2]),
+			json!({"desired_retention": 1.0}),
+			json!({"desired_retention": 0.0}),
+			json!({"growth_at_max_priority": 0.0}),
+			json!({"easy_factor": -1.0}),
+			json!({"growth_at_min_priority": 1e300}),
+			json!({"hard_factor": 100.5}),
+			json!({"jitter": 1.0}),
+			json!({"jitter": -0.1}),
+			json!({"difficulty_weight": 1.5}),
+		] {
+			assert!(
+				SchedulerConfig::from_json(&invalid).is_err(),
+				"accepted {}",
+				invalid
+			);
+		}
+	}
+}
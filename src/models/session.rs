@@ -0,0 +1,121 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::models::SessionId;
+use crate::time_utils::now_ms;
+
+/// Represents a review session, a span of studying that reviews can be
+/// recorded against
+#[derive(
+	Queryable, Selectable, Insertable, Debug, Clone, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[diesel(table_name = crate::schema::sessions)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Session {
+	/// Unique identifier for the session (UUID v4 as string)
+	id: SessionId,
+
+	/// When this session started
+	started_at: NaiveDateTime,
+
+	/// When this session ended (or null if it is still running)
+	ended_at: Option<NaiveDateTime>,
+}
+
+impl Session {
+	/// Creates a new session starting now
+	///
+	/// ### Returns
+	///
+	/// A new, still running `Session`
+	pub fn new() -> Self {
+		Self {
+			id: SessionId::new(),
+			started_at: now_ms(),
+			ended_at: None,
+		}
+	}
+
+	/// Gets the session's ID
+	///
+	/// ### Returns
+	///
+	/// The unique identifier of the session
+	pub fn get_id(&self) -> SessionId {
+		self.id.clone()
+	}
+
+	/// Gets when the session started
+	///
+	/// ### Returns
+	///
+	/// The timestamp when this session started
+	pub fn get_started_at(&self) -> DateTime<Utc> {
+		DateTime::from_naive_utc_and_offset(self.started_at, Utc)
+	}
+
+	/// Gets when the session ended
+	///
+	/// ### Returns
+	///
+	/// The timestamp when this session ended, or None if it is still running
+	pub fn get_ended_at(&self) -> Option<DateTime<Utc>> {
+		self.ended_at
+			.map(|dt| DateTime::from_naive_utc_and_offset(dt, Utc))
+	}
+
+	/// Sets when the session ended
+	///
+	/// ### Arguments
+	///
+	/// * `ended_at` - The new end timestamp, or None to mark it as running
+	pub fn set_ended_at(&mut self, ended_at: Option<DateTime<Utc>>) {
+		self.ended_at = ended_at.map(|dt| dt.naive_utc());
+	}
+
+	/// Gets how long the session lasted
+	///
+	/// ### Returns
+	///
+	/// The time between the start and end of the session, or None if it is
+	/// still running
+	pub fn get_duration(&self) -> Option<Duration> {
+		self.ended_at.map(|ended_at| ended_at - self.started_at)
+	}
+}
+
+impl Default for Session {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_session_new() {
+		let session = Session::new();
+
+		assert!(session.get_id().0.starts_with("session-"));
+		assert_eq!(session.get_ended_at(), None);
+		assert_eq!(session.get_duration(), None);
+
+		// Ensure started_at is within the last second
+		let diff = Utc::now().signed_duration_since(session.get_started_at());
+		assert!(diff.num_seconds() < 1);
+	}
+
+	#[test]
+	fn test_session_duration() {
+		let mut session = Session::new();
+		let ended_at = session.get_started_at() + Duration::minutes(25);
+
+		session.set_ended_at(Some(ended_at));
+
+		assert_eq!(session.get_ended_at(), Some(ended_at));
+		assert_eq!(session.get_duration(), Some(Duration::minutes(25)));
+	}
+}
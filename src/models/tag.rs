@@ -22,6 +22,14 @@ pub struct Tag {
 
 	/// Whether the tag is visible to the user
 	visible: bool,
+
+	/// When this tag was last renamed or had its visibility changed
+	#[serde(default)]
+	updated_at: NaiveDateTime,
+
+	/// The tag this one sits under, if any
+	#[serde(default)]
+	parent_id: Option<TagId>,
 }
 
 impl Tag {
@@ -36,11 +44,14 @@ impl Tag {
 	///
 	/// A new `Tag` instance with the specified name and visibility
 	pub fn new(name: String, visible: bool) -> Self {
+		let now = Utc::now().naive_utc();
 		Self {
 			id: TagId::new(),
 			name,
-			created_at: Utc::now().naive_utc(),
+			created_at: now,
 			visible,
+			updated_at: now,
+			parent_id: None,
 		}
 	}
 
@@ -67,6 +78,8 @@ impl Tag {
 			name,
 			created_at: created_at.naive_utc(),
 			visible,
+			updated_at: created_at.naive_utc(),
+			parent_id: None,
 		}
 	}
 
@@ -132,6 +145,35 @@ impl Tag {
 	pub fn get_created_at_raw(&self) -> NaiveDateTime {
 		self.created_at
 	}
+
+	/// Gets the tag's updated_at timestamp
+	///
+	/// ### Returns
+	///
+	/// The timestamp when this tag was last renamed or had its visibility
+	/// changed
+	pub fn get_updated_at(&self) -> DateTime<Utc> {
+		DateTime::from_naive_utc_and_offset(self.updated_at, Utc)
+	}
+
+	/// Gets the tag's parent
+	///
+	/// ### Returns
+	///
+	/// The ID of the tag this one sits under, or None for a top-level tag
+	pub fn get_parent_id(&self) -> Option<TagId> {
+		self.parent_id.clone()
+	}
+
+	/// Sets the tag's parent
+	///
+	/// ### Arguments
+	///
+	/// * `parent_id` - The ID of the tag this one sits under, or None to make
+	///   it a top-level tag
+	pub fn set_parent_id(&mut self, parent_id: Option<TagId>) {
+		self.parent_id = parent_id;
+	}
 }
 
 #[cfg(test)]
@@ -157,5 +199,7 @@ mod tests {
 		let diff = now.signed_duration_since(created_at);
 
 		assert!(diff.num_seconds() < 1);
+		assert_eq!(tag.get_updated_at(), created_at);
+		assert_eq!(tag.get_parent_id(), None);
 	}
 }
@@ -1,6 +1,7 @@
 use crate::card_event_registry::is_registered;
 use crate::db::{DbPool, ExecuteWithRetry};
 use crate::models::{CardEventFnName, CardFetchedEvent, ItemTypeId, OrderIndex};
+use crate::repo::item_type_cache::item_type_cache;
 use crate::schema::{card_fetched_events, item_types};
 use anyhow::Result;
 use diesel::prelude::*;
@@ -80,6 +81,8 @@ pub async fn create_card_fetched_event(
 		.await
 	{
 		Ok(_) => {
+			// The insert trigger bumped the item type's `updated_at`
+			item_type_cache().invalidate(item_type_id);
 			info!(
 				"Successfully created card fetched event for item type {} at index {}",
 				item_type_id, order_index
@@ -301,6 +304,8 @@ pub async fn delete_card_fetched_event(
 
 	match result {
 		Ok(()) => {
+			// The delete trigger bumped the item type's `updated_at`
+			item_type_cache().invalidate(item_type_id);
 			info!(
 				"Successfully deleted card fetched event '{}' for item type {}",
 				function_name, item_type_id
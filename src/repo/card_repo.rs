@@ -2,21 +2,43 @@ use crate::card_event_registry::CardEventChainError;
 use crate::db::{
 	DbPool, ExecuteWithRetry, deferred_transaction_with_retry, transaction_with_retry,
 };
-use crate::models::{Card, CardId, Item, ItemId};
+use crate::dto::{ForecastBucket, ItemWithCardsAndTags, StatsOverview};
+use crate::models::{Card, CardDirection, CardId, Item, ItemId, ItemType, Tag, TagId};
 use crate::repo::card_cache::{self, CacheScope, EnsureCacheError};
 use crate::repo::query_repo;
-use crate::schema::{cards, metadata};
+use crate::repo::settings_repo::get_settings_with_conn;
+use crate::schema::{cards, item_tags, items, metadata, reviews, tags};
+use crate::time_utils::{local_today, start_of_local_day, start_of_next_local_day};
 use crate::{GetQueryDto, SuspendedFilter};
 use anyhow::{Result, anyhow};
-use chrono::Utc;
+use chrono::{DateTime, Days, Utc};
 use diesel::prelude::*;
 use rand::Rng;
+use std::collections::HashMap;
+use thiserror::Error;
 use tracing::{debug, info, instrument, warn};
 
+/// Error returned when a card update expected a version the card is no
+/// longer at, because something else changed it after it was read
+///
+/// Carried inside the `anyhow::Error` returned by the repo functions, so
+/// handlers can `downcast` to it and answer with a 409.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Card {card_id} has changed since version {expected_version} was read")]
+pub struct CardVersionConflictError {
+	/// The card that was being updated
+	pub card_id: CardId,
+	/// The version the update expected the card to be at
+	pub expected_version: i32,
+}
+
 /// Creates cards for an item
 ///
 /// This function automatically creates the necessary cards for an item
-/// based on its type and data. Currently, it creates exactly one card per item.
+/// based on its type and data. New cards get the item type's default
+/// priority if it has one, and the configured default priority otherwise.
+/// They're due straight away, or on the item's `due_date` for todos (see
+/// [`first_review_date`](super::first_review_date)).
 ///
 /// ### Arguments
 ///
@@ -31,6 +53,7 @@ use tracing::{debug, info, instrument, warn};
 ///
 /// Returns an error if:
 /// - Unable to get a connection from the pool
+/// - The item type is not one cards can be built for
 /// - The database insert operation fails
 #[instrument(skip(pool, item), fields(item_id = %item.get_id(), item_type = %item.get_item_type()))]
 pub async fn create_cards_for_item(pool: &DbPool, item: &Item) -> Result<Vec<Card>> {
@@ -42,61 +65,217 @@ pub async fn create_cards_for_item(pool: &DbPool, item: &Item) -> Result<Vec<Car
 
 	debug!("Item type: {}", item_type.get_name());
 
+	// Build the cards the item's type and data call for
+	let settings = super::get_settings(pool)?;
+	let new_cards = super::new_cards_for_item(
+		&item_type,
+		item,
+		settings.default_priority,
+		settings.timezone,
+		Utc::now(),
+	)?;
+	debug!("Creating {} cards", new_cards.len());
+
 	// Vector to store the created cards
-	let mut cards = Vec::new();
+	let mut cards = Vec::with_capacity(new_cards.len());
+	for card in new_cards {
+		cards.push(insert_card(pool, card).await?);
+	}
+
+	info!("Created {} cards for item {}", cards.len(), item.get_id());
+
+	// Return all created cards
+	Ok(cards)
+}
+
+/// Brings an existing item's cards in line with what its type and data call
+/// for now
+///
+/// Cards are matched to the cards the item should have by `card_index`.
+/// Missing cards are created as `create_cards_for_item` would, and cards
+/// whose index is still wanted are kept as they are, with their review
+/// history. Cards past the end (e.g. for a cloze deletion that's been
+/// removed) are only deleted, along with their reviews, when
+/// `remove_extra` is set. Everything happens in one transaction.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `item_id` - The ID of the item to regenerate cards for
+/// * `remove_extra` - Whether to delete cards the item no longer calls for
+///
+/// ### Returns
+///
+/// A Result containing all of the item's cards afterwards, in index order,
+/// or None if the item doesn't exist
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The item type is not one cards can be built for
+/// - A database operation fails
+#[instrument(skip(pool), fields(item_id = %item_id))]
+pub async fn regenerate_cards_for_item(
+	pool: &DbPool,
+	item_id: &ItemId,
+	remove_extra: bool,
+) -> Result<Option<Vec<Card>>, CardFetchError> {
+	debug!("Regenerating cards for item");
+
+	let Some(item) = super::get_item(pool, item_id)? else {
+		debug!("Item not found");
+		return Ok(None);
+	};
+	let item_type = super::get_item_type(pool, &item.get_item_type())?
+		.ok_or_else(|| anyhow!("Item type not found"))?;
+
+	let card_count = card_count_for_item(&item_type, &item.get_data().0)?;
+	let settings = super::get_settings(pool)?;
+	let priority = item_type
+		.get_default_priority()
+		.unwrap_or(settings.default_priority);
+	let next_review = super::first_review_date(
+		&item_type.get_review_function(),
+		&item.get_data().0,
+		settings.timezone,
+		Utc::now(),
+	);
+
+	let conn = &mut pool.get().map_err(anyhow::Error::from)?;
+	let (created, removed) = transaction_with_retry(conn, |c| {
+		let existing: Vec<i32> = cards::table
+			.filter(cards::item_id.eq(item_id))
+			.select(cards::card_index)
+			.load(c)?;
+
+		let missing: Vec<Card> = (0..card_count as i32)
+			.filter(|index| !existing.contains(index))
+			.map(|index| {
+				let mut card = Card::new(item_id.clone(), index, next_review, priority);
+				card.set_direction(card_direction_for_item(&item_type, index as usize));
+				card
+			})
+			.collect();
+		diesel::insert_into(cards::table)
+			.values(&missing)
+			.execute(c)?;
+
+		let removed = if remove_extra {
+			diesel::delete(
+				cards::table
+					.filter(cards::item_id.eq(item_id))
+					.filter(cards::card_index.ge(card_count as i32)),
+			)
+			.execute(c)?
+		} else {
+			0
+		};
+
+		Ok((missing.len(), removed))
+	})
+	.await
+	.map_err(anyhow::Error::from)?;
+
+	info!(
+		"Regenerated cards for item {}: {} created, {} removed",
+		item_id, created, removed
+	);
 
-	// Determine how many cards to create based on the item type
+	let mut cards = list_cards_by_item(pool, item_id).await?;
+	cards.sort_by_key(Card::get_card_index);
+	Ok(Some(cards))
+}
+
+/// Works out how many cards an item of the given type and data gets
+///
+/// This is the single source of truth for card generation: both
+/// `create_cards_for_item` and `preview_cards_for_item` go through it, so a
+/// preview can never disagree with what is actually created.
+///
+/// ### Arguments
+///
+/// * `item_type` - The type of the item
+/// * `item_data` - The item's JSON data
+///
+/// ### Returns
+///
+/// A Result containing the number of cards the item gets
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - The item type is not one cards can be built for
+/// - A Basic item's `reversible` flag is not a boolean
+/// - A Cloze item's data has no `clozes` array or an unknown `cloze_mode`
+pub(crate) fn card_count_for_item(
+	item_type: &ItemType,
+	item_data: &serde_json::Value,
+) -> Result<usize> {
 	match item_type.get_name().as_str() {
-		"Basic" => {
-			debug!("Creating basic card (front/back)");
-			// Basic items have just one card (front/back)
-			let card = create_card(pool, &item.get_id(), 0, 0.5).await?;
-			cards.push(card);
-		}
+		// Basic items have one card (front/back), plus its reverse
+		// (back/front) if they are reversible
+		"Basic" => match item_data.get("reversible") {
+			None | Some(serde_json::Value::Null | serde_json::Value::Bool(false)) => Ok(1),
+			Some(serde_json::Value::Bool(true)) => Ok(2),
+			Some(_) => Err(anyhow!("reversible must be a boolean")),
+		},
 		"Cloze" => {
-			debug!("Creating cloze deletion cards");
-			// Cloze items might have multiple cards (one per cloze deletion)
-			let data = item.get_data();
-			let cloze_deletions = data.0["clozes"].clone();
-			let cloze_deletions = cloze_deletions
+			// Cloze items have one card per cloze deletion, unless every
+			// deletion is shown together on a single card
+			let cloze_deletions = item_data["clozes"]
 				.as_array()
 				.ok_or_else(|| anyhow!("cloze deletion must be an array"))?;
 
-			debug!("Creating {} cloze cards", cloze_deletions.len());
-			for (index, _) in cloze_deletions.iter().enumerate() {
-				let card = create_card(pool, &item.get_id(), index as i32, 0.5).await?;
-				cards.push(card);
+			match item_data.get("cloze_mode").and_then(|m| m.as_str()) {
+				None | Some("per_deletion") => Ok(cloze_deletions.len()),
+				Some("all_in_one") => Ok(1),
+				Some(mode) => Err(anyhow!("unknown cloze mode: {}", mode)),
 			}
 		}
-		"Todo" => {
-			debug!("Creating todo card");
-			// Todo items have 1 card (each todo is a card)
-			let card = create_card(pool, &item.get_id(), 0, 0.5).await?;
-			cards.push(card);
-		}
+		// Vocabulary items have 2 cards (term→definition and definition→term)
+		"Vocabulary" => Ok(2),
+		// Todo items have 1 card (each todo is a card), recurring or not
+		"Todo" | "RecurringTodo" => Ok(1),
 		// TODO: this is a hack
-		name if name.contains("Test") => {
-			debug!("Creating test cards");
-			// Test item types have 2 cards
-			for i in 0..2 {
-				let card = create_card(pool, &item.get_id(), i, 0.5).await?;
-				cards.push(card);
-			}
-		}
-		_ => {
-			warn!("Unknown item type: {}", item_type.get_name());
+		// Test item types have 2 cards
+		name if name.contains("Test") => Ok(2),
+		name => {
+			warn!("Unknown item type: {}", name);
 			// Return an error for unknown item types
-			return Err(anyhow!(
+			Err(anyhow!(
 				"Unable to construct cards for unknown item type: {}",
-				item_type.get_name()
-			));
+				name
+			))
 		}
 	}
+}
 
-	info!("Created {} cards for item {}", cards.len(), item.get_id());
+/// Today's date in the stored timezone, as stored in the daily markers
+fn local_today_string(conn: &mut SqliteConnection) -> Result<String, diesel::result::Error> {
+	let tz = get_settings_with_conn(conn)?.timezone;
+	Ok(local_today(Utc::now(), tz).to_string())
+}
 
-	// Return all created cards
-	Ok(cards)
+/// Works out which way round the card at `index` of an item asks its question
+///
+/// Basic and Vocabulary items put their forward card first, followed by the
+/// reverse one if they have it; other item types' cards have no direction.
+///
+/// ### Arguments
+///
+/// * `item_type` - The type of the item
+/// * `index` - The index of the card within the item
+///
+/// ### Returns
+///
+/// The card's direction, or None if it has none
+pub(crate) fn card_direction_for_item(item_type: &ItemType, index: usize) -> Option<CardDirection> {
+	match (item_type.get_name().as_str(), index) {
+		("Basic" | "Vocabulary", 0) => Some(CardDirection::Forward),
+		("Basic" | "Vocabulary", 1) => Some(CardDirection::Reverse),
+		_ => None,
+	}
 }
 
 /// Creates a new card in the database
@@ -125,10 +304,15 @@ pub async fn create_card(
 ) -> Result<Card> {
 	debug!("Creating new card");
 
-	let conn = &mut pool.get()?;
-
 	// Create a new card for the item
 	let new_card = Card::new(item_id.clone(), card_index, Utc::now(), priority);
+	insert_card(pool, new_card).await
+}
+
+/// Inserts an already built card into the database
+async fn insert_card(pool: &DbPool, new_card: Card) -> Result<Card> {
+	let conn = &mut pool.get()?;
+
 	let new_card_id = new_card.get_id();
 
 	debug!("Inserting card into database with id: {}", new_card_id);
@@ -365,8 +549,245 @@ pub async fn set_card_suspended(pool: &DbPool, card_id: &CardId, suspended: bool
 	Ok(())
 }
 
+/// Buries a card, hiding it from card lists and the study queue until
+/// `until`
+///
+/// Unlike suspension, burying is temporary: the card comes back on its own
+/// once `until` passes. Burying an already buried card replaces its bury time.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `card_id` - The ID of the card to bury
+/// * `until` - When the card returns, or None for the start of the next day
+///   in the configured timezone
+///
+/// ### Returns
+///
+/// A Result containing the time the card returns if successful
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database update operation fails
+/// - The card does not exist
+#[instrument(skip(pool), fields(card_id = %card_id))]
+pub async fn bury_card(
+	pool: &DbPool,
+	card_id: &CardId,
+	until: Option<DateTime<Utc>>,
+) -> Result<DateTime<Utc>> {
+	let until = match until {
+		Some(until) => until,
+		None => start_of_next_local_day(Utc::now(), super::get_settings(pool)?.timezone),
+	};
+	debug!("Burying card until {}", until);
+
+	let conn = &mut pool.get()?;
+
+	let updated = diesel::update(cards::table.find(card_id.clone()))
+		.set(cards::buried_until.eq(Some(until.naive_utc())))
+		.execute_with_retry(conn)
+		.await?;
+
+	if updated == 0 {
+		return Err(anyhow!("Card not found"));
+	}
+
+	info!("Buried card {} until {}", card_id, until);
+
+	Ok(until)
+}
+
+/// Sets or clears the free-text note attached to a card
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `card_id` - The ID of the card to annotate
+/// * `note` - The new note, or None to clear it
+///
+/// ### Returns
+///
+/// A Result indicating success or failure
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database update operation fails
+/// - The card does not exist
+#[instrument(skip(pool, note), fields(card_id = %card_id))]
+pub async fn set_card_note(pool: &DbPool, card_id: &CardId, note: Option<String>) -> Result<()> {
+	debug!("Setting card note");
+
+	let conn = &mut pool.get()?;
+
+	let updated = diesel::update(cards::table.find(card_id.clone()))
+		.set(cards::note.eq(note))
+		.execute_with_retry(conn)
+		.await?;
+
+	if updated == 0 {
+		return Err(anyhow!("Card not found"));
+	}
+
+	info!("Set note on card {}", card_id);
+
+	Ok(())
+}
+
+/// Sets or unsets how hard the user finds a card
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `card_id` - The ID of the card
+/// * `difficulty` - The new difficulty, between 0 and 1, or None to unset it
+///
+/// ### Returns
+///
+/// A Result indicating success or failure
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - The difficulty isn't between 0 and 1
+/// - Unable to get a connection from the pool
+/// - The database update operation fails
+/// - The card does not exist
+#[instrument(skip(pool), fields(card_id = %card_id))]
+pub async fn set_card_difficulty(
+	pool: &DbPool,
+	card_id: &CardId,
+	difficulty: Option<f32>,
+) -> Result<()> {
+	debug!("Setting card difficulty");
+
+	if let Some(difficulty) = difficulty
+		&& !(0.0..=1.0).contains(&difficulty)
+	{
+		return Err(anyhow!(
+			"Difficulty must be between 0 and 1, got {}",
+			difficulty
+		));
+	}
+
+	let conn = &mut pool.get()?;
+
+	let updated = diesel::update(cards::table.find(card_id.clone()))
+		.set(cards::difficulty.eq(difficulty))
+		.execute_with_retry(conn)
+		.await?;
+
+	if updated == 0 {
+		return Err(anyhow!("Card not found"));
+	}
+
+	info!("Set difficulty of card {}", card_id);
+
+	Ok(())
+}
+
+/// Sets the suspension state of every card matching a query
+///
+/// The query's `suspended_filter` is ignored, since the point is to flip
+/// cards from one state to the other: suspending only touches unsuspended
+/// matches and unsuspending only touches suspended ones, so cards that are
+/// already suspended keep their original suspension time. Buried cards are
+/// included, as burying only hides a card for the rest of the day. All matches are
+/// updated by a single statement, so either every one changes or none do.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `query` - The filters selecting which cards to update
+/// * `suspended` - The new suspension state for the matching cards
+///
+/// ### Returns
+///
+/// A Result containing the number of cards whose suspension state changed
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database update operation fails
+#[instrument(skip(pool, query), fields(suspended = %suspended))]
+pub async fn set_cards_suspended(
+	pool: &DbPool,
+	query: &GetQueryDto,
+	suspended: bool,
+) -> Result<usize> {
+	debug!(
+		"Setting suspension of cards matching {:?} to {}",
+		query, suspended
+	);
+
+	let query = GetQueryDto {
+		suspended_filter: SuspendedFilter::Include,
+		..query.clone()
+	};
+
+	let conn = &mut pool.get()?;
+
+	// Uses `.execute` rather than `execute_with_retry` for the same reason as
+	// `clear_sort_positions`: the boxed subquery borrows `query`.
+	let matching = cards::table.filter(
+		cards::id.eq_any(query_repo::cards_matching_including_buried(&query)),
+	);
+	let updated = if suspended {
+		diesel::update(matching.filter(cards::suspended.is_null()))
+			.set(cards::suspended.eq(Some(Utc::now().naive_utc())))
+			.execute(conn)?
+	} else {
+		diesel::update(matching.filter(cards::suspended.is_not_null()))
+			.set(cards::suspended.eq(None::<chrono::NaiveDateTime>))
+			.execute(conn)?
+	};
+
+	info!("Set suspension of {} cards to {}", updated, suspended);
+
+	Ok(updated)
+}
+
+/// Unburies every buried card, returning them all to the queue at once
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+///
+/// ### Returns
+///
+/// A Result containing the number of cards that had a bury time cleared
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database update operation fails
+#[instrument(skip(pool))]
+pub async fn unbury_all(pool: &DbPool) -> Result<usize> {
+	debug!("Unburying all cards");
+
+	let conn = &mut pool.get()?;
+
+	let unburied = diesel::update(cards::table.filter(cards::buried_until.is_not_null()))
+		.set(cards::buried_until.eq(None::<chrono::NaiveDateTime>))
+		.execute_with_retry(conn)
+		.await?;
+
+	info!("Unburied {} cards", unburied);
+
+	Ok(unburied)
+}
+
 /// Updates a card in the database
 ///
+/// The update only applies if the card is still at the version `card` was
+/// read at, so it can't silently overwrite a change made in the meantime.
+///
 /// ### Arguments
 ///
 /// * `pool` - A reference to the database connection pool
@@ -380,8 +801,9 @@ pub async fn set_card_suspended(pool: &DbPool, card_id: &CardId, suspended: bool
 ///
 /// Returns an error if:
 /// - Unable to get a connection from the pool
+/// - The card has changed since it was read (a `CardVersionConflictError`)
 /// - The database update operation fails
-#[instrument(skip(pool, card), fields(card_id = %card.get_id()))]
+#[instrument(skip(pool, card), fields(card_id = %card.get_id(), version = card.get_version()))]
 pub async fn update_card(pool: &DbPool, card: &Card) -> Result<()> {
 	debug!("Updating card");
 	let conn = &mut pool.get()?;
@@ -389,18 +811,43 @@ pub async fn update_card(pool: &DbPool, card: &Card) -> Result<()> {
 	let card_id = card.get_id();
 	debug!("Executing update for card_id: {}", card_id);
 
-	diesel::update(cards::table.find(card.get_id()))
-		.set((
-			cards::next_review.eq(card.get_next_review_raw()),
-			cards::last_review.eq(card.get_last_review_raw()),
-			cards::scheduler_data.eq(card.get_scheduler_data()),
-			cards::priority.eq(card.get_priority()),
-			cards::suspended.eq(card.get_suspended_raw()),
-			cards::sort_position.eq(card.get_sort_position()),
-			cards::priority_offset.eq(card.get_priority_offset()),
-		))
-		.execute_with_retry(conn)
-		.await?;
+	// The version check and the write have to be one statement, or another
+	// update could land between them. The `update_card_version` trigger
+	// bumps the version if anything changed.
+	let updated = diesel::update(
+		cards::table
+			.find(card.get_id())
+			.filter(cards::version.eq(card.get_version())),
+	)
+	.set((
+		cards::next_review.eq(card.get_next_review_raw()),
+		cards::last_review.eq(card.get_last_review_raw()),
+		cards::scheduler_data.eq(card.get_scheduler_data()),
+		cards::priority.eq(card.get_priority()),
+		cards::suspended.eq(card.get_suspended_raw()),
+		cards::sort_position.eq(card.get_sort_position()),
+		cards::priority_offset.eq(card.get_priority_offset()),
+	))
+	.execute_with_retry(conn)
+	.await?;
+
+	// No row matched: either the card is gone, which (as before versions)
+	// isn't an error, or it's at another version
+	if updated == 0 {
+		let exists = cards::table
+			.find(&card_id)
+			.count()
+			.get_result::<i64>(conn)?
+			> 0;
+		if exists {
+			warn!("Card {} changed since it was read", card_id);
+			return Err(CardVersionConflictError {
+				card_id,
+				expected_version: card.get_version(),
+			}
+			.into());
+		}
+	}
 
 	debug!("Successfully updated card_id: {}", card_id);
 	Ok(())
@@ -413,11 +860,26 @@ pub async fn update_card(pool: &DbPool, card: &Card) -> Result<()> {
 /// * `pool` - A reference to the database connection pool
 /// * `card_id` - The ID of the card to update
 /// * `priority` - The new priority for the card - must be between 0 and 1
+/// * `expected_version` - If given, only update the card if it is still at
+///   this version
 ///
 /// ### Returns
 ///
-/// A Result indicating success (Ok(())) or an error
-pub async fn update_card_priority(pool: &DbPool, card_id: &CardId, priority: f32) -> Result<Card> {
+/// A Result containing the updated card
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - The priority is out of range
+/// - The card doesn't exist
+/// - The card isn't at `expected_version` (a `CardVersionConflictError`)
+/// - The database update operation fails
+pub async fn update_card_priority(
+	pool: &DbPool,
+	card_id: &CardId,
+	priority: f32,
+	expected_version: Option<i32>,
+) -> Result<Card> {
 	// Precondition: priority must be in [0, 1]. This is a caller error, not
 	// a DB concern, so fail fast before we open any transaction.
 	if priority < 0.0 || priority > 1.0 {
@@ -431,10 +893,16 @@ pub async fn update_card_priority(pool: &DbPool, card_id: &CardId, priority: f32
 	// the ensure *inside* the transaction is the fix for the pre-review
 	// race: without it, a stale-marker day could see the next read's
 	// daily regen overwrite our offset=0 reset with a random value.
-	transaction_with_retry(conn, |c| {
-		let exists = cards::table.find(card_id).count().get_result::<i64>(c)? > 0;
-		if !exists {
-			return Err(diesel::result::Error::NotFound);
+	// The expected version, if the card turned out to be at another one
+	let conflict = transaction_with_retry(conn, |c| {
+		// A missing card is the NotFound mapped to "Card not found" below
+		let version = cards::table
+			.find(card_id)
+			.select(cards::version)
+			.first::<i32>(c)?;
+		let conflict = expected_version.filter(|&expected| expected != version);
+		if conflict.is_some() {
+			return Ok(conflict);
 		}
 
 		ensure_offsets_current(c)?;
@@ -446,7 +914,7 @@ pub async fn update_card_priority(pool: &DbPool, card_id: &CardId, priority: f32
 			))
 			.execute(c)?;
 
-		Ok(())
+		Ok(None)
 	})
 	.await
 	.map_err(|e| match e {
@@ -454,6 +922,18 @@ pub async fn update_card_priority(pool: &DbPool, card_id: &CardId, priority: f32
 		other => anyhow::Error::from(other),
 	})?;
 
+	if let Some(expected_version) = conflict {
+		warn!(
+			"Card {} changed since version {}",
+			card_id, expected_version
+		);
+		return Err(CardVersionConflictError {
+			card_id: card_id.clone(),
+			expected_version,
+		}
+		.into());
+	}
+
 	// Cache-aware read so the returned card carries fresh card_data.
 	let card = get_card(pool, card_id).await?;
 
@@ -581,7 +1061,8 @@ pub async fn move_card_to_bottom(pool: &DbPool, card_id: &CardId) -> Result<Card
 		.ok_or(anyhow!("Card not found after update"))
 }
 
-/// Which id was missing when `move_card_relative` short-circuits. Lets the
+/// Which id was missing when `move_card_relative` (or
+/// `swap_card_sort_positions`) short-circuits. Lets the
 /// tx body signal "not found" while preserving which-card-was-missing
 /// through to the outer error — `diesel::result::Error::NotFound` has no
 /// room to carry that.
@@ -703,13 +1184,100 @@ pub async fn move_card_relative(
 		.ok_or(anyhow!("Card not found after update"))
 }
 
-/// Transaction-body worker that actually zeroes every card's
-/// `sort_position` and bumps the `last_sort_clear_date` marker. The
-/// caller must already be inside an IMMEDIATE transaction so the bulk
-/// clear and the marker write commit atomically — otherwise a partial
-/// failure could leave cards reset but the marker stale, forcing a
-/// redundant clear on the next request and defeating the once-per-day
-/// invariant.
+/// Swaps two cards' sort positions
+///
+/// The two cards exchange `sort_position` values in one transaction, so each
+/// takes the other's place in the queue and every other card stays where it
+/// is. Swapping a card with itself changes nothing.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `card_a` - The ID of one card to swap
+/// * `card_b` - The ID of the other card to swap
+///
+/// ### Returns
+///
+/// A Result containing the two updated cards, in the order given
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - Either card doesn't exist
+/// - The database update fails
+#[instrument(skip(pool), fields(card_a = %card_a, card_b = %card_b))]
+pub async fn swap_card_sort_positions(
+	pool: &DbPool,
+	card_a: &CardId,
+	card_b: &CardId,
+) -> Result<(Card, Card)> {
+	debug!("Swapping card sort positions");
+
+	let conn = &mut pool.get()?;
+
+	// As in `move_card_relative`, the positions are read after the daily
+	// clear, inside the same IMMEDIATE transaction as the writes, so the
+	// swap exchanges the positions cards actually have now.
+	let outcome = transaction_with_retry(conn, |c| {
+		let a_exists = cards::table.find(card_a).count().get_result::<i64>(c)? > 0;
+		if !a_exists {
+			return Ok(Err(Missing::Source));
+		}
+		let b_exists = cards::table.find(card_b).count().get_result::<i64>(c)? > 0;
+		if !b_exists {
+			return Ok(Err(Missing::Target));
+		}
+
+		ensure_sort_positions_cleared(c)?;
+
+		let position_a: f32 = cards::table
+			.find(card_a)
+			.select(cards::sort_position)
+			.first::<f32>(c)?;
+		let position_b: f32 = cards::table
+			.find(card_b)
+			.select(cards::sort_position)
+			.first::<f32>(c)?;
+
+		diesel::update(cards::table.find(card_a.clone()))
+			.set(cards::sort_position.eq(position_b))
+			.execute(c)?;
+		diesel::update(cards::table.find(card_b.clone()))
+			.set(cards::sort_position.eq(position_a))
+			.execute(c)?;
+
+		Ok(Ok((position_a, position_b)))
+	})
+	.await?;
+
+	let (position_a, position_b) = match outcome {
+		Ok(positions) => positions,
+		Err(Missing::Source) => return Err(anyhow!("Card not found")),
+		Err(Missing::Target) => return Err(anyhow!("Card to swap with not found")),
+	};
+
+	info!(
+		"Swapped card {} (now at {}) with card {} (now at {})",
+		card_a, position_b, card_b, position_a
+	);
+
+	let updated_a = get_card(pool, card_a)
+		.await?
+		.ok_or(anyhow!("Card not found after update"))?;
+	let updated_b = get_card(pool, card_b)
+		.await?
+		.ok_or(anyhow!("Card not found after update"))?;
+	Ok((updated_a, updated_b))
+}
+
+/// Transaction-body worker that actually zeroes every card's
+/// `sort_position` and bumps the `last_sort_clear_date` marker. The
+/// caller must already be inside an IMMEDIATE transaction so the bulk
+/// clear and the marker write commit atomically — otherwise a partial
+/// failure could leave cards reset but the marker stale, forcing a
+/// redundant clear on the next request and defeating the once-per-day
+/// invariant.
 fn do_clear_all_sort_positions(
 	conn: &mut SqliteConnection,
 	today: &str,
@@ -762,11 +1330,12 @@ pub async fn clear_sort_positions(pool: &DbPool, query: &GetQueryDto) -> Result<
 		&& query.last_review_after.is_none()
 		&& query.suspended_filter == SuspendedFilter::default()
 		&& query.suspended_after.is_none()
-		&& query.suspended_before.is_none();
+		&& query.suspended_before.is_none()
+		&& query.state.is_none();
 
 	if is_default {
 		info!("Empty query, clearing all cards");
-		let today = Utc::now().date_naive().to_string();
+		let today = local_today_string(conn)?;
 
 		// One IMMEDIATE transaction wraps the bulk clear and the daily
 		// marker so a partial failure can't leave cards reset but the
@@ -788,11 +1357,10 @@ pub async fn clear_sort_positions(pool: &DbPool, query: &GetQueryDto) -> Result<
 		// one statement on SQLite so the driver-level busy handler covers
 		// transient lock retries; giving up the repo-layer retry is an
 		// acceptable trade for the atomic single-statement mutation.
-		let affected = diesel::update(
-			cards::table.filter(cards::id.eq_any(query_repo::cards_matching(query))),
-		)
-		.set(cards::sort_position.eq(0.0_f32))
-		.execute(conn)?;
+		let matching = query_repo::cards_matching_including_buried(query);
+		let affected = diesel::update(cards::table.filter(cards::id.eq_any(matching)))
+			.set(cards::sort_position.eq(0.0_f32))
+			.execute(conn)?;
 
 		info!("Cleared sort positions for {} matching cards", affected);
 	}
@@ -832,7 +1400,9 @@ pub async fn clear_card_sort_position(pool: &DbPool, card_id: &CardId) -> Result
 /// Regenerates priority offsets for all cards
 ///
 /// Sets each card's priority_offset to a random value in [-0.05, +0.05]
-/// and updates the last_offset_date in the metadata table.
+/// and updates the last_offset_date in the metadata table. If priority
+/// offsets are disabled, every card's priority_offset is set to 0.0
+/// instead.
 ///
 /// ### Arguments
 ///
@@ -846,14 +1416,21 @@ pub async fn regenerate_priority_offsets(pool: &DbPool) -> Result<()> {
 	debug!("Regenerating priority offsets for all cards");
 
 	let conn = &mut pool.get()?;
-	let today = Utc::now().date_naive().to_string();
+	let today = local_today_string(conn)?;
 
 	// One IMMEDIATE transaction wraps card-offset updates and the
 	// `last_offset_date` metadata write so a partial failure can't leave
 	// the DB with shuffled offsets but a stale staleness marker (which
 	// would re-shuffle on the next request, defeating the once-per-day
 	// invariant).
-	let count = transaction_with_retry(conn, |c| do_regenerate_priority_offsets(c, &today)).await?;
+	let count = transaction_with_retry(conn, |c| {
+		if get_settings_with_conn(c)?.priority_offsets_enabled {
+			do_regenerate_priority_offsets(c, &today)
+		} else {
+			do_zero_priority_offsets(c)
+		}
+	})
+	.await?;
 
 	info!("Regenerated priority offsets for {} cards", count);
 	Ok(())
@@ -890,6 +1467,47 @@ fn do_regenerate_priority_offsets(
 	Ok(card_ids.len())
 }
 
+/// The `last_offset_date` marker value meaning every card's offset has been
+/// zeroed because priority offsets are disabled. It's never a date, so the
+/// next ensure after offsets are turned back on shuffles them.
+const OFFSETS_DISABLED_MARKER: &str = "disabled";
+
+/// Transaction-body worker for when priority offsets are disabled: zeroes
+/// every card's `priority_offset` and sets the `last_offset_date` marker to
+/// [`OFFSETS_DISABLED_MARKER`]. New cards start with an offset of 0.0, so
+/// once this has run the offsets stay zeroed for as long as offsets are
+/// disabled. Same caller-owns-transaction contract as
+/// [`do_regenerate_priority_offsets`].
+fn do_zero_priority_offsets(conn: &mut SqliteConnection) -> Result<usize, diesel::result::Error> {
+	let count = diesel::update(cards::table.filter(cards::priority_offset.ne(0.0_f32)))
+		.set(cards::priority_offset.eq(0.0_f32))
+		.execute(conn)?;
+
+	diesel::replace_into(metadata::table)
+		.values((
+			metadata::key.eq("last_offset_date"),
+			metadata::value.eq(OFFSETS_DISABLED_MARKER),
+		))
+		.execute(conn)?;
+
+	Ok(count)
+}
+
+/// True iff the priority offsets need no work: the `last_offset_date`
+/// marker holds today's date if offsets are enabled, or
+/// [`OFFSETS_DISABLED_MARKER`] if they're disabled.
+fn are_offsets_current(
+	conn: &mut SqliteConnection,
+	today: &str,
+) -> Result<bool, diesel::result::Error> {
+	let expected = if get_settings_with_conn(conn)?.priority_offsets_enabled {
+		today
+	} else {
+		OFFSETS_DISABLED_MARKER
+	};
+	is_marker_today(conn, "last_offset_date", expected)
+}
+
 /// True iff the metadata row keyed `key` holds today's date string.
 ///
 /// Used by the ensure functions as their staleness check. Pulled out so
@@ -910,7 +1528,9 @@ fn is_marker_today(
 
 /// Ensures priority offsets are current. Reads the `last_offset_date`
 /// marker and, if stale, regenerates every card's `priority_offset` and
-/// bumps the marker; if today, returns Ok with no DB writes.
+/// bumps the marker; if today, returns Ok with no DB writes. While priority
+/// offsets are disabled, the daily regeneration is skipped and any non-zero
+/// offsets are zeroed instead.
 ///
 /// **The caller owns the transaction.** Pass a conn that's already
 /// inside one (typically IMMEDIATE for write paths that mix this with a
@@ -923,13 +1543,16 @@ fn is_marker_today(
 pub(crate) fn ensure_offsets_current(
 	conn: &mut SqliteConnection,
 ) -> Result<(), diesel::result::Error> {
-	let today = Utc::now().date_naive().to_string();
-	if is_marker_today(conn, "last_offset_date", &today)? {
+	let today = local_today_string(conn)?;
+	if are_offsets_current(conn, &today)? {
 		debug!("Priority offsets are current");
 		Ok(())
-	} else {
+	} else if get_settings_with_conn(conn)?.priority_offsets_enabled {
 		debug!("Priority offsets are stale, regenerating");
 		do_regenerate_priority_offsets(conn, &today).map(|_| ())
+	} else {
+		debug!("Priority offsets are disabled, zeroing them");
+		do_zero_priority_offsets(conn).map(|_| ())
 	}
 }
 
@@ -942,7 +1565,7 @@ pub(crate) fn ensure_offsets_current(
 pub(crate) fn ensure_sort_positions_cleared(
 	conn: &mut SqliteConnection,
 ) -> Result<(), diesel::result::Error> {
-	let today = Utc::now().date_naive().to_string();
+	let today = local_today_string(conn)?;
 	if is_marker_today(conn, "last_sort_clear_date", &today)? {
 		debug!("Sort positions already cleared today");
 		Ok(())
@@ -956,7 +1579,8 @@ pub(crate) fn ensure_sort_positions_cleared(
 /// clear, with an up-front fast path.
 ///
 /// Reads both `last_offset_date` and `last_sort_clear_date` first; if
-/// both are today, returns Ok without entering either per-component
+/// both are current (today, or for the offsets, zeroed while they're
+/// disabled), returns Ok without entering either per-component
 /// ensure — that fast path is the whole point of running this from a
 /// DEFERRED transaction in the read path, since it leaves the
 /// connection on SHARED only and lets concurrent readers proceed in
@@ -969,14 +1593,14 @@ pub(crate) fn ensure_sort_positions_cleared(
 pub(crate) fn ensure_daily_state_current(
 	conn: &mut SqliteConnection,
 ) -> Result<(), diesel::result::Error> {
-	let today = Utc::now().date_naive().to_string();
-	let offsets_today = is_marker_today(conn, "last_offset_date", &today)?;
+	let today = local_today_string(conn)?;
+	let offsets_current = are_offsets_current(conn, &today)?;
 	let sort_today = is_marker_today(conn, "last_sort_clear_date", &today)?;
-	if offsets_today && sort_today {
+	if offsets_current && sort_today {
 		debug!("Daily state already current");
 		return Ok(());
 	}
-	if !offsets_today {
+	if !offsets_current {
 		ensure_offsets_current(conn)?;
 	}
 	if !sort_today {
@@ -1019,7 +1643,8 @@ pub fn list_all_cards(pool: &DbPool) -> Result<Vec<Card>> {
 /// the staleness check guarantee eventual freshness on the next fetch.
 ///
 /// Results are ordered by `sort_position DESC` (positive first, 0 = unsorted,
-/// negative last), tiebroken by effective priority `(priority + priority_offset) DESC`.
+/// negative last), tiebroken by effective priority `(priority + priority_offset) DESC`,
+/// then by id.
 #[instrument(skip(pool, query))]
 pub async fn list_cards(pool: &DbPool, query: &GetQueryDto) -> Result<Vec<Card>, CardFetchError> {
 	// See `get_card` for why DEFERRED + separate conn + drop-before-cache.
@@ -1040,10 +1665,602 @@ pub async fn list_cards(pool: &DbPool, query: &GetQueryDto) -> Result<Vec<Card>,
 		.order_by((
 			cards::sort_position.desc(),
 			diesel::dsl::sql::<diesel::sql_types::Float>("(priority + priority_offset) DESC"),
+			cards::id.asc(),
 		))
 		.load::<Card>(conn)?)
 }
 
+/// Cache-aware page of `list_cards`: the `limit` cards matching `query` after
+/// skipping the first `offset`, in the same order as `list_cards`.
+///
+/// Ties in that order are broken by id, so paging through with increasing
+/// offsets visits every matching card exactly once as long as nothing
+/// changes in between. Only the page's cards have their `card_data` cache
+/// ensured, so reading a large set page by page never holds (or refreshes)
+/// all of it at once.
+///
+/// ### Returns
+///
+/// Up to `limit` cards with fresh `card_data`; fewer means the last page
+#[instrument(skip(pool, query))]
+pub async fn list_cards_page(
+	pool: &DbPool,
+	query: &GetQueryDto,
+	limit: u32,
+	offset: u32,
+) -> Result<Vec<Card>, CardFetchError> {
+	// See `get_card` for why DEFERRED + separate conn + drop-before-cache.
+	let page_ids = {
+		let conn = &mut pool
+			.get()
+			.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+		deferred_transaction_with_retry(conn, ensure_daily_state_current)
+			.await
+			.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+
+		cards::table
+			.filter(cards::id.eq_any(query_repo::cards_matching(query)))
+			.order_by((
+				cards::sort_position.desc(),
+				diesel::dsl::sql::<diesel::sql_types::Float>("(priority + priority_offset) DESC"),
+				cards::id.asc(),
+			))
+			.select(cards::id)
+			.limit(i64::from(limit))
+			.offset(i64::from(offset))
+			.load::<CardId>(conn)?
+	};
+
+	card_cache::ensure_list_cards_cache(pool, CacheScope::Cards(&page_ids)).await?;
+	let conn = &mut pool
+		.get()
+		.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+	let mut by_id: HashMap<CardId, Card> = cards::table
+		.filter(cards::id.eq_any(&page_ids))
+		.load::<Card>(conn)?
+		.into_iter()
+		.map(|card| (card.get_id(), card))
+		.collect();
+
+	// A card deleted since its id was read is skipped
+	Ok(page_ids.iter().filter_map(|id| by_id.remove(id)).collect())
+}
+
+/// The most cards [`get_cards_by_ids`] fetches at once
+pub const MAX_BATCH_GET_CARDS: usize = 500;
+
+/// Cache-aware fetch of a set of cards by id, in a single query
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `card_ids` - The IDs of the cards to fetch
+///
+/// ### Returns
+///
+/// One entry per requested id, in request order: the card with fresh
+/// `card_data`, or None if no card has that id. A repeated id gets an entry
+/// each time.
+#[instrument(skip(pool, card_ids), fields(count = card_ids.len()))]
+pub async fn get_cards_by_ids(
+	pool: &DbPool,
+	card_ids: &[CardId],
+) -> Result<Vec<Option<Card>>, CardFetchError> {
+	// See `get_card` for why DEFERRED + separate conn + drop-before-cache.
+	{
+		let conn = &mut pool
+			.get()
+			.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+		deferred_transaction_with_retry(conn, ensure_daily_state_current)
+			.await
+			.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+	}
+	card_cache::ensure_list_cards_cache(pool, CacheScope::Cards(card_ids)).await?;
+	let conn = &mut pool
+		.get()
+		.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+	let by_id: HashMap<CardId, Card> = cards::table
+		.filter(cards::id.eq_any(card_ids))
+		.load::<Card>(conn)?
+		.into_iter()
+		.map(|card| (card.get_id(), card))
+		.collect();
+
+	Ok(card_ids.iter().map(|id| by_id.get(id).cloned()).collect())
+}
+
+/// The cards [`next_card`] and [`random_card`] choose between: those
+/// matching `query` that are due.
+///
+/// "Due" means `next_review` is at or before now, unless the caller already
+/// set `next_review_before`, in which case that cutoff is used as-is. With
+/// `cram` set, cards don't have to be due at all. Suspended cards are
+/// excluded unless the query's `suspended_filter` says otherwise.
+fn due_cards_matching(query: &GetQueryDto) -> query_repo::BoxedCardIdQuery<'_> {
+	let mut due = cards::table
+		.filter(cards::id.eq_any(query_repo::cards_matching(query)))
+		.select(cards::id)
+		.into_boxed();
+	if query.next_review_before.is_none() && !query.cram.unwrap_or(false) {
+		due = due.filter(cards::next_review.le(Utc::now().naive_utc()));
+	}
+	due
+}
+
+/// Cache-aware fetch of the next card to review: the first card that
+/// `list_cards` would return for `query`, restricted to cards that are due
+/// (see [`due_cards_matching`]).
+///
+/// Ordering is the same as `list_cards` (`sort_position DESC`, then
+/// effective priority DESC).
+///
+/// Only the chosen card has its `card_data` cache ensured, rather than
+/// every card matching the filter.
+///
+/// ### Returns
+///
+/// `Ok(Some(card))` with fresh `card_data`, or `Ok(None)` when no card is due.
+#[instrument(skip(pool, query))]
+pub async fn next_card(pool: &DbPool, query: &GetQueryDto) -> Result<Option<Card>, CardFetchError> {
+	// See `get_card` for why DEFERRED + separate conn + drop-before-cache.
+	// The daily ensure has to run before the pick, since it can change
+	// `priority_offset` and so the ordering.
+	let next_id = {
+		let conn = &mut pool
+			.get()
+			.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+		deferred_transaction_with_retry(conn, ensure_daily_state_current)
+			.await
+			.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+
+		due_cards_matching(query)
+			.order_by((
+				cards::sort_position.desc(),
+				diesel::dsl::sql::<diesel::sql_types::Float>("(priority + priority_offset) DESC"),
+			))
+			.first::<CardId>(conn)
+			.optional()?
+	};
+
+	match next_id {
+		Some(card_id) => Ok(card_cache::ensure_and_read_card(pool, &card_id).await?),
+		None => Ok(None),
+	}
+}
+
+/// Cache-aware fetch of a random card to review: one card picked uniformly
+/// from those [`next_card`] would choose between (see
+/// [`due_cards_matching`]).
+///
+/// Only the order differs, and since the daily state only affects the
+/// order, it isn't brought up to date first.
+///
+/// ### Returns
+///
+/// `Ok(Some(card))` with fresh `card_data`, or `Ok(None)` when no card is due.
+#[instrument(skip(pool, query))]
+pub async fn random_card(
+	pool: &DbPool,
+	query: &GetQueryDto,
+) -> Result<Option<Card>, CardFetchError> {
+	let random_id = {
+		let conn = &mut pool
+			.get()
+			.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+		due_cards_matching(query)
+			.order_by(diesel::dsl::sql::<diesel::sql_types::Integer>("RANDOM()"))
+			.first::<CardId>(conn)
+			.optional()?
+	};
+
+	match random_id {
+		Some(card_id) => Ok(card_cache::ensure_and_read_card(pool, &card_id).await?),
+		None => Ok(None),
+	}
+}
+
+/// Number of failed (rating 1) reviews that makes a card a leech, as in Anki
+pub const DEFAULT_LEECH_THRESHOLD: u32 = 8;
+
+/// Cache-aware fetch of leeches: cards with at least `threshold` reviews
+/// rated 1 (again), most-failed first.
+///
+/// When `suspend` is set, each leech is suspended before being returned.
+/// Leeches that are already suspended are still listed.
+///
+/// ### Returns
+///
+/// The leech cards with fresh `card_data`
+#[instrument(skip(pool))]
+pub async fn list_leeches(
+	pool: &DbPool,
+	threshold: u32,
+	suspend: bool,
+) -> Result<Vec<Card>, CardFetchError> {
+	let leech_ids = {
+		let conn = &mut pool
+			.get()
+			.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+		cards::table
+			.inner_join(reviews::table)
+			.filter(reviews::rating.eq(1))
+			.group_by(cards::id)
+			.having(diesel::dsl::count_star().ge(threshold as i64))
+			.order_by((diesel::dsl::count_star().desc(), cards::id))
+			.select(cards::id)
+			.load::<CardId>(conn)?
+	};
+
+	info!("Found {} leeches", leech_ids.len());
+
+	let mut leeches = Vec::with_capacity(leech_ids.len());
+	for card_id in leech_ids {
+		if suspend {
+			set_card_suspended(pool, &card_id, true).await?;
+		}
+		if let Some(card) = card_cache::ensure_and_read_card(pool, &card_id).await? {
+			leeches.push(card);
+		}
+	}
+
+	Ok(leeches)
+}
+
+/// Number of cards `list_hardest_cards` returns when no limit is given
+pub const DEFAULT_HARDEST_CARDS_LIMIT: u32 = 10;
+
+/// Most cards `list_hardest_cards` will return, whatever is asked for
+pub const MAX_HARDEST_CARDS_LIMIT: u32 = 100;
+
+/// Fewest reviews a card needs before `list_hardest_cards` ranks it, so that
+/// one unlucky review doesn't put a card at the top
+pub const DEFAULT_HARDEST_CARDS_MIN_REVIEWS: u32 = 5;
+
+/// Cache-aware fetch of the hardest cards: those with the highest share of
+/// reviews rated 1 (again), hardest first.
+///
+/// Cards with fewer than `min_reviews` reviews aren't ranked. Cards with the
+/// same failure rate come most-reviewed first.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `limit` - The most cards to return, capped at `MAX_HARDEST_CARDS_LIMIT`
+/// * `min_reviews` - The fewest reviews a card needs to be ranked
+///
+/// ### Returns
+///
+/// The hardest cards with fresh `card_data`
+#[instrument(skip(pool))]
+pub async fn list_hardest_cards(
+	pool: &DbPool,
+	limit: u32,
+	min_reviews: u32,
+) -> Result<Vec<Card>, CardFetchError> {
+	let limit = limit.min(MAX_HARDEST_CARDS_LIMIT);
+	let hardest_ids = {
+		let conn = &mut pool
+			.get()
+			.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+		cards::table
+			.inner_join(reviews::table)
+			.group_by(cards::id)
+			.having(diesel::dsl::count_star().ge(min_reviews as i64))
+			.order_by((
+				diesel::dsl::sql::<diesel::sql_types::Double>(
+					"CAST(SUM(reviews.rating = 1) AS REAL) / COUNT(*) DESC",
+				),
+				diesel::dsl::count_star().desc(),
+				cards::id,
+			))
+			.limit(limit as i64)
+			.select(cards::id)
+			.load::<CardId>(conn)?
+	};
+
+	info!("Found {} hardest cards", hardest_ids.len());
+
+	let mut hardest = Vec::with_capacity(hardest_ids.len());
+	for card_id in hardest_ids {
+		if let Some(card) = card_cache::ensure_and_read_card(pool, &card_id).await? {
+			hardest.push(card);
+		}
+	}
+
+	Ok(hardest)
+}
+
+/// Number of cards `list_related_cards` returns when no limit is given
+pub const DEFAULT_RELATED_CARDS_LIMIT: u32 = 10;
+
+/// Most cards `list_related_cards` will return, whatever is asked for
+pub const MAX_RELATED_CARDS_LIMIT: u32 = 100;
+
+/// Cache-aware fetch of the cards most related to a card: the cards of other
+/// items sharing the most tags with its item, most shared tags first.
+///
+/// Cards of the card's own item are never included, nor are cards whose item
+/// shares no tags with it.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `card_id` - The ID of the card to find related cards for
+/// * `limit` - The most cards to return, capped at `MAX_RELATED_CARDS_LIMIT`
+///
+/// ### Returns
+///
+/// The related cards with fresh `card_data`, or None if the card doesn't exist
+#[instrument(skip(pool), fields(card_id = %card_id))]
+pub async fn list_related_cards(
+	pool: &DbPool,
+	card_id: &CardId,
+	limit: u32,
+) -> Result<Option<Vec<Card>>, CardFetchError> {
+	let limit = limit.min(MAX_RELATED_CARDS_LIMIT);
+	let related_ids = {
+		let conn = &mut pool
+			.get()
+			.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+
+		let Some(item_id) = cards::table
+			.find(card_id)
+			.select(cards::item_id)
+			.first::<ItemId>(conn)
+			.optional()?
+		else {
+			debug!("Card not found");
+			return Ok(None);
+		};
+
+		// Rank the other items by how many of the item's tags they share
+		let item_tag_ids: Vec<TagId> = item_tags::table
+			.filter(item_tags::item_id.eq(&item_id))
+			.select(item_tags::tag_id)
+			.load(conn)?;
+		let ranked_items: Vec<ItemId> = item_tags::table
+			.filter(item_tags::tag_id.eq_any(item_tag_ids))
+			.filter(item_tags::item_id.ne(&item_id))
+			.group_by(item_tags::item_id)
+			.order_by((diesel::dsl::count_star().desc(), item_tags::item_id))
+			.select(item_tags::item_id)
+			.load(conn)?;
+		let rank: HashMap<&ItemId, usize> = ranked_items
+			.iter()
+			.enumerate()
+			.map(|(i, item_id)| (item_id, i))
+			.collect();
+
+		// Their cards, in the order of their items
+		let mut related: Vec<(CardId, ItemId)> = cards::table
+			.filter(cards::item_id.eq_any(&ranked_items))
+			.order_by(cards::id)
+			.select((cards::id, cards::item_id))
+			.load(conn)?;
+		related.sort_by_key(|(_, item_id)| rank[item_id]);
+		related
+			.into_iter()
+			.take(limit as usize)
+			.map(|(card_id, _)| card_id)
+			.collect::<Vec<_>>()
+	};
+
+	info!("Found {} related cards", related_ids.len());
+
+	let mut related = Vec::with_capacity(related_ids.len());
+	for card_id in related_ids {
+		if let Some(card) = card_cache::ensure_and_read_card(pool, &card_id).await? {
+			related.push(card);
+		}
+	}
+
+	Ok(Some(related))
+}
+
+/// Number of days `forecast_due_cards` covers when none is given
+pub const DEFAULT_FORECAST_DAYS: u32 = 7;
+
+/// Most days `forecast_due_cards` will cover, whatever is asked for
+pub const MAX_FORECAST_DAYS: u32 = 365;
+
+/// Helper struct for the forecast query result
+#[derive(QueryableByName, Debug)]
+struct ForecastRow {
+	/// -1 for overdue cards, otherwise days from today
+	#[diesel(sql_type = diesel::sql_types::Integer)]
+	bucket: i32,
+	#[diesel(sql_type = diesel::sql_types::BigInt)]
+	due_count: i64,
+}
+
+/// Counts the unsuspended cards coming due on each of the next `days` local
+/// days, without loading any cards
+///
+/// Days are counted in the configured timezone. Cards due at or before now
+/// fall in the overdue bucket rather than today's, so today's bucket holds
+/// what is still to come today; cards due after the last day are left out.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `days` - How many days to cover, starting with today (at most
+///   [`MAX_FORECAST_DAYS`])
+///
+/// ### Returns
+///
+/// The overdue bucket, followed by one bucket per day in order, including
+/// days with nothing due
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+#[instrument(skip(pool))]
+pub fn forecast_due_cards(pool: &DbPool, days: u32) -> Result<Vec<ForecastBucket>> {
+	let days = days.min(MAX_FORECAST_DAYS);
+	let conn = &mut pool.get()?;
+
+	let now = Utc::now();
+	let tz = get_settings_with_conn(conn)?.timezone;
+	let today = local_today(now, tz);
+	let dates: Vec<_> = (0..days).map(|i| today + Days::new(i.into())).collect();
+
+	// Bucket each card by the first day boundary it falls before, all in one
+	// grouped query; cards past the last boundary get no bucket
+	let mut case = String::from("CASE WHEN next_review <= ? THEN -1");
+	for i in 0..dates.len() {
+		case.push_str(&format!(" WHEN next_review < ? THEN {}", i));
+	}
+	case.push_str(" END");
+	let mut query = diesel::sql_query(format!(
+		"SELECT {} AS bucket, COUNT(*) AS due_count FROM cards \
+		WHERE suspended IS NULL \
+		GROUP BY bucket HAVING bucket IS NOT NULL",
+		case
+	))
+	.into_boxed()
+	.bind::<diesel::sql_types::Timestamp, _>(now.naive_utc());
+	for date in &dates {
+		let end = start_of_local_day(*date + Days::new(1), tz);
+		query = query.bind::<diesel::sql_types::Timestamp, _>(end.naive_utc());
+	}
+	let rows = query.load::<ForecastRow>(conn)?;
+
+	let mut buckets: Vec<ForecastBucket> = std::iter::once(None)
+		.chain(dates.into_iter().map(Some))
+		.map(|date| ForecastBucket { date, due_count: 0 })
+		.collect();
+	for row in rows {
+		if let Some(bucket) = buckets.get_mut((row.bucket + 1) as usize) {
+			bucket.due_count = row.due_count;
+		}
+	}
+
+	debug!("Forecast {} days", days);
+	Ok(buckets)
+}
+
+/// Lists the IDs of the cards due now, without loading the cards
+///
+/// A card is due if its next review is at or before now and it's neither
+/// suspended nor buried, the same as the cards `GET /cards/next` picks from.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+///
+/// ### Returns
+///
+/// The IDs of the due cards, the longest overdue first
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+#[instrument(skip(pool))]
+pub fn list_due_card_ids(pool: &DbPool) -> Result<Vec<CardId>> {
+	let conn = &mut pool.get()?;
+
+	let now = Utc::now().naive_utc();
+	let card_ids = cards::table
+		.filter(cards::next_review.le(now))
+		.filter(cards::suspended.is_null())
+		.filter(cards::buried_until.is_null().or(cards::buried_until.le(now)))
+		.order((cards::next_review.asc(), cards::id.asc()))
+		.select(cards::id)
+		.load::<CardId>(conn)?;
+
+	debug!("{} cards due", card_ids.len());
+	Ok(card_ids)
+}
+
+/// Interval, in days, at which a card counts as mature, as in Anki
+pub const MATURE_INTERVAL_DAYS: i32 = 21;
+
+/// A card's interval in days, as SQL, counting the days it was scheduled
+/// for
+///
+/// The schedulers set `next_review` an hour short of a whole number of days,
+/// so a card comes due a little before the time it was last reviewed at; the
+/// hour is added back here so a card scheduled for a day counts as a day.
+/// The gap is rounded to whole seconds first, so that `julianday`'s floating
+/// point doesn't leave it a hair short of the day.
+pub(crate) const SCHEDULED_INTERVAL_DAYS_SQL: &str = "((round((julianday(cards.next_review) \
+	- julianday(cards.last_review)) * 86400) + 3600) / 86400.0)";
+
+/// Helper struct for the stats overview query result
+#[derive(QueryableByName, Debug)]
+struct StatsOverviewRow {
+	#[diesel(sql_type = diesel::sql_types::BigInt)]
+	new_count: i64,
+	#[diesel(sql_type = diesel::sql_types::BigInt)]
+	learning_count: i64,
+	#[diesel(sql_type = diesel::sql_types::BigInt)]
+	young_count: i64,
+	#[diesel(sql_type = diesel::sql_types::BigInt)]
+	mature_count: i64,
+	#[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Double>)]
+	average_difficulty: Option<f64>,
+}
+
+/// Counts the unsuspended cards in each stage of learning, and averages
+/// their difficulty, without loading any cards
+///
+/// Cards are bucketed by their interval, the days they were scheduled for
+/// (see [`SCHEDULED_INTERVAL_DAYS_SQL`]): new cards have never been
+/// reviewed, learning cards have an interval under a day, young cards under
+/// [`MATURE_INTERVAL_DAYS`], and mature cards the rest. The learning and
+/// review split matches the `state` filter on `GET /cards`.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+///
+/// ### Returns
+///
+/// The count in each bucket, and the average difficulty from the cards'
+/// scheduler data
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+#[instrument(skip(pool))]
+pub fn get_stats_overview(pool: &DbPool) -> Result<StatsOverview> {
+	let conn = &mut pool.get()?;
+
+	let interval = SCHEDULED_INTERVAL_DAYS_SQL;
+	let row = diesel::sql_query(format!(
+		"SELECT \
+		COALESCE(SUM(last_review IS NULL), 0) AS new_count, \
+		COALESCE(SUM(last_review IS NOT NULL AND {interval} < 1), 0) AS learning_count, \
+		COALESCE(SUM(last_review IS NOT NULL AND {interval} >= 1 AND {interval} < ?), 0) \
+			AS young_count, \
+		COALESCE(SUM(last_review IS NOT NULL AND {interval} >= ?), 0) AS mature_count, \
+		AVG(json_extract(scheduler_data, '$.difficulty')) AS average_difficulty \
+		FROM cards WHERE suspended IS NULL"
+	))
+	.bind::<diesel::sql_types::Integer, _>(MATURE_INTERVAL_DAYS)
+	.bind::<diesel::sql_types::Integer, _>(MATURE_INTERVAL_DAYS)
+	.get_result::<StatsOverviewRow>(conn)?;
+
+	debug!(
+		"{} new, {} learning, {} young and {} mature cards",
+		row.new_count, row.learning_count, row.young_count, row.mature_count
+	);
+
+	Ok(StatsOverview {
+		new_count: row.new_count,
+		learning_count: row.learning_count,
+		young_count: row.young_count,
+		mature_count: row.mature_count,
+		average_difficulty: row.average_difficulty,
+	})
+}
+
 /// Cache-aware list: ensures `card_data` is current for every card owned by
 /// `item_id`, then returns them. Used by `GET /items/{item_id}/cards`.
 #[instrument(skip(pool), fields(item_id = %item_id))]
@@ -1064,6 +2281,48 @@ pub async fn list_cards_by_item(
 	Ok(get_cards_for_item(pool, item_id)?)
 }
 
+/// Cache-aware composite read: an item together with its cards and tags.
+/// Used by `GET /items/{item_id}/full`.
+///
+/// The caches are ensured first, exactly as in `list_cards_by_item`; the
+/// item, cards and tags are then read in one deferred transaction on a
+/// single connection, so the three sections agree with each other.
+///
+/// Returns `Ok(None)` if the item doesn't exist.
+#[instrument(skip(pool), fields(item_id = %item_id))]
+pub async fn get_item_with_cards_and_tags(
+	pool: &DbPool,
+	item_id: &ItemId,
+) -> Result<Option<ItemWithCardsAndTags>, CardFetchError> {
+	let conn = &mut pool
+		.get()
+		.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+	deferred_transaction_with_retry(conn, ensure_daily_state_current)
+		.await
+		.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+	card_cache::ensure_list_cards_cache(pool, CacheScope::Item(item_id)).await?;
+
+	let full = deferred_transaction_with_retry(conn, |conn| {
+		let Some(item) = items::table.find(item_id).first::<Item>(conn).optional()? else {
+			return Ok(None);
+		};
+		let cards = cards::table
+			.filter(cards::item_id.eq(item_id))
+			.order_by(cards::card_index.asc())
+			.load::<Card>(conn)?;
+		let tags = tags::table
+			.inner_join(item_tags::table.on(tags::id.eq(item_tags::tag_id)))
+			.filter(item_tags::item_id.eq(item_id))
+			.select(tags::all_columns)
+			.load::<Tag>(conn)?;
+		Ok(Some(ItemWithCardsAndTags { item, cards, tags }))
+	})
+	.await
+	.map_err(|e| CardFetchError::Other(anyhow::Error::from(e)))?;
+
+	Ok(full)
+}
+
 #[cfg(test)]
 mod prop_tests;
 #[cfg(test)]
@@ -1,5 +1,5 @@
 use super::*;
-use crate::GetQueryDto;
+use crate::{GetQueryDto, TagMatch};
 use crate::db::transaction_with_retry;
 use crate::models::{ItemTypeId, TagId};
 use crate::repo::tests::setup_test_db;
@@ -188,7 +188,7 @@ proptest! {
 			).await.unwrap();
 			let card = get_cards_for_item(&pool, &item.get_id()).unwrap().remove(0);
 
-			let updated = update_card_priority(&pool, &card.get_id(), priority).await.unwrap();
+			let updated = update_card_priority(&pool, &card.get_id(), priority, None).await.unwrap();
 			prop_assert!((updated.get_priority() - priority).abs() < 1e-6);
 
 			let retrieved = get_card_raw(&pool, &card.get_id()).unwrap().unwrap();
@@ -211,7 +211,7 @@ proptest! {
 			let card = get_cards_for_item(&pool, &item.get_id()).unwrap().remove(0);
 			let original_priority = card.get_priority();
 
-			let result = update_card_priority(&pool, &card.get_id(), priority).await;
+			let result = update_card_priority(&pool, &card.get_id(), priority, None).await;
 			prop_assert!(result.is_err());
 
 			let retrieved = get_card_raw(&pool, &card.get_id()).unwrap().unwrap();
@@ -699,6 +699,11 @@ proptest! {
 				 split_priority: None,
 				parent_item_id: None,
 				child_item_id: None,
+				state: None,
+				include_descendant_tags: None,
+				cram: None,
+				exclude_item_type_ids: Vec::new(),
+				tag_match: TagMatch::default(),
 			 };
 
 			let all_cards = list_all_cards(&pool).unwrap();
@@ -1198,14 +1203,14 @@ proptest! {
 			let item_a = create_item(&pool, &item_type.get_id(), "A".to_string(),
 				json!({"front": "F", "back": "B"})).await.unwrap();
 			let card_a = get_cards_for_item(&pool, &item_a.get_id()).unwrap().remove(0);
-			update_card_priority(&pool, &card_a.get_id(), low_prio).await.unwrap();
+			update_card_priority(&pool, &card_a.get_id(), low_prio, None).await.unwrap();
 			move_card_to_top(&pool, &card_a.get_id()).await.unwrap();
 
 			// Card B: high priority, no sort_position
 			let item_b = create_item(&pool, &item_type.get_id(), "B".to_string(),
 				json!({"front": "F", "back": "B"})).await.unwrap();
 			let card_b = get_cards_for_item(&pool, &item_b.get_id()).unwrap().remove(0);
-			update_card_priority(&pool, &card_b.get_id(), high_prio).await.unwrap();
+			update_card_priority(&pool, &card_b.get_id(), high_prio, None).await.unwrap();
 
 			let query = GetQueryDto {
 				suspended_filter: SuspendedFilter::Include,
@@ -1232,7 +1237,7 @@ proptest! {
 			// Set distinct priorities with offsets, no sort_positions
 			for (i, c) in cards.iter().enumerate() {
 				let priority = (i as f32 + 1.0) / (n as f32 + 1.0);
-				update_card_priority(&pool, &c.get_id(), priority).await.unwrap();
+				update_card_priority(&pool, &c.get_id(), priority, None).await.unwrap();
 				// Set a small offset that doesn't change relative ordering
 				let mut card = get_card_raw(&pool, &c.get_id()).unwrap().unwrap();
 				card.set_priority_offset(0.001 * i as f32);
@@ -1315,6 +1320,11 @@ proptest! {
 				 split_priority: None,
 				parent_item_id: None,
 				child_item_id: None,
+				state: None,
+				include_descendant_tags: None,
+				cram: None,
+				exclude_item_type_ids: Vec::new(),
+				tag_match: TagMatch::default(),
 			 };
 
 			// Compute oracle matching set
@@ -1414,6 +1424,11 @@ proptest! {
 				 split_priority: None,
 				parent_item_id: None,
 				child_item_id: None,
+				state: None,
+				include_descendant_tags: None,
+				cram: None,
+				exclude_item_type_ids: Vec::new(),
+				tag_match: TagMatch::default(),
 			 };
 
 			// Compute oracle matching set
@@ -1486,6 +1501,82 @@ proptest! {
 	}
 }
 
+proptest! {
+	/// T4.16: Swapping two cards twice restores the original order
+	#[test]
+	fn prop_t4_16_swap_twice_is_identity(
+		n in 2usize..12,
+		a in any::<prop::sample::Index>(),
+		b in any::<prop::sample::Index>(),
+	) {
+		let rt = tokio::runtime::Runtime::new().unwrap();
+		rt.block_on(async {
+			let pool = setup_test_db();
+			let cards = create_n_cards(&pool, n).await;
+			for c in &cards {
+				move_card_to_top(&pool, &c.get_id()).await.unwrap();
+			}
+			let card_a = cards[a.index(n)].get_id();
+			let card_b = cards[b.index(n)].get_id();
+
+			let query = GetQueryDto {
+				suspended_filter: SuspendedFilter::Include,
+				..Default::default()
+			};
+			let before: Vec<_> = list_cards(&pool, &query).await.unwrap()
+				.iter().map(|c| c.get_id()).collect();
+
+			swap_card_sort_positions(&pool, &card_a, &card_b).await.unwrap();
+			swap_card_sort_positions(&pool, &card_a, &card_b).await.unwrap();
+
+			let after: Vec<_> = list_cards(&pool, &query).await.unwrap()
+				.iter().map(|c| c.get_id()).collect();
+			prop_assert_eq!(before, after);
+			Ok::<_, TestCaseError>(())
+		})?;
+	}
+
+	/// T4.17: A swap exchanges the two cards' sort positions and leaves every
+	/// other card's alone
+	#[test]
+	fn prop_t4_17_swap_exchanges_positions_only(
+		n in 2usize..12,
+		a in any::<prop::sample::Index>(),
+		b in any::<prop::sample::Index>(),
+	) {
+		let rt = tokio::runtime::Runtime::new().unwrap();
+		rt.block_on(async {
+			let pool = setup_test_db();
+			let cards = create_n_cards(&pool, n).await;
+			for c in &cards {
+				move_card_to_top(&pool, &c.get_id()).await.unwrap();
+			}
+			let card_a = cards[a.index(n)].get_id();
+			let card_b = cards[b.index(n)].get_id();
+
+			let positions = |all: Vec<Card>| -> HashMap<CardId, f32> {
+				all.into_iter().map(|c| (c.get_id(), c.get_sort_position())).collect()
+			};
+			let before = positions(list_all_cards(&pool).unwrap());
+
+			let (swapped_a, swapped_b) =
+				swap_card_sort_positions(&pool, &card_a, &card_b).await.unwrap();
+			prop_assert_eq!(swapped_a.get_id(), card_a.clone());
+			prop_assert_eq!(swapped_b.get_id(), card_b.clone());
+
+			let after = positions(list_all_cards(&pool).unwrap());
+			prop_assert_eq!(after[&card_a], before[&card_b]);
+			prop_assert_eq!(after[&card_b], before[&card_a]);
+			for (id, position) in &before {
+				if *id != card_a && *id != card_b {
+					prop_assert_eq!(after[id], *position, "card {} moved", id);
+				}
+			}
+			Ok::<_, TestCaseError>(())
+		})?;
+	}
+}
+
 // ============================================================================
 // T4 Error Cases (unit-style)
 // ============================================================================
@@ -1542,6 +1633,24 @@ async fn test_t4_e5_clear_card_sort_position_nonexistent() {
 	assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_t4_e6_swap_nonexistent_card() {
+	let pool = setup_test_db();
+	let cards = create_n_cards(&pool, 1).await;
+	let missing = CardId("nonexistent-id".to_string());
+
+	assert!(
+		swap_card_sort_positions(&pool, &cards[0].get_id(), &missing)
+			.await
+			.is_err()
+	);
+	assert!(
+		swap_card_sort_positions(&pool, &missing, &cards[0].get_id())
+			.await
+			.is_err()
+	);
+}
+
 // ============================================================================
 // T5: Priority Offset Property Tests (DB Operations)
 // ============================================================================
@@ -1655,7 +1764,7 @@ proptest! {
 			update_card(&pool, &card).await.unwrap();
 
 			// Now update priority — should reset offset to 0.0
-			let updated = update_card_priority(&pool, &card_id, priority).await.unwrap();
+			let updated = update_card_priority(&pool, &card_id, priority, None).await.unwrap();
 
 			prop_assert!((updated.get_priority() - priority).abs() < 1e-6);
 			prop_assert_eq!(updated.get_priority_offset(), 0.0);
@@ -1674,7 +1783,7 @@ proptest! {
 			// Set distinct priorities
 			for (i, c) in cards.iter().enumerate() {
 				let p = (i as f32 + 1.0) / (n as f32 + 1.0);
-				update_card_priority(&pool, &c.get_id(), p).await.unwrap();
+				update_card_priority(&pool, &c.get_id(), p, None).await.unwrap();
 			}
 
 			let before: Vec<_> = list_all_cards(&pool).unwrap()
@@ -1,8 +1,11 @@
 use super::*;
-use crate::GetQueryDtoBuilder;
-use crate::models::{ItemTypeId, TagId};
+use crate::models::{CardDirection, Item, ItemTypeId, JsonValue, Rating, TagId};
 use crate::repo::tests::setup_test_db;
-use crate::repo::{add_tag_to_item, create_item, create_item_type, create_tag};
+use crate::repo::{
+	add_tag_to_item, create_item, create_item_type, create_tag, list_items_with_filters,
+	update_settings,
+};
+use crate::{CardState, GetQueryDtoBuilder};
 use chrono::{Duration, Utc};
 use serde_json::json;
 
@@ -37,6 +40,89 @@ async fn test_create_card() {
 	assert!((card.get_priority() - priority).abs() < 0.0001);
 }
 
+#[tokio::test]
+async fn test_basic_item_gets_a_single_forward_card() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+
+	for (title, item_data) in [
+		("Default", json!({"front": "Hello", "back": "World"})),
+		(
+			"Not reversible",
+			json!({"front": "Hello", "back": "World", "reversible": false}),
+		),
+	] {
+		let item = create_item(&pool, &item_type.get_id(), title.to_string(), item_data)
+			.await
+			.unwrap();
+
+		let cards = get_cards_for_item(&pool, &item.get_id()).unwrap();
+		assert_eq!(cards.len(), 1, "{}", title);
+		assert_eq!(cards[0].get_card_index(), 0);
+		assert_eq!(cards[0].get_direction(), Some(CardDirection::Forward));
+	}
+}
+
+#[tokio::test]
+async fn test_reversible_basic_item_gets_a_reverse_card() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Reversible".to_string(),
+		json!({"front": "Hello", "back": "World", "reversible": true}),
+	)
+	.await
+	.unwrap();
+
+	let mut cards = get_cards_for_item(&pool, &item.get_id()).unwrap();
+	cards.sort_by_key(|card| card.get_card_index());
+	let directions: Vec<_> = cards
+		.iter()
+		.map(|card| (card.get_card_index(), card.get_direction()))
+		.collect();
+	assert_eq!(
+		directions,
+		vec![
+			(0, Some(CardDirection::Forward)),
+			(1, Some(CardDirection::Reverse)),
+		]
+	);
+
+	// The direction is part of the card's JSON
+	assert_eq!(cards[1].to_json()["direction"], "reverse");
+}
+
+#[tokio::test]
+async fn test_cards_without_a_direction() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+
+	for card in get_cards_for_item(&pool, &item.get_id()).unwrap() {
+		assert_eq!(card.get_direction(), None);
+		assert!(card.to_json()["direction"].is_null());
+	}
+
+	// Cards created by hand have no direction either
+	let card = create_card(&pool, &item.get_id(), 2, 0.5).await.unwrap();
+	assert_eq!(card.get_direction(), None);
+}
+
 #[tokio::test]
 async fn test_get_card() {
 	let pool = setup_test_db();
@@ -68,6 +154,43 @@ async fn test_get_card() {
 	assert_eq!(retrieved_card.get_item_id(), item.get_id());
 }
 
+#[tokio::test]
+async fn test_get_cards_by_ids() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let card1 = create_card(&pool, &item.get_id(), 2, 0.5).await.unwrap();
+	let card2 = create_card(&pool, &item.get_id(), 3, 0.5).await.unwrap();
+	let missing = CardId("nonexistent".to_string());
+
+	// Cards come back in request order, with None for unknown ids
+	let ids = vec![
+		card2.get_id(),
+		missing.clone(),
+		card1.get_id(),
+		card2.get_id(),
+	];
+	let cards = get_cards_by_ids(&pool, &ids).await.unwrap();
+
+	assert_eq!(cards.len(), 4);
+	assert_eq!(cards[0].as_ref().unwrap().get_id(), card2.get_id());
+	assert!(cards[1].is_none());
+	assert_eq!(cards[2].as_ref().unwrap().get_id(), card1.get_id());
+	assert_eq!(cards[3].as_ref().unwrap().get_id(), card2.get_id());
+
+	assert!(get_cards_by_ids(&pool, &[]).await.unwrap().is_empty());
+}
+
 #[tokio::test]
 async fn test_retrieve_cards_by_item_id() {
 	let pool = setup_test_db();
@@ -208,6 +331,131 @@ async fn test_filter_cards_by_item_type() {
 	assert_eq!(type2_cards[1].get_item_id(), type2_item.get_id());
 }
 
+#[tokio::test]
+async fn test_exclude_cards_by_item_type() {
+	let pool = setup_test_db();
+
+	let type1_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let type2_type = create_item_type(&pool, "Test Type 2".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let type3_type = create_item_type(&pool, "Test Type 3".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+
+	create_item(
+		&pool,
+		&type1_type.get_id(),
+		"Type 1 Item".to_string(),
+		json!({"front": "F1", "back": "B1"}),
+	)
+	.await
+	.unwrap();
+	let type2_item = create_item(
+		&pool,
+		&type2_type.get_id(),
+		"Type 2 Item".to_string(),
+		json!({"front": "F2", "back": "B2"}),
+	)
+	.await
+	.unwrap();
+	create_item(
+		&pool,
+		&type3_type.get_id(),
+		"Type 3 Item".to_string(),
+		json!({"front": "F3", "back": "B3"}),
+	)
+	.await
+	.unwrap();
+
+	let excluding_one = GetQueryDtoBuilder::new()
+		.exclude_item_type_id(type1_type.get_id())
+		.build();
+	let cards = list_cards(&pool, &excluding_one).await.unwrap();
+	assert_eq!(cards.len(), 4);
+
+	let excluding_two = GetQueryDtoBuilder::new()
+		.exclude_item_type_id(type1_type.get_id())
+		.exclude_item_type_id(type3_type.get_id())
+		.build();
+	let cards = list_cards(&pool, &excluding_two).await.unwrap();
+	assert_eq!(cards.len(), 2);
+	assert!(cards.iter().all(|c| c.get_item_id() == type2_item.get_id()));
+
+	// Including and excluding the same type matches nothing
+	let contradictory = GetQueryDtoBuilder::new()
+		.item_type_id(type2_type.get_id())
+		.exclude_item_type_id(type2_type.get_id())
+		.build();
+	assert!(list_cards(&pool, &contradictory).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_exclude_cards_by_item_type_with_tags() {
+	let pool = setup_test_db();
+
+	let type1_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let type2_type = create_item_type(&pool, "Test Type 2".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+
+	let type1_item = create_item(
+		&pool,
+		&type1_type.get_id(),
+		"Type 1 Item".to_string(),
+		json!({"front": "F1", "back": "B1"}),
+	)
+	.await
+	.unwrap();
+	let type2_tagged = create_item(
+		&pool,
+		&type2_type.get_id(),
+		"Tagged Type 2 Item".to_string(),
+		json!({"front": "F2", "back": "B2"}),
+	)
+	.await
+	.unwrap();
+	create_item(
+		&pool,
+		&type2_type.get_id(),
+		"Untagged Type 2 Item".to_string(),
+		json!({"front": "F3", "back": "B3"}),
+	)
+	.await
+	.unwrap();
+
+	let tag = create_tag(&pool, "Important".to_string(), true)
+		.await
+		.unwrap();
+	add_tag_to_item(&pool, &tag.get_id(), &type1_item.get_id())
+		.await
+		.unwrap();
+	add_tag_to_item(&pool, &tag.get_id(), &type2_tagged.get_id())
+		.await
+		.unwrap();
+
+	let query = GetQueryDtoBuilder::new()
+		.add_tag_id(tag.get_id())
+		.exclude_item_type_id(type1_type.get_id())
+		.build();
+	let cards = list_cards(&pool, &query).await.unwrap();
+
+	assert_eq!(cards.len(), 2);
+	assert!(
+		cards
+			.iter()
+			.all(|c| c.get_item_id() == type2_tagged.get_id())
+	);
+
+	let items = list_items_with_filters(&pool, &query).unwrap();
+	assert_eq!(items.len(), 1);
+	assert_eq!(items[0].get_id(), type2_tagged.get_id());
+}
+
 #[tokio::test]
 async fn test_filter_cards_by_tags() {
 	let pool = setup_test_db();
@@ -492,7 +740,7 @@ async fn test_update_card_priority() {
 
 	// Test updating to a valid priority
 	let new_priority = 0.8;
-	let result = update_card_priority(&pool, &card.get_id(), new_priority).await;
+	let result = update_card_priority(&pool, &card.get_id(), new_priority, None).await;
 	assert!(result.is_ok());
 
 	// Verify the priority was updated
@@ -501,7 +749,7 @@ async fn test_update_card_priority() {
 
 	// Test updating to minimum valid priority (0.0)
 	let min_priority = 0.0;
-	let result = update_card_priority(&pool, &card.get_id(), min_priority).await;
+	let result = update_card_priority(&pool, &card.get_id(), min_priority, None).await;
 	assert!(result.is_ok());
 
 	// Verify the priority was updated to minimum
@@ -510,7 +758,7 @@ async fn test_update_card_priority() {
 
 	// Test updating to maximum valid priority (1.0)
 	let max_priority = 1.0;
-	let result = update_card_priority(&pool, &card.get_id(), max_priority).await;
+	let result = update_card_priority(&pool, &card.get_id(), max_priority, None).await;
 	assert!(result.is_ok());
 
 	// Verify the priority was updated to maximum
@@ -545,12 +793,12 @@ async fn test_update_card_priority_invalid_values() {
 
 	// Test updating to a priority below the valid range
 	let below_min_priority = -0.1;
-	let result = update_card_priority(&pool, &card.get_id(), below_min_priority).await;
+	let result = update_card_priority(&pool, &card.get_id(), below_min_priority, None).await;
 	assert!(result.is_err());
 
 	// Test updating to a priority above the valid range
 	let above_max_priority = 1.1;
-	let result = update_card_priority(&pool, &card.get_id(), above_max_priority).await;
+	let result = update_card_priority(&pool, &card.get_id(), above_max_priority, None).await;
 	assert!(result.is_err());
 }
 
@@ -560,10 +808,107 @@ async fn test_update_card_priority_nonexistent_card() {
 
 	// Try to update a card that doesn't exist
 	let nonexistent_card_id = "00000000-0000-0000-0000-000000000000";
-	let result = update_card_priority(&pool, &CardId(nonexistent_card_id.to_string()), 0.5).await;
+	let result =
+		update_card_priority(&pool, &CardId(nonexistent_card_id.to_string()), 0.5, None).await;
 	assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_update_card_rejects_stale_version() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let card = create_card(&pool, &item.get_id(), 2, 0.5).await.unwrap();
+	assert_eq!(card.get_version(), 0);
+
+	// Two clients read the same card
+	let mut first = get_card_raw(&pool, &card.get_id()).unwrap().unwrap();
+	let mut second = first.clone();
+
+	// The first update lands and bumps the version
+	first.set_priority(0.8);
+	update_card(&pool, &first).await.unwrap();
+	let stored = get_card_raw(&pool, &card.get_id()).unwrap().unwrap();
+	assert_eq!(stored.get_priority(), 0.8);
+	assert_eq!(stored.get_version(), 1);
+
+	// The second, made against the version both read, is rejected
+	second.set_priority(0.2);
+	let err = update_card(&pool, &second).await.unwrap_err();
+	assert_eq!(
+		err.downcast::<CardVersionConflictError>().unwrap(),
+		CardVersionConflictError {
+			card_id: card.get_id(),
+			expected_version: 0,
+		}
+	);
+	let stored = get_card_raw(&pool, &card.get_id()).unwrap().unwrap();
+	assert_eq!(stored.get_priority(), 0.8);
+	assert_eq!(stored.get_version(), 1);
+
+	// Re-reading and retrying succeeds
+	let mut retried = stored;
+	retried.set_priority(0.2);
+	update_card(&pool, &retried).await.unwrap();
+	assert_eq!(
+		get_card_raw(&pool, &card.get_id())
+			.unwrap()
+			.unwrap()
+			.get_priority(),
+		0.2
+	);
+}
+
+#[tokio::test]
+async fn test_update_card_priority_checks_expected_version() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let card = create_card(&pool, &item.get_id(), 2, 0.5).await.unwrap();
+
+	// Any change to the card bumps its version, not just priority updates
+	crate::repo::record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let reviewed = get_card_raw(&pool, &card.get_id()).unwrap().unwrap();
+	assert_eq!(reviewed.get_version(), 1);
+
+	// A client still holding the version from before the review is rejected
+	let err = update_card_priority(&pool, &card.get_id(), 0.9, Some(0))
+		.await
+		.unwrap_err();
+	assert!(err.downcast_ref::<CardVersionConflictError>().is_some());
+	let stored = get_card_raw(&pool, &card.get_id()).unwrap().unwrap();
+	assert_eq!(stored.get_priority(), 0.5);
+
+	// One with the current version goes through
+	let updated = update_card_priority(&pool, &card.get_id(), 0.9, Some(1))
+		.await
+		.unwrap();
+	assert_eq!(updated.get_priority(), 0.9);
+	assert_eq!(updated.get_version(), 2);
+}
+
 #[tokio::test]
 async fn test_filter_cards_by_suspended_state_exclude() {
 	let pool = setup_test_db();
@@ -1650,3 +1995,1371 @@ async fn test_list_cards_with_child_item_id_filter() {
 		);
 	}
 }
+
+#[tokio::test]
+async fn test_list_cards_with_state_filter() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+
+	let mut card_ids = Vec::new();
+	for title in ["New", "Learning", "Review"] {
+		let item = create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			serde_json::json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		let cards = get_cards_for_item(&pool, &item.get_id()).unwrap();
+		card_ids.push(cards[0].get_id());
+	}
+	let (new_id, learning_id, review_id) = (&card_ids[0], &card_ids[1], &card_ids[2]);
+
+	// Schedule one card ten minutes after its last review, and the other
+	// three days after, both already overdue
+	let now = Utc::now();
+	let conn = &mut pool.get().unwrap();
+	for (card_id, last_review, next_review) in [
+		(
+			learning_id,
+			now - Duration::hours(1),
+			now - Duration::minutes(50),
+		),
+		(review_id, now - Duration::days(4), now - Duration::days(1)),
+	] {
+		diesel::update(cards::table.find(card_id))
+			.set((
+				cards::last_review.eq(Some(last_review.naive_utc())),
+				cards::next_review.eq(next_review.naive_utc()),
+			))
+			.execute(conn)
+			.unwrap();
+	}
+	// The new card isn't due until tomorrow
+	diesel::update(cards::table.find(new_id))
+		.set(cards::next_review.eq((now + Duration::days(1)).naive_utc()))
+		.execute(conn)
+		.unwrap();
+
+	let ids_for = |state| {
+		let pool = pool.clone();
+		async move {
+			let query = GetQueryDtoBuilder::new().state(state).build();
+			let mut ids: Vec<CardId> = list_cards(&pool, &query)
+				.await
+				.unwrap()
+				.into_iter()
+				.map(|c| c.get_id())
+				.collect();
+			ids.sort();
+			ids
+		}
+	};
+
+	assert_eq!(ids_for(CardState::New).await, vec![new_id.clone()]);
+	assert_eq!(
+		ids_for(CardState::Learning).await,
+		vec![learning_id.clone()]
+	);
+	assert_eq!(ids_for(CardState::Review).await, vec![review_id.clone()]);
+	let mut due = vec![learning_id.clone(), review_id.clone()];
+	due.sort();
+	assert_eq!(ids_for(CardState::Due).await, due);
+
+	// The state is ANDed with the date filters
+	let query = GetQueryDtoBuilder::new()
+		.state(CardState::New)
+		.last_review_after(now - Duration::days(30))
+		.build();
+	assert!(list_cards(&pool, &query).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_next_card_follows_sort_position_and_skips_suspended() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+	let cards = get_cards_for_item(&pool, &item.get_id()).unwrap();
+	assert_eq!(cards.len(), 2);
+
+	// Moving a card to the top makes it next, regardless of priority
+	move_card_to_top(&pool, &cards[1].get_id()).await.unwrap();
+	let next = next_card(&pool, &GetQueryDto::default()).await.unwrap();
+	assert_eq!(next.unwrap().get_id(), cards[1].get_id());
+
+	// Once suspended, it's skipped in favour of the other card
+	set_card_suspended(&pool, &cards[1].get_id(), true)
+		.await
+		.unwrap();
+	let next = next_card(&pool, &GetQueryDto::default()).await.unwrap();
+	assert_eq!(next.unwrap().get_id(), cards[0].get_id());
+}
+
+#[tokio::test]
+async fn test_next_card_only_returns_due_cards() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+
+	// Push every card's next review into the future
+	let tomorrow = Utc::now() + Duration::days(1);
+	for mut card in get_cards_for_item(&pool, &item.get_id()).unwrap() {
+		card.set_next_review(tomorrow);
+		update_card(&pool, &card).await.unwrap();
+	}
+
+	let next = next_card(&pool, &GetQueryDto::default()).await.unwrap();
+	assert!(next.is_none());
+
+	// An explicit cutoff replaces the "due now" default
+	let query = GetQueryDtoBuilder::new()
+		.next_review_before(tomorrow + Duration::hours(1))
+		.build();
+	let next = next_card(&pool, &query).await.unwrap();
+	assert_eq!(next.unwrap().get_item_id(), item.get_id());
+}
+
+#[tokio::test]
+async fn test_random_card_varies_among_due_cards() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let mut cards = Vec::new();
+	for title in ["Item 1", "Item 2"] {
+		let item = create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		cards.extend(get_cards_for_item(&pool, &item.get_id()).unwrap());
+	}
+	assert_eq!(cards.len(), 4);
+
+	// One card is suspended and another isn't due, so neither is picked
+	set_card_suspended(&pool, &cards[0].get_id(), true)
+		.await
+		.unwrap();
+	let mut later = cards[1].clone();
+	later.set_next_review(Utc::now() + Duration::days(1));
+	update_card(&pool, &later).await.unwrap();
+
+	let mut seen = std::collections::HashSet::new();
+	for _ in 0..50 {
+		let card = random_card(&pool, &GetQueryDto::default())
+			.await
+			.unwrap()
+			.unwrap();
+		seen.insert(card.get_id());
+	}
+	assert_eq!(
+		seen,
+		[cards[2].get_id(), cards[3].get_id()].into_iter().collect()
+	);
+
+	// Nothing due, nothing picked
+	for mut card in cards.into_iter().skip(2) {
+		card.set_next_review(Utc::now() + Duration::days(1));
+		update_card(&pool, &card).await.unwrap();
+	}
+	assert!(
+		random_card(&pool, &GetQueryDto::default())
+			.await
+			.unwrap()
+			.is_none()
+	);
+}
+
+#[tokio::test]
+async fn test_cram_lists_cards_regardless_of_schedule() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+	let cards = get_cards_for_item(&pool, &item.get_id()).unwrap();
+	assert_eq!(cards.len(), 2);
+
+	// One card isn't due until tomorrow and is buried, the other is suspended
+	let tomorrow = Utc::now() + Duration::days(1);
+	let mut not_due = cards[0].clone();
+	not_due.set_next_review(tomorrow);
+	update_card(&pool, &not_due).await.unwrap();
+	bury_card(&pool, &not_due.get_id(), None).await.unwrap();
+	set_card_suspended(&pool, &cards[1].get_id(), true)
+		.await
+		.unwrap();
+
+	// Normally neither card is studied
+	let query = GetQueryDtoBuilder::new()
+		.next_review_before(Utc::now())
+		.build();
+	assert!(list_cards(&pool, &query).await.unwrap().is_empty());
+	assert!(
+		next_card(&pool, &GetQueryDto::default())
+			.await
+			.unwrap()
+			.is_none()
+	);
+
+	// Cramming ignores the schedule, burying and the due cutoff, but still
+	// skips suspended cards, even when asked to include them
+	let query = GetQueryDtoBuilder::new()
+		.next_review_before(Utc::now())
+		.suspended_filter(SuspendedFilter::Include)
+		.cram(true)
+		.build();
+	let crammed = list_cards(&pool, &query).await.unwrap();
+	assert_eq!(crammed.len(), 1);
+	assert_eq!(crammed[0].get_id(), not_due.get_id());
+
+	let query = GetQueryDtoBuilder::new().cram(true).build();
+	let next = next_card(&pool, &query).await.unwrap();
+	assert_eq!(next.unwrap().get_id(), not_due.get_id());
+}
+
+#[tokio::test]
+async fn test_next_card_empty_queue() {
+	let pool = setup_test_db();
+
+	let next = next_card(&pool, &GetQueryDto::default()).await.unwrap();
+
+	assert!(next.is_none());
+}
+
+#[tokio::test]
+async fn test_list_leeches_respects_threshold_and_suspends() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+	let cards = get_cards_for_item(&pool, &item.get_id()).unwrap();
+	let leech_id = cards[0].get_id();
+	let other_id = cards[1].get_id();
+
+	// Three lapses on one card, a single lapse and a pass on the other
+	for _ in 0..3 {
+		crate::repo::record_review(&pool, &leech_id, Rating::Again)
+			.await
+			.unwrap();
+	}
+	crate::repo::record_review(&pool, &other_id, Rating::Again)
+		.await
+		.unwrap();
+	crate::repo::record_review(&pool, &other_id, Rating::Good)
+		.await
+		.unwrap();
+
+	// Below the threshold nothing qualifies, and the default threshold is higher still
+	assert!(list_leeches(&pool, 4, false).await.unwrap().is_empty());
+	assert!(
+		list_leeches(&pool, DEFAULT_LEECH_THRESHOLD, false)
+			.await
+			.unwrap()
+			.is_empty()
+	);
+
+	// Only "Again" ratings count towards the threshold
+	let leeches = list_leeches(&pool, 2, false).await.unwrap();
+	assert_eq!(leeches.len(), 1);
+	assert_eq!(leeches[0].get_id(), leech_id);
+	assert!(leeches[0].get_suspended().is_none());
+
+	// Most-lapsed cards come first
+	let leeches = list_leeches(&pool, 1, false).await.unwrap();
+	let ids: Vec<CardId> = leeches.iter().map(|c| c.get_id()).collect();
+	assert_eq!(ids, vec![leech_id.clone(), other_id.clone()]);
+
+	// Suspending only touches the returned cards
+	let leeches = list_leeches(&pool, 3, true).await.unwrap();
+	assert_eq!(leeches.len(), 1);
+	assert!(leeches[0].get_suspended().is_some());
+	let other = get_card(&pool, &other_id).await.unwrap().unwrap();
+	assert!(other.get_suspended().is_none());
+}
+
+#[tokio::test]
+async fn test_list_hardest_cards_orders_by_failure_rate() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let mut card_ids = Vec::new();
+	for title in ["First", "Second"] {
+		let item = create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		for card in get_cards_for_item(&pool, &item.get_id()).unwrap() {
+			card_ids.push(card.get_id());
+		}
+	}
+
+	// Failed reviews out of total reviews for each card: 1/4, 3/4, 2/4 and,
+	// with too few reviews to count by default, 1/1
+	let histories = [(1, 4), (3, 4), (2, 4), (1, 1)];
+	for (card_id, (failed, total)) in card_ids.iter().zip(histories) {
+		for i in 0..total {
+			let rating = if i < failed {
+				Rating::Again
+			} else {
+				Rating::Good
+			};
+			crate::repo::record_review(&pool, card_id, rating)
+				.await
+				.unwrap();
+		}
+	}
+
+	let hardest = list_hardest_cards(&pool, DEFAULT_HARDEST_CARDS_LIMIT, 4)
+		.await
+		.unwrap();
+	let ids: Vec<CardId> = hardest.iter().map(|c| c.get_id()).collect();
+	assert_eq!(
+		ids,
+		vec![
+			card_ids[1].clone(),
+			card_ids[2].clone(),
+			card_ids[0].clone()
+		]
+	);
+
+	// With no minimum, the once-failed card comes first, and the limit applies
+	let hardest = list_hardest_cards(&pool, 2, 0).await.unwrap();
+	let ids: Vec<CardId> = hardest.iter().map(|c| c.get_id()).collect();
+	assert_eq!(ids, vec![card_ids[3].clone(), card_ids[1].clone()]);
+
+	// None of the cards has enough reviews for the default minimum
+	assert!(
+		list_hardest_cards(
+			&pool,
+			DEFAULT_HARDEST_CARDS_LIMIT,
+			DEFAULT_HARDEST_CARDS_MIN_REVIEWS
+		)
+		.await
+		.unwrap()
+		.is_empty()
+	);
+}
+
+#[tokio::test]
+async fn test_list_related_cards_ranks_by_shared_tags() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let mut tags = Vec::new();
+	for name in ["A", "B", "C", "D"] {
+		tags.push(create_tag(&pool, name.to_string(), true).await.unwrap());
+	}
+
+	// The card's item has A, B and C; the others share 2, 1 and 0 of them
+	let mut cards = Vec::new();
+	for (title, tag_indices) in [
+		("Source", vec![0, 1, 2]),
+		("Shares one", vec![2, 3]),
+		("Shares two", vec![0, 1]),
+		("Shares none", vec![3]),
+	] {
+		let item = create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		for i in tag_indices {
+			add_tag_to_item(&pool, &tags[i].get_id(), &item.get_id())
+				.await
+				.unwrap();
+		}
+		cards.push(get_cards_for_item(&pool, &item.get_id()).unwrap().remove(0));
+	}
+
+	// Most shared tags first; neither the card itself nor the unrelated card
+	let related = list_related_cards(&pool, &cards[0].get_id(), DEFAULT_RELATED_CARDS_LIMIT)
+		.await
+		.unwrap()
+		.unwrap();
+	let ids: Vec<CardId> = related.iter().map(|c| c.get_id()).collect();
+	assert_eq!(ids, vec![cards[2].get_id(), cards[1].get_id()]);
+
+	// The limit keeps the best matches
+	let related = list_related_cards(&pool, &cards[0].get_id(), 1)
+		.await
+		.unwrap()
+		.unwrap();
+	assert_eq!(related.len(), 1);
+	assert_eq!(related[0].get_id(), cards[2].get_id());
+
+	assert!(
+		list_related_cards(&pool, &CardId::new(), DEFAULT_RELATED_CARDS_LIMIT)
+			.await
+			.unwrap()
+			.is_none()
+	);
+}
+
+#[tokio::test]
+async fn test_configured_default_priority_applies_to_new_cards() {
+	let pool = setup_test_db();
+
+	update_settings(&pool, |s| s.default_priority = 0.8)
+		.await
+		.unwrap();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+
+	let cards = get_cards_for_item(&pool, &item.get_id()).unwrap();
+	assert_eq!(cards.len(), 2);
+	for card in cards {
+		assert_eq!(card.get_priority(), 0.8);
+	}
+}
+
+#[tokio::test]
+async fn test_item_type_default_priority_overrides_configured_default() {
+	let pool = setup_test_db();
+	update_settings(&pool, |s| s.default_priority = 0.8)
+		.await
+		.unwrap();
+
+	let item_type = create_item_type(&pool, "Test Urgent".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	crate::repo::update_item_type_default_priority(&pool, &item_type.get_id(), Some(0.95))
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+
+	for card in get_cards_for_item(&pool, &item.get_id()).unwrap() {
+		assert_eq!(card.get_priority(), 0.95);
+	}
+}
+
+#[tokio::test]
+async fn test_buried_card_excluded_until_bury_time_passes() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+	let cards = get_cards_for_item(&pool, &item.get_id()).unwrap();
+	let buried_id = cards[0].get_id();
+	let other_id = cards[1].get_id();
+
+	// By default a card is buried until the start of the next day in the
+	// configured timezone
+	let tz: chrono_tz::Tz = "Australia/Melbourne".parse().unwrap();
+	update_settings(&pool, |s| s.timezone = tz).await.unwrap();
+	let now = Utc::now();
+	let until = bury_card(&pool, &buried_id, None).await.unwrap();
+	assert!(until > now);
+	assert_eq!(until, crate::time_utils::start_of_next_local_day(now, tz));
+
+	// Buried cards are left out of lists and the study queue
+	let listed = list_cards(&pool, &GetQueryDto::default()).await.unwrap();
+	assert_eq!(listed.len(), 1);
+	assert_eq!(listed[0].get_id(), other_id);
+	move_card_to_top(&pool, &buried_id).await.unwrap();
+	let next = next_card(&pool, &GetQueryDto::default()).await.unwrap();
+	assert_eq!(next.unwrap().get_id(), other_id);
+
+	// Once the bury time has passed the card is back
+	bury_card(&pool, &buried_id, Some(Utc::now() - Duration::minutes(1)))
+		.await
+		.unwrap();
+	let listed = list_cards(&pool, &GetQueryDto::default()).await.unwrap();
+	assert_eq!(listed.len(), 2);
+	let next = next_card(&pool, &GetQueryDto::default()).await.unwrap();
+	assert_eq!(next.unwrap().get_id(), buried_id);
+}
+
+#[tokio::test]
+async fn test_bury_nonexistent_card() {
+	let pool = setup_test_db();
+
+	let result = bury_card(&pool, &CardId("nonexistent".to_string()), None).await;
+
+	assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_unbury_all_returns_cards_to_queue() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let mut card_ids = Vec::new();
+	for title in ["Item 1", "Item 2"] {
+		let item = create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		for card in get_cards_for_item(&pool, &item.get_id()).unwrap() {
+			card_ids.push(card.get_id());
+		}
+	}
+
+	// Bury three of the four cards
+	for card_id in &card_ids[..3] {
+		bury_card(&pool, card_id, None).await.unwrap();
+	}
+	let due = list_cards(&pool, &GetQueryDto::default()).await.unwrap();
+	assert_eq!(due.len(), 1);
+
+	assert_eq!(unbury_all(&pool).await.unwrap(), 3);
+
+	// Every card is due again, and nothing is left to unbury
+	let due = list_cards(&pool, &GetQueryDto::default()).await.unwrap();
+	assert_eq!(due.len(), 4);
+	for card_id in &card_ids {
+		let card = get_card_raw(&pool, card_id).unwrap().unwrap();
+		assert_eq!(card.get_buried_until(), None);
+	}
+	assert_eq!(unbury_all(&pool).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_set_cards_suspended_by_item_type() {
+	let pool = setup_test_db();
+
+	let exam_type = create_item_type(&pool, "Test Exam".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let other_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	for (item_type, title) in [(&exam_type, "Exam item"), (&other_type, "Other item")] {
+		create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+	}
+
+	let exam_query = GetQueryDtoBuilder::new()
+		.item_type_id(exam_type.get_id())
+		.build();
+	let other_query = GetQueryDtoBuilder::new()
+		.item_type_id(other_type.get_id())
+		.suspended_filter(SuspendedFilter::Include)
+		.build();
+
+	// Both cards of the exam item are suspended
+	assert_eq!(
+		set_cards_suspended(&pool, &exam_query, true).await.unwrap(),
+		2
+	);
+	let exam_cards = list_cards(
+		&pool,
+		&GetQueryDtoBuilder::new()
+			.item_type_id(exam_type.get_id())
+			.suspended_filter(SuspendedFilter::Only)
+			.build(),
+	)
+	.await
+	.unwrap();
+	assert_eq!(exam_cards.len(), 2);
+
+	// The other item type's card is untouched
+	let other_cards = list_cards(&pool, &other_query).await.unwrap();
+	assert_eq!(other_cards.len(), 1);
+	assert!(other_cards[0].get_suspended().is_none());
+
+	// Suspending again changes nothing and keeps the original suspension times
+	assert_eq!(
+		set_cards_suspended(&pool, &exam_query, true).await.unwrap(),
+		0
+	);
+	for card in &exam_cards {
+		let reread = get_card_raw(&pool, &card.get_id()).unwrap().unwrap();
+		assert_eq!(reread.get_suspended(), card.get_suspended());
+	}
+
+	// Unsuspending finds the suspended cards despite the default filter
+	assert_eq!(
+		set_cards_suspended(&pool, &exam_query, false)
+			.await
+			.unwrap(),
+		2
+	);
+	assert_eq!(list_cards(&pool, &exam_query).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_set_cards_suspended_includes_buried_cards() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Exam".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Exam item".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+	let cards = get_cards_for_item(&pool, &item.get_id()).unwrap();
+	let buried_id = cards[0].get_id();
+	bury_card(&pool, &buried_id, None).await.unwrap();
+
+	let query = GetQueryDtoBuilder::new()
+		.item_type_id(item_type.get_id())
+		.build();
+
+	// The buried card is suspended along with the other one
+	assert_eq!(set_cards_suspended(&pool, &query, true).await.unwrap(), 2);
+	let buried = get_card_raw(&pool, &buried_id).unwrap().unwrap();
+	assert!(buried.get_suspended().is_some());
+	assert!(buried.get_buried_until().is_some());
+
+	// And unsuspended again
+	assert_eq!(set_cards_suspended(&pool, &query, false).await.unwrap(), 2);
+	let buried = get_card_raw(&pool, &buried_id).unwrap().unwrap();
+	assert!(buried.get_suspended().is_none());
+}
+
+#[tokio::test]
+async fn test_clear_sort_positions_includes_buried_cards() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type A".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item A".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+	let cards = get_cards_for_item(&pool, &item.get_id()).unwrap();
+	for c in &cards {
+		move_card_to_top(&pool, &c.get_id()).await.unwrap();
+	}
+	bury_card(&pool, &cards[0].get_id(), None).await.unwrap();
+
+	let query = GetQueryDtoBuilder::new()
+		.item_type_id(item_type.get_id())
+		.build();
+	clear_sort_positions(&pool, &query).await.unwrap();
+
+	for c in get_cards_for_item(&pool, &item.get_id()).unwrap() {
+		assert_eq!(c.get_sort_position(), 0.0, "card {} should be cleared", c.get_id());
+	}
+}
+
+#[tokio::test]
+async fn test_get_item_with_cards_and_tags() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item 1".to_string(),
+		json!({"front": "F1", "back": "B1"}),
+	)
+	.await
+	.unwrap();
+	let tag = create_tag(&pool, "Important".to_string(), true)
+		.await
+		.unwrap();
+	add_tag_to_item(&pool, &tag.get_id(), &item.get_id())
+		.await
+		.unwrap();
+
+	let full = get_item_with_cards_and_tags(&pool, &item.get_id())
+		.await
+		.unwrap()
+		.unwrap();
+
+	assert_eq!(full.item.get_id(), item.get_id());
+	assert_eq!(full.cards.len(), 2);
+	assert_eq!(full.cards[0].get_card_index(), 0);
+	assert_eq!(full.cards[1].get_card_index(), 1);
+	assert!(full.cards.iter().all(|c| c.get_item_id() == item.get_id()));
+	assert_eq!(full.tags.len(), 1);
+	assert_eq!(full.tags[0].get_id(), tag.get_id());
+
+	// A missing item gives None rather than an error
+	let missing =
+		get_item_with_cards_and_tags(&pool, &crate::models::ItemId("item-missing".to_string()))
+			.await
+			.unwrap();
+	assert!(missing.is_none());
+}
+
+#[tokio::test]
+async fn test_set_card_note() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item 1".to_string(),
+		json!({"front": "F1", "back": "B1"}),
+	)
+	.await
+	.unwrap();
+	let cards = get_cards_for_item(&pool, &item.get_id()).unwrap();
+	let card_id = cards[0].get_id();
+	assert_eq!(cards[0].get_note(), None);
+
+	// Setting a note round-trips through get_card, and marks the card as
+	// changed so its cached event chain output is recomputed
+	std::thread::sleep(std::time::Duration::from_millis(10));
+	set_card_note(&pool, &card_id, Some("Think of a hippo".to_string()))
+		.await
+		.unwrap();
+	let card = get_card(&pool, &card_id).await.unwrap().unwrap();
+	assert_eq!(card.get_note(), Some("Think of a hippo"));
+	assert!(card.get_updated_at_raw() > cards[0].get_updated_at_raw());
+
+	// Setting it again replaces it
+	set_card_note(&pool, &card_id, Some("Think of a campus".to_string()))
+		.await
+		.unwrap();
+	let card = get_card(&pool, &card_id).await.unwrap().unwrap();
+	assert_eq!(card.get_note(), Some("Think of a campus"));
+
+	// None clears it
+	set_card_note(&pool, &card_id, None).await.unwrap();
+	let card = get_card(&pool, &card_id).await.unwrap().unwrap();
+	assert_eq!(card.get_note(), None);
+
+	// The other card of the item is untouched
+	let other = get_card(&pool, &cards[1].get_id()).await.unwrap().unwrap();
+	assert_eq!(other.get_note(), None);
+
+	// A missing card is an error
+	assert!(
+		set_card_note(&pool, &CardId("card-missing".to_string()), None)
+			.await
+			.is_err()
+	);
+}
+
+#[tokio::test]
+async fn test_set_card_difficulty() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item 1".to_string(),
+		json!({"front": "F1", "back": "B1"}),
+	)
+	.await
+	.unwrap();
+	let cards = get_cards_for_item(&pool, &item.get_id()).unwrap();
+	let card_id = cards[0].get_id();
+	assert_eq!(cards[0].get_difficulty(), None);
+
+	// Setting a difficulty round-trips through get_card without touching the
+	// card's priority, and marks the card as changed
+	tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+	set_card_difficulty(&pool, &card_id, Some(0.8))
+		.await
+		.unwrap();
+	let card = get_card(&pool, &card_id).await.unwrap().unwrap();
+	assert_eq!(card.get_difficulty(), Some(0.8));
+	assert_eq!(card.get_priority(), cards[0].get_priority());
+	assert_eq!(card.get_version(), cards[0].get_version() + 1);
+	assert!(card.get_updated_at() > cards[0].get_updated_at());
+
+	// None unsets it
+	set_card_difficulty(&pool, &card_id, None).await.unwrap();
+	let card = get_card(&pool, &card_id).await.unwrap().unwrap();
+	assert_eq!(card.get_difficulty(), None);
+
+	// Out of range difficulties and missing cards are errors
+	for difficulty in [-0.1, 1.1] {
+		assert!(
+			set_card_difficulty(&pool, &card_id, Some(difficulty))
+				.await
+				.is_err()
+		);
+	}
+	assert_eq!(
+		get_card(&pool, &card_id)
+			.await
+			.unwrap()
+			.unwrap()
+			.get_difficulty(),
+		None
+	);
+	assert!(
+		set_card_difficulty(&pool, &CardId("card-missing".to_string()), Some(0.5))
+			.await
+			.is_err()
+	);
+}
+
+#[tokio::test]
+async fn test_daily_markers_use_configured_timezone() {
+	let pool = setup_test_db();
+
+	// Pick whichever far-flung zone is on a different date from UTC right
+	// now, so the marker is guaranteed to differ from the UTC date
+	let now = Utc::now();
+	let tz: chrono_tz::Tz = ["Pacific/Kiritimati", "Pacific/Pago_Pago"]
+		.into_iter()
+		.map(|name| name.parse().unwrap())
+		.find(|tz| crate::time_utils::local_today(now, *tz) != now.date_naive())
+		.unwrap();
+	update_settings(&pool, |s| s.timezone = tz).await.unwrap();
+
+	regenerate_priority_offsets(&pool).await.unwrap();
+
+	let conn = &mut pool.get().unwrap();
+	let marker: String = crate::schema::metadata::table
+		.find("last_offset_date")
+		.select(crate::schema::metadata::value)
+		.first(conn)
+		.unwrap();
+	assert_eq!(
+		marker,
+		crate::time_utils::local_today(Utc::now(), tz).to_string()
+	);
+}
+
+#[tokio::test]
+async fn test_disabled_priority_offsets_order_by_base_priority() {
+	let pool = setup_test_db();
+
+	update_settings(&pool, |s| s.priority_offsets_enabled = false)
+		.await
+		.unwrap();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let mut card_ids = Vec::new();
+	for (title, priority) in [("Lower", 0.5), ("Higher", 0.6)] {
+		let item = create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		let card = get_cards_for_item(&pool, &item.get_id()).unwrap().remove(0);
+		update_card_priority(&pool, &card.get_id(), priority, None)
+			.await
+			.unwrap();
+		card_ids.push(card.get_id());
+	}
+
+	// However often the offsets are regenerated, they stay at 0.0 and the
+	// higher base priority always comes first
+	for _ in 0..10 {
+		regenerate_priority_offsets(&pool).await.unwrap();
+
+		let cards = list_cards(&pool, &GetQueryDto::default()).await.unwrap();
+		assert!(cards.iter().all(|card| card.get_priority_offset() == 0.0));
+		let order: Vec<CardId> = cards
+			.iter()
+			.map(|card| card.get_id())
+			.filter(|id| card_ids.contains(id))
+			.collect();
+		assert_eq!(order, vec![card_ids[1].clone(), card_ids[0].clone()]);
+	}
+}
+
+#[tokio::test]
+async fn test_disabling_priority_offsets_zeroes_existing_offsets() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+	regenerate_priority_offsets(&pool).await.unwrap();
+	let offset_marker = |pool: &DbPool| -> String {
+		let conn = &mut pool.get().unwrap();
+		crate::schema::metadata::table
+			.find("last_offset_date")
+			.select(crate::schema::metadata::value)
+			.first(conn)
+			.unwrap()
+	};
+	let today = offset_marker(&pool);
+
+	// The next read after turning offsets off zeroes them, without waiting
+	// for the next day
+	update_settings(&pool, |s| s.priority_offsets_enabled = false)
+		.await
+		.unwrap();
+	let cards = list_cards(&pool, &GetQueryDto::default()).await.unwrap();
+	assert!(cards.iter().all(|card| card.get_priority_offset() == 0.0));
+
+	// and the next read after turning them back on shuffles them again
+	update_settings(&pool, |s| s.priority_offsets_enabled = true)
+		.await
+		.unwrap();
+	list_cards(&pool, &GetQueryDto::default()).await.unwrap();
+	assert_eq!(offset_marker(&pool), today);
+}
+
+#[tokio::test]
+async fn test_forecast_due_cards_counts_each_bucket() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let mut cards = Vec::new();
+	for title in ["Item 1", "Item 2", "Item 3", "Item 4"] {
+		let item = create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		cards.extend(get_cards_for_item(&pool, &item.get_id()).unwrap());
+	}
+	assert_eq!(cards.len(), 8);
+
+	// Midday on the given local day, counted from today
+	let now = Utc::now();
+	let today = local_today(now, chrono_tz::Tz::UTC);
+	let midday = |days: u64| {
+		start_of_local_day(today + Days::new(days), chrono_tz::Tz::UTC) + Duration::hours(12)
+	};
+
+	let due_dates = [
+		now - Duration::days(2),
+		now - Duration::hours(1),
+		midday(1),
+		midday(2),
+		midday(2),
+		midday(4),
+		// Suspended, so not counted
+		midday(1),
+		// Past the end of the forecast
+		midday(10),
+	];
+	for (card, due) in cards.iter_mut().zip(due_dates) {
+		card.set_next_review(due);
+		update_card(&pool, card).await.unwrap();
+	}
+	set_card_suspended(&pool, &cards[6].get_id(), true)
+		.await
+		.unwrap();
+
+	let forecast = forecast_due_cards(&pool, 7).unwrap();
+
+	assert_eq!(forecast.len(), 8);
+	assert_eq!(forecast[0].date, None);
+	let dates: Vec<_> = forecast[1..].iter().map(|b| b.date.unwrap()).collect();
+	let expected_dates: Vec<_> = (0..7).map(|i| today + Days::new(i)).collect();
+	assert_eq!(dates, expected_dates);
+
+	let counts: Vec<i64> = forecast.iter().map(|b| b.due_count).collect();
+	assert_eq!(counts, vec![2, 0, 1, 2, 0, 1, 0, 0]);
+}
+
+/// Creates a Cloze item with the given deletions
+async fn create_cloze_item(pool: &DbPool, clozes: &[&str]) -> Item {
+	let item_type = create_item_type(pool, "Cloze".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	create_item(
+		pool,
+		&item_type.get_id(),
+		"Cloze".to_string(),
+		json!({"text": "Paris is in France", "clozes": clozes}),
+	)
+	.await
+	.unwrap()
+}
+
+#[tokio::test]
+async fn test_regenerate_cards_adds_cloze_deletions() {
+	let pool = setup_test_db();
+	let item = create_cloze_item(&pool, &["Paris"]).await;
+	let original = get_cards_for_item(&pool, &item.get_id()).unwrap();
+	assert_eq!(original.len(), 1);
+	crate::repo::record_review(&pool, &original[0].get_id(), Rating::Good)
+		.await
+		.unwrap();
+
+	// Nothing to do while the cards already match
+	let cards = regenerate_cards_for_item(&pool, &item.get_id(), false)
+		.await
+		.unwrap()
+		.unwrap();
+	assert_eq!(cards.len(), 1);
+
+	crate::repo::update_item(
+		&pool,
+		&item.get_id(),
+		None,
+		Some(json!({"text": "Paris is in France", "clozes": ["Paris", "France"]})),
+	)
+	.await
+	.unwrap();
+	let cards = regenerate_cards_for_item(&pool, &item.get_id(), false)
+		.await
+		.unwrap()
+		.unwrap();
+
+	// The new deletion gets a card, and the old card keeps its history
+	let indices: Vec<i32> = cards.iter().map(|c| c.get_card_index()).collect();
+	assert_eq!(indices, vec![0, 1]);
+	assert_eq!(cards[0].get_id(), original[0].get_id());
+	assert!(cards[0].get_last_review().is_some());
+	assert_eq!(
+		crate::repo::get_reviews_for_card(&pool, &cards[0].get_id())
+			.unwrap()
+			.len(),
+		1
+	);
+	assert!(cards[1].get_last_review().is_none());
+}
+
+#[tokio::test]
+async fn test_regenerate_cards_removes_cloze_deletions_only_when_asked() {
+	let pool = setup_test_db();
+	let item = create_cloze_item(&pool, &["Paris", "France"]).await;
+	let original = get_cards_for_item(&pool, &item.get_id()).unwrap();
+	assert_eq!(original.len(), 2);
+
+	crate::repo::update_item(
+		&pool,
+		&item.get_id(),
+		None,
+		Some(json!({"text": "Paris is in France", "clozes": ["Paris"]})),
+	)
+	.await
+	.unwrap();
+
+	// Extra cards are kept unless asked to remove them
+	let cards = regenerate_cards_for_item(&pool, &item.get_id(), false)
+		.await
+		.unwrap()
+		.unwrap();
+	assert_eq!(cards.len(), 2);
+
+	let cards = regenerate_cards_for_item(&pool, &item.get_id(), true)
+		.await
+		.unwrap()
+		.unwrap();
+	assert_eq!(cards.len(), 1);
+	assert_eq!(cards[0].get_card_index(), 0);
+	assert!(
+		original
+			.iter()
+			.any(|card| card.get_id() == cards[0].get_id())
+	);
+}
+
+#[tokio::test]
+async fn test_regenerate_cards_for_nonexistent_item() {
+	let pool = setup_test_db();
+
+	let result = regenerate_cards_for_item(&pool, &ItemId("nonexistent".to_string()), true)
+		.await
+		.unwrap();
+
+	assert!(result.is_none());
+}
+
+#[tokio::test]
+async fn test_get_stats_overview_counts_each_bucket() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let mut cards = Vec::new();
+	for title in ["Item 1", "Item 2", "Item 3", "Item 4"] {
+		let item = create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		cards.extend(get_cards_for_item(&pool, &item.get_id()).unwrap());
+	}
+	assert_eq!(cards.len(), 8);
+
+	// Nothing reviewed yet, so everything is new and there's no difficulty
+	let overview = get_stats_overview(&pool).unwrap();
+	assert_eq!(overview.new_count, 8);
+	assert_eq!(overview.average_difficulty, None);
+
+	// (interval, scheduler data) for each card that's been reviewed; the
+	// first two stay new
+	let last_review = Utc::now() - Duration::days(1);
+	let reviewed = [
+		// Learning
+		(
+			Duration::minutes(10),
+			json!({"stability": 0.1, "difficulty": 6.0}),
+		),
+		// Young
+		(
+			Duration::days(1),
+			json!({"stability": 1.0, "difficulty": 4.0}),
+		),
+		(
+			Duration::days(20),
+			json!({"ease_factor": 2.5, "interval": 20.0}),
+		),
+		// Mature
+		(
+			Duration::days(21),
+			json!({"ease_factor": 2.0, "interval": 21.0}),
+		),
+		(
+			Duration::days(90),
+			json!({"stability": 90.0, "difficulty": 5.0}),
+		),
+		// Suspended, so not counted
+		(
+			Duration::days(90),
+			json!({"ease_factor": 9.0, "interval": 90.0}),
+		),
+	];
+	for (card, (interval, data)) in cards[2..].iter_mut().zip(reviewed) {
+		card.set_last_review(Some(last_review));
+		card.set_next_review(last_review + interval);
+		card.set_scheduler_data(Some(JsonValue(data)));
+		update_card(&pool, card).await.unwrap();
+	}
+	set_card_suspended(&pool, &cards[7].get_id(), true)
+		.await
+		.unwrap();
+
+	let overview = get_stats_overview(&pool).unwrap();
+	assert_eq!(
+		(
+			overview.new_count,
+			overview.learning_count,
+			overview.young_count,
+			overview.mature_count
+		),
+		(2, 1, 2, 2)
+	);
+	assert_eq!(overview.average_difficulty, Some(5.0));
+}
+
+#[tokio::test]
+async fn test_get_stats_overview_buckets_reviews_by_scheduled_days() {
+	let pool = setup_test_db();
+	update_settings(&pool, |s| s.interval_fuzz = 0.0)
+		.await
+		.unwrap();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let mut cards = Vec::new();
+	for title in ["Item 1", "Item 2", "Item 3"] {
+		let item = create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		cards.extend(get_cards_for_item(&pool, &item.get_id()).unwrap());
+	}
+
+	// FSRS schedules each of these an hour short of whole days, which still
+	// count as the days they were scheduled for
+	crate::repo::record_review(&pool, &cards[0].get_id(), Rating::Again)
+		.await
+		.unwrap();
+	for (card, days) in [(&cards[1], 20), (&cards[2], 21)] {
+		crate::repo::tests::set_up_fsrs_interval(&pool, &card.get_id(), days);
+		crate::repo::record_review(&pool, &card.get_id(), Rating::Good)
+			.await
+			.unwrap();
+		let card = get_card_raw(&pool, &card.get_id()).unwrap().unwrap();
+		assert_eq!(
+			card.get_next_review() - card.get_last_review().unwrap(),
+			Duration::days(days) - Duration::hours(1)
+		);
+	}
+
+	// A learning step is under a day
+	update_settings(&pool, |s| {
+		s.learning_steps.learning = vec![Duration::minutes(10)]
+	})
+	.await
+	.unwrap();
+	crate::repo::record_review(&pool, &cards[3].get_id(), Rating::Good)
+		.await
+		.unwrap();
+
+	let overview = get_stats_overview(&pool).unwrap();
+	assert_eq!(
+		(
+			overview.new_count,
+			overview.learning_count,
+			overview.young_count,
+			overview.mature_count
+		),
+		(2, 1, 2, 1)
+	);
+}
+
+#[tokio::test]
+async fn test_forecast_due_cards_day_limits() {
+	let pool = setup_test_db();
+
+	// No days leaves just the (empty) overdue bucket
+	let forecast = forecast_due_cards(&pool, 0).unwrap();
+	assert_eq!(
+		forecast,
+		vec![ForecastBucket {
+			date: None,
+			due_count: 0
+		}]
+	);
+
+	// Asking for too many days is capped
+	let forecast = forecast_due_cards(&pool, MAX_FORECAST_DAYS + 100).unwrap();
+	assert_eq!(forecast.len(), MAX_FORECAST_DAYS as usize + 1);
+}
+
+#[tokio::test]
+async fn test_card_timestamps() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type 1".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let before = Utc::now() - Duration::seconds(1);
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item 1".to_string(),
+		json!({"front": "F1", "back": "B1"}),
+	)
+	.await
+	.unwrap();
+	let card = get_cards_for_item(&pool, &item.get_id()).unwrap().remove(0);
+	assert!(card.get_created_at() >= before);
+	assert!(card.get_created_at() <= Utc::now());
+
+	// Changing the card bumps updated_at but leaves created_at alone
+	std::thread::sleep(std::time::Duration::from_millis(10));
+	let updated = update_card_priority(&pool, &card.get_id(), 0.9, None)
+		.await
+		.unwrap();
+	assert_eq!(updated.get_created_at(), card.get_created_at());
+	assert!(updated.get_updated_at() > card.get_updated_at());
+}
@@ -1,13 +1,271 @@
-use crate::db::{DbPool, ExecuteWithRetry};
+use crate::db::{DbPool, ExecuteWithRetry, transaction_with_retry};
 use crate::dto::GetQueryDto;
-use crate::models::{Item, ItemId, ItemTypeId, JsonValue};
-use crate::schema::items;
-use anyhow::Result;
+use crate::models::{Card, Item, ItemId, ItemTag, ItemType, ItemTypeId, JsonValue, TagId};
+use crate::schema::{cards, item_tags, item_types, items};
+use crate::time_utils::{Recurrence, now_ms, parse_due_date};
+use anyhow::{Result, anyhow};
+use chrono_tz::Tz;
 use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use thiserror::Error;
 use tracing::{debug, info, instrument};
 
-use super::card_repo::create_cards_for_item;
+use super::card_repo::{
+	card_count_for_item, card_direction_for_item, create_cards_for_item,
+};
 use super::query_repo;
+use super::settings_repo::Settings;
+
+/// Error returned when submitted `item_data` doesn't have the shape its item
+/// type requires
+///
+/// Carried inside the `anyhow::Error` returned by `create_item`, so handlers
+/// can `downcast` to it and answer with a 400 instead of a 500.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid item data: {0}")]
+pub struct InvalidItemDataError(pub String);
+
+/// Error returned when one item of a batch create can't be created
+///
+/// Carried inside the `anyhow::Error` returned by `create_items_batch`, so handlers
+/// can `downcast` to it and report which item was at fault.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Item {index}: {message}")]
+pub struct InvalidBatchItemError {
+	/// The position of the offending item in the batch
+	pub index: usize,
+	/// What's wrong with it
+	pub message: String,
+}
+
+/// Error returned when an item is moved to an item type that doesn't exist
+///
+/// Carried inside the `anyhow::Error` returned by `change_item_type`, so
+/// handlers can `downcast` to it and answer with a 400.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Item type not found: {0}")]
+pub struct ItemTypeNotFoundError(pub ItemTypeId);
+
+/// The most items [`create_items_batch`] creates at once
+pub const MAX_BATCH_CREATE_ITEMS: usize = 500;
+
+/// Checks that `item_data` has the shape required by the named item type
+///
+/// Cloze items need a non-empty `clozes` array, and an optional `cloze_mode`
+/// of `"per_deletion"` (one card per entry, the default) or `"all_in_one"`
+/// (a single card showing every blank). Basic items need `front` and `back`
+/// strings. Todo items may have a `due_date`, which must be a `YYYY-MM-DD`
+/// date or an RFC 3339 timestamp. RecurringTodo items need an `every`
+/// recurrence such as `"1d"`, `"2w"` or `"1mo"`. Other item types accept any
+/// data.
+///
+/// ### Arguments
+///
+/// * `item_type_name` - The name of the item type the data belongs to
+/// * `item_data` - The JSON data submitted for the item
+///
+/// ### Returns
+///
+/// Ok(()) if the data is valid, or an `InvalidItemDataError` describing the
+/// first problem found
+pub fn validate_item_data(
+	item_type_name: &str,
+	item_data: &serde_json::Value,
+) -> Result<(), InvalidItemDataError> {
+	match item_type_name {
+		"Cloze" => {
+			match item_data.get("clozes").and_then(|c| c.as_array()) {
+				Some(clozes) if !clozes.is_empty() => {}
+				Some(_) => {
+					return Err(InvalidItemDataError(
+						"Cloze items require at least one entry in `clozes`".to_string(),
+					));
+				}
+				None => {
+					return Err(InvalidItemDataError(
+						"Cloze items require a `clozes` array".to_string(),
+					));
+				}
+			}
+			match item_data.get("cloze_mode") {
+				None => Ok(()),
+				Some(mode) if mode == "per_deletion" || mode == "all_in_one" => Ok(()),
+				Some(_) => Err(InvalidItemDataError(
+					"Cloze `cloze_mode` must be \"per_deletion\" or \"all_in_one\"".to_string(),
+				)),
+			}
+		}
+		"Basic" => {
+			for field in ["front", "back"] {
+				if !item_data.get(field).is_some_and(|v| v.is_string()) {
+					return Err(InvalidItemDataError(format!(
+						"Basic items require a string `{}` field",
+						field
+					)));
+				}
+			}
+			match item_data.get("reversible") {
+				None | Some(serde_json::Value::Null | serde_json::Value::Bool(_)) => Ok(()),
+				Some(_) => Err(InvalidItemDataError(
+					"Basic `reversible` must be a boolean".to_string(),
+				)),
+			}
+		}
+		"Todo" => match item_data.get("due_date") {
+			None | Some(serde_json::Value::Null) => Ok(()),
+			Some(serde_json::Value::String(due)) if parse_due_date(due, Tz::UTC).is_some() => {
+				Ok(())
+			}
+			Some(_) => Err(InvalidItemDataError(
+				"Todo `due_date` must be a YYYY-MM-DD date or an RFC 3339 timestamp".to_string(),
+			)),
+		},
+		"RecurringTodo" => match item_data.get("every").and_then(|every| every.as_str()) {
+			Some(every) => every
+				.parse::<Recurrence>()
+				.map(|_| ())
+				.map_err(|e| InvalidItemDataError(format!("RecurringTodo `every` is an {}", e))),
+			None => Err(InvalidItemDataError(
+				"RecurringTodo items require a string `every` field, e.g. \"1d\"".to_string(),
+			)),
+		},
+		_ => Ok(()),
+	}
+}
+
+/// Looks for things in `item_data` that are allowed but probably a mistake
+///
+/// Unlike `validate_item_data`, nothing found here stops the item being
+/// created; the warnings are passed back to the client to show. Basic items
+/// are warned about an empty `front` or `back`. Cloze items are warned about
+/// an empty `text`, empty `clozes` entries, and entries that don't appear in
+/// the text (which would leave nothing blanked out). Data that fails
+/// `validate_item_data` gets no warnings for the fields it's missing.
+///
+/// ### Arguments
+///
+/// * `item_type_name` - The name of the item type the data belongs to
+/// * `item_data` - The JSON data submitted for the item
+///
+/// ### Returns
+///
+/// A message for each problem found, in field order, or an empty list
+pub fn validate_item_data_warnings(
+	item_type_name: &str,
+	item_data: &serde_json::Value,
+) -> Vec<String> {
+	let is_blank = |value: &serde_json::Value| value.as_str().is_some_and(|s| s.trim().is_empty());
+
+	let mut warnings = Vec::new();
+	match item_type_name {
+		"Basic" => {
+			for field in ["front", "back"] {
+				if item_data.get(field).is_some_and(is_blank) {
+					warnings.push(format!("`{}` is empty", field));
+				}
+			}
+		}
+		"Cloze" => {
+			let text = item_data.get("text").and_then(|t| t.as_str());
+			if text.is_some_and(|t| t.trim().is_empty()) {
+				warnings.push("`text` is empty".to_string());
+			}
+			let clozes = item_data.get("clozes").and_then(|c| c.as_array());
+			for (index, cloze) in clozes.into_iter().flatten().enumerate() {
+				let Some(cloze) = cloze.as_str() else {
+					continue;
+				};
+				if cloze.trim().is_empty() {
+					warnings.push(format!("`clozes` entry {} is empty", index));
+				} else if let Some(text) = text
+					&& !text.contains(cloze)
+				{
+					warnings.push(format!(
+						"`clozes` entry {} ({:?}) doesn't appear in `text`",
+						index, cloze
+					));
+				}
+			}
+		}
+		_ => {}
+	}
+	warnings
+}
+
+/// Checks `item_data` against everything its item type requires
+///
+/// Runs the built-in shape checks from `validate_item_data`, then validates
+/// against the item type's `item_data_schema` if it has one.
+///
+/// ### Arguments
+///
+/// * `item_type` - The item type the data belongs to
+/// * `item_data` - The JSON data submitted for the item
+///
+/// ### Returns
+///
+/// Ok(()) if the data is valid
+///
+/// ### Errors
+///
+/// Returns an `InvalidItemDataError` listing every schema violation, or an
+/// `InvalidItemDataSchemaError` if the stored schema can't be compiled
+fn check_item_data(item_type: &ItemType, item_data: &serde_json::Value) -> Result<()> {
+	validate_item_data(&item_type.get_name(), item_data)?;
+
+	let Some(schema) = item_type.get_item_data_schema() else {
+		return Ok(());
+	};
+
+	let validator = super::compile_item_data_schema(&schema.0)?;
+	let violations: Vec<String> = validator
+		.iter_errors(item_data)
+		.map(|e| format!("{} (at '{}')", e, e.instance_path()))
+		.collect();
+
+	if violations.is_empty() {
+		Ok(())
+	} else {
+		Err(InvalidItemDataError(format!(
+			"item data does not match the item type's schema: {}",
+			violations.join("; ")
+		))
+		.into())
+	}
+}
+
+/// Works out how many cards an item would get, without creating anything
+///
+/// Validates the data exactly as `create_item` does, then counts cards with
+/// the same logic `create_cards_for_item` uses, so a preview always matches
+/// what creating the item would produce.
+///
+/// ### Arguments
+///
+/// * `item_type` - The item type the item would have
+/// * `item_data` - The JSON data the item would have
+///
+/// ### Returns
+///
+/// A Result containing the number of cards the item would get
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - The item data is invalid for the item type (an `InvalidItemDataError`)
+/// - The item type is not one cards can be built for
+#[instrument(skip(item_type, item_data), fields(item_type_id = %item_type.get_id()))]
+pub fn preview_cards_for_item(
+	item_type: &ItemType,
+	item_data: &serde_json::Value,
+) -> Result<usize> {
+	debug!("Previewing cards for item");
+
+	check_item_data(item_type, item_data)?;
+	card_count_for_item(item_type, item_data)
+}
 
 /// Creates a new item in the database
 ///
@@ -25,6 +283,8 @@ use super::query_repo;
 /// ### Errors
 ///
 /// Returns an error if:
+/// - The item type does not exist
+/// - The item data is invalid for the item type (an `InvalidItemDataError`)
 /// - Unable to get a connection from the pool
 /// - The database insert operation fails
 #[instrument(skip(pool, item_data), fields(item_type_id = %item_type_id, title = %new_title))]
@@ -34,25 +294,96 @@ pub async fn create_item(
 	new_title: String,
 	item_data: serde_json::Value,
 ) -> Result<Item> {
+	let (item, _) = create_item_with_id(pool, None, item_type_id, new_title, item_data).await?;
+	Ok(item)
+}
+
+/// Creates a new item in the database, optionally with a caller-chosen ID
+///
+/// With an ID this is idempotent: if an item with that ID already exists it
+/// is returned unchanged and nothing is inserted, so a client can safely
+/// retry a create whose response it never saw. The insert uses `ON
+/// CONFLICT(id) DO NOTHING`, so concurrent retries can't race each other
+/// into a duplicate, while a clashing title is still an error.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `item_id` - The ID to give the item, or None to generate one
+/// * `item_type_id` - The ID of the item type for this item
+/// * `new_title` - The title for the new item
+/// * `item_data` - JSON data specific to this item type
+///
+/// ### Returns
+///
+/// A Result containing the newly created Item, or the existing one with the
+/// given ID, along with its item type
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - The item type does not exist
+/// - The item data is invalid for the item type (an `InvalidItemDataError`)
+/// - Another item already has the title (a unique violation)
+/// - Unable to get a connection from the pool
+/// - The database operations fail
+#[instrument(skip(pool, item_data), fields(item_type_id = %item_type_id, title = %new_title))]
+pub async fn create_item_with_id(
+	pool: &DbPool,
+	item_id: Option<ItemId>,
+	item_type_id: &ItemTypeId,
+	new_title: String,
+	item_data: serde_json::Value,
+) -> Result<(Item, ItemType)> {
 	debug!("Creating new item");
 
+	// Reject data that the item type can't build cards from before inserting anything
+	let item_type =
+		super::get_item_type(pool, item_type_id)?.ok_or_else(|| anyhow!("Item type not found"))?;
+	check_item_data(&item_type, &item_data)?;
+
 	// Get a connection from the pool
 	let mut conn = pool.get()?;
 
 	// Create a new item with the provided title
-	let new_item = Item::new(item_type_id.clone(), new_title, JsonValue(item_data));
+	let new_item = match item_id {
+		Some(item_id) => {
+			let now = now_ms().and_utc();
+			Item::new_with_fields(
+				item_id,
+				item_type_id.clone(),
+				new_title,
+				JsonValue(item_data),
+				now,
+				now,
+			)
+		}
+		None => Item::new(item_type_id.clone(), new_title, JsonValue(item_data)),
+	};
 
 	debug!(
 		"Inserting item into database with id: {}",
 		new_item.get_id()
 	);
 
-	// Insert the new item into the database
-	diesel::insert_into(items::table)
+	// Insert the new item into the database, leaving any existing item with
+	// the same ID untouched. Only a clashing ID is ignored; any other
+	// constraint, such as the unique title, still fails the insert.
+	let inserted = diesel::insert_into(items::table)
 		.values(new_item.clone())
+		.on_conflict(items::id)
+		.do_nothing()
 		.execute_with_retry(&mut conn)
 		.await?;
 
+	if inserted == 0 {
+		info!("Item {} already exists, returning it", new_item.get_id());
+		let existing = items::table
+			.find(new_item.get_id())
+			.first::<Item>(&mut conn)?;
+		return Ok((existing, item_type));
+	}
+
 	// Drop the connection back to the pool
 	drop(conn);
 
@@ -66,7 +397,203 @@ pub async fn create_item(
 	info!("Successfully created item with id: {}", new_item.get_id());
 
 	// Return the newly created item
-	Ok(new_item)
+	Ok((new_item, item_type))
+}
+
+/// Creates several items, with their cards, in a single transaction
+///
+/// Every item is validated before anything is written, and the items are
+/// then inserted in order, so either all of them are created or none are.
+/// As with `create_item_with_id`, an item given an ID that already exists is
+/// returned as stored rather than created again, so a batch can be safely
+/// resent; the existing item must have the same title, item type and data.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `new_items` - For each item: its ID (or None to generate one), item type
+///   ID, title and item data
+///
+/// ### Returns
+///
+/// A Result containing the created items, in the order they were given
+///
+/// ### Errors
+///
+/// Returns an `InvalidBatchItemError` naming the first item that:
+/// - Has an item type that does not exist
+/// - Has item data that is invalid for its item type
+/// - Has an item type that cards can't be built for
+/// - Has a title already used by another item, or by an earlier item in the
+///   batch
+/// - Has the ID of an existing item with a different title, item type or data
+///
+/// Otherwise returns an error if unable to get a connection from the pool or
+/// a database operation fails.
+#[instrument(skip(pool, new_items), fields(count = new_items.len()))]
+pub async fn create_items_batch(
+	pool: &DbPool,
+	new_items: Vec<(Option<ItemId>, ItemTypeId, String, serde_json::Value)>,
+) -> Result<Vec<Item>> {
+	debug!("Creating a batch of items");
+
+	let Settings {
+		default_priority,
+		timezone: tz,
+		..
+	} = super::get_settings(pool)?;
+	let now = chrono::Utc::now();
+
+	// Validate everything and build the cards before touching the database
+	let mut item_types: HashMap<ItemTypeId, ItemType> = HashMap::new();
+	let mut prepared: Vec<(Item, Vec<Card>)> = Vec::with_capacity(new_items.len());
+	for (index, (item_id, item_type_id, title, item_data)) in new_items.into_iter().enumerate() {
+		let invalid = |message: String| InvalidBatchItemError { index, message };
+
+		let item_type = match item_types.entry(item_type_id.clone()) {
+			Entry::Occupied(entry) => entry.into_mut(),
+			Entry::Vacant(entry) => match super::get_item_type(pool, &item_type_id)? {
+				Some(item_type) => entry.insert(item_type),
+				None => return Err(invalid(format!("Unknown item type: {}", item_type_id)).into()),
+			},
+		};
+		if let Err(e) = check_item_data(item_type, &item_data) {
+			return Err(match e.downcast::<InvalidItemDataError>() {
+				Ok(invalid_data) => invalid(invalid_data.to_string()).into(),
+				Err(e) => e,
+			});
+		}
+
+		let item = match item_id {
+			Some(item_id) => {
+				let created_at = now_ms().and_utc();
+				Item::new_with_fields(
+					item_id,
+					item_type_id,
+					title,
+					JsonValue(item_data),
+					created_at,
+					created_at,
+				)
+			}
+			None => Item::new(item_type_id, title, JsonValue(item_data)),
+		};
+		let cards = new_cards_for_item(item_type, &item, default_priority, tz, now)
+			.map_err(|e| invalid(e.to_string()))?;
+		prepared.push((item, cards));
+	}
+
+	let conn = &mut pool.get()?;
+	let result = transaction_with_retry(conn, |c| {
+		// Carried out of the transaction as the error that rolls it back
+		let reject = |index, message| {
+			DieselError::QueryBuilderError(Box::new(InvalidBatchItemError { index, message }))
+		};
+
+		let mut created = Vec::with_capacity(prepared.len());
+		for (index, (item, cards)) in prepared.iter().enumerate() {
+			if let Some(existing) = items::table
+				.find(item.get_id())
+				.first::<Item>(c)
+				.optional()?
+			{
+				if existing.get_title() != item.get_title()
+					|| existing.get_item_type() != item.get_item_type()
+					|| existing.get_data() != item.get_data()
+				{
+					return Err(reject(
+						index,
+						format!(
+							"Item {} already exists with a different title, item type or data",
+							item.get_id()
+						),
+					));
+				}
+				created.push(existing);
+				continue;
+			}
+
+			// Titles are unique; this also catches a repeat within the batch,
+			// since earlier items are already inserted
+			let taken = diesel::select(diesel::dsl::exists(
+				items::table.filter(items::title.eq(item.get_title())),
+			))
+			.get_result::<bool>(c)?;
+			if taken {
+				return Err(reject(
+					index,
+					format!("An item titled {:?} already exists", item.get_title()),
+				));
+			}
+
+			diesel::insert_into(items::table).values(item).execute(c)?;
+			diesel::insert_into(cards::table).values(cards).execute(c)?;
+			created.push(item.clone());
+		}
+		Ok(created)
+	})
+	.await;
+
+	let created = match result {
+		Ok(created) => created,
+		Err(DieselError::QueryBuilderError(e)) => {
+			return Err(match e.downcast::<InvalidBatchItemError>() {
+				Ok(invalid) => (*invalid).into(),
+				Err(e) => anyhow!(DieselError::QueryBuilderError(e)),
+			});
+		}
+		Err(e) => return Err(e.into()),
+	};
+
+	info!("Successfully created a batch of {} items", created.len());
+
+	Ok(created)
+}
+
+/// Builds the cards a new item gets, without inserting them
+///
+/// This is what `create_cards_for_item` inserts: the item type decides how
+/// many cards there are and which way round each one is, and every card
+/// starts at the item type's default priority (or `default_priority`) with
+/// its first review date worked out from the item type's review function.
+///
+/// ### Arguments
+///
+/// * `item_type` - The item's type
+/// * `item` - The new item
+/// * `default_priority` - The priority to use if the item type has none
+/// * `tz` - The timezone due dates are interpreted in
+/// * `now` - The current time
+///
+/// ### Returns
+///
+/// A Result containing the item's cards
+///
+/// ### Errors
+///
+/// Returns an error if the item type is not one cards can be built for
+pub(crate) fn new_cards_for_item(
+	item_type: &ItemType,
+	item: &Item,
+	default_priority: f32,
+	tz: Tz,
+	now: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<Card>> {
+	let card_count = card_count_for_item(item_type, &item.get_data().0)?;
+	let priority = item_type.get_default_priority().unwrap_or(default_priority);
+	let next_review = super::first_review_date(
+		&item_type.get_review_function(),
+		&item.get_data().0,
+		tz,
+		now,
+	);
+	Ok((0..card_count)
+		.map(|index| {
+			let mut card = Card::new(item.get_id(), index as i32, next_review, priority);
+			card.set_direction(card_direction_for_item(item_type, index));
+			card
+		})
+		.collect())
 }
 
 /// Retrieves an item from the database by its ID
@@ -108,8 +635,230 @@ pub fn get_item(pool: &DbPool, item_id: &ItemId) -> Result<Option<Item>> {
 	Ok(result)
 }
 
+/// Creates a copy of an item, with fresh cards and the same tags
+///
+/// The copy gets a new ID, the source's title with " (copy)" appended (or
+/// " (copy 2)", " (copy 3)" and so on, since titles are unique), and the
+/// source's `item_data`. Its cards are generated from scratch, as for a
+/// newly created item, so they have no review history. The item, its cards
+/// and its tags are inserted in one transaction.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `item_id` - The ID of the item to copy
+///
+/// ### Returns
+///
+/// A Result containing the new item, or None if the source item doesn't
+/// exist
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The item type is not one cards can be built for
+/// - A database operation fails
+#[instrument(skip(pool), fields(item_id = %item_id))]
+pub async fn duplicate_item(pool: &DbPool, item_id: &ItemId) -> Result<Option<Item>> {
+	debug!("Duplicating item");
+
+	let Some(source) = get_item(pool, item_id)? else {
+		debug!("Item not found");
+		return Ok(None);
+	};
+	let item_type = super::get_item_type(pool, &source.get_item_type())?
+		.ok_or_else(|| anyhow!("Item type not found"))?;
+
+	let mut new_item = Item::new(source.get_item_type(), String::new(), source.get_data());
+
+	// The copy's cards are built exactly as `create_cards_for_item` would
+	let settings = super::get_settings(pool)?;
+	let new_cards = new_cards_for_item(
+		&item_type,
+		&new_item,
+		settings.default_priority,
+		settings.timezone,
+		chrono::Utc::now(),
+	)?;
+
+	let conn = &mut pool.get()?;
+	let tag_count = transaction_with_retry(conn, |c| {
+		// Item titles are unique, so take the first copy title not in use
+		let mut copy_number = 1;
+		let title = loop {
+			let title = match copy_number {
+				1 => format!("{} (copy)", source.get_title()),
+				n => format!("{} (copy {})", source.get_title(), n),
+			};
+			let taken = diesel::select(diesel::dsl::exists(
+				items::table.filter(items::title.eq(&title)),
+			))
+			.get_result::<bool>(c)?;
+			if !taken {
+				break title;
+			}
+			copy_number += 1;
+		};
+		new_item.set_title(title);
+
+		diesel::insert_into(items::table)
+			.values(&new_item)
+			.execute(c)?;
+		diesel::insert_into(cards::table)
+			.values(&new_cards)
+			.execute(c)?;
+
+		let tag_ids: Vec<TagId> = item_tags::table
+			.filter(item_tags::item_id.eq(item_id))
+			.select(item_tags::tag_id)
+			.load(c)?;
+		let new_item_tags: Vec<ItemTag> = tag_ids
+			.into_iter()
+			.map(|tag_id| ItemTag::new(new_item.get_id(), tag_id))
+			.collect();
+		diesel::insert_into(item_tags::table)
+			.values(&new_item_tags)
+			.execute(c)
+	})
+	.await?;
+
+	info!(
+		"Duplicated item {} as {} with {} cards and {} tags",
+		item_id,
+		new_item.get_id(),
+		new_cards.len(),
+		tag_count
+	);
+
+	Ok(Some(new_item))
+}
+
+/// Moves an item to a different item type, reconciling its cards
+///
+/// The item's data must be valid for the new type. Its cards are then
+/// matched to the cards the new type calls for by `card_index`, as
+/// `regenerate_cards_for_item` does: cards whose index is still wanted are
+/// kept with their review history (and take the new type's direction),
+/// missing cards are created as for a new item, and cards past the end are
+/// deleted along with their reviews. Everything happens in one transaction.
+///
+/// If the new type has a different review function, the kept cards'
+/// `scheduler_data` means nothing to it, so they're rescheduled as new cards
+/// of the new type would be: no `scheduler_data`, and due on the new type's
+/// first review date (see [`first_review_date`](super::first_review_date)).
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `item_id` - The ID of the item to change
+/// * `item_type_id` - The ID of the item type to move it to
+///
+/// ### Returns
+///
+/// A Result containing the updated item, or None if the item doesn't exist
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - The item type does not exist (an `ItemTypeNotFoundError`)
+/// - The item's data is invalid for the new item type (an `InvalidItemDataError`)
+/// - The new item type is not one cards can be built for
+/// - Unable to get a connection from the pool
+/// - A database operation fails
+#[instrument(skip(pool), fields(item_id = %item_id, item_type_id = %item_type_id))]
+pub async fn change_item_type(
+	pool: &DbPool,
+	item_id: &ItemId,
+	item_type_id: &ItemTypeId,
+) -> Result<Option<Item>> {
+	debug!("Changing item type");
+
+	let Some(mut item) = get_item(pool, item_id)? else {
+		debug!("Item not found");
+		return Ok(None);
+	};
+	let item_type = super::get_item_type(pool, item_type_id)?
+		.ok_or_else(|| ItemTypeNotFoundError(item_type_id.clone()))?;
+	check_item_data(&item_type, &item.get_data().0)?;
+
+	// Kept cards are only rescheduled if the scheduler changes
+	let old_review_function =
+		super::get_item_type(pool, &item.get_item_type())?.map(|old| old.get_review_function());
+	let reschedule = old_review_function != Some(item_type.get_review_function());
+
+	item.set_item_type(item_type_id.clone());
+	let settings = super::get_settings(pool)?;
+	let new_cards = new_cards_for_item(
+		&item_type,
+		&item,
+		settings.default_priority,
+		settings.timezone,
+		chrono::Utc::now(),
+	)?;
+	let card_count = new_cards.len() as i32;
+
+	let conn = &mut pool.get()?;
+	let (created, removed) = transaction_with_retry(conn, |c| {
+		diesel::update(items::table.find(item_id))
+			.set(items::item_type.eq(item_type_id))
+			.execute(c)?;
+
+		let removed = diesel::delete(
+			cards::table
+				.filter(cards::item_id.eq(item_id))
+				.filter(cards::card_index.ge(card_count)),
+		)
+		.execute(c)?;
+
+		let existing: Vec<i32> = cards::table
+			.filter(cards::item_id.eq(item_id))
+			.select(cards::card_index)
+			.load(c)?;
+		for index in &existing {
+			let kept = cards::table
+				.filter(cards::item_id.eq(item_id))
+				.filter(cards::card_index.eq(index));
+			diesel::update(kept)
+				.set(cards::direction.eq(card_direction_for_item(&item_type, *index as usize)))
+				.execute(c)?;
+			if reschedule {
+				let new_card = &new_cards[*index as usize];
+				diesel::update(kept)
+					.set((
+						cards::next_review.eq(new_card.get_next_review().naive_utc()),
+						cards::scheduler_data.eq(None::<JsonValue>),
+					))
+					.execute(c)?;
+			}
+		}
+
+		let missing: Vec<Card> = new_cards
+			.iter()
+			.filter(|card| !existing.contains(&card.get_card_index()))
+			.cloned()
+			.collect();
+		diesel::insert_into(cards::table)
+			.values(&missing)
+			.execute(c)?;
+
+		Ok((missing.len(), removed))
+	})
+	.await?;
+
+	info!(
+		"Changed item {} to type {}: {} cards created, {} removed",
+		item_id, item_type_id, created, removed
+	);
+
+	get_item(pool, item_id)
+}
+
 /// Updates an item in the database by its ID
 ///
+/// If the item's type uses the `"todo"` review function and the update
+/// changes its `due_date`, the item's cards are rescheduled for the new date.
+///
 /// ### Arguments
 ///
 /// * `pool` - A reference to the database connection pool
@@ -127,6 +876,7 @@ pub fn get_item(pool: &DbPool, item_id: &ItemId) -> Result<Option<Item>> {
 /// - Unable to get a connection from the pool
 /// - The database update operation fails
 /// - The item is not found
+/// - The new item data is invalid for the item type (an `InvalidItemDataError`)
 #[instrument(skip(pool), fields(item_id = %item_id))]
 pub async fn update_item(
 	pool: &DbPool,
@@ -145,6 +895,23 @@ pub async fn update_item(
 		return Ok(existing_item);
 	}
 
+	// New data must still satisfy the item type. A todo whose due date
+	// changes is rescheduled for the new date.
+	let mut new_due_date = None;
+	if let Some(ref data) = item_data {
+		let item_type = super::get_item_type(pool, &existing_item.get_item_type())?
+			.ok_or_else(|| anyhow!("Item type not found"))?;
+		check_item_data(&item_type, data)?;
+
+		if item_type.get_review_function() == "todo" {
+			let tz = super::get_settings(pool)?.timezone;
+			let due_date = super::todo_due_date(data, tz);
+			if due_date != super::todo_due_date(&existing_item.get_data().0, tz) {
+				new_due_date = due_date;
+			}
+		}
+	}
+
 	// Create a struct for changeset that implements AsChangeset
 	// This allows us to only include fields that are Some
 	// Note: updated_at is managed by a SQLite trigger when title or item_data changes
@@ -168,6 +935,14 @@ pub async fn update_item(
 		.execute_with_retry(&mut conn)
 		.await?;
 
+	if let Some(due_date) = new_due_date {
+		debug!("Rescheduling todo for its new due date {}", due_date);
+		diesel::update(cards::table.filter(cards::item_id.eq(item_id.clone())))
+			.set(cards::next_review.eq(due_date.naive_utc()))
+			.execute_with_retry(&mut conn)
+			.await?;
+	}
+
 	drop(conn);
 
 	// Get the updated item
@@ -263,6 +1038,45 @@ pub fn list_items_with_filters(pool: &DbPool, query: &GetQueryDto) -> Result<Vec
 	Ok(result)
 }
 
+/// Lists the items matching a query, each with the name of its item type
+///
+/// The names come from a join with `item_types` in the same query, rather
+/// than a lookup per item.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `query` - The filters to apply
+///
+/// ### Returns
+///
+/// A Result containing the matching items, each paired with its item type's
+/// name
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+#[instrument(skip(pool), fields(query = ?query))]
+pub fn list_items_with_item_type_names(
+	pool: &DbPool,
+	query: &GetQueryDto,
+) -> Result<Vec<(Item, String)>> {
+	debug!("Listing items with item type names");
+	let conn = &mut pool.get()?;
+	let result = items::table
+		.inner_join(item_types::table)
+		.filter(items::id.eq_any(query_repo::items_matching(query)))
+		.select((Item::as_select(), item_types::name))
+		.load::<(Item, String)>(conn)?;
+	info!(
+		"Retrieved {} items with item type names from query filter",
+		result.len()
+	);
+	Ok(result)
+}
+
 /// Retrieves items of a specific type from the database
 ///
 /// ### Arguments
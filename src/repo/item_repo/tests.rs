@@ -29,6 +29,87 @@ async fn test_create_item() {
 	assert_eq!(item.get_data().0, data);
 }
 
+#[tokio::test]
+async fn test_create_item_with_id_is_idempotent() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let id = ItemId::new();
+	let data = json!({"front": "Hello", "back": "World"});
+
+	let (first, first_item_type) = create_item_with_id(
+		&pool,
+		Some(id.clone()),
+		&item_type.get_id(),
+		"First".to_string(),
+		data.clone(),
+	)
+	.await
+	.unwrap();
+	assert_eq!(first.get_id(), id);
+	assert_eq!(first_item_type, item_type);
+
+	// Retrying with the same ID returns the original item untouched
+	let (second, _) = create_item_with_id(
+		&pool,
+		Some(id.clone()),
+		&item_type.get_id(),
+		"Second".to_string(),
+		data,
+	)
+	.await
+	.unwrap();
+	assert_eq!(second, first);
+
+	// Only one item, with one set of cards, was created
+	assert_eq!(list_items(&pool).unwrap().len(), 1);
+	assert_eq!(
+		crate::repo::get_cards_for_item(&pool, &id).unwrap().len(),
+		2
+	);
+}
+
+#[tokio::test]
+async fn test_create_item_with_new_id_and_taken_title_fails() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let data = json!({"front": "Hello", "back": "World"});
+	create_item(&pool, &item_type.get_id(), "Word".to_string(), data.clone())
+		.await
+		.unwrap();
+
+	// Only a clashing ID makes the create a no-op; a clashing title is
+	// still rejected by the unique constraint
+	for item_id in [Some(ItemId::new()), None] {
+		let error = create_item_with_id(
+			&pool,
+			item_id,
+			&item_type.get_id(),
+			"Word".to_string(),
+			data.clone(),
+		)
+		.await
+		.unwrap_err();
+		assert!(
+			matches!(
+				error.downcast_ref::<DieselError>(),
+				Some(DieselError::DatabaseError(
+					diesel::result::DatabaseErrorKind::UniqueViolation,
+					_
+				))
+			),
+			"got: {}",
+			error
+		);
+	}
+	assert_eq!(list_items(&pool).unwrap().len(), 1);
+}
+
 #[tokio::test]
 async fn test_get_item() {
 	let pool = setup_test_db();
@@ -742,8 +823,308 @@ async fn test_update_with_empty_changes() {
 	// without mocking time or introducing complex test logic
 }
 
+#[tokio::test]
+async fn test_create_vocabulary_item_creates_two_cards() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Vocabulary".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Hund".to_string(),
+		json!({"term": "Hund", "definition": "dog"}),
+	)
+	.await
+	.unwrap();
+
+	// One card for term→definition and one for definition→term
+	let cards = crate::repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+	let mut indices: Vec<_> = cards
+		.iter()
+		.map(|c| (c.get_card_index(), c.get_direction()))
+		.collect();
+	indices.sort_by_key(|(index, _)| *index);
+	assert_eq!(
+		indices,
+		vec![
+			(0, Some(crate::models::CardDirection::Forward)),
+			(1, Some(crate::models::CardDirection::Reverse)),
+		]
+	);
+}
+
+// ============================================================================
+// Item data validation tests
+// ============================================================================
+
+/// Creates an item of a freshly created item type and returns the validation
+/// error it was rejected with
+async fn create_invalid_item(type_name: &str, data: serde_json::Value) -> InvalidItemDataError {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, type_name.to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+
+	let err = create_item(&pool, &item_type.get_id(), "Title".to_string(), data)
+		.await
+		.unwrap_err();
+
+	// Nothing should have been inserted
+	assert!(list_items(&pool).unwrap().is_empty());
+
+	err.downcast::<InvalidItemDataError>()
+		.expect("error should be an InvalidItemDataError")
+}
+
+#[tokio::test]
+async fn test_create_cloze_item_without_clozes_is_rejected() {
+	let err = create_invalid_item("Cloze", json!({"text": "Paris is in France"})).await;
+	assert!(err.0.contains("`clozes` array"), "got: {}", err);
+}
+
+#[tokio::test]
+async fn test_create_cloze_item_with_non_array_clozes_is_rejected() {
+	let err = create_invalid_item("Cloze", json!({"clozes": "Paris"})).await;
+	assert!(err.0.contains("`clozes` array"), "got: {}", err);
+}
+
+#[tokio::test]
+async fn test_create_cloze_item_with_empty_clozes_is_rejected() {
+	let err = create_invalid_item("Cloze", json!({"clozes": []})).await;
+	assert!(err.0.contains("at least one entry"), "got: {}", err);
+}
+
+#[tokio::test]
+async fn test_create_cloze_item_with_unknown_cloze_mode_is_rejected() {
+	let err = create_invalid_item(
+		"Cloze",
+		json!({"clozes": ["Paris"], "cloze_mode": "sideways"}),
+	)
+	.await;
+	assert!(err.0.contains("`cloze_mode`"), "got: {}", err);
+}
+
+#[tokio::test]
+async fn test_create_basic_item_without_front_is_rejected() {
+	let err = create_invalid_item("Basic", json!({"back": "World"})).await;
+	assert!(err.0.contains("`front`"), "got: {}", err);
+}
+
+#[tokio::test]
+async fn test_create_basic_item_without_back_is_rejected() {
+	let err = create_invalid_item("Basic", json!({"front": "Hello"})).await;
+	assert!(err.0.contains("`back`"), "got: {}", err);
+}
+
+#[test]
+fn test_validate_item_data_warnings() {
+	// Nothing to point out in well-formed data, or for other item types
+	assert!(
+		validate_item_data_warnings("Basic", &json!({"front": "Hello", "back": "World"}))
+			.is_empty()
+	);
+	assert!(
+		validate_item_data_warnings(
+			"Cloze",
+			&json!({"text": "Paris is in France", "clozes": ["Paris", "France"]})
+		)
+		.is_empty()
+	);
+	assert!(validate_item_data_warnings("Test Type", &json!({"front": ""})).is_empty());
+
+	assert_eq!(
+		validate_item_data_warnings("Basic", &json!({"front": "  ", "back": ""})),
+		vec!["`front` is empty", "`back` is empty"]
+	);
+	assert_eq!(
+		validate_item_data_warnings("Basic", &json!({"front": "", "back": "World"})),
+		vec!["`front` is empty"]
+	);
+	assert_eq!(
+		validate_item_data_warnings(
+			"Cloze",
+			&json!({"text": "Paris is in France", "clozes": ["", "Berlin"]})
+		),
+		vec![
+			"`clozes` entry 0 is empty",
+			"`clozes` entry 1 (\"Berlin\") doesn't appear in `text`"
+		]
+	);
+	assert_eq!(
+		validate_item_data_warnings("Cloze", &json!({"text": " ", "clozes": ["Paris"]})),
+		vec!["`text` is empty", "`clozes` entry 0 (\"Paris\") doesn't appear in `text`"]
+	);
+	// A missing `text` is left to `validate_item_data` to reject
+	assert!(validate_item_data_warnings("Cloze", &json!({"clozes": ["Paris"]})).is_empty());
+}
+
+#[tokio::test]
+async fn test_create_basic_item_with_non_boolean_reversible_is_rejected() {
+	let err = create_invalid_item(
+		"Basic",
+		json!({"front": "Hello", "back": "World", "reversible": "yes"}),
+	)
+	.await;
+	assert!(err.0.contains("`reversible`"), "got: {}", err);
+}
+
+#[tokio::test]
+async fn test_create_basic_item_with_non_string_front_is_rejected() {
+	let err = create_invalid_item("Basic", json!({"front": 42, "back": "World"})).await;
+	assert!(err.0.contains("`front`"), "got: {}", err);
+}
+
+#[tokio::test]
+async fn test_create_cloze_item_creates_card_per_cloze() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Cloze".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Capitals".to_string(),
+		json!({"text": "Paris is in France", "clozes": ["Paris", "France"]}),
+	)
+	.await
+	.unwrap();
+
+	let cards = crate::repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+	assert_eq!(cards.len(), 2);
+}
+
+#[tokio::test]
+async fn test_create_cloze_item_card_count_follows_cloze_mode() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Cloze".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+
+	for (mode, expected) in [("per_deletion", 3), ("all_in_one", 1)] {
+		let item = create_item(
+			&pool,
+			&item_type.get_id(),
+			format!("Capitals ({})", mode),
+			json!({
+				"text": "Paris is in France, Rome is in Italy",
+				"clozes": ["Paris", "France", "Rome"],
+				"cloze_mode": mode,
+			}),
+		)
+		.await
+		.unwrap();
+
+		let cards = crate::repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+		assert_eq!(cards.len(), expected, "cloze_mode {}", mode);
+		assert_eq!(cards[0].get_card_index(), 0);
+	}
+}
+
+/// Creates an item type whose item data must have string `term` and
+/// `definition` fields
+async fn create_glossary_type(pool: &DbPool) -> crate::models::ItemType {
+	crate::repo::create_item_type_with_schema(
+		pool,
+		"Glossary Test".to_string(),
+		"fsrs".to_string(),
+		Some(json!({
+			"type": "object",
+			"required": ["term", "definition"],
+			"properties": {
+				"term": {"type": "string"},
+				"definition": {"type": "string"}
+			}
+		})),
+	)
+	.await
+	.unwrap()
+}
+
+#[tokio::test]
+async fn test_create_item_matching_schema() {
+	let pool = setup_test_db();
+	let item_type = create_glossary_type(&pool).await;
+
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Hund".to_string(),
+		json!({"term": "Hund", "definition": "dog"}),
+	)
+	.await
+	.unwrap();
+
+	assert_eq!(item.get_item_type(), item_type.get_id());
+}
+
+#[tokio::test]
+async fn test_create_item_violating_schema_is_rejected() {
+	let pool = setup_test_db();
+	let item_type = create_glossary_type(&pool).await;
+
+	let err = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Hund".to_string(),
+		json!({"term": 42}),
+	)
+	.await
+	.unwrap_err()
+	.downcast::<InvalidItemDataError>()
+	.expect("error should be an InvalidItemDataError");
+
+	// Every violation is reported
+	assert!(
+		err.0.contains("\"definition\" is a required property"),
+		"got: {}",
+		err
+	);
+	assert!(err.0.contains("/term"), "got: {}", err);
+	assert!(list_items(&pool).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_update_item_violating_schema_is_rejected() {
+	let pool = setup_test_db();
+	let item_type = create_glossary_type(&pool).await;
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Hund".to_string(),
+		json!({"term": "Hund", "definition": "dog"}),
+	)
+	.await
+	.unwrap();
+
+	let err = update_item(&pool, &item.get_id(), None, Some(json!({"term": "Hund"})))
+		.await
+		.unwrap_err();
+	assert!(err.downcast_ref::<InvalidItemDataError>().is_some());
+
+	// The stored data is unchanged
+	let unchanged = get_item(&pool, &item.get_id()).unwrap().unwrap();
+	assert_eq!(
+		unchanged.get_data().0,
+		json!({"term": "Hund", "definition": "dog"})
+	);
+
+	// A title-only update doesn't touch the data, so it isn't validated
+	update_item(&pool, &item.get_id(), Some("Der Hund".to_string()), None)
+		.await
+		.unwrap();
+}
+
+#[test]
+fn test_validate_item_data_accepts_anything_for_other_types() {
+	assert!(validate_item_data("Todo", &json!(null)).is_ok());
+	assert!(validate_item_data("Vocabulary", &json!({"anything": 1})).is_ok());
+}
+
 /// Regression: create_item with an item type whose name doesn't contain "Test"
-/// (or match "Basic"/"Cloze"/"Todo") fails because create_cards_for_item
+/// (or match "Basic"/"Cloze"/"Vocabulary"/"Todo") fails because create_cards_for_item
 /// doesn't know how to construct cards for unknown item type names.
 #[tokio::test]
 async fn test_create_item_unknown_item_type_name_fails() {
@@ -879,3 +1260,477 @@ async fn test_list_items_with_child_item_id_filter() {
 	assert!(ids.contains(&parent1.get_id()));
 	assert!(ids.contains(&parent2.get_id()));
 }
+
+#[tokio::test]
+async fn test_preview_cards_for_item_matches_created_cards() {
+	let pool = setup_test_db();
+
+	let cases = [
+		("Basic", json!({"front": "Hello", "back": "World"}), 1),
+		(
+			"Basic",
+			json!({"front": "Hello", "back": "World", "reversible": true}),
+			2,
+		),
+		(
+			"Vocabulary",
+			json!({"term": "hello", "definition": "hi"}),
+			2,
+		),
+		(
+			"Cloze",
+			json!({"text": "Paris is in France, Rome is in Italy", "clozes": ["Paris", "France", "Rome"]}),
+			3,
+		),
+	];
+
+	let mut item_types = std::collections::HashMap::new();
+	for (type_name, item_data, expected) in cases {
+		if !item_types.contains_key(type_name) {
+			let item_type = create_item_type(&pool, type_name.to_string(), "fsrs".to_string())
+				.await
+				.unwrap();
+			item_types.insert(type_name, item_type);
+		}
+		let item_type = &item_types[type_name];
+		let items_before = get_items_by_type(&pool, &item_type.get_id()).unwrap().len();
+
+		let preview = preview_cards_for_item(item_type, &item_data).unwrap();
+		assert_eq!(preview, expected, "{} preview", type_name);
+
+		// Previewing writes nothing
+		assert_eq!(
+			get_items_by_type(&pool, &item_type.get_id()).unwrap().len(),
+			items_before
+		);
+
+		// And creating the item gives exactly the previewed cards
+		let title = format!("{} with {} cards", type_name, expected);
+		let item = create_item(&pool, &item_type.get_id(), title, item_data)
+			.await
+			.unwrap();
+		let cards = crate::repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+		assert_eq!(cards.len(), preview, "{} created", type_name);
+	}
+}
+
+#[tokio::test]
+async fn test_preview_cards_for_item_rejects_invalid_data() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Cloze".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+
+	let err = preview_cards_for_item(&item_type, &json!({"clozes": []})).unwrap_err();
+	assert!(err.downcast::<InvalidItemDataError>().is_ok());
+}
+
+#[test]
+fn test_validate_item_data_checks_todo_due_date() {
+	assert!(validate_item_data("Todo", &json!({"due_date": "2026-11-01"})).is_ok());
+	assert!(validate_item_data("Todo", &json!({"due_date": "2026-11-01T09:00:00Z"})).is_ok());
+	assert!(validate_item_data("Todo", &json!({"due_date": null})).is_ok());
+	assert!(validate_item_data("Todo", &json!({"due_date": "tomorrow"})).is_err());
+	assert!(validate_item_data("Todo", &json!({"due_date": 20261101})).is_err());
+}
+
+#[test]
+fn test_validate_item_data_checks_recurring_todo_every() {
+	for every in ["1d", "2w", "1mo"] {
+		assert!(validate_item_data("RecurringTodo", &json!({ "every": every })).is_ok());
+	}
+	for data in [
+		json!({}),
+		json!({"every": 1}),
+		json!({"every": "daily"}),
+		json!({"every": "0d"}),
+	] {
+		assert!(
+			validate_item_data("RecurringTodo", &data).is_err(),
+			"{} should be rejected",
+			data
+		);
+	}
+}
+
+#[tokio::test]
+async fn test_item_timestamps() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let before = chrono::Utc::now() - chrono::Duration::seconds(1);
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Original Title".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let stored = get_item(&pool, &item.get_id()).unwrap().unwrap();
+	assert!(stored.get_created_at() >= before);
+	assert_eq!(stored.get_updated_at(), stored.get_created_at());
+
+	// Changing the item bumps updated_at but leaves created_at alone
+	std::thread::sleep(std::time::Duration::from_millis(10));
+	let updated = update_item(&pool, &item.get_id(), Some("New Title".to_string()), None)
+		.await
+		.unwrap();
+	assert_eq!(updated.get_created_at(), stored.get_created_at());
+	assert!(updated.get_updated_at() > stored.get_updated_at());
+}
+
+/// Tests that duplicating an item copies its data and tags but not its
+/// cards' history
+///
+/// This test verifies that:
+/// 1. The copy has a new ID, the title with " (copy)" appended and the same
+///    data and item type
+/// 2. The copy has the same tags as the source
+/// 3. The copy has as many cards as the source, all new and unreviewed
+/// 4. Duplicating it again doesn't reuse the first copy's title
+/// 5. Duplicating an unknown item returns None
+#[tokio::test]
+async fn test_duplicate_item() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let data = json!({"front": "Hello", "back": "World"});
+	let source = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Greeting".to_string(),
+		data.clone(),
+	)
+	.await
+	.unwrap();
+
+	let mut tag_ids = Vec::new();
+	for name in ["Alpha", "Beta"] {
+		let tag = crate::repo::create_tag(&pool, name.to_string(), true)
+			.await
+			.unwrap();
+		crate::repo::add_tag_to_item(&pool, &tag.get_id(), &source.get_id())
+			.await
+			.unwrap();
+		tag_ids.push(tag.get_id());
+	}
+
+	// Give the source some history, which the copy shouldn't get
+	let source_cards = crate::repo::get_cards_for_item(&pool, &source.get_id()).unwrap();
+	for card in &source_cards {
+		crate::repo::record_review(&pool, &card.get_id(), crate::models::Rating::Good)
+			.await
+			.unwrap();
+	}
+
+	let copy = duplicate_item(&pool, &source.get_id())
+		.await
+		.unwrap()
+		.unwrap();
+
+	assert_ne!(copy.get_id(), source.get_id());
+	assert_eq!(copy.get_title(), "Greeting (copy)");
+	assert_eq!(copy.get_data().0, data);
+	assert_eq!(copy.get_item_type(), item_type.get_id());
+	assert_eq!(get_item(&pool, &copy.get_id()).unwrap(), Some(copy.clone()));
+
+	let mut copy_tag_ids: Vec<TagId> = crate::repo::list_tags_for_item(&pool, &copy.get_id())
+		.unwrap()
+		.iter()
+		.map(|tag| tag.get_id())
+		.collect();
+	copy_tag_ids.sort_by(|a, b| a.0.cmp(&b.0));
+	tag_ids.sort_by(|a, b| a.0.cmp(&b.0));
+	assert_eq!(copy_tag_ids, tag_ids);
+
+	let copy_cards = crate::repo::get_cards_for_item(&pool, &copy.get_id()).unwrap();
+	assert_eq!(copy_cards.len(), source_cards.len());
+	for card in &copy_cards {
+		assert!(source_cards.iter().all(|c| c.get_id() != card.get_id()));
+		assert_eq!(card.get_last_review(), None);
+		assert!(
+			crate::repo::get_reviews_for_card(&pool, &card.get_id())
+				.unwrap()
+				.is_empty()
+		);
+	}
+
+	// Copies of the same item get titles of their own
+	let second = duplicate_item(&pool, &source.get_id())
+		.await
+		.unwrap()
+		.unwrap();
+	assert_eq!(second.get_title(), "Greeting (copy 2)");
+	let copy_of_copy = duplicate_item(&pool, &copy.get_id())
+		.await
+		.unwrap()
+		.unwrap();
+	assert_eq!(copy_of_copy.get_title(), "Greeting (copy) (copy)");
+
+	let missing = duplicate_item(&pool, &ItemId("item-missing".to_string()))
+		.await
+		.unwrap();
+	assert_eq!(missing, None);
+}
+
+#[tokio::test]
+async fn test_create_items_batch() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+
+	let new_items = (0..3)
+		.map(|i| {
+			(
+				None,
+				item_type.get_id(),
+				format!("Word {}", i),
+				json!({"front": format!("Front {}", i), "back": "Back"}),
+			)
+		})
+		.collect();
+	let created = create_items_batch(&pool, new_items).await.unwrap();
+
+	let titles: Vec<String> = created.iter().map(|item| item.get_title()).collect();
+	assert_eq!(titles, vec!["Word 0", "Word 1", "Word 2"]);
+	for item in &created {
+		assert_eq!(get_item(&pool, &item.get_id()).unwrap(), Some(item.clone()));
+		let cards = crate::repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+		assert_eq!(cards.len(), 1);
+	}
+}
+
+#[tokio::test]
+async fn test_create_items_batch_rolls_back_on_invalid_item() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let valid = |title: &str| {
+		(
+			None,
+			item_type.get_id(),
+			title.to_string(),
+			json!({"front": "Front", "back": "Back"}),
+		)
+	};
+
+	// Bad item data is caught before anything is written
+	let bad_data = vec![
+		valid("First"),
+		(
+			None,
+			item_type.get_id(),
+			"Second".to_string(),
+			json!({"front": "Front"}),
+		),
+	];
+	let error = create_items_batch(&pool, bad_data).await.unwrap_err();
+	let invalid = error.downcast::<InvalidBatchItemError>().unwrap();
+	assert_eq!(invalid.index, 1);
+
+	// A repeated title is caught inside the transaction, which rolls back
+	let repeated_title = vec![valid("First"), valid("Second"), valid("First")];
+	let error = create_items_batch(&pool, repeated_title).await.unwrap_err();
+	let invalid = error.downcast::<InvalidBatchItemError>().unwrap();
+	assert_eq!(invalid.index, 2);
+
+	let unknown_type = vec![(
+		None,
+		ItemTypeId("item_type-missing".to_string()),
+		"Third".to_string(),
+		json!({}),
+	)];
+	let error = create_items_batch(&pool, unknown_type).await.unwrap_err();
+	let invalid = error.downcast::<InvalidBatchItemError>().unwrap();
+	assert_eq!(invalid.index, 0);
+
+	assert!(list_items(&pool).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_create_items_batch_with_existing_id() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let id = ItemId::new();
+	let entry = |title: &str, back: &str| {
+		(
+			Some(id.clone()),
+			item_type.get_id(),
+			title.to_string(),
+			json!({"front": "Front", "back": back}),
+		)
+	};
+	let created = create_items_batch(&pool, vec![entry("Word", "Back")])
+		.await
+		.unwrap();
+
+	// Resending the same item returns it as stored
+	let resent = create_items_batch(&pool, vec![entry("Word", "Back")])
+		.await
+		.unwrap();
+	assert_eq!(resent, created);
+	assert_eq!(list_items(&pool).unwrap().len(), 1);
+
+	// But the ID can't be reused for a different item
+	for changed in [entry("Other word", "Back"), entry("Word", "Other back")] {
+		let error = create_items_batch(&pool, vec![changed]).await.unwrap_err();
+		let invalid = error.downcast::<InvalidBatchItemError>().unwrap();
+		assert_eq!(invalid.index, 0);
+	}
+	assert_eq!(get_item(&pool, &id).unwrap(), Some(created[0].clone()));
+}
+
+#[tokio::test]
+async fn test_change_item_type() {
+	let pool = setup_test_db();
+	let basic = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let test_type = create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&basic.get_id(),
+		"Word".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let original = crate::repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+	assert_eq!(original.len(), 1);
+	crate::repo::record_review(&pool, &original[0].get_id(), crate::models::Rating::Good)
+		.await
+		.unwrap();
+
+	// Moving to a type with two cards keeps the first and creates the second
+	let changed = change_item_type(&pool, &item.get_id(), &test_type.get_id())
+		.await
+		.unwrap()
+		.unwrap();
+	assert_eq!(changed.get_item_type(), test_type.get_id());
+	let mut cards = crate::repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+	cards.sort_by_key(Card::get_card_index);
+	assert_eq!(cards.len(), 2);
+	assert_eq!(cards[0].get_id(), original[0].get_id());
+	assert!(cards[0].get_last_review().is_some());
+	assert_eq!(cards[0].get_direction(), None);
+	// Both types use FSRS, so the kept card's schedule stands
+	assert!(cards[0].get_scheduler_data().is_some());
+	assert!(cards[0].get_next_review() > chrono::Utc::now());
+	assert!(cards[1].get_last_review().is_none());
+	let reviews = crate::repo::get_reviews_for_card(&pool, &cards[0].get_id()).unwrap();
+	assert_eq!(reviews.len(), 1);
+
+	// Moving back drops the extra card
+	change_item_type(&pool, &item.get_id(), &basic.get_id())
+		.await
+		.unwrap()
+		.unwrap();
+	let cards = crate::repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+	assert_eq!(cards.len(), 1);
+	assert_eq!(cards[0].get_id(), original[0].get_id());
+	assert_eq!(
+		cards[0].get_direction(),
+		Some(crate::models::CardDirection::Forward)
+	);
+}
+
+#[tokio::test]
+async fn test_change_item_type_to_another_scheduler_reschedules_kept_cards() {
+	let pool = setup_test_db();
+	let basic = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let test_type = create_item_type(
+		&pool,
+		"Test Type".to_string(),
+		"incremental_queue".to_string(),
+	)
+	.await
+	.unwrap();
+	let item = create_item(
+		&pool,
+		&basic.get_id(),
+		"Word".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let original = crate::repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+	crate::repo::record_review(&pool, &original[0].get_id(), crate::models::Rating::Good)
+		.await
+		.unwrap();
+
+	change_item_type(&pool, &item.get_id(), &test_type.get_id())
+		.await
+		.unwrap()
+		.unwrap();
+
+	// The kept card starts over under the new scheduler, keeping its history
+	let mut cards = crate::repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+	cards.sort_by_key(Card::get_card_index);
+	assert_eq!(cards[0].get_id(), original[0].get_id());
+	assert_eq!(cards[0].get_scheduler_data(), None);
+	assert!(cards[0].get_next_review() <= chrono::Utc::now());
+	assert!(cards[0].get_last_review().is_some());
+	let reviews = crate::repo::get_reviews_for_card(&pool, &cards[0].get_id()).unwrap();
+	assert_eq!(reviews.len(), 1);
+}
+
+#[tokio::test]
+async fn test_change_item_type_rejects_bad_target() {
+	let pool = setup_test_db();
+	let basic = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let test_type = create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(&pool, &test_type.get_id(), "Word".to_string(), json!({}))
+		.await
+		.unwrap();
+
+	// The item's data has no front or back, so it can't be a Basic item
+	let error = change_item_type(&pool, &item.get_id(), &basic.get_id())
+		.await
+		.unwrap_err();
+	assert!(error.downcast_ref::<InvalidItemDataError>().is_some());
+
+	let missing = ItemTypeId("item_type-missing".to_string());
+	let error = change_item_type(&pool, &item.get_id(), &missing)
+		.await
+		.unwrap_err();
+	assert_eq!(
+		error.downcast_ref::<ItemTypeNotFoundError>(),
+		Some(&ItemTypeNotFoundError(missing))
+	);
+
+	assert_eq!(
+		get_item(&pool, &item.get_id())
+			.unwrap()
+			.unwrap()
+			.get_item_type(),
+		test_type.get_id()
+	);
+	assert_eq!(
+		crate::repo::get_cards_for_item(&pool, &item.get_id())
+			.unwrap()
+			.len(),
+		2
+	);
+
+	let unknown_item =
+		change_item_type(&pool, &ItemId("item-missing".to_string()), &basic.get_id())
+			.await
+			.unwrap();
+	assert_eq!(unknown_item, None);
+}
@@ -0,0 +1,125 @@
+//! An optional in-memory cache of item types, keyed by id.
+//!
+//! Item types rarely change, but `create_cards_for_item` looks one up for
+//! every item it creates cards for, which adds up on bulk imports. When the
+//! `item_type_cache` setting is on, `get_item_type` answers repeat lookups
+//! from here instead of the database.
+//!
+//! Every write to `item_types` in the repo invalidates the entry it touched,
+//! so the cache never serves an item type older than the last write made
+//! through the repo. That includes adding or removing a card fetched event,
+//! which bumps its item type's `updated_at`. Writes made to the database
+//! behind the server's back aren't seen until it restarts, which is why the
+//! cache is opt-in.
+
+use crate::models::{ItemType, ItemTypeId};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{LazyLock, RwLock};
+use tracing::{debug, info};
+
+/// The process-wide item type cache `get_item_type` reads through
+static ITEM_TYPE_CACHE: LazyLock<ItemTypeCache> = LazyLock::new(ItemTypeCache::default);
+
+/// Returns the process-wide item type cache
+pub(super) fn item_type_cache() -> &'static ItemTypeCache {
+	&ITEM_TYPE_CACHE
+}
+
+/// Turns the item type cache on or off
+///
+/// The server calls this at startup with the configured `item_type_cache`.
+/// Turning the cache off also empties it, so turning it back on later can't
+/// serve entries that missed invalidations in between.
+///
+/// ### Arguments
+///
+/// * `enabled` - Whether `get_item_type` should cache what it reads
+pub fn set_item_type_cache_enabled(enabled: bool) {
+	info!("Item type cache {}", if enabled { "on" } else { "off" });
+	item_type_cache().set_enabled(enabled);
+}
+
+/// Item types read from the database, keyed by id
+#[derive(Debug, Default)]
+pub(super) struct ItemTypeCache {
+	/// Whether lookups are cached at all
+	enabled: AtomicBool,
+	/// The cached item types
+	entries: RwLock<HashMap<ItemTypeId, ItemType>>,
+	/// Bumped (under the `entries` write lock) on every invalidation, so a
+	/// load that raced one can tell its result may be stale
+	generation: AtomicU64,
+}
+
+impl ItemTypeCache {
+	/// Turns caching on or off, emptying the cache when turning it off
+	pub(super) fn set_enabled(&self, enabled: bool) {
+		self.enabled.store(enabled, Ordering::Relaxed);
+		if !enabled {
+			let mut entries = self.entries.write().unwrap();
+			self.generation.fetch_add(1, Ordering::AcqRel);
+			entries.clear();
+		}
+	}
+
+	/// Gets an item type from the cache, or from `load` if it isn't cached
+	///
+	/// Only item types that exist are cached, so one created after a miss is
+	/// found on the next lookup. A load that overlaps an invalidation isn't
+	/// cached either, since it may have read the item type from before the
+	/// write. With the cache off this just calls `load`.
+	///
+	/// ### Arguments
+	///
+	/// * `id` - The ID of the item type to get
+	/// * `load` - Reads the item type from the database
+	///
+	/// ### Returns
+	///
+	/// The item type, or None if it doesn't exist
+	///
+	/// ### Errors
+	///
+	/// Returns any error from `load`
+	pub(super) fn get_or_load(
+		&self,
+		id: &ItemTypeId,
+		load: impl FnOnce() -> Result<Option<ItemType>>,
+	) -> Result<Option<ItemType>> {
+		if !self.enabled.load(Ordering::Relaxed) {
+			return load();
+		}
+
+		if let Some(item_type) = self.entries.read().unwrap().get(id) {
+			debug!("Item type cache hit");
+			return Ok(Some(item_type.clone()));
+		}
+
+		debug!("Item type cache miss");
+		let generation = self.generation.load(Ordering::Acquire);
+		let item_type = load()?;
+		if let Some(ref item_type) = item_type {
+			let mut entries = self.entries.write().unwrap();
+			if self.generation.load(Ordering::Acquire) == generation {
+				entries.insert(id.clone(), item_type.clone());
+			} else {
+				debug!("Item type invalidated while loading, not caching it");
+			}
+		}
+
+		Ok(item_type)
+	}
+
+	/// Drops an item type from the cache, after it's been created, changed or
+	/// deleted
+	pub(super) fn invalidate(&self, id: &ItemTypeId) {
+		let mut entries = self.entries.write().unwrap();
+		self.generation.fetch_add(1, Ordering::AcqRel);
+		entries.remove(id);
+	}
+}
+
+#[cfg(test)]
+mod tests;
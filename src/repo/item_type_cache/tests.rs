@@ -0,0 +1,171 @@
+use super::*;
+use crate::db::DbPool;
+use crate::models::{CardEventFnName, OrderIndex};
+use crate::repo::{
+	create_card_fetched_event, create_item_type, delete_card_fetched_event, get_item_type,
+};
+use crate::repo::tests::setup_test_db;
+use crate::schema::item_types;
+use diesel::prelude::*;
+use std::cell::Cell;
+
+/// Reads an item type straight from the database, counting the queries made
+fn load_counting(
+	pool: &DbPool,
+	id: &ItemTypeId,
+	queries: &Cell<usize>,
+) -> Result<Option<ItemType>> {
+	queries.set(queries.get() + 1);
+	let conn = &mut pool.get()?;
+	Ok(item_types::table
+		.find(id)
+		.first::<ItemType>(conn)
+		.optional()?)
+}
+
+#[tokio::test]
+async fn test_second_lookup_does_not_hit_the_database() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let cache = ItemTypeCache::default();
+	cache.set_enabled(true);
+	let queries = Cell::new(0);
+
+	let first = cache
+		.get_or_load(&item_type.get_id(), || {
+			load_counting(&pool, &item_type.get_id(), &queries)
+		})
+		.unwrap();
+	let second = cache
+		.get_or_load(&item_type.get_id(), || {
+			load_counting(&pool, &item_type.get_id(), &queries)
+		})
+		.unwrap();
+
+	assert_eq!(first, Some(item_type.clone()));
+	assert_eq!(second, Some(item_type));
+	assert_eq!(queries.get(), 1);
+}
+
+#[tokio::test]
+async fn test_disabled_cache_always_hits_the_database() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let cache = ItemTypeCache::default();
+	let queries = Cell::new(0);
+
+	for _ in 0..2 {
+		cache
+			.get_or_load(&item_type.get_id(), || {
+				load_counting(&pool, &item_type.get_id(), &queries)
+			})
+			.unwrap();
+	}
+
+	assert_eq!(queries.get(), 2);
+}
+
+#[tokio::test]
+async fn test_invalidate_and_misses_reload() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let missing = ItemTypeId::new();
+	let cache = ItemTypeCache::default();
+	cache.set_enabled(true);
+	let queries = Cell::new(0);
+	let lookup = |id: &ItemTypeId| {
+		cache
+			.get_or_load(id, || load_counting(&pool, id, &queries))
+			.unwrap()
+	};
+
+	// Item types that don't exist aren't cached
+	assert_eq!(lookup(&missing), None);
+	assert_eq!(lookup(&missing), None);
+	assert_eq!(queries.get(), 2);
+
+	// Invalidating an item type makes the next lookup read it again
+	lookup(&item_type.get_id());
+	cache.invalidate(&item_type.get_id());
+	lookup(&item_type.get_id());
+	lookup(&item_type.get_id());
+	assert_eq!(queries.get(), 4);
+
+	// Turning the cache off empties it
+	cache.set_enabled(false);
+	cache.set_enabled(true);
+	lookup(&item_type.get_id());
+	assert_eq!(queries.get(), 5);
+}
+
+#[tokio::test]
+async fn test_load_racing_an_invalidate_is_not_cached() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let cache = ItemTypeCache::default();
+	cache.set_enabled(true);
+	let queries = Cell::new(0);
+
+	// A write lands (and invalidates) after the load has read the old row
+	cache
+		.get_or_load(&item_type.get_id(), || {
+			let loaded = load_counting(&pool, &item_type.get_id(), &queries);
+			cache.invalidate(&item_type.get_id());
+			loaded
+		})
+		.unwrap();
+
+	// So the possibly stale result wasn't cached
+	cache
+		.get_or_load(&item_type.get_id(), || {
+			load_counting(&pool, &item_type.get_id(), &queries)
+		})
+		.unwrap();
+	assert_eq!(queries.get(), 2);
+}
+
+#[tokio::test]
+async fn test_card_fetched_events_invalidate_the_cached_item_type() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let id = item_type.get_id();
+	let stored_updated_at = || -> chrono::NaiveDateTime {
+		item_types::table
+			.find(&id)
+			.select(item_types::updated_at)
+			.first(&mut pool.get().unwrap())
+			.unwrap()
+	};
+	let function_name = CardEventFnName("test_set_title".to_string());
+	// Only this test turns the process-wide cache on. The other tests that
+	// might run alongside it only see item types they created themselves.
+	item_type_cache().set_enabled(true);
+
+	get_item_type(&pool, &id).unwrap();
+	tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+	create_card_fetched_event(&pool, &id, OrderIndex(0), function_name.clone())
+		.await
+		.unwrap();
+	let after_create = get_item_type(&pool, &id).unwrap().unwrap();
+
+	tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+	delete_card_fetched_event(&pool, &id, &function_name)
+		.await
+		.unwrap();
+	let after_delete = get_item_type(&pool, &id).unwrap().unwrap();
+
+	item_type_cache().set_enabled(false);
+	assert!(after_create.get_updated_at() > item_type.get_updated_at());
+	assert_eq!(after_delete.get_updated_at_raw(), stored_updated_at());
+	assert!(after_delete.get_updated_at() > after_create.get_updated_at());
+}
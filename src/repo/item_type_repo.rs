@@ -1,8 +1,53 @@
-use crate::db::{DbPool, ExecuteWithRetry};
-use crate::models::{ItemType, ItemTypeId};
-use anyhow::Result;
+use crate::db::{DbPool, ExecuteWithRetry, transaction_with_retry};
+use crate::dto::ItemTypeWithCounts;
+use crate::models::{ItemType, ItemTypeId, JsonValue};
+use crate::repo::item_type_cache::item_type_cache;
+use crate::schema::{card_fetched_events, cards, item_types, items};
+use anyhow::{Result, anyhow};
+use diesel::dsl::count_star;
 use diesel::prelude::*;
-use tracing::{debug, info, instrument};
+use std::collections::HashMap;
+use thiserror::Error;
+use tracing::{debug, info, instrument, warn};
+
+/// Error returned when an item type's `item_data_schema` is not a valid JSON Schema
+///
+/// Carried inside the `anyhow::Error` returned by the item type repo
+/// functions, so handlers can `downcast` to it and answer with a 400.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid item data schema: {0}")]
+pub struct InvalidItemDataSchemaError(pub String);
+
+/// Error returned when deleting an item type that still has items, without
+/// asking for them to be deleted too
+///
+/// Carried inside the `anyhow::Error` returned by [`delete_item_type`], so
+/// handlers can `downcast` to it and answer with a 409.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Item type {item_type_id} still has {item_count} items")]
+pub struct ItemTypeInUseError {
+	/// The item type that was being deleted
+	pub item_type_id: ItemTypeId,
+	/// How many items of that type exist
+	pub item_count: i64,
+}
+
+/// Compiles an item data schema into a validator
+///
+/// ### Arguments
+///
+/// * `item_data_schema` - The JSON Schema to compile
+///
+/// ### Returns
+///
+/// A validator for the schema, or an `InvalidItemDataSchemaError` if the
+/// schema is not a valid JSON Schema
+pub fn compile_item_data_schema(
+	item_data_schema: &serde_json::Value,
+) -> Result<jsonschema::Validator, InvalidItemDataSchemaError> {
+	jsonschema::validator_for(item_data_schema)
+		.map_err(|e| InvalidItemDataSchemaError(e.to_string()))
+}
 
 /// Creates a new item type in the database
 ///
@@ -21,25 +66,60 @@ use tracing::{debug, info, instrument};
 /// Returns an error if:
 /// - Unable to get a connection from the pool
 /// - The database insert operation fails
-#[instrument(skip(pool), fields(name = %name, review_function = %review_function))]
 pub async fn create_item_type(
 	pool: &DbPool,
 	name: String,
 	review_function: String,
+) -> Result<ItemType> {
+	create_item_type_with_schema(pool, name, review_function, None).await
+}
+
+/// Creates a new item type whose item data is validated against a JSON Schema
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `name` - The name for the new item type
+/// * `review_function` - The review function to use for scheduling
+/// * `item_data_schema` - The JSON Schema item data must satisfy, or None to skip validation
+///
+/// ### Returns
+///
+/// A Result containing the newly created ItemType if successful
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - The schema is not a valid JSON Schema (an `InvalidItemDataSchemaError`)
+/// - Unable to get a connection from the pool
+/// - The database insert operation fails
+#[instrument(skip(pool, item_data_schema), fields(name = %name, review_function = %review_function))]
+pub async fn create_item_type_with_schema(
+	pool: &DbPool,
+	name: String,
+	review_function: String,
+	item_data_schema: Option<serde_json::Value>,
 ) -> Result<ItemType> {
 	debug!("Creating new item type");
 
+	// Reject schemas that can't be compiled before storing them
+	if let Some(ref schema) = item_data_schema {
+		compile_item_data_schema(schema)?;
+	}
+
 	// Get a connection from the pool
 	let conn = &mut pool.get()?;
 
 	// Create a new item type with the provided name and review function
-	let new_item_type = ItemType::new(name, review_function);
+	let mut new_item_type = ItemType::new(name, review_function);
+	new_item_type.set_item_data_schema(item_data_schema.map(JsonValue));
 
 	// Insert the new item type into the database
 	diesel::insert_into(crate::schema::item_types::table)
 		.values(new_item_type.clone())
 		.execute_with_retry(conn)
 		.await?;
+	item_type_cache().invalidate(&new_item_type.get_id());
 
 	info!(
 		"Successfully created item type with id: {}",
@@ -70,14 +150,15 @@ pub async fn create_item_type(
 pub fn get_item_type(pool: &DbPool, id: &ItemTypeId) -> Result<Option<ItemType>> {
 	debug!("Retrieving item type");
 
-	// Get a connection from the pool
-	let conn = &mut pool.get()?;
-
-	// Query the database for the item type with the specified ID
-	let result = crate::schema::item_types::table
-		.find(id)
-		.first::<ItemType>(conn)
-		.optional()?;
+	// Query the database for the item type with the specified ID, unless
+	// it's already cached
+	let result = item_type_cache().get_or_load(id, || {
+		let conn = &mut pool.get()?;
+		Ok(crate::schema::item_types::table
+			.find(id)
+			.first::<ItemType>(conn)
+			.optional()?)
+	})?;
 
 	if result.is_some() {
 		debug!("Item type found");
@@ -120,6 +201,61 @@ pub fn list_item_types(pool: &DbPool) -> Result<Vec<ItemType>> {
 	Ok(result)
 }
 
+/// Retrieves all item types along with how many items and cards each has
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+///
+/// ### Returns
+///
+/// A Result containing every item type with its `item_count` and `card_count` set
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+#[instrument(skip(pool))]
+pub fn list_item_types_with_counts(pool: &DbPool) -> Result<Vec<ItemTypeWithCounts>> {
+	debug!("Listing all item types with counts");
+
+	let conn = &mut pool.get()?;
+
+	let item_types = crate::schema::item_types::table.load::<ItemType>(conn)?;
+
+	// Count items and cards per item type with one grouped query each
+	let item_counts: HashMap<ItemTypeId, i64> = items::table
+		.group_by(items::item_type)
+		.select((items::item_type, count_star()))
+		.load::<(ItemTypeId, i64)>(conn)?
+		.into_iter()
+		.collect();
+	let card_counts: HashMap<ItemTypeId, i64> = cards::table
+		.inner_join(items::table)
+		.group_by(items::item_type)
+		.select((items::item_type, count_star()))
+		.load::<(ItemTypeId, i64)>(conn)?
+		.into_iter()
+		.collect();
+
+	let result: Vec<ItemTypeWithCounts> = item_types
+		.into_iter()
+		.map(|item_type| {
+			let id = item_type.get_id();
+			ItemTypeWithCounts {
+				item_type,
+				item_count: Some(item_counts.get(&id).copied().unwrap_or(0)),
+				card_count: Some(card_counts.get(&id).copied().unwrap_or(0)),
+			}
+		})
+		.collect();
+
+	info!("Retrieved {} item types with counts", result.len());
+
+	Ok(result)
+}
+
 /// Updates the review function of an item type
 ///
 /// ### Arguments
@@ -153,6 +289,7 @@ pub async fn update_item_type_review_function(
 		.set(crate::schema::item_types::review_function.eq(review_function.clone()))
 		.execute_with_retry(conn)
 		.await?;
+	item_type_cache().invalidate(id);
 
 	if updated == 0 {
 		return Err(anyhow::anyhow!("Item type not found: {}", id));
@@ -171,6 +308,245 @@ pub async fn update_item_type_review_function(
 	Ok(item_type)
 }
 
+/// Sets or clears the JSON Schema that item data of an item type must satisfy
+///
+/// Existing items are not re-validated; the schema applies to items created
+/// or updated afterwards.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `id` - The ID of the item type to update
+/// * `item_data_schema` - The new schema, or None to stop validating item data
+///
+/// ### Returns
+///
+/// A Result containing the updated ItemType if successful
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - The schema is not a valid JSON Schema (an `InvalidItemDataSchemaError`)
+/// - Unable to get a connection from the pool
+/// - The item type is not found
+/// - The database update operation fails
+#[instrument(skip(pool, item_data_schema), fields(item_type_id = %id))]
+pub async fn update_item_type_item_data_schema(
+	pool: &DbPool,
+	id: &ItemTypeId,
+	item_data_schema: Option<serde_json::Value>,
+) -> Result<ItemType> {
+	debug!("Updating item type item data schema");
+
+	if let Some(ref schema) = item_data_schema {
+		compile_item_data_schema(schema)?;
+	}
+
+	let conn = &mut pool.get()?;
+
+	let updated = diesel::update(crate::schema::item_types::table.find(id.clone()))
+		.set(crate::schema::item_types::item_data_schema.eq(item_data_schema.map(JsonValue)))
+		.execute_with_retry(conn)
+		.await?;
+	item_type_cache().invalidate(id);
+
+	if updated == 0 {
+		return Err(anyhow::anyhow!("Item type not found: {}", id));
+	}
+
+	// Retrieve and return the updated item type
+	let item_type = crate::schema::item_types::table
+		.find(id)
+		.first::<ItemType>(conn)?;
+
+	info!("Successfully updated item type {} item data schema", id);
+
+	Ok(item_type)
+}
+
+/// Updates the priority given to new cards of an item type
+///
+/// Existing cards keep their priority; only cards created afterwards are
+/// affected.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `id` - The ID of the item type to update
+/// * `default_priority` - The new priority, or None to use the configured default
+///
+/// ### Returns
+///
+/// A Result containing the updated ItemType if successful
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The item type is not found
+/// - The database update operation fails
+#[instrument(skip(pool), fields(item_type_id = %id))]
+pub async fn update_item_type_default_priority(
+	pool: &DbPool,
+	id: &ItemTypeId,
+	default_priority: Option<f32>,
+) -> Result<ItemType> {
+	debug!("Updating item type default priority");
+
+	let conn = &mut pool.get()?;
+
+	let updated = diesel::update(crate::schema::item_types::table.find(id.clone()))
+		.set(crate::schema::item_types::default_priority.eq(default_priority))
+		.execute_with_retry(conn)
+		.await?;
+	item_type_cache().invalidate(id);
+
+	if updated == 0 {
+		return Err(anyhow::anyhow!("Item type not found: {}", id));
+	}
+
+	// Retrieve and return the updated item type
+	let item_type = crate::schema::item_types::table
+		.find(id)
+		.first::<ItemType>(conn)?;
+
+	info!("Successfully updated item type {} default priority", id);
+
+	Ok(item_type)
+}
+
+/// Updates the tuning parameters for the scheduler of an item type
+///
+/// Cards of the type are scheduled with the new parameters from their next
+/// review on; their current due dates are left alone.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `id` - The ID of the item type to update
+/// * `scheduler_config` - The new parameters, or None to use the defaults
+///
+/// ### Returns
+///
+/// A Result containing the updated ItemType if successful
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The item type is not found
+/// - The database update operation fails
+#[instrument(skip(pool, scheduler_config), fields(item_type_id = %id))]
+pub async fn update_item_type_scheduler_config(
+	pool: &DbPool,
+	id: &ItemTypeId,
+	scheduler_config: Option<serde_json::Value>,
+) -> Result<ItemType> {
+	debug!("Updating item type scheduler config");
+
+	let conn = &mut pool.get()?;
+
+	let updated = diesel::update(crate::schema::item_types::table.find(id.clone()))
+		.set(crate::schema::item_types::scheduler_config.eq(scheduler_config.map(JsonValue)))
+		.execute_with_retry(conn)
+		.await?;
+	item_type_cache().invalidate(id);
+
+	if updated == 0 {
+		return Err(anyhow::anyhow!("Item type not found: {}", id));
+	}
+
+	// Retrieve and return the updated item type
+	let item_type = crate::schema::item_types::table
+		.find(id)
+		.first::<ItemType>(conn)?;
+
+	info!("Successfully updated item type {} scheduler config", id);
+
+	Ok(item_type)
+}
+
+/// Deletes an item type
+///
+/// An item type with items is only deleted if `cascade` is set, in which case
+/// its items (and, through them, their cards, reviews, tags and relations)
+/// are deleted along with it, all in one transaction. The item type's card
+/// fetched events always go with it.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `id` - The ID of the item type to delete
+/// * `cascade` - Whether to delete the item type's items too
+///
+/// ### Returns
+///
+/// A Result containing the number of items deleted along with the item type
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The item type is not found
+/// - The item type still has items and `cascade` isn't set (an
+///   `ItemTypeInUseError`)
+/// - The database delete operations fail
+#[instrument(skip(pool), fields(item_type_id = %id, cascade = cascade))]
+pub async fn delete_item_type(pool: &DbPool, id: &ItemTypeId, cascade: bool) -> Result<usize> {
+	debug!("Deleting item type");
+
+	let conn = &mut pool.get()?;
+
+	// Either the number of items deleted, or the number that blocked the delete
+	let outcome = transaction_with_retry(conn, |c| {
+		// A missing item type is the NotFound mapped to "Item type not found" below
+		item_types::table
+			.find(id)
+			.select(item_types::id)
+			.first::<ItemTypeId>(c)?;
+
+		let item_count = items::table
+			.filter(items::item_type.eq(id))
+			.count()
+			.get_result::<i64>(c)?;
+		if item_count > 0 && !cascade {
+			return Ok(Err(item_count));
+		}
+
+		let deleted_items =
+			diesel::delete(items::table.filter(items::item_type.eq(id))).execute(c)?;
+		diesel::delete(card_fetched_events::table.filter(card_fetched_events::item_type_id.eq(id)))
+			.execute(c)?;
+		diesel::delete(item_types::table.find(id)).execute(c)?;
+
+		Ok(Ok(deleted_items))
+	})
+	.await
+	.map_err(|e| match e {
+		diesel::result::Error::NotFound => anyhow!("Item type not found: {}", id),
+		other => anyhow::Error::from(other),
+	})?;
+	item_type_cache().invalidate(id);
+
+	match outcome {
+		Ok(deleted_items) => {
+			info!(
+				"Successfully deleted item type {} and {} items",
+				id, deleted_items
+			);
+			Ok(deleted_items)
+		}
+		Err(item_count) => {
+			warn!("Item type {} still has {} items", id, item_count);
+			Err(ItemTypeInUseError {
+				item_type_id: id.clone(),
+				item_count,
+			}
+			.into())
+		}
+	}
+}
+
 #[cfg(test)]
 mod prop_tests;
 #[cfg(test)]
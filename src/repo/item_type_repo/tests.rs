@@ -1,5 +1,6 @@
 use super::*;
 use crate::repo::tests::setup_test_db;
+use serde_json::json;
 
 #[tokio::test]
 async fn test_create_item_type() {
@@ -105,3 +106,321 @@ async fn test_update_item_type_review_function_not_found() {
 	.await;
 	assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn test_create_item_type_with_schema() {
+	let pool = setup_test_db();
+	let schema = json!({"type": "object", "required": ["term"]});
+
+	let item_type = create_item_type_with_schema(
+		&pool,
+		"Glossary".to_string(),
+		"fsrs".to_string(),
+		Some(schema.clone()),
+	)
+	.await
+	.unwrap();
+
+	// The schema should round-trip through the database
+	let fetched = get_item_type(&pool, &item_type.get_id()).unwrap().unwrap();
+	assert_eq!(fetched.get_item_data_schema(), Some(JsonValue(schema)));
+}
+
+#[tokio::test]
+async fn test_create_item_type_with_invalid_schema() {
+	let pool = setup_test_db();
+
+	let err = create_item_type_with_schema(
+		&pool,
+		"Glossary".to_string(),
+		"fsrs".to_string(),
+		Some(json!({"type": "not-a-type"})),
+	)
+	.await
+	.unwrap_err();
+
+	assert!(err.downcast_ref::<InvalidItemDataSchemaError>().is_some());
+	assert!(list_item_types(&pool).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_update_item_type_item_data_schema() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Glossary".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	assert_eq!(item_type.get_item_data_schema(), None);
+
+	// Set a schema
+	let schema = json!({"type": "object"});
+	let updated =
+		update_item_type_item_data_schema(&pool, &item_type.get_id(), Some(schema.clone()))
+			.await
+			.unwrap();
+	assert_eq!(updated.get_item_data_schema(), Some(JsonValue(schema)));
+
+	// Clear it again
+	let cleared = update_item_type_item_data_schema(&pool, &item_type.get_id(), None)
+		.await
+		.unwrap();
+	assert_eq!(cleared.get_item_data_schema(), None);
+}
+
+#[tokio::test]
+async fn test_update_item_type_default_priority() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Glossary".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	assert_eq!(item_type.get_default_priority(), None);
+
+	let updated = update_item_type_default_priority(&pool, &item_type.get_id(), Some(0.8))
+		.await
+		.unwrap();
+	assert_eq!(updated.get_default_priority(), Some(0.8));
+
+	let cleared = update_item_type_default_priority(&pool, &item_type.get_id(), None)
+		.await
+		.unwrap();
+	assert_eq!(cleared.get_default_priority(), None);
+
+	let result =
+		update_item_type_default_priority(&pool, &ItemTypeId("nonexistent".to_string()), None)
+			.await;
+	assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_update_item_type_scheduler_config() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Tuned".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	assert_eq!(item_type.get_scheduler_config(), None);
+
+	let config = json!({"desired_retention": 0.95});
+	let updated =
+		update_item_type_scheduler_config(&pool, &item_type.get_id(), Some(config.clone()))
+			.await
+			.unwrap();
+	assert_eq!(updated.get_scheduler_config(), Some(JsonValue(config)));
+
+	let cleared = update_item_type_scheduler_config(&pool, &item_type.get_id(), None)
+		.await
+		.unwrap();
+	assert_eq!(cleared.get_scheduler_config(), None);
+
+	let result =
+		update_item_type_scheduler_config(&pool, &ItemTypeId("nonexistent".to_string()), None)
+			.await;
+	assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_update_item_type_item_data_schema_rejects_invalid_schema() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Glossary".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+
+	let err = update_item_type_item_data_schema(
+		&pool,
+		&item_type.get_id(),
+		Some(json!({"required": "term"})),
+	)
+	.await
+	.unwrap_err();
+
+	assert!(err.downcast_ref::<InvalidItemDataSchemaError>().is_some());
+	let unchanged = get_item_type(&pool, &item_type.get_id()).unwrap().unwrap();
+	assert_eq!(unchanged.get_item_data_schema(), None);
+}
+
+#[tokio::test]
+async fn test_update_item_type_item_data_schema_not_found() {
+	let pool = setup_test_db();
+
+	let result = update_item_type_item_data_schema(
+		&pool,
+		&ItemTypeId("nonexistent".to_string()),
+		Some(json!({"type": "object"})),
+	)
+	.await;
+	assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_list_item_types_with_counts() {
+	let pool = setup_test_db();
+
+	// "Test" types get two cards per item, Basic types one
+	let test_type = create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let basic_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let empty_type = create_item_type(&pool, "Empty Test".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+
+	for i in 0..3 {
+		crate::repo::create_item(
+			&pool,
+			&test_type.get_id(),
+			format!("Test {}", i),
+			json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+	}
+	crate::repo::create_item(
+		&pool,
+		&basic_type.get_id(),
+		"Basic".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+
+	let listed = list_item_types_with_counts(&pool).unwrap();
+	assert_eq!(listed.len(), 3);
+
+	let counts = |id: &ItemTypeId| {
+		let entry = listed.iter().find(|e| &e.item_type.get_id() == id).unwrap();
+		(entry.item_count, entry.card_count)
+	};
+	assert_eq!(counts(&test_type.get_id()), (Some(3), Some(6)));
+	assert_eq!(counts(&basic_type.get_id()), (Some(1), Some(1)));
+	assert_eq!(counts(&empty_type.get_id()), (Some(0), Some(0)));
+}
+
+#[tokio::test]
+async fn test_delete_item_type_refuses_while_it_has_items() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = crate::repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+
+	let err = delete_item_type(&pool, &item_type.get_id(), false)
+		.await
+		.unwrap_err();
+	assert_eq!(
+		err.downcast::<ItemTypeInUseError>().unwrap(),
+		ItemTypeInUseError {
+			item_type_id: item_type.get_id(),
+			item_count: 1,
+		}
+	);
+
+	// Nothing was deleted
+	assert!(get_item_type(&pool, &item_type.get_id()).unwrap().is_some());
+	assert!(
+		crate::repo::get_item(&pool, &item.get_id())
+			.unwrap()
+			.is_some()
+	);
+}
+
+#[tokio::test]
+async fn test_delete_item_type_cascade_deletes_items_cards_and_reviews() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let other_type = create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	crate::repo::create_card_fetched_event(
+		&pool,
+		&item_type.get_id(),
+		crate::models::OrderIndex(0),
+		crate::models::CardEventFnName("test_increment".to_owned()),
+	)
+	.await
+	.unwrap();
+
+	let item = crate::repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+	let other = crate::repo::create_item(
+		&pool,
+		&other_type.get_id(),
+		"Other".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+	let card = crate::repo::get_cards_for_item(&pool, &item.get_id())
+		.unwrap()
+		.remove(0);
+	crate::repo::record_review(&pool, &card.get_id(), crate::models::Rating::Good)
+		.await
+		.unwrap();
+
+	let deleted = delete_item_type(&pool, &item_type.get_id(), true)
+		.await
+		.unwrap();
+	assert_eq!(deleted, 1);
+
+	assert!(get_item_type(&pool, &item_type.get_id()).unwrap().is_none());
+	assert!(
+		crate::repo::get_item(&pool, &item.get_id())
+			.unwrap()
+			.is_none()
+	);
+	let conn = &mut pool.get().unwrap();
+	let remaining_cards = cards::table
+		.filter(cards::item_id.eq(item.get_id()))
+		.count()
+		.get_result::<i64>(conn)
+		.unwrap();
+	assert_eq!(remaining_cards, 0);
+	let remaining_reviews = crate::schema::reviews::table
+		.filter(crate::schema::reviews::card_id.eq(card.get_id()))
+		.count()
+		.get_result::<i64>(conn)
+		.unwrap();
+	assert_eq!(remaining_reviews, 0);
+
+	// Other item types keep their items
+	assert!(
+		crate::repo::get_item(&pool, &other.get_id())
+			.unwrap()
+			.is_some()
+	);
+}
+
+#[tokio::test]
+async fn test_delete_item_type_without_items_and_not_found() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Empty".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+
+	assert_eq!(
+		delete_item_type(&pool, &item_type.get_id(), false)
+			.await
+			.unwrap(),
+		0
+	);
+	assert!(get_item_type(&pool, &item_type.get_id()).unwrap().is_none());
+
+	let err = delete_item_type(&pool, &item_type.get_id(), false)
+		.await
+		.unwrap_err();
+	assert!(err.to_string().contains("not found"), "got: {}", err);
+}
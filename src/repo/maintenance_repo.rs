@@ -0,0 +1,228 @@
+use crate::db::{DbPool, ExecuteWithRetry};
+use crate::dto::{MigrationStatus, VacuumResult};
+use crate::models::BackupLogEntry;
+use crate::schema::backup_log;
+use crate::time_utils::now_ms;
+use crate::{BackupType, MIGRATIONS};
+use anyhow::{Result, anyhow};
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::BigInt;
+use diesel::sqlite::SqliteConnection;
+use diesel_migrations::MigrationHarness;
+use tracing::{debug, info, instrument};
+
+/// The size of the database, as reported by SQLite's page count pragmas
+#[derive(QueryableByName)]
+struct DatabaseSize {
+	#[diesel(sql_type = BigInt)]
+	bytes: i64,
+}
+
+/// Gets the size of the database, in bytes
+///
+/// ### Arguments
+///
+/// * `conn` - A mutable reference to the SQLite connection
+///
+/// ### Returns
+///
+/// A QueryResult containing the number of pages times the page size
+fn database_size_with_conn(conn: &mut SqliteConnection) -> QueryResult<i64> {
+	sql_query("SELECT page_count * page_size AS bytes FROM pragma_page_count(), pragma_page_size()")
+		.get_result::<DatabaseSize>(conn)
+		.map(|size| size.bytes)
+}
+
+/// Rebuilds the database file to reclaim the space left by deleted rows, then
+/// refreshes the query planner's statistics
+///
+/// Runs `VACUUM` then `ANALYZE`. `VACUUM` rewrites the whole file and holds a
+/// write lock while it does, so other requests may block (and retry) until it
+/// finishes.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+///
+/// ### Returns
+///
+/// A Result containing the database's size before and after, and the space
+/// freed
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - `VACUUM` or `ANALYZE` fails, such as because the database stayed locked
+#[instrument(skip(pool))]
+pub async fn vacuum_database(pool: &DbPool) -> Result<VacuumResult> {
+	debug!("Vacuuming database");
+
+	let conn = &mut pool.get()?;
+
+	let size_before_bytes = database_size_with_conn(conn)?;
+	sql_query("VACUUM").execute_with_retry(conn).await?;
+	sql_query("ANALYZE").execute_with_retry(conn).await?;
+	let size_after_bytes = database_size_with_conn(conn)?;
+
+	let result = VacuumResult {
+		size_before_bytes,
+		size_after_bytes,
+		freed_bytes: (size_before_bytes - size_after_bytes).max(0),
+	};
+
+	info!(
+		"Vacuumed database from {} to {} bytes",
+		size_before_bytes, size_after_bytes
+	);
+
+	Ok(result)
+}
+
+/// Gets which migrations have been applied to the database, and which of the
+/// embedded migrations are still pending
+///
+/// Applied versions are read from `__diesel_schema_migrations`. Nothing is
+/// run or changed.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+///
+/// ### Returns
+///
+/// A Result containing the applied and pending migration versions
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - Unable to read the applied migrations
+#[instrument(skip(pool))]
+pub fn get_migration_status(pool: &DbPool) -> Result<MigrationStatus> {
+	debug!("Getting migration status");
+
+	let conn = &mut pool.get()?;
+
+	let mut applied: Vec<String> = conn
+		.applied_migrations()
+		.map_err(|e| anyhow!(e))?
+		.iter()
+		.map(ToString::to_string)
+		.collect();
+	applied.sort();
+
+	let mut pending: Vec<String> = conn
+		.pending_migrations(MIGRATIONS)
+		.map_err(|e| anyhow!(e))?
+		.iter()
+		.map(|migration| migration.name().version().to_string())
+		.collect();
+	pending.sort();
+
+	debug!(
+		"{} migrations applied, {} pending",
+		applied.len(),
+		pending.len()
+	);
+
+	Ok(MigrationStatus {
+		has_pending: !pending.is_empty(),
+		applied,
+		pending,
+	})
+}
+
+/// The number of backup attempts the backup log keeps; recording a new one
+/// drops the oldest beyond this
+pub const BACKUP_LOG_LIMIT: i64 = 100;
+
+/// Records an attempt at backing up the database in the backup log
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `backup_type` - Which kind of backup was attempted
+/// * `error` - Why the backup failed, or None if it succeeded
+///
+/// ### Returns
+///
+/// A Result indicating success or failure
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database write fails
+#[instrument(skip(pool))]
+pub async fn record_backup_attempt(
+	pool: &DbPool,
+	backup_type: BackupType,
+	error: Option<String>,
+) -> Result<()> {
+	debug!("Recording backup attempt");
+
+	let conn = &mut pool.get()?;
+
+	diesel::insert_into(backup_log::table)
+		.values((
+			backup_log::attempted_at.eq(now_ms()),
+			backup_log::backup_type.eq(backup_type.as_str().to_string()),
+			backup_log::success.eq(error.is_none()),
+			backup_log::error.eq(error),
+		))
+		.execute_with_retry(conn)
+		.await?;
+
+	// Keep the log small: only the most recent attempts matter
+	let newest_dropped: Option<i32> = backup_log::table
+		.select(backup_log::id)
+		.order(backup_log::id.desc())
+		.offset(BACKUP_LOG_LIMIT)
+		.first(conn)
+		.optional()?;
+	if let Some(newest_dropped) = newest_dropped {
+		diesel::delete(backup_log::table.filter(backup_log::id.le(newest_dropped)))
+			.execute_with_retry(conn)
+			.await?;
+	}
+
+	Ok(())
+}
+
+/// Lists the recorded backup attempts, newest first
+///
+/// At most [`BACKUP_LOG_LIMIT`] attempts are kept.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+///
+/// ### Returns
+///
+/// A Result containing the backup log's entries
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+#[instrument(skip(pool))]
+pub fn list_backup_attempts(pool: &DbPool) -> Result<Vec<BackupLogEntry>> {
+	debug!("Listing backup attempts");
+
+	let conn = &mut pool.get()?;
+
+	let entries = backup_log::table
+		.select(BackupLogEntry::as_select())
+		.order(backup_log::id.desc())
+		.load(conn)?;
+
+	debug!("Found {} backup attempts", entries.len());
+
+	Ok(entries)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,132 @@
+use super::*;
+use crate::repo::tests::setup_test_db;
+use crate::repo::{create_item, create_item_type, delete_item};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_record_backup_attempt() {
+	let pool = setup_test_db();
+	assert!(list_backup_attempts(&pool).unwrap().is_empty());
+
+	record_backup_attempt(&pool, BackupType::Periodic, None)
+		.await
+		.unwrap();
+	record_backup_attempt(
+		&pool,
+		BackupType::Periodic,
+		Some("Failed to write backup: disk full".to_string()),
+	)
+	.await
+	.unwrap();
+
+	// Newest first, with the failure's error
+	let entries = list_backup_attempts(&pool).unwrap();
+	assert_eq!(entries.len(), 2);
+	assert!(!entries[0].get_success());
+	assert_eq!(
+		entries[0].get_error(),
+		Some("Failed to write backup: disk full")
+	);
+	assert!(entries[1].get_success());
+	assert_eq!(entries[1].get_error(), None);
+	assert!(
+		entries
+			.iter()
+			.all(|entry| entry.get_backup_type() == "periodic")
+	);
+	assert!(entries[0].get_attempted_at() >= entries[1].get_attempted_at());
+}
+
+#[tokio::test]
+async fn test_backup_log_keeps_only_recent_attempts() {
+	let pool = setup_test_db();
+
+	for i in 0..BACKUP_LOG_LIMIT + 5 {
+		record_backup_attempt(&pool, BackupType::Periodic, Some(format!("failure {}", i)))
+			.await
+			.unwrap();
+	}
+
+	let entries = list_backup_attempts(&pool).unwrap();
+	assert_eq!(entries.len(), BACKUP_LOG_LIMIT as usize);
+	assert_eq!(
+		entries[0].get_error(),
+		Some(format!("failure {}", BACKUP_LOG_LIMIT + 4).as_str())
+	);
+	assert_eq!(entries.last().unwrap().get_error(), Some("failure 5"));
+}
+
+#[tokio::test]
+async fn test_vacuum_database() {
+	let pool = setup_test_db();
+
+	let result = vacuum_database(&pool).await.unwrap();
+
+	assert!(result.size_before_bytes > 0);
+	assert!(result.size_after_bytes > 0);
+	assert_eq!(
+		result.freed_bytes,
+		(result.size_before_bytes - result.size_after_bytes).max(0)
+	);
+}
+
+#[tokio::test]
+async fn test_vacuum_database_reclaims_deleted_rows() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let mut items = Vec::new();
+	for i in 0..200 {
+		let item = create_item(
+			&pool,
+			&item_type.get_id(),
+			format!("Item {}", i),
+			json!({"front": "F".repeat(500), "back": "B".repeat(500)}),
+		)
+		.await
+		.unwrap();
+		items.push(item.get_id());
+	}
+	for item_id in &items {
+		delete_item(&pool, item_id).await.unwrap();
+	}
+
+	let result = vacuum_database(&pool).await.unwrap();
+
+	assert!(result.freed_bytes > 0, "got {:?}", result);
+	assert_eq!(
+		result.size_after_bytes,
+		database_size_with_conn(&mut pool.get().unwrap()).unwrap()
+	);
+}
+
+#[test]
+fn test_get_migration_status_after_run_migrations() {
+	let pool = setup_test_db();
+
+	let status = get_migration_status(&pool).unwrap();
+
+	assert!(!status.applied.is_empty());
+	assert!(status.pending.is_empty());
+	assert!(!status.has_pending);
+	let mut sorted = status.applied.clone();
+	sorted.sort();
+	assert_eq!(status.applied, sorted);
+}
+
+#[test]
+fn test_get_migration_status_before_run_migrations() {
+	let database_url = format!(
+		"file:test_{}?mode=memory&cache=shared",
+		uuid::Uuid::new_v4()
+	);
+	let pool = crate::db::init_pool(&database_url);
+
+	let status = get_migration_status(&pool).unwrap();
+
+	assert!(status.applied.is_empty());
+	assert!(!status.pending.is_empty());
+	assert!(status.has_pending);
+}
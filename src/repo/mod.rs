@@ -3,6 +3,8 @@ mod card_fetched_event_repo;
 mod card_repo;
 mod item_relation_repo;
 mod item_repo;
+mod item_type_cache;
+mod maintenance_repo;
 mod query_repo;
 /// Repository module
 ///
@@ -14,6 +16,8 @@ mod query_repo;
 /// and provides a clean API for the rest of the application to use.
 mod item_type_repo;
 mod review_repo;
+mod session_repo;
+mod settings_repo;
 mod tag_repo;
 
 // Re-export all repository functions.
@@ -26,8 +30,12 @@ pub use card_fetched_event_repo::*;
 pub use card_repo::*;
 pub use item_relation_repo::*;
 pub use item_repo::*;
+pub use item_type_cache::set_item_type_cache_enabled;
 pub use item_type_repo::*;
+pub use maintenance_repo::*;
 pub use review_repo::*;
+pub use session_repo::*;
+pub use settings_repo::*;
 pub use tag_repo::*;
 
 #[cfg(test)]
@@ -35,14 +43,13 @@ pub mod tests {
 	use std::sync::Arc;
 
 	use crate::db::{self, DbPool};
-	use diesel::connection::SimpleConnection;
 	use diesel_migrations::MigrationHarness;
 
 	/// Sets up a test database with migrations applied
 	///
 	/// This function:
 	/// 1. Creates an in-memory SQLite database
-	/// 2. Enables foreign key constraints
+	/// 2. Gets a connection (foreign keys are enabled by the pool)
 	/// 3. Runs all migrations to set up the schema
 	///
 	/// ### Returns
@@ -61,9 +68,6 @@ pub mod tests {
 		// Run migrations on the in-memory database
 		let mut conn = pool.get().expect("Failed to get connection");
 
-		// Enable foreign key constraints for SQLite
-		conn.batch_execute("PRAGMA foreign_keys = ON").unwrap();
-
 		// Run all migrations to set up the schema
 		let migrations = diesel_migrations::FileBasedMigrations::find_migrations_directory()
 			.expect("Failed to find migrations directory");
@@ -72,4 +76,73 @@ pub mod tests {
 
 		Arc::new(pool)
 	}
+
+	/// Puts an `"fsrs"` card in the state from which a Good review schedules
+	/// it `days` days out, the way FSRS really would
+	///
+	/// The card is given a graduated memory state whose stability is searched
+	/// for until FSRS picks an interval of exactly `days` days, and is due now
+	/// after last being reviewed that many days ago.
+	///
+	/// ### Arguments
+	///
+	/// * `pool` - The test database
+	/// * `card_id` - The ID of the card, which must belong to an `"fsrs"`
+	///   item type with the default scheduler config
+	/// * `days` - The interval a Good review should give the card
+	#[cfg(test)]
+	pub fn set_up_fsrs_interval(pool: &DbPool, card_id: &crate::models::CardId, days: i64) {
+		use crate::models::{Card, JsonValue, Rating, SchedulerConfig};
+		use crate::repo::{ItemScheduling, LearningSteps, compute_next_interval};
+		use crate::schema::cards;
+		use chrono::{Duration, Utc};
+		use diesel::prelude::*;
+
+		let conn = &mut pool.get().unwrap();
+		let now = Utc::now();
+		let mut card = cards::table.find(card_id).first::<Card>(conn).unwrap();
+		card.set_last_review(Some(now - Duration::days(days)));
+		card.set_next_review(now);
+		let item = ItemScheduling {
+			review_function: "fsrs".to_string(),
+			item_data: serde_json::json!({}),
+			scheduler_config: SchedulerConfig::default(),
+		};
+		let good_interval = |card: &mut Card, stability: f64| {
+			card.set_scheduler_data(Some(JsonValue(serde_json::json!({
+				"stability": stability,
+				"difficulty": 5.0,
+				"graduated": true,
+			}))));
+			let steps = LearningSteps::default();
+			compute_next_interval(card, &item, &steps, Duration::zero(), Rating::Good, now)
+				.unwrap()
+				.0
+		};
+
+		// FSRS intervals grow with stability, so bisect for the one wanted
+		let wanted = Duration::days(days) - Duration::hours(1);
+		let (mut low, mut high) = (0.01, 10_000.0);
+		for _ in 0..100 {
+			let stability = (low + high) / 2.0;
+			let interval = good_interval(&mut card, stability);
+			if interval == wanted {
+				diesel::update(cards::table.find(card_id))
+					.set((
+						cards::last_review.eq(card.get_last_review().map(|lr| lr.naive_utc())),
+						cards::next_review.eq(card.get_next_review().naive_utc()),
+						cards::scheduler_data.eq(card.get_scheduler_data()),
+					))
+					.execute(conn)
+					.unwrap();
+				return;
+			}
+			if interval < wanted {
+				low = stability;
+			} else {
+				high = stability;
+			}
+		}
+		panic!("No stability gives an FSRS interval of {} days", days);
+	}
 }
@@ -12,16 +12,35 @@
 //! ```
 //!
 //! Tag filtering is done in SQL via `GROUP BY ... HAVING COUNT(DISTINCT) = N`
-//! — not in Rust. See `DESIRED_BEHAVIOUR.md` for the full design.
+//! — not in Rust. See `DESIRED_BEHAVIOUR.md` for the full design. With
+//! `include_descendant_tags`, each tag instead gets a recursive subquery over
+//! its subtree (`TAGGED_IN_SUBTREE_SQL_*`). With `tag_match=any`, an item
+//! only needs one of the tags, so the count check (or the per-tag subqueries)
+//! is replaced by a plain membership test (or their disjunction).
 
-use crate::dto::{GetQueryDto, SuspendedFilter};
+use crate::dto::{CardState, GetQueryDto, SuspendedFilter, TagMatch};
 use crate::schema::{cards, item_relations, item_tags, items, reviews};
-use diesel::dsl::{Select, count};
+use chrono::Utc;
+use diesel::dsl::{Select, count, sql};
 use diesel::expression_methods::AggregateExpressionMethods;
 use diesel::helper_types::IntoBoxed;
 use diesel::prelude::*;
+use diesel::sql_types::{Bool, Text};
 use diesel::sqlite::Sqlite;
 
+/// Whether an item is tagged with a tag or any tag beneath it: the SQL before
+/// the tag id is bound, and the SQL after.
+///
+/// `UNION` (rather than `UNION ALL`) stops the recursion at tags already
+/// visited, so a cycle in `parent_id` can't loop forever.
+const TAGGED_IN_SUBTREE_SQL_BEFORE: &str = "items.id IN (\
+	WITH RECURSIVE subtree(id) AS (SELECT ";
+const TAGGED_IN_SUBTREE_SQL_AFTER: &str = " \
+		UNION SELECT tags.id FROM tags JOIN subtree ON tags.parent_id = subtree.id\
+	) \
+	SELECT item_tags.item_id FROM item_tags \
+	WHERE item_tags.tag_id IN (SELECT id FROM subtree))";
+
 /// Boxed SELECT of `cards::id` matching a query.
 pub type BoxedCardIdQuery<'a> = IntoBoxed<'a, Select<cards::table, cards::id>, Sqlite>;
 
@@ -39,8 +58,8 @@ pub type BoxedReviewIdQuery<'a> =
 // callers finish with `.select(col)` to get a single-column subquery.
 // ---------------------------------------------------------------------------
 
-/// Applies item-level predicates (`item_type_id`, `tag_ids`, `parent_item_id`,
-/// `child_item_id`) to `items::table`.
+/// Applies item-level predicates (`item_type_id`, `exclude_item_type_ids`,
+/// `tag_ids`, `parent_item_id`, `child_item_id`) to `items::table`.
 ///
 /// The tag predicate uses `GROUP BY item_id HAVING COUNT(DISTINCT tag_id) = N`
 /// so that:
@@ -51,7 +70,8 @@ pub type BoxedReviewIdQuery<'a> =
 ///     against any future schema change that drops the PK on `(item_id, tag_id)`).
 ///
 /// Tag-id deduplication is done in Rust (not SQL) because we want `N` in the
-/// HAVING clause to match `tag_ids.len()` exactly.
+/// HAVING clause to match `tag_ids.len()` exactly. With `TagMatch::Any` there
+/// is no count: an item qualifies as soon as it has one of the tags.
 fn item_level_filters_on_items<'a>(
 	query: &'a GetQueryDto,
 ) -> IntoBoxed<'a, items::table, Sqlite> {
@@ -61,6 +81,12 @@ fn item_level_filters_on_items<'a>(
 		q = q.filter(items::item_type.eq(it));
 	}
 
+	if !query.exclude_item_type_ids.is_empty() {
+		q = q.filter(diesel::dsl::not(
+			items::item_type.eq_any(query.exclude_item_type_ids.clone()),
+		));
+	}
+
 	if !query.tag_ids.is_empty() {
 		// Dedupe client-side so the `HAVING COUNT(DISTINCT tag_id) = N` count
 		// lines up with the actual number of distinct tags requested. Owned
@@ -71,15 +97,53 @@ fn item_level_filters_on_items<'a>(
 		distinct.dedup();
 		let n = distinct.len() as i64;
 
-		q = q.filter(
-			items::id.eq_any(
-				item_tags::table
-					.filter(item_tags::tag_id.eq_any(distinct))
-					.group_by(item_tags::item_id)
-					.having(count(item_tags::tag_id).aggregate_distinct().eq(n))
-					.select(item_tags::item_id),
-			),
-		);
+		let descendants = query.include_descendant_tags.unwrap_or(false);
+		if query.tag_match == TagMatch::Any && descendants {
+			// Any one subtree will do, so OR the per-tag subqueries together
+			let mut any: Option<Box<dyn BoxableExpression<items::table, Sqlite, SqlType = Bool>>> =
+				None;
+			for tag_id in distinct {
+				let tagged = sql::<Bool>(TAGGED_IN_SUBTREE_SQL_BEFORE)
+					.bind::<Text, _>(tag_id.0)
+					.sql(TAGGED_IN_SUBTREE_SQL_AFTER);
+				any = Some(match any {
+					Some(acc) => Box::new(acc.or(tagged)),
+					None => Box::new(tagged),
+				});
+			}
+			if let Some(any) = any {
+				q = q.filter(any);
+			}
+		} else if query.tag_match == TagMatch::Any {
+			q = q.filter(
+				items::id.eq_any(
+					item_tags::table
+						.filter(item_tags::tag_id.eq_any(distinct))
+						.select(item_tags::item_id),
+				),
+			);
+		} else if descendants {
+			// Each tag is satisfied by itself or any tag beneath it, which
+			// the HAVING count can't express, so every tag gets its own
+			// subquery over its subtree
+			for tag_id in distinct {
+				q = q.filter(
+					sql::<Bool>(TAGGED_IN_SUBTREE_SQL_BEFORE)
+						.bind::<Text, _>(tag_id.0)
+						.sql(TAGGED_IN_SUBTREE_SQL_AFTER),
+				);
+			}
+		} else {
+			q = q.filter(
+				items::id.eq_any(
+					item_tags::table
+						.filter(item_tags::tag_id.eq_any(distinct))
+						.group_by(item_tags::item_id)
+						.having(count(item_tags::tag_id).aggregate_distinct().eq(n))
+						.select(item_tags::item_id),
+				),
+			);
+		}
 	}
 
 	if let Some(ref parent_id) = query.parent_item_id {
@@ -106,7 +170,15 @@ fn item_level_filters_on_items<'a>(
 }
 
 /// Applies card-level predicates (`next_review_before`, `last_review_after`,
-/// `suspended_filter`, `suspended_after`, `suspended_before`) to `cards::table`.
+/// `suspended_filter`, `suspended_after`, `suspended_before`, `state`) to
+/// `cards::table`.
+///
+/// Cards buried until some time in the future are excluded unless
+/// `include_buried` is set, whatever the query; they come back once their
+/// `buried_until` passes. The exception is `cram`, which ignores
+/// scheduling: burying and
+/// `next_review_before` are ignored, and suspended cards are always
+/// excluded instead of following `suspended_filter`.
 ///
 /// NULL-falsy semantics fall out of SQL's three-valued logic: `NULL > x` and
 /// `NULL < x` are both `NULL` (neither TRUE nor FALSE), so rows with a NULL
@@ -114,16 +186,25 @@ fn item_level_filters_on_items<'a>(
 /// The proptests in Q3.2, Q4.4, Q4.5 pin this.
 fn card_level_filters_on_cards<'a>(
 	query: &'a GetQueryDto,
+	include_buried: bool,
 ) -> IntoBoxed<'a, cards::table, Sqlite> {
+	let now = Utc::now().naive_utc();
+	let cram = query.cram.unwrap_or(false);
 	let mut q = cards::table.into_boxed::<Sqlite>();
 
-	if let Some(cutoff) = query.next_review_before {
-		q = q.filter(cards::next_review.lt(cutoff.naive_utc()));
+	if !cram {
+		if !include_buried {
+			q = q.filter(cards::buried_until.is_null().or(cards::buried_until.le(now)));
+		}
+		if let Some(cutoff) = query.next_review_before {
+			q = q.filter(cards::next_review.lt(cutoff.naive_utc()));
+		}
 	}
 	if let Some(cutoff) = query.last_review_after {
 		q = q.filter(cards::last_review.gt(cutoff.naive_utc()));
 	}
 	match query.suspended_filter {
+		_ if cram => q = q.filter(cards::suspended.is_null()),
 		SuspendedFilter::Include => {}
 		SuspendedFilter::Exclude => q = q.filter(cards::suspended.is_null()),
 		SuspendedFilter::Only => q = q.filter(cards::suspended.is_not_null()),
@@ -134,12 +215,26 @@ fn card_level_filters_on_cards<'a>(
 	if let Some(cutoff) = query.suspended_before {
 		q = q.filter(cards::suspended.lt(cutoff.naive_utc()));
 	}
+	if let Some(state) = query.state {
+		// The gap between reviews, in days, separates learning from review
+		let interval_days = super::card_repo::SCHEDULED_INTERVAL_DAYS_SQL;
+		q = match state {
+			CardState::New => q.filter(cards::last_review.is_null()),
+			CardState::Due => q.filter(cards::next_review.le(now)),
+			CardState::Learning => q
+				.filter(cards::last_review.is_not_null())
+				.filter(sql::<Bool>(&format!("{} < 1", interval_days))),
+			CardState::Review => q
+				.filter(cards::last_review.is_not_null())
+				.filter(sql::<Bool>(&format!("{} >= 1", interval_days))),
+		};
+	}
 
 	q
 }
 
 /// Matches the oracle's notion of "any filter that narrows by card
-/// attributes (not item attributes)." If this is false, `items_matching`
+/// attributes (not item attributes)", including `state`. If this is false, `items_matching`
 /// doesn't need to require the existence of a matching card — every item
 /// that passes item-level filters is returned regardless of card state
 /// (including zero-card items; see Q7B.2).
@@ -149,6 +244,8 @@ fn has_card_level_filter(query: &GetQueryDto) -> bool {
 		|| query.suspended_after.is_some()
 		|| query.suspended_before.is_some()
 		|| query.suspended_filter != SuspendedFilter::default()
+		|| query.cram == Some(true)
+		|| query.state.is_some()
 }
 
 // ---------------------------------------------------------------------------
@@ -163,7 +260,20 @@ fn has_card_level_filter(query: &GetQueryDto) -> bool {
 /// and is ignored here.
 pub fn cards_matching<'a>(query: &'a GetQueryDto) -> BoxedCardIdQuery<'a> {
 	let item_ids_sub = item_level_filters_on_items(query).select(items::id);
-	card_level_filters_on_cards(query)
+	card_level_filters_on_cards(query, false)
+		.filter(cards::item_id.eq_any(item_ids_sub))
+		.select(cards::id)
+}
+
+/// Returns a boxed subquery selecting `cards::id` for every card that
+/// matches `query`, including cards that are currently buried.
+///
+/// Listing leaves buried cards out until they come back, but bulk changes
+/// such as suspending by filter should reach every card the filters
+/// describe, buried or not.
+pub fn cards_matching_including_buried<'a>(query: &'a GetQueryDto) -> BoxedCardIdQuery<'a> {
+	let item_ids_sub = item_level_filters_on_items(query).select(items::id);
+	card_level_filters_on_cards(query, true)
 		.filter(cards::item_id.eq_any(item_ids_sub))
 		.select(cards::id)
 }
@@ -177,7 +287,7 @@ pub fn cards_matching<'a>(query: &'a GetQueryDto) -> BoxedCardIdQuery<'a> {
 pub fn items_matching<'a>(query: &'a GetQueryDto) -> BoxedItemIdQuery<'a> {
 	let mut q = item_level_filters_on_items(query);
 	if has_card_level_filter(query) {
-		let card_item_ids = card_level_filters_on_cards(query).select(cards::item_id);
+		let card_item_ids = card_level_filters_on_cards(query, false).select(cards::item_id);
 		q = q.filter(items::id.eq_any(card_item_ids));
 	}
 	q.select(items::id)
@@ -18,14 +18,14 @@
 
 use super::*;
 use crate::db::DbPool;
-use crate::dto::{GetQueryDto, GetQueryDtoBuilder, SuspendedFilter};
-use crate::models::{Card, CardId, Item, ItemId, ItemTypeId, Review, TagId};
+use crate::dto::{CardState, GetQueryDto, GetQueryDtoBuilder, SuspendedFilter, TagMatch};
+use crate::models::{Card, CardId, Item, ItemId, ItemTypeId, Rating, Review, TagId};
 use crate::repo::tests::setup_test_db;
 use crate::repo::{
 	add_tag_to_item, create_item, create_item_relation, create_item_type, create_tag,
 	get_cards_for_item, record_review, update_card,
 };
-use crate::test_utils::arb_datetime_utc;
+use crate::test_utils::{arb_card_state, arb_datetime_utc, arb_rating};
 use chrono::{DateTime, Utc};
 use proptest::prelude::*;
 use std::collections::{HashMap, HashSet};
@@ -226,6 +226,7 @@ fn has_card_level_filter(query: &GetQueryDto) -> bool {
 		|| query.suspended_after.is_some()
 		|| query.suspended_before.is_some()
 		|| query.suspended_filter != SuspendedFilter::default()
+		|| query.state.is_some()
 }
 
 fn card_matches_query(card: &Card, world: &TestWorld, query: &GetQueryDto) -> bool {
@@ -273,6 +274,21 @@ fn card_matches_query(card: &Card, world: &TestWorld, query: &GetQueryDto) -> bo
 			_ => return false,
 		}
 	}
+	if let Some(state) = query.state {
+		// Intervals are scheduled an hour short of whole days
+		let interval = card
+			.get_last_review()
+			.map(|last| card.get_next_review() - last + chrono::Duration::hours(1));
+		let matched = match state {
+			CardState::New => interval.is_none(),
+			CardState::Due => card.get_next_review() <= Utc::now(),
+			CardState::Learning => interval.is_some_and(|i| i < chrono::Duration::days(1)),
+			CardState::Review => interval.is_some_and(|i| i >= chrono::Duration::days(1)),
+		};
+		if !matched {
+			return false;
+		}
+	}
 	true
 }
 
@@ -282,9 +298,18 @@ fn item_id_matches_item_filters(item_id: &ItemId, world: &TestWorld, query: &Get
 			return false;
 		}
 	}
+	if let Some(type_id) = world.item_type_of(item_id) {
+		if query.exclude_item_type_ids.contains(&type_id) {
+			return false;
+		}
+	}
 	if !query.tag_ids.is_empty() {
 		let item_tags = world.item_tags.get(item_id).cloned().unwrap_or_default();
-		if !query.tag_ids.iter().all(|t| item_tags.contains(t)) {
+		let matched = match query.tag_match {
+			TagMatch::All => query.tag_ids.iter().all(|t| item_tags.contains(t)),
+			TagMatch::Any => query.tag_ids.iter().any(|t| item_tags.contains(t)),
+		};
+		if !matched {
 			return false;
 		}
 	}
@@ -398,7 +423,7 @@ proptest! {
 	#[test]
 	fn prop_q0_3_empty_query_selects_all_reviews(
 		n_items in 1usize..=3,
-		ratings in prop::collection::vec((0usize..6, 1i32..=4), 1..6),
+		ratings in prop::collection::vec((0usize..6, arb_rating()), 1..6),
 	) {
 		let rt = tokio::runtime::Runtime::new().unwrap();
 		rt.block_on(async {
@@ -743,6 +768,89 @@ proptest! {
 			Ok(())
 		})?;
 	}
+
+	/// Q2.8: With `tag_match = any`, multiple tag ids compose with OR — an
+	/// item is included iff it has *at least one* requested tag. The mirror
+	/// image of Q2.2.
+	#[test]
+	fn prop_q2_8_multiple_tags_any_semantics(
+		n_items in 2usize..=5,
+		n_tags in 2usize..=6,
+		attach_pairs in prop::collection::vec((0usize..5, 0usize..6), 0..25),
+		query_tag_ixs in prop::collection::vec(0usize..6, 1..=5),
+	) {
+		let rt = tokio::runtime::Runtime::new().unwrap();
+		rt.block_on(async {
+			let pool = setup_test_db();
+			let mut world = build_basic_world(&pool, 1, n_items).await;
+			add_tags(&pool, &mut world, n_tags).await;
+			let pairs: Vec<(usize, usize)> = attach_pairs
+				.into_iter()
+				.map(|(i, t)| (i % n_items, t % n_tags))
+				.collect();
+			attach_tags(&pool, &mut world, &pairs).await;
+
+			let query_tags: Vec<TagId> = query_tag_ixs
+				.into_iter()
+				.map(|ix| world.tags[ix % n_tags].clone())
+				.collect();
+
+			let query = GetQueryDtoBuilder::new()
+				.tag_ids(query_tags)
+				.tag_match(TagMatch::Any)
+				.build();
+
+			let sql_c = sql_cards_matching(&pool, &query);
+			let oracle_c = oracle_cards_matching(&world, &query);
+			prop_assert_eq!(sql_c, oracle_c);
+
+			let sql_i = sql_items_matching(&pool, &query);
+			let oracle_i = oracle_items_matching(&world, &query);
+			prop_assert_eq!(sql_i, oracle_i);
+			Ok(())
+		})?;
+	}
+
+	/// Q2.9: OR-ing a known tag with a nonexistent tag selects exactly what
+	/// the known tag selects alone — the bogus tag can never be satisfied, but
+	/// under `any` semantics it doesn't have to be. The counterpart of Q2.7.
+	#[test]
+	fn prop_q2_9_known_tag_or_unknown_tag_matches_known_alone(
+		n_items in 2usize..=4,
+		n_tags in 1usize..=3,
+		attach_pairs in prop::collection::vec((0usize..4, 0usize..3), 0..8),
+		known_tag_ix in 0usize..3,
+		garbage in "\\PC*",
+	) {
+		let rt = tokio::runtime::Runtime::new().unwrap();
+		rt.block_on(async {
+			let pool = setup_test_db();
+			let mut world = build_basic_world(&pool, 1, n_items).await;
+			add_tags(&pool, &mut world, n_tags).await;
+			let pairs: Vec<(usize, usize)> = attach_pairs
+				.into_iter()
+				.map(|(i, t)| (i % n_items, t % n_tags))
+				.collect();
+			attach_tags(&pool, &mut world, &pairs).await;
+
+			let known = world.tags[known_tag_ix % n_tags].clone();
+			let bogus = TagId(format!("not-a-tag-{}", garbage));
+			let either = GetQueryDtoBuilder::new()
+				.tag_ids(vec![known.clone(), bogus])
+				.tag_match(TagMatch::Any)
+				.build();
+			let alone = GetQueryDtoBuilder::new().add_tag_id(known).build();
+
+			let sql_c = sql_cards_matching(&pool, &either);
+			prop_assert_eq!(&sql_c, &oracle_cards_matching(&world, &either));
+			prop_assert_eq!(sql_c, sql_cards_matching(&pool, &alone));
+
+			let sql_i = sql_items_matching(&pool, &either);
+			prop_assert_eq!(&sql_i, &oracle_items_matching(&world, &either));
+			prop_assert_eq!(sql_i, sql_items_matching(&pool, &alone));
+			Ok(())
+		})?;
+	}
 }
 
 // ===========================================================================
@@ -1146,7 +1254,7 @@ proptest! {
 			),
 			1..6
 		),
-		ratings in prop::collection::vec((0usize..6, 1i32..=4), 0..6),
+		ratings in prop::collection::vec((0usize..6, arb_rating()), 0..6),
 		type_ix in 0usize..2,
 		query_tag_ixs in prop::collection::vec(0usize..3, 0..=2),
 		next_cutoff in prop_oneof![Just(None), arb_datetime_utc().prop_map(Some)],
@@ -1456,7 +1564,7 @@ proptest! {
 /// date-based oracles would compare against pre-review Rust values while SQL
 /// sees the post-review DB values, giving false failures on tests like Q6.1
 /// and Q8.6.
-async fn add_reviews(pool: &DbPool, world: &mut TestWorld, ratings: &[(usize, i32)]) {
+async fn add_reviews(pool: &DbPool, world: &mut TestWorld, ratings: &[(usize, Rating)]) {
 	for (ci, rating) in ratings {
 		let card_id = world.cards[*ci % world.cards.len()].get_id();
 		let r = record_review(pool, &card_id, *rating).await.unwrap();
@@ -1475,7 +1583,7 @@ proptest! {
 	#[test]
 	fn prop_q8_1_reviews_match_cards_set(
 		n_items in 1usize..=3,
-		ratings in prop::collection::vec((0usize..6, 1i32..=4), 1..8),
+		ratings in prop::collection::vec((0usize..6, arb_rating()), 1..8),
 	) {
 		let rt = tokio::runtime::Runtime::new().unwrap();
 		rt.block_on(async {
@@ -1496,7 +1604,7 @@ proptest! {
 	#[test]
 	fn prop_q8_2_excluded_cards_yield_no_reviews(
 		n_items in 1usize..=3,
-		ratings in prop::collection::vec((0usize..6, 1i32..=4), 1..6),
+		ratings in prop::collection::vec((0usize..6, arb_rating()), 1..6),
 	) {
 		let rt = tokio::runtime::Runtime::new().unwrap();
 		rt.block_on(async {
@@ -1517,7 +1625,7 @@ proptest! {
 	/// of cards belonging to that type.
 	#[test]
 	fn prop_q8_3_reviews_filter_by_item_type(
-		ratings in prop::collection::vec((0usize..4, 1i32..=4), 1..6),
+		ratings in prop::collection::vec((0usize..4, arb_rating()), 1..6),
 	) {
 		let rt = tokio::runtime::Runtime::new().unwrap();
 		rt.block_on(async {
@@ -1542,7 +1650,7 @@ proptest! {
 		n_items in 2usize..=4,
 		n_tags in 1usize..=3,
 		attach_pairs in prop::collection::vec((0usize..4, 0usize..3), 0..8),
-		ratings in prop::collection::vec((0usize..4, 1i32..=4), 1..6),
+		ratings in prop::collection::vec((0usize..4, arb_rating()), 1..6),
 		query_tag_ixs in prop::collection::vec(0usize..3, 1..=2),
 	) {
 		let rt = tokio::runtime::Runtime::new().unwrap();
@@ -1581,7 +1689,7 @@ proptest! {
 	fn prop_q8_5b_reviews_filter_by_child_item(
 		n_items in 2usize..=5,
 		relations in prop::collection::vec((0usize..5, 0usize..5), 0..8),
-		ratings in prop::collection::vec((0usize..6, 1i32..=4), 1..6),
+		ratings in prop::collection::vec((0usize..6, arb_rating()), 1..6),
 		child_ix in 0usize..5,
 	) {
 		let rt = tokio::runtime::Runtime::new().unwrap();
@@ -1610,7 +1718,7 @@ proptest! {
 	fn prop_q8_5_reviews_filter_by_parent_item(
 		n_items in 2usize..=5,
 		relations in prop::collection::vec((0usize..5, 0usize..5), 0..8),
-		ratings in prop::collection::vec((0usize..6, 1i32..=4), 1..6),
+		ratings in prop::collection::vec((0usize..6, arb_rating()), 1..6),
 		parent_ix in 0usize..5,
 	) {
 		let rt = tokio::runtime::Runtime::new().unwrap();
@@ -1648,7 +1756,7 @@ proptest! {
 			),
 			1..6
 		),
-		ratings in prop::collection::vec((0usize..6, 1i32..=4), 1..6),
+		ratings in prop::collection::vec((0usize..6, arb_rating()), 1..6),
 		next_cutoff in arb_datetime_utc(),
 		last_cutoff in arb_datetime_utc(),
 	) {
@@ -1680,7 +1788,7 @@ proptest! {
 			prop_oneof![Just(None), arb_datetime_utc().prop_map(Some)],
 			1..6
 		),
-		ratings in prop::collection::vec((0usize..6, 1i32..=4), 1..6),
+		ratings in prop::collection::vec((0usize..6, arb_rating()), 1..6),
 		cutoff in arb_datetime_utc(),
 	) {
 		let rt = tokio::runtime::Runtime::new().unwrap();
@@ -1729,7 +1837,7 @@ proptest! {
 	fn prop_q9_1_split_priority_is_ignored(
 		n_items in 1usize..=3,
 		choice in 0u8..3,
-		ratings in prop::collection::vec((0usize..6, 1i32..=4), 0..6),
+		ratings in prop::collection::vec((0usize..6, arb_rating()), 0..6),
 	) {
 		let rt = tokio::runtime::Runtime::new().unwrap();
 		rt.block_on(async {
@@ -1819,6 +1927,81 @@ async fn q10_2_subquery_usable_in_dml_filter() {
 	);
 }
 
+// ===========================================================================
+// Q11: state filter
+// ===========================================================================
+
+proptest! {
+	/// Q11.1: Filtering cards by `state` keeps exactly the cards in that
+	/// state, after an arbitrary set of reviews.
+	#[test]
+	fn prop_q11_1_cards_by_state(
+		n_items in 1usize..=3,
+		ratings in prop::collection::vec((0usize..6, arb_rating()), 0..8),
+		state in arb_card_state(),
+	) {
+		let rt = tokio::runtime::Runtime::new().unwrap();
+		rt.block_on(async {
+			let pool = setup_test_db();
+			let mut world = build_basic_world(&pool, 1, n_items).await;
+			add_reviews(&pool, &mut world, &ratings).await;
+
+			let query = GetQueryDtoBuilder::new().state(state).build();
+			let sql = sql_cards_matching(&pool, &query);
+			let oracle = oracle_cards_matching(&world, &query);
+			prop_assert_eq!(sql, oracle);
+			Ok(())
+		})?;
+	}
+
+	/// Q11.2: `state` is a card-level filter, so on items it keeps only the
+	/// items with at least one card in that state — including items that
+	/// have no cards at all being left out.
+	#[test]
+	fn prop_q11_2_items_by_state(
+		n_items in 1usize..=3,
+		ratings in prop::collection::vec((0usize..6, arb_rating()), 0..8),
+		state in arb_card_state(),
+	) {
+		let rt = tokio::runtime::Runtime::new().unwrap();
+		rt.block_on(async {
+			let pool = setup_test_db();
+			let mut world = build_basic_world(&pool, 1, n_items).await;
+			add_reviews(&pool, &mut world, &ratings).await;
+
+			let query = GetQueryDtoBuilder::new().state(state).build();
+			let sql = sql_items_matching(&pool, &query);
+			let oracle = oracle_items_matching(&world, &query);
+			prop_assert_eq!(sql, oracle);
+			Ok(())
+		})?;
+	}
+}
+
+/// Q11.3: An item whose cards have all been reviewed has no `new` card, so
+/// a `state=new` item query leaves it out.
+#[tokio::test]
+async fn q11_3_state_filter_applies_to_items() {
+	let pool = setup_test_db();
+	let mut world = build_basic_world(&pool, 1, 2).await;
+	let reviewed = world.items[0].get_id();
+	let ratings = world
+		.cards
+		.iter()
+		.enumerate()
+		.filter(|(_, c)| c.get_item_id() == reviewed)
+		.map(|(ix, _)| (ix, Rating::Good))
+		.collect::<Vec<_>>();
+	add_reviews(&pool, &mut world, &ratings).await;
+
+	let query = GetQueryDtoBuilder::new().state(CardState::New).build();
+	let sql = sql_items_matching(&pool, &query);
+
+	assert_eq!(sql, oracle_items_matching(&world, &query));
+	assert!(!sql.contains(&reviewed));
+	assert!(sql.contains(&world.items[1].get_id()));
+}
+
 // ===========================================================================
 // Regression replays.
 //
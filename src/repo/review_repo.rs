@@ -1,26 +1,207 @@
-use crate::db::{DbPool, ExecuteWithRetry};
-use crate::models::{Card, CardId, ItemTypeId, JsonValue, Review};
-use crate::schema::{cards, item_types, items, metadata, reviews};
+use crate::config::LeechAction;
+use crate::db::{DbPool, transaction_with_retry};
+use crate::dto::{ListReviewsQueryDto, ReviewPreview, ReviewsPageQueryDto};
+use crate::models::{
+	Card, CardId, ItemId, ItemTag, ItemTypeId, JsonValue, MAX_INCREMENTAL_QUEUE_INTERVAL_DAYS,
+	Rating, Review, ReviewId, SchedulerConfig, SessionId, Tag, TagId,
+};
+use crate::repo::card_repo::{DEFAULT_LEECH_THRESHOLD, MATURE_INTERVAL_DAYS};
+use crate::repo::item_type_cache::item_type_cache;
+use crate::repo::session_repo::{SessionEndedError, get_session_with_conn};
+use crate::repo::settings_repo::{Settings, get_settings_with_conn};
+use crate::schema::{cards, item_tags, item_types, items, metadata, reviews, tags};
+use crate::time_utils::{Recurrence, parse_due_date};
 use anyhow::{Result, anyhow};
 use chrono::Duration;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use diesel::prelude::*;
 use fsrs::{FSRS, MemoryState};
+use rand::Rng;
 use tracing::{debug, info, instrument, warn};
 
 /// Valid review function values
-pub const VALID_REVIEW_FUNCTIONS: &[&str] = &["fsrs", "incremental_queue"];
+pub const VALID_REVIEW_FUNCTIONS: &[&str] = &["fsrs", "incremental_queue", "todo", "recurring"];
+
+/// Number of reviews `list_reviews_with_filters` returns when no limit is given
+pub const DEFAULT_REVIEWS_PAGE_SIZE: u32 = 100;
+
+/// Largest page `list_reviews_with_filters` will return, whatever limit is asked for
+pub const MAX_REVIEWS_PAGE_SIZE: u32 = 1000;
+
+/// The waits `"fsrs"` cards go through while (re)learning, and the interval
+/// new cards graduate with, see [`compute_next_interval`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LearningSteps {
+	/// The waits a new card goes through before graduating
+	pub learning: Vec<Duration>,
+	/// The waits a lapsed card goes through before returning to its interval
+	pub relearning: Vec<Duration>,
+	/// The interval a new card gets when it graduates, in place of the one
+	/// its review function gives it, if set
+	pub graduating_interval: Option<Duration>,
+}
+
+/// How much reviewing a card moves its priority, see [`adjust_priority`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PriorityAdjustments {
+	/// Amount a card's priority rises by when it's rated Again
+	pub fail_boost: f32,
+	/// Amount a card's priority falls by when it's rated Easy
+	pub easy_decay: f32,
+}
+
+/// Works out a card's priority after a review
+///
+/// Rating a card Again raises its priority by the fail boost, so a card that
+/// keeps being failed comes up sooner, and rating it Easy lowers it by the
+/// easy decay. The result stays between 0 and 1; other ratings leave the
+/// priority alone.
+///
+/// ### Arguments
+///
+/// * `priority` - The card's priority before the review
+/// * `adjustments` - The configured priority adjustments
+/// * `rating` - The rating given during the review
+///
+/// ### Returns
+///
+/// The card's new priority
+pub fn adjust_priority(priority: f32, adjustments: PriorityAdjustments, rating: Rating) -> f32 {
+	match rating {
+		Rating::Again => (priority + adjustments.fail_boost).min(1.0),
+		Rating::Easy => (priority - adjustments.easy_decay).max(0.0),
+		Rating::Hard | Rating::Good => priority,
+	}
+}
+
+/// Takes the leech action on a card that has just been rated Again, if that
+/// made it a leech
+///
+/// A card becomes a leech on the review that brings its (non-cram) reviews
+/// rated Again up to [`DEFAULT_LEECH_THRESHOLD`], so the action is taken once
+/// rather than on every failure after that. Suspending leaves an already suspended
+/// card's suspension time alone, and tagging reuses the oldest tag with the
+/// leech tag's name, creating a visible top-level one if there's none.
+///
+/// ### Returns
+///
+/// Whether the card became a leech
+fn apply_leech_action(
+	conn: &mut diesel::SqliteConnection,
+	card: &Card,
+	action: &LeechAction,
+) -> std::result::Result<bool, diesel::result::Error> {
+	let failures: i64 = reviews::table
+		.filter(reviews::card_id.eq(card.get_id()))
+		.filter(reviews::rating.eq(1))
+		.filter(reviews::cram.eq(false))
+		.count()
+		.get_result(conn)?;
+	if failures != DEFAULT_LEECH_THRESHOLD as i64 {
+		return Ok(false);
+	}
+
+	match action {
+		LeechAction::None => {}
+		LeechAction::Suspend => {
+			diesel::update(
+				cards::table
+					.find(card.get_id())
+					.filter(cards::suspended.is_null()),
+			)
+			.set(cards::suspended.eq(Some(Utc::now().naive_utc())))
+			.execute(conn)?;
+		}
+		LeechAction::Tag(name) => {
+			let existing = tags::table
+				.filter(tags::name.eq(name))
+				.order_by(tags::created_at)
+				.select(tags::id)
+				.first::<TagId>(conn)
+				.optional()?;
+			let tag_id = match existing {
+				Some(tag_id) => tag_id,
+				None => {
+					let tag = Tag::new(name.clone(), true);
+					diesel::insert_into(tags::table)
+						.values(&tag)
+						.execute(conn)?;
+					tag.get_id()
+				}
+			};
+			diesel::insert_or_ignore_into(item_tags::table)
+				.values(ItemTag::new(card.get_item_id(), tag_id))
+				.execute(conn)?;
+		}
+	}
+
+	Ok(true)
+}
+
+/// Randomly stretches or shrinks a review interval, so that cards reviewed
+/// together don't all come due on the same day
+///
+/// The interval moves by a whole number of days, at most `fuzz` times its
+/// length in either direction (rounded towards zero), which keeps the time of
+/// day the scheduler picked. Intervals too short to move by a whole day are
+/// returned unchanged, as is every interval when `fuzz` is 0, in which case
+/// `rng` isn't used at all.
+///
+/// ### Arguments
+///
+/// * `interval` - The interval computed by the scheduler
+/// * `fuzz` - The largest fraction of the interval to move it by
+/// * `rng` - The source of randomness, seeded in tests for repeatable results
+///
+/// ### Returns
+///
+/// The fuzzed interval
+pub fn fuzz_interval(interval: Duration, fuzz: f64, rng: &mut impl Rng) -> Duration {
+	let max_shift_days = interval.num_seconds() as f64 / 86_400.0 * fuzz;
+	if max_shift_days < 1.0 {
+		return interval;
+	}
+
+	let shift_days = rng.random_range(-max_shift_days..=max_shift_days).trunc();
+	interval + Duration::days(shift_days as i64)
+}
+
+/// Adds the interval a review gave a card to the time of the review
+///
+/// ### Arguments
+///
+/// * `reviewed_at` - The time of the review
+/// * `interval` - The interval the card was given
+///
+/// ### Returns
+///
+/// When the card is next due
+///
+/// ### Errors
+///
+/// Returns an error if that's too far in the future to represent
+fn next_review_after(reviewed_at: DateTime<Utc>, interval: Duration) -> Result<DateTime<Utc>> {
+	reviewed_at
+		.checked_add_signed(interval)
+		.ok_or_else(|| anyhow!("Next review is too far in the future"))
+}
 
 /// Records a review for a card
 ///
 /// This function records a review for a card and updates the card's scheduling
-/// information based on the result of the review.
+/// information based on the result of the review. If priority adjustments
+/// are configured, the card's priority is moved too (see [`adjust_priority`]),
+/// and if the review makes the card a leech, the configured leech action is
+/// taken on it (see [`Settings::leech_action`]). Failing a mature card adds to its
+/// `lapses`.
 ///
 /// ### Arguments
 ///
 /// * `pool` - A reference to the database connection pool
 /// * `card_id` - The ID of the card being reviewed
-/// * `rating_val` - The rating given during the review (1-3)
+/// * `rating` - The rating given during the review, on the configured rating
+///   scale
 ///
 /// ### Returns
 ///
@@ -32,22 +213,100 @@ pub const VALID_REVIEW_FUNCTIONS: &[&str] = &["fsrs", "incremental_queue"];
 /// - Unable to get a connection from the pool
 /// - The database operations fail
 /// - The card does not exist
-/// - The rating is invalid (not 1-4)
-#[instrument(skip(pool), fields(card_id = %card_id, rating = %rating_val))]
-pub async fn record_review(pool: &DbPool, card_id: &CardId, rating_val: i32) -> Result<Review> {
+/// - The rating is above the configured rating scale (an
+///   `OutOfScaleRatingError`)
+#[instrument(skip(pool), fields(card_id = %card_id, rating = %rating))]
+pub async fn record_review(pool: &DbPool, card_id: &CardId, rating: Rating) -> Result<Review> {
+	record_review_in_session(pool, card_id, rating, None, None).await
+}
+
+/// Records a review for a card, optionally as part of a review session
+///
+/// Behaves exactly like `record_review`, additionally tagging the review with
+/// the given session so it counts towards that session's stats, and keeping
+/// what was answered with it.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `card_id` - The ID of the card being reviewed
+/// * `rating` - The rating given during the review, on the configured rating
+///   scale
+/// * `session_id` - The session the review belongs to, if any
+/// * `response` - What was answered in the review, if it's to be kept
+///
+/// ### Returns
+///
+/// A Result containing the newly created Review if successful
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database operations fail
+/// - The card does not exist
+/// - The rating is above the configured rating scale (an
+///   `OutOfScaleRatingError`)
+/// - The session does not exist
+/// - The session has already ended (a `SessionEndedError`)
+#[instrument(skip(pool, response), fields(card_id = %card_id, rating = %rating))]
+pub async fn record_review_in_session(
+	pool: &DbPool,
+	card_id: &CardId,
+	rating: Rating,
+	session_id: Option<&SessionId>,
+	response: Option<String>,
+) -> Result<Review> {
+	record_review_with_options(pool, card_id, rating, session_id, response, false).await
+}
+
+/// Records a cram review for a card, optionally as part of a review session
+///
+/// The review is logged, marked as a cram review, exactly as
+/// `record_review_in_session` would log it, but the card's schedule
+/// (`last_review`, `next_review` and `scheduler_data`) is left alone, so
+/// reviewing ahead of an exam doesn't disturb the real schedule.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `card_id` - The ID of the card being reviewed
+/// * `rating` - The rating given during the review, on the configured rating
+///   scale
+/// * `session_id` - The session the review belongs to, if any
+/// * `response` - What was answered in the review, if it's to be kept
+///
+/// ### Returns
+///
+/// A Result containing the newly created Review if successful
+///
+/// ### Errors
+///
+/// Returns the same errors as `record_review_in_session`
+#[instrument(skip(pool, response), fields(card_id = %card_id, rating = %rating))]
+pub async fn record_cram_review_in_session(
+	pool: &DbPool,
+	card_id: &CardId,
+	rating: Rating,
+	session_id: Option<&SessionId>,
+	response: Option<String>,
+) -> Result<Review> {
+	record_review_with_options(pool, card_id, rating, session_id, response, true).await
+}
+
+/// Records a review, rescheduling its card unless it's a cram review
+async fn record_review_with_options(
+	pool: &DbPool,
+	card_id: &CardId,
+	rating: Rating,
+	session_id: Option<&SessionId>,
+	response: Option<String>,
+	cram: bool,
+) -> Result<Review> {
 	debug!("Recording new review for card");
 
 	let conn = &mut pool.get()?;
 
-	// Validate the rating
-	if rating_val < 1 || rating_val > 4 {
-		warn!("Invalid rating provided: {}", rating_val);
-		return Err(anyhow!(
-			"Rating must be between 1 and 4, got {}",
-			rating_val
-		));
-	}
-
 	// Verify that the card exists and get its current data
 	let card = cards::table
 		.find(card_id)
@@ -57,97 +316,504 @@ pub async fn record_review(pool: &DbPool, card_id: &CardId, rating_val: i32) ->
 			anyhow!("Card not found")
 		})?;
 
-	debug!("Found card, creating review");
+	let settings = get_settings_with_conn(conn)?;
 
-	// Create the review
-	let new_review = Review::new(card_id.clone(), rating_val);
+	// On the 2-button scale a pass is scheduled as Good, and 3 and 4 can't
+	// be given at all
+	let rating = rating.on_scale(settings.rating_scale)?;
 
-	// Insert the review into the database
-	diesel::insert_into(reviews::table)
-		.values(new_review.clone())
-		.execute_with_retry(conn)
-		.await?;
+	// Verify that the session, if any, exists and is still running
+	if let Some(session_id) = session_id {
+		let session = get_session_with_conn(conn, session_id)?.ok_or_else(|| {
+			debug!("Session not found");
+			anyhow!("Session not found")
+		})?;
+		if session.get_ended_at().is_some() {
+			return Err(SessionEndedError(session_id.clone()).into());
+		}
+	}
 
-	// Look up the review_function for this card's item type
-	let review_function: String = items::table
-		.inner_join(item_types::table.on(item_types::id.eq(items::item_type)))
-		.filter(items::id.eq(card.get_item_id()))
-		.select(item_types::review_function)
-		.first::<String>(conn)
-		.map_err(|e| anyhow!("Failed to look up review function: {}", e))?;
+	debug!("Found card, creating review");
 
-	debug!(
-		"Calculating next review date using review function: {}",
-		review_function
-	);
+	// Cram reviews leave the card's priority alone too
+	let priority = if cram {
+		card.get_priority()
+	} else {
+		adjust_priority(card.get_priority(), settings.priority_adjustments, rating)
+	};
 
-	// Update the card's scheduling information
-	let (next_review, scheduler_data) = calculate_next_review(&card, &review_function, rating_val)?;
+	// Create the review, remembering the priority it changes so that undoing
+	// it can restore it
+	let mut new_review = Review::new(card_id.clone(), rating);
+	new_review.set_session_id(session_id.cloned());
+	new_review.set_response(response);
+	new_review.set_cram(cram);
+	if priority != card.get_priority() {
+		new_review.set_priority_before(Some(card.get_priority()));
+	}
 
-	debug!("Next review scheduled for: {}", next_review);
+	// Work out the card's new schedule before writing anything, so that a
+	// card that can't be scheduled doesn't get half a review. Cram reviews
+	// are only logged, leaving the card's schedule as it was.
+	let schedule = if cram {
+		None
+	} else {
+		// Look up the review_function for this card's item type
+		let item = get_item_scheduling(conn, &card)
+			.map_err(|e| anyhow!("Failed to look up review function: {}", e))?;
+
+		debug!(
+			"Calculating next review date using review function: {}",
+			item.review_function
+		);
+
+		// The review's own timestamp is used as "now" so that `undo_review`
+		// can replay it (all but the fuzz) exactly.
+		let reviewed_at = new_review.get_review_timestamp();
+		let minimum_interval = settings.minimum_interval;
+		let (interval, scheduler_data) = compute_next_interval(
+			&card,
+			&item,
+			&settings.learning_steps,
+			minimum_interval,
+			rating,
+			reviewed_at,
+		)?;
+		// Todos are due on dates the user picked, so they aren't fuzzed
+		let fuzz = match item.review_function.as_str() {
+			"todo" | "recurring" => 0.0,
+			_ => settings.interval_fuzz,
+		};
+		// Fuzzing doesn't take an interval below the floor, unless it was already
+		// below it (a learning step)
+		let fuzzed = fuzz_interval(interval, fuzz, &mut rand::rng());
+		let next_review =
+			next_review_after(reviewed_at, fuzzed.max(interval.min(minimum_interval)))?;
+
+		debug!("Next review scheduled for: {}", next_review);
+
+		let leech_action = match rating {
+			Rating::Again => Some(settings.leech_action.clone()),
+			_ => None,
+		};
+		Some((reviewed_at, next_review, scheduler_data, leech_action))
+	};
 
-	// Update the card in the database
-	diesel::update(cards::table.find(card_id.clone()))
-		.set((
-			cards::last_review.eq(Utc::now().naive_utc()),
-			cards::next_review.eq(next_review.naive_utc()),
-			cards::scheduler_data.eq(Some(scheduler_data)),
-		))
-		.execute_with_retry(conn)
-		.await?;
+	// Insert the review, take any leech action and update the card together,
+	// so a failure part way leaves neither the review nor its effects behind
+	let lapses = card.get_mature_lapses() + i32::from(!cram && is_lapse(&card, rating));
+	let became_leech = transaction_with_retry(conn, |c| {
+		diesel::insert_into(reviews::table)
+			.values(new_review.clone())
+			.execute(c)?;
+
+		let Some((reviewed_at, next_review, scheduler_data, leech_action)) = &schedule else {
+			return Ok(None);
+		};
+
+		let became_leech = match leech_action {
+			Some(action) if apply_leech_action(c, &card, action)? => Some(action),
+			_ => None,
+		};
+
+		diesel::update(cards::table.find(card_id))
+			.set((
+				cards::last_review.eq(reviewed_at.naive_utc()),
+				cards::next_review.eq(next_review.naive_utc()),
+				cards::scheduler_data.eq(Some(scheduler_data)),
+				cards::priority.eq(priority),
+				cards::lapses.eq(lapses),
+			))
+			.execute(c)?;
+
+		Ok(became_leech)
+	})
+	.await?;
+
+	if cram {
+		info!(
+			"Successfully recorded cram review with id: {}",
+			new_review.get_id()
+		);
+		crate::metrics::metrics().record_review(rating);
+		return Ok(new_review);
+	}
+
+	if let Some(action) = became_leech {
+		info!("Card became a leech, leech action: {:?}", action);
+	}
 
 	info!(
 		"Successfully recorded review with id: {}",
 		new_review.get_id()
 	);
+	crate::metrics::metrics().record_review(rating);
 
 	// Return the review
 	Ok(new_review)
 }
 
-/// Dispatches to the appropriate review calculation function based on the review function name
+/// Whether rating a card counts as a lapse: rating it Again while it's
+/// mature, scheduled [`MATURE_INTERVAL_DAYS`] or more after its last review
+///
+/// Schedulers store an interval of n days as an hour short of them, so an
+/// hour's slack is allowed, as `get_stats_overview` allows.
+///
+/// ### Arguments
+///
+/// * `card` - The card as it was before the review
+/// * `rating` - The rating given in the review
+///
+/// ### Returns
+///
+/// True if the review is a lapse
+fn is_lapse(card: &Card, rating: Rating) -> bool {
+	rating == Rating::Again
+		&& card.get_last_review().is_some_and(|last_review| {
+			card.get_next_review() - last_review
+				>= Duration::days(MATURE_INTERVAL_DAYS.into()) - Duration::hours(1)
+		})
+}
+
+/// What a card's item and item type contribute to scheduling it, see
+/// [`compute_next_interval`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemScheduling {
+	/// The name of the item type's review function
+	pub review_function: String,
+	/// The item's data, which some review functions schedule by
+	pub item_data: serde_json::Value,
+	/// The item type's tuning parameters for its scheduler
+	pub scheduler_config: SchedulerConfig,
+}
+
+/// Looks up the review function and scheduler config of a card's item type,
+/// and the card's item's data, which between them are all a scheduler needs
+/// besides the card
+///
+/// A stored scheduler config that no longer parses falls back to the
+/// defaults rather than failing the review.
+fn get_item_scheduling(
+	conn: &mut diesel::SqliteConnection,
+	card: &Card,
+) -> QueryResult<ItemScheduling> {
+	let (review_function, item_data, scheduler_config) = items::table
+		.inner_join(item_types::table.on(item_types::id.eq(items::item_type)))
+		.filter(items::id.eq(card.get_item_id()))
+		.select((
+			item_types::review_function,
+			items::item_data,
+			item_types::scheduler_config,
+		))
+		.first::<(String, JsonValue, Option<JsonValue>)>(conn)?;
+
+	let scheduler_config = match scheduler_config {
+		Some(config) => SchedulerConfig::from_json(&config.0).unwrap_or_else(|e| {
+			warn!("Ignoring invalid scheduler config: {}", e);
+			SchedulerConfig::default()
+		}),
+		None => SchedulerConfig::default(),
+	};
+
+	Ok(ItemScheduling {
+		review_function,
+		item_data: item_data.0,
+		scheduler_config,
+	})
+}
+
+/// Computes how long until a card is next due after a review, without
+/// touching the database
+///
+/// This is the scheduling math shared by `record_review`, `undo_review` and
+/// the review previews, so that a preview always matches what recording the
+/// same rating at the same time would do. It dispatches on the review
+/// function name.
+///
+/// New `"fsrs"` cards first go through the learning steps, if any are
+/// configured: each passing rating (Hard, Good or Easy) moves the card to
+/// the next step, due after that step's wait, and a passing rating on the
+/// last step graduates it to FSRS. Again sends it back to the first step.
+/// The current step is kept in `scheduler_data` as `learning_step`.
+///
+/// Rating a graduated `"fsrs"` card (one that has passed a review, marked
+/// `graduated` in its `scheduler_data`) Again is a lapse, counted in
+/// `scheduler_data` as `lapses`. If relearning steps are configured, a lapsed
+/// card goes through them the same way (the step is kept as
+/// `relearning_step`), then returns to the reduced interval FSRS gave it for
+/// the lapse.
+///
+/// If a graduating interval is configured, a new `"fsrs"` or
+/// `"incremental_queue"` card graduating with a passing rating (its first
+/// review, or its last learning step) is due after that interval instead of
+/// the one its review function gives it.
 ///
 /// ### Arguments
 ///
 /// * `card` - The card being reviewed
-/// * `review_function` - The name of the review function to use
-/// * `rating` - The rating given during the review (1-4)
+/// * `item` - The review function to use, with the card's item data and
+///   item type's scheduler config
+/// * `steps` - The configured learning and relearning steps
+/// * `minimum_interval` - The shortest interval a graduated card gets
+/// * `rating` - The rating given during the review
+/// * `now` - The time the review happens at
 ///
 /// ### Returns
 ///
-/// A Result containing a tuple of (next_review, scheduler_data)
+/// A Result containing a tuple of (interval until the next review, scheduler_data)
 ///
 /// ### Errors
 ///
-/// Returns an error if the review function is unknown, the rating is invalid, or computation fails
-#[instrument(skip_all, fields(card_id = %card.get_id(), review_function = %review_function, rating = %rating))]
+/// Returns an error if the review function is unknown or computation fails
+#[instrument(skip_all, fields(card_id = %card.get_id(), review_function = %item.review_function, rating = %rating))]
+pub fn compute_next_interval(
+	card: &Card,
+	item: &ItemScheduling,
+	steps: &LearningSteps,
+	minimum_interval: Duration,
+	rating: Rating,
+	now: chrono::DateTime<Utc>,
+) -> Result<(Duration, JsonValue)> {
+	let floored = |(interval, data): (Duration, JsonValue)| (interval.max(minimum_interval), data);
+	let scheduler_config = &item.scheduler_config;
+	match item.review_function.as_str() {
+		"fsrs" => {
+			if let Some(step) = next_learning_step(card, &steps.learning, rating) {
+				return Ok(step);
+			}
+			if let Some(step) = next_relearning_step(
+				card,
+				scheduler_config,
+				&steps.relearning,
+				minimum_interval,
+				rating,
+				now,
+			)? {
+				return Ok(step);
+			}
+			compute_next_fsrs_interval(card, rating, scheduler_config, now)
+				.map(|next| graduate(card, steps.graduating_interval, rating, next))
+				.map(floored)
+		}
+		"incremental_queue" => {
+			compute_next_incremental_queue_interval(card, rating, scheduler_config)
+				.map(|next| graduate(card, steps.graduating_interval, rating, next))
+				.map(floored)
+		}
+		"todo" => compute_next_todo_interval(card, rating, now),
+		"recurring" => compute_next_recurring_interval(card, &item.item_data, now),
+		_ => Err(anyhow!("Unknown review function: {}", item.review_function)),
+	}
+}
+
+/// Computes the next review date for a card, see [`compute_next_interval`]
+///
+/// ### Returns
+///
+/// A Result containing a tuple of (next_review, scheduler_data)
 fn calculate_next_review(
 	card: &Card,
-	review_function: &str,
-	rating: i32,
+	item: &ItemScheduling,
+	steps: &LearningSteps,
+	minimum_interval: Duration,
+	rating: Rating,
+	now: chrono::DateTime<Utc>,
 ) -> Result<(chrono::DateTime<Utc>, JsonValue)> {
-	match review_function {
-		"fsrs" => calculate_next_fsrs_review(card, rating),
-		"incremental_queue" => calculate_next_incremental_queue_review(card, rating),
-		_ => Err(anyhow!("Unknown review function: {}", review_function)),
+	let (interval, scheduler_data) =
+		compute_next_interval(card, item, steps, minimum_interval, rating, now)?;
+	Ok((next_review_after(now, interval)?, scheduler_data))
+}
+
+/// Gives a new card graduating with a passing rating the graduating
+/// interval, see [`compute_next_interval`]
+///
+/// A card is new until its first review outside the learning steps, so it
+/// either has no scheduler data yet or is on a learning step.
+///
+/// ### Arguments
+///
+/// * `card` - The card being reviewed
+/// * `graduating_interval` - The configured graduating interval, if any
+/// * `rating` - The rating given during the review
+/// * `next` - The interval and scheduler_data the review function gave the
+///   card
+///
+/// ### Returns
+///
+/// The graduating interval and the scheduler_data (with its `interval`, if
+/// it keeps one, to match) if the card graduates, otherwise `next` unchanged
+fn graduate(
+	card: &Card,
+	graduating_interval: Option<Duration>,
+	rating: Rating,
+	next: (Duration, JsonValue),
+) -> (Duration, JsonValue) {
+	let Some(graduating_interval) = graduating_interval else {
+		return next;
+	};
+	let is_new = match card.get_scheduler_data() {
+		None => true,
+		Some(data) => data.0.get("learning_step").is_some(),
+	};
+	if !is_new || rating == Rating::Again {
+		return next;
 	}
+
+	let (_, mut scheduler_data) = next;
+	if let Some(interval) = scheduler_data.0.get_mut("interval") {
+		*interval = (graduating_interval.num_seconds() as f64 / 86400.0).into();
+	}
+	(graduating_interval, scheduler_data)
 }
 
-/// Calculates the next review date using the FSRS algorithm
+/// Moves a card through the learning steps, see [`compute_next_interval`]
 ///
 /// ### Arguments
 ///
 /// * `card` - The card being reviewed
-/// * `rating` - The rating given during the review (1-4)
+/// * `learning_steps` - The configured learning steps
+/// * `rating` - The rating given during the review
 ///
 /// ### Returns
 ///
-/// A Result containing a tuple of (next_review, scheduler_data)
+/// The wait until the card's next step and its new scheduler_data, or None
+/// if the card has graduated (or graduates with this review), or there are
+/// no learning steps
+fn next_learning_step(
+	card: &Card,
+	learning_steps: &[Duration],
+	rating: Rating,
+) -> Option<(Duration, JsonValue)> {
+	if learning_steps.is_empty() {
+		return None;
+	}
+
+	// A card without scheduler data is new, so about to start learning; one
+	// with scheduler data but no step has graduated
+	let current_step = match card.get_scheduler_data() {
+		None => None,
+		Some(data) => Some(data.0.get("learning_step")?.as_u64()? as usize),
+	};
+	let next_step = match (rating, current_step) {
+		(Rating::Again, _) | (_, None) => 0,
+		(_, Some(step)) => step + 1,
+	};
+
+	let wait = learning_steps.get(next_step)?;
+	Some((
+		*wait,
+		JsonValue(serde_json::json!({ "learning_step": next_step })),
+	))
+}
+
+/// Moves a lapsed card through the relearning steps, see
+/// [`compute_next_interval`]
+///
+/// The card's FSRS state is updated for the lapse when it happens, and the
+/// interval FSRS gave it then is kept (as `lapse_interval_seconds`) until it
+/// passes the last step and returns to that interval.
+///
+/// ### Arguments
+///
+/// * `card` - The card being reviewed
+/// * `relearning_steps` - The configured relearning steps
+/// * `minimum_interval` - The shortest interval the card returns to after
+///   its last step
+/// * `rating` - The rating given during the review
+/// * `now` - The time the review happens at
+///
+/// ### Returns
+///
+/// A Result containing the wait until the card is next due and its new
+/// scheduler_data, or None if the card isn't lapsing or relearning
+///
+/// ### Errors
+///
+/// Returns an error if computing the lapse's FSRS state fails
+fn next_relearning_step(
+	card: &Card,
+	scheduler_config: &SchedulerConfig,
+	relearning_steps: &[Duration],
+	minimum_interval: Duration,
+	rating: Rating,
+	now: chrono::DateTime<Utc>,
+) -> Result<Option<(Duration, JsonValue)>> {
+	let Some(serde_json::Value::Object(data)) = card.get_scheduler_data().map(|data| data.0) else {
+		return Ok(None);
+	};
+
+	let Some(step) = data.get("relearning_step").and_then(|step| step.as_u64()) else {
+		// Only a graduated card failing a review starts relearning
+		if rating != Rating::Again || relearning_steps.is_empty() || !has_graduated(card) {
+			return Ok(None);
+		}
+		let (lapse_interval, lapsed) =
+			compute_next_fsrs_interval(card, rating, scheduler_config, now)?;
+		let serde_json::Value::Object(mut lapsed) = lapsed.0 else {
+			return Err(anyhow!("Invalid scheduler data"));
+		};
+		lapsed.insert("relearning_step".to_string(), 0.into());
+		lapsed.insert(
+			"lapse_interval_seconds".to_string(),
+			lapse_interval.num_seconds().into(),
+		);
+		return Ok(Some((relearning_steps[0], JsonValue(lapsed.into()))));
+	};
+
+	let next_step = match rating {
+		Rating::Again => 0,
+		_ => step as usize + 1,
+	};
+	let mut relearning = data.clone();
+	if let Some(wait) = relearning_steps.get(next_step) {
+		relearning.insert("relearning_step".to_string(), next_step.into());
+		return Ok(Some((*wait, JsonValue(relearning.into()))));
+	}
+
+	// Passed the last step, so back to the interval the lapse left it with
+	relearning.remove("relearning_step");
+	let lapse_interval = relearning
+		.remove("lapse_interval_seconds")
+		.and_then(|seconds| seconds.as_i64())
+		.map(Duration::seconds)
+		.unwrap_or_else(|| Duration::days(1));
+	Ok(Some((
+		lapse_interval.max(minimum_interval),
+		JsonValue(relearning.into()),
+	)))
+}
+
+/// Whether an `"fsrs"` card has ever passed a review, kept in its
+/// scheduler_data as `graduated`
+///
+/// A card that has only ever been failed hasn't been learned yet, so failing
+/// it again isn't a lapse.
+fn has_graduated(card: &Card) -> bool {
+	card.get_scheduler_data()
+		.and_then(|data| data.0.get("graduated")?.as_bool())
+		.unwrap_or(false)
+}
+
+/// Computes the next review interval using the FSRS algorithm
+///
+/// ### Arguments
+///
+/// * `card` - The card being reviewed
+/// * `rating` - The rating given during the review
+/// * `scheduler_config` - The tuning parameters of the card's item type, of
+///   which FSRS uses `desired_retention`
+/// * `now` - The time the review happens at
+///
+/// ### Returns
+///
+/// A Result containing a tuple of (interval, scheduler_data)
 #[instrument(skip_all, fields(card_id = %card.get_id(), rating = %rating))]
-fn calculate_next_fsrs_review(
+fn compute_next_fsrs_interval(
 	card: &Card,
-	rating: i32,
-) -> Result<(chrono::DateTime<Utc>, JsonValue)> {
+	rating: Rating,
+	scheduler_config: &SchedulerConfig,
+	now: chrono::DateTime<Utc>,
+) -> Result<(Duration, JsonValue)> {
 	debug!("Calculating next review date with FSRS algorithm");
 
 	use serde_json::json;
@@ -166,7 +832,7 @@ fn calculate_next_fsrs_review(
 	// Calculate days elapsed since last review
 	let days_elapsed = card
 		.get_last_review()
-		.map(|lr| (Utc::now() - lr).num_days().max(0) as u32)
+		.map(|lr| (now - lr).num_days().max(0) as u32)
 		.unwrap_or(0);
 
 	debug!(
@@ -174,37 +840,48 @@ fn calculate_next_fsrs_review(
 		current_state, days_elapsed
 	);
 
-	let next_states = fsrs.next_states(current_state, 0.9, days_elapsed)?;
+	let next_states = fsrs.next_states(
+		current_state,
+		scheduler_config.desired_retention as f32,
+		days_elapsed,
+	)?;
 
 	// Pick the state for the given rating
 	let chosen = match rating {
-		1 => next_states.again,
-		2 => next_states.hard,
-		3 => next_states.good,
-		4 => next_states.easy,
-		_ => {
-			warn!("Invalid rating: {}", rating);
-			return Err(anyhow!("Invalid rating: {}", rating));
-		}
+		Rating::Again => next_states.again,
+		Rating::Hard => next_states.hard,
+		Rating::Good => next_states.good,
+		Rating::Easy => next_states.easy,
 	};
 
-	let next_review =
-		Utc::now() + Duration::days(chosen.interval.ceil() as i64) - Duration::hours(1);
+	let interval = Duration::days(chosen.interval.ceil() as i64) - Duration::hours(1);
 
-	let scheduler_data = JsonValue(json!({
+	let mut scheduler_data = json!({
 		"stability": chosen.memory.stability,
 		"difficulty": chosen.memory.difficulty,
-	}));
+	});
+
+	// A card graduates the first time it passes, and only failing a card
+	// that has graduated is a lapse
+	let graduated = has_graduated(card);
+	if graduated || rating != Rating::Again {
+		scheduler_data["graduated"] = true.into();
+	}
+	let lapses = card.get_lapses() + u32::from(rating == Rating::Again && graduated);
+	if lapses > 0 {
+		scheduler_data["lapses"] = lapses.into();
+	}
+	let scheduler_data = JsonValue(scheduler_data);
 
 	debug!(
-		"Next review scheduled for: {}, stability: {}, difficulty: {}",
-		next_review, chosen.memory.stability, chosen.memory.difficulty
+		"Next review due in: {}, stability: {}, difficulty: {}",
+		interval, chosen.memory.stability, chosen.memory.difficulty
 	);
 
-	Ok((next_review, scheduler_data))
+	Ok((interval, scheduler_data))
 }
 
-/// Calculates the next review date using the incremental queue algorithm
+/// Computes the next review interval using the incremental queue algorithm
 ///
 /// This scheduler is designed for Todo/Incremental Reading/Incremental Watching
 /// item types where priority controls how often content is revisited.
@@ -212,16 +889,23 @@ fn calculate_next_fsrs_review(
 /// ### Arguments
 ///
 /// * `card` - The card being reviewed
-/// * `rating` - The rating given during the review (1-4)
+/// * `rating` - The rating given during the review
+/// * `scheduler_config` - The tuning parameters of the card's item type
 ///
 /// ### Returns
 ///
-/// A Result containing a tuple of (next_review, scheduler_data)
+/// A Result containing a tuple of (interval, scheduler_data). The interval
+/// is at most [`MAX_INCREMENTAL_QUEUE_INTERVAL_DAYS`].
+///
+/// ### Errors
+///
+/// Returns an error if the card's scheduler data isn't an object
 #[instrument(skip_all, fields(card_id = %card.get_id(), rating = %rating))]
-fn calculate_next_incremental_queue_review(
+fn compute_next_incremental_queue_interval(
 	card: &Card,
-	rating: i32,
-) -> Result<(chrono::DateTime<Utc>, JsonValue)> {
+	rating: Rating,
+	scheduler_config: &SchedulerConfig,
+) -> Result<(Duration, JsonValue)> {
 	debug!("Calculating next review date for incremental queue");
 
 	use serde_json::json;
@@ -240,31 +924,46 @@ fn calculate_next_incremental_queue_review(
 
 	let priority = card.get_priority() as f64;
 
-	// Priority controls the growth rate of intervals.
+	// Priority controls the growth rate of intervals. By default:
 	//   priority 1.0 (highest) -> multiplier ~1.2 (slow growth, seen often)
 	//   priority 0.0 (lowest)  -> multiplier ~3.0 (fast growth, fades away)
-	const GROWTH_AT_MAX_PRIORITY: f64 = 1.2;
-	const GROWTH_AT_MIN_PRIORITY: f64 = 3.0;
+	let SchedulerConfig {
+		growth_at_max_priority,
+		growth_at_min_priority,
+		hard_factor,
+		easy_factor,
+		jitter,
+		difficulty_weight,
+		..
+	} = *scheduler_config;
 	let base_multiplier =
-		GROWTH_AT_MIN_PRIORITY - priority * (GROWTH_AT_MIN_PRIORITY - GROWTH_AT_MAX_PRIORITY);
+		growth_at_min_priority - priority * (growth_at_min_priority - growth_at_max_priority);
 
-	// Jitter +/-15% to prevent clustering
-	let jitter = 1.0 + (rand::random::<f64>() - 0.5) * 0.3;
+	// Cards the user finds hard grow slower, by as much as the item type's
+	// difficulty_weight: at a weight of 1, a card of difficulty 1 doesn't
+	// grow at all
+	let difficulty = card.get_difficulty().unwrap_or(0.0) as f64;
+	let base_multiplier = 1.0 + (base_multiplier - 1.0) * (1.0 - difficulty_weight * difficulty);
+
+	// Jitter (+/-15% by default) to prevent clustering
+	let jitter = 1.0 + (rand::random::<f64>() - 0.5) * 2.0 * jitter;
 
 	// Rating semantics:
-	//   1 (again) -> reset to 1.0 day
-	//   2 (hard)  -> sooner than default (min 2 days)
-	//   3 (good)  -> normal pace (min 4 days)
-	//   4 (easy)  -> longer interval (min 7 days)
+	//   Again -> reset to 1.0 day
+	//   Hard  -> sooner than default (min 2 days)
+	//   Good  -> normal pace (min 4 days)
+	//   Easy  -> longer interval (min 7 days)
 	let new_interval = match rating {
-		1 => 1.0,
-		2 => (current_interval * base_multiplier * 0.6 * jitter).max(2.0),
-		3 => (current_interval * base_multiplier * jitter).max(4.0),
-		4 => (current_interval * base_multiplier * 1.8 * jitter).max(7.0),
-		_ => return Err(anyhow!("Invalid rating: {}", rating)),
-	};
+		Rating::Again => 1.0,
+		Rating::Hard => (current_interval * base_multiplier * hard_factor * jitter).max(2.0),
+		Rating::Good => (current_interval * base_multiplier * jitter).max(4.0),
+		Rating::Easy => (current_interval * base_multiplier * easy_factor * jitter).max(7.0),
+	}
+	.min(MAX_INCREMENTAL_QUEUE_INTERVAL_DAYS);
 
-	let next_review = Utc::now() + Duration::days(new_interval.ceil() as i64) - Duration::hours(1);
+	let interval = Duration::try_days(new_interval.ceil() as i64)
+		.ok_or_else(|| anyhow!("Interval of {} days is too long", new_interval))?
+		- Duration::hours(1);
 
 	let scheduler_data = JsonValue(json!({ "interval": new_interval }));
 
@@ -273,7 +972,139 @@ fn calculate_next_incremental_queue_review(
 		priority, base_multiplier, rating, current_interval, new_interval
 	);
 
-	Ok((next_review, scheduler_data))
+	Ok((interval, scheduler_data))
+}
+
+/// Computes the next review interval for a todo, by snoozing it
+///
+/// A todo scheduled by the `"todo"` review function is due on its item's
+/// `due_date` (see [`first_review_date`]). Reviewing it pushes that date
+/// back, from the date it was due or from `now` if it's overdue, by a day
+/// for Again, 2 days for Hard, 4 days for Good and a week for Easy.
+///
+/// ### Arguments
+///
+/// * `card` - The todo's card
+/// * `rating` - The rating given during the review
+/// * `now` - The time the review happens at
+///
+/// ### Returns
+///
+/// A Result containing a tuple of (interval, scheduler_data)
+#[instrument(skip_all, fields(card_id = %card.get_id(), rating = %rating))]
+fn compute_next_todo_interval(
+	card: &Card,
+	rating: Rating,
+	now: DateTime<Utc>,
+) -> Result<(Duration, JsonValue)> {
+	debug!("Snoozing todo");
+
+	use serde_json::json;
+
+	let snooze = Duration::days(match rating {
+		Rating::Again => 1,
+		Rating::Hard => 2,
+		Rating::Good => 4,
+		Rating::Easy => 7,
+	});
+	let due = card.get_next_review().max(now) + snooze;
+
+	let snoozes = card
+		.get_scheduler_data()
+		.and_then(|data| data.0.get("snoozes")?.as_u64())
+		.unwrap_or(0);
+	let scheduler_data = JsonValue(json!({ "snoozes": snoozes + 1 }));
+
+	debug!("Todo snoozed until {}", due);
+
+	Ok((due - now, scheduler_data))
+}
+
+/// Computes the next review interval for a recurring todo
+///
+/// Reviewing a recurring todo, whatever the rating, marks this occurrence
+/// done, so it's next due one recurrence (its item's `every`, e.g. `"1w"`)
+/// from now.
+///
+/// ### Arguments
+///
+/// * `card` - The recurring todo's card
+/// * `item_data` - The recurring todo's item data
+/// * `now` - The time the review happens at
+///
+/// ### Returns
+///
+/// A Result containing a tuple of (interval, scheduler_data)
+///
+/// ### Errors
+///
+/// Returns an error if the item data has no valid `every`, or its next
+/// occurrence can't be represented
+#[instrument(skip_all, fields(card_id = %card.get_id()))]
+fn compute_next_recurring_interval(
+	card: &Card,
+	item_data: &serde_json::Value,
+	now: DateTime<Utc>,
+) -> Result<(Duration, JsonValue)> {
+	let every = item_data
+		.get("every")
+		.and_then(|every| every.as_str())
+		.ok_or_else(|| anyhow!("Recurring todo has no `every`"))?;
+	let recurrence: Recurrence = every.parse().map_err(|e: String| anyhow!(e))?;
+
+	let completions = card
+		.get_scheduler_data()
+		.and_then(|data| data.0.get("completions")?.as_u64())
+		.unwrap_or(0);
+	let scheduler_data = JsonValue(serde_json::json!({ "completions": completions + 1 }));
+
+	debug!("Recurring todo done, next due in {}", every);
+
+	let next = recurrence
+		.next_after(now)
+		.ok_or_else(|| anyhow!("Recurring todo's next occurrence is too far away"))?;
+
+	Ok((next - now, scheduler_data))
+}
+
+/// Works out when a new card is first due
+///
+/// Cards are due as soon as they're created, except cards scheduled by the
+/// `"todo"` review function whose item data has a `due_date`, which are due
+/// on that date.
+///
+/// ### Arguments
+///
+/// * `review_function` - The review function of the card's item type
+/// * `item_data` - The card's item's data
+/// * `tz` - The timezone a bare `due_date` is in
+/// * `now` - The time the card is created at
+///
+/// ### Returns
+///
+/// The card's first `next_review`
+pub fn first_review_date(
+	review_function: &str,
+	item_data: &serde_json::Value,
+	tz: Tz,
+	now: DateTime<Utc>,
+) -> DateTime<Utc> {
+	match review_function {
+		"todo" => todo_due_date(item_data, tz).unwrap_or(now),
+		_ => now,
+	}
+}
+
+/// Reads a todo's `due_date` from its item data
+///
+/// ### Returns
+///
+/// The due date, or None if the item has none (or it doesn't parse)
+pub fn todo_due_date(item_data: &serde_json::Value, tz: Tz) -> Option<DateTime<Utc>> {
+	item_data
+		.get("due_date")
+		.and_then(|due| due.as_str())
+		.and_then(|due| parse_due_date(due, tz))
 }
 
 /// Gets all possible next review dates for a card based on different rating values
@@ -318,24 +1149,26 @@ pub async fn get_all_next_reviews_for_card(
 		})?;
 
 	// Look up the review_function for this card's item type
-	let review_function: String = items::table
-		.inner_join(item_types::table.on(item_types::id.eq(items::item_type)))
-		.filter(items::id.eq(card.get_item_id()))
-		.select(item_types::review_function)
-		.first::<String>(conn)
+	let item = get_item_scheduling(conn, &card)
 		.map_err(|e| anyhow!("Failed to look up review function: {}", e))?;
 
 	debug!(
 		"Found card, calculating next reviews for all possible ratings using {}",
-		review_function
+		item.review_function
 	);
 
-	// Calculate next review for each possible rating (1-4)
-	let mut results = Vec::with_capacity(4);
+	// Calculate next review for each possible rating
+	let Settings {
+		learning_steps: steps,
+		minimum_interval,
+		..
+	} = get_settings_with_conn(conn)?;
+	let now = Utc::now();
+	let mut results = Vec::with_capacity(Rating::ALL.len());
 
-	for rating in 1..=4 {
+	for rating in Rating::ALL {
 		debug!("Calculating next review for rating {}", rating);
-		match calculate_next_review(&card, &review_function, rating) {
+		match calculate_next_review(&card, &item, &steps, minimum_interval, rating, now) {
 			Ok((next_review, scheduler_data)) => {
 				debug!("Rating {}: next review at {}", rating, next_review);
 				results.push((next_review, scheduler_data));
@@ -363,6 +1196,430 @@ pub async fn get_all_next_reviews_for_card(
 	Ok(results)
 }
 
+/// Previews the next review date for each rating without recording anything
+///
+/// Uses [`compute_next_interval`], the same scheduling as `record_review`,
+/// so recording a rating straight after previewing it schedules the card
+/// for the previewed date (up to the time between the two calls, and the
+/// incremental queue scheduler's random jitter).
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `card_id` - The ID of the card to preview
+///
+/// ### Returns
+///
+/// A Result containing a preview for each rating 1-4, or `None` if the card
+/// does not exist
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+/// - The card's review function is unknown
+#[instrument(skip(pool), fields(card_id = %card_id))]
+pub fn preview_reviews(pool: &DbPool, card_id: &CardId) -> Result<Option<Vec<ReviewPreview>>> {
+	debug!("Previewing reviews for card");
+
+	let conn = &mut pool.get()?;
+
+	let Some(card) = cards::table.find(card_id).first::<Card>(conn).optional()? else {
+		debug!("Card not found");
+		return Ok(None);
+	};
+
+	// Look up the review_function for this card's item type
+	let item = get_item_scheduling(conn, &card)
+		.map_err(|e| anyhow!("Failed to look up review function: {}", e))?;
+
+	let Settings {
+		learning_steps: steps,
+		minimum_interval,
+		..
+	} = get_settings_with_conn(conn)?;
+	let now = Utc::now();
+	let previews = Rating::ALL
+		.into_iter()
+		.map(|rating| {
+			let (interval, _) =
+				compute_next_interval(&card, &item, &steps, minimum_interval, rating, now)?;
+			Ok(ReviewPreview {
+				rating,
+				next_review: next_review_after(now, interval)?,
+				interval_seconds: interval.num_seconds(),
+			})
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	Ok(Some(previews))
+}
+
+/// Undoes a review, restoring the card's schedule to what it would be
+/// without it
+///
+/// The review is deleted and the card's `last_review`, `next_review` and
+/// `scheduler_data` are recomputed by replaying the card's remaining
+/// reviews, oldest first, each as of its own timestamp. If no reviews
+/// remain, the card is reset to the new state: never reviewed, no scheduler
+/// data, and due when a new card would be (see [`first_review_date`]).
+/// Cram reviews are skipped in the replay, and undoing one just deletes it,
+/// since they never changed their card. Everything happens in one
+/// transaction.
+///
+/// Replay uses the card's current review function and priority, and the
+/// incremental queue scheduler's jitter is re-rolled, so for those cards
+/// the restored interval can differ slightly from the original one. The
+/// interval fuzz isn't reapplied, so the restored `next_review` is the
+/// scheduler's unfuzzed one. If the undone review was the card's latest and
+/// changed its priority (see [`adjust_priority`]), the priority it had
+/// before is restored; an older review's change is left, since later
+/// reviews built on it. Any leech action the review triggered is kept.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `review_id` - The ID of the review to undo
+///
+/// ### Returns
+///
+/// A Result containing the updated card, or `None` if no review exists with
+/// that ID
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database operations fail
+/// - The card's review function is unknown
+#[instrument(skip(pool), fields(review_id = %review_id))]
+pub async fn undo_review(pool: &DbPool, review_id: &ReviewId) -> Result<Option<Card>> {
+	debug!("Undoing review");
+
+	let conn = &mut pool.get()?;
+
+	let card = transaction_with_retry(conn, |c| {
+		let Some(review) = reviews::table
+			.find(review_id)
+			.first::<Review>(c)
+			.optional()?
+		else {
+			return Ok(None);
+		};
+
+		diesel::delete(reviews::table.find(review_id)).execute(c)?;
+
+		// A cram review never changed the card, so there's nothing to replay
+		if review.get_cram() {
+			let card = cards::table.find(review.get_card_id()).first::<Card>(c)?;
+			return Ok(Some(card));
+		}
+
+		if let Some(priority_before) = review.get_priority_before() {
+			let later_reviews = reviews::table
+				.filter(reviews::card_id.eq(review.get_card_id()))
+				.filter(reviews::cram.eq(false))
+				.filter(reviews::review_timestamp.ge(review.get_review_timestamp_raw()))
+				.count()
+				.get_result::<i64>(c)?;
+			if later_reviews == 0 {
+				diesel::update(cards::table.find(review.get_card_id()))
+					.set(cards::priority.eq(priority_before))
+					.execute(c)?;
+			}
+		}
+
+		let card = cards::table.find(review.get_card_id()).first::<Card>(c)?;
+
+		replay_card_reviews(c, card).map(Some)
+	})
+	.await?;
+
+	match &card {
+		Some(card) => info!("Undid review for card {}", card.get_id()),
+		None => debug!("Review not found"),
+	}
+
+	Ok(card)
+}
+
+/// Recomputes a card's schedule from its reviews and stores it
+///
+/// The card's `last_review`, `next_review`, `scheduler_data` and `lapses`
+/// are rebuilt by [`replay_card_history`], then stored.
+///
+/// The caller owns the transaction, so that the replay commits together with
+/// the change to the card's reviews that called for it.
+///
+/// ### Arguments
+///
+/// * `conn` - A connection inside a transaction
+/// * `card` - The card to reschedule, as currently stored
+///
+/// ### Returns
+///
+/// The rescheduled card
+///
+/// ### Errors
+///
+/// Returns an error if a database operation fails, or the card's review
+/// function fails to schedule a review (as a `QueryBuilderError`, so the
+/// transaction rolls back)
+fn replay_card_reviews(conn: &mut diesel::SqliteConnection, card: Card) -> QueryResult<Card> {
+	let card = replay_card_history(conn, card)?;
+
+	diesel::update(cards::table.find(card.get_id()))
+		.set((
+			cards::last_review.eq(card.get_last_review_raw()),
+			cards::next_review.eq(card.get_next_review_raw()),
+			cards::scheduler_data.eq(card.get_scheduler_data()),
+			cards::lapses.eq(card.get_mature_lapses()),
+		))
+		.execute(conn)?;
+
+	Ok(card)
+}
+
+/// Recomputes a card's schedule from its reviews, without storing it
+///
+/// The card's `last_review`, `next_review` and `scheduler_data` are rebuilt
+/// by starting from a never-reviewed card and replaying its non-cram
+/// reviews, oldest first, each as of its own timestamp, as `record_review`
+/// would have applied them (without the interval fuzz), counting each lapse
+/// (see [`is_lapse`]) in `lapses` as it goes. With no reviews, the card is
+/// left new and due when a new card would be (see [`first_review_date`]).
+///
+/// ### Arguments
+///
+/// * `conn` - A database connection
+/// * `card` - The card to replay, as currently stored
+///
+/// ### Returns
+///
+/// The card as its reviews leave it
+///
+/// ### Errors
+///
+/// Returns an error if a database query fails, or the card's review function
+/// fails to schedule a review (as a `QueryBuilderError`)
+fn replay_card_history(conn: &mut diesel::SqliteConnection, mut card: Card) -> QueryResult<Card> {
+	let item = get_item_scheduling(conn, &card)?;
+	let Settings {
+		timezone: tz,
+		learning_steps: steps,
+		minimum_interval,
+		..
+	} = get_settings_with_conn(conn)?;
+	let history = reviews::table
+		.filter(reviews::card_id.eq(card.get_id()))
+		.filter(reviews::cram.eq(false))
+		.order_by((reviews::review_timestamp.asc(), reviews::id.asc()))
+		.load::<Review>(conn)?;
+
+	debug!("Replaying {} reviews", history.len());
+
+	// Start from a never-reviewed card, then replay each review as
+	// `record_review` would have applied it
+	card.set_last_review(None);
+	card.set_scheduler_data(None);
+	card.set_mature_lapses(0);
+	card.set_next_review(first_review_date(
+		&item.review_function,
+		&item.item_data,
+		tz,
+		Utc::now(),
+	));
+	for past in &history {
+		let reviewed_at = past.get_review_timestamp();
+		let (next_review, scheduler_data) = calculate_next_review(
+			&card,
+			&item,
+			&steps,
+			minimum_interval,
+			past.get_rating(),
+			reviewed_at,
+		)
+		// Surface scheduler errors as a diesel error so the whole
+		// transaction rolls back
+		.map_err(|e| diesel::result::Error::QueryBuilderError(e.into()))?;
+		if is_lapse(&card, past.get_rating()) {
+			card.set_mature_lapses(card.get_mature_lapses() + 1);
+		}
+		card.set_last_review(Some(reviewed_at));
+		card.set_next_review(next_review);
+		card.set_scheduler_data(Some(scheduler_data));
+	}
+
+	Ok(card)
+}
+
+/// Recounts every card's lapses from its review history
+///
+/// For cards reviewed before lapses were counted. Each card's reviews are
+/// replayed as [`undo_review`] does, to find which failures came while it
+/// was mature, but only `lapses` is stored: the rest of the card is left as
+/// it is. Replaying uses the current settings and leaves out the interval
+/// fuzz, so a failure whose interval was close to
+/// [`MATURE_INTERVAL_DAYS`] can be counted differently than it would have
+/// been at the time.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+///
+/// ### Returns
+///
+/// A Result containing the number of cards whose lapse count changed
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database operations fail
+/// - A card's review function is unknown
+#[instrument(skip(pool))]
+pub async fn backfill_lapses(pool: &DbPool) -> Result<usize> {
+	debug!("Backfilling lapses");
+
+	let conn = &mut pool.get()?;
+
+	let updated = transaction_with_retry(conn, |c| {
+		// A card without reviews only needs correcting if it has lapses
+		let reviewed = cards::table
+			.filter(
+				cards::id
+					.eq_any(reviews::table.select(reviews::card_id))
+					.or(cards::lapses.ne(0)),
+			)
+			.load::<Card>(c)?;
+
+		let mut updated = 0;
+		for card in reviewed {
+			let stored = card.get_mature_lapses();
+			let replayed = replay_card_history(c, card)?;
+			if replayed.get_mature_lapses() != stored {
+				diesel::update(cards::table.find(replayed.get_id()))
+					.set(cards::lapses.eq(replayed.get_mature_lapses()))
+					.execute(c)?;
+				updated += 1;
+			}
+		}
+		Ok(updated)
+	})
+	.await?;
+
+	info!("Backfilled lapses for {} cards", updated);
+
+	Ok(updated)
+}
+
+/// Error returned when a log of reviews being imported names a card that
+/// doesn't exist
+///
+/// Carried inside the `anyhow::Error` returned by [`import_reviews`], so
+/// handlers can `downcast` to it and answer with a 404.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Card not found: {0}")]
+pub struct ImportedCardNotFoundError(pub CardId);
+
+/// Imports a log of past reviews, rescheduling their cards from their full
+/// history
+///
+/// Every review is stored with its own timestamp, then each card reviewed is
+/// rescheduled by replaying all of its reviews, the imported ones along with
+/// any it already had, oldest first (see [`replay_card_reviews`]). The card
+/// ends up scheduled as if each review had been recorded at its timestamp.
+/// Everything happens in one transaction, so a failed import changes
+/// nothing.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `log` - The reviews to import, as (card ID, rating on the configured
+///   rating scale, timestamp)
+///
+/// ### Returns
+///
+/// A Result containing the rescheduled cards, in the order they first appear
+/// in the log
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database operations fail
+/// - A card does not exist (an [`ImportedCardNotFoundError`])
+/// - A rating is above the configured rating scale (an
+///   `OutOfScaleRatingError`)
+/// - A card's review function fails to schedule a review
+#[instrument(skip(pool, log), fields(count = log.len()))]
+pub async fn import_reviews(
+	pool: &DbPool,
+	log: &[(CardId, Rating, DateTime<Utc>)],
+) -> Result<Vec<Card>> {
+	debug!("Importing reviews");
+
+	let conn = &mut pool.get()?;
+
+	// On the 2-button scale a pass is scheduled as Good, as in record_review
+	let scale = get_settings_with_conn(conn)?.rating_scale;
+	let new_reviews = log
+		.iter()
+		.map(|(card_id, rating, timestamp)| {
+			Ok(Review::new_with_fields(
+				ReviewId::new(),
+				card_id.clone(),
+				rating.on_scale(scale)?,
+				*timestamp,
+			))
+		})
+		.collect::<Result<Vec<Review>>>()?;
+	let mut card_ids: Vec<CardId> = Vec::new();
+	for (card_id, _, _) in log {
+		if !card_ids.contains(card_id) {
+			card_ids.push(card_id.clone());
+		}
+	}
+
+	// As in `move_card_relative`, a missing card comes back as `Ok(Err(_))`
+	// so it isn't retried or mistaken for a database failure
+	let outcome = transaction_with_retry(conn, |c| {
+		let found = cards::table
+			.filter(cards::id.eq_any(&card_ids))
+			.select(cards::id)
+			.load::<CardId>(c)?;
+		if let Some(missing) = card_ids.iter().find(|id| !found.contains(id)) {
+			return Ok(Err(anyhow::Error::from(ImportedCardNotFoundError(
+				missing.clone(),
+			))));
+		}
+
+		diesel::insert_into(reviews::table)
+			.values(&new_reviews)
+			.execute(c)?;
+
+		card_ids
+			.iter()
+			.map(|card_id| {
+				let card = cards::table.find(card_id).first::<Card>(c)?;
+				replay_card_reviews(c, card)
+			})
+			.collect::<QueryResult<Vec<Card>>>()
+			.map(Ok)
+	})
+	.await?;
+	let cards = outcome?;
+
+	info!(
+		"Imported {} reviews of {} cards",
+		new_reviews.len(),
+		cards.len()
+	);
+
+	Ok(cards)
+}
+
 /// Gets all reviews for a card
 ///
 /// ### Arguments
@@ -395,6 +1652,117 @@ pub fn get_reviews_for_card(pool: &DbPool, card_id: &CardId) -> Result<Vec<Revie
 	Ok(reviews)
 }
 
+/// Lists the reviews of every card of an item
+///
+/// Reviews are ordered newest first (ties broken by ID so pages are stable)
+/// and paginated with `limit`/`offset`.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `item_id` - The ID of the item to get reviews for
+/// * `page` - The page of reviews to return
+///
+/// ### Returns
+///
+/// A Result containing the page of the item's Reviews
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+#[instrument(skip(pool, page), fields(item_id = %item_id))]
+pub fn list_reviews_for_item(
+	pool: &DbPool,
+	item_id: &ItemId,
+	page: &ReviewsPageQueryDto,
+) -> Result<Vec<Review>> {
+	debug!("Listing reviews for item");
+
+	let conn = &mut pool.get()?;
+
+	let limit = page
+		.limit
+		.unwrap_or(DEFAULT_REVIEWS_PAGE_SIZE)
+		.min(MAX_REVIEWS_PAGE_SIZE);
+
+	let result = reviews::table
+		.inner_join(cards::table)
+		.filter(cards::item_id.eq(item_id))
+		.select(Review::as_select())
+		.order_by((reviews::review_timestamp.desc(), reviews::id.desc()))
+		.limit(limit as i64)
+		.offset(page.offset as i64)
+		.load::<Review>(conn)?;
+
+	info!("Retrieved {} reviews for item {}", result.len(), item_id);
+
+	Ok(result)
+}
+
+/// Lists reviews across all cards matching the given filters
+///
+/// Reviews are ordered newest first (ties broken by ID so pages are stable)
+/// and paginated with `limit`/`offset`.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `query` - The filters and page to return; `from` is inclusive and `to` exclusive
+///
+/// ### Returns
+///
+/// A Result containing the page of matching Reviews
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+#[instrument(skip(pool, query))]
+pub fn list_reviews_with_filters(
+	pool: &DbPool,
+	query: &ListReviewsQueryDto,
+) -> Result<Vec<Review>> {
+	debug!("Listing reviews with filters");
+
+	let conn = &mut pool.get()?;
+
+	let mut reviews_query = reviews::table.into_boxed();
+
+	if let Some(from) = query.from {
+		reviews_query = reviews_query.filter(reviews::review_timestamp.ge(from.naive_utc()));
+	}
+
+	if let Some(to) = query.to {
+		reviews_query = reviews_query.filter(reviews::review_timestamp.lt(to.naive_utc()));
+	}
+
+	if let Some(rating) = query.rating {
+		reviews_query = reviews_query.filter(reviews::rating.eq(rating));
+	}
+
+	if let Some(ref card_id) = query.card_id {
+		reviews_query = reviews_query.filter(reviews::card_id.eq(card_id));
+	}
+
+	let limit = query
+		.limit
+		.unwrap_or(DEFAULT_REVIEWS_PAGE_SIZE)
+		.min(MAX_REVIEWS_PAGE_SIZE);
+
+	let result = reviews_query
+		.order_by((reviews::review_timestamp.desc(), reviews::id.desc()))
+		.limit(limit as i64)
+		.offset(query.offset as i64)
+		.load::<Review>(conn)?;
+
+	info!("Retrieved {} reviews", result.len());
+
+	Ok(result)
+}
+
 /// Migrates SM-2 scheduler data to FSRS format (none -> fsrs-0)
 ///
 /// Converts all cards with SM-2-format `scheduler_data` (containing
@@ -473,6 +1841,9 @@ fn migrate_scheduler_data_fsrs_0_fsrs_1(conn: &mut diesel::SqliteConnection) ->
 	diesel::update(item_types::table.filter(item_types::id.eq_any(&iq_type_ids)))
 		.set(item_types::review_function.eq("incremental_queue"))
 		.execute(conn)?;
+	for id in &iq_type_ids {
+		item_type_cache().invalidate(id);
+	}
 
 	info!(
 		"Set review_function to 'incremental_queue' for {} item types",
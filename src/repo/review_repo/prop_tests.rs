@@ -7,7 +7,7 @@ use proptest::prelude::*;
 use serde_json::json;
 
 // ============================================================================
-// T1: calculate_next_review Pure-Logic Properties
+// T1: compute_next_fsrs_interval Pure-Logic Properties
 // ============================================================================
 
 proptest! {
@@ -43,7 +43,9 @@ proptest! {
 		rating in arb_rating(),
 	) {
 		let card = card_with_fsrs_data(stability, difficulty);
-		let (next_review, _) = calculate_next_fsrs_review(&card, rating).unwrap();
+		let now = Utc::now();
+		let (interval, _) = compute_next_fsrs_interval(&card, rating, &SchedulerConfig::default(), now).unwrap();
+		let next_review = now + interval;
 		let threshold = Utc::now() - Duration::hours(2);
 		prop_assert!(
 			next_review > threshold,
@@ -52,19 +54,34 @@ proptest! {
 		);
 	}
 
-	/// T1.3: Scheduler data has exactly stability and difficulty keys
+	/// T1.3: Scheduler data has exactly stability and difficulty keys, plus
+	/// `graduated` once the card passes, and the lapse count when a graduated
+	/// card is failed
 	#[test]
 	fn prop_t1_3_scheduler_data_keys(
 		stability in arb_stability(),
 		difficulty in arb_difficulty(),
+		graduated in any::<bool>(),
 		rating in arb_rating(),
 	) {
-		let card = card_with_fsrs_data(stability, difficulty);
-		let (_, scheduler_data) = calculate_next_fsrs_review(&card, rating).unwrap();
+		let mut card = card_with_fsrs_data(stability, difficulty);
+		if graduated {
+			card.set_scheduler_data(Some(JsonValue(json!({
+				"stability": stability,
+				"difficulty": difficulty,
+				"graduated": true,
+			}))));
+		}
+		let (_, scheduler_data) = compute_next_fsrs_interval(&card, rating, &SchedulerConfig::default(), Utc::now()).unwrap();
 		let obj = scheduler_data.0.as_object().unwrap();
-		prop_assert_eq!(obj.len(), 2, "Should have exactly 2 keys, got: {:?}", obj.keys().collect::<Vec<_>>());
+		let now_graduated = graduated || rating != Rating::Again;
+		let lapsed = graduated && rating == Rating::Again;
+		let expected_keys = 2 + usize::from(now_graduated) + usize::from(lapsed);
+		prop_assert_eq!(obj.len(), expected_keys, "Should have exactly {} keys, got: {:?}", expected_keys, obj.keys().collect::<Vec<_>>());
 		prop_assert!(obj.contains_key("stability"), "Missing stability key");
 		prop_assert!(obj.contains_key("difficulty"), "Missing difficulty key");
+		prop_assert_eq!(obj.contains_key("graduated"), now_graduated);
+		prop_assert_eq!(obj.contains_key("lapses"), lapsed);
 	}
 
 	/// T1.4: Stability is always positive
@@ -75,7 +92,7 @@ proptest! {
 		rating in arb_rating(),
 	) {
 		let card = card_with_fsrs_data(stability, difficulty);
-		let (_, scheduler_data) = calculate_next_fsrs_review(&card, rating).unwrap();
+		let (_, scheduler_data) = compute_next_fsrs_interval(&card, rating, &SchedulerConfig::default(), Utc::now()).unwrap();
 		let s = scheduler_data.0["stability"].as_f64().unwrap();
 		prop_assert!(s > 0.0, "Stability should be positive, got {}", s);
 	}
@@ -88,22 +105,16 @@ proptest! {
 		rating in arb_rating(),
 	) {
 		let card = card_with_fsrs_data(stability, difficulty);
-		let (_, scheduler_data) = calculate_next_fsrs_review(&card, rating).unwrap();
+		let (_, scheduler_data) = compute_next_fsrs_interval(&card, rating, &SchedulerConfig::default(), Utc::now()).unwrap();
 		let d = scheduler_data.0["difficulty"].as_f64().unwrap();
 		prop_assert!(d > 0.0, "Difficulty should be positive, got {}", d);
 	}
 
-	/// T1.6: Invalid rating returns Err
+	/// T1.6: Invalid ratings can't be constructed, so never reach scheduling
 	#[test]
-	fn prop_t1_6_invalid_rating_returns_err(
-		stability in arb_stability(),
-		difficulty in arb_difficulty(),
-		rating in arb_invalid_rating(),
-	) {
-		let card = card_with_fsrs_data(stability, difficulty);
-		let result = calculate_next_fsrs_review(&card, rating);
-		prop_assert!(result.is_err(),
-			"calculate_next_review should return Err for rating {}", rating);
+	fn prop_t1_6_invalid_rating_rejected(rating in arb_invalid_rating()) {
+		prop_assert!(Rating::try_from(rating).is_err(),
+			"Rating::try_from should return Err for rating {}", rating);
 	}
 
 	/// T1.7: Fresh card (no scheduler_data, no last_review) succeeds for all valid ratings
@@ -120,14 +131,14 @@ proptest! {
 			None,
 		);
 
-		let result = calculate_next_fsrs_review(&card, rating);
+		let result = compute_next_fsrs_interval(&card, rating, &SchedulerConfig::default(), Utc::now());
 		prop_assert!(result.is_ok(),
 			"Fresh card should succeed for rating {}, got: {:?}", rating, result.err());
 	}
 }
 
 // ============================================================================
-// T1r: calculate_next_review Robustness
+// T1r: compute_next_fsrs_interval Robustness
 // ============================================================================
 
 proptest! {
@@ -148,15 +159,17 @@ proptest! {
 			None,
 		);
 		// May return Err but must not panic
-		let _ = calculate_next_fsrs_review(&card, rating);
+		let _ = compute_next_fsrs_interval(&card, rating, &SchedulerConfig::default(), Utc::now());
 	}
 
 	/// T1r.2: Does not panic for any i32 rating with valid card
 	#[test]
 	fn prop_t1r_2_any_rating_no_panic(rating in any::<i32>()) {
 		let card = card_with_fsrs_data(10.0, 5.0);
-		// May return Err but must not panic
-		let _ = calculate_next_fsrs_review(&card, rating);
+		// Out-of-range ratings are rejected on conversion; the rest must not panic
+		if let Ok(rating) = Rating::try_from(rating) {
+			let _ = compute_next_fsrs_interval(&card, rating, &SchedulerConfig::default(), Utc::now());
+		}
 	}
 }
 
@@ -281,7 +294,7 @@ proptest! {
 	/// T2.6: Card's next_review moves forward after review with rating >= 2
 	#[test]
 	fn prop_t2_6_next_review_moves_forward(
-		rating in 2i32..=4i32,
+		rating in arb_rating().prop_filter("rating >= Hard", |r| *r >= Rating::Hard),
 		params in arb_setup_card_params(),
 	) {
 		let rt = tokio::runtime::Runtime::new().unwrap();
@@ -303,40 +316,6 @@ proptest! {
 		});
 	}
 
-	/// T2.7: Invalid rating returns Err without side effects
-	#[test]
-	fn prop_t2_7_invalid_rating_no_side_effects(
-		rating in arb_invalid_rating(),
-		params in arb_setup_card_params(),
-	) {
-		let rt = tokio::runtime::Runtime::new().unwrap();
-		rt.block_on(async {
-			let pool = setup_test_db();
-			let tc = setup_card(&pool, params).await;
-
-			let before_reviews = get_reviews_for_card(&pool, &tc.card.get_id()).unwrap().len();
-			let before_card = crate::schema::cards::table
-				.find(tc.card.get_id())
-				.first::<Card>(&mut pool.get().unwrap())
-				.unwrap();
-
-			let result = record_review(&pool, &tc.card.get_id(), rating).await;
-			assert!(result.is_err(), "Should fail for rating {}", rating);
-
-			let after_reviews = get_reviews_for_card(&pool, &tc.card.get_id()).unwrap().len();
-			let after_card = crate::schema::cards::table
-				.find(tc.card.get_id())
-				.first::<Card>(&mut pool.get().unwrap())
-				.unwrap();
-
-			assert_eq!(before_reviews, after_reviews, "Review count should not change");
-			assert_eq!(before_card.get_scheduler_data(), after_card.get_scheduler_data(),
-				"scheduler_data should not change");
-			assert_eq!(before_card.get_last_review(), after_card.get_last_review(),
-				"last_review should not change");
-		});
-	}
-
 	/// T2.8: Nonexistent card_id returns Err
 	#[test]
 	fn prop_t2_8_nonexistent_card_returns_err(
@@ -369,7 +348,7 @@ proptest! {
 			let tc = setup_card(&pool, params).await;
 
 			// Optionally review first to give the card some state
-			if rating > 1 {
+			if rating > Rating::Again {
 				record_review(&pool, &tc.card.get_id(), rating).await.unwrap();
 			}
 
@@ -389,7 +368,7 @@ proptest! {
 			let pool = setup_test_db();
 			let tc = setup_card(&pool, params).await;
 
-			if rating > 1 {
+			if rating > Rating::Again {
 				record_review(&pool, &tc.card.get_id(), rating).await.unwrap();
 			}
 
@@ -446,7 +425,7 @@ proptest! {
 			let review_fn = params.review_function.clone();
 			let tc = setup_card(&pool, params).await;
 
-			if rating > 1 {
+			if rating > Rating::Again {
 				record_review(&pool, &tc.card.get_id(), rating).await.unwrap();
 			}
 
@@ -489,7 +468,7 @@ proptest! {
 			let tc = setup_card(&pool, params).await;
 
 			for _ in 0..num_reviews {
-				record_review(&pool, &tc.card.get_id(), 3).await.unwrap();
+				record_review(&pool, &tc.card.get_id(), Rating::Good).await.unwrap();
 				std::thread::sleep(std::time::Duration::from_millis(10));
 			}
 
@@ -539,7 +518,7 @@ proptest! {
 }
 
 // ============================================================================
-// IQ1: calculate_next_incremental_queue_review Properties
+// IQ1: compute_next_incremental_queue_interval Properties
 // ============================================================================
 
 proptest! {
@@ -550,7 +529,7 @@ proptest! {
 		priority in crate::test_utils::arb_priority(),
 	) {
 		let card = super::tests::card_with_iq_data(interval, priority);
-		let (_, scheduler_data) = calculate_next_incremental_queue_review(&card, 1).unwrap();
+		let (_, scheduler_data) = compute_next_incremental_queue_interval(&card, Rating::Again, &SchedulerConfig::default()).unwrap();
 		let new_interval = scheduler_data.0["interval"].as_f64().unwrap();
 		prop_assert!(
 			(new_interval - 1.0).abs() < f64::EPSILON,
@@ -566,7 +545,9 @@ proptest! {
 		rating in arb_rating(),
 	) {
 		let card = super::tests::card_with_iq_data(interval, priority);
-		let (next_review, _) = calculate_next_incremental_queue_review(&card, rating).unwrap();
+		let now = Utc::now();
+		let (interval, _) = compute_next_incremental_queue_interval(&card, rating, &SchedulerConfig::default()).unwrap();
+		let next_review = now + interval;
 		let threshold = Utc::now() - Duration::hours(2);
 		prop_assert!(
 			next_review > threshold,
@@ -582,7 +563,7 @@ proptest! {
 		rating in arb_rating(),
 	) {
 		let card = super::tests::card_with_iq_data(interval, priority);
-		let (_, scheduler_data) = calculate_next_incremental_queue_review(&card, rating).unwrap();
+		let (_, scheduler_data) = compute_next_incremental_queue_interval(&card, rating, &SchedulerConfig::default()).unwrap();
 		let obj = scheduler_data.0.as_object().unwrap();
 		prop_assert!(obj.contains_key("interval"), "Missing 'interval' key in {:?}", obj);
 	}
@@ -595,15 +576,15 @@ proptest! {
 	) {
 		let card = super::tests::card_with_iq_data(interval, priority);
 
-		let (_, data2) = calculate_next_incremental_queue_review(&card, 2).unwrap();
+		let (_, data2) = compute_next_incremental_queue_interval(&card, Rating::Hard, &SchedulerConfig::default()).unwrap();
 		let int2 = data2.0["interval"].as_f64().unwrap();
 		prop_assert!(int2 >= 2.0, "Rating 2 interval should be >= 2.0, got {}", int2);
 
-		let (_, data3) = calculate_next_incremental_queue_review(&card, 3).unwrap();
+		let (_, data3) = compute_next_incremental_queue_interval(&card, Rating::Good, &SchedulerConfig::default()).unwrap();
 		let int3 = data3.0["interval"].as_f64().unwrap();
 		prop_assert!(int3 >= 4.0, "Rating 3 interval should be >= 4.0, got {}", int3);
 
-		let (_, data4) = calculate_next_incremental_queue_review(&card, 4).unwrap();
+		let (_, data4) = compute_next_incremental_queue_interval(&card, Rating::Easy, &SchedulerConfig::default()).unwrap();
 		let int4 = data4.0["interval"].as_f64().unwrap();
 		prop_assert!(int4 >= 7.0, "Rating 4 interval should be >= 7.0, got {}", int4);
 	}
@@ -617,7 +598,7 @@ proptest! {
 	#[test]
 	fn prop_iq1_5_priority_affects_growth(
 		interval in 10.0f64..=100.0f64,
-		rating in 2i32..=4i32,
+		rating in arb_rating().prop_filter("rating >= Hard", |r| *r >= Rating::Hard),
 	) {
 		let high_priority_card = super::tests::card_with_iq_data(interval, 1.0);
 		let low_priority_card = super::tests::card_with_iq_data(interval, 0.0);
@@ -627,8 +608,8 @@ proptest! {
 		let mut high_sum = 0.0;
 		let mut low_sum = 0.0;
 		for _ in 0..samples {
-			let (_, high_data) = calculate_next_incremental_queue_review(&high_priority_card, rating).unwrap();
-			let (_, low_data) = calculate_next_incremental_queue_review(&low_priority_card, rating).unwrap();
+			let (_, high_data) = compute_next_incremental_queue_interval(&high_priority_card, rating, &SchedulerConfig::default()).unwrap();
+			let (_, low_data) = compute_next_incremental_queue_interval(&low_priority_card, rating, &SchedulerConfig::default()).unwrap();
 			high_sum += high_data.0["interval"].as_f64().unwrap();
 			low_sum += low_data.0["interval"].as_f64().unwrap();
 		}
@@ -642,17 +623,6 @@ proptest! {
 		);
 	}
 
-	/// IQ1.6: Ratings outside 1-4 fail
-	#[test]
-	fn prop_iq1_6_invalid_rating_returns_err(
-		rating in crate::test_utils::arb_invalid_rating(),
-	) {
-		let card = super::tests::card_with_iq_data(10.0, 0.5);
-		let result = calculate_next_incremental_queue_review(&card, rating);
-		prop_assert!(result.is_err(),
-			"Should fail for rating {}", rating);
-	}
-
 	/// IQ1.7: Full DB roundtrip: create item type with "incremental_queue", review, verify card updated
 	#[test]
 	fn prop_iq1_7_record_review_iq_db_roundtrip(rating in arb_rating()) {
@@ -1,7 +1,7 @@
 use super::*;
-use crate::models::ItemId;
+use crate::models::{ItemId, MAX_MULTIPLIER};
 use crate::repo::tests::setup_test_db;
-use crate::repo::{create_item, create_item_type};
+use crate::repo::{create_item, create_item_type, update_settings};
 use serde_json::json;
 
 #[tokio::test]
@@ -30,7 +30,7 @@ async fn test_record_review() {
 		.unwrap();
 
 	// Test recording a review
-	let rating = 2;
+	let rating = Rating::Hard;
 	let review = record_review(&pool, &card.get_id(), rating).await.unwrap();
 
 	assert_eq!(review.get_card_id(), card.get_id());
@@ -77,12 +77,16 @@ async fn test_get_reviews_for_card() {
 		.unwrap();
 
 	// Record some reviews
-	let review1 = record_review(&pool, &card.get_id(), 2).await.unwrap();
+	let review1 = record_review(&pool, &card.get_id(), Rating::Hard)
+		.await
+		.unwrap();
 
 	// We need to wait a moment to ensure the timestamps are different
 	std::thread::sleep(std::time::Duration::from_millis(10));
 
-	let review2 = record_review(&pool, &card.get_id(), 3).await.unwrap();
+	let review2 = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
 
 	// Get reviews for the card
 	let reviews = get_reviews_for_card(&pool, &card.get_id()).unwrap();
@@ -118,34 +122,17 @@ async fn test_record_review_edge_cases() {
 		.first::<Card>(&mut pool.get().unwrap())
 		.unwrap();
 
-	// Try an invalid rating
-	let result = record_review(&pool, &card.get_id(), 0).await;
-	assert!(result.is_err());
-	assert!(
-		result
-			.unwrap_err()
-			.to_string()
-			.contains("Rating must be between 1 and 4")
-	);
-
-	let result = record_review(&pool, &card.get_id(), 5).await;
-	assert!(result.is_err());
-	assert!(
-		result
-			.unwrap_err()
-			.to_string()
-			.contains("Rating must be between 1 and 4")
-	);
-
 	// Try a non-existent card
-	let result = record_review(&pool, &CardId("nonexistent-id".to_string()), 2).await;
+	let result = record_review(&pool, &CardId("nonexistent-id".to_string()), Rating::Hard).await;
 	assert!(result.is_err());
 	assert!(result.unwrap_err().to_string().contains("Card not found"));
 
 	// Test different ratings affect the scheduler data correctly
 
 	// First, record a review with rating 1 (again)
-	let _review1 = record_review(&pool, &card.get_id(), 1).await.unwrap();
+	let _review1 = record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
 	let card1 = crate::schema::cards::table
 		.find(card.get_id())
 		.first::<Card>(&mut pool.get().unwrap())
@@ -177,7 +164,9 @@ async fn test_record_review_edge_cases() {
 		.unwrap();
 
 	// Record a review with rating 4 (easy)
-	let _review2 = record_review(&pool, &card2.get_id(), 4).await.unwrap();
+	let _review2 = record_review(&pool, &card2.get_id(), Rating::Easy)
+		.await
+		.unwrap();
 	let card2_updated = crate::schema::cards::table
 		.find(card2.get_id())
 		.first::<Card>(&mut pool.get().unwrap())
@@ -209,11 +198,17 @@ async fn test_record_review_edge_cases() {
 		.unwrap();
 
 	// Do a series of "good" reviews
-	record_review(&pool, &card3.get_id(), 3).await.unwrap();
+	record_review(&pool, &card3.get_id(), Rating::Good)
+		.await
+		.unwrap();
 	std::thread::sleep(std::time::Duration::from_millis(10));
-	record_review(&pool, &card3.get_id(), 3).await.unwrap();
+	record_review(&pool, &card3.get_id(), Rating::Good)
+		.await
+		.unwrap();
 	std::thread::sleep(std::time::Duration::from_millis(10));
-	record_review(&pool, &card3.get_id(), 3).await.unwrap();
+	record_review(&pool, &card3.get_id(), Rating::Good)
+		.await
+		.unwrap();
 
 	let card3_updated = crate::schema::cards::table
 		.find(card3.get_id())
@@ -231,6 +226,16 @@ async fn test_record_review_edge_cases() {
 	);
 }
 
+/// Scheduling inputs for an item with no data, of a type with the given
+/// review function and the default scheduler config
+fn scheduling(review_function: &str) -> ItemScheduling {
+	ItemScheduling {
+		review_function: review_function.to_string(),
+		item_data: json!({}),
+		scheduler_config: SchedulerConfig::default(),
+	}
+}
+
 /// Helper: build a Card with FSRS scheduler data for pure-logic tests
 pub(super) fn card_with_fsrs_data(stability: f32, difficulty: f32) -> Card {
 	Card::new_with_fields(
@@ -248,11 +253,11 @@ pub(super) fn card_with_fsrs_data(stability: f32, difficulty: f32) -> Card {
 	)
 }
 
-/// Extract the interval in days from calculate_next_review's next_review datetime
-pub(super) fn interval_days_for(card: &Card, rating: i32) -> f64 {
-	let (next_review, _) = calculate_next_fsrs_review(card, rating).unwrap();
-	let diff = next_review - Utc::now();
-	diff.num_hours() as f64 / 24.0
+/// Extract the interval in days from compute_next_fsrs_interval
+pub(super) fn interval_days_for(card: &Card, rating: Rating) -> f64 {
+	let (interval, _) =
+		compute_next_fsrs_interval(card, rating, &SchedulerConfig::default(), Utc::now()).unwrap();
+	interval.num_hours() as f64 / 24.0
 }
 
 #[test]
@@ -269,7 +274,7 @@ fn test_intervals_monotonic_fresh_card() {
 		None,
 	);
 
-	let intervals: Vec<f64> = (1..=4).map(|r| interval_days_for(&card, r)).collect();
+	let intervals: Vec<f64> = Rating::ALL.map(|r| interval_days_for(&card, r)).to_vec();
 	for i in 0..3 {
 		assert!(
 			intervals[i] < intervals[i + 1],
@@ -295,7 +300,7 @@ fn test_intervals_monotonic_various_states() {
 
 	for (stability, difficulty) in cases {
 		let card = card_with_fsrs_data(stability, difficulty);
-		let intervals: Vec<f64> = (1..=4).map(|r| interval_days_for(&card, r)).collect();
+		let intervals: Vec<f64> = Rating::ALL.map(|r| interval_days_for(&card, r)).to_vec();
 
 		for i in 0..3 {
 			assert!(
@@ -390,8 +395,8 @@ mod proptests {
 		fn intervals_monotonic_for_any_card_state(
 			stability in 5.0f32..=365.0,
 			difficulty in 1.0f32..=10.0,
-			r1 in 1i32..=4i32,
-			r2 in 1i32..=4i32,
+			r1 in crate::test_utils::arb_rating(),
+			r2 in crate::test_utils::arb_rating(),
 		) {
 			prop_assume!(r1 != r2);
 			let (lo, hi) = if r1 < r2 { (r1, r2) } else { (r2, r1) };
@@ -459,9 +464,11 @@ async fn test_record_review_incremental_queue() {
 		.unwrap();
 
 	// Record a review
-	let review = record_review(&pool, &card.get_id(), 3).await.unwrap();
+	let review = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
 	assert_eq!(review.get_card_id(), card.get_id());
-	assert_eq!(review.get_rating(), 3);
+	assert_eq!(review.get_rating(), Rating::Good);
 
 	// Verify the card was updated with incremental queue scheduler data
 	let updated_card = crate::schema::cards::table
@@ -483,7 +490,9 @@ async fn test_record_review_incremental_queue() {
 #[test]
 fn test_incremental_queue_rating_1_resets() {
 	let card = card_with_iq_data(30.0, 0.5);
-	let (_, scheduler_data) = calculate_next_incremental_queue_review(&card, 1).unwrap();
+	let (_, scheduler_data) =
+		compute_next_incremental_queue_interval(&card, Rating::Again, &SchedulerConfig::default())
+			.unwrap();
 	let interval = scheduler_data.0["interval"].as_f64().unwrap();
 	assert!(
 		(interval - 1.0).abs() < f64::EPSILON,
@@ -497,7 +506,9 @@ fn test_incremental_queue_intervals_bounded() {
 	let card = card_with_iq_data(1.0, 0.5);
 
 	// Rating 2: min 2 days
-	let (_, data2) = calculate_next_incremental_queue_review(&card, 2).unwrap();
+	let (_, data2) =
+		compute_next_incremental_queue_interval(&card, Rating::Hard, &SchedulerConfig::default())
+			.unwrap();
 	let interval2 = data2.0["interval"].as_f64().unwrap();
 	assert!(
 		interval2 >= 2.0,
@@ -506,7 +517,9 @@ fn test_incremental_queue_intervals_bounded() {
 	);
 
 	// Rating 3: min 4 days
-	let (_, data3) = calculate_next_incremental_queue_review(&card, 3).unwrap();
+	let (_, data3) =
+		compute_next_incremental_queue_interval(&card, Rating::Good, &SchedulerConfig::default())
+			.unwrap();
 	let interval3 = data3.0["interval"].as_f64().unwrap();
 	assert!(
 		interval3 >= 4.0,
@@ -515,7 +528,9 @@ fn test_incremental_queue_intervals_bounded() {
 	);
 
 	// Rating 4: min 7 days
-	let (_, data4) = calculate_next_incremental_queue_review(&card, 4).unwrap();
+	let (_, data4) =
+		compute_next_incremental_queue_interval(&card, Rating::Easy, &SchedulerConfig::default())
+			.unwrap();
 	let interval4 = data4.0["interval"].as_f64().unwrap();
 	assert!(
 		interval4 >= 7.0,
@@ -524,6 +539,141 @@ fn test_incremental_queue_intervals_bounded() {
 	);
 }
 
+#[test]
+fn test_incremental_queue_uses_custom_multipliers() {
+	let card = card_with_iq_data(3.0, 0.5);
+	let config = SchedulerConfig {
+		growth_at_max_priority: 2.0,
+		growth_at_min_priority: 2.0,
+		hard_factor: 0.5,
+		easy_factor: 3.0,
+		jitter: 0.0,
+		..SchedulerConfig::default()
+	};
+
+	let expected = [
+		(Rating::Hard, 3.0),
+		(Rating::Good, 6.0),
+		(Rating::Easy, 18.0),
+	];
+	for (rating, days) in expected {
+		let (interval, data) =
+			compute_next_incremental_queue_interval(&card, rating, &config).unwrap();
+		assert_eq!(data.0["interval"].as_f64().unwrap(), days);
+		assert_eq!(
+			interval,
+			Duration::days(days as i64) - Duration::hours(1),
+			"rating {}",
+			rating
+		);
+	}
+}
+
+#[test]
+fn test_incremental_queue_interval_is_capped() {
+	// A long interval stored before the cap, grown by the largest multipliers
+	let card = card_with_iq_data(1e300, 0.0);
+	let config = SchedulerConfig {
+		growth_at_min_priority: MAX_MULTIPLIER,
+		easy_factor: MAX_MULTIPLIER,
+		..SchedulerConfig::default()
+	};
+
+	for rating in [Rating::Hard, Rating::Good, Rating::Easy] {
+		let (interval, data) =
+			compute_next_incremental_queue_interval(&card, rating, &config).unwrap();
+		assert_eq!(
+			data.0["interval"].as_f64().unwrap(),
+			MAX_INCREMENTAL_QUEUE_INTERVAL_DAYS
+		);
+		assert_eq!(
+			interval,
+			Duration::days(MAX_INCREMENTAL_QUEUE_INTERVAL_DAYS as i64) - Duration::hours(1)
+		);
+	}
+}
+
+#[test]
+fn test_fsrs_uses_custom_desired_retention() {
+	let card = card_with_fsrs_data(10.0, 5.0);
+	let interval_at = |desired_retention| {
+		let config = SchedulerConfig {
+			desired_retention,
+			..SchedulerConfig::default()
+		};
+		compute_next_fsrs_interval(&card, Rating::Good, &config, Utc::now())
+			.unwrap()
+			.0
+	};
+
+	// Remembering more of the cards takes reviewing them sooner
+	assert!(interval_at(0.95) < interval_at(0.9));
+	assert!(interval_at(0.9) < interval_at(0.8));
+	assert_eq!(
+		interval_at(0.9),
+		compute_next_fsrs_interval(&card, Rating::Good, &SchedulerConfig::default(), Utc::now())
+			.unwrap()
+			.0
+	);
+}
+
+#[tokio::test]
+async fn test_record_review_uses_item_type_scheduler_config() {
+	let pool = setup_test_db();
+	update_settings(&pool, |s| s.interval_fuzz = 0.0)
+		.await
+		.unwrap();
+
+	let item_type = create_item_type(
+		&pool,
+		"IQ Test Type".to_string(),
+		"incremental_queue".to_string(),
+	)
+	.await
+	.unwrap();
+	crate::repo::update_item_type_scheduler_config(
+		&pool,
+		&item_type.get_id(),
+		Some(json!({
+			"growth_at_max_priority": 10.0,
+			"growth_at_min_priority": 10.0,
+			"jitter": 0.0,
+		})),
+	)
+	.await
+	.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"IQ Item".to_string(),
+		json!({"content": "Some content"}),
+	)
+	.await
+	.unwrap();
+	let card = crate::schema::cards::table
+		.filter(crate::schema::cards::item_id.eq(item.get_id()))
+		.first::<Card>(&mut pool.get().unwrap())
+		.unwrap();
+
+	let review = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+
+	let updated_card = crate::schema::cards::table
+		.find(card.get_id())
+		.first::<Card>(&mut pool.get().unwrap())
+		.unwrap();
+	// A new card starts at a 1 day interval, which grows tenfold
+	assert_eq!(
+		updated_card.get_scheduler_data().unwrap().0["interval"],
+		json!(10.0)
+	);
+	assert_eq!(
+		updated_card.get_next_review() - review.get_review_timestamp(),
+		Duration::days(10) - Duration::hours(1)
+	);
+}
+
 // ============================================================================
 // Edge-case / error-path tests
 // ============================================================================
@@ -560,7 +710,7 @@ async fn test_record_review_review_function_lookup_fails() {
 	let pool = setup_test_db();
 	let card_id = insert_orphaned_card(&pool);
 
-	let result = record_review(&pool, &card_id, 3).await;
+	let result = record_review(&pool, &card_id, Rating::Good).await;
 	assert!(result.is_err());
 	assert!(
 		result
@@ -575,7 +725,14 @@ async fn test_record_review_review_function_lookup_fails() {
 #[test]
 fn test_calculate_next_review_unknown_function() {
 	let card = card_with_fsrs_data(5.0, 3.0);
-	let result = calculate_next_review(&card, "unknown_function", 3);
+	let result = calculate_next_review(
+		&card,
+		&scheduling("unknown_function"),
+		&LearningSteps::default(),
+		Duration::zero(),
+		Rating::Good,
+		Utc::now(),
+	);
 	assert!(result.is_err());
 	assert!(
 		result
@@ -938,3 +1095,1911 @@ async fn test_migrate_fsrs_0_fsrs_1_with_iq_types() {
 		.unwrap();
 	assert_eq!(marker, "fsrs-1");
 }
+
+// ============================================================================
+// undo_review tests
+// ============================================================================
+
+/// Helper: create an fsrs item and return its first card
+async fn create_fsrs_card(pool: &crate::db::DbPool) -> Card {
+	let item_type = create_item_type(pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+
+	crate::schema::cards::table
+		.filter(crate::schema::cards::item_id.eq(item.get_id()))
+		.first::<Card>(&mut pool.get().unwrap())
+		.unwrap()
+}
+
+/// Helper: load a card straight from the database
+fn load_card(pool: &crate::db::DbPool, card_id: &CardId) -> Card {
+	crate::schema::cards::table
+		.find(card_id)
+		.first::<Card>(&mut pool.get().unwrap())
+		.unwrap()
+}
+
+#[tokio::test]
+async fn test_undo_last_review_restores_previous_schedule() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+
+	record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let after_first = load_card(&pool, &card.get_id());
+
+	let second = record_review(&pool, &card.get_id(), Rating::Easy)
+		.await
+		.unwrap();
+	assert_ne!(
+		load_card(&pool, &card.get_id()).get_scheduler_data(),
+		after_first.get_scheduler_data()
+	);
+
+	let undone = undo_review(&pool, &second.get_id()).await.unwrap().unwrap();
+	let stored = load_card(&pool, &card.get_id());
+
+	// The schedule matches having only done the first review
+	for restored in [&undone, &stored] {
+		assert_eq!(
+			restored.get_last_review_raw(),
+			after_first.get_last_review_raw()
+		);
+		assert_eq!(
+			restored.get_next_review_raw(),
+			after_first.get_next_review_raw()
+		);
+		assert_eq!(
+			restored.get_scheduler_data(),
+			after_first.get_scheduler_data()
+		);
+	}
+
+	// And the undone review is gone
+	let remaining = get_reviews_for_card(&pool, &card.get_id()).unwrap();
+	assert_eq!(remaining.len(), 1);
+	assert_ne!(remaining[0].get_id(), second.get_id());
+}
+
+#[tokio::test]
+async fn test_undo_earlier_review_replays_later_one() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+
+	let first = record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	let second = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+
+	let undone = undo_review(&pool, &first.get_id()).await.unwrap().unwrap();
+
+	// The second review is now applied to a never-reviewed card
+	let (expected_next, expected_data) = calculate_next_review(
+		&card,
+		&scheduling("fsrs"),
+		&LearningSteps::default(),
+		Duration::zero(),
+		Rating::Good,
+		second.get_review_timestamp(),
+	)
+	.unwrap();
+	assert_eq!(
+		undone.get_last_review(),
+		Some(second.get_review_timestamp())
+	);
+	assert_eq!(undone.get_next_review(), expected_next);
+	assert_eq!(undone.get_scheduler_data(), Some(expected_data));
+}
+
+#[tokio::test]
+async fn test_undo_only_review_resets_to_new() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+
+	let review = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let before_undo = Utc::now();
+	undo_review(&pool, &review.get_id()).await.unwrap();
+
+	let stored = load_card(&pool, &card.get_id());
+	assert!(stored.get_last_review().is_none());
+	assert!(stored.get_scheduler_data().is_none());
+	assert!(stored.get_next_review() >= before_undo);
+	assert!(stored.get_next_review() <= Utc::now());
+}
+
+#[tokio::test]
+async fn test_import_reviews_matches_sequential_recording() {
+	let pool = setup_test_db();
+	let recorded = create_fsrs_card(&pool).await;
+	let item_type_id = crate::repo::get_item(&pool, &recorded.get_item_id())
+		.unwrap()
+		.unwrap()
+		.get_item_type();
+	let item = create_item(
+		&pool,
+		&item_type_id,
+		"Imported Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let imported = crate::repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone();
+
+	// Record three reviews of one card as they happen
+	let mut log = Vec::new();
+	for rating in [Rating::Again, Rating::Good, Rating::Easy] {
+		let review = record_review(&pool, &recorded.get_id(), rating)
+			.await
+			.unwrap();
+		log.push((imported.get_id(), rating, review.get_review_timestamp()));
+	}
+
+	// and import the same history for the other
+	let cards = import_reviews(&pool, &log).await.unwrap();
+	assert_eq!(cards.len(), 1);
+
+	let expected = load_card(&pool, &recorded.get_id());
+	let stored = load_card(&pool, &imported.get_id());
+	for actual in [&cards[0], &stored] {
+		assert_eq!(actual.get_id(), imported.get_id());
+		assert_eq!(actual.get_last_review_raw(), expected.get_last_review_raw());
+		assert_eq!(actual.get_next_review_raw(), expected.get_next_review_raw());
+		assert_eq!(actual.get_scheduler_data(), expected.get_scheduler_data());
+	}
+
+	// The reviews are stored with their own timestamps
+	let mut reviews = get_reviews_for_card(&pool, &imported.get_id()).unwrap();
+	reviews.sort_by_key(|review| review.get_review_timestamp());
+	let stored_log: Vec<_> = reviews
+		.iter()
+		.map(|review| {
+			(
+				review.get_card_id(),
+				review.get_rating(),
+				review.get_review_timestamp(),
+			)
+		})
+		.collect();
+	assert_eq!(stored_log, log);
+}
+
+#[tokio::test]
+async fn test_import_reviews_with_unknown_card_imports_nothing() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+	let now = Utc::now();
+
+	let log = vec![
+		(card.get_id(), Rating::Good, now - Duration::days(2)),
+		(
+			CardId("nonexistent".to_string()),
+			Rating::Good,
+			now - Duration::days(1),
+		),
+	];
+	let result = import_reviews(&pool, &log).await;
+
+	assert_eq!(
+		result.unwrap_err().downcast_ref::<ImportedCardNotFoundError>(),
+		Some(&ImportedCardNotFoundError(CardId("nonexistent".to_string())))
+	);
+	assert!(
+		get_reviews_for_card(&pool, &card.get_id())
+			.unwrap()
+			.is_empty()
+	);
+	let stored = load_card(&pool, &card.get_id());
+	assert!(stored.get_last_review().is_none());
+}
+
+#[tokio::test]
+async fn test_undo_nonexistent_review() {
+	let pool = setup_test_db();
+
+	let result = undo_review(&pool, &ReviewId("nonexistent".to_string()))
+		.await
+		.unwrap();
+
+	assert!(result.is_none());
+}
+
+// ============================================================================
+// preview_reviews tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_preview_matches_recorded_review() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+	record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+
+	let previews = preview_reviews(&pool, &card.get_id()).unwrap().unwrap();
+	assert_eq!(
+		previews.iter().map(|p| p.rating).collect::<Vec<_>>(),
+		Rating::ALL.to_vec()
+	);
+
+	// Previewing records nothing
+	assert_eq!(
+		get_reviews_for_card(&pool, &card.get_id()).unwrap().len(),
+		1
+	);
+
+	record_review(&pool, &card.get_id(), Rating::Easy)
+		.await
+		.unwrap();
+	let scheduled = load_card(&pool, &card.get_id()).get_next_review();
+
+	// Identical scheduling, offset only by the time between the two calls
+	let drift = (scheduled - previews[3].next_review).abs();
+	assert!(
+		drift < Duration::seconds(5),
+		"preview {} should match scheduled {}",
+		previews[3].next_review,
+		scheduled
+	);
+}
+
+#[tokio::test]
+async fn test_preview_intervals_increase_with_rating() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+
+	let previews = preview_reviews(&pool, &card.get_id()).unwrap().unwrap();
+
+	for pair in previews.windows(2) {
+		assert!(pair[0].interval_seconds < pair[1].interval_seconds);
+		assert!(pair[0].next_review < pair[1].next_review);
+	}
+}
+
+#[tokio::test]
+async fn test_preview_nonexistent_card() {
+	let pool = setup_test_db();
+
+	let result = preview_reviews(&pool, &CardId("nonexistent".to_string())).unwrap();
+
+	assert!(result.is_none());
+}
+
+// ============================================================================
+// list_reviews_with_filters tests
+// ============================================================================
+
+/// Inserts a review directly so its timestamp can be chosen
+fn insert_review_at(
+	pool: &crate::db::DbPool,
+	card_id: &CardId,
+	rating: Rating,
+	at: chrono::DateTime<Utc>,
+) -> Review {
+	let review = Review::new_with_fields(ReviewId::new(), card_id.clone(), rating, at);
+	diesel::insert_into(reviews::table)
+		.values(&review)
+		.execute(&mut pool.get().unwrap())
+		.unwrap();
+	review
+}
+
+/// Two cards with reviews one day apart:
+/// card A rated 1, 3, 4 on days 0, 1, 2 and card B rated 3, 1 on days 1, 3
+async fn setup_review_history(pool: &crate::db::DbPool) -> (CardId, CardId, chrono::DateTime<Utc>) {
+	// A "Test" item type gives the item two cards
+	let card_a = create_fsrs_card(pool).await;
+	let card_b = crate::repo::get_cards_for_item(pool, &card_a.get_item_id())
+		.unwrap()
+		.into_iter()
+		.find(|c| c.get_id() != card_a.get_id())
+		.unwrap()
+		.get_id();
+	let card_a = card_a.get_id();
+	let start = Utc::now() - Duration::days(10);
+	let day = |n: i64| start + Duration::days(n);
+
+	insert_review_at(pool, &card_a, Rating::Again, day(0));
+	insert_review_at(pool, &card_a, Rating::Good, day(1));
+	insert_review_at(pool, &card_a, Rating::Easy, day(2));
+	insert_review_at(pool, &card_b, Rating::Good, day(1) + Duration::hours(1));
+	insert_review_at(pool, &card_b, Rating::Again, day(3));
+
+	(card_a, card_b, start)
+}
+
+#[tokio::test]
+async fn test_list_reviews_no_filters_newest_first() {
+	let pool = setup_test_db();
+	setup_review_history(&pool).await;
+
+	let reviews = list_reviews_with_filters(&pool, &ListReviewsQueryDto::default()).unwrap();
+
+	assert_eq!(reviews.len(), 5);
+	for pair in reviews.windows(2) {
+		assert!(pair[0].get_review_timestamp() >= pair[1].get_review_timestamp());
+	}
+}
+
+#[tokio::test]
+async fn test_list_reviews_filter_by_date_range() {
+	let pool = setup_test_db();
+	let (_, _, start) = setup_review_history(&pool).await;
+
+	// `from` is inclusive and `to` exclusive
+	let query = ListReviewsQueryDto {
+		from: Some(start + Duration::days(1)),
+		to: Some(start + Duration::days(3)),
+		..Default::default()
+	};
+	let reviews = list_reviews_with_filters(&pool, &query).unwrap();
+
+	let ratings: Vec<i32> = reviews.iter().map(|r| r.get_rating().as_i32()).collect();
+	assert_eq!(ratings, vec![4, 3, 3]);
+}
+
+#[tokio::test]
+async fn test_list_reviews_filter_by_rating() {
+	let pool = setup_test_db();
+	setup_review_history(&pool).await;
+
+	let query = ListReviewsQueryDto {
+		rating: Some(1),
+		..Default::default()
+	};
+	let reviews = list_reviews_with_filters(&pool, &query).unwrap();
+
+	assert_eq!(reviews.len(), 2);
+	assert!(reviews.iter().all(|r| r.get_rating() == Rating::Again));
+}
+
+#[tokio::test]
+async fn test_list_reviews_filter_by_card() {
+	let pool = setup_test_db();
+	let (_, card_b, _) = setup_review_history(&pool).await;
+
+	let query = ListReviewsQueryDto {
+		card_id: Some(card_b.clone()),
+		..Default::default()
+	};
+	let reviews = list_reviews_with_filters(&pool, &query).unwrap();
+
+	assert_eq!(reviews.len(), 2);
+	assert!(reviews.iter().all(|r| r.get_card_id() == card_b));
+}
+
+#[tokio::test]
+async fn test_list_reviews_combined_filters() {
+	let pool = setup_test_db();
+	let (card_a, _, start) = setup_review_history(&pool).await;
+
+	// Card A's reviews from day 1 onwards rated 3: just the day 1 review
+	let query = ListReviewsQueryDto {
+		from: Some(start + Duration::days(1)),
+		rating: Some(3),
+		card_id: Some(card_a.clone()),
+		..Default::default()
+	};
+	let reviews = list_reviews_with_filters(&pool, &query).unwrap();
+
+	assert_eq!(reviews.len(), 1);
+	assert_eq!(reviews[0].get_card_id(), card_a);
+	assert_eq!(reviews[0].get_rating(), Rating::Good);
+	assert_eq!(
+		reviews[0].get_review_timestamp().timestamp(),
+		(start + Duration::days(1)).timestamp()
+	);
+}
+
+#[tokio::test]
+async fn test_list_reviews_pagination() {
+	let pool = setup_test_db();
+	setup_review_history(&pool).await;
+
+	let all = list_reviews_with_filters(&pool, &ListReviewsQueryDto::default()).unwrap();
+
+	// Walking the pages yields every review exactly once, in order
+	let mut paged = Vec::new();
+	for offset in (0..6).step_by(2) {
+		let query = ListReviewsQueryDto {
+			limit: Some(2),
+			offset,
+			..Default::default()
+		};
+		paged.extend(list_reviews_with_filters(&pool, &query).unwrap());
+	}
+	assert_eq!(paged, all);
+
+	// A page past the end is empty
+	let query = ListReviewsQueryDto {
+		offset: 5,
+		..Default::default()
+	};
+	assert!(list_reviews_with_filters(&pool, &query).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_list_reviews_for_item_covers_all_its_cards() {
+	let pool = setup_test_db();
+
+	// "Test Type" items get two cards each
+	let item_type = create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let other = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Other".to_string(),
+		json!({"front": "Bonjour", "back": "Monde"}),
+	)
+	.await
+	.unwrap();
+	let cards = crate::repo::get_cards_for_item(&pool, &item.get_id()).unwrap();
+	assert_eq!(cards.len(), 2);
+	let other_card = crate::repo::get_cards_for_item(&pool, &other.get_id())
+		.unwrap()
+		.remove(0);
+
+	let mut recorded = Vec::new();
+	for (card, rating) in [
+		(&cards[0], Rating::Good),
+		(&cards[1], Rating::Hard),
+		(&cards[0], Rating::Again),
+	] {
+		std::thread::sleep(std::time::Duration::from_millis(10));
+		recorded.push(record_review(&pool, &card.get_id(), rating).await.unwrap());
+	}
+	record_review(&pool, &other_card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+
+	// Every review of the item's cards, newest first, and no others
+	let reviews =
+		list_reviews_for_item(&pool, &item.get_id(), &ReviewsPageQueryDto::default()).unwrap();
+	recorded.reverse();
+	assert_eq!(reviews, recorded);
+
+	// Pages split the same list
+	let page = ReviewsPageQueryDto {
+		limit: Some(2),
+		offset: 1,
+	};
+	assert_eq!(
+		list_reviews_for_item(&pool, &item.get_id(), &page).unwrap(),
+		recorded[1..]
+	);
+}
+
+// ============================================================================
+// Interval fuzz tests
+// ============================================================================
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+#[test]
+fn test_fuzz_interval_stays_within_bounds() {
+	let mut rng = StdRng::seed_from_u64(42);
+	let base = Duration::days(100) - Duration::hours(1);
+
+	let mut shifts = std::collections::HashSet::new();
+	for _ in 0..200 {
+		let fuzzed = fuzz_interval(base, 0.05, &mut rng);
+		let shift = fuzzed - base;
+
+		// Moved by whole days, at most 5% of the interval either way
+		assert_eq!(shift.num_seconds() % 86_400, 0);
+		assert!(shift.num_days().abs() <= 4, "shift was {:?}", shift);
+		shifts.insert(shift.num_days());
+	}
+	assert!(shifts.len() > 1, "fuzz should actually move intervals");
+}
+
+#[test]
+fn test_fuzz_interval_is_repeatable_with_a_seed() {
+	let base = Duration::days(60) - Duration::hours(1);
+	let run = |seed| {
+		let mut rng = StdRng::seed_from_u64(seed);
+		(0..10)
+			.map(|_| fuzz_interval(base, 0.1, &mut rng))
+			.collect::<Vec<_>>()
+	};
+
+	assert_eq!(run(7), run(7));
+}
+
+#[test]
+fn test_fuzz_interval_zero_fuzz_is_unchanged() {
+	let mut rng = StdRng::seed_from_u64(1);
+	for days in [1, 10, 365] {
+		let base = Duration::days(days) - Duration::hours(1);
+		assert_eq!(fuzz_interval(base, 0.0, &mut rng), base);
+	}
+}
+
+#[test]
+fn test_fuzz_interval_short_intervals_are_unchanged() {
+	let mut rng = StdRng::seed_from_u64(3);
+	let base = Duration::days(5) - Duration::hours(1);
+	for _ in 0..50 {
+		assert_eq!(fuzz_interval(base, 0.1, &mut rng), base);
+	}
+}
+
+#[tokio::test]
+async fn test_record_review_applies_interval_fuzz() {
+	let pool = setup_test_db();
+	update_settings(&pool, |s| s.interval_fuzz = 0.5)
+		.await
+		.unwrap();
+
+	// A well-established card, so its next interval is long enough to fuzz
+	let mut card = create_fsrs_card(&pool).await;
+	card.set_last_review(Some(Utc::now() - Duration::days(30)));
+	card.set_scheduler_data(Some(JsonValue(json!({
+		"stability": 30.0,
+		"difficulty": 5.0,
+	}))));
+	diesel::update(cards::table.find(card.get_id()))
+		.set((
+			cards::last_review.eq(card.get_last_review_raw()),
+			cards::scheduler_data.eq(card.get_scheduler_data()),
+		))
+		.execute(&mut pool.get().unwrap())
+		.unwrap();
+
+	let review = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let reviewed_at = review.get_review_timestamp();
+	let (base, _) = compute_next_interval(
+		&card,
+		&scheduling("fsrs"),
+		&LearningSteps::default(),
+		Duration::zero(),
+		Rating::Good,
+		reviewed_at,
+	)
+	.unwrap();
+
+	let stored = load_card(&pool, &card.get_id());
+	let shift = stored.get_next_review() - (reviewed_at + base);
+	let max_shift = base.num_seconds() as f64 / 86_400.0 * 0.5;
+	assert_eq!(shift.num_seconds() % 86_400, 0);
+	assert!(
+		(shift.num_days().abs() as f64) <= max_shift,
+		"shift {:?} exceeds {} days",
+		shift,
+		max_shift
+	);
+}
+
+#[tokio::test]
+async fn test_review_scheduled_past_the_end_of_time_errors() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+	update_settings(&pool, |s| s.minimum_interval = Duration::days(100_000_000))
+		.await
+		.unwrap();
+
+	assert!(
+		record_review(&pool, &card.get_id(), Rating::Good)
+			.await
+			.is_err()
+	);
+	assert!(preview_reviews(&pool, &card.get_id()).is_err());
+	assert_eq!(load_card(&pool, &card.get_id()).get_last_review(), None);
+}
+
+#[test]
+fn test_difficulty_slows_incremental_queue_growth_when_weighted() {
+	let now = Utc::now();
+	let mut card = Card::new(ItemId("item".to_string()), 0, now, 0.0);
+	card.set_scheduler_data(Some(JsonValue(json!({ "interval": 10.0 }))));
+	let interval = |card: &Card, difficulty_weight| {
+		let item = ItemScheduling {
+			scheduler_config: SchedulerConfig {
+				difficulty_weight,
+				jitter: 0.0,
+				..SchedulerConfig::default()
+			},
+			..scheduling("incremental_queue")
+		};
+		compute_next_interval(
+			card,
+			&item,
+			&LearningSteps::default(),
+			Duration::zero(),
+			Rating::Good,
+			now,
+		)
+		.unwrap()
+		.0
+	};
+
+	// At priority 0 a Good review triples the interval
+	let easy = interval(&card, 1.0);
+	assert_eq!(easy, Duration::days(30) - Duration::hours(1));
+
+	// A hard card grows slower the more the item type weighs difficulty
+	card.set_difficulty(Some(1.0));
+	assert_eq!(
+		interval(&card, 0.5),
+		Duration::days(20) - Duration::hours(1)
+	);
+	assert_eq!(
+		interval(&card, 1.0),
+		Duration::days(10) - Duration::hours(1)
+	);
+
+	// and not at all differently if it doesn't
+	assert_eq!(interval(&card, 0.0), easy);
+}
+
+#[test]
+fn test_compute_next_interval_raises_graduated_intervals_to_the_floor() {
+	let card = Card::new(ItemId("item".to_string()), 0, Utc::now(), 0.5);
+	let now = Utc::now();
+	let floor = Duration::days(30);
+	let interval = |steps: &LearningSteps, minimum_interval| {
+		compute_next_interval(
+			&card,
+			&scheduling("fsrs"),
+			steps,
+			minimum_interval,
+			Rating::Good,
+			now,
+		)
+		.unwrap()
+		.0
+	};
+
+	// A new card's first interval is well under the floor, so it's raised
+	let unfloored = interval(&LearningSteps::default(), Duration::zero());
+	assert!(unfloored < floor, "got {:?}", unfloored);
+	assert_eq!(interval(&LearningSteps::default(), floor), floor);
+
+	// Learning steps aren't graduated intervals, so they can be shorter
+	let steps = LearningSteps {
+		learning: vec![Duration::minutes(10)],
+		relearning: Vec::new(),
+		graduating_interval: None,
+	};
+	assert_eq!(interval(&steps, floor), Duration::minutes(10));
+
+	// Intervals already above the floor are left alone
+	assert_eq!(
+		interval(&LearningSteps::default(), Duration::hours(1)),
+		unfloored
+	);
+}
+
+#[tokio::test]
+async fn test_record_review_keeps_fuzzed_interval_above_floor() {
+	let pool = setup_test_db();
+	update_settings(&pool, |s| s.interval_fuzz = 0.5)
+		.await
+		.unwrap();
+	update_settings(&pool, |s| s.minimum_interval = Duration::days(40))
+		.await
+		.unwrap();
+
+	// Fuzz could shrink a 40 day interval by up to 20 days, but the floor
+	// holds it at 40
+	let card = create_fsrs_card(&pool).await;
+	let review = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let stored = load_card(&pool, &card.get_id());
+	assert!(stored.get_next_review() >= review.get_review_timestamp() + Duration::days(40));
+}
+
+#[tokio::test]
+async fn test_record_review_on_four_button_scale() {
+	let pool = setup_test_db();
+
+	// Every rating is accepted and recorded as given
+	let card = create_fsrs_card(&pool).await;
+	for rating in Rating::ALL {
+		let review = record_review(&pool, &card.get_id(), rating).await.unwrap();
+		assert_eq!(review.get_rating(), rating);
+	}
+}
+
+#[tokio::test]
+async fn test_record_review_on_two_button_scale() {
+	let pool = setup_test_db();
+	update_settings(&pool, |s| s.rating_scale = 2)
+		.await
+		.unwrap();
+
+	// A pass is scheduled as Good
+	let card = create_fsrs_card(&pool).await;
+	let review = record_review(&pool, &card.get_id(), Rating::Hard)
+		.await
+		.unwrap();
+	assert_eq!(review.get_rating(), Rating::Good);
+	let (good_interval, _) = compute_next_interval(
+		&card,
+		&scheduling("fsrs"),
+		&LearningSteps::default(),
+		Duration::zero(),
+		Rating::Good,
+		review.get_review_timestamp(),
+	)
+	.unwrap();
+	assert_eq!(
+		load_card(&pool, &card.get_id()).get_next_review(),
+		review.get_review_timestamp() + good_interval
+	);
+
+	// A fail is scheduled as Again
+	let review = record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	assert_eq!(review.get_rating(), Rating::Again);
+
+	// 3 and 4 are off the scale, and nothing is recorded for them
+	for rating in [Rating::Good, Rating::Easy] {
+		let err = record_review(&pool, &card.get_id(), rating)
+			.await
+			.unwrap_err();
+		assert_eq!(
+			err.downcast_ref::<crate::models::OutOfScaleRatingError>(),
+			Some(&crate::models::OutOfScaleRatingError { rating, scale: 2 })
+		);
+	}
+	assert_eq!(
+		get_reviews_for_card(&pool, &card.get_id()).unwrap().len(),
+		2
+	);
+}
+
+#[tokio::test]
+async fn test_cram_review_is_logged_without_rescheduling() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+
+	let review = record_cram_review_in_session(&pool, &card.get_id(), Rating::Good, None, None)
+		.await
+		.unwrap();
+	assert!(review.get_cram());
+	assert_eq!(review.get_rating(), Rating::Good);
+
+	// The review is logged with its cram marker
+	let reviews = get_reviews_for_card(&pool, &card.get_id()).unwrap();
+	assert_eq!(reviews.len(), 1);
+	assert!(reviews[0].get_cram());
+
+	// The card's schedule is untouched
+	let stored = load_card(&pool, &card.get_id());
+	assert_eq!(stored.get_next_review(), card.get_next_review());
+	assert_eq!(stored.get_last_review(), card.get_last_review());
+	assert_eq!(stored.get_scheduler_data(), card.get_scheduler_data());
+
+	// A normal review still reschedules, and isn't marked
+	let review = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	assert!(!review.get_cram());
+	assert!(load_card(&pool, &card.get_id()).get_last_review().is_some());
+}
+
+#[tokio::test]
+async fn test_review_response_is_kept() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+
+	let review = record_review_in_session(
+		&pool,
+		&card.get_id(),
+		Rating::Again,
+		None,
+		Some("Sydney".to_string()),
+	)
+	.await
+	.unwrap();
+	assert_eq!(review.get_response(), Some("Sydney".to_string()));
+
+	let reviews = get_reviews_for_card(&pool, &card.get_id()).unwrap();
+	assert_eq!(reviews.len(), 1);
+	assert_eq!(reviews[0].get_response(), Some("Sydney".to_string()));
+
+	// Cram reviews keep theirs too, and a review without one has none
+	record_cram_review_in_session(
+		&pool,
+		&card.get_id(),
+		Rating::Good,
+		None,
+		Some("Canberra".to_string()),
+	)
+	.await
+	.unwrap();
+	record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let mut responses: Vec<Option<String>> = get_reviews_for_card(&pool, &card.get_id())
+		.unwrap()
+		.iter()
+		.map(Review::get_response)
+		.collect();
+	responses.sort();
+	assert_eq!(
+		responses,
+		vec![
+			None,
+			Some("Canberra".to_string()),
+			Some("Sydney".to_string())
+		]
+	);
+}
+
+#[tokio::test]
+async fn test_undo_review_ignores_cram_reviews() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+
+	let real = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let after_real = load_card(&pool, &card.get_id());
+	let cram = record_cram_review_in_session(&pool, &card.get_id(), Rating::Again, None, None)
+		.await
+		.unwrap();
+
+	// Undoing the cram review leaves the schedule from the real review
+	let undone = undo_review(&pool, &cram.get_id()).await.unwrap().unwrap();
+	assert_eq!(undone.get_next_review(), after_real.get_next_review());
+	assert_eq!(
+		get_reviews_for_card(&pool, &card.get_id()).unwrap().len(),
+		1
+	);
+
+	// A cram review isn't replayed when undoing a later real review
+	let cram = record_cram_review_in_session(&pool, &card.get_id(), Rating::Again, None, None)
+		.await
+		.unwrap();
+	let second = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let restored = undo_review(&pool, &second.get_id()).await.unwrap().unwrap();
+	assert_eq!(
+		restored.get_last_review(),
+		Some(real.get_review_timestamp())
+	);
+	let remaining: Vec<ReviewId> = get_reviews_for_card(&pool, &card.get_id())
+		.unwrap()
+		.iter()
+		.map(|r| r.get_id())
+		.collect();
+	assert!(remaining.contains(&cram.get_id()));
+}
+
+// ============================================================================
+// todo scheduler tests
+// ============================================================================
+
+/// Helper: create a todo due on `due_date` and return its card
+async fn create_todo_card(pool: &crate::db::DbPool, due_date: &str) -> Card {
+	let item_type = create_item_type(pool, "Todo".to_string(), "todo".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		pool,
+		&item_type.get_id(),
+		"File taxes".to_string(),
+		json!({ "due_date": due_date }),
+	)
+	.await
+	.unwrap();
+
+	crate::repo::get_cards_for_item(pool, &item.get_id())
+		.unwrap()
+		.remove(0)
+}
+
+#[tokio::test]
+async fn test_todo_with_due_date_is_queued_on_that_date() {
+	let pool = setup_test_db();
+	let tz: Tz = "Australia/Melbourne".parse().unwrap();
+	update_settings(&pool, |s| s.timezone = tz).await.unwrap();
+
+	let due = crate::time_utils::local_today(Utc::now(), tz) + chrono::Days::new(3);
+	let card = create_todo_card(&pool, &due.format("%Y-%m-%d").to_string()).await;
+	let start_of_due_day = crate::time_utils::start_of_local_day(due, tz);
+	assert_eq!(card.get_next_review(), start_of_due_day);
+
+	// Not in the queue the day before, nor at the very start of the due day
+	// (the cutoff is exclusive), but in it during that day
+	let before_due_day = start_of_due_day - Duration::hours(12);
+	assert_eq!(count_cards_due_before(&pool, before_due_day).await, 0);
+	assert_eq!(count_cards_due_before(&pool, start_of_due_day).await, 0);
+	let during_due_day = start_of_due_day + Duration::hours(1);
+	assert_eq!(count_cards_due_before(&pool, during_due_day).await, 1);
+}
+
+/// Helper: count the cards the queue holds as of `cutoff`
+async fn count_cards_due_before(pool: &crate::db::DbPool, cutoff: DateTime<Utc>) -> usize {
+	let query = crate::GetQueryDto {
+		next_review_before: Some(cutoff),
+		..Default::default()
+	};
+	crate::repo::list_cards(pool, &query).await.unwrap().len()
+}
+
+#[tokio::test]
+async fn test_snoozing_todo_pushes_due_date() {
+	let pool = setup_test_db();
+	let due = Utc::now() + Duration::days(10);
+	let card = create_todo_card(&pool, &due.to_rfc3339()).await;
+
+	// Snoozing before the due date pushes the due date itself
+	record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let snoozed = load_card(&pool, &card.get_id());
+	assert_eq!(
+		snoozed.get_next_review(),
+		card.get_next_review() + Duration::days(4)
+	);
+	assert_eq!(
+		snoozed.get_scheduler_data().unwrap().0,
+		json!({"snoozes": 1})
+	);
+
+	record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	let snoozed_again = load_card(&pool, &card.get_id());
+	assert_eq!(
+		snoozed_again.get_next_review(),
+		card.get_next_review() + Duration::days(5)
+	);
+	assert_eq!(
+		snoozed_again.get_scheduler_data().unwrap().0,
+		json!({"snoozes": 2})
+	);
+}
+
+#[tokio::test]
+async fn test_snoozing_overdue_todo_counts_from_now() {
+	let pool = setup_test_db();
+	let card = create_todo_card(&pool, "2020-01-01").await;
+
+	let review = record_review(&pool, &card.get_id(), Rating::Hard)
+		.await
+		.unwrap();
+	assert_eq!(
+		load_card(&pool, &card.get_id()).get_next_review(),
+		review.get_review_timestamp() + Duration::days(2)
+	);
+}
+
+#[tokio::test]
+async fn test_snoozing_todo_is_not_fuzzed() {
+	let pool = setup_test_db();
+	update_settings(&pool, |s| s.interval_fuzz = 0.5)
+		.await
+		.unwrap();
+	let due = Utc::now() + Duration::days(60);
+	let card = create_todo_card(&pool, &due.to_rfc3339()).await;
+
+	record_review(&pool, &card.get_id(), Rating::Easy)
+		.await
+		.unwrap();
+	assert_eq!(
+		load_card(&pool, &card.get_id()).get_next_review(),
+		card.get_next_review() + Duration::days(7)
+	);
+}
+
+#[tokio::test]
+async fn test_undoing_only_todo_review_restores_due_date() {
+	let pool = setup_test_db();
+	let card = create_todo_card(&pool, "2099-06-01").await;
+
+	let review = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let undone = undo_review(&pool, &review.get_id()).await.unwrap().unwrap();
+	assert_eq!(undone.get_next_review(), card.get_next_review());
+	assert!(undone.get_scheduler_data().is_none());
+}
+
+#[tokio::test]
+async fn test_changing_todo_due_date_reschedules_it() {
+	let pool = setup_test_db();
+	let card = create_todo_card(&pool, "2099-06-01").await;
+
+	crate::repo::update_item(
+		&pool,
+		&card.get_item_id(),
+		None,
+		Some(json!({ "due_date": "2099-07-01" })),
+	)
+	.await
+	.unwrap();
+	assert_eq!(
+		load_card(&pool, &card.get_id()).get_next_review(),
+		"2099-07-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+	);
+}
+
+#[tokio::test]
+async fn test_todo_without_due_date_is_due_now() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "Todo".to_string(), "todo".to_string())
+		.await
+		.unwrap();
+	let before = Utc::now();
+	let item = create_item(&pool, &item_type.get_id(), "Someday".to_string(), json!({}))
+		.await
+		.unwrap();
+
+	let card = crate::repo::get_cards_for_item(&pool, &item.get_id())
+		.unwrap()
+		.remove(0);
+	assert!(card.get_next_review() >= before);
+	assert!(card.get_next_review() <= Utc::now());
+}
+
+// ============================================================================
+// recurring todo scheduler tests
+// ============================================================================
+
+/// Helper: create a recurring todo repeating `every` and return its card
+async fn create_recurring_todo_card(pool: &crate::db::DbPool, every: &str) -> Card {
+	let item_type = create_item_type(pool, "RecurringTodo".to_string(), "recurring".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		pool,
+		&item_type.get_id(),
+		"Water the plants".to_string(),
+		json!({ "every": every }),
+	)
+	.await
+	.unwrap();
+
+	crate::repo::get_cards_for_item(pool, &item.get_id())
+		.unwrap()
+		.remove(0)
+}
+
+#[tokio::test]
+async fn test_reviewing_daily_recurring_todo_reschedules_it_a_day_out() {
+	let pool = setup_test_db();
+	update_settings(&pool, |s| s.interval_fuzz = 0.5)
+		.await
+		.unwrap();
+	let card = create_recurring_todo_card(&pool, "1d").await;
+	assert!(card.get_next_review() <= Utc::now());
+
+	for (completions, rating) in [(1, Rating::Good), (2, Rating::Again)] {
+		let review = record_review(&pool, &card.get_id(), rating).await.unwrap();
+		let rescheduled = load_card(&pool, &card.get_id());
+		assert_eq!(
+			rescheduled.get_next_review(),
+			review.get_review_timestamp() + Duration::days(1)
+		);
+		assert!(rescheduled.get_suspended().is_none());
+		assert_eq!(
+			rescheduled.get_scheduler_data().unwrap().0,
+			json!({ "completions": completions })
+		);
+	}
+}
+
+#[tokio::test]
+async fn test_reviewing_monthly_recurring_todo_reschedules_it_a_month_out() {
+	let pool = setup_test_db();
+	let card = create_recurring_todo_card(&pool, "1mo").await;
+
+	let review = record_review(&pool, &card.get_id(), Rating::Easy)
+		.await
+		.unwrap();
+	let reviewed_at = review.get_review_timestamp();
+	assert_eq!(
+		load_card(&pool, &card.get_id()).get_next_review(),
+		reviewed_at + chrono::Months::new(1)
+	);
+}
+
+#[tokio::test]
+async fn test_recurring_todo_with_huge_recurrence_is_rejected() {
+	let pool = setup_test_db();
+	let item_type = create_item_type(&pool, "RecurringTodo".to_string(), "recurring".to_string())
+		.await
+		.unwrap();
+
+	let result = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Far too rarely".to_string(),
+		json!({ "every": "4294967295d" }),
+	)
+	.await;
+
+	assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_reviewing_recurring_todo_with_stored_huge_recurrence_errors() {
+	let pool = setup_test_db();
+	let card = create_recurring_todo_card(&pool, "1d").await;
+	// Data stored before recurrences were bounded
+	diesel::sql_query(format!(
+		"UPDATE items SET item_data = '{{\"every\":\"4294967295d\"}}' WHERE id = '{}'",
+		card.get_item_id()
+	))
+	.execute(&mut pool.get().unwrap())
+	.unwrap();
+
+	let result = record_review(&pool, &card.get_id(), Rating::Good).await;
+
+	assert!(result.is_err());
+	assert_eq!(
+		load_card(&pool, &card.get_id()).get_next_review(),
+		card.get_next_review()
+	);
+}
+
+#[tokio::test]
+async fn test_previewing_recurring_todo_matches_its_recurrence() {
+	let pool = setup_test_db();
+	let card = create_recurring_todo_card(&pool, "2w").await;
+
+	let previews = preview_reviews(&pool, &card.get_id()).unwrap().unwrap();
+	for preview in previews {
+		assert_eq!(preview.interval_seconds, Duration::weeks(2).num_seconds());
+	}
+}
+
+// ============================================================================
+// learning steps tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_new_card_walks_through_learning_steps_to_graduation() {
+	let pool = setup_test_db();
+	let steps = [Duration::minutes(1), Duration::minutes(10)];
+	update_settings(&pool, |s| s.learning_steps.learning = steps.to_vec())
+		.await
+		.unwrap();
+	let card = create_fsrs_card(&pool).await;
+
+	for (step, wait) in steps.iter().enumerate() {
+		let review = record_review(&pool, &card.get_id(), Rating::Good)
+			.await
+			.unwrap();
+		let learning = load_card(&pool, &card.get_id());
+		assert_eq!(
+			learning.get_next_review(),
+			review.get_review_timestamp() + *wait
+		);
+		assert_eq!(
+			learning.get_scheduler_data().unwrap().0,
+			json!({ "learning_step": step })
+		);
+	}
+
+	// Passing the last step graduates the card to FSRS
+	let review = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let graduated = load_card(&pool, &card.get_id());
+	let data = graduated.get_scheduler_data().unwrap().0;
+	assert!(data.get("learning_step").is_none());
+	assert!(data.get("stability").is_some());
+	assert!(graduated.get_next_review() > review.get_review_timestamp() + Duration::hours(12));
+
+	// And it stays graduated, even after a lapse
+	record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	let lapsed = load_card(&pool, &card.get_id());
+	assert!(
+		lapsed
+			.get_scheduler_data()
+			.unwrap()
+			.0
+			.get("stability")
+			.is_some()
+	);
+}
+
+#[tokio::test]
+async fn test_failing_a_learning_step_resets_to_the_first() {
+	let pool = setup_test_db();
+	let steps = [Duration::minutes(1), Duration::minutes(10)];
+	update_settings(&pool, |s| s.learning_steps.learning = steps.to_vec())
+		.await
+		.unwrap();
+	let card = create_fsrs_card(&pool).await;
+
+	record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let review = record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+
+	let reset = load_card(&pool, &card.get_id());
+	assert_eq!(
+		reset.get_scheduler_data().unwrap().0,
+		json!({ "learning_step": 0 })
+	);
+	assert_eq!(
+		reset.get_next_review(),
+		review.get_review_timestamp() + Duration::minutes(1)
+	);
+}
+
+#[tokio::test]
+async fn test_previews_and_undo_follow_learning_steps() {
+	let pool = setup_test_db();
+	update_settings(&pool, |s| {
+		s.learning_steps.learning = vec![Duration::minutes(1), Duration::minutes(10)]
+	})
+	.await
+	.unwrap();
+	let card = create_fsrs_card(&pool).await;
+
+	let first = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let previews = preview_reviews(&pool, &card.get_id()).unwrap().unwrap();
+	let intervals: Vec<i64> = previews.iter().map(|p| p.interval_seconds).collect();
+	assert_eq!(intervals, vec![60, 600, 600, 600]);
+
+	let second = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let undone = undo_review(&pool, &second.get_id()).await.unwrap().unwrap();
+	assert_eq!(
+		undone.get_scheduler_data().unwrap().0,
+		json!({ "learning_step": 0 })
+	);
+	assert_eq!(
+		undone.get_next_review(),
+		first.get_review_timestamp() + Duration::minutes(1)
+	);
+}
+
+#[tokio::test]
+async fn test_new_card_reviewed_good_is_due_after_graduating_interval() {
+	let pool = setup_test_db();
+	update_settings(&pool, |s| {
+		s.learning_steps.graduating_interval = Some(Duration::days(3))
+	})
+	.await
+	.unwrap();
+	let card = create_fsrs_card(&pool).await;
+
+	let first = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let graduated = load_card(&pool, &card.get_id());
+	assert_eq!(
+		graduated.get_next_review(),
+		first.get_review_timestamp() + Duration::days(3)
+	);
+	// It still has its FSRS memory state to go on from
+	assert!(
+		graduated
+			.get_scheduler_data()
+			.unwrap()
+			.0
+			.get("stability")
+			.is_some()
+	);
+
+	// Later reviews are left to FSRS
+	let second = record_review(&pool, &graduated.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let (fsrs_interval, _) = compute_next_interval(
+		&graduated,
+		&scheduling("fsrs"),
+		&LearningSteps::default(),
+		Duration::zero(),
+		Rating::Good,
+		second.get_review_timestamp(),
+	)
+	.unwrap();
+	assert_eq!(
+		load_card(&pool, &card.get_id()).get_next_review(),
+		second.get_review_timestamp() + fsrs_interval
+	);
+}
+
+#[test]
+fn test_graduating_interval_applies_when_a_new_card_graduates() {
+	let now = Utc::now();
+	let steps = LearningSteps {
+		learning: vec![Duration::minutes(10)],
+		relearning: Vec::new(),
+		graduating_interval: Some(Duration::days(2)),
+	};
+	let review = |card: &Card, review_function, rating| {
+		compute_next_interval(
+			card,
+			&scheduling(review_function),
+			&steps,
+			Duration::zero(),
+			rating,
+			now,
+		)
+		.unwrap()
+	};
+
+	// A new card goes through its learning step first
+	let mut card = Card::new(ItemId("item".to_string()), 0, now, 0.5);
+	let (interval, data) = review(&card, "fsrs", Rating::Good);
+	assert_eq!(interval, Duration::minutes(10));
+
+	// then graduates with the graduating interval, unless it fails the step
+	card.set_scheduler_data(Some(data));
+	let (interval, _) = review(&card, "fsrs", Rating::Again);
+	assert_eq!(interval, Duration::minutes(10));
+	let (interval, _) = review(&card, "fsrs", Rating::Easy);
+	assert_eq!(interval, Duration::days(2));
+
+	// A new incremental queue card graduates with it too, and grows from it
+	let card = Card::new(ItemId("item".to_string()), 0, now, 0.5);
+	let (interval, data) = review(&card, "incremental_queue", Rating::Good);
+	assert_eq!(interval, Duration::days(2));
+	assert_eq!(data.0["interval"], 2.0);
+}
+
+// ============================================================================
+// relearning steps tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_lapsed_card_relearns_then_returns_to_a_reduced_interval() {
+	let pool = setup_test_db();
+	update_settings(&pool, |s| {
+		s.learning_steps.relearning = vec![Duration::minutes(10)]
+	})
+	.await
+	.unwrap();
+	let card = create_fsrs_card(&pool).await;
+
+	let learned = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let mature = load_card(&pool, &card.get_id());
+	let mature_interval = mature.get_next_review() - learned.get_review_timestamp();
+	assert_eq!(mature.get_lapses(), 0);
+
+	// Failing the card counts a lapse and starts relearning
+	let lapse = record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	let relearning = load_card(&pool, &card.get_id());
+	let data = relearning.get_scheduler_data().unwrap().0;
+	assert_eq!(relearning.get_lapses(), 1);
+	assert_eq!(data["relearning_step"], json!(0));
+	assert_eq!(
+		relearning.get_next_review(),
+		lapse.get_review_timestamp() + Duration::minutes(10)
+	);
+	let reduced_interval = Duration::seconds(data["lapse_interval_seconds"].as_i64().unwrap());
+	assert!(reduced_interval < mature_interval);
+
+	// Passing the relearning step restores the reduced interval
+	let passed = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	let relearned = load_card(&pool, &card.get_id());
+	let data = relearned.get_scheduler_data().unwrap().0;
+	assert!(data.get("relearning_step").is_none());
+	assert!(data.get("lapse_interval_seconds").is_none());
+	assert!(data.get("stability").is_some());
+	assert_eq!(relearned.get_lapses(), 1);
+	assert_eq!(
+		relearned.get_next_review(),
+		passed.get_review_timestamp() + reduced_interval
+	);
+}
+
+#[tokio::test]
+async fn test_lapses_are_counted_without_relearning_steps() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+
+	// Failing a card that has never passed isn't a lapse, however often
+	for _ in 0..2 {
+		record_review(&pool, &card.get_id(), Rating::Again)
+			.await
+			.unwrap();
+		assert_eq!(load_card(&pool, &card.get_id()).get_lapses(), 0);
+	}
+
+	// Once it has passed, failing it is
+	record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	let lapsed = load_card(&pool, &card.get_id());
+	assert_eq!(lapsed.get_lapses(), 1);
+	assert!(
+		lapsed
+			.get_scheduler_data()
+			.unwrap()
+			.0
+			.get("relearning_step")
+			.is_none()
+	);
+}
+
+#[tokio::test]
+async fn test_failing_card_that_never_passed_does_not_start_relearning() {
+	let pool = setup_test_db();
+	update_settings(&pool, |s| {
+		s.learning_steps.relearning = vec![Duration::minutes(10)]
+	})
+	.await
+	.unwrap();
+	let card = create_fsrs_card(&pool).await;
+
+	for _ in 0..2 {
+		record_review(&pool, &card.get_id(), Rating::Again)
+			.await
+			.unwrap();
+	}
+
+	let failed = load_card(&pool, &card.get_id());
+	let data = failed.get_scheduler_data().unwrap().0;
+	assert!(data.get("relearning_step").is_none());
+	assert_eq!(failed.get_lapses(), 0);
+}
+
+/// Regression for the proptest seed `stability = 0.01, difficulty = 1.0,
+/// rating = Again` (T1.3): failing a card with FSRS state that has never
+/// passed gives exactly stability and difficulty, with no lapse counted
+#[test]
+fn test_failing_low_stability_card_that_never_passed_keeps_two_keys() {
+	let card = card_with_fsrs_data(0.01, 1.0);
+
+	let (_, scheduler_data) =
+		compute_next_fsrs_interval(&card, Rating::Again, &SchedulerConfig::default(), Utc::now())
+			.unwrap();
+
+	let obj = scheduler_data.0.as_object().unwrap();
+	assert_eq!(obj.len(), 2, "got: {:?}", obj.keys().collect::<Vec<_>>());
+	assert!(obj.contains_key("stability"));
+	assert!(obj.contains_key("difficulty"));
+	assert!(obj["stability"].as_f64().unwrap() > 0.0);
+	assert!(obj["difficulty"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn test_adjust_priority() {
+	let adjustments = PriorityAdjustments {
+		fail_boost: 0.3,
+		easy_decay: 0.1,
+	};
+
+	assert_eq!(adjust_priority(0.5, adjustments, Rating::Again), 0.8);
+	assert_eq!(adjust_priority(0.5, adjustments, Rating::Hard), 0.5);
+	assert_eq!(adjust_priority(0.5, adjustments, Rating::Good), 0.5);
+	assert_eq!(adjust_priority(0.5, adjustments, Rating::Easy), 0.4);
+	// Priorities stay between 0 and 1
+	assert_eq!(adjust_priority(0.9, adjustments, Rating::Again), 1.0);
+	assert_eq!(adjust_priority(0.05, adjustments, Rating::Easy), 0.0);
+	// Unconfigured, nothing moves
+	for rating in [Rating::Again, Rating::Hard, Rating::Good, Rating::Easy] {
+		assert_eq!(
+			adjust_priority(0.5, PriorityAdjustments::default(), rating),
+			0.5
+		);
+	}
+}
+
+#[tokio::test]
+async fn test_repeated_failures_raise_priority() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+	let before = card.get_priority();
+
+	// Without a boost configured, failing a card leaves its priority alone
+	record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	assert_eq!(load_card(&pool, &card.get_id()).get_priority(), before);
+
+	update_settings(&pool, |s| {
+		s.priority_adjustments = PriorityAdjustments {
+			fail_boost: 0.1,
+			easy_decay: 0.05,
+		}
+	})
+	.await
+	.unwrap();
+	for _ in 0..3 {
+		record_review(&pool, &card.get_id(), Rating::Again)
+			.await
+			.unwrap();
+	}
+	let boosted = load_card(&pool, &card.get_id()).get_priority();
+	assert!(
+		(boosted - (before + 0.3)).abs() < 1e-6,
+		"expected {} after three failures, got {}",
+		before + 0.3,
+		boosted
+	);
+
+	// Easy ratings bring it back down, and cram reviews don't touch it
+	record_review(&pool, &card.get_id(), Rating::Easy)
+		.await
+		.unwrap();
+	let decayed = load_card(&pool, &card.get_id()).get_priority();
+	assert!((decayed - (boosted - 0.05)).abs() < 1e-6);
+	record_cram_review_in_session(&pool, &card.get_id(), Rating::Again, None, None)
+		.await
+		.unwrap();
+	assert_eq!(load_card(&pool, &card.get_id()).get_priority(), decayed);
+}
+
+#[tokio::test]
+async fn test_undo_review_restores_priority() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+	let before = card.get_priority();
+	update_settings(&pool, |s| {
+		s.priority_adjustments = PriorityAdjustments {
+			fail_boost: 0.2,
+			easy_decay: 0.0,
+		}
+	})
+	.await
+	.unwrap();
+
+	let first = record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	let second = record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	let boosted = load_card(&pool, &card.get_id()).get_priority();
+
+	// Undoing an earlier review leaves the priority later ones built on
+	undo_review(&pool, &first.get_id()).await.unwrap();
+	assert_eq!(load_card(&pool, &card.get_id()).get_priority(), boosted);
+
+	// Undoing the latest puts back the priority it had before
+	undo_review(&pool, &second.get_id()).await.unwrap();
+	let restored = load_card(&pool, &card.get_id()).get_priority();
+	assert!((restored - (before + 0.2)).abs() < 1e-6);
+
+	// A review that didn't change the priority doesn't restore anything
+	let good = record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	assert_eq!(good.get_priority_before(), None);
+	crate::repo::update_card_priority(&pool, &card.get_id(), 0.1, None)
+		.await
+		.unwrap();
+	undo_review(&pool, &good.get_id()).await.unwrap();
+	assert_eq!(load_card(&pool, &card.get_id()).get_priority(), 0.1);
+}
+
+#[tokio::test]
+async fn test_failed_review_leaves_the_priority_alone() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+	update_settings(&pool, |s| {
+		s.priority_adjustments = PriorityAdjustments {
+			fail_boost: 0.2,
+			easy_decay: 0.0,
+		}
+	})
+	.await
+	.unwrap();
+	let item_type = crate::repo::get_item(&pool, &card.get_item_id())
+		.unwrap()
+		.unwrap()
+		.get_item_type();
+	diesel::update(crate::schema::item_types::table.find(&item_type))
+		.set(crate::schema::item_types::review_function.eq("unknown_function"))
+		.execute(&mut pool.get().unwrap())
+		.unwrap();
+
+	// The card can't be scheduled, so neither the boost nor a review
+	// claiming it was made is written
+	assert!(
+		record_review(&pool, &card.get_id(), Rating::Again)
+			.await
+			.is_err()
+	);
+	assert_eq!(
+		load_card(&pool, &card.get_id()).get_priority(),
+		card.get_priority()
+	);
+	assert!(
+		get_reviews_for_card(&pool, &card.get_id())
+			.unwrap()
+			.is_empty()
+	);
+}
+
+/// Helper: fails a card until one more failure would make it a leech
+async fn fail_until_almost_leech(pool: &crate::db::DbPool, card_id: &CardId) {
+	for _ in 1..DEFAULT_LEECH_THRESHOLD {
+		record_review(pool, card_id, Rating::Again).await.unwrap();
+	}
+}
+
+#[tokio::test]
+async fn test_leech_action_suspend() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+	update_settings(&pool, |s| s.leech_action = LeechAction::Suspend)
+		.await
+		.unwrap();
+
+	fail_until_almost_leech(&pool, &card.get_id()).await;
+	assert!(load_card(&pool, &card.get_id()).get_suspended().is_none());
+
+	record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	assert!(load_card(&pool, &card.get_id()).get_suspended().is_some());
+}
+
+#[tokio::test]
+async fn test_cram_reviews_dont_make_a_leech() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+	update_settings(&pool, |s| s.leech_action = LeechAction::Suspend)
+		.await
+		.unwrap();
+
+	// Cram failures neither trigger the action nor count towards it
+	fail_until_almost_leech(&pool, &card.get_id()).await;
+	for _ in 0..2 {
+		record_cram_review_in_session(&pool, &card.get_id(), Rating::Again, None, None)
+			.await
+			.unwrap();
+	}
+	assert!(load_card(&pool, &card.get_id()).get_suspended().is_none());
+
+	record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	assert!(load_card(&pool, &card.get_id()).get_suspended().is_some());
+}
+
+#[tokio::test]
+async fn test_review_that_cant_be_scheduled_writes_nothing() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+	update_settings(&pool, |s| s.leech_action = LeechAction::Suspend)
+		.await
+		.unwrap();
+	fail_until_almost_leech(&pool, &card.get_id()).await;
+	let before = load_card(&pool, &card.get_id());
+
+	// An item type whose review function has since gone can't be scheduled
+	let item_type = crate::repo::get_item(&pool, &card.get_item_id())
+		.unwrap()
+		.unwrap()
+		.get_item_type();
+	diesel::update(crate::schema::item_types::table.find(&item_type))
+		.set(crate::schema::item_types::review_function.eq("unknown_function"))
+		.execute(&mut pool.get().unwrap())
+		.unwrap();
+
+	// So the review that would have made it a leech is rejected outright
+	assert!(
+		record_review(&pool, &card.get_id(), Rating::Again)
+			.await
+			.is_err()
+	);
+	assert_eq!(
+		get_reviews_for_card(&pool, &card.get_id()).unwrap().len(),
+		DEFAULT_LEECH_THRESHOLD as usize - 1
+	);
+	assert_eq!(load_card(&pool, &card.get_id()), before);
+}
+
+#[tokio::test]
+async fn test_leech_action_tag() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+	update_settings(&pool, |s| {
+		s.leech_action = LeechAction::Tag("leech".to_string())
+	})
+	.await
+	.unwrap();
+
+	fail_until_almost_leech(&pool, &card.get_id()).await;
+	assert!(
+		crate::repo::list_tags_for_item(&pool, &card.get_item_id())
+			.unwrap()
+			.is_empty()
+	);
+
+	record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	let tags = crate::repo::list_tags_for_item(&pool, &card.get_item_id()).unwrap();
+	assert_eq!(tags.len(), 1);
+	assert_eq!(tags[0].get_name(), "leech");
+	assert!(load_card(&pool, &card.get_id()).get_suspended().is_none());
+
+	// A second leech reuses the tag rather than creating another
+	let item_type = crate::repo::get_item(&pool, &card.get_item_id())
+		.unwrap()
+		.unwrap()
+		.get_item_type();
+	let item = create_item(
+		&pool,
+		&item_type,
+		"Other Item".to_string(),
+		json!({"front": "Goodbye", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let other = crate::repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone();
+	fail_until_almost_leech(&pool, &other.get_id()).await;
+	record_review(&pool, &other.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	assert_eq!(
+		crate::repo::list_tags_for_item(&pool, &item.get_id()).unwrap(),
+		tags
+	);
+	assert_eq!(crate::repo::list_tags(&pool).unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_leech_action_none() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+	update_settings(&pool, |s| s.leech_action = LeechAction::None)
+		.await
+		.unwrap();
+
+	fail_until_almost_leech(&pool, &card.get_id()).await;
+	record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+
+	assert!(load_card(&pool, &card.get_id()).get_suspended().is_none());
+	assert!(
+		crate::repo::list_tags_for_item(&pool, &card.get_item_id())
+			.unwrap()
+			.is_empty()
+	);
+	assert!(crate::repo::list_tags(&pool).unwrap().is_empty());
+}
+
+// ============================================================================
+// lapses tests
+// ============================================================================
+
+/// Helper: passes an FSRS card that's due, with the stability that gets it
+/// scheduled `days` days out, as the scheduler writes it (an hour short)
+async fn schedule_fsrs_interval(pool: &crate::db::DbPool, card_id: &CardId, days: i64) {
+	crate::repo::tests::set_up_fsrs_interval(pool, card_id, days);
+	record_review(pool, card_id, Rating::Good).await.unwrap();
+	let card = load_card(pool, card_id);
+	assert_eq!(
+		card.get_next_review() - card.get_last_review().unwrap(),
+		Duration::days(days) - Duration::hours(1)
+	);
+}
+
+#[tokio::test]
+async fn test_failing_mature_card_counts_lapse() {
+	let pool = setup_test_db();
+	update_settings(&pool, |s| s.interval_fuzz = 0.0)
+		.await
+		.unwrap();
+	let card = create_fsrs_card(&pool).await;
+
+	// Failing a new card isn't a lapse
+	record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	assert_eq!(load_card(&pool, &card.get_id()).get_mature_lapses(), 0);
+
+	// Nor is a cram review of a mature card, or passing one
+	schedule_fsrs_interval(&pool, &card.get_id(), 21).await;
+	record_cram_review_in_session(&pool, &card.get_id(), Rating::Again, None, None)
+		.await
+		.unwrap();
+	record_review(&pool, &card.get_id(), Rating::Good)
+		.await
+		.unwrap();
+	assert_eq!(load_card(&pool, &card.get_id()).get_mature_lapses(), 0);
+
+	// Nor is failing a card scheduled a day short of mature
+	schedule_fsrs_interval(&pool, &card.get_id(), 20).await;
+	record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	assert_eq!(load_card(&pool, &card.get_id()).get_mature_lapses(), 0);
+
+	// A 21 day interval is mature, even though it's stored an hour short
+	schedule_fsrs_interval(&pool, &card.get_id(), 21).await;
+	let lapse = record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	assert_eq!(load_card(&pool, &card.get_id()).get_mature_lapses(), 1);
+
+	// The card is no longer mature, so failing it again isn't another lapse
+	record_review(&pool, &card.get_id(), Rating::Again)
+		.await
+		.unwrap();
+	assert_eq!(load_card(&pool, &card.get_id()).get_mature_lapses(), 1);
+
+	// Undoing the lapse replays the rest of the history, which has none
+	undo_review(&pool, &lapse.get_id()).await.unwrap();
+	assert_eq!(load_card(&pool, &card.get_id()).get_mature_lapses(), 0);
+}
+
+#[tokio::test]
+async fn test_backfill_lapses() {
+	let pool = setup_test_db();
+	let card = create_fsrs_card(&pool).await;
+	let start = Utc::now() - Duration::days(300);
+	let day = |n: i64| start + Duration::days(n);
+
+	// Failing the new card isn't a lapse, but failing it once easy reviews
+	// have spaced it out for months is
+	for (rating, at) in [
+		(Rating::Again, day(0)),
+		(Rating::Easy, day(1)),
+		(Rating::Easy, day(20)),
+		(Rating::Easy, day(80)),
+		(Rating::Again, day(250)),
+	] {
+		insert_review_at(&pool, &card.get_id(), rating, at);
+	}
+	let before = load_card(&pool, &card.get_id());
+	assert_eq!(before.get_mature_lapses(), 0);
+
+	tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+	assert_eq!(backfill_lapses(&pool).await.unwrap(), 1);
+	let after = load_card(&pool, &card.get_id());
+	assert_eq!(after.get_mature_lapses(), 1);
+	// Correcting the count is a change to the card
+	assert_eq!(after.get_version(), before.get_version() + 1);
+	assert!(after.get_updated_at() > before.get_updated_at());
+	// Only the lapses are stored, not the replayed schedule
+	assert_eq!(after.get_next_review(), before.get_next_review());
+	assert_eq!(after.get_last_review(), before.get_last_review());
+
+	// Running it again has nothing left to correct
+	assert_eq!(backfill_lapses(&pool).await.unwrap(), 0);
+}
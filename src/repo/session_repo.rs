@@ -0,0 +1,166 @@
+use crate::db::{DbPool, ExecuteWithRetry};
+use crate::dto::SessionWithStats;
+use crate::models::{Session, SessionId};
+use crate::schema::{reviews, sessions};
+use crate::time_utils::now_ms;
+use anyhow::{Result, anyhow};
+use diesel::dsl::count;
+use diesel::prelude::*;
+use thiserror::Error;
+use tracing::{debug, info, instrument};
+
+/// Error returned when a review is recorded against, or an end is requested
+/// for, a session that has already ended
+///
+/// Carried inside the `anyhow::Error` returned by the repo functions, so
+/// handlers can `downcast` to it and answer with a 409.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Session {0} has already ended")]
+pub struct SessionEndedError(pub SessionId);
+
+/// Starts a new review session
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+///
+/// ### Returns
+///
+/// A Result containing the newly started Session if successful
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database insert operation fails
+#[instrument(skip(pool))]
+pub async fn start_session(pool: &DbPool) -> Result<Session> {
+	debug!("Starting new session");
+
+	let conn = &mut pool.get()?;
+
+	let session = Session::new();
+	diesel::insert_into(sessions::table)
+		.values(session.clone())
+		.execute_with_retry(conn)
+		.await?;
+
+	info!("Started session {}", session.get_id());
+
+	Ok(session)
+}
+
+/// Retrieves a session from the database by its ID
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `session_id` - The ID of the session to retrieve
+///
+/// ### Returns
+///
+/// A Result containing an Option with the Session if found, or None if not found
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+#[instrument(skip(pool), fields(session_id = %session_id))]
+pub fn get_session(pool: &DbPool, session_id: &SessionId) -> Result<Option<Session>> {
+	let conn = &mut pool.get()?;
+	get_session_with_conn(conn, session_id)
+}
+
+/// Retrieves a session using an existing connection
+///
+/// Lets `record_review_in_session` check the session on the connection it
+/// records the review with.
+pub(crate) fn get_session_with_conn(
+	conn: &mut SqliteConnection,
+	session_id: &SessionId,
+) -> Result<Option<Session>> {
+	let session = sessions::table
+		.find(session_id)
+		.first::<Session>(conn)
+		.optional()?;
+	Ok(session)
+}
+
+/// Ends a running review session
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `session_id` - The ID of the session to end
+///
+/// ### Returns
+///
+/// A Result containing the ended Session if successful
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The session does not exist
+/// - The session has already ended (a `SessionEndedError`)
+/// - The database update operation fails
+#[instrument(skip(pool), fields(session_id = %session_id))]
+pub async fn end_session(pool: &DbPool, session_id: &SessionId) -> Result<Session> {
+	debug!("Ending session");
+
+	let conn = &mut pool.get()?;
+
+	let mut session =
+		get_session_with_conn(conn, session_id)?.ok_or_else(|| anyhow!("Session not found"))?;
+	if session.get_ended_at().is_some() {
+		return Err(SessionEndedError(session_id.clone()).into());
+	}
+
+	let ended_at = now_ms();
+	diesel::update(sessions::table.find(session_id.clone()))
+		.set(sessions::ended_at.eq(Some(ended_at)))
+		.execute_with_retry(conn)
+		.await?;
+	session.set_ended_at(Some(ended_at.and_utc()));
+
+	info!("Ended session {}", session_id);
+
+	Ok(session)
+}
+
+/// Derives a session's stats from the reviews recorded in it
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `session` - The session to compute stats for
+///
+/// ### Returns
+///
+/// A Result containing the session with its card count and duration
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+#[instrument(skip(pool, session), fields(session_id = %session.get_id()))]
+pub fn get_session_stats(pool: &DbPool, session: Session) -> Result<SessionWithStats> {
+	let conn = &mut pool.get()?;
+
+	let card_count = reviews::table
+		.filter(reviews::session_id.eq(session.get_id()))
+		.select(count(reviews::card_id).aggregate_distinct())
+		.first::<i64>(conn)?;
+	let duration_secs = session.get_duration().map(|d| d.num_seconds());
+
+	Ok(SessionWithStats {
+		session,
+		card_count,
+		duration_secs,
+	})
+}
+
+#[cfg(test)]
+mod tests;
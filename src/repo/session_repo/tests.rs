@@ -0,0 +1,143 @@
+use super::*;
+use crate::models::Rating;
+use crate::repo::tests::setup_test_db;
+use crate::repo::{create_item, create_item_type, get_cards_for_item, record_review_in_session};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_start_session() {
+	let pool = setup_test_db();
+
+	let session = start_session(&pool).await.unwrap();
+
+	assert_eq!(session.get_ended_at(), None);
+	assert_eq!(session.get_duration(), None);
+	assert_eq!(
+		get_session(&pool, &session.get_id()).unwrap(),
+		Some(session)
+	);
+}
+
+#[tokio::test]
+async fn test_get_session_not_found() {
+	let pool = setup_test_db();
+
+	assert_eq!(get_session(&pool, &SessionId::new()).unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_session_with_reviews() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let cards = get_cards_for_item(&pool, &item.get_id()).unwrap();
+	assert_eq!(cards.len(), 2);
+
+	let session = start_session(&pool).await.unwrap();
+
+	// Review the first card twice and the second once
+	for card in [&cards[0], &cards[0], &cards[1]] {
+		let review = record_review_in_session(
+			&pool,
+			&card.get_id(),
+			Rating::Good,
+			Some(&session.get_id()),
+			None,
+		)
+		.await
+		.unwrap();
+		assert_eq!(review.get_session_id(), Some(session.get_id()));
+	}
+
+	// A review outside the session doesn't count towards it
+	let other = record_review_in_session(&pool, &cards[0].get_id(), Rating::Good, None, None)
+		.await
+		.unwrap();
+	assert_eq!(other.get_session_id(), None);
+
+	let ended = end_session(&pool, &session.get_id()).await.unwrap();
+	assert!(ended.get_ended_at().unwrap() >= ended.get_started_at());
+	assert_eq!(
+		get_session(&pool, &session.get_id()).unwrap(),
+		Some(ended.clone())
+	);
+
+	let stats = get_session_stats(&pool, ended).unwrap();
+	assert_eq!(stats.card_count, 2);
+	assert!(stats.duration_secs.unwrap() >= 0);
+}
+
+#[tokio::test]
+async fn test_end_session_twice() {
+	let pool = setup_test_db();
+
+	let session = start_session(&pool).await.unwrap();
+	end_session(&pool, &session.get_id()).await.unwrap();
+
+	let err = end_session(&pool, &session.get_id()).await.unwrap_err();
+	assert_eq!(
+		err.downcast::<SessionEndedError>().unwrap(),
+		SessionEndedError(session.get_id())
+	);
+}
+
+#[tokio::test]
+async fn test_end_session_not_found() {
+	let pool = setup_test_db();
+
+	let err = end_session(&pool, &SessionId::new()).await.unwrap_err();
+	assert!(err.to_string().contains("Session not found"));
+}
+
+#[tokio::test]
+async fn test_record_review_in_ended_session() {
+	let pool = setup_test_db();
+
+	let item_type = create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let card = get_cards_for_item(&pool, &item.get_id()).unwrap().remove(0);
+
+	let session = start_session(&pool).await.unwrap();
+	end_session(&pool, &session.get_id()).await.unwrap();
+
+	let err = record_review_in_session(
+		&pool,
+		&card.get_id(),
+		Rating::Good,
+		Some(&session.get_id()),
+		None,
+	)
+	.await
+	.unwrap_err();
+	assert!(err.downcast::<SessionEndedError>().is_ok());
+
+	let err = record_review_in_session(
+		&pool,
+		&card.get_id(),
+		Rating::Good,
+		Some(&SessionId::new()),
+		None,
+	)
+	.await
+	.unwrap_err();
+	assert!(err.to_string().contains("Session not found"));
+}
@@ -0,0 +1,371 @@
+//! Settings the server can change while it's running.
+//!
+//! Card creation, scheduling and the daily resets deep in the repo layer
+//! need a handful of the server's settings: the default priority, the
+//! timezone, and how reviews are scheduled. Rather than threading the config
+//! through every handler, the server stores them in the `metadata` table at
+//! startup, and again whenever the config file is reloaded (see
+//! `config_watcher`), and the repo reads them back as [`Settings`] when it
+//! needs them. All of them are written in one transaction and read in one
+//! query, so a reload is never seen half applied.
+//!
+//! Each setting has its own `metadata` row, so a database written before a
+//! setting existed reads its default.
+
+use crate::config::{
+	DEFAULT_EASY_PRIORITY_DECAY, DEFAULT_FAIL_PRIORITY_BOOST, DEFAULT_INTERVAL_FUZZ,
+	DEFAULT_LEECH_TAG, DEFAULT_PRIORITY, DEFAULT_RATING_SCALE, DEFAULT_TIMEZONE, LeechAction,
+};
+use crate::db::{DbPool, transaction_with_retry};
+use crate::repo::{LearningSteps, PriorityAdjustments};
+use crate::schema::metadata;
+use anyhow::Result;
+use chrono::Duration;
+use chrono_tz::Tz;
+use diesel::prelude::*;
+use std::collections::HashMap;
+use std::str::FromStr;
+use tracing::{debug, instrument, warn};
+
+/// Key of the `metadata` row holding the default card priority
+const DEFAULT_PRIORITY_KEY: &str = "default_priority";
+
+/// Key of the `metadata` row holding the timezone
+const TIMEZONE_KEY: &str = "timezone";
+
+/// Key of the `metadata` row holding the interval fuzz
+const INTERVAL_FUZZ_KEY: &str = "interval_fuzz";
+
+/// Key of the `metadata` row holding the rating scale
+const RATING_SCALE_KEY: &str = "rating_scale";
+
+/// Key of the `metadata` row holding the minimum interval, in seconds
+const MINIMUM_INTERVAL_KEY: &str = "minimum_interval";
+
+/// Key of the `metadata` row holding the learning steps, as a JSON list of
+/// seconds
+const LEARNING_STEPS_KEY: &str = "learning_steps";
+
+/// Key of the `metadata` row holding the relearning steps, as a JSON list of
+/// seconds
+const RELEARNING_STEPS_KEY: &str = "relearning_steps";
+
+/// Key of the `metadata` row holding the graduating interval, in seconds
+/// (absent if there's none)
+const GRADUATING_INTERVAL_KEY: &str = "graduating_interval";
+
+/// Key of the `metadata` row holding whether priority offsets are enabled
+const PRIORITY_OFFSETS_ENABLED_KEY: &str = "priority_offsets_enabled";
+
+/// Key of the `metadata` row holding the fail priority boost
+const FAIL_PRIORITY_BOOST_KEY: &str = "fail_priority_boost";
+
+/// Key of the `metadata` row holding the easy priority decay
+const EASY_PRIORITY_DECAY_KEY: &str = "easy_priority_decay";
+
+/// Key of the `metadata` row holding the leech action
+const LEECH_ACTION_KEY: &str = "leech_action";
+
+/// Key of the `metadata` row holding the tag applied to leeches (absent
+/// unless the leech action is `tag`)
+const LEECH_TAG_KEY: &str = "leech_tag";
+
+/// Every `metadata` key a setting is stored under
+const SETTING_KEYS: [&str; 13] = [
+	DEFAULT_PRIORITY_KEY,
+	TIMEZONE_KEY,
+	INTERVAL_FUZZ_KEY,
+	RATING_SCALE_KEY,
+	MINIMUM_INTERVAL_KEY,
+	LEARNING_STEPS_KEY,
+	RELEARNING_STEPS_KEY,
+	GRADUATING_INTERVAL_KEY,
+	PRIORITY_OFFSETS_ENABLED_KEY,
+	FAIL_PRIORITY_BOOST_KEY,
+	EASY_PRIORITY_DECAY_KEY,
+	LEECH_ACTION_KEY,
+	LEECH_TAG_KEY,
+];
+
+/// The settings the repo layer reads, see the module docs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+	/// Priority given to new cards whose item type has no default, between
+	/// 0 and 1
+	pub default_priority: f32,
+	/// Timezone that decides when one day ends and the next begins
+	pub timezone: Tz,
+	/// Fraction review intervals are randomly stretched or shrunk by, at
+	/// least 0 and less than 1
+	pub interval_fuzz: f64,
+	/// Number of buttons reviews are rated with, 2 or 4
+	pub rating_scale: u8,
+	/// Shortest interval graduated cards are scheduled for, or zero for none
+	pub minimum_interval: Duration,
+	/// Learning and relearning steps, and the graduating interval
+	pub learning_steps: LearningSteps,
+	/// Whether cards' priorities are shuffled by a daily random offset.
+	/// Turning offsets off zeroes them on the next request; turning them back
+	/// on shuffles them on the next request.
+	pub priority_offsets_enabled: bool,
+	/// How much reviewing a card moves its priority
+	pub priority_adjustments: PriorityAdjustments,
+	/// What happens to a card when it becomes a leech
+	pub leech_action: LeechAction,
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Self {
+			default_priority: DEFAULT_PRIORITY,
+			timezone: DEFAULT_TIMEZONE,
+			interval_fuzz: DEFAULT_INTERVAL_FUZZ,
+			rating_scale: DEFAULT_RATING_SCALE,
+			minimum_interval: Duration::zero(),
+			learning_steps: LearningSteps::default(),
+			priority_offsets_enabled: true,
+			priority_adjustments: PriorityAdjustments {
+				fail_boost: DEFAULT_FAIL_PRIORITY_BOOST,
+				easy_decay: DEFAULT_EASY_PRIORITY_DECAY,
+			},
+			leech_action: LeechAction::None,
+		}
+	}
+}
+
+/// Stores every setting at once, replacing the ones stored before
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `settings` - The settings to store
+///
+/// ### Returns
+///
+/// A Result indicating success or failure
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database writes fail
+#[instrument(skip(pool))]
+pub async fn set_settings(pool: &DbPool, settings: &Settings) -> Result<()> {
+	debug!("Storing settings");
+
+	let conn = &mut pool.get()?;
+	transaction_with_retry(conn, |c| write_settings(c, settings)).await?;
+
+	Ok(())
+}
+
+/// Changes some of the stored settings, leaving the rest as they are
+///
+/// The settings are read, changed and written back in one transaction.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `change` - Changes the settings read; called again if the transaction
+///   is retried
+///
+/// ### Returns
+///
+/// The settings now stored
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query or writes fail
+#[instrument(skip(pool, change))]
+pub async fn update_settings(pool: &DbPool, change: impl Fn(&mut Settings)) -> Result<Settings> {
+	debug!("Updating settings");
+
+	let conn = &mut pool.get()?;
+	let settings = transaction_with_retry(conn, |c| {
+		let mut settings = get_settings_with_conn(c)?;
+		change(&mut settings);
+		write_settings(c, &settings)?;
+		Ok(settings)
+	})
+	.await?;
+
+	Ok(settings)
+}
+
+/// Gets the stored settings
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+///
+/// ### Returns
+///
+/// The stored settings, with the default for any that hasn't been stored
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+pub fn get_settings(pool: &DbPool) -> Result<Settings> {
+	let conn = &mut pool.get()?;
+	Ok(get_settings_with_conn(conn)?)
+}
+
+/// Reads the stored settings on an existing connection
+///
+/// Values are checked when the config is loaded, so a stored value that
+/// doesn't parse could only have been written by hand (or by a newer
+/// server). It falls back to the default rather than failing every request,
+/// which also lets this run inside the daily ensures, which can only fail
+/// with a diesel error.
+pub(crate) fn get_settings_with_conn(
+	conn: &mut SqliteConnection,
+) -> Result<Settings, diesel::result::Error> {
+	let stored: HashMap<String, String> = metadata::table
+		.filter(metadata::key.eq_any(SETTING_KEYS))
+		.select((metadata::key, metadata::value))
+		.load::<(String, String)>(conn)?
+		.into_iter()
+		.collect();
+	let defaults = Settings::default();
+
+	let seconds = |key: &str| -> Option<Duration> {
+		read(&stored, key, |value| {
+			Duration::try_seconds(value.parse().ok()?)
+		})
+	};
+	let steps = |key: &str| -> Vec<Duration> {
+		read(&stored, key, |value| {
+			serde_json::from_str::<Vec<i64>>(value)
+				.ok()?
+				.into_iter()
+				.map(Duration::try_seconds)
+				.collect()
+		})
+		.unwrap_or_default()
+	};
+	let leech_action = read(&stored, LEECH_ACTION_KEY, |value| match value {
+		"none" => Some(LeechAction::None),
+		"suspend" => Some(LeechAction::Suspend),
+		"tag" => Some(LeechAction::Tag(
+			stored
+				.get(LEECH_TAG_KEY)
+				.cloned()
+				.unwrap_or_else(|| DEFAULT_LEECH_TAG.to_string()),
+		)),
+		_ => None,
+	});
+
+	Ok(Settings {
+		default_priority: parse(&stored, DEFAULT_PRIORITY_KEY).unwrap_or(defaults.default_priority),
+		timezone: parse(&stored, TIMEZONE_KEY).unwrap_or(defaults.timezone),
+		interval_fuzz: parse(&stored, INTERVAL_FUZZ_KEY).unwrap_or(defaults.interval_fuzz),
+		rating_scale: parse(&stored, RATING_SCALE_KEY).unwrap_or(defaults.rating_scale),
+		minimum_interval: seconds(MINIMUM_INTERVAL_KEY).unwrap_or(defaults.minimum_interval),
+		learning_steps: LearningSteps {
+			learning: steps(LEARNING_STEPS_KEY),
+			relearning: steps(RELEARNING_STEPS_KEY),
+			graduating_interval: seconds(GRADUATING_INTERVAL_KEY),
+		},
+		priority_offsets_enabled: parse(&stored, PRIORITY_OFFSETS_ENABLED_KEY)
+			.unwrap_or(defaults.priority_offsets_enabled),
+		priority_adjustments: PriorityAdjustments {
+			fail_boost: parse(&stored, FAIL_PRIORITY_BOOST_KEY)
+				.unwrap_or(defaults.priority_adjustments.fail_boost),
+			easy_decay: parse(&stored, EASY_PRIORITY_DECAY_KEY)
+				.unwrap_or(defaults.priority_adjustments.easy_decay),
+		},
+		leech_action: leech_action.unwrap_or(defaults.leech_action),
+	})
+}
+
+/// Reads the setting stored under `key` with `from_str`, or None if it
+/// isn't stored or doesn't parse
+fn read<T>(
+	stored: &HashMap<String, String>,
+	key: &str,
+	from_str: impl FnOnce(&str) -> Option<T>,
+) -> Option<T> {
+	let value = stored.get(key)?;
+	let parsed = from_str(value);
+	if parsed.is_none() {
+		warn!("Stored {} {:?} is invalid, using the default", key, value);
+	}
+	parsed
+}
+
+/// Reads the setting stored under `key` with its `FromStr`, or None if it
+/// isn't stored or doesn't parse
+fn parse<T: FromStr>(stored: &HashMap<String, String>, key: &str) -> Option<T> {
+	read(stored, key, |value| value.parse().ok())
+}
+
+/// Writes every setting on an existing connection, deleting the rows of
+/// optional ones that aren't set
+fn write_settings(
+	conn: &mut SqliteConnection,
+	settings: &Settings,
+) -> Result<(), diesel::result::Error> {
+	let seconds = |steps: &[Duration]| {
+		serde_json::to_string(&steps.iter().map(Duration::num_seconds).collect::<Vec<_>>())
+			.expect("a list of integers always serializes")
+	};
+	let (leech_action, leech_tag) = match &settings.leech_action {
+		LeechAction::None => ("none", None),
+		LeechAction::Suspend => ("suspend", None),
+		LeechAction::Tag(tag) => ("tag", Some(tag.clone())),
+	};
+	let steps = &settings.learning_steps;
+
+	let rows = [
+		(
+			DEFAULT_PRIORITY_KEY,
+			Some(settings.default_priority.to_string()),
+		),
+		(TIMEZONE_KEY, Some(settings.timezone.name().to_string())),
+		(INTERVAL_FUZZ_KEY, Some(settings.interval_fuzz.to_string())),
+		(RATING_SCALE_KEY, Some(settings.rating_scale.to_string())),
+		(
+			MINIMUM_INTERVAL_KEY,
+			Some(settings.minimum_interval.num_seconds().to_string()),
+		),
+		(LEARNING_STEPS_KEY, Some(seconds(&steps.learning))),
+		(RELEARNING_STEPS_KEY, Some(seconds(&steps.relearning))),
+		(
+			GRADUATING_INTERVAL_KEY,
+			steps
+				.graduating_interval
+				.map(|interval| interval.num_seconds().to_string()),
+		),
+		(
+			PRIORITY_OFFSETS_ENABLED_KEY,
+			Some(settings.priority_offsets_enabled.to_string()),
+		),
+		(
+			FAIL_PRIORITY_BOOST_KEY,
+			Some(settings.priority_adjustments.fail_boost.to_string()),
+		),
+		(
+			EASY_PRIORITY_DECAY_KEY,
+			Some(settings.priority_adjustments.easy_decay.to_string()),
+		),
+		(LEECH_ACTION_KEY, Some(leech_action.to_string())),
+		(LEECH_TAG_KEY, leech_tag),
+	];
+	for (key, value) in rows {
+		match value {
+			Some(value) => diesel::replace_into(metadata::table)
+				.values((metadata::key.eq(key), metadata::value.eq(value)))
+				.execute(conn)?,
+			None => diesel::delete(metadata::table.find(key)).execute(conn)?,
+		};
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,119 @@
+use super::*;
+use crate::repo::tests::setup_test_db;
+
+/// Settings with every field changed from its default
+fn non_default_settings() -> Settings {
+	Settings {
+		default_priority: 0.8,
+		timezone: "America/New_York".parse().unwrap(),
+		interval_fuzz: 0.05,
+		rating_scale: 2,
+		minimum_interval: Duration::hours(36),
+		learning_steps: LearningSteps {
+			learning: vec![Duration::seconds(30), Duration::hours(1)],
+			relearning: vec![Duration::minutes(10)],
+			graduating_interval: Some(Duration::days(3)),
+		},
+		priority_offsets_enabled: false,
+		priority_adjustments: PriorityAdjustments {
+			fail_boost: 0.1,
+			easy_decay: 0.02,
+		},
+		leech_action: LeechAction::Tag("hard".to_string()),
+	}
+}
+
+/// Counts the `metadata` rows a setting is stored in
+fn stored_rows(pool: &DbPool) -> i64 {
+	metadata::table
+		.filter(metadata::key.eq_any(SETTING_KEYS))
+		.count()
+		.get_result(&mut pool.get().unwrap())
+		.unwrap()
+}
+
+#[tokio::test]
+async fn test_settings_default_when_none_are_stored() {
+	let pool = setup_test_db();
+
+	let settings = get_settings(&pool).unwrap();
+
+	assert_eq!(settings, Settings::default());
+	assert_eq!(settings.default_priority, crate::config::DEFAULT_PRIORITY);
+	assert_eq!(settings.timezone, chrono_tz::Tz::UTC);
+	assert_eq!(settings.minimum_interval, Duration::zero());
+	assert!(settings.priority_offsets_enabled);
+	assert_eq!(settings.leech_action, LeechAction::None);
+}
+
+#[tokio::test]
+async fn test_settings_round_trip() {
+	let pool = setup_test_db();
+	let settings = non_default_settings();
+
+	set_settings(&pool, &settings).await.unwrap();
+
+	assert_eq!(get_settings(&pool).unwrap(), settings);
+}
+
+#[tokio::test]
+async fn test_unset_optional_settings_are_removed() {
+	let pool = setup_test_db();
+	set_settings(&pool, &non_default_settings()).await.unwrap();
+	assert_eq!(stored_rows(&pool), SETTING_KEYS.len() as i64);
+
+	set_settings(&pool, &Settings::default()).await.unwrap();
+
+	// Neither the graduating interval nor the leech tag is left behind
+	assert_eq!(get_settings(&pool).unwrap(), Settings::default());
+	assert_eq!(stored_rows(&pool), SETTING_KEYS.len() as i64 - 2);
+}
+
+#[tokio::test]
+async fn test_update_settings_keeps_the_others() {
+	let pool = setup_test_db();
+	set_settings(&pool, &non_default_settings()).await.unwrap();
+
+	let updated = update_settings(&pool, |s| s.interval_fuzz = 0.0)
+		.await
+		.unwrap();
+
+	let expected = Settings {
+		interval_fuzz: 0.0,
+		..non_default_settings()
+	};
+	assert_eq!(updated, expected);
+	assert_eq!(get_settings(&pool).unwrap(), expected);
+}
+
+#[tokio::test]
+async fn test_invalid_stored_setting_reads_as_default() {
+	let pool = setup_test_db();
+	set_settings(&pool, &non_default_settings()).await.unwrap();
+
+	for (key, value) in [
+		(TIMEZONE_KEY, "Mars/Olympus_Mons"),
+		(RATING_SCALE_KEY, "lots"),
+		(LEARNING_STEPS_KEY, "[\"soon\"]"),
+		(LEECH_ACTION_KEY, "explode"),
+	] {
+		diesel::replace_into(metadata::table)
+			.values((metadata::key.eq(key), metadata::value.eq(value)))
+			.execute(&mut pool.get().unwrap())
+			.unwrap();
+	}
+
+	let settings = get_settings(&pool).unwrap();
+	let defaults = Settings::default();
+	assert_eq!(settings.timezone, defaults.timezone);
+	assert_eq!(settings.rating_scale, defaults.rating_scale);
+	assert!(settings.learning_steps.learning.is_empty());
+	assert_eq!(settings.leech_action, defaults.leech_action);
+
+	// The valid ones are unaffected
+	assert_eq!(settings.default_priority, 0.8);
+	assert_eq!(
+		settings.learning_steps.relearning,
+		vec![Duration::minutes(10)]
+	);
+}
@@ -1,8 +1,12 @@
-use crate::db::{DbPool, ExecuteWithRetry};
+use crate::db::{DbPool, ExecuteWithRetry, transaction_with_retry};
+use crate::dto::TagStats;
 use crate::models::{CardId, ItemId, ItemTag, Tag, TagId};
-use crate::schema::{item_tags, tags};
+use crate::schema::{cards, item_tags, tags};
 use anyhow::{Result, anyhow};
+use chrono::Utc;
 use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use std::collections::{HashMap, HashSet};
 use tracing::{debug, info, instrument};
 
 /// Creates a new tag in the database
@@ -22,14 +26,52 @@ use tracing::{debug, info, instrument};
 /// Returns an error if:
 /// - Unable to get a connection from the pool
 /// - The database insert operation fails
-#[instrument(skip(pool), fields(name = %name, visible = %visible))]
 pub async fn create_tag(pool: &DbPool, name: String, visible: bool) -> Result<Tag> {
+	create_tag_with_parent(pool, name, visible, None).await
+}
+
+/// Creates a new tag in the database, optionally under another tag
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `name` - The name for the new tag
+/// * `visible` - Whether the tag is visible to the user
+/// * `parent_id` - The tag to create this one under, or None for a top-level tag
+///
+/// ### Returns
+///
+/// A Result containing the newly created Tag if successful
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The parent tag does not exist
+/// - The database insert operation fails
+#[instrument(skip(pool), fields(name = %name, visible = %visible))]
+pub async fn create_tag_with_parent(
+	pool: &DbPool,
+	name: String,
+	visible: bool,
+	parent_id: Option<TagId>,
+) -> Result<Tag> {
 	debug!("Creating new tag");
 
 	let conn = &mut pool.get()?;
 
-	// Create a new tag with the provided name and visibility
-	let new_tag = Tag::new(name, visible);
+	if let Some(ref parent_id) = parent_id {
+		tags::table
+			.find(parent_id)
+			.select(tags::id)
+			.first::<TagId>(conn)
+			.optional()?
+			.ok_or_else(|| anyhow!("Parent tag not found: {}", parent_id))?;
+	}
+
+	// Create a new tag with the provided name, visibility and parent
+	let mut new_tag = Tag::new(name, visible);
+	new_tag.set_parent_id(parent_id);
 
 	debug!("Inserting tag into database with id: {}", new_tag.get_id());
 
@@ -159,6 +201,159 @@ pub fn list_tags_for_item(pool: &DbPool, item_id: &ItemId) -> Result<Vec<Tag>> {
 	Ok(results)
 }
 
+/// Lists the tags on an item together with all their ancestors
+///
+/// Each tag on the item brings its parent, its parent's parent and so on,
+/// so an item tagged `Verbs` under `Spanish` under `Language` lists all
+/// three. Each tag is listed once, however many ways it's reached.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `item_id` - The ID of the item to get tags for
+///
+/// ### Returns
+///
+/// A Result containing the item's tags and their ancestors
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+#[instrument(skip(pool), fields(item_id = %item_id))]
+pub fn list_tags_for_item_with_ancestors(pool: &DbPool, item_id: &ItemId) -> Result<Vec<Tag>> {
+	debug!("Listing tags and their ancestors for item");
+
+	let conn = &mut pool.get()?;
+
+	let parents = tag_parents_with_conn(conn)?;
+	let mut ids: HashSet<TagId> = HashSet::new();
+	let direct: Vec<TagId> = item_tags::table
+		.filter(item_tags::item_id.eq(item_id))
+		.select(item_tags::tag_id)
+		.load(conn)?;
+	for tag_id in direct {
+		// Stop at a tag already seen, which also guards against cycles
+		let mut next = Some(tag_id);
+		while let Some(tag_id) = next {
+			if !ids.insert(tag_id.clone()) {
+				break;
+			}
+			next = parents.get(&tag_id).cloned().flatten();
+		}
+	}
+
+	let results = tags::table.filter(tags::id.eq_any(ids)).load::<Tag>(conn)?;
+
+	info!(
+		"Retrieved {} tags and ancestors for item {}",
+		results.len(),
+		item_id
+	);
+
+	Ok(results)
+}
+
+/// Gets a tag together with every tag beneath it
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `tag_id` - The ID of the tag at the top of the subtree
+///
+/// ### Returns
+///
+/// A Result containing the tag followed by its descendants, each level
+/// before the next, or None if the tag doesn't exist
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+#[instrument(skip(pool), fields(tag_id = %tag_id))]
+pub fn get_tag_subtree(pool: &DbPool, tag_id: &TagId) -> Result<Option<Vec<Tag>>> {
+	debug!("Getting tag subtree");
+
+	let conn = &mut pool.get()?;
+
+	let mut all: HashMap<TagId, Tag> = tags::table
+		.load::<Tag>(conn)?
+		.into_iter()
+		.map(|tag| (tag.get_id(), tag))
+		.collect();
+	let Some(root) = all.remove(tag_id) else {
+		debug!("Tag not found");
+		return Ok(None);
+	};
+
+	let mut children: HashMap<TagId, Vec<Tag>> = HashMap::new();
+	for tag in all.into_values() {
+		if let Some(parent_id) = tag.get_parent_id() {
+			children.entry(parent_id).or_default().push(tag);
+		}
+	}
+	for siblings in children.values_mut() {
+		siblings.sort_by_key(|tag| tag.get_name());
+	}
+
+	// Breadth first, so each tag comes after its parent. Tags are taken out
+	// of `children` as they're visited, so a cycle can't loop forever.
+	let mut subtree = vec![root];
+	let mut visited = 0;
+	while visited < subtree.len() {
+		let parent_id = subtree[visited].get_id();
+		subtree.extend(children.remove(&parent_id).unwrap_or_default());
+		visited += 1;
+	}
+
+	info!("Tag {} has {} descendants", tag_id, subtree.len() - 1);
+
+	Ok(Some(subtree))
+}
+
+/// Checks whether one tag is above another in the hierarchy
+///
+/// ### Arguments
+///
+/// * `parents` - Every tag's parent, from [`tag_parents_with_conn`]
+/// * `ancestor_id` - The tag that might be above
+/// * `tag_id` - The tag to walk up from
+///
+/// ### Returns
+///
+/// Whether `ancestor_id` is `tag_id`'s parent, grandparent and so on
+fn is_ancestor(
+	parents: &HashMap<TagId, Option<TagId>>,
+	ancestor_id: &TagId,
+	tag_id: &TagId,
+) -> bool {
+	let mut seen = HashSet::new();
+	let mut next = parents.get(tag_id).cloned().flatten();
+	while let Some(parent_id) = next {
+		if &parent_id == ancestor_id {
+			return true;
+		}
+		if !seen.insert(parent_id.clone()) {
+			return false;
+		}
+		next = parents.get(&parent_id).cloned().flatten();
+	}
+	false
+}
+
+/// Loads every tag's parent, keyed by tag ID
+fn tag_parents_with_conn(
+	conn: &mut SqliteConnection,
+) -> QueryResult<HashMap<TagId, Option<TagId>>> {
+	Ok(tags::table
+		.select((tags::id, tags::parent_id))
+		.load::<(TagId, Option<TagId>)>(conn)?
+		.into_iter()
+		.collect())
+}
+
 /// Lists all tags in the database
 ///
 /// ### Arguments
@@ -291,6 +486,160 @@ pub async fn remove_tag_from_item(pool: &DbPool, tag_id: &TagId, item_id: &ItemI
 	Ok(())
 }
 
+/// Merges one tag into another
+///
+/// Every item tagged with the source tag is tagged with the target instead,
+/// skipping items that already have the target so none ends up with it
+/// twice. The source's child tags move under the target (a target that was
+/// beneath the source first moves up to the source's parent), and then the
+/// source tag is deleted. It all happens in one
+/// transaction, so a failure leaves both tags as they were.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `source_id` - The ID of the tag to merge away
+/// * `target_id` - The ID of the tag to keep
+///
+/// ### Returns
+///
+/// A Result containing the surviving target tag
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - The source and target are the same tag
+/// - Unable to get a connection from the pool
+/// - Either tag does not exist
+/// - The database operations fail
+#[instrument(skip(pool), fields(source_id = %source_id, target_id = %target_id))]
+pub async fn merge_tags(pool: &DbPool, source_id: &TagId, target_id: &TagId) -> Result<Tag> {
+	debug!("Merging tags");
+
+	if source_id == target_id {
+		return Err(anyhow!("Cannot merge a tag into itself"));
+	}
+
+	let conn = &mut pool.get()?;
+
+	let target = transaction_with_retry(conn, |c| {
+		let source = tags::table.find(source_id).first::<Tag>(c)?;
+		tags::table.find(target_id).first::<Tag>(c)?;
+
+		// Repoint the source's associations, except on items that already
+		// have the target; those are dropped along with the source tag
+		let already_tagged: Vec<ItemId> = item_tags::table
+			.filter(item_tags::tag_id.eq(target_id))
+			.select(item_tags::item_id)
+			.load(c)?;
+		diesel::update(
+			item_tags::table
+				.filter(item_tags::tag_id.eq(source_id))
+				.filter(item_tags::item_id.ne_all(already_tagged)),
+		)
+		.set(item_tags::tag_id.eq(target_id))
+		.execute(c)?;
+
+		diesel::delete(item_tags::table.filter(item_tags::tag_id.eq(source_id))).execute(c)?;
+
+		// The source's children move under the target. A target beneath the
+		// source first takes the source's place, so no tag ends up beneath
+		// itself.
+		let parents = tag_parents_with_conn(c)?;
+		if is_ancestor(&parents, source_id, target_id) {
+			diesel::update(tags::table.find(target_id))
+				.set(tags::parent_id.eq(source.get_parent_id()))
+				.execute(c)?;
+		}
+		diesel::update(
+			tags::table
+				.filter(tags::parent_id.eq(source_id))
+				.filter(tags::id.ne(target_id)),
+		)
+		.set(tags::parent_id.eq(target_id))
+		.execute(c)?;
+
+		diesel::delete(tags::table.find(source_id)).execute(c)?;
+
+		tags::table.find(target_id).first::<Tag>(c)
+	})
+	.await
+	.map_err(|e| match e {
+		diesel::result::Error::NotFound => anyhow!("Tag not found"),
+		other => anyhow::Error::from(other),
+	})?;
+
+	info!("Successfully merged tag {} into {}", source_id, target_id);
+
+	Ok(target)
+}
+
+/// Counts the items and cards that carry a tag, and how many of the cards
+/// are due
+///
+/// A card is due when it isn't suspended and its next review is at or
+/// before now.
+///
+/// ### Arguments
+///
+/// * `pool` - A reference to the database connection pool
+/// * `tag_id` - The ID of the tag to count for
+///
+/// ### Returns
+///
+/// A Result containing the tag's counts, or None if the tag doesn't exist
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Unable to get a connection from the pool
+/// - The database query fails
+#[instrument(skip(pool), fields(tag_id = %tag_id))]
+pub fn get_tag_stats(pool: &DbPool, tag_id: &TagId) -> Result<Option<TagStats>> {
+	debug!("Getting tag stats");
+
+	let conn = &mut pool.get()?;
+
+	let exists = tags::table
+		.find(tag_id)
+		.select(tags::id)
+		.first::<TagId>(conn)
+		.optional()?
+		.is_some();
+	if !exists {
+		debug!("Tag not found");
+		return Ok(None);
+	}
+
+	let item_count = item_tags::table
+		.filter(item_tags::tag_id.eq(tag_id))
+		.count()
+		.get_result::<i64>(conn)?;
+
+	let tagged_cards = || {
+		cards::table
+			.inner_join(item_tags::table.on(item_tags::item_id.eq(cards::item_id)))
+			.filter(item_tags::tag_id.eq(tag_id))
+	};
+	let card_count = tagged_cards().count().get_result::<i64>(conn)?;
+	let due_count = tagged_cards()
+		.filter(cards::suspended.is_null())
+		.filter(cards::next_review.le(Utc::now().naive_utc()))
+		.count()
+		.get_result::<i64>(conn)?;
+
+	debug!(
+		"Tag has {} items, {} cards and {} due",
+		item_count, card_count, due_count
+	);
+
+	Ok(Some(TagStats {
+		item_count,
+		card_count,
+		due_count,
+	}))
+}
+
 #[cfg(test)]
 mod prop_tests;
 #[cfg(test)]
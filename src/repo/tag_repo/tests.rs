@@ -1,4 +1,5 @@
 use super::*;
+use crate::dto::{GetQueryDto, TagMatch};
 use crate::repo::tests::setup_test_db;
 
 #[tokio::test]
@@ -254,3 +255,472 @@ async fn test_remove_tag_from_item() {
 	let tags_after = list_tags_for_item(&pool, &item.get_id()).unwrap();
 	assert_eq!(tags_after.len(), 0);
 }
+
+#[tokio::test]
+async fn test_merge_tags() {
+	let pool = setup_test_db();
+
+	let item_type =
+		crate::repo::create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+
+	let source = create_tag(&pool, "biology".to_string(), true)
+		.await
+		.unwrap();
+	let target = create_tag(&pool, "Biology".to_string(), true)
+		.await
+		.unwrap();
+	let other = create_tag(&pool, "Chemistry".to_string(), true)
+		.await
+		.unwrap();
+
+	// One item with only the source, one with only the target, one with both
+	let mut items = Vec::new();
+	for (title, tag_ids) in [
+		("Source only", vec![&source]),
+		("Target only", vec![&target]),
+		("Both", vec![&source, &target, &other]),
+	] {
+		let item = crate::repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			serde_json::json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		for tag in tag_ids {
+			add_tag_to_item(&pool, &tag.get_id(), &item.get_id())
+				.await
+				.unwrap();
+		}
+		items.push(item);
+	}
+
+	let merged = merge_tags(&pool, &source.get_id(), &target.get_id())
+		.await
+		.unwrap();
+	assert_eq!(merged.get_id(), target.get_id());
+	assert_eq!(merged.get_name(), "Biology");
+
+	// Every item has the target exactly once, and nothing has the source
+	for item in &items {
+		let tags = list_tags_for_item(&pool, &item.get_id()).unwrap();
+		let target_count = tags
+			.iter()
+			.filter(|t| t.get_id() == target.get_id())
+			.count();
+		assert_eq!(target_count, 1, "item {}", item.get_title());
+		assert!(tags.iter().all(|t| t.get_id() != source.get_id()));
+	}
+
+	// Unrelated tags are kept, and the source tag is gone
+	let both_tags = list_tags_for_item(&pool, &items[2].get_id()).unwrap();
+	assert_eq!(both_tags.len(), 2);
+	assert!(get_tag(&pool, &source.get_id()).is_err());
+	let remaining: Vec<TagId> = list_tags(&pool)
+		.unwrap()
+		.iter()
+		.map(|t| t.get_id())
+		.collect();
+	assert!(!remaining.contains(&source.get_id()));
+	assert!(remaining.contains(&other.get_id()));
+}
+
+#[tokio::test]
+async fn test_merge_tags_errors() {
+	let pool = setup_test_db();
+
+	let tag = create_tag(&pool, "Important".to_string(), true)
+		.await
+		.unwrap();
+	let missing = TagId("nonexistent".to_string());
+
+	// A tag can't be merged into itself
+	let err = merge_tags(&pool, &tag.get_id(), &tag.get_id())
+		.await
+		.unwrap_err();
+	assert!(err.to_string().contains("Cannot merge a tag into itself"));
+
+	// Either side missing is reported, and the existing tag survives
+	for (source, target) in [(&missing, &tag.get_id()), (&tag.get_id(), &missing)] {
+		let err = merge_tags(&pool, source, target).await.unwrap_err();
+		assert!(err.to_string().contains("Tag not found"));
+	}
+	assert!(get_tag(&pool, &tag.get_id()).is_ok());
+}
+
+#[tokio::test]
+async fn test_tag_timestamps() {
+	let pool = setup_test_db();
+
+	let tag = create_tag(&pool, "Important".to_string(), true)
+		.await
+		.unwrap();
+	let stored = get_tag(&pool, &tag.get_id()).unwrap();
+	assert_eq!(stored.get_created_at(), tag.get_created_at());
+	assert_eq!(stored.get_updated_at(), stored.get_created_at());
+
+	// Renaming the tag bumps updated_at but leaves created_at alone
+	std::thread::sleep(std::time::Duration::from_millis(10));
+	diesel::update(crate::schema::tags::table.find(tag.get_id()))
+		.set(crate::schema::tags::name.eq("Urgent"))
+		.execute(&mut pool.get().unwrap())
+		.unwrap();
+	let renamed = get_tag(&pool, &tag.get_id()).unwrap();
+	assert_eq!(renamed.get_created_at(), stored.get_created_at());
+	assert!(renamed.get_updated_at() > stored.get_updated_at());
+}
+
+#[tokio::test]
+async fn test_get_tag_stats() {
+	let pool = setup_test_db();
+
+	// "Test" item types give each item two cards
+	let item_type =
+		crate::repo::create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+	let tag = create_tag(&pool, "Biology".to_string(), true)
+		.await
+		.unwrap();
+
+	let mut tagged = Vec::new();
+	for title in ["Tagged 1", "Tagged 2", "Untagged"] {
+		let item = crate::repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			serde_json::json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		if title.starts_with("Tagged") {
+			add_tag_to_item(&pool, &tag.get_id(), &item.get_id())
+				.await
+				.unwrap();
+			tagged.push(item);
+		}
+	}
+
+	// New cards are due straight away
+	let stats = get_tag_stats(&pool, &tag.get_id()).unwrap().unwrap();
+	assert_eq!(
+		stats,
+		TagStats {
+			item_count: 2,
+			card_count: 4,
+			due_count: 4,
+		}
+	);
+
+	// Reviewing one card schedules it for later, and suspending another
+	// takes it out of the due count
+	let cards = crate::repo::get_cards_for_item(&pool, &tagged[0].get_id()).unwrap();
+	crate::repo::record_review(&pool, &cards[0].get_id(), crate::models::Rating::Good)
+		.await
+		.unwrap();
+	crate::repo::set_card_suspended(&pool, &cards[1].get_id(), true)
+		.await
+		.unwrap();
+
+	let stats = get_tag_stats(&pool, &tag.get_id()).unwrap().unwrap();
+	assert_eq!(stats.card_count, 4);
+	assert_eq!(stats.due_count, 2);
+}
+
+#[tokio::test]
+async fn test_get_tag_stats_missing_tag() {
+	let pool = setup_test_db();
+
+	assert_eq!(get_tag_stats(&pool, &TagId::new()).unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_create_tag_with_parent() {
+	let pool = setup_test_db();
+
+	let parent = create_tag(&pool, "Language".to_string(), true)
+		.await
+		.unwrap();
+	let child = create_tag_with_parent(&pool, "Spanish".to_string(), true, Some(parent.get_id()))
+		.await
+		.unwrap();
+	assert_eq!(child.get_parent_id(), Some(parent.get_id()));
+	assert_eq!(
+		get_tag(&pool, &child.get_id()).unwrap().get_parent_id(),
+		Some(parent.get_id())
+	);
+
+	// A missing parent is an error, and no tag is created
+	let result =
+		create_tag_with_parent(&pool, "French".to_string(), true, Some(TagId::new())).await;
+	assert!(
+		result
+			.unwrap_err()
+			.to_string()
+			.contains("Parent tag not found")
+	);
+	assert_eq!(list_tags(&pool).unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_list_tags_for_item_with_ancestors() {
+	let pool = setup_test_db();
+
+	let item_type =
+		crate::repo::create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+			.await
+			.unwrap();
+	let item = crate::repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		serde_json::json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+
+	// Language > Spanish > Verbs, plus an unrelated tag
+	let language = create_tag(&pool, "Language".to_string(), true)
+		.await
+		.unwrap();
+	let spanish =
+		create_tag_with_parent(&pool, "Spanish".to_string(), true, Some(language.get_id()))
+			.await
+			.unwrap();
+	let verbs = create_tag_with_parent(&pool, "Verbs".to_string(), true, Some(spanish.get_id()))
+		.await
+		.unwrap();
+	create_tag(&pool, "Unrelated".to_string(), true)
+		.await
+		.unwrap();
+
+	// Tagging with both Verbs and Spanish still lists Spanish only once
+	add_tag_to_item(&pool, &verbs.get_id(), &item.get_id())
+		.await
+		.unwrap();
+	add_tag_to_item(&pool, &spanish.get_id(), &item.get_id())
+		.await
+		.unwrap();
+
+	let mut names: Vec<String> = list_tags_for_item_with_ancestors(&pool, &item.get_id())
+		.unwrap()
+		.iter()
+		.map(|t| t.get_name())
+		.collect();
+	names.sort();
+	assert_eq!(names, vec!["Language", "Spanish", "Verbs"]);
+
+	// The plain listing is unchanged
+	assert_eq!(list_tags_for_item(&pool, &item.get_id()).unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_get_tag_subtree() {
+	let pool = setup_test_db();
+
+	// Language > {Spanish > Verbs, French}, plus an unrelated tag
+	let language = create_tag(&pool, "Language".to_string(), true)
+		.await
+		.unwrap();
+	let spanish =
+		create_tag_with_parent(&pool, "Spanish".to_string(), true, Some(language.get_id()))
+			.await
+			.unwrap();
+	create_tag_with_parent(&pool, "Verbs".to_string(), true, Some(spanish.get_id()))
+		.await
+		.unwrap();
+	create_tag_with_parent(&pool, "French".to_string(), true, Some(language.get_id()))
+		.await
+		.unwrap();
+	create_tag(&pool, "Unrelated".to_string(), true)
+		.await
+		.unwrap();
+
+	// Each level comes before the next, siblings by name
+	let names: Vec<String> = get_tag_subtree(&pool, &language.get_id())
+		.unwrap()
+		.unwrap()
+		.iter()
+		.map(|t| t.get_name())
+		.collect();
+	assert_eq!(names, vec!["Language", "French", "Spanish", "Verbs"]);
+
+	let names: Vec<String> = get_tag_subtree(&pool, &spanish.get_id())
+		.unwrap()
+		.unwrap()
+		.iter()
+		.map(|t| t.get_name())
+		.collect();
+	assert_eq!(names, vec!["Spanish", "Verbs"]);
+
+	assert!(get_tag_subtree(&pool, &TagId::new()).unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_merge_tags_moves_children() {
+	let pool = setup_test_db();
+
+	// Root > Source > {Target, Child}
+	let root = create_tag(&pool, "Root".to_string(), true).await.unwrap();
+	let source = create_tag_with_parent(&pool, "Source".to_string(), true, Some(root.get_id()))
+		.await
+		.unwrap();
+	let target = create_tag_with_parent(&pool, "Target".to_string(), true, Some(source.get_id()))
+		.await
+		.unwrap();
+	let child = create_tag_with_parent(&pool, "Child".to_string(), true, Some(source.get_id()))
+		.await
+		.unwrap();
+
+	// The target takes the source's place, and the child moves under it
+	let merged = merge_tags(&pool, &source.get_id(), &target.get_id())
+		.await
+		.unwrap();
+	assert_eq!(merged.get_parent_id(), Some(root.get_id()));
+	assert_eq!(
+		get_tag(&pool, &child.get_id()).unwrap().get_parent_id(),
+		Some(target.get_id())
+	);
+
+	let names: Vec<String> = get_tag_subtree(&pool, &root.get_id())
+		.unwrap()
+		.unwrap()
+		.iter()
+		.map(|t| t.get_name())
+		.collect();
+	assert_eq!(names, vec!["Root", "Target", "Child"]);
+}
+
+#[tokio::test]
+async fn test_list_items_include_descendant_tags() {
+	let pool = setup_test_db();
+
+	let item_type = crate::repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let language = create_tag(&pool, "Language".to_string(), true)
+		.await
+		.unwrap();
+	let spanish =
+		create_tag_with_parent(&pool, "Spanish".to_string(), true, Some(language.get_id()))
+			.await
+			.unwrap();
+	let verbs = create_tag_with_parent(&pool, "Verbs".to_string(), true, Some(spanish.get_id()))
+		.await
+		.unwrap();
+	let other = create_tag(&pool, "Other".to_string(), true).await.unwrap();
+
+	for (title, tag) in [
+		("Language item", &language),
+		("Verbs item", &verbs),
+		("Other item", &other),
+	] {
+		let item = crate::repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			serde_json::json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		add_tag_to_item(&pool, &tag.get_id(), &item.get_id())
+			.await
+			.unwrap();
+	}
+
+	let titles = |include_descendant_tags: Option<bool>, tag_ids: Vec<TagId>| {
+		let query = GetQueryDto {
+			tag_ids,
+			include_descendant_tags,
+			..Default::default()
+		};
+		let mut titles: Vec<String> = crate::repo::list_items_with_filters(&pool, &query)
+			.unwrap()
+			.iter()
+			.map(|item| item.get_title())
+			.collect();
+		titles.sort();
+		titles
+	};
+
+	// Only the exact tag matches by default
+	assert_eq!(titles(None, vec![language.get_id()]), vec!["Language item"]);
+	// With descendants, the grandchild's item matches too
+	assert_eq!(
+		titles(Some(true), vec![language.get_id()]),
+		vec!["Language item", "Verbs item"]
+	);
+	// Every filter tag must still be satisfied
+	assert_eq!(
+		titles(Some(true), vec![spanish.get_id(), other.get_id()]),
+		Vec::<String>::new()
+	);
+	assert_eq!(
+		titles(Some(true), vec![spanish.get_id()]),
+		vec!["Verbs item"]
+	);
+
+	// Cards follow their items
+	let query = GetQueryDto {
+		tag_ids: vec![language.get_id()],
+		include_descendant_tags: Some(true),
+		..Default::default()
+	};
+	assert_eq!(
+		crate::repo::list_cards(&pool, &query).await.unwrap().len(),
+		2
+	);
+}
+
+#[tokio::test]
+async fn test_list_items_any_tag_with_descendant_tags() {
+	let pool = setup_test_db();
+
+	let item_type = crate::repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let spanish = create_tag(&pool, "Spanish".to_string(), true)
+		.await
+		.unwrap();
+	let verbs = create_tag_with_parent(&pool, "Verbs".to_string(), true, Some(spanish.get_id()))
+		.await
+		.unwrap();
+	let french = create_tag(&pool, "French".to_string(), true).await.unwrap();
+	let other = create_tag(&pool, "Other".to_string(), true).await.unwrap();
+
+	for (title, tag) in [
+		("Verbs item", &verbs),
+		("French item", &french),
+		("Other item", &other),
+	] {
+		let item = crate::repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			title.to_string(),
+			serde_json::json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+		add_tag_to_item(&pool, &tag.get_id(), &item.get_id())
+			.await
+			.unwrap();
+	}
+
+	let query = GetQueryDto {
+		tag_ids: vec![spanish.get_id(), french.get_id()],
+		tag_match: TagMatch::Any,
+		include_descendant_tags: Some(true),
+		..Default::default()
+	};
+	let mut titles: Vec<String> = crate::repo::list_items_with_filters(&pool, &query)
+		.unwrap()
+		.iter()
+		.map(|item| item.get_title())
+		.collect();
+	titles.sort();
+
+	assert_eq!(titles, vec!["French item", "Verbs item"]);
+}
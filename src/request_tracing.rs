@@ -0,0 +1,110 @@
+use axum::http::{Request, Response};
+use std::time::Duration;
+use tower_http::{
+	classify::{ServerErrorsAsFailures, SharedClassifier},
+	trace::{MakeSpan, OnResponse, TraceLayer},
+};
+use tracing::{Level, Span, field};
+
+/// Creates a span at a level only known at runtime
+///
+/// `tracing::span!` needs a constant level (it's baked into the callsite's
+/// static metadata), so we match over every level instead.
+macro_rules! span_at {
+	($level:expr, $($args:tt)+) => {
+		match $level {
+			Level::ERROR => tracing::span!(Level::ERROR, $($args)+),
+			Level::WARN => tracing::span!(Level::WARN, $($args)+),
+			Level::INFO => tracing::span!(Level::INFO, $($args)+),
+			Level::DEBUG => tracing::span!(Level::DEBUG, $($args)+),
+			Level::TRACE => tracing::span!(Level::TRACE, $($args)+),
+		}
+	};
+}
+
+/// Emits an event at a level only known at runtime (see [`span_at`])
+macro_rules! event_at {
+	($level:expr, $($args:tt)+) => {
+		match $level {
+			Level::ERROR => tracing::event!(Level::ERROR, $($args)+),
+			Level::WARN => tracing::event!(Level::WARN, $($args)+),
+			Level::INFO => tracing::event!(Level::INFO, $($args)+),
+			Level::DEBUG => tracing::event!(Level::DEBUG, $($args)+),
+			Level::TRACE => tracing::event!(Level::TRACE, $($args)+),
+		}
+	};
+}
+
+/// The `TraceLayer` type produced by [`trace_layer`]
+pub type RequestTraceLayer =
+	TraceLayer<SharedClassifier<ServerErrorsAsFailures>, RequestSpan, (), RequestSpan>;
+
+/// Builds the per-request span and completion event for the HTTP layer
+///
+/// Every request gets a `request` span carrying `method`, `path`, `status`
+/// and `latency_ms`. The last two are recorded once the response is ready,
+/// alongside a "finished processing request" event, so each request ends up
+/// as a single line in the log with everything needed to find slow endpoints.
+///
+/// Handlers run inside this span, so the spans created by their
+/// `#[instrument]` attributes nest beneath it.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestSpan {
+	level: Level,
+}
+
+impl RequestSpan {
+	/// Creates a new `RequestSpan` that logs at the given level
+	pub fn new(level: Level) -> Self {
+		RequestSpan { level }
+	}
+}
+
+impl<B> MakeSpan<B> for RequestSpan {
+	fn make_span(&mut self, request: &Request<B>) -> Span {
+		span_at!(
+			self.level,
+			"request",
+			method = %request.method(),
+			path = %request.uri().path(),
+			status = field::Empty,
+			latency_ms = field::Empty,
+		)
+	}
+}
+
+impl<B> OnResponse<B> for RequestSpan {
+	fn on_response(self, response: &Response<B>, latency: Duration, span: &Span) {
+		let status = response.status().as_u16();
+		let latency_ms = latency.as_millis() as u64;
+
+		span.record("status", status);
+		span.record("latency_ms", latency_ms);
+
+		event_at!(
+			self.level,
+			status,
+			latency_ms,
+			"finished processing request"
+		);
+	}
+}
+
+/// Creates the request tracing layer for the application router
+///
+/// Server errors (5xx responses) are additionally reported at `ERROR` level
+/// by `tower-http`'s default failure handler, regardless of `level`.
+///
+/// ### Arguments
+///
+/// * `level` - The level to emit request spans and completion events at
+///
+/// ### Returns
+///
+/// A `TraceLayer` that wraps each request in a `request` span
+pub fn trace_layer(level: Level) -> RequestTraceLayer {
+	TraceLayer::new_for_http()
+		.make_span_with(RequestSpan::new(level))
+		.on_request(())
+		.on_response(RequestSpan::new(level))
+}
@@ -1,5 +1,15 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+	backup_log (id) {
+		id -> Integer,
+		attempted_at -> Timestamp,
+		backup_type -> Text,
+		success -> Bool,
+		error -> Nullable<Text>,
+	}
+}
+
 diesel::table! {
 	card_fetched_events (item_type_id, order_index) {
 		item_type_id -> Text,
@@ -23,6 +33,13 @@ diesel::table! {
 		card_data -> Nullable<Text>,
 		updated_at -> Timestamp,
 		cache_updated_at -> Nullable<Timestamp>,
+		buried_until -> Nullable<Timestamp>,
+		note -> Nullable<Text>,
+		direction -> Nullable<Text>,
+		version -> Integer,
+		created_at -> Timestamp,
+		difficulty -> Nullable<Float>,
+		lapses -> Integer,
 	}
 }
 
@@ -50,6 +67,9 @@ diesel::table! {
 		created_at -> Timestamp,
 		review_function -> Text,
 		updated_at -> Timestamp,
+		item_data_schema -> Nullable<Text>,
+		default_priority -> Nullable<Float>,
+		scheduler_config -> Nullable<Text>,
 	}
 }
 
@@ -77,6 +97,18 @@ diesel::table! {
 		card_id -> Text,
 		rating -> Integer,
 		review_timestamp -> Timestamp,
+		session_id -> Nullable<Text>,
+		cram -> Bool,
+		response -> Nullable<Text>,
+		priority_before -> Nullable<Float>,
+	}
+}
+
+diesel::table! {
+	sessions (id) {
+		id -> Text,
+		started_at -> Timestamp,
+		ended_at -> Nullable<Timestamp>,
 	}
 }
 
@@ -86,6 +118,8 @@ diesel::table! {
 		name -> Text,
 		created_at -> Timestamp,
 		visible -> Bool,
+		updated_at -> Timestamp,
+		parent_id -> Nullable<Text>,
 	}
 }
 
@@ -95,8 +129,10 @@ diesel::joinable!(item_tags -> items (item_id));
 diesel::joinable!(item_tags -> tags (tag_id));
 diesel::joinable!(items -> item_types (item_type));
 diesel::joinable!(reviews -> cards (card_id));
+diesel::joinable!(reviews -> sessions (session_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+	backup_log,
 	card_fetched_events,
 	cards,
 	item_relations,
@@ -105,5 +141,6 @@ diesel::allow_tables_to_appear_in_same_query!(
 	items,
 	metadata,
 	reviews,
+	sessions,
 	tags,
 );
@@ -1,9 +1,8 @@
-use crate::models::{CardId, ItemId, ItemTypeId, ReviewId, TagId};
+use crate::models::{CardId, ItemId, ItemTypeId, Rating, ReviewId, TagId};
 use crate::*;
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
 use chrono::{DateTime, Utc};
-use diesel::connection::SimpleConnection;
 use diesel::prelude::*;
 use proptest::prelude::*;
 use serde_json::{Number, Value};
@@ -14,7 +13,7 @@ use tower::ServiceExt;
 ///
 /// This function:
 /// 1. Creates an in-memory SQLite database
-/// 2. Enables foreign key constraints
+/// 2. Gets a connection (foreign keys are enabled by the pool)
 /// 3. Runs all migrations to set up the schema
 ///
 /// ### Returns
@@ -33,9 +32,6 @@ pub fn setup_test_db() -> Arc<db::DbPool> {
 	// Get a connection from the pool
 	let mut conn = pool.get().expect("Failed to get connection");
 
-	// Enable foreign key constraints for SQLite
-	conn.batch_execute("PRAGMA foreign_keys = ON").unwrap();
-
 	// Run all migrations to set up the schema
 	run_migrations(&mut conn);
 
@@ -168,6 +164,16 @@ pub fn arb_suspended_filter() -> impl Strategy<Value = SuspendedFilter> {
 	]
 }
 
+/// Generates an arbitrary CardState variant
+pub fn arb_card_state() -> impl Strategy<Value = CardState> {
+	prop_oneof![
+		Just(CardState::New),
+		Just(CardState::Due),
+		Just(CardState::Learning),
+		Just(CardState::Review),
+	]
+}
+
 /// Mutable card state fields for property testing
 #[derive(Debug, Clone)]
 pub struct CardMutations {
@@ -215,9 +221,9 @@ pub fn arb_any_f32() -> impl Strategy<Value = f32> {
 	proptest::num::f32::ANY
 }
 
-/// Generates a valid review rating in [1, 4]
-pub fn arb_rating() -> impl Strategy<Value = i32> {
-	1i32..=4i32
+/// Generates a valid review rating
+pub fn arb_rating() -> impl Strategy<Value = Rating> {
+	prop::sample::select(Rating::ALL.to_vec())
 }
 
 /// Generates an invalid review rating outside [1, 4]
@@ -1,4 +1,7 @@
-use chrono::{NaiveDateTime, Timelike, Utc};
+use chrono::{
+	DateTime, Days, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc,
+};
+use chrono_tz::Tz;
 
 /// Returns the current UTC time truncated to millisecond precision
 ///
@@ -17,3 +20,373 @@ pub fn truncate_to_ms(t: NaiveDateTime) -> NaiveDateTime {
 	t.with_nanosecond((t.nanosecond() / 1_000_000) * 1_000_000)
 		.unwrap_or(t)
 }
+
+/// Returns the date it is in `tz` at `now`
+///
+/// This is "today" for everything that happens once a day.
+pub fn local_today(now: DateTime<Utc>, tz: Tz) -> NaiveDate {
+	now.with_timezone(&tz).date_naive()
+}
+
+/// Returns the first instant of `date` in `tz`
+///
+/// This is normally local midnight, but if a DST change skips midnight the
+/// day starts at the first local time that does exist.
+pub fn start_of_local_day(date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+	let midnight = date.and_time(NaiveTime::MIN);
+	(0..=180)
+		.step_by(15)
+		.find_map(|minutes| {
+			tz.from_local_datetime(&(midnight + chrono::Duration::minutes(minutes)))
+				.earliest()
+		})
+		.map(|start| start.with_timezone(&Utc))
+		.unwrap_or_else(|| midnight.and_utc())
+}
+
+/// Returns the bounds of the local day in `tz` containing `now`
+///
+/// ### Arguments
+///
+/// * `now` - The instant whose day to find
+/// * `tz` - The timezone days are counted in
+///
+/// ### Returns
+///
+/// The start of that day (inclusive) and the start of the next (exclusive)
+pub fn local_today_bounds(now: DateTime<Utc>, tz: Tz) -> (DateTime<Utc>, DateTime<Utc>) {
+	let today = local_today(now, tz);
+	(
+		start_of_local_day(today, tz),
+		start_of_local_day(today + Days::new(1), tz),
+	)
+}
+
+/// Returns the start of the local day in `tz` after `now`
+pub fn start_of_next_local_day(now: DateTime<Utc>, tz: Tz) -> DateTime<Utc> {
+	local_today_bounds(now, tz).1
+}
+
+/// Parses a due date given as an RFC 3339 timestamp or a bare `YYYY-MM-DD`
+///
+/// A bare date means the start of that day in `tz`, so something due "on"
+/// a date is due from the moment that day begins locally.
+///
+/// ### Arguments
+///
+/// * `value` - The due date to parse
+/// * `tz` - The timezone bare dates are in
+///
+/// ### Returns
+///
+/// The instant the due date falls at, or None if `value` is neither format
+pub fn parse_due_date(value: &str, tz: Tz) -> Option<DateTime<Utc>> {
+	if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+		return Some(start_of_local_day(date, tz));
+	}
+	DateTime::parse_from_rfc3339(value)
+		.ok()
+		.map(|due| due.with_timezone(&Utc))
+}
+
+/// Parses a short duration such as `30s`, `10m`, `1h` or `1d`
+///
+/// ### Returns
+///
+/// The duration, or None if `value` isn't a positive whole number followed by
+/// one of the units `s`, `m`, `h` or `d`
+pub fn parse_step_duration(value: &str) -> Option<chrono::Duration> {
+	let digits = value.find(|c: char| !c.is_ascii_digit())?;
+	let (count, unit) = value.split_at(digits);
+	let count: i64 = count.parse().ok().filter(|count| *count > 0)?;
+	match unit {
+		"s" => chrono::Duration::try_seconds(count),
+		"m" => chrono::Duration::try_minutes(count),
+		"h" => chrono::Duration::try_hours(count),
+		"d" => chrono::Duration::try_days(count),
+		_ => None,
+	}
+}
+
+/// Describes when something is next due, relative to `now`, for people
+///
+/// The time left is rounded to the nearest minute, hour or day, whichever is
+/// the largest unit it reaches, so an interval of 2 days 23 hours reads as
+/// "3 days". Shared by the card JSON's `due_in` and the CLI's human output.
+///
+/// ### Arguments
+///
+/// * `due` - When it's next due, or None for a new card that hasn't been
+///   scheduled by a review yet
+/// * `now` - The time to describe it relative to
+///
+/// ### Returns
+///
+/// "new", "overdue" (when `due` is at or before `now`), or a count such as
+/// "1 minute", "2 hours" or "3 days"
+pub fn format_due_in(due: Option<DateTime<Utc>>, now: DateTime<Utc>) -> String {
+	let Some(due) = due else {
+		return "new".to_string();
+	};
+	let seconds = (due - now).num_seconds();
+	if seconds <= 0 {
+		return "overdue".to_string();
+	}
+
+	let plural = |count: i64, unit: &str| match count {
+		1 => format!("1 {}", unit),
+		count => format!("{} {}s", count, unit),
+	};
+	let rounded = |unit_seconds: i64| (seconds + unit_seconds / 2) / unit_seconds;
+	match (rounded(60), rounded(3_600)) {
+		(minutes, _) if minutes < 60 => plural(minutes.max(1), "minute"),
+		(_, hours) if hours < 24 => plural(hours, "hour"),
+		_ => plural(rounded(86_400), "day"),
+	}
+}
+
+/// How often a recurring todo comes round, e.g. every 2 weeks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recurrence {
+	/// Every this many days
+	Days(u32),
+	/// Every this many weeks
+	Weeks(u32),
+	/// Every this many calendar months
+	Months(u32),
+}
+
+/// The longest a recurrence can be, in days, weeks and months: 100 years
+const MAX_RECURRENCE_DAYS: u32 = 36_500;
+const MAX_RECURRENCE_WEEKS: u32 = 5_200;
+const MAX_RECURRENCE_MONTHS: u32 = 1_200;
+
+impl Recurrence {
+	/// Returns when the next occurrence after `from` is
+	///
+	/// Months are calendar months, so a monthly todo done on the 31st is next
+	/// due on the last day of a shorter month.
+	///
+	/// Returns `None` if the next occurrence is past the latest representable
+	/// date.
+	pub fn next_after(&self, from: DateTime<Utc>) -> Option<DateTime<Utc>> {
+		match *self {
+			Recurrence::Days(n) => from.checked_add_days(Days::new(n.into())),
+			Recurrence::Weeks(n) => from.checked_add_days(Days::new(u64::from(n) * 7)),
+			Recurrence::Months(n) => from.checked_add_months(Months::new(n)),
+		}
+	}
+}
+
+impl std::str::FromStr for Recurrence {
+	type Err = String;
+
+	/// Parses a recurrence such as `1d`, `2w` or `3mo`, of at most 100 years
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let invalid = || format!("invalid recurrence {:?}, expected e.g. 1d, 2w or 3mo", s);
+		let digits = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+		let (count, unit) = s.split_at(digits);
+		let count: u32 = count.parse().map_err(|_| invalid())?;
+		if count == 0 {
+			return Err(invalid());
+		}
+		let (recurrence, max) = match unit {
+			"d" => (Recurrence::Days(count), MAX_RECURRENCE_DAYS),
+			"w" => (Recurrence::Weeks(count), MAX_RECURRENCE_WEEKS),
+			"mo" => (Recurrence::Months(count), MAX_RECURRENCE_MONTHS),
+			_ => return Err(invalid()),
+		};
+		if count > max {
+			return Err(format!(
+				"invalid recurrence {:?}, it can be at most 100 years",
+				s
+			));
+		}
+		Ok(recurrence)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn utc(s: &str) -> DateTime<Utc> {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn test_local_today_bounds_utc() {
+		let (start, end) = local_today_bounds(utc("2026-03-10T23:30:00Z"), Tz::UTC);
+		assert_eq!(start, utc("2026-03-10T00:00:00Z"));
+		assert_eq!(end, utc("2026-03-11T00:00:00Z"));
+	}
+
+	#[test]
+	fn test_local_today_bounds_shift_with_timezone() {
+		// 23:30 UTC is already the next morning in Melbourne (UTC+11 in March)
+		let now = utc("2026-03-10T23:30:00Z");
+		let tz: Tz = "Australia/Melbourne".parse().unwrap();
+		assert_eq!(
+			local_today(now, tz),
+			NaiveDate::from_ymd_opt(2026, 3, 11).unwrap()
+		);
+		let (start, end) = local_today_bounds(now, tz);
+		assert_eq!(start, utc("2026-03-10T13:00:00Z"));
+		assert_eq!(end, utc("2026-03-11T13:00:00Z"));
+
+		// And it is still the previous evening in New York (UTC-4 after DST starts)
+		let tz: Tz = "America/New_York".parse().unwrap();
+		assert_eq!(
+			local_today(now, tz),
+			NaiveDate::from_ymd_opt(2026, 3, 10).unwrap()
+		);
+		let (start, end) = local_today_bounds(now, tz);
+		assert_eq!(start, utc("2026-03-10T04:00:00Z"));
+		assert_eq!(end, utc("2026-03-11T04:00:00Z"));
+	}
+
+	#[test]
+	fn test_local_today_bounds_across_dst_change() {
+		// New York springs forward on 2026-03-08, so that day is 23 hours long
+		let tz: Tz = "America/New_York".parse().unwrap();
+		let (start, end) = local_today_bounds(utc("2026-03-08T12:00:00Z"), tz);
+		assert_eq!(start, utc("2026-03-08T05:00:00Z"));
+		assert_eq!(end, utc("2026-03-09T04:00:00Z"));
+	}
+
+	#[test]
+	fn test_local_today_bounds_when_midnight_is_skipped() {
+		// Santiago springs forward at midnight on 2026-09-06, so the day
+		// starts at 01:00 local time
+		let tz: Tz = "America/Santiago".parse().unwrap();
+		let (start, _) = local_today_bounds(utc("2026-09-06T12:00:00Z"), tz);
+		assert_eq!(start, utc("2026-09-06T04:00:00Z"));
+	}
+
+	#[test]
+	fn test_parse_due_date() {
+		let tz: Tz = "Australia/Melbourne".parse().unwrap();
+		assert_eq!(
+			parse_due_date("2026-03-11", tz),
+			Some(utc("2026-03-10T13:00:00Z"))
+		);
+		assert_eq!(
+			parse_due_date("2026-03-11", Tz::UTC),
+			Some(utc("2026-03-11T00:00:00Z"))
+		);
+		assert_eq!(
+			parse_due_date("2026-03-11T09:30:00+11:00", Tz::UTC),
+			Some(utc("2026-03-10T22:30:00Z"))
+		);
+		assert_eq!(parse_due_date("next tuesday", tz), None);
+		assert_eq!(parse_due_date("2026-02-30", tz), None);
+	}
+
+	#[test]
+	fn test_parse_step_duration() {
+		assert_eq!(
+			parse_step_duration("30s"),
+			Some(chrono::Duration::seconds(30))
+		);
+		assert_eq!(
+			parse_step_duration("10m"),
+			Some(chrono::Duration::minutes(10))
+		);
+		assert_eq!(parse_step_duration("1h"), Some(chrono::Duration::hours(1)));
+		assert_eq!(parse_step_duration("2d"), Some(chrono::Duration::days(2)));
+		for invalid in ["", "m", "0m", "10", "10mo", "1.5h", "-1m", " 1m"] {
+			assert_eq!(parse_step_duration(invalid), None, "{:?}", invalid);
+		}
+	}
+
+	#[test]
+	fn test_parse_recurrence() {
+		assert_eq!("1d".parse(), Ok(Recurrence::Days(1)));
+		assert_eq!("2w".parse(), Ok(Recurrence::Weeks(2)));
+		assert_eq!("3mo".parse(), Ok(Recurrence::Months(3)));
+		assert_eq!("36500d".parse(), Ok(Recurrence::Days(36_500)));
+		assert_eq!("1200mo".parse(), Ok(Recurrence::Months(1_200)));
+		for invalid in [
+			"",
+			"d",
+			"0d",
+			"1",
+			"1m",
+			"1 d",
+			"-1d",
+			"1.5d",
+			"36501d",
+			"5201w",
+			"1201mo",
+			"4294967295d",
+			"4294967296d",
+		] {
+			assert!(
+				invalid.parse::<Recurrence>().is_err(),
+				"{:?} should be rejected",
+				invalid
+			);
+		}
+	}
+
+	#[test]
+	fn test_recurrence_next_after() {
+		let from = utc("2026-01-31T08:00:00Z");
+		assert_eq!(
+			Recurrence::Days(1).next_after(from),
+			Some(utc("2026-02-01T08:00:00Z"))
+		);
+		assert_eq!(
+			Recurrence::Weeks(2).next_after(from),
+			Some(utc("2026-02-14T08:00:00Z"))
+		);
+		// There's no February 31st, so the month ends the interval
+		assert_eq!(
+			Recurrence::Months(1).next_after(from),
+			Some(utc("2026-02-28T08:00:00Z"))
+		);
+	}
+
+	#[test]
+	fn test_recurrence_next_after_overflow() {
+		let from = DateTime::<Utc>::MAX_UTC - chrono::Duration::days(1);
+		assert_eq!(Recurrence::Days(2).next_after(from), None);
+		assert_eq!(Recurrence::Weeks(u32::MAX).next_after(from), None);
+		assert_eq!(Recurrence::Months(1).next_after(from), None);
+	}
+
+	#[test]
+	fn test_format_due_in() {
+		let now = utc("2026-03-10T12:00:00Z");
+		let due_in = |due: &str| format_due_in(Some(utc(due)), now);
+
+		assert_eq!(format_due_in(None, now), "new");
+
+		// Overdue, including due right now
+		assert_eq!(due_in("2026-03-09T12:00:00Z"), "overdue");
+		assert_eq!(due_in("2026-03-10T12:00:00Z"), "overdue");
+
+		// Minutes, at least one
+		assert_eq!(due_in("2026-03-10T12:00:10Z"), "1 minute");
+		assert_eq!(due_in("2026-03-10T12:45:00Z"), "45 minutes");
+
+		// Hours
+		assert_eq!(due_in("2026-03-10T13:00:00Z"), "1 hour");
+		assert_eq!(due_in("2026-03-10T14:20:00Z"), "2 hours");
+		assert_eq!(due_in("2026-03-11T10:00:00Z"), "22 hours");
+
+		// Days, rounded to the nearest
+		assert_eq!(due_in("2026-03-11T12:00:00Z"), "1 day");
+		assert_eq!(due_in("2026-03-13T11:00:00Z"), "3 days");
+		assert_eq!(due_in("2026-04-09T12:00:00Z"), "30 days");
+	}
+
+	#[test]
+	fn test_start_of_next_local_day() {
+		let tz: Tz = "Australia/Melbourne".parse().unwrap();
+		assert_eq!(
+			start_of_next_local_day(utc("2026-03-10T23:30:00Z"), tz),
+			utc("2026-03-11T13:00:00Z")
+		);
+	}
+}
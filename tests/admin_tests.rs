@@ -0,0 +1,234 @@
+/// Integration tests for the admin maintenance routes
+///
+/// This file contains tests for the `/admin` routes:
+/// - Vacuuming reports the database size before and after
+/// - The migration status lists the applied migrations, with none pending
+/// - Failed periodic backups show up in the backup log
+/// - Backfilling lapses corrects cards' lapse counts
+/// - The route isn't served unless the admin setting is turned on
+use axum::{
+	Router,
+	body::{Body, to_bytes},
+	http::{Request, StatusCode},
+};
+use hippocampus::{
+	AppOptions, create_app_with_options,
+	db::{DbPool, init_pool},
+	dto::{BackfillLapsesResult, MigrationStatus, VacuumResult},
+	models::BackupLogEntry,
+	repo,
+};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+/// Creates a test application with the admin routes turned on or off
+///
+/// ### Arguments
+///
+/// * `admin` - Whether the application should serve the admin routes
+///
+/// ### Returns
+///
+/// An Axum Router connected to a fresh in-memory database, and the pool
+/// behind it
+fn create_admin_test_app(admin: bool) -> (Router, Arc<DbPool>) {
+	let unique_id = uuid::Uuid::new_v4();
+	let database_url = format!("file:test_{}?mode=memory&cache=shared", unique_id);
+	let pool = Arc::new(init_pool(&database_url));
+
+	let conn = &mut pool.get().unwrap();
+	hippocampus::run_migrations(conn);
+
+	let app = create_app_with_options(
+		pool.clone(),
+		AppOptions {
+			admin,
+			..Default::default()
+		},
+	);
+	(app, pool)
+}
+
+/// Sends `POST /admin/vacuum`
+async fn vacuum(app: Router) -> axum::response::Response {
+	let request = Request::builder()
+		.uri("/admin/vacuum")
+		.method("POST")
+		.body(Body::empty())
+		.unwrap();
+	app.oneshot(request).await.unwrap()
+}
+
+/// Tests that vacuuming reports the database size
+///
+/// This test verifies:
+/// 1. `POST /admin/vacuum` returns 200 when admin routes are on
+/// 2. The response has the sizes before and after, and the difference
+#[tokio::test]
+async fn test_vacuum() {
+	let (app, _) = create_admin_test_app(true);
+
+	let response = vacuum(app).await;
+	assert_eq!(response.status(), StatusCode::OK);
+
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let result: VacuumResult = serde_json::from_slice(&body).unwrap();
+	assert!(result.size_before_bytes > 0);
+	assert!(result.size_after_bytes > 0);
+	assert_eq!(
+		result.freed_bytes,
+		(result.size_before_bytes - result.size_after_bytes).max(0)
+	);
+}
+
+/// Tests that the migration status is reported after migrating
+///
+/// This test verifies:
+/// 1. `GET /admin/migrations` returns 200 when admin routes are on
+/// 2. The applied list isn't empty after `run_migrations`, and nothing is
+///    pending
+#[tokio::test]
+async fn test_migration_status() {
+	let (app, _) = create_admin_test_app(true);
+
+	let request = Request::builder()
+		.uri("/admin/migrations")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.oneshot(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let status: MigrationStatus = serde_json::from_slice(&body).unwrap();
+	assert!(!status.applied.is_empty());
+	assert!(status.pending.is_empty());
+	assert!(!status.has_pending);
+}
+
+/// Tests that the admin routes are off by default
+///
+/// This test verifies:
+/// 1. `POST /admin/vacuum` is a 404 without the admin setting
+#[tokio::test]
+async fn test_admin_disabled_by_default() {
+	let (app, _) = create_admin_test_app(false);
+
+	let response = vacuum(app).await;
+	assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// Tests that failed periodic backups are listed in the backup log
+///
+/// This test verifies:
+/// 1. `GET /admin/backups` lists nothing before any periodic backup
+/// 2. A periodic backup that can't be written is listed as a failure, with
+///    its error
+/// 3. A later successful backup is listed first
+#[tokio::test]
+async fn test_list_backups() {
+	let (app, pool) = create_admin_test_app(true);
+
+	let list_backups = async |app: &Router| {
+		let request = Request::builder()
+			.uri("/admin/backups")
+			.method("GET")
+			.body(Body::empty())
+			.unwrap();
+		let response = app.clone().oneshot(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		serde_json::from_slice::<Vec<BackupLogEntry>>(&body).unwrap()
+	};
+	assert!(list_backups(&app).await.is_empty());
+
+	// A file where the backups directory should be stops any backup
+	let dir = tempfile::tempdir().unwrap();
+	let database_path = dir.path().join("hippocampus.db");
+	drop(hippocampus::db::init_pool(database_path.to_str().unwrap()));
+	std::fs::write(dir.path().join("backups"), b"not a directory").unwrap();
+	let database_path = database_path.to_str().unwrap();
+	assert!(
+		hippocampus::run_periodic_backup(&pool, database_path, 5)
+			.await
+			.is_err()
+	);
+
+	let entries = list_backups(&app).await;
+	assert_eq!(entries.len(), 1);
+	assert_eq!(entries[0].get_backup_type(), "periodic");
+	assert!(!entries[0].get_success());
+	assert!(
+		entries[0]
+			.get_error()
+			.unwrap()
+			.contains("Failed to create backup file")
+	);
+
+	// Once the directory can be created, backups succeed again
+	std::fs::remove_file(dir.path().join("backups")).unwrap();
+	assert_eq!(
+		hippocampus::run_periodic_backup(&pool, database_path, 5).await,
+		Ok(true)
+	);
+
+	let entries = list_backups(&app).await;
+	assert_eq!(entries.len(), 2);
+	assert!(entries[0].get_success());
+	assert_eq!(entries[0].get_error(), None);
+	assert!(!entries[1].get_success());
+}
+
+/// Tests that backfilling lapses corrects cards' lapse counts
+///
+/// This test verifies:
+/// 1. `POST /admin/backfill-lapses` returns 200 when admin routes are on
+/// 2. A card whose lapse count doesn't match its review history is corrected
+/// 3. Running it again corrects nothing
+#[tokio::test]
+async fn test_backfill_lapses() {
+	use diesel::prelude::*;
+	use hippocampus::schema::cards;
+
+	let (app, pool) = create_admin_test_app(true);
+	let item_type = repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Lapsed".to_string(),
+		serde_json::json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let card = repo::get_cards_for_item(&pool, &item.get_id()).unwrap()[0].clone();
+
+	// The card has never been reviewed, so it can't have lapsed
+	diesel::update(cards::table.find(card.get_id()))
+		.set(cards::lapses.eq(3))
+		.execute(&mut pool.get().unwrap())
+		.unwrap();
+
+	let backfill = async |app: &Router| {
+		let request = Request::builder()
+			.uri("/admin/backfill-lapses")
+			.method("POST")
+			.body(Body::empty())
+			.unwrap();
+		let response = app.clone().oneshot(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		serde_json::from_slice::<BackfillLapsesResult>(&body).unwrap()
+	};
+
+	assert_eq!(backfill(&app).await.cards_updated, 1);
+	assert_eq!(
+		repo::get_card_raw(&pool, &card.get_id())
+			.unwrap()
+			.unwrap()
+			.get_mature_lapses(),
+		0
+	);
+	assert_eq!(backfill(&app).await.cards_updated, 0);
+}
@@ -0,0 +1,139 @@
+/// Integration tests for request body size limits
+///
+/// This file contains tests for the configurable body limits:
+/// - Bodies over `max_body_size` get a 413 with the `payload_too_large` code
+/// - The default limit still accepts ordinary requests
+/// - The review import accepts bodies up to its own, higher, limit
+use axum::{
+	Router,
+	body::{Body, to_bytes},
+	http::{Request, StatusCode},
+};
+use hippocampus::{AppOptions, create_app_with_options, db::init_pool};
+use serde_json::json;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+mod common;
+use common::create_test_app;
+
+/// The body limit the tests set, small enough to exceed cheaply
+const MAX_BODY_SIZE: usize = 1024;
+
+/// Creates a test application with a small body limit
+///
+/// ### Arguments
+///
+/// * `max_import_body_size` - The body limit for the review import
+///
+/// ### Returns
+///
+/// An Axum Router connected to a fresh in-memory database
+fn create_body_limit_test_app(max_import_body_size: usize) -> Router {
+	let unique_id = uuid::Uuid::new_v4();
+	let database_url = format!("file:test_{}?mode=memory&cache=shared", unique_id);
+	let pool = Arc::new(init_pool(&database_url));
+
+	let conn = &mut pool.get().unwrap();
+	hippocampus::run_migrations(conn);
+
+	create_app_with_options(
+		pool,
+		AppOptions {
+			max_body_size: MAX_BODY_SIZE,
+			max_import_body_size,
+			..Default::default()
+		},
+	)
+}
+
+/// Posts a JSON body to the app
+///
+/// ### Arguments
+///
+/// * `app` - The application to send the request to
+/// * `uri` - The path to post to
+/// * `body` - The request body
+///
+/// ### Returns
+///
+/// The response's status and its body parsed as JSON (null if it isn't JSON)
+async fn post_json(app: Router, uri: &str, body: String) -> (StatusCode, serde_json::Value) {
+	let request = Request::builder()
+		.uri(uri)
+		.method("POST")
+		.header("Content-Type", "application/json")
+		.body(Body::from(body))
+		.unwrap();
+	let response = app.oneshot(request).await.unwrap();
+	let status = response.status();
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	(
+		status,
+		serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null),
+	)
+}
+
+/// Tests that a body over the limit is refused with a 413
+///
+/// This test verifies:
+/// 1. An item type whose name pushes the body past `max_body_size` gets a 413
+/// 2. The error body carries the `payload_too_large` code
+/// 3. A body under the limit is still accepted
+#[tokio::test]
+async fn test_oversized_body_is_rejected() {
+	let app = create_body_limit_test_app(MAX_BODY_SIZE * 4);
+
+	let oversized = json!({
+		"name": "x".repeat(MAX_BODY_SIZE),
+		"review_function": "fsrs"
+	});
+	let (status, body) = post_json(app.clone(), "/item_types", oversized.to_string()).await;
+	assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+	assert_eq!(body["code"], "payload_too_large");
+
+	let small = json!({"name": "Basic", "review_function": "fsrs"});
+	let (status, _) = post_json(app, "/item_types", small.to_string()).await;
+	assert_eq!(status, StatusCode::OK);
+}
+
+/// Tests that the review import has its own, higher, limit
+///
+/// This test verifies:
+/// 1. An import body over `max_body_size` but under `max_import_body_size`
+///    isn't refused for its size
+/// 2. An import body over `max_import_body_size` gets a 413
+#[tokio::test]
+async fn test_import_has_its_own_limit() {
+	let app = create_body_limit_test_app(MAX_BODY_SIZE * 4);
+
+	// An empty import padded with whitespace, so only its size matters
+	let padded = |size: usize| format!("[{}]", " ".repeat(size));
+
+	let (status, _) = post_json(app.clone(), "/reviews/import", padded(MAX_BODY_SIZE * 2)).await;
+	assert_ne!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+	let (status, body) = post_json(app, "/reviews/import", padded(MAX_BODY_SIZE * 8)).await;
+	assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+	assert_eq!(body["code"], "payload_too_large");
+}
+
+/// Tests that the default limit accepts bodies larger than axum's own default
+/// but refuses ones larger than 16 MiB
+///
+/// This test verifies:
+/// 1. A 4 MiB body isn't refused for its size
+/// 2. A body over 16 MiB gets a 413
+#[tokio::test]
+async fn test_default_body_limit() {
+	let app = create_test_app();
+
+	let body =
+		|size: usize| json!({"name": "x".repeat(size), "review_function": "fsrs"}).to_string();
+
+	let (status, _) = post_json(app.clone(), "/item_types", body(4 * 1024 * 1024)).await;
+	assert_ne!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+	let (status, _) = post_json(app, "/item_types", body(17 * 1024 * 1024)).await;
+	assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
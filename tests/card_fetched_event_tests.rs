@@ -103,7 +103,7 @@ async fn register_list_delete_roundtrip() {
 #[tokio::test]
 async fn register_rejects_negative_order_index() {
 	// A negative `order_index` can't fit in the `OrderIndex(u16)` newtype,
-	// so axum's JSON extractor rejects it before the handler runs. Axum
+	// so the JSON extractor rejects it before the handler runs. It
 	// surfaces `serde` parse failures as 422 Unprocessable Entity; we don't
 	// particularly care *which* 4xx status it is as long as the request
 	// doesn't make it into the DB.
@@ -29,7 +29,13 @@ async fn test_update_card_priority() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Get the cards for the item
 	let cards = get_cards_for_item(&mut app, &item.get_id()).await;
@@ -86,7 +92,13 @@ async fn test_update_card_priority_boundary_values() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Get the cards for the item
 	let cards = get_cards_for_item(&mut app, &item.get_id()).await;
@@ -146,8 +158,10 @@ async fn test_update_card_priority_boundary_values() {
 /// Tests updating a card's priority with invalid values
 ///
 /// This test verifies:
-/// 1. A priority value below 0.0 is rejected with a 400 status
+/// 1. A priority value below 0.0 is rejected with a 400 status and the
+///    `invalid_priority` code
 /// 2. A priority value above 1.0 is rejected with a 400 status
+/// 3. A priority value that isn't a number is rejected with a 422 status
 #[tokio::test]
 async fn test_update_card_priority_invalid_values() {
 	// Create our test app
@@ -157,7 +171,13 @@ async fn test_update_card_priority_invalid_values() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Get the cards for the item
 	let cards = get_cards_for_item(&mut app, &item.get_id()).await;
@@ -178,6 +198,9 @@ async fn test_update_card_priority_invalid_values() {
 
 	let response = app.call(request).await.unwrap();
 	assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let error: Value = serde_json::from_slice(&body).unwrap();
+	assert_eq!(error["code"], "invalid_priority");
 
 	// Test priority above maximum (> 1.0)
 	let too_high_priority = 1.1_f32;
@@ -192,12 +215,28 @@ async fn test_update_card_priority_invalid_values() {
 
 	let response = app.call(request).await.unwrap();
 	assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+	// Test a priority that isn't a number at all
+	let request = Request::builder()
+		.uri(format!("/cards/{}/priority", card.get_id()))
+		.method("PATCH")
+		.header("Content-Type", "application/json")
+		.body(Body::from(r#""high""#))
+		.unwrap();
+
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let error: Value = serde_json::from_slice(&body).unwrap();
+	assert!(error["error"].is_string());
+	assert_eq!(error["code"], "invalid_json");
 }
 
 /// Tests updating the priority of a non-existent card
 ///
 /// This test verifies:
 /// 1. Attempting to update a non-existent card returns a 404 status
+/// 2. The error body carries the `card_not_found` code
 #[tokio::test]
 async fn test_update_nonexistent_card_priority() {
 	// Create our test app
@@ -219,4 +258,9 @@ async fn test_update_nonexistent_card_priority() {
 
 	// Check that the response has a 404 Not Found status
 	assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+	// Check that the error body says it was the card that was missing
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let error: Value = serde_json::from_slice(&body).unwrap();
+	assert_eq!(error["code"], "card_not_found");
 }
@@ -0,0 +1,157 @@
+/// Integration tests for streaming cards as newline-delimited JSON
+///
+/// This file contains tests for `GET /cards/stream`:
+/// - Every card is streamed exactly once, one per line, across batches
+/// - The stream honors the same filters as `GET /cards`
+use axum::{
+	Router,
+	body::{Body, to_bytes},
+	http::{Request, StatusCode, header},
+};
+use hippocampus::{
+	create_app,
+	db::{DbPool, init_pool},
+	handlers::CARD_STREAM_BATCH_SIZE,
+	repo,
+};
+use serde_json::{Value, json};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+/// Creates a test application along with its database pool
+///
+/// ### Returns
+///
+/// An Axum Router connected to a fresh in-memory database, and the pool
+/// behind it
+fn create_stream_test_app() -> (Router, Arc<DbPool>) {
+	let unique_id = uuid::Uuid::new_v4();
+	let database_url = format!("file:test_{}?mode=memory&cache=shared", unique_id);
+	let pool = Arc::new(init_pool(&database_url));
+
+	let conn = &mut pool.get().unwrap();
+	hippocampus::run_migrations(conn);
+
+	(create_app(pool.clone()), pool)
+}
+
+/// Streams cards, returning the lines of the response parsed as JSON
+///
+/// ### Arguments
+///
+/// * `app` - The application to send the request to
+/// * `uri` - The path to request, with any query string
+///
+/// ### Returns
+///
+/// One JSON value per line of the response
+async fn stream_cards(app: &Router, uri: &str) -> Vec<Value> {
+	let request = Request::builder()
+		.uri(uri)
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.clone().oneshot(request).await.unwrap();
+
+	assert_eq!(response.status(), StatusCode::OK);
+	assert_eq!(
+		response.headers()[header::CONTENT_TYPE],
+		"application/x-ndjson"
+	);
+
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let body = String::from_utf8(body.to_vec()).unwrap();
+	assert!(body.is_empty() || body.ends_with('\n'));
+	body.lines()
+		.map(|line| serde_json::from_str(line).unwrap())
+		.collect()
+}
+
+/// Tests that streaming returns every card once, across several batches
+///
+/// This test verifies:
+/// 1. There are as many lines as cards, even with more cards than fit in a
+///    batch
+/// 2. Each line is a card, and no card appears twice
+#[tokio::test]
+async fn test_stream_cards_returns_every_card() {
+	let (app, pool) = create_stream_test_app();
+	// "Test" item types get two cards per item
+	let item_type = repo::create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item_count = CARD_STREAM_BATCH_SIZE as usize / 2 + 10;
+	for i in 0..item_count {
+		repo::create_item(
+			&pool,
+			&item_type.get_id(),
+			format!("Item {}", i),
+			json!({"front": "F", "back": "B"}),
+		)
+		.await
+		.unwrap();
+	}
+	let card_count = repo::list_all_cards(&pool).unwrap().len();
+	assert!(card_count > CARD_STREAM_BATCH_SIZE as usize);
+
+	let lines = stream_cards(&app, "/cards/stream").await;
+
+	assert_eq!(lines.len(), card_count);
+	let ids: HashSet<&str> = lines
+		.iter()
+		.map(|card| card["id"].as_str().unwrap())
+		.collect();
+	assert_eq!(ids.len(), card_count);
+}
+
+/// Tests that streaming honors the `GET /cards` filters
+///
+/// This test verifies:
+/// 1. Filtering by item type streams only that type's cards, in the same
+///    order as `GET /cards`
+/// 2. A filter nothing matches gives an empty stream
+#[tokio::test]
+async fn test_stream_cards_honors_filters() {
+	let (app, pool) = create_stream_test_app();
+	let basic = repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let other = repo::create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	for (item_type, count) in [(&basic, 3), (&other, 4)] {
+		for i in 0..count {
+			repo::create_item(
+				&pool,
+				&item_type.get_id(),
+				format!("{} item {}", item_type.get_name(), i),
+				json!({"front": "F", "back": "B"}),
+			)
+			.await
+			.unwrap();
+		}
+	}
+
+	let uri = format!("/cards/stream?item_type_id={}", basic.get_id());
+	let lines = stream_cards(&app, &uri).await;
+	assert_eq!(lines.len(), 3);
+
+	let request = Request::builder()
+		.uri(format!("/cards?item_type_id={}", basic.get_id()))
+		.body(Body::empty())
+		.unwrap();
+	let response = app.clone().oneshot(request).await.unwrap();
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let listed: Vec<Value> = serde_json::from_slice(&body).unwrap();
+	let ids = |cards: &[Value]| -> Vec<String> {
+		cards
+			.iter()
+			.map(|card| card["id"].as_str().unwrap().to_string())
+			.collect()
+	};
+	assert_eq!(ids(&lines), ids(&listed));
+
+	let uri = format!("/cards/stream?item_type_id={}", uuid::Uuid::new_v4());
+	assert!(stream_cards(&app, &uri).await.is_empty());
+}
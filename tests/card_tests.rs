@@ -9,7 +9,7 @@ use axum::{
 	http::{Request, StatusCode},
 };
 use hippocampus::models::Card;
-use serde_json::json;
+use serde_json::{Value, json};
 use tower::Service;
 
 mod common;
@@ -34,7 +34,7 @@ async fn test_create_card() {
 		&mut app,
 		&item_type.get_id(),
 		"Item with Cards".to_string(),
-		None,
+		Some(json!({"front": "F", "back": "B"})),
 	)
 	.await;
 
@@ -94,7 +94,7 @@ async fn test_get_card() {
 		&mut app,
 		&item_type.get_id(),
 		"Item for Card Get".to_string(),
-		None,
+		Some(json!({"front": "F", "back": "B"})),
 	)
 	.await;
 
@@ -152,7 +152,7 @@ async fn test_list_cards_for_item() {
 		&mut app,
 		&item_type.get_id(),
 		"Item for Card List".to_string(),
-		None,
+		Some(json!({"front": "F", "back": "B"})),
 	)
 	.await;
 
@@ -198,9 +198,8 @@ async fn test_list_cards_for_item() {
 /// Tests getting a non-existent card via the API
 ///
 /// This test verifies:
-/// 1. A GET request to /cards/{id} with a non-existent ID returns null
-/// 2. The response has a 200 OK status
-/// 3. The response body can be correctly deserialized as a null Option<Card>
+/// 1. A GET request to /cards/{id} with a non-existent ID returns a 404 status
+/// 2. The error body carries the `card_not_found` code
 #[tokio::test]
 async fn test_get_nonexistent_card() {
 	// Create our test app
@@ -219,17 +218,13 @@ async fn test_get_nonexistent_card() {
 	// Send the request to the application and get the response
 	let response = app.call(request).await.unwrap();
 
-	// Check that the response has a 200 OK status
-	assert_eq!(response.status(), StatusCode::OK);
+	// Check that the response has a 404 Not Found status
+	assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
-	// Convert the response body into bytes for parsing
+	// Check that the error body says it was the card that was missing
 	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
-
-	// Parse the body as JSON into an Option<Card>
-	let card: Option<Card> = serde_json::from_slice(&body).unwrap();
-
-	// Check that the card is None (null in JSON)
-	assert!(card.is_none(), "Non-existent card should return null");
+	let error: Value = serde_json::from_slice(&body).unwrap();
+	assert_eq!(error["code"], "card_not_found");
 }
 
 /// Tests listing all cards via the API
@@ -251,7 +246,7 @@ async fn test_list_all_cards() {
 		&mut app,
 		&item_type.get_id(),
 		"Item 1 for Card List".to_string(),
-		None,
+		Some(json!({"front": "F", "back": "B"})),
 	)
 	.await;
 
@@ -259,7 +254,7 @@ async fn test_list_all_cards() {
 		&mut app,
 		&item_type.get_id(),
 		"Item 2 for Card List".to_string(),
-		None,
+		Some(json!({"front": "F", "back": "B"})),
 	)
 	.await;
 
@@ -292,3 +287,582 @@ async fn test_list_all_cards() {
 	assert!(has_item1_card, "Should have a card for item 1");
 	assert!(has_item2_card, "Should have a card for item 2");
 }
+
+/// Tests filtering cards by state via the API
+///
+/// This test verifies:
+/// 1. A GET request to /cards?state=new only lists never-reviewed cards
+/// 2. A card drops out of the new state once it has been reviewed
+#[tokio::test]
+async fn test_list_new_cards() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let reviewed_item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Reviewed".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let new_item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"New".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let reviewed_card = get_cards_for_item(&mut app, &reviewed_item.get_id())
+		.await
+		.remove(0);
+	let new_card = get_cards_for_item(&mut app, &new_item.get_id())
+		.await
+		.remove(0);
+
+	// Review one of the cards
+	let request = Request::builder()
+		.uri("/reviews")
+		.method("POST")
+		.header("Content-Type", "application/json")
+		.body(Body::from(
+			json!({"card_id": reviewed_card.get_id(), "rating": 3}).to_string(),
+		))
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+
+	// Only the unreviewed card is new
+	let request = Request::builder()
+		.uri("/cards?state=new")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let cards: Vec<Card> = serde_json::from_slice(&body).unwrap();
+	assert_eq!(cards.len(), 1);
+	assert_eq!(cards[0].get_id(), new_card.get_id());
+	assert!(cards[0].get_last_review().is_none());
+}
+
+/// Tests suspending cards by filter via the API
+///
+/// This test verifies:
+/// 1. A POST request to /cards/suspend?item_type_id=... suspends every card of that type
+/// 2. The response is the number of cards suspended
+/// 3. Cards of other item types stay in GET /cards
+#[tokio::test]
+async fn test_suspend_cards_by_filter() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	let exam_type = create_item_type(&mut app, "Test Exam".to_string()).await;
+	let other_type = create_item_type(&mut app, "Basic".to_string()).await;
+	create_item(
+		&mut app,
+		&exam_type.get_id(),
+		"Exam item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let other_item = create_item(
+		&mut app,
+		&other_type.get_id(),
+		"Other item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+
+	let request = Request::builder()
+		.uri(format!(
+			"/cards/suspend?item_type_id={}",
+			exam_type.get_id()
+		))
+		.method("POST")
+		.header("Content-Type", "application/json")
+		.body(Body::from("true"))
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let suspended: usize = serde_json::from_slice(&body).unwrap();
+	assert_eq!(suspended, 2);
+
+	// Only the other item type's card is still listed
+	let request = Request::builder()
+		.uri("/cards")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let cards: Vec<Card> = serde_json::from_slice(&body).unwrap();
+	assert_eq!(cards.len(), 1);
+	assert_eq!(cards[0].get_item_id(), other_item.get_id());
+}
+
+/// Tests getting the next card to review via the API
+///
+/// This test verifies:
+/// 1. A GET request to /cards/next returns a single due card
+/// 2. The card moved to the top of the sort order is the one returned
+#[tokio::test]
+async fn test_next_card() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	// Create two items, each with a due card
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 1".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let item2 = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 2".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let card2 = get_cards_for_item(&mut app, &item2.get_id())
+		.await
+		.remove(0);
+
+	// Move the second item's card to the top of the queue
+	let request = Request::builder()
+		.uri(format!("/cards/{}/sort_position", card2.get_id()))
+		.method("PATCH")
+		.header("Content-Type", "application/json")
+		.body(Body::from(json!({"position": "top"}).to_string()))
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+
+	// Ask for the next card
+	let request = Request::builder()
+		.uri("/cards/next")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+
+	// Check that the response has a 200 OK status
+	assert_eq!(response.status(), StatusCode::OK);
+
+	// The top card should be returned
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let card: Card = serde_json::from_slice(&body).unwrap();
+	assert_eq!(card.get_id(), card2.get_id());
+}
+
+/// Tests getting the next card when nothing is due
+///
+/// This test verifies:
+/// 1. A GET request to /cards/next with an empty queue returns 204 No Content
+/// 2. The response body is empty
+#[tokio::test]
+async fn test_next_card_empty_queue() {
+	// Create our test app with no cards
+	let mut app = create_test_app();
+
+	let request = Request::builder()
+		.uri("/cards/next")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+
+	assert_eq!(response.status(), StatusCode::NO_CONTENT);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	assert!(body.is_empty());
+}
+
+/// Tests getting a random due card via the API
+///
+/// This test verifies:
+/// 1. A GET request to /cards/random with an empty queue returns 204 No Content
+/// 2. Over many requests against a few due cards, more than one distinct card
+///    is returned
+#[tokio::test]
+async fn test_random_card() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	let random_card = async |app: &mut axum::Router| {
+		let request = Request::builder()
+			.uri("/cards/random")
+			.method("GET")
+			.body(Body::empty())
+			.unwrap();
+		app.call(request).await.unwrap()
+	};
+
+	let response = random_card(&mut app).await;
+	assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+	// Create three items, each with a due card
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	for title in ["Item 1", "Item 2", "Item 3"] {
+		create_item(
+			&mut app,
+			&item_type.get_id(),
+			title.to_string(),
+			Some(json!({"front": "F", "back": "B"})),
+		)
+		.await;
+	}
+
+	let mut seen = std::collections::HashSet::new();
+	for _ in 0..50 {
+		let response = random_card(&mut app).await;
+		assert_eq!(response.status(), StatusCode::OK);
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		let card: Card = serde_json::from_slice(&body).unwrap();
+		seen.insert(card.get_id());
+	}
+	assert!(seen.len() > 1, "always got the same card");
+}
+
+/// Tests listing and suspending leech cards via the API
+///
+/// This test verifies:
+/// 1. A GET request to /cards/leeches only returns cards with enough "Again" ratings
+/// 2. Passing suspend=true suspends the returned cards
+#[tokio::test]
+async fn test_list_leeches() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 1".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let card = get_cards_for_item(&mut app, &item.get_id()).await.remove(0);
+
+	// Fail the card twice
+	for _ in 0..2 {
+		let request = Request::builder()
+			.uri("/reviews")
+			.method("POST")
+			.header("Content-Type", "application/json")
+			.body(Body::from(
+				json!({"card_id": card.get_id(), "rating": 1}).to_string(),
+			))
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+	}
+
+	// Two lapses are below the default threshold
+	let request = Request::builder()
+		.uri("/cards/leeches")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let leeches: Vec<Card> = serde_json::from_slice(&body).unwrap();
+	assert!(leeches.is_empty());
+
+	// A lower threshold picks the card up and suspends it
+	let request = Request::builder()
+		.uri("/cards/leeches?threshold=2&suspend=true")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let leeches: Vec<Card> = serde_json::from_slice(&body).unwrap();
+	assert_eq!(leeches.len(), 1);
+	assert_eq!(leeches[0].get_id(), card.get_id());
+	assert!(leeches[0].get_suspended().is_some());
+}
+
+/// Tests reversible Basic items via the API
+///
+/// This test verifies:
+/// 1. A Basic item with `reversible: true` gets a forward and a reverse card
+/// 2. Each card's JSON says which direction it is
+#[tokio::test]
+async fn test_reversible_basic_item_cards() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 1".to_string(),
+		Some(json!({"front": "F", "back": "B", "reversible": true})),
+	)
+	.await;
+
+	let request = Request::builder()
+		.uri(format!("/items/{}/cards", item.get_id()))
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let cards: Vec<Value> = serde_json::from_slice(&body).unwrap();
+
+	let mut directions: Vec<(i64, &str)> = cards
+		.iter()
+		.map(|card| {
+			(
+				card["card_index"].as_i64().unwrap(),
+				card["direction"].as_str().unwrap(),
+			)
+		})
+		.collect();
+	directions.sort();
+	assert_eq!(directions, vec![(0, "forward"), (1, "reverse")]);
+}
+
+/// Tests forecasting when cards come due via the API
+///
+/// This test verifies:
+/// 1. A GET request to /cards/forecast?days=3 returns an overdue bucket and one bucket per day
+/// 2. New cards, due immediately, are counted as overdue
+/// 3. Suspended cards are left out
+#[tokio::test]
+async fn test_forecast_due_cards() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Test Forecast".to_string()).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 1".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let card = get_cards_for_item(&mut app, &item.get_id()).await.remove(0);
+
+	let forecast = |app: &mut axum::Router| {
+		let request = Request::builder()
+			.uri("/cards/forecast?days=3")
+			.method("GET")
+			.body(Body::empty())
+			.unwrap();
+		let call = app.call(request);
+		async move {
+			let response = call.await.unwrap();
+			assert_eq!(response.status(), StatusCode::OK);
+			let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+			serde_json::from_slice::<Vec<Value>>(&body).unwrap()
+		}
+	};
+
+	let buckets = forecast(&mut app).await;
+	assert_eq!(buckets.len(), 4);
+	assert_eq!(buckets[0], json!({"date": null, "due_count": 2}));
+	for bucket in &buckets[1..] {
+		assert!(bucket["date"].is_string());
+		assert_eq!(bucket["due_count"], 0);
+	}
+
+	// Suspending a card takes it out of the forecast
+	let request = Request::builder()
+		.uri(format!("/cards/{}/suspend", card.get_id()))
+		.method("PATCH")
+		.header("Content-Type", "application/json")
+		.body(Body::from("true"))
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+
+	let buckets = forecast(&mut app).await;
+	assert_eq!(buckets[0]["due_count"], 1);
+}
+
+/// Tests burying a card via the API
+///
+/// This test verifies:
+/// 1. A POST request to /cards/{card_id}/bury hides the card from GET /cards
+/// 2. The other cards are still listed
+/// 3. A POST request to /cards/unbury_all lists it again
+#[tokio::test]
+async fn test_bury_card() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let mut card_ids = Vec::new();
+	for title in ["Item 1", "Item 2"] {
+		let item = create_item(
+			&mut app,
+			&item_type.get_id(),
+			title.to_string(),
+			Some(json!({"front": "F", "back": "B"})),
+		)
+		.await;
+		let card = get_cards_for_item(&mut app, &item.get_id()).await.remove(0);
+		card_ids.push(card.get_id());
+	}
+
+	// Bury the first card until tomorrow
+	let request = Request::builder()
+		.uri(format!("/cards/{}/bury", card_ids[0]))
+		.method("POST")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let card: Card = serde_json::from_slice(&body).unwrap();
+	assert!(card.get_buried_until().is_some());
+
+	// Only the other card is listed
+	let request = Request::builder()
+		.uri("/cards")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let cards: Vec<Card> = serde_json::from_slice(&body).unwrap();
+	assert_eq!(cards.len(), 1);
+	assert_eq!(cards[0].get_id(), card_ids[1]);
+
+	// Unburying everything brings it back
+	let request = Request::builder()
+		.uri("/cards/unbury_all")
+		.method("POST")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let unburied: usize = serde_json::from_slice(&body).unwrap();
+	assert_eq!(unburied, 1);
+
+	let request = Request::builder()
+		.uri("/cards")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let cards: Vec<Card> = serde_json::from_slice(&body).unwrap();
+	assert_eq!(cards.len(), 2);
+}
+
+/// Tests setting, updating and clearing a card's note via the API
+///
+/// This test verifies:
+/// 1. A PUT request to /cards/{card_id}/note sets the note
+/// 2. The note is returned by GET /cards/{id}
+/// 3. A second PUT replaces the note
+/// 4. A null note clears it
+#[tokio::test]
+async fn test_set_card_note() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 1".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let card_id = get_cards_for_item(&mut app, &item.get_id())
+		.await
+		.remove(0)
+		.get_id();
+
+	for note in [
+		json!("First mnemonic"),
+		json!("Second mnemonic"),
+		json!(null),
+	] {
+		let request = Request::builder()
+			.uri(format!("/cards/{}/note", card_id))
+			.method("PUT")
+			.header("Content-Type", "application/json")
+			.body(Body::from(json!({"note": note}).to_string()))
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+
+		let request = Request::builder()
+			.uri(format!("/cards/{}", card_id))
+			.method("GET")
+			.body(Body::empty())
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		let card: Card = serde_json::from_slice(&body).unwrap();
+		assert_eq!(card.get_note(), note.as_str());
+	}
+}
+
+/// Tests setting a card's difficulty via the API
+///
+/// This test verifies:
+/// 1. A PUT request to /cards/{card_id}/difficulty sets the difficulty
+/// 2. The difficulty is returned by GET /cards/{id}
+/// 3. A difficulty outside 0 to 1 is rejected with a 400
+/// 4. A null difficulty unsets it
+#[tokio::test]
+async fn test_set_card_difficulty() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 1".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let card_id = get_cards_for_item(&mut app, &item.get_id())
+		.await
+		.remove(0)
+		.get_id();
+
+	for (difficulty, status, expected) in [
+		(json!(0.75), StatusCode::OK, Some(0.75)),
+		(json!(1.5), StatusCode::BAD_REQUEST, Some(0.75)),
+		(json!(null), StatusCode::OK, None),
+	] {
+		let request = Request::builder()
+			.uri(format!("/cards/{}/difficulty", card_id))
+			.method("PUT")
+			.header("Content-Type", "application/json")
+			.body(Body::from(json!({"difficulty": difficulty}).to_string()))
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		assert_eq!(response.status(), status);
+
+		let request = Request::builder()
+			.uri(format!("/cards/{}", card_id))
+			.method("GET")
+			.body(Body::empty())
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		let card: Card = serde_json::from_slice(&body).unwrap();
+		assert_eq!(card.get_difficulty(), expected);
+	}
+}
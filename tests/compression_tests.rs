@@ -0,0 +1,151 @@
+/// Integration tests for response compression
+///
+/// This file contains tests for the configurable gzip layer:
+/// - Large responses are gzipped for clients that accept it
+/// - Clients that don't ask for gzip, small responses and streamed cards
+///   are left alone
+/// - Turning compression off leaves every response uncompressed
+use axum::{
+	Router,
+	body::Body,
+	http::{Request, StatusCode, header},
+};
+use hippocampus::{AppOptions, create_app_with_options, db::init_pool, repo};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+/// Creates a test application with the given compression threshold
+///
+/// ### Arguments
+///
+/// * `compression_min_size` - The size responses are compressed above, or
+///   None to turn compression off
+/// * `item_types` - The number of item types to create, so listing them
+///   returns a large response
+///
+/// ### Returns
+///
+/// An Axum Router connected to a fresh in-memory database
+async fn create_compression_test_app(
+	compression_min_size: Option<u16>,
+	item_types: usize,
+) -> Router {
+	let unique_id = uuid::Uuid::new_v4();
+	let database_url = format!("file:test_{}?mode=memory&cache=shared", unique_id);
+	let pool = Arc::new(init_pool(&database_url));
+
+	let conn = &mut pool.get().unwrap();
+	hippocampus::run_migrations(conn);
+
+	for i in 0..item_types {
+		repo::create_item_type(&pool, format!("Item type {}", i), "fsrs".to_string())
+			.await
+			.unwrap();
+	}
+
+	create_app_with_options(
+		pool,
+		AppOptions {
+			compression_min_size,
+			..Default::default()
+		},
+	)
+}
+
+/// Lists the app's item types, optionally accepting gzip
+///
+/// ### Arguments
+///
+/// * `app` - The application to send the request to
+/// * `accept_gzip` - Whether to send `Accept-Encoding: gzip`
+///
+/// ### Returns
+///
+/// The response's `Content-Encoding` header, if it has one
+async fn list_item_types_encoding(app: Router, accept_gzip: bool) -> Option<String> {
+	let mut request = Request::builder().uri("/item_types").method("GET");
+	if accept_gzip {
+		request = request.header(header::ACCEPT_ENCODING, "gzip");
+	}
+
+	let response = app
+		.oneshot(request.body(Body::empty()).unwrap())
+		.await
+		.unwrap();
+
+	assert_eq!(response.status(), StatusCode::OK);
+	response
+		.headers()
+		.get(header::CONTENT_ENCODING)
+		.map(|encoding| encoding.to_str().unwrap().to_string())
+}
+
+/// Tests that a large list response is gzipped when the client accepts it
+///
+/// This test verifies:
+/// 1. With `Accept-Encoding: gzip`, the response has `Content-Encoding: gzip`
+/// 2. Without it, the same response isn't compressed
+#[tokio::test]
+async fn test_large_response_is_gzipped_when_requested() {
+	let app = create_compression_test_app(Some(1024), 50).await;
+
+	assert_eq!(
+		list_item_types_encoding(app.clone(), true).await.as_deref(),
+		Some("gzip")
+	);
+	assert_eq!(list_item_types_encoding(app, false).await, None);
+}
+
+/// Tests that responses below the threshold aren't compressed
+///
+/// This test verifies:
+/// 1. An empty list is smaller than the threshold
+/// 2. It comes back uncompressed even though the client accepts gzip
+#[tokio::test]
+async fn test_small_response_is_not_compressed() {
+	let app = create_compression_test_app(Some(1024), 0).await;
+
+	assert_eq!(list_item_types_encoding(app, true).await, None);
+}
+
+/// Tests that compression can be turned off
+///
+/// This test verifies:
+/// 1. With compression off, a large response isn't compressed even though
+///    the client accepts gzip
+#[tokio::test]
+async fn test_compression_can_be_turned_off() {
+	let app = create_compression_test_app(None, 50).await;
+
+	assert_eq!(list_item_types_encoding(app, true).await, None);
+}
+
+/// Tests that streamed cards aren't compressed
+///
+/// This test verifies:
+/// 1. `GET /cards/stream` comes back uncompressed even though the client
+///    accepts gzip and the stream's size is unknown, so each line reaches
+///    the client as soon as it's sent
+#[tokio::test]
+async fn test_card_stream_is_not_compressed() {
+	let app = create_compression_test_app(Some(1024), 0).await;
+
+	let response = app
+		.oneshot(
+			Request::builder()
+				.uri("/cards/stream")
+				.method("GET")
+				.header(header::ACCEPT_ENCODING, "gzip")
+				.body(Body::empty())
+				.unwrap(),
+		)
+		.await
+		.unwrap();
+
+	assert_eq!(response.status(), StatusCode::OK);
+	assert_eq!(
+		response.headers()[header::CONTENT_TYPE],
+		"application/x-ndjson"
+	);
+	assert_eq!(response.headers().get(header::CONTENT_ENCODING), None);
+}
@@ -0,0 +1,185 @@
+/// Integration tests for CORS handling
+///
+/// This file contains tests for the configurable CORS layer:
+/// - Allowed origins receive an `Access-Control-Allow-Origin` header
+/// - Preflight `OPTIONS` requests succeed for mutating routes
+/// - Disallowed origins and the same-origin default receive no CORS headers
+use axum::{
+	Router,
+	body::Body,
+	http::{Request, StatusCode, header},
+};
+use hippocampus::{AppOptions, config::AllowedOrigins, create_app_with_options, db::init_pool};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+/// Creates a test application with the given allowed origins
+///
+/// ### Arguments
+///
+/// * `allowed_origins` - The origins the application should accept
+///
+/// ### Returns
+///
+/// An Axum Router connected to a fresh in-memory database
+fn create_cors_test_app(allowed_origins: AllowedOrigins) -> Router {
+	let unique_id = uuid::Uuid::new_v4();
+	let database_url = format!("file:test_{}?mode=memory&cache=shared", unique_id);
+	let pool = Arc::new(init_pool(&database_url));
+
+	let conn = &mut pool.get().unwrap();
+	hippocampus::run_migrations(conn);
+
+	create_app_with_options(
+		pool,
+		AppOptions {
+			allowed_origins,
+			..Default::default()
+		},
+	)
+}
+
+/// Tests that an allowed origin receives the CORS header on a simple request
+///
+/// This test verifies:
+/// 1. A GET request with an allowed `Origin` succeeds
+/// 2. The response echoes that origin in `Access-Control-Allow-Origin`
+#[tokio::test]
+async fn test_allowed_origin_receives_cors_header() {
+	let app = create_cors_test_app(AllowedOrigins::List(vec![
+		"http://localhost:5173".to_string(),
+	]));
+
+	let request = Request::builder()
+		.uri("/item_types")
+		.method("GET")
+		.header(header::ORIGIN, "http://localhost:5173")
+		.body(Body::empty())
+		.unwrap();
+
+	let response = app.oneshot(request).await.unwrap();
+
+	assert_eq!(response.status(), StatusCode::OK);
+	assert_eq!(
+		response
+			.headers()
+			.get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+			.unwrap(),
+		"http://localhost:5173"
+	);
+}
+
+/// Tests that preflight requests are answered for mutating methods
+///
+/// This test verifies:
+/// 1. An `OPTIONS` preflight for `PATCH`, `PUT` and `DELETE` succeeds
+/// 2. The response allows the origin and lists the requested method
+#[tokio::test]
+async fn test_preflight_for_mutating_methods() {
+	let app = create_cors_test_app(AllowedOrigins::List(vec![
+		"http://localhost:5173".to_string(),
+	]));
+
+	for method in ["PATCH", "PUT", "DELETE"] {
+		let request = Request::builder()
+			.uri("/cards/some-card/priority")
+			.method("OPTIONS")
+			.header(header::ORIGIN, "http://localhost:5173")
+			.header(header::ACCESS_CONTROL_REQUEST_METHOD, method)
+			.body(Body::empty())
+			.unwrap();
+
+		let response = app.clone().oneshot(request).await.unwrap();
+
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(
+			response
+				.headers()
+				.get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+				.unwrap(),
+			"http://localhost:5173"
+		);
+		let allowed_methods = response
+			.headers()
+			.get(header::ACCESS_CONTROL_ALLOW_METHODS)
+			.unwrap()
+			.to_str()
+			.unwrap();
+		assert!(
+			allowed_methods.contains(method),
+			"{} missing from {}",
+			method,
+			allowed_methods
+		);
+	}
+}
+
+/// Tests that an origin not on the list receives no CORS header
+#[tokio::test]
+async fn test_disallowed_origin_has_no_cors_header() {
+	let app = create_cors_test_app(AllowedOrigins::List(vec![
+		"http://localhost:5173".to_string(),
+	]));
+
+	let request = Request::builder()
+		.uri("/item_types")
+		.method("GET")
+		.header(header::ORIGIN, "http://evil.example.com")
+		.body(Body::empty())
+		.unwrap();
+
+	let response = app.oneshot(request).await.unwrap();
+
+	assert!(
+		response
+			.headers()
+			.get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+			.is_none()
+	);
+}
+
+/// Tests that the wildcard setting allows any origin
+#[tokio::test]
+async fn test_wildcard_allows_any_origin() {
+	let app = create_cors_test_app(AllowedOrigins::Any);
+
+	let request = Request::builder()
+		.uri("/item_types")
+		.method("GET")
+		.header(header::ORIGIN, "http://anything.example.com")
+		.body(Body::empty())
+		.unwrap();
+
+	let response = app.oneshot(request).await.unwrap();
+
+	assert_eq!(
+		response
+			.headers()
+			.get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+			.unwrap(),
+		"*"
+	);
+}
+
+/// Tests that the default (same-origin only) sends no CORS headers
+#[tokio::test]
+async fn test_same_origin_default_has_no_cors_header() {
+	let app = create_cors_test_app(AllowedOrigins::default());
+
+	let request = Request::builder()
+		.uri("/item_types")
+		.method("GET")
+		.header(header::ORIGIN, "http://localhost:5173")
+		.body(Body::empty())
+		.unwrap();
+
+	let response = app.oneshot(request).await.unwrap();
+
+	assert_eq!(response.status(), StatusCode::OK);
+	assert!(
+		response
+			.headers()
+			.get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+			.is_none()
+	);
+}
@@ -0,0 +1,194 @@
+/// Integration tests for due card notifications
+///
+/// This file contains tests for `GET /events/due`:
+/// - An event with the due count is sent as soon as the stream opens
+/// - Cards that come due are announced at the next check
+use axum::{
+	Router,
+	body::{Body, BodyDataStream},
+	http::{Request, StatusCode, header},
+};
+use chrono::{Duration as ChronoDuration, Utc};
+use futures_util::StreamExt;
+use hippocampus::{
+	AppOptions, DueCardsEvent, create_app_with_options,
+	db::{DbPool, init_pool},
+	models::CardId,
+	repo,
+};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt;
+
+/// How often the test application checks for due cards
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Creates a test application that checks for due cards every
+/// [`POLL_INTERVAL`], along with its database pool
+///
+/// ### Returns
+///
+/// An Axum Router connected to a fresh in-memory database, and the pool
+/// behind it
+fn create_due_events_test_app() -> (Router, Arc<DbPool>) {
+	let unique_id = uuid::Uuid::new_v4();
+	let database_url = format!("file:test_{}?mode=memory&cache=shared", unique_id);
+	let pool = Arc::new(init_pool(&database_url));
+
+	let conn = &mut pool.get().unwrap();
+	hippocampus::run_migrations(conn);
+
+	let app = create_app_with_options(
+		pool.clone(),
+		AppOptions {
+			due_events_interval: POLL_INTERVAL,
+			..Default::default()
+		},
+	);
+	(app, pool)
+}
+
+/// Reads server-sent events from a response body, one at a time
+struct EventReader {
+	body: BodyDataStream,
+	buffer: String,
+}
+
+impl EventReader {
+	/// Waits for the next `due` event, skipping keep-alive comments
+	///
+	/// ### Returns
+	///
+	/// The event's data
+	async fn next_due_event(&mut self) -> DueCardsEvent {
+		loop {
+			if let Some(end) = self.buffer.find("\n\n") {
+				let event: String = self.buffer.drain(..end + 2).collect();
+				let mut name = None;
+				let mut data = None;
+				for line in event.lines() {
+					if let Some(value) = line.strip_prefix("event: ") {
+						name = Some(value.to_string());
+					} else if let Some(value) = line.strip_prefix("data: ") {
+						data = Some(value.to_string());
+					}
+				}
+				if let (Some(name), Some(data)) = (name, data) {
+					assert_eq!(name, "due");
+					return serde_json::from_str(&data).unwrap();
+				}
+				continue;
+			}
+
+			let chunk = tokio::time::timeout(Duration::from_secs(5), self.body.next())
+				.await
+				.expect("no event within 5 seconds")
+				.expect("the stream ended")
+				.unwrap();
+			self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+		}
+	}
+}
+
+/// Opens `GET /events/due`
+///
+/// ### Arguments
+///
+/// * `app` - The application to send the request to
+///
+/// ### Returns
+///
+/// A reader for the events sent
+async fn subscribe(app: &Router) -> EventReader {
+	let request = Request::builder()
+		.uri("/events/due")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.clone().oneshot(request).await.unwrap();
+
+	assert_eq!(response.status(), StatusCode::OK);
+	assert_eq!(
+		response.headers()[header::CONTENT_TYPE],
+		"text/event-stream"
+	);
+
+	EventReader {
+		body: response.into_body().into_data_stream(),
+		buffer: String::new(),
+	}
+}
+
+/// Moves a card's next review
+///
+/// ### Arguments
+///
+/// * `pool` - The database connection pool
+/// * `card_id` - The card to move
+/// * `offset` - How far from now the card is next due
+async fn move_due_time(pool: &DbPool, card_id: &CardId, offset: ChronoDuration) {
+	let mut card = repo::get_card_raw(pool, card_id).unwrap().unwrap();
+	card.set_next_review(Utc::now() + offset);
+	repo::update_card(pool, &card).await.unwrap();
+}
+
+/// Tests that a card coming due is announced
+///
+/// This test verifies:
+/// 1. The first event comes straight away, with nothing due and no newly
+///    due cards
+/// 2. Once a card's next review is moved into the past, the next event
+///    counts it and lists it as newly due
+/// 3. Moving it back out of the past sends an event with the lower count
+#[tokio::test]
+async fn test_due_events_announce_newly_due_cards() {
+	let (app, pool) = create_due_events_test_app();
+
+	let item_type = repo::create_item_type(&pool, "Test Type".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		json!({"front": "Hello", "back": "World"}),
+	)
+	.await
+	.unwrap();
+	let card_ids: Vec<CardId> = repo::get_cards_for_item(&pool, &item.get_id())
+		.unwrap()
+		.iter()
+		.map(|card| card.get_id())
+		.collect();
+	for card_id in &card_ids {
+		move_due_time(&pool, card_id, ChronoDuration::days(1)).await;
+	}
+
+	let mut events = subscribe(&app).await;
+	assert_eq!(
+		events.next_due_event().await,
+		DueCardsEvent {
+			due_count: 0,
+			newly_due_card_ids: vec![],
+		}
+	);
+
+	move_due_time(&pool, &card_ids[0], ChronoDuration::days(-1)).await;
+	assert_eq!(
+		events.next_due_event().await,
+		DueCardsEvent {
+			due_count: 1,
+			newly_due_card_ids: vec![card_ids[0].clone()],
+		}
+	);
+
+	move_due_time(&pool, &card_ids[0], ChronoDuration::days(1)).await;
+	assert_eq!(
+		events.next_due_event().await,
+		DueCardsEvent {
+			due_count: 0,
+			newly_due_card_ids: vec![],
+		}
+	);
+}
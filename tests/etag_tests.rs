@@ -0,0 +1,220 @@
+/// Integration tests for ETag caching
+///
+/// This file contains tests for the weak ETags on item and card reads:
+/// - A second request with the returned ETag gets a 304 Not Modified
+/// - Changing the data changes the ETag, so the client gets the new data
+use axum::{
+	Router,
+	body::{Body, to_bytes},
+	http::{Request, StatusCode, header},
+	response::Response,
+};
+use hippocampus::{
+	create_app,
+	db::{DbPool, init_pool},
+	models::ItemType,
+	repo,
+};
+use serde_json::json;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+/// Creates a test application along with its database pool and an item type
+///
+/// ### Returns
+///
+/// An Axum Router connected to a fresh in-memory database, the pool behind it,
+/// and an item type to create items of
+async fn create_etag_test_app() -> (Router, Arc<DbPool>, ItemType) {
+	let unique_id = uuid::Uuid::new_v4();
+	let database_url = format!("file:test_{}?mode=memory&cache=shared", unique_id);
+	let pool = Arc::new(init_pool(&database_url));
+
+	let conn = &mut pool.get().unwrap();
+	hippocampus::run_migrations(conn);
+
+	let item_type = repo::create_item_type(&pool, "Basic".to_string(), "fsrs".to_string())
+		.await
+		.unwrap();
+
+	(create_app(pool.clone()), pool, item_type)
+}
+
+/// Sends a GET request, optionally with an `If-None-Match` header
+///
+/// ### Arguments
+///
+/// * `app` - The application to send the request to
+/// * `uri` - The path to request
+/// * `if_none_match` - The ETag to send in `If-None-Match`, if any
+///
+/// ### Returns
+///
+/// The response
+async fn get(app: &Router, uri: &str, if_none_match: Option<&str>) -> Response {
+	let mut request = Request::builder().uri(uri).method("GET");
+	if let Some(etag) = if_none_match {
+		request = request.header(header::IF_NONE_MATCH, etag);
+	}
+	app.clone()
+		.oneshot(request.body(Body::empty()).unwrap())
+		.await
+		.unwrap()
+}
+
+/// Gets a response's ETag
+fn etag_of(response: &Response) -> String {
+	response
+		.headers()
+		.get(header::ETAG)
+		.expect("response should have an ETag")
+		.to_str()
+		.unwrap()
+		.to_string()
+}
+
+/// Tests that re-requesting unchanged items with their ETag gets a 304
+///
+/// This test verifies:
+/// 1. `GET /items` returns a weak ETag
+/// 2. Sending it back in `If-None-Match` gets an empty 304 with the same ETag
+/// 3. After another item is created, the same ETag gets the full list again
+#[tokio::test]
+async fn test_list_items_not_modified() {
+	let (app, pool, item_type) = create_etag_test_app().await;
+	repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"First".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+
+	let response = get(&app, "/items", None).await;
+	assert_eq!(response.status(), StatusCode::OK);
+	let etag = etag_of(&response);
+	assert!(etag.starts_with("W/\""), "got: {}", etag);
+
+	let response = get(&app, "/items", Some(&etag)).await;
+	assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+	assert_eq!(etag_of(&response), etag);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	assert!(body.is_empty());
+
+	repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Second".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+
+	let response = get(&app, "/items", Some(&etag)).await;
+	assert_eq!(response.status(), StatusCode::OK);
+	assert_ne!(etag_of(&response), etag);
+}
+
+/// Tests that re-requesting unchanged cards with their ETag gets a 304
+///
+/// This test verifies:
+/// 1. `GET /cards` and `GET /cards/{id}` answer a matching `If-None-Match`
+///    with 304
+/// 2. Changing the card's priority changes its ETag
+#[tokio::test]
+async fn test_cards_not_modified() {
+	let (app, pool, item_type) = create_etag_test_app().await;
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+	let card = repo::get_cards_for_item(&pool, &item.get_id())
+		.unwrap()
+		.remove(0);
+
+	let etag = etag_of(&get(&app, "/cards", None).await);
+	assert_eq!(
+		get(&app, "/cards", Some(&etag)).await.status(),
+		StatusCode::NOT_MODIFIED
+	);
+
+	let uri = format!("/cards/{}", card.get_id());
+	let etag = etag_of(&get(&app, &uri, None).await);
+	assert_eq!(
+		get(&app, &uri, Some(&etag)).await.status(),
+		StatusCode::NOT_MODIFIED
+	);
+
+	repo::update_card_priority(&pool, &card.get_id(), 0.9, None)
+		.await
+		.unwrap();
+	let response = get(&app, &uri, Some(&etag)).await;
+	assert_eq!(response.status(), StatusCode::OK);
+	assert_ne!(etag_of(&response), etag);
+}
+
+/// Tests that errors don't get an ETag
+///
+/// This test verifies:
+/// 1. A 404 for a missing item has no ETag header
+#[tokio::test]
+async fn test_not_found_has_no_etag() {
+	let (app, _pool, _item_type) = create_etag_test_app().await;
+
+	let response = get(&app, "/items/nonexistent", None).await;
+	assert_eq!(response.status(), StatusCode::NOT_FOUND);
+	assert!(response.headers().get(header::ETAG).is_none());
+}
+
+/// Tests that reviewed cards, whose computed fields change with the clock,
+/// still get a 304 while unchanged
+///
+/// This test verifies:
+/// 1. `GET /cards/{id}` for a reviewed card answers its own ETag with 304
+///    a little later, although its `retrievability` has moved on
+/// 2. `GET /cards` does the same
+/// 3. Reviewing the card again changes its ETag
+#[tokio::test]
+async fn test_reviewed_card_not_modified() {
+	let (app, pool, item_type) = create_etag_test_app().await;
+	let item = repo::create_item(
+		&pool,
+		&item_type.get_id(),
+		"Item".to_string(),
+		json!({"front": "F", "back": "B"}),
+	)
+	.await
+	.unwrap();
+	let card = repo::get_cards_for_item(&pool, &item.get_id())
+		.unwrap()
+		.remove(0);
+	repo::record_review(&pool, &card.get_id(), hippocampus::models::Rating::Good)
+		.await
+		.unwrap();
+
+	let uri = format!("/cards/{}", card.get_id());
+	let card_etag = etag_of(&get(&app, &uri, None).await);
+	let list_etag = etag_of(&get(&app, "/cards?cram=true", None).await);
+
+	tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+	assert_eq!(
+		get(&app, &uri, Some(&card_etag)).await.status(),
+		StatusCode::NOT_MODIFIED
+	);
+	assert_eq!(
+		get(&app, "/cards?cram=true", Some(&list_etag)).await.status(),
+		StatusCode::NOT_MODIFIED
+	);
+
+	repo::record_review(&pool, &card.get_id(), hippocampus::models::Rating::Good)
+		.await
+		.unwrap();
+	let response = get(&app, &uri, Some(&card_etag)).await;
+	assert_eq!(response.status(), StatusCode::OK);
+	assert_ne!(etag_of(&response), card_etag);
+}
@@ -5,6 +5,8 @@
 /// - Getting items by ID
 /// - Listing all items
 /// - Handling non-existent items
+/// - Creating items in bulk
+/// - Changing an item's type
 use axum::{
 	body::{Body, to_bytes},
 	http::{Request, StatusCode},
@@ -66,6 +68,52 @@ async fn test_create_item() {
 	assert!(!item.get_id().0.is_empty());
 }
 
+/// Tests that creating an item with suspicious data warns about it
+///
+/// This test verifies:
+/// 1. A Basic item with an empty `front` is still created, with a 200 OK
+/// 2. The response carries a warning about the empty `front`
+/// 3. An item with nothing wrong gets an empty `warnings` array
+#[tokio::test]
+async fn test_create_item_returns_warnings() {
+	let mut app = create_test_app();
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+
+	for (title, front, expected) in [
+		("Blank", "", json!(["`front` is empty"])),
+		("Greeting", "Hello", json!([])),
+	] {
+		let request = Request::builder()
+			.uri("/items")
+			.method("POST")
+			.header("Content-Type", "application/json")
+			.body(Body::from(
+				json!({
+					"item_type_id": item_type.get_id(),
+					"title": title,
+					"item_data": {"front": front, "back": "World"}
+				})
+				.to_string(),
+			))
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		let created: serde_json::Value = serde_json::from_slice(&body).unwrap();
+		assert_eq!(created["warnings"], expected);
+		assert_eq!(created["title"], title);
+
+		let request = Request::builder()
+			.uri(format!("/items/{}", created["id"].as_str().unwrap()))
+			.method("GET")
+			.body(Body::empty())
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+	}
+}
+
 /// Tests retrieving an item by ID via the API
 ///
 /// This test verifies:
@@ -184,9 +232,8 @@ async fn test_list_items() {
 /// Tests retrieving a non-existent item via the API
 ///
 /// This test verifies:
-/// 1. A GET request to /items/{id} with a non-existent ID returns null
-/// 2. The response has a 200 OK status (not 404, as the endpoint returns null for non-existent items)
-/// 3. The response body can be correctly deserialized as a null Option<Item>
+/// 1. A GET request to /items/{id} with a non-existent ID returns a 404 status
+/// 2. The error body carries the `item_not_found` code
 #[tokio::test]
 async fn test_get_nonexistent_item() {
 	// Create our test app
@@ -205,15 +252,589 @@ async fn test_get_nonexistent_item() {
 	// Send the request to the application and get the response
 	let response = app.call(request).await.unwrap();
 
-	// Check that the response has a 200 OK status
+	// Check that the response has a 404 Not Found status
+	assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+	// Check that the error body says it was the item that was missing
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+	assert_eq!(error["code"], "item_not_found");
+}
+
+/// Tests that creating an item whose data doesn't fit its item type is rejected
+///
+/// This test verifies:
+/// 1. A POST request to /items for a Cloze item without a `clozes` array returns 400
+/// 2. The error message names the missing field
+#[tokio::test]
+async fn test_create_item_invalid_item_data() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	// First create a Cloze item type
+	let item_type = create_item_type(&mut app, "Cloze".to_string()).await;
+
+	// Create a request with item data that has no clozes
+	let request = Request::builder()
+		.uri("/items")
+		.method("POST")
+		.header("Content-Type", "application/json")
+		.body(Body::from(
+			serde_json::to_string(&json!({
+				"item_type_id": item_type.get_id(),
+				"title": "Broken Cloze",
+				"item_data": {"text": "The capital of France is Paris"}
+			}))
+			.unwrap(),
+		))
+		.unwrap();
+
+	// Send the request to the application and get the response
+	let response = app.call(request).await.unwrap();
+
+	// Check that the response has a 400 Bad Request status
+	assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+	// Check that the error describes the problem
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+	assert!(
+		error["error"].as_str().unwrap().contains("clozes"),
+		"unexpected error body: {}",
+		error
+	);
+}
+
+/// Tests that malformed JSON bodies are rejected with a 422
+///
+/// This test verifies:
+/// 1. A syntactically invalid body is a 422 with a JSON error body
+/// 2. A body with a field of the wrong type is a 422 naming that field
+#[tokio::test]
+async fn test_create_item_malformed_json() {
+	let mut app = create_test_app();
+
+	async fn post_items(app: &mut axum::Router, body: &str) -> (StatusCode, serde_json::Value) {
+		let request = Request::builder()
+			.uri("/items")
+			.method("POST")
+			.header("Content-Type", "application/json")
+			.body(Body::from(body.to_string()))
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		let status = response.status();
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		(status, serde_json::from_slice(&body).unwrap())
+	}
+
+	// Syntactically invalid JSON
+	let (status, error) = post_items(&mut app, r#"{"title":}"#).await;
+	assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+	assert!(
+		error["error"]
+			.as_str()
+			.unwrap()
+			.contains("line 1 column 10"),
+		"unexpected error body: {}",
+		error
+	);
+
+	// Valid JSON with a field of the wrong type
+	let (status, error) = post_items(
+		&mut app,
+		r#"{"item_type_id": "x", "title": 5, "item_data": {}}"#,
+	)
+	.await;
+	assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+	assert!(
+		error["error"].as_str().unwrap().contains("title"),
+		"unexpected error body: {}",
+		error
+	);
+}
+
+/// Tests creating an item with an item type that doesn't exist
+///
+/// This test verifies:
+/// 1. The request is rejected with a 400 status
+/// 2. The error body carries the `item_type_unknown` code
+#[tokio::test]
+async fn test_create_item_unknown_item_type() {
+	let mut app = create_test_app();
+
+	let request = Request::builder()
+		.uri("/items")
+		.method("POST")
+		.header("Content-Type", "application/json")
+		.body(Body::from(
+			serde_json::to_string(&json!({
+				"item_type_id": "item-type-missing",
+				"title": "Orphan",
+				"item_data": {"front": "F", "back": "B"}
+			}))
+			.unwrap(),
+		))
+		.unwrap();
+
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+	assert_eq!(error["code"], "item_type_unknown");
+}
+
+/// Tests that creating an item with a client-supplied id is idempotent
+///
+/// This test verifies:
+/// 1. Posting the same id twice returns the same item both times
+/// 2. Only one item exists afterwards
+/// 3. An id that isn't a UUID is rejected with a 400
+#[tokio::test]
+async fn test_create_item_with_id_is_idempotent() {
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let id = uuid::Uuid::new_v4().to_string();
+
+	async fn post_item(
+		app: &mut axum::Router,
+		body: serde_json::Value,
+	) -> (StatusCode, serde_json::Value) {
+		let request = Request::builder()
+			.uri("/items")
+			.method("POST")
+			.header("Content-Type", "application/json")
+			.body(Body::from(serde_json::to_string(&body).unwrap()))
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		let status = response.status();
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		(status, serde_json::from_slice(&body).unwrap())
+	}
+
+	let body = json!({
+		"id": id,
+		"item_type_id": item_type.get_id(),
+		"title": "Retried Item",
+		"item_data": {"front": "F", "back": "B"}
+	});
+
+	let (status, first) = post_item(&mut app, body.clone()).await;
+	assert_eq!(status, StatusCode::OK);
+	assert_eq!(first["id"], format!("item-{}", id));
+
+	let (status, second) = post_item(&mut app, body).await;
+	assert_eq!(status, StatusCode::OK);
+	assert_eq!(second, first);
+
+	// Only one item was created
+	let request = Request::builder()
+		.uri("/items")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let items: Vec<Item> = serde_json::from_slice(&body).unwrap();
+	assert_eq!(items.len(), 1);
+
+	// Malformed ids are rejected
+	let (status, error) = post_item(
+		&mut app,
+		json!({
+			"id": "not-a-uuid",
+			"item_type_id": item_type.get_id(),
+			"title": "Bad Id",
+			"item_data": {"front": "F", "back": "B"}
+		}),
+	)
+	.await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+	assert_eq!(error["code"], "invalid_id");
+}
+
+/// Tests fetching an item together with its cards and tags
+///
+/// This test verifies:
+/// 1. A GET request to /items/{item_id}/full returns a 200 OK status
+/// 2. The item, cards and tags sections are all populated
+#[tokio::test]
+async fn test_get_item_full() {
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Test Item Type".to_string()).await;
+	let item = create_item(&mut app, &item_type.get_id(), "Full Item".to_string(), None).await;
+	let tag = create_tag(&mut app, "Important".to_string()).await;
+
+	let request = Request::builder()
+		.uri(format!("/items/{}/tags/{}", item.get_id(), tag.get_id()))
+		.method("POST")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert!(response.status().is_success());
+
+	let request = Request::builder()
+		.uri(format!("/items/{}/full", item.get_id()))
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
 	assert_eq!(response.status(), StatusCode::OK);
 
-	// Convert the response body into bytes for parsing
 	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let full: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+	assert_eq!(full["item"]["title"], "Full Item");
+	let cards = full["cards"].as_array().unwrap();
+	assert_eq!(cards.len(), 2);
+	assert!(cards.iter().all(|c| c["item_id"] == json!(item.get_id())));
+	let tags = full["tags"].as_array().unwrap();
+	assert_eq!(tags.len(), 1);
+	assert_eq!(tags[0]["name"], "Important");
+}
 
-	// Parse the body as JSON into an Option<Item>
-	let item: Option<Item> = serde_json::from_slice(&body).unwrap();
+/// Tests previewing the cards an item would get via the API
+///
+/// This test verifies:
+/// 1. A POST request to /items/preview_cards returns the card count for Basic,
+///    Vocabulary and Cloze items
+/// 2. No items are created by previewing
+#[tokio::test]
+async fn test_preview_cards() {
+	let mut app = create_test_app();
+
+	let cases = [
+		("Basic", json!({"front": "Hello", "back": "World"}), 1),
+		(
+			"Vocabulary",
+			json!({"term": "hello", "definition": "hi"}),
+			2,
+		),
+		(
+			"Cloze",
+			json!({"text": "Paris is in France, Rome is in Italy", "clozes": ["Paris", "France", "Rome"]}),
+			3,
+		),
+	];
+
+	for (type_name, item_data, expected) in cases {
+		let item_type = create_item_type(&mut app, type_name.to_string()).await;
+
+		let request = Request::builder()
+			.uri("/items/preview_cards")
+			.method("POST")
+			.header("Content-Type", "application/json")
+			.body(Body::from(
+				json!({"item_type_id": item_type.get_id(), "item_data": item_data}).to_string(),
+			))
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		let card_count: usize = serde_json::from_slice(&body).unwrap();
+		assert_eq!(card_count, expected, "{} preview", type_name);
+	}
+
+	// Nothing was created
+	let request = Request::builder()
+		.uri("/items")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let items: Vec<Item> = serde_json::from_slice(&body).unwrap();
+	assert!(items.is_empty());
+}
+
+/// Tests listing items with their item type names
+///
+/// This test verifies:
+/// 1. By default, items only carry their item type's id
+/// 2. `expand=item_type` adds each item's item type name
+/// 3. The expansion composes with the usual filters
+#[tokio::test]
+async fn test_list_items_expand_item_type() {
+	let mut app = create_test_app();
+
+	let vocab = create_item_type(&mut app, "Vocabulary".to_string()).await;
+	let basic = create_item_type(&mut app, "Basic".to_string()).await;
+	let word = create_item(
+		&mut app,
+		&vocab.get_id(),
+		"Word".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	create_item(
+		&mut app,
+		&basic.get_id(),
+		"Rule".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+
+	let mut list = async |uri: String| {
+		let request = Request::builder()
+			.uri(uri)
+			.method("GET")
+			.body(Body::empty())
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		serde_json::from_slice::<Vec<serde_json::Value>>(&body).unwrap()
+	};
+
+	let items = list("/items".to_string()).await;
+	assert_eq!(items.len(), 2);
+	assert!(
+		items
+			.iter()
+			.all(|item| item.get("item_type_name").is_none())
+	);
+
+	let items = list("/items?expand=item_type".to_string()).await;
+	assert_eq!(items.len(), 2);
+	for item in &items {
+		let expected = if item["title"] == "Word" {
+			"Vocabulary"
+		} else {
+			"Basic"
+		};
+		assert_eq!(item["item_type_name"], expected);
+	}
+
+	let items = list(format!(
+		"/items?expand=item_type&item_type_id={}",
+		vocab.get_id()
+	))
+	.await;
+	assert_eq!(items.len(), 1);
+	assert_eq!(items[0]["id"], word.get_id().0);
+	assert_eq!(items[0]["item_type"], vocab.get_id().0);
+	assert_eq!(items[0]["item_type_name"], "Vocabulary");
+}
+
+/// Tests duplicating an item via the API
+///
+/// This test verifies:
+/// 1. A POST request to /items/{item_id}/duplicate returns the new item
+/// 2. The copy has the source's data and tags, but its own ID and cards
+#[tokio::test]
+async fn test_duplicate_item() {
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Test Item Type".to_string()).await;
+	let item = create_item(&mut app, &item_type.get_id(), "Original".to_string(), None).await;
+	let tag = create_tag(&mut app, "Important".to_string()).await;
+
+	let request = Request::builder()
+		.uri(format!("/items/{}/tags/{}", item.get_id(), tag.get_id()))
+		.method("POST")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert!(response.status().is_success());
+
+	let request = Request::builder()
+		.uri(format!("/items/{}/duplicate", item.get_id()))
+		.method("POST")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let copy: Item = serde_json::from_slice(&body).unwrap();
+	assert_ne!(copy.get_id(), item.get_id());
+	assert_eq!(copy.get_title(), "Original (copy)");
+	assert_eq!(copy.get_data(), item.get_data());
+
+	let request = Request::builder()
+		.uri(format!("/items/{}/full", copy.get_id()))
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let full: serde_json::Value = serde_json::from_slice(&body).unwrap();
+	let tags = full["tags"].as_array().unwrap();
+	assert_eq!(tags.len(), 1);
+	assert_eq!(tags[0]["id"], json!(tag.get_id()));
+
+	let source_cards = get_cards_for_item(&mut app, &item.get_id()).await;
+	let cards = full["cards"].as_array().unwrap();
+	assert_eq!(cards.len(), source_cards.len());
+	for card in cards {
+		assert!(card["last_review"].is_null());
+		assert!(source_cards.iter().all(|c| card["id"] != json!(c.get_id())));
+	}
+}
+
+/// Tests creating many items in one call via the API
+///
+/// This test verifies:
+/// 1. A POST request to /items/batch with 50 items creates all of them
+/// 2. The created items are returned in request order
+/// 3. Every item gets its cards
+#[tokio::test]
+async fn test_create_items_batch() {
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let payload: Vec<serde_json::Value> = (0..50)
+		.map(|i| {
+			json!({
+				"item_type_id": item_type.get_id(),
+				"title": format!("Word {}", i),
+				"item_data": {"front": format!("Front {}", i), "back": "Back"}
+			})
+		})
+		.collect();
+
+	let request = Request::builder()
+		.uri("/items/batch")
+		.method("POST")
+		.header("Content-Type", "application/json")
+		.body(Body::from(serde_json::to_string(&payload).unwrap()))
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
 
-	// Check that the item is None (null in JSON)
-	assert!(item.is_none(), "Non-existent item should return null");
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let created: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+	assert_eq!(created.len(), 50);
+	for (i, item) in created.iter().enumerate() {
+		assert_eq!(item["title"], format!("Word {}", i));
+		assert_eq!(item["warnings"], json!([]));
+	}
+
+	let item: Item = serde_json::from_value(created[49].clone()).unwrap();
+	assert_eq!(get_cards_for_item(&mut app, &item.get_id()).await.len(), 1);
+
+	let request = Request::builder()
+		.uri("/items")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let items: Vec<Item> = serde_json::from_slice(&body).unwrap();
+	assert_eq!(items.len(), 50);
+}
+
+/// Tests that a batch create with a bad item creates nothing
+///
+/// This test verifies:
+/// 1. The response is a 400 naming the index of the bad item
+/// 2. None of the batch's items are created
+#[tokio::test]
+async fn test_create_items_batch_rolls_back() {
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let payload = json!([
+		{
+			"item_type_id": item_type.get_id(),
+			"title": "Good",
+			"item_data": {"front": "Front", "back": "Back"}
+		},
+		{
+			"item_type_id": item_type.get_id(),
+			"title": "Bad",
+			"item_data": {"front": "Front"}
+		}
+	]);
+
+	let request = Request::builder()
+		.uri("/items/batch")
+		.method("POST")
+		.header("Content-Type", "application/json")
+		.body(Body::from(serde_json::to_string(&payload).unwrap()))
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+	assert_eq!(error["code"], "invalid_batch_item");
+	assert!(
+		error["error"].as_str().unwrap().starts_with("Item 1:"),
+		"unexpected error body: {}",
+		error
+	);
+
+	let request = Request::builder()
+		.uri("/items")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let items: Vec<Item> = serde_json::from_slice(&body).unwrap();
+	assert!(items.is_empty());
+}
+
+/// Tests moving an item to a different item type via the API
+///
+/// This test verifies:
+/// 1. A PUT request to /items/{item_id}/type changes the item's type
+/// 2. A Basic item (1 card) moved to a Test type gets its second card, and
+///    keeps its first
+/// 3. An unknown item type is a 400 and leaves the item alone
+#[tokio::test]
+async fn test_change_item_type() {
+	let mut app = create_test_app();
+
+	let basic = create_item_type(&mut app, "Basic".to_string()).await;
+	let test_type = create_item_type(&mut app, "Test Type".to_string()).await;
+	let item = create_item(
+		&mut app,
+		&basic.get_id(),
+		"Word".to_string(),
+		Some(json!({"front": "Hello", "back": "World"})),
+	)
+	.await;
+	let original = get_cards_for_item(&mut app, &item.get_id()).await;
+	assert_eq!(original.len(), 1);
+
+	let request = Request::builder()
+		.uri(format!("/items/{}/type", item.get_id()))
+		.method("PUT")
+		.header("Content-Type", "application/json")
+		.body(Body::from(
+			serde_json::to_string(&json!({"item_type_id": test_type.get_id()})).unwrap(),
+		))
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let changed: Item = serde_json::from_slice(&body).unwrap();
+	assert_eq!(changed.get_id(), item.get_id());
+	assert_eq!(changed.get_item_type(), test_type.get_id());
+
+	let mut cards = get_cards_for_item(&mut app, &item.get_id()).await;
+	cards.sort_by_key(|card| card.get_card_index());
+	assert_eq!(cards.len(), 2);
+	assert_eq!(cards[0].get_id(), original[0].get_id());
+	assert_eq!(cards[1].get_card_index(), 1);
+
+	let request = Request::builder()
+		.uri(format!("/items/{}/type", item.get_id()))
+		.method("PUT")
+		.header("Content-Type", "application/json")
+		.body(Body::from(
+			serde_json::to_string(&json!({"item_type_id": "item_type-missing"})).unwrap(),
+		))
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+	assert_eq!(get_cards_for_item(&mut app, &item.get_id()).await.len(), 2);
 }
@@ -151,6 +151,55 @@ async fn test_list_item_types() {
 	assert!(found, "Created item type should be in the list");
 }
 
+/// Tests listing item types with item and card counts via the API
+///
+/// This test verifies:
+/// 1. Without `include_counts`, item types have no count fields
+/// 2. With `include_counts=true`, each item type carries `item_count` and `card_count`
+/// 3. The counts reflect the items created across two types
+#[tokio::test]
+async fn test_list_item_types_include_counts() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	// Create two item types with different numbers of items
+	let basic_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let test_type = create_item_type(&mut app, "Test Type".to_string()).await;
+	let data = Some(json!({"front": "F", "back": "B"}));
+	create_item(&mut app, &basic_type.get_id(), "Basic 1".to_string(), data.clone()).await;
+	create_item(&mut app, &basic_type.get_id(), "Basic 2".to_string(), data.clone()).await;
+	create_item(&mut app, &test_type.get_id(), "Test 1".to_string(), data).await;
+
+	// Lists item types at the given URI and returns the parsed body
+	async fn list(app: &mut axum::Router, uri: &str) -> Vec<Value> {
+		let request = Request::builder()
+			.uri(uri)
+			.method("GET")
+			.body(Body::empty())
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		serde_json::from_slice(&body).unwrap()
+	}
+
+	// The default response shape is unchanged
+	let plain = list(&mut app, "/item_types").await;
+	assert!(plain.iter().all(|it| it.get("item_count").is_none()));
+	assert!(plain.iter().all(|it| it.get("card_count").is_none()));
+
+	// With counts, each type reports its items and cards
+	let counted = list(&mut app, "/item_types?include_counts=true").await;
+	let find = |id: &str| counted.iter().find(|it| it["id"] == id).unwrap().clone();
+	let basic = find(&basic_type.get_id().0);
+	assert_eq!(basic["item_count"], 2);
+	assert_eq!(basic["card_count"], 2);
+	let test = find(&test_type.get_id().0);
+	assert_eq!(test["item_count"], 1);
+	assert_eq!(test["card_count"], 2);
+	assert_eq!(test["name"], "Test Type");
+}
+
 /// Tests retrieving a non-existent item type via the API
 ///
 /// This test verifies:
@@ -245,3 +294,143 @@ async fn test_get_items_by_item_type() {
 		);
 	}
 }
+
+/// Tests getting all cards of a specific item type via the API
+///
+/// This test verifies:
+/// 1. A GET request to /item_types/{id}/cards returns the cards of that type only
+/// 2. An item type without cards gives an empty list
+/// 3. An unknown item type gives a 404
+#[tokio::test]
+async fn test_get_cards_by_item_type() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	// Two items of one type, one of another
+	let item_type = create_item_type(&mut app, "Test Item Type for Cards".to_string()).await;
+	let other_type = create_item_type(&mut app, "Test Other Type".to_string()).await;
+	let empty_type = create_item_type(&mut app, "Test Empty Type".to_string()).await;
+	for i in 1..=2 {
+		create_item(
+			&mut app,
+			&item_type.get_id(),
+			format!("Test Item {} for Type", i),
+			None,
+		)
+		.await;
+	}
+	create_item(
+		&mut app,
+		&other_type.get_id(),
+		"Other Item".to_string(),
+		None,
+	)
+	.await;
+
+	let request = Request::builder()
+		.uri(format!("/item_types/{}/cards", item_type.get_id()))
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let cards: Vec<Value> = serde_json::from_slice(&body).unwrap();
+
+	// "Test" item types have two cards per item
+	assert_eq!(cards.len(), 4);
+
+	// An item type without items has no cards, which is not an error
+	let request = Request::builder()
+		.uri(format!("/item_types/{}/cards", empty_type.get_id()))
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let cards: Vec<Value> = serde_json::from_slice(&body).unwrap();
+	assert!(cards.is_empty());
+
+	// An unknown item type is a 404
+	let request = Request::builder()
+		.uri(format!("/item_types/{}/cards", uuid::Uuid::new_v4()))
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// Tests that item data is validated against an item type's JSON Schema
+///
+/// This test verifies:
+/// 1. A POST request to /item_types can attach an item data schema
+/// 2. Creating an item that matches the schema succeeds
+/// 3. Creating an item that violates the schema returns 400 with the violations
+#[tokio::test]
+async fn test_item_type_item_data_schema() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	// Create an item type whose items must have a string `term`
+	let request = Request::builder()
+		.uri("/item_types")
+		.method("POST")
+		.header("Content-Type", "application/json")
+		.body(Body::from(
+			serde_json::to_string(&json!({
+				"name": "Vocabulary",
+				"item_data_schema": {
+					"type": "object",
+					"required": ["term"],
+					"properties": {"term": {"type": "string"}}
+				}
+			}))
+			.unwrap(),
+		))
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let item_type: Value = serde_json::from_slice(&body).unwrap();
+	assert_eq!(item_type["item_data_schema"]["required"], json!(["term"]));
+
+	// Sends a request to create an item of the new type with the given data
+	let create_item_request = |item_data: Value| {
+		Request::builder()
+			.uri("/items")
+			.method("POST")
+			.header("Content-Type", "application/json")
+			.body(Body::from(
+				serde_json::to_string(&json!({
+					"item_type_id": item_type["id"],
+					"title": "Entry",
+					"item_data": item_data
+				}))
+				.unwrap(),
+			))
+			.unwrap()
+	};
+
+	// Item data that matches the schema is accepted
+	let response = app
+		.call(create_item_request(json!({"term": "Hund"})))
+		.await
+		.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+
+	// Item data that violates the schema is rejected with the violations
+	let response = app
+		.call(create_item_request(json!({"definition": "dog"})))
+		.await
+		.unwrap();
+	assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let error: Value = serde_json::from_slice(&body).unwrap();
+	assert!(
+		error["error"].as_str().unwrap().contains("\"term\" is a required property"),
+		"unexpected error body: {}",
+		error
+	);
+}
@@ -0,0 +1,149 @@
+/// Integration tests for the Prometheus metrics endpoint
+///
+/// This file contains tests for `GET /metrics`:
+/// - Recording a review increments `reviews_recorded_total` for its rating
+/// - Handled requests are counted in `http_requests_total`
+/// - The endpoint isn't served unless metrics are turned on
+use axum::{
+	Router,
+	body::{Body, to_bytes},
+	http::{Request, StatusCode, header},
+};
+use hippocampus::{AppOptions, create_app_with_options, db::init_pool};
+use serde_json::json;
+use std::sync::Arc;
+use tower::Service;
+
+mod common;
+use common::*;
+
+/// Creates a test application with metrics turned on or off
+///
+/// ### Arguments
+///
+/// * `metrics` - Whether the application should serve metrics
+///
+/// ### Returns
+///
+/// An Axum Router connected to a fresh in-memory database
+fn create_metrics_test_app(metrics: bool) -> Router {
+	let unique_id = uuid::Uuid::new_v4();
+	let database_url = format!("file:test_{}?mode=memory&cache=shared", unique_id);
+	let pool = Arc::new(init_pool(&database_url));
+
+	let conn = &mut pool.get().unwrap();
+	hippocampus::run_migrations(conn);
+
+	create_app_with_options(
+		pool,
+		AppOptions {
+			metrics,
+			..Default::default()
+		},
+	)
+}
+
+/// Scrapes `/metrics`, returning the response body
+async fn scrape(app: &mut Router) -> String {
+	let request = Request::builder()
+		.uri("/metrics")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	assert!(
+		response.headers()[header::CONTENT_TYPE]
+			.to_str()
+			.unwrap()
+			.starts_with("text/plain; version=0.0.4")
+	);
+
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	String::from_utf8(body.to_vec()).unwrap()
+}
+
+/// Finds the value of a sample in scraped metrics
+///
+/// ### Arguments
+///
+/// * `metrics` - The scraped metrics
+/// * `sample` - The metric name and labels, e.g. `reviews_recorded_total{rating="3"}`
+///
+/// ### Returns
+///
+/// The sample's value, or None if it isn't there
+fn sample_value(metrics: &str, sample: &str) -> Option<u64> {
+	metrics.lines().find_map(|line| {
+		line.strip_prefix(sample)
+			.and_then(|rest| rest.strip_prefix(' '))
+			.map(|value| value.parse().unwrap())
+	})
+}
+
+/// Tests that recording a review increments the reviews counter
+///
+/// This test verifies:
+/// 1. `reviews_recorded_total` is reported for every rating
+/// 2. A review recorded through the API increments its rating's counter
+/// 3. The requests made so far show up in `http_requests_total`
+#[tokio::test]
+async fn test_metrics_count_reviews() {
+	let mut app = create_metrics_test_app(true);
+
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Metrics Item".to_string(),
+		Some(json!({"front": "Hello", "back": "World"})),
+	)
+	.await;
+	let card = &get_cards_for_item(&mut app, &item.get_id()).await[0];
+
+	let before = scrape(&mut app).await;
+	let reviews_before = sample_value(&before, "reviews_recorded_total{rating=\"3\"}").unwrap();
+
+	let request = Request::builder()
+		.uri("/reviews")
+		.method("POST")
+		.header("Content-Type", "application/json")
+		.body(Body::from(
+			json!({"card_id": card.get_id(), "rating": 3}).to_string(),
+		))
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+
+	let after = scrape(&mut app).await;
+	assert_eq!(
+		sample_value(&after, "reviews_recorded_total{rating=\"3\"}").unwrap(),
+		reviews_before + 1
+	);
+	assert!(
+		sample_value(
+			&after,
+			"http_requests_total{method=\"POST\",status=\"200\"}"
+		)
+		.unwrap() >= 3
+	);
+	assert!(sample_value(&after, "db_pool_connections_in_use").is_some());
+}
+
+/// Tests that metrics aren't served unless turned on
+///
+/// This test verifies that `GET /metrics` is a 404 with the default options
+#[tokio::test]
+async fn test_metrics_disabled_by_default() {
+	let mut app = create_metrics_test_app(false);
+
+	let request = Request::builder()
+		.uri("/metrics")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+
+	assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
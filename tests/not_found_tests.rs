@@ -0,0 +1,182 @@
+/// Integration tests for requests against nonexistent resources
+///
+/// This file checks that every route addressing a resource by ID answers a
+/// missing ID with a 404 (and the matching error code) rather than a 500 or
+/// a 200 with a null body.
+use axum::{
+	body::{Body, to_bytes},
+	http::{Request, StatusCode},
+};
+use serde_json::{Value, json};
+use tower::Service;
+
+mod common;
+use common::*;
+
+/// Sends a request and returns its status and parsed JSON body
+///
+/// ### Arguments
+///
+/// * `app` - The test application
+/// * `method` - The HTTP method
+/// * `uri` - The request URI
+/// * `body` - The JSON request body, if any
+///
+/// ### Returns
+///
+/// The response status and body (null if the body isn't JSON)
+async fn send(
+	app: &mut axum::Router,
+	method: &str,
+	uri: &str,
+	body: Option<Value>,
+) -> (StatusCode, Value) {
+	let request = Request::builder().uri(uri).method(method);
+	let request = match body {
+		Some(body) => request
+			.header("Content-Type", "application/json")
+			.body(Body::from(serde_json::to_string(&body).unwrap())),
+		None => request.body(Body::empty()),
+	}
+	.unwrap();
+
+	let response = app.call(request).await.unwrap();
+	let status = response.status();
+	let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	(
+		status,
+		serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+	)
+}
+
+/// Tests that GET requests for nonexistent resources return 404
+///
+/// This test verifies:
+/// 1. Every GET route taking an item, card or item type ID returns 404 for
+///    an unknown ID
+/// 2. The error body names which kind of resource was missing
+#[tokio::test]
+async fn test_get_nonexistent_resources() {
+	let mut app = create_test_app();
+
+	let cases = [
+		("/items/item-missing", "item_not_found"),
+		("/items/item-missing/cards", "item_not_found"),
+		("/items/item-missing/full", "item_not_found"),
+		("/items/item-missing/tags", "item_not_found"),
+		("/items/item-missing/children_graph", "item_not_found"),
+		("/items/item-missing/parent_graph", "item_not_found"),
+		("/cards/card-missing", "card_not_found"),
+		("/cards/card-missing/reviews", "card_not_found"),
+		("/cards/card-missing/tags", "card_not_found"),
+		("/cards/card-missing/next_reviews", "card_not_found"),
+		("/cards/card-missing/preview", "card_not_found"),
+		("/item_types/item-type-missing", "item_type_not_found"),
+		("/item_types/item-type-missing/items", "item_type_not_found"),
+		("/item_types/item-type-missing/cards", "item_type_not_found"),
+	];
+
+	for (uri, code) in cases {
+		let (status, body) = send(&mut app, "GET", uri, None).await;
+		assert_eq!(
+			status,
+			StatusCode::NOT_FOUND,
+			"GET {} returned {}: {}",
+			uri,
+			status,
+			body
+		);
+		assert_eq!(body["code"], code, "GET {}", uri);
+	}
+}
+
+/// Tests that updates to nonexistent resources return 404
+///
+/// This test verifies:
+/// 1. Every route updating a card, item or item type by ID returns 404 for an
+///    unknown ID
+/// 2. Placing a card relative to, or swapping it with, an unknown card is
+///    also a 404
+#[tokio::test]
+async fn test_update_nonexistent_resources() {
+	let mut app = create_test_app();
+
+	let cases = [
+		(
+			"PATCH",
+			"/items/item-missing",
+			Some(json!({"title": "New"})),
+		),
+		("DELETE", "/items/item-missing", None),
+		("POST", "/items/item-missing/duplicate", None),
+		(
+			"PATCH",
+			"/item_types/item-type-missing",
+			Some(json!({"name": "New"})),
+		),
+		("PATCH", "/cards/card-missing/priority", Some(json!(0.5))),
+		("PATCH", "/cards/card-missing/suspend", Some(json!(true))),
+		(
+			"PATCH",
+			"/cards/card-missing/sort_position",
+			Some(json!({"position": "top"})),
+		),
+		("DELETE", "/cards/card-missing/sort_position", None),
+		("POST", "/cards/card-missing/bury", None),
+		(
+			"PUT",
+			"/cards/card-missing/note",
+			Some(json!({"note": "mnemonic"})),
+		),
+		(
+			"PUT",
+			"/cards/card-missing/difficulty",
+			Some(json!({"difficulty": 0.5})),
+		),
+	];
+
+	for (method, uri, body) in cases {
+		let (status, body) = send(&mut app, method, uri, body).await;
+		assert_eq!(
+			status,
+			StatusCode::NOT_FOUND,
+			"{} {} returned {}: {}",
+			method,
+			uri,
+			status,
+			body
+		);
+	}
+
+	// Placing an existing card relative to a missing one
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let card = get_cards_for_item(&mut app, &item.get_id()).await.remove(0);
+
+	let (status, body) = send(
+		&mut app,
+		"PATCH",
+		&format!("/cards/{}/sort_position", card.get_id()),
+		Some(json!({"position": "before", "card_id": "card-missing"})),
+	)
+	.await;
+	assert_eq!(status, StatusCode::NOT_FOUND);
+	assert_eq!(body["code"], "card_not_found");
+
+	// Swapping an existing card with a missing one
+	let (status, body) = send(
+		&mut app,
+		"POST",
+		"/cards/swap",
+		Some(json!({"card_a": card.get_id(), "card_b": "card-missing"})),
+	)
+	.await;
+	assert_eq!(status, StatusCode::NOT_FOUND);
+	assert_eq!(body["code"], "card_not_found");
+}
@@ -0,0 +1,160 @@
+/// Integration tests for per-request tracing
+///
+/// This file contains tests for the request tracing layer:
+/// - Each request is logged with its method, path, status and latency
+/// - Handler `#[instrument]` spans nest beneath the request span
+/// - The request log level is respected
+use axum::{
+	Router,
+	body::Body,
+	http::{Request, StatusCode},
+};
+use hippocampus::{AppOptions, create_app_with_options, db::init_pool};
+use std::{
+	io::Write,
+	sync::{Arc, Mutex},
+};
+use tower::ServiceExt;
+use tracing::Level;
+use tracing_subscriber::{filter::LevelFilter, fmt::MakeWriter, prelude::*};
+
+/// A log sink that collects everything written to it in memory
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl CapturedLogs {
+	fn contents(&self) -> String {
+		String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+	}
+}
+
+impl Write for CapturedLogs {
+	fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+		self.0.lock().unwrap().extend_from_slice(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> std::io::Result<()> {
+		Ok(())
+	}
+}
+
+impl<'a> MakeWriter<'a> for CapturedLogs {
+	type Writer = CapturedLogs;
+
+	fn make_writer(&'a self) -> Self::Writer {
+		self.clone()
+	}
+}
+
+/// Creates a test application that logs requests at the given level
+///
+/// ### Arguments
+///
+/// * `request_log_level` - The level to log requests at
+///
+/// ### Returns
+///
+/// An Axum Router connected to a fresh in-memory database
+fn create_tracing_test_app(request_log_level: Level) -> Router {
+	let unique_id = uuid::Uuid::new_v4();
+	let database_url = format!("file:test_{}?mode=memory&cache=shared", unique_id);
+	let pool = Arc::new(init_pool(&database_url));
+
+	let conn = &mut pool.get().unwrap();
+	hippocampus::run_migrations(conn);
+
+	create_app_with_options(
+		pool,
+		AppOptions {
+			request_log_level,
+			..Default::default()
+		},
+	)
+}
+
+/// Sends a GET request to `/item_types` while capturing logs at `filter`
+///
+/// ### Returns
+///
+/// The response status and everything that was logged
+async fn get_item_types_with_logs(
+	request_log_level: Level,
+	filter: LevelFilter,
+) -> (StatusCode, String) {
+	let logs = CapturedLogs::default();
+	let subscriber = tracing_subscriber::registry().with(
+		tracing_subscriber::fmt::layer()
+			.with_ansi(false)
+			.with_writer(logs.clone())
+			.with_filter(filter),
+	);
+	let _guard = tracing::subscriber::set_default(subscriber);
+
+	let app = create_tracing_test_app(request_log_level);
+	let request = Request::builder()
+		.uri("/item_types")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+
+	let response = app.oneshot(request).await.unwrap();
+
+	(response.status(), logs.contents())
+}
+
+/// Tests that a request is logged with its method, path, status and latency
+///
+/// This test verifies:
+/// 1. A "finished processing request" line is logged for the request
+/// 2. That line carries the request's method, path, status and latency
+#[tokio::test]
+async fn test_request_is_logged_with_status_and_latency() {
+	let (status, logs) = get_item_types_with_logs(Level::INFO, LevelFilter::INFO).await;
+
+	assert_eq!(status, StatusCode::OK);
+
+	let finished = logs
+		.lines()
+		.find(|line| line.contains("finished processing request"))
+		.unwrap_or_else(|| panic!("no request completion line in logs:\n{}", logs));
+	assert!(finished.contains("method=GET"), "{}", finished);
+	assert!(finished.contains("path=/item_types"), "{}", finished);
+	assert!(finished.contains("status=200"), "{}", finished);
+	assert!(finished.contains("latency_ms="), "{}", finished);
+}
+
+/// Tests that handler spans nest beneath the request span
+///
+/// This test verifies:
+/// 1. Events logged inside an `#[instrument]`ed handler include the request
+///    span as their parent, followed by the handler's own span
+#[tokio::test]
+async fn test_handler_spans_nest_under_request_span() {
+	let (_, logs) = get_item_types_with_logs(Level::INFO, LevelFilter::INFO).await;
+
+	let handler_line = logs
+		.lines()
+		.find(|line| line.contains("Retrieved 0 item types"))
+		.unwrap_or_else(|| panic!("no handler line in logs:\n{}", logs));
+	let request_span = handler_line
+		.find("request{")
+		.unwrap_or_else(|| panic!("handler line has no request span: {}", handler_line));
+	let handler_span = handler_line
+		.find("list_item_types_handler")
+		.unwrap_or_else(|| panic!("handler line has no handler span: {}", handler_line));
+	assert!(request_span < handler_span, "{}", handler_line);
+}
+
+/// Tests that the request log level is respected
+///
+/// This test verifies:
+/// 1. With requests logged at DEBUG, an INFO-level subscriber sees no
+///    request completion line
+#[tokio::test]
+async fn test_request_log_level_is_respected() {
+	let (status, logs) = get_item_types_with_logs(Level::DEBUG, LevelFilter::INFO).await;
+
+	assert_eq!(status, StatusCode::OK);
+	assert!(!logs.contains("finished processing request"), "{}", logs);
+}
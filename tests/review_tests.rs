@@ -76,7 +76,13 @@ async fn test_create_review_rating_again() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Get the cards for the item
 	let cards = get_cards_for_item(&mut app, &item.get_id()).await;
@@ -144,7 +150,13 @@ async fn test_create_review_rating_hard() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Get the cards for the item
 	let cards = get_cards_for_item(&mut app, &item.get_id()).await;
@@ -208,7 +220,13 @@ async fn test_create_review_rating_good() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Get the cards for the item
 	let cards = get_cards_for_item(&mut app, &item.get_id()).await;
@@ -272,7 +290,13 @@ async fn test_create_review_rating_easy() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Get the cards for the item
 	let cards = get_cards_for_item(&mut app, &item.get_id()).await;
@@ -334,7 +358,13 @@ async fn test_create_multiple_reviews() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Get the cards for the item
 	let cards = get_cards_for_item(&mut app, &item.get_id()).await;
@@ -428,7 +458,13 @@ async fn test_create_review_rating_too_low() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Get the cards for the item
 	let cards = get_cards_for_item(&mut app, &item.get_id()).await;
@@ -471,7 +507,13 @@ async fn test_create_review_rating_too_high() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Get the cards for the item
 	let cards = get_cards_for_item(&mut app, &item.get_id()).await;
@@ -546,7 +588,13 @@ async fn test_list_reviews_for_card() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Get the cards for the item
 	let cards = get_cards_for_item(&mut app, &item.get_id()).await;
@@ -598,6 +646,67 @@ async fn test_list_reviews_for_card() {
 	assert!(has_review2, "Review 2 should be in the list");
 }
 
+/// Tests keeping what was answered with a review
+///
+/// This test verifies:
+/// 1. A POST request to /reviews with a `response` returns it with the review
+/// 2. The response is listed with the card's reviews
+/// 3. A review recorded without one has a null `response`
+#[tokio::test]
+async fn test_create_review_with_response() {
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Capital of Australia".to_string(),
+		Some(json!({"front": "Capital of Australia?", "back": "Canberra"})),
+	)
+	.await;
+	let card = &get_cards_for_item(&mut app, &item.get_id()).await[0];
+
+	let request = Request::builder()
+		.uri("/reviews")
+		.method("POST")
+		.header("Content-Type", "application/json")
+		.body(Body::from(
+			json!({
+				"card_id": card.get_id(),
+				"rating": 1,
+				"response": "Sydney"
+			})
+			.to_string(),
+		))
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let answered: Value = serde_json::from_slice(&body).unwrap();
+	assert_eq!(answered["response"], "Sydney");
+
+	std::thread::sleep(std::time::Duration::from_millis(10));
+	let unanswered = create_review(&mut app, &card.get_id(), 3).await;
+	assert!(unanswered["response"].is_null());
+
+	let request = Request::builder()
+		.uri(format!("/cards/{}/reviews", card.get_id()))
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let reviews: Vec<Value> = serde_json::from_slice(&body).unwrap();
+
+	// Newest first
+	assert_eq!(reviews.len(), 2);
+	assert_eq!(reviews[0]["id"], unanswered["id"]);
+	assert!(reviews[0]["response"].is_null());
+	assert_eq!(reviews[1]["id"], answered["id"]);
+	assert_eq!(reviews[1]["response"], "Sydney");
+}
+
 /// Tests listing reviews for a non-existent card
 ///
 /// This test verifies:
@@ -620,3 +729,171 @@ async fn test_list_reviews_nonexistent_card() {
 	// Check that the response has a 404 Not Found status
 	assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
+
+/// Tests listing reviews across cards with filters
+///
+/// This test verifies:
+/// 1. A GET request to /reviews returns reviews of every card, newest first
+/// 2. `rating` and `card_id` narrow the results, alone and combined
+/// 3. `limit` caps the page size
+/// 4. An out-of-range rating filter returns 400
+#[tokio::test]
+async fn test_list_reviews_with_filters() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	// Create two single-card items
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let mut card_ids = Vec::new();
+	for title in ["Item 1", "Item 2"] {
+		let item = create_item(
+			&mut app,
+			&item_type.get_id(),
+			title.to_string(),
+			Some(json!({"front": "F", "back": "B"})),
+		)
+		.await;
+		card_ids.push(get_cards_for_item(&mut app, &item.get_id()).await[0].get_id());
+	}
+
+	// Review the first card twice and the second once
+	create_review(&mut app, &card_ids[0], 1).await;
+	create_review(&mut app, &card_ids[0], 3).await;
+	create_review(&mut app, &card_ids[1], 3).await;
+
+	// Lists reviews at the given URI and returns the status and parsed body
+	async fn list(app: &mut axum::Router, uri: &str) -> (StatusCode, Value) {
+		let request = Request::builder()
+			.uri(uri)
+			.method("GET")
+			.body(Body::empty())
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		let status = response.status();
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		(status, serde_json::from_slice(&body).unwrap())
+	}
+
+	// All reviews, newest first
+	let (status, all) = list(&mut app, "/reviews").await;
+	assert_eq!(status, StatusCode::OK);
+	let all = all.as_array().unwrap().clone();
+	assert_eq!(all.len(), 3);
+	assert_eq!(all[0]["card_id"].as_str().unwrap(), card_ids[1].0);
+
+	// Filter by rating
+	let (_, good) = list(&mut app, "/reviews?rating=3").await;
+	assert_eq!(good.as_array().unwrap().len(), 2);
+
+	// Filter by card and rating combined
+	let (_, filtered) = list(
+		&mut app,
+		&format!("/reviews?card_id={}&rating=3", card_ids[0]),
+	)
+	.await;
+	let filtered = filtered.as_array().unwrap();
+	assert_eq!(filtered.len(), 1);
+	assert_eq!(filtered[0]["card_id"].as_str().unwrap(), card_ids[0].0);
+
+	// Limit the page size
+	let (_, page) = list(&mut app, "/reviews?limit=2&offset=2").await;
+	let page = page.as_array().unwrap();
+	assert_eq!(page.len(), 1);
+	assert_eq!(page[0], all[2]);
+
+	// An invalid rating filter is rejected
+	let (status, _) = list(&mut app, "/reviews?rating=7").await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+/// Tests recording reviews within a session
+///
+/// This test verifies:
+/// 1. A POST request to /sessions starts a session
+/// 2. Reviews posted with its `session_id` count towards the session
+/// 3. A POST request to /sessions/{session_id}/end reports the duration and
+///    the number of distinct cards reviewed
+/// 4. Ending the session again, or reviewing in it afterwards, is a conflict
+#[tokio::test]
+async fn test_review_session() {
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Test Type".to_string()).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let cards = get_cards_for_item(&mut app, &item.get_id()).await;
+	assert_eq!(cards.len(), 2);
+
+	async fn post(app: &mut axum::Router, uri: &str, body: Value) -> (StatusCode, Value) {
+		let request = Request::builder()
+			.uri(uri)
+			.method("POST")
+			.header("Content-Type", "application/json")
+			.body(Body::from(serde_json::to_string(&body).unwrap()))
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		let status = response.status();
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		(status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+	}
+
+	// Start a session
+	let (status, session) = post(&mut app, "/sessions", Value::Null).await;
+	assert_eq!(status, StatusCode::OK);
+	assert_eq!(session["card_count"], 0);
+	assert!(session["ended_at"].is_null());
+	let session_id = session["id"].as_str().unwrap().to_string();
+
+	// Review one card twice and the other once within the session
+	for card in [&cards[0], &cards[0], &cards[1]] {
+		let (status, review) = post(
+			&mut app,
+			"/reviews",
+			json!({"card_id": card.get_id(), "rating": 3, "session_id": session_id}),
+		)
+		.await;
+		assert_eq!(status, StatusCode::OK);
+		assert_eq!(review["session_id"].as_str().unwrap(), session_id);
+	}
+
+	// A review outside the session doesn't count towards it
+	create_review(&mut app, &cards[1].get_id(), 3).await;
+
+	// End the session
+	let (status, ended) = post(
+		&mut app,
+		&format!("/sessions/{}/end", session_id),
+		Value::Null,
+	)
+	.await;
+	assert_eq!(status, StatusCode::OK);
+	assert_eq!(ended["id"].as_str().unwrap(), session_id);
+	assert_eq!(ended["card_count"], 2);
+	assert!(ended["ended_at"].is_string());
+	assert!(ended["duration_secs"].as_i64().unwrap() >= 0);
+
+	// It can't be ended twice, nor reviewed in afterwards
+	let (status, _) = post(
+		&mut app,
+		&format!("/sessions/{}/end", session_id),
+		Value::Null,
+	)
+	.await;
+	assert_eq!(status, StatusCode::CONFLICT);
+	let (status, _) = post(
+		&mut app,
+		"/reviews",
+		json!({"card_id": cards[0].get_id(), "rating": 3, "session_id": session_id}),
+	)
+	.await;
+	assert_eq!(status, StatusCode::CONFLICT);
+
+	// Unknown sessions are not found
+	let (status, _) = post(&mut app, "/sessions/session-unknown/end", Value::Null).await;
+	assert_eq!(status, StatusCode::NOT_FOUND);
+}
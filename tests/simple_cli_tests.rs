@@ -29,3 +29,25 @@ fn test_cli_help_success() {
 		String::from_utf8_lossy(&out.stderr)
 	);
 }
+
+/// Tests that the CLI can generate shell completions.
+///
+/// This test verifies:
+/// 1. Running `hippocampus-cli completions bash` exits successfully, without
+///    needing a server
+/// 2. A non-empty completion script covering the subcommands is written to stdout
+#[test]
+fn test_cli_bash_completions() {
+	let mut cmd = cargo_bin_cmd!("hippocampus-cli");
+
+	let assert = cmd
+		.args(["--server-url", "http://127.0.0.1:1", "completions", "bash"])
+		.assert()
+		.success();
+
+	let out = assert.get_output();
+	let script = String::from_utf8_lossy(&out.stdout);
+	assert!(!script.is_empty(), "expected a non-empty completion script");
+	assert!(script.contains("hippocampus-cli"), "got:\n{}", script);
+	assert!(script.contains("item-type"), "got:\n{}", script);
+}
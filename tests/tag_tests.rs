@@ -7,6 +7,7 @@
 /// - Removing tags from items
 /// - Listing tags for items
 /// - Listing tags for cards
+/// - Merging tags
 /// - Error cases
 use axum::{
 	body::{Body, to_bytes},
@@ -160,7 +161,13 @@ async fn test_add_tag_to_item() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Create a tag
 	let tag = create_tag(&mut app, "Important".to_string()).await;
@@ -210,7 +217,13 @@ async fn test_remove_tag_from_item() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Create a tag
 	let tag = create_tag(&mut app, "Important".to_string()).await;
@@ -267,8 +280,20 @@ async fn test_list_tags_for_item() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create two items
-	let item1 = create_item(&mut app, &item_type.get_id(), "Item 1".to_string(), None).await;
-	let item2 = create_item(&mut app, &item_type.get_id(), "Item 2".to_string(), None).await;
+	let item1 = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 1".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let item2 = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 2".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Create two tags
 	let tag1 = create_tag(&mut app, "Important".to_string()).await;
@@ -357,7 +382,13 @@ async fn test_list_tags_for_card() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Get the cards for the item
 	let cards = get_cards_for_item(&mut app, &item.get_id()).await;
@@ -447,7 +478,13 @@ async fn test_add_nonexistent_tag_to_item() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Try to add a non-existent tag to the item
 	let request = Request::builder()
@@ -503,7 +540,13 @@ async fn test_remove_nonexistent_tag_from_item() {
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
 
 	// Create an item
-	let item = create_item(&mut app, &item_type.get_id(), "Test Item".to_string(), None).await;
+	let item = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Test Item".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Try to remove a non-existent tag from the item
 	let request = Request::builder()
@@ -547,8 +590,20 @@ async fn test_list_cards_filtered_by_tag_id() {
 
 	// Create an item type and two items
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
-	let item1 = create_item(&mut app, &item_type.get_id(), "Item 1".to_string(), None).await;
-	let item2 = create_item(&mut app, &item_type.get_id(), "Item 2".to_string(), None).await;
+	let item1 = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 1".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let item2 = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 2".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Create a tag and add it only to item1
 	let tag = create_tag(&mut app, "Special".to_string()).await;
@@ -605,9 +660,27 @@ async fn test_list_items_filtered_by_multiple_tag_ids() {
 
 	// Create an item type and three items
 	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
-	let item1 = create_item(&mut app, &item_type.get_id(), "Item 1".to_string(), None).await;
-	let item2 = create_item(&mut app, &item_type.get_id(), "Item 2".to_string(), None).await;
-	let item3 = create_item(&mut app, &item_type.get_id(), "Item 3".to_string(), None).await;
+	let item1 = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 1".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let item2 = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 2".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
+	let item3 = create_item(
+		&mut app,
+		&item_type.get_id(),
+		"Item 3".to_string(),
+		Some(json!({"front": "F", "back": "B"})),
+	)
+	.await;
 
 	// Create two tags
 	let tag_a = create_tag(&mut app, "TagA".to_string()).await;
@@ -679,3 +752,116 @@ async fn test_list_tags_for_nonexistent_card() {
 	let response = app.call(request).await.unwrap();
 	assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
+
+/// Tests merging one tag into another via the API
+///
+/// This test verifies:
+/// 1. A POST request to /tags/{source_id}/merge/{target_id} returns the target tag
+/// 2. Items that had either tag now have the target exactly once
+/// 3. The source tag no longer exists
+/// 4. Merging a missing tag gives 404, and merging a tag into itself gives 409
+#[tokio::test]
+async fn test_merge_tags() {
+	// Create our test app
+	let mut app = create_test_app();
+
+	let item_type = create_item_type(&mut app, "Basic".to_string()).await;
+	let source = create_tag(&mut app, "biology".to_string()).await;
+	let target = create_tag(&mut app, "Biology".to_string()).await;
+
+	// Tag one item with the source and another with both
+	let mut items = Vec::new();
+	for (title, tags) in [
+		("Source only", vec![&source]),
+		("Both", vec![&source, &target]),
+	] {
+		let item = create_item(
+			&mut app,
+			&item_type.get_id(),
+			title.to_string(),
+			Some(json!({"front": "F", "back": "B"})),
+		)
+		.await;
+		for tag in tags {
+			let request = Request::builder()
+				.uri(format!("/items/{}/tags/{}", item.get_id(), tag.get_id()))
+				.method("POST")
+				.body(Body::empty())
+				.unwrap();
+			let response = app.call(request).await.unwrap();
+			assert!(response.status().is_success());
+		}
+		items.push(item);
+	}
+
+	// Merge the source into the target
+	let request = Request::builder()
+		.uri(format!(
+			"/tags/{}/merge/{}",
+			source.get_id(),
+			target.get_id()
+		))
+		.method("POST")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let merged: Value = serde_json::from_slice(&body).unwrap();
+	assert_eq!(merged["id"].as_str().unwrap(), target.get_id().0);
+
+	// Each item has the target tag exactly once
+	for item in &items {
+		let request = Request::builder()
+			.uri(format!("/items/{}/tags", item.get_id()))
+			.method("GET")
+			.body(Body::empty())
+			.unwrap();
+		let response = app.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		let tags: Vec<Value> = serde_json::from_slice(&body).unwrap();
+		assert_eq!(tags.len(), 1);
+		assert_eq!(tags[0]["id"].as_str().unwrap(), target.get_id().0);
+	}
+
+	// The source tag is gone
+	let request = Request::builder()
+		.uri("/tags")
+		.method("GET")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let tags: Vec<Value> = serde_json::from_slice(&body).unwrap();
+	assert!(
+		tags.iter()
+			.all(|t| t["id"].as_str().unwrap() != source.get_id().0)
+	);
+
+	// Merging the now-missing source again is a 404
+	let request = Request::builder()
+		.uri(format!(
+			"/tags/{}/merge/{}",
+			source.get_id(),
+			target.get_id()
+		))
+		.method("POST")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+	// Merging a tag into itself is a conflict
+	let request = Request::builder()
+		.uri(format!(
+			"/tags/{}/merge/{}",
+			target.get_id(),
+			target.get_id()
+		))
+		.method("POST")
+		.body(Body::empty())
+		.unwrap();
+	let response = app.call(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::CONFLICT);
+}